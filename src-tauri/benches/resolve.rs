@@ -0,0 +1,101 @@
+// src-tauri/benches/resolve.rs
+
+use std::fmt::Write;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use kernelforge::core::config::ConfigValue;
+use kernelforge::core::generator::{ConfigGenerator, KernelProfile};
+use kernelforge::core::kconfig::KconfigTree;
+use kernelforge::core::resolver::{ConfigLayer, ResolverIndex};
+
+/// Roughly the number of symbols an x86_64 tree exposes
+const SYMBOLS: usize = 20_000;
+
+/// Synthetic Kconfig with dependency chains, selects and conditional defaults
+fn synthetic_kconfig(symbols: usize) -> String {
+    let mut text = String::new();
+    for index in 0..symbols {
+        writeln!(text, "config SYM_{}", index).unwrap();
+        writeln!(text, "\ttristate \"Synthetic symbol {}\"", index).unwrap();
+        if index > 0 {
+            writeln!(text, "\tdepends on SYM_{} || SYM_{}", index / 2, index / 3).unwrap();
+        }
+        if index % 7 == 0 && index + 1 < symbols {
+            writeln!(text, "\tselect SYM_{}", index + 1).unwrap();
+        }
+        if index % 3 == 0 {
+            writeln!(text, "\tdefault m if SYM_{}", index / 4).unwrap();
+        }
+        writeln!(text, "\thelp\n\t  Help text for symbol {}.\n", index).unwrap();
+    }
+    text
+}
+
+fn user_layer(toggled: usize) -> ConfigLayer {
+    let mut layer = ConfigLayer::new("user");
+    for index in (0..SYMBOLS).step_by(4) {
+        layer.set(&format!("SYM_{}", index), ConfigValue::yes());
+    }
+    layer.set(&format!("SYM_{}", toggled), ConfigValue::NotSet);
+    layer
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let text = synthetic_kconfig(SYMBOLS);
+    c.bench_function("kconfig/parse_20k", |b| {
+        b.iter(|| {
+            let mut tree = KconfigTree::new();
+            tree.parse_str(black_box(&text));
+            tree
+        })
+    });
+}
+
+fn bench_resolve(c: &mut Criterion) {
+    let mut tree = KconfigTree::new();
+    tree.parse_str(&synthetic_kconfig(SYMBOLS));
+
+    c.bench_function("resolver/index_build_20k", |b| {
+        b.iter(|| ResolverIndex::build(black_box(&tree)))
+    });
+
+    let index = ResolverIndex::build(&tree);
+    let generator = ConfigGenerator::x86_64_baseline()
+        .with_profile(KernelProfile::Gaming)
+        .with_layer(user_layer(1));
+    c.bench_function("resolver/full_resolve_20k", |b| {
+        b.iter(|| generator.resolve(black_box(&index)))
+    });
+
+    let mut toggle = 0;
+    c.bench_function("resolver/toggle_and_emit_20k", |b| {
+        b.iter(|| {
+            toggle = (toggle + 97) % SYMBOLS;
+            let layers = vec![generator.layers()[0].clone(), user_layer(toggle)];
+            index.resolve(black_box(&layers)).to_config(&tree).emit()
+        })
+    });
+
+    let stacks: Vec<Vec<ConfigLayer>> = [
+        KernelProfile::Desktop,
+        KernelProfile::Gaming,
+        KernelProfile::Server,
+        KernelProfile::Rt,
+    ]
+    .into_iter()
+    .map(|profile| {
+        ConfigGenerator::x86_64_baseline()
+            .with_profile(profile)
+            .with_layer(user_layer(1))
+            .layers()
+            .to_vec()
+    })
+    .collect();
+    c.bench_function("resolver/resolve_many_parallel_4x20k", |b| {
+        b.iter(|| index.resolve_many(black_box(&stacks)))
+    });
+}
+
+criterion_group!(benches, bench_parse, bench_resolve);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+// src-tauri/benches/search.rs
+
+use std::fmt::Write;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use kernelforge::core::config::{ConfigValue, KernelConfig};
+use kernelforge::core::kconfig::KconfigTree;
+use kernelforge::core::search::{SearchIndex, DEFAULT_LIMIT};
+
+/// Roughly the number of symbols an x86_64 tree exposes
+const SYMBOLS: usize = 20_000;
+
+/// Words the synthetic prompts are assembled from, so prompt matches are spread over the tree
+const WORDS: &[&str] = &[
+    "support", "driver", "network", "sound", "usb", "controller", "debug", "filesystem",
+    "wireless", "graphics", "sensor", "crypto", "virtio", "power", "thermal", "input",
+];
+
+/// Synthetic Kconfig with a prompt on every symbol
+fn synthetic_kconfig(symbols: usize) -> String {
+    let mut text = String::new();
+    for index in 0..symbols {
+        writeln!(text, "config SYM_{}", index).unwrap();
+        writeln!(
+            text,
+            "\ttristate \"{} {} {}\"\n",
+            WORDS[index % WORDS.len()],
+            WORDS[(index / WORDS.len()) % WORDS.len()],
+            index
+        )
+        .unwrap();
+    }
+    text
+}
+
+/// The search-as-you-type command runs on every keystroke and has to stay under 10ms
+fn bench_search(c: &mut Criterion) {
+    let mut tree = KconfigTree::new();
+    tree.parse_str(&synthetic_kconfig(SYMBOLS));
+
+    c.bench_function("search/index_build_20k", |b| {
+        b.iter(|| SearchIndex::build(black_box(&tree)))
+    });
+
+    let index = SearchIndex::build(&tree);
+    let mut config = KernelConfig::new();
+    for symbol in (0..SYMBOLS).step_by(3) {
+        config.set(&format!("SYM_{}", symbol), ConfigValue::yes());
+    }
+
+    // One query per match tier: name prefix, prompt word, substring and subsequence
+    for query in ["sym_19", "wireless", "ess cont", "snddrv"] {
+        c.bench_function(&format!("search/keystroke_20k/{}", query), |b| {
+            b.iter(|| index.search(black_box(query), Some(&config), DEFAULT_LIMIT))
+        });
+    }
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+// src-tauri/examples/distro_options.rs
+
+//! Regenerates data/distro-options.json from the configs listed in
+//! data/distro-options.survey.json, each passed as `<id>=<path>`:
+//!
+//! cargo run --example distro_options -- data/distro-options.survey.json \
+//!     arch=config-arch debian=config-debian fedora=config-fedora ubuntu=config-ubuntu \
+//!     > data/distro-options.json
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use kernelforge::core::config::KernelConfig;
+use kernelforge::core::popularity::PopularitySurvey;
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let Some(survey_path) = args.next() else {
+        bail!("usage: distro_options <survey.json> <id>=<config>...");
+    };
+    let text =
+        fs::read_to_string(&survey_path).with_context(|| format!("reading {}", survey_path))?;
+    let survey = PopularitySurvey::parse(&text)?;
+
+    let mut configs = BTreeMap::new();
+    for arg in args {
+        let (id, path) = arg
+            .split_once('=')
+            .with_context(|| format!("{} is not <id>=<config>", arg))?;
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path))?;
+        let config = KernelConfig::parse(&text).with_context(|| format!("parsing {}", path))?;
+        configs.insert(id.to_string(), config);
+    }
+
+    let dataset = survey.generate(&configs)?;
+    println!("{}", serde_json::to_string_pretty(&dataset)?);
+    Ok(())
+}
@@ -0,0 +1,239 @@
+# English defaults for user-facing messages.
+# Keys are stable identifiers; the frontend ships translations under the same keys.
+
+## NVIDIA advisor
+
+nvidia-unsupported = NVIDIA driver does not support this kernel
+nvidia-unsupported-detail = Driver { $version } (branch { $branch }) is only known to build up to Linux { $newest }; Linux { $target } will boot without a working GPU driver. Update the driver or pick an older kernel.
+nvidia-unknown = NVIDIA driver compatibility unknown
+nvidia-unknown-detail = Driver { $version } is newer than the compatibility data; check the release notes for Linux { $target } support.
+nvidia-missing-config = Config lacks symbols nvidia-drm needs
+nvidia-missing-config-detail = The NVIDIA modules cannot load without { $symbols }.
+nvidia-ibt = Indirect branch tracking breaks this driver
+nvidia-ibt-detail = Driver branch { $branch } predates IBT support and faults on load with CONFIG_X86_KERNEL_IBT. Disable it or boot with ibt=off.
+nvidia-fbdev = nvidia-drm cannot take over the console
+nvidia-fbdev-detail = Without CONFIG_DRM_FBDEV_EMULATION the console stays on the firmware framebuffer from simpledrm after nvidia-drm loads with fbdev=1.
+nvidia-no-simpledrm = No early framebuffer console
+nvidia-no-simpledrm-detail = Driver branch { $branch } has no fbdev support, so with CONFIG_DRM_SIMPLEDRM disabled the boot console stays blank.
+
+## ZFS guardrails
+
+zfs-unsupported = OpenZFS does not support this kernel
+zfs-unsupported-detail = OpenZFS { $version } supports kernels up to { $newest }; pools { $pools } would not import on Linux { $target }.
+zfs-unknown = OpenZFS compatibility unknown
+zfs-unknown-detail = Could not match OpenZFS { $version } against Linux { $target }; confirm support before installing.
+zfs-missing-config = Config would prevent the zfs module from loading
+zfs-missing-config-detail = Adjust { $symbols } so DKMS can build and load zfs.
+zfs-preempt-rt = OpenZFS does not build on PREEMPT_RT
+zfs-preempt-rt-detail = The zfs module is incompatible with real-time preemption.
+zfs-install-blocked = Install blocked to protect the ZFS root pool: { $reasons }
+
+## Appliance builder
+
+appliance-missing-core = Appliance config cannot boot to init
+appliance-missing-core-detail = { $symbols } must be built in for the kernel to start a userspace init.
+appliance-not-size-optimized = Appliance config is not optimized for size
+appliance-not-size-optimized-detail = { $symbols } would shrink the image; the kernel still boots without them.
+appliance-root-fs = Root filesystem is not built in
+appliance-root-fs-detail = Without an initramfs the kernel has to mount the { $fstype } root itself, so its driver cannot be a module.
+appliance-root-fs-unknown = Unsupported root filesystem
+appliance-root-fs-unknown-detail = The appliance builder does not know which driver mounts { $fstype }; pick a supported root filesystem.
+appliance-no-storage = No storage controller for the root device
+appliance-no-storage-detail = None of the detected disk controllers is enabled, so the root device would never appear.
+appliance-no-initrd = Initramfs support is disabled
+appliance-no-initrd-detail = The initramfs feature was selected but CONFIG_BLK_DEV_INITRD is off, so the bootloader's initramfs would be ignored.
+appliance-no-efi-stub = Kernel cannot be started by UEFI directly
+appliance-no-efi-stub-detail = This machine boots through UEFI; without CONFIG_EFI_STUB a bootloader that loads legacy images is required.
+appliance-no-console = No console enabled
+appliance-no-console-detail = Neither a serial, virtio nor framebuffer console is enabled; boot messages and emergency shells will be invisible.
+appliance-oversize = Appliance kernel exceeds the size target
+appliance-oversize-detail = The image is { $size } MiB, over the { $target } MiB appliance target. Untick features or drop unused drivers.
+
+## Build report
+
+build-warning-regression = New kinds of compiler warnings
+build-warning-regression-detail = This build introduced { $categories } warnings that the previous build of the plan did not have ({ $before } warnings before, { $after } now).
+build-warning-budget = Warning budget exceeded
+build-warning-budget-detail = The build produced { $count } warnings, more than the { $budget } allowed for this plan.
+
+## Build watchdog
+
+stage-timed-out = The { $stage } stage is taking too long
+stage-timed-out-detail = The { $stage } stage has been running for more than { $minutes } minutes. Its last output was: { $line }
+stage-inactive = The { $stage } stage looks stuck
+stage-inactive-detail = The { $stage } stage has printed nothing for { $minutes } minutes, which usually means it is waiting for input. Its last output was: { $line }
+
+## Profiles
+
+profile-debug-performance = Debug kernels are much slower
+profile-debug-performance-detail = KASAN, UBSAN and lockdep instrument most memory accesses and locks; expect workloads to run several times slower and use considerably more memory. Keep a performance kernel installed for daily use.
+profile-debug-size = Debug kernels are large
+profile-debug-size-detail = Full debug info and instrumentation make the image and modules several times bigger, and builds take noticeably longer.
+
+## Benchmarks
+
+benchmark-throttle-events = CPU throttled during the benchmark
+benchmark-throttle-events-detail = The CPU reported { $events } thermal throttling events while { $name } ran. Its timings depend on cooling rather than the kernel and should not be compared.
+benchmark-frequency-drop = CPU clocked down during the benchmark
+benchmark-frequency-drop-detail = At { $temp } °C the CPU ran at { $percent }% of its maximum frequency while { $name } ran. Let the machine cool down and run it again before comparing kernels.
+
+## Known issues
+
+known-issue = { $title }
+known-issue-detail = { $explanation }
+hardware-quirk = { $title }
+hardware-quirk-detail = { $explanation } Command line: { $cmdline }
+
+## Signing keys
+
+key-expiring = A signing key expires soon
+key-expiring-detail = The key of { $uid } ({ $fingerprint }) expires in { $days } days. Refresh it before then or sources it signs can no longer be verified.
+key-expired = A signing key has expired
+key-expired-detail = The key of { $uid } ({ $fingerprint }) has expired. Refresh or replace it; signatures made with it are no longer accepted.
+
+## Boot watchdog
+
+boot-watchdog-missing = No hardware watchdog found
+boot-watchdog-missing-detail = The machine exposes no watchdog device, so a trial kernel that hangs while booting will not be reset automatically. Someone has to power-cycle it to fall back to the previous kernel.
+boot-watchdog-core = Watchdog support is disabled
+boot-watchdog-core-detail = The new kernel is built without CONFIG_WATCHDOG_CORE, so nothing can reset the machine if it hangs during the trial boot.
+boot-watchdog-driver = Watchdog driver missing from the new kernel
+boot-watchdog-driver-detail = This machine's watchdog uses the { $driver } driver, but CONFIG_{ $symbol } is disabled in the new kernel. Enable it or a hung trial boot will not be reset.
+
+## Kexec
+
+kexec-skips-firmware = Trying a kernel without rebooting
+kexec-skips-firmware-detail = The new kernel starts straight from the running one, without firmware initialisation. Some GPUs and devices may not come back correctly, which does not mean the kernel is broken. Nothing is installed: reboot normally to return to your default kernel.
+kexec-disabled = Kexec is disabled
+kexec-disabled-detail = The running kernel has kexec_load_disabled set, so it cannot load another kernel until the next reboot.
+kexec-lockdown = Kernel lockdown is active
+kexec-lockdown-detail = With lockdown enabled only kernels signed with a trusted key can be loaded. Sign the new kernel or it will be refused.
+
+## Compiler flags
+
+cflag-o0 = { $flag } cannot build a kernel
+cflag-o0-detail = The kernel relies on optimisation to build at all; unoptimised builds fail to link or break at runtime. Remove { $flag }.
+cflag-fast-math = { $flag } is known to miscompile the kernel
+cflag-fast-math-detail = { $flag } relaxes floating-point and aliasing rules the kernel depends on. Remove it.
+cflag-o3 = { $flag } is not supported upstream
+cflag-o3-detail = The kernel is tested at -O2. { $flag } has produced miscompiled drivers and spurious warnings with several compiler versions; benchmark before keeping it.
+cflag-simd = { $flag } lets the compiler use vector registers
+cflag-simd-detail = The kernel only saves vector state around explicit kernel_fpu_begin sections. { $flag } lets the compiler use those registers anywhere and corrupts userspace state. Remove it.
+cflag-lto = { $flag } bypasses the kernel's LTO support
+cflag-lto-detail = Link-time optimisation must be enabled with the kernel's own Clang LTO options, not through { $flag }. Remove it and choose an LTO mode in the configuration.
+cflag-pic = { $flag } conflicts with the kernel's code model
+cflag-pic-detail = The kernel chooses its own code model and relocation handling. { $flag } breaks the build or the boot. Remove it.
+cflag-march = { $flag } makes the kernel machine-specific
+cflag-march-detail = The kernel will only run on CPUs matching { $flag }. Prefer the processor family option in the configuration, which the kernel knows how to handle.
+cflag-debug = { $flag } duplicates the debug info settings
+cflag-debug-detail = Debug information is controlled by the CONFIG_DEBUG_INFO options; { $flag } bloats the build without the matching tooling support.
+cflag-werror = { $flag } may fail the build on harmless warnings
+cflag-werror-detail = New compilers add warnings the kernel has not been fixed for yet. Use CONFIG_WERROR instead, which covers the warnings upstream cares about.
+cflag-stack-protector = { $flag } overrides the stack protector configuration
+cflag-stack-protector-detail = Stack protection is chosen with CONFIG_STACKPROTECTOR; { $flag } can disagree with it and break the boot. Change the configuration option instead.
+cflag-unknown = { $flag } has not been vetted
+cflag-unknown-detail = KernelForge knows nothing about { $flag }. It is passed to the compiler as given.
+
+## Prebuilt kernels
+
+prebuilt-equivalent = { $kernel } already ships this configuration
+prebuilt-equivalent-detail = Scheduler, timer frequency, preemption, LTO, mitigations and CPU level all match what this plan builds. Installing { $kernel } saves the build; building is still worth it for your own patches or a trimmed module set.
+prebuilt-close = { $kernel } is close to this plan
+prebuilt-close-detail = { $kernel } differs only in: { $features }. If those settings do not matter to you, the prebuilt kernel saves the build.
+prebuilt-level-unsupported = This CPU cannot run the planned build
+prebuilt-level-unsupported-detail = The plan compiles for x86-64-v{ $planned }, but this processor only supports x86-64-v{ $supported }. The kernel would not boot here.
+hibernate-missing-config = The plan would break hibernation
+hibernate-missing-config-detail = This machine hibernates, but { $symbols } are missing. The new kernel could neither hibernate nor resume the saved session.
+hibernate-lockdown = Forced lockdown disables hibernation
+hibernate-lockdown-detail = The plan forces kernel lockdown, which refuses to hibernate because the image could be tampered with while the machine is off.
+hibernate-resume-dropped = The new boot entry would lose the resume parameters
+hibernate-resume-dropped-detail = { $params } is on the running command line but not on the new entry's. Without it the kernel boots fresh and the hibernated session is lost.
+hibernate-no-swap = No swap holds the resume image
+hibernate-no-swap-detail = resume={ $resume } does not match an active swap area, so hibernating would fail. Activate the swap it names or update resume=.
+hibernate-swap-small = Swap is too small to hibernate
+hibernate-swap-small-detail = The resume swap holds { $swap }, but the image needs about { $image }. Hibernation will fail until swap grows or memory use drops.
+hibernate-disabled = Hibernation is disabled on the running kernel
+hibernate-disabled-detail = Lockdown or the kernel configuration currently prevents hibernating, so these checks describe the new kernel only.
+popularity-all = All { $distros } major distros enable this
+popularity-some = { $enabled } of { $distros } major distros enable this
+popularity-none = None of { $distros } major distros enable this
+
+## Compression
+
+compression-tool-missing = { $program } is not installed
+compression-tool-missing-detail = The build compresses with { $format }, which needs { $program } on this machine. Install it or pick another format.
+compression-kmod-unsupported = kmod cannot load { $format } modules
+compression-kmod-unsupported-detail = This machine's kmod was built without { $format } support, so modprobe and the initramfs generator could not load the new kernel's modules. Pick another module compression.
+compression-image-zboot = The image compression setting has no effect
+compression-image-zboot-detail = On this architecture the kernel image is only compressed with { $format } inside the EFI zboot wrapper. Without CONFIG_EFI_ZBOOT the image is left uncompressed.
+compression-zboot-no-efi = This machine does not boot through UEFI
+compression-zboot-no-efi-detail = A zboot image decompresses itself as an EFI application. Bootloaders loading the kernel directly, such as U-Boot's booti, cannot start it.
+compression-zboot-grub = GRUB { $version } cannot start a zboot image
+compression-zboot-grub-detail = GRUB releases before 2.12 read the kernel image header themselves and reject the EFI zboot wrapper. Update GRUB or turn off CONFIG_EFI_ZBOOT.
+compression-initramfs-unsupported = The new kernel cannot unpack this machine's initramfs
+compression-initramfs-unsupported-detail = { $tool } compresses the initramfs with { $format }, but CONFIG_{ $symbol } is off, so the kernel would boot without its initramfs. Turn it on or change { $tool }'s compression.
+
+## Boot image
+
+image-efi-only = This machine cannot start an EFI-only image
+image-efi-only-detail = The plan builds a zboot image, which only UEFI firmware can start, but this machine boots through BIOS. Build the plain image instead.
+image-needs-efi-stub = { $bootloader } needs a kernel with the EFI stub
+image-needs-efi-stub-detail = { $bootloader } hands the kernel to the firmware as an EFI application, which a kernel without CONFIG_EFI_STUB is not. The new kernel would not start.
+
+## Plugins
+
+plugin-finding = { $plugin }: { $title }
+plugin-finding-detail = { $detail }
+
+## Containers
+
+containers-missing-config = The plan would stop { $runtimes } containers from starting
+containers-missing-config-detail = This machine runs { $runtimes }, which need { $features } from the kernel. Without { $symbols } the containers would not start on the new kernel.
+
+## Printing
+
+printing-missing-config = The plan would break printing or scanning
+printing-missing-config-detail = This machine prints or scans, but { $symbols } are missing. Printers and scanners that work today would stop working on the new kernel.
+
+## Camera
+
+camera-missing-config = The plan would drop the camera
+camera-missing-config-detail = { $users } need { $symbols }. Without them the camera disappears on the new kernel; choose "no camera" in the removal review if that is what you want.
+
+## Input devices
+
+input-missing-config = { $device } would stop working
+input-missing-config-detail = The plan leaves out { $symbols }, which { $device } needs. On the new kernel the device would not respond.
+
+## Thunderbolt
+
+thunderbolt-missing-config = The plan would break Thunderbolt and docks
+thunderbolt-missing-config-detail = Without { $symbols } the new kernel cannot authorize Thunderbolt devices or reach what sits behind a dock. Attached now: { $devices }.
+dock-display-missing-config = Displays on the dock would go dark
+dock-display-missing-config-detail = { $displays } appear to run through the dock, which needs { $symbols } to carry the picture.
+
+## Sensors
+
+sensors-missing-config = The plan would drop the hardware sensors
+sensors-missing-config-detail = Without { $symbols } the temperature, voltage and fan readings of this machine disappear on the new kernel.
+fancontrol-missing-config = The plan would stop fan control
+fancontrol-missing-config-detail = fancontrol drives the fans from the sensors that { $symbols } provide. Without them it cannot start and the fans stay at whatever the firmware sets.
+sensors-bus-removed = Removing { $bus } takes the sensor chips with it
+sensors-bus-removed-detail = { $chips } need it. Dropping the buses and the hwmon core wholesale, as the embedded systems removal does, breaks board and memory sensors on many desktops.
+
+## RGB and cooling tools
+
+rgb-missing-config = The plan would cut off { $tools }
+rgb-missing-config-detail = { $tools } reach lighting, fans and pumps through { $symbols }. Without them the devices fall back to their default lighting and firmware fan curves.
+
+## Networking
+
+firewall-missing-config = The firewall ruleset could not load on the new kernel
+firewall-missing-config-detail = The ruleset loaded now uses { $symbols }. Without them the firewall fails to load at boot and the machine comes up unprotected.
+networking-missing-config = The plan would cut off { $users }
+networking-missing-config-detail = { $users } need { $symbols }, which the plan leaves out. Tunnels and open sockets that work today would fail on the new kernel.
+
+## Bluetooth audio
+
+bluetooth-audio-missing-config = The plan would break Bluetooth headsets
+bluetooth-audio-missing-config-detail = The controllers of this machine ({ $controllers }) need { $symbols } for headset audio. Without them music, calls or LE Audio stop working on the new kernel.
@@ -0,0 +1,1296 @@
+// src-tauri/src/commands.rs
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Builder, Emitter, Runtime, State};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time;
+
+use crate::core::advisory::Advisory;
+use crate::core::bluetooth::BluetoothSetup;
+use crate::core::boot_image::{ImageFormat, ImagePlacement};
+use crate::core::bootloader::{BootEntryRequest, Bootloader};
+use crate::core::build::{BuildEvent, BuildExecutor, BuildOptions, BuildOutcome};
+use crate::core::cache::CacheIndex;
+use crate::core::checkpoint::{Checkpoint, CheckpointKey};
+use crate::core::cmdline::{CmdlineChange, CmdlinePlan};
+use crate::core::compression::{
+    measure_tradeoffs, uncompressed_image, CompressionChoice, CompressionTradeoff,
+};
+use crate::core::config::KernelConfig;
+use crate::core::containers::{self, ContainerRuntime};
+use crate::core::dashboard::Dashboard;
+use crate::core::distro::InstallTemplate;
+use crate::core::dtb::available_dtbs;
+use crate::core::explain::PlanExplanation;
+use crate::core::generator::ConfigGenerator;
+use crate::core::hardware::{Firmware, HardwareSnapshot};
+use crate::core::hibernate::HibernateSetup;
+use crate::core::host::host_system;
+use crate::core::ikconfig::{verify_running, ConfigVerification};
+use crate::core::input::{self, InputAnalysis};
+use crate::core::install::InstallTransaction;
+use crate::core::kconfig::KconfigTree;
+use crate::core::keyring::{ArtifactSignature, Keyring, TrustedKey};
+use crate::core::machine_capture::{capture_script, import_capture};
+use crate::core::module_policy::ModulePolicy;
+use crate::core::net::{http_client, NetworkSettings};
+use crate::core::networking::NetworkingSetup;
+use crate::core::output::{OutputBatch, OutputBatcher, StreamSettings};
+use crate::core::patch_manifest::{CompatibilityMatrix, PatchManifest};
+use crate::core::patches::{check_pins, preview_patches, PatchPreview, PatchSource, PinCheck};
+use crate::core::paths::Paths;
+use crate::core::plan::{BuildPlan, TargetArch};
+use crate::core::plugins::{
+    discover, find as find_plugin, Capability, PluginDiscovery, PluginGrants, PluginSession,
+};
+use crate::core::policy::{Policy, PolicyViolation};
+use crate::core::profiles::{CustomProfile, ProfileEntry, ProfileLibrary};
+use crate::core::quick_build::{QuickBuild, QuickBuildPlan};
+use crate::core::quirks::{Quirk, QuirkDatabase, DEFAULT_QUIRKS_URL};
+use crate::core::requirements::MachineRequirements;
+use crate::core::resolver::ConfigLayer;
+use crate::core::results::ResultsStore;
+use crate::core::review::distro_config;
+use crate::core::rgb::RgbSetup;
+use crate::core::sbc::SbcBoard;
+use crate::core::scripting::{
+    boot_entry, configure_tree, entry_cmdline, hook_scripts, run_hooks, save_script, HookPoint,
+    HookReport, HookRun,
+};
+use crate::core::search::{SearchHit, SearchIndex, DEFAULT_LIMIT};
+use crate::core::sensors::SensorSetup;
+use crate::core::sources::{ProviderListing, SourceProviders};
+use crate::core::stats::StatsStore;
+use crate::core::thunderbolt::ThunderboltSetup;
+use crate::core::toolchain::CrossToolchain;
+use crate::core::version_catalog::{AnnotatedCatalog, CatalogSettings, VersionCatalog};
+use crate::core::virtme::{boot_in_virtme, VirtmeReport};
+use crate::core::watchdog::StallAction;
+use crate::core::wizard::{Wizard, WizardAction, WizardView};
+use crate::core::workspace::{outside_changes, OutsideChanges, WorkspaceSnapshot};
+
+/// Event carrying an `OutputBatch` of raw make output
+pub const BUILD_OUTPUT_EVENT: &str = "build-output";
+
+/// Event carrying every other `BuildEvent`
+pub const BUILD_PROGRESS_EVENT: &str = "build-progress";
+
+/// Event carrying the `OutsideChanges` of the watched source tree whenever they change
+pub const WORKSPACE_CHANGED_EVENT: &str = "workspace-changed";
+
+/// How often the watched source tree is scanned for edits made outside KernelForge
+const WORKSPACE_POLL: Duration = Duration::from_secs(5);
+
+/// Errors cross the IPC boundary as their message chain
+pub type CommandResult<T> = Result<T, String>;
+
+/// Search index and loaded config behind the config browser, managed as Tauri state
+#[derive(Debug, Default)]
+pub struct ConfigBrowser {
+    index: RwLock<SearchIndex>,
+    config: RwLock<Option<KernelConfig>>,
+}
+
+impl ConfigBrowser {
+    /// Rebuilds the index for a newly loaded source tree
+    pub fn load_tree(&self, tree: &KconfigTree) {
+        let index = SearchIndex::build(tree);
+        if let Ok(mut current) = self.index.write() {
+            *current = index;
+        }
+    }
+
+    /// Replaces the config whose values and enabled state feed the ranking
+    pub fn load_config(&self, config: Option<KernelConfig>) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+}
+
+/// Incremental search for the config browser, called on every keystroke
+#[tauri::command]
+pub fn search_config(
+    browser: State<'_, ConfigBrowser>,
+    query: String,
+    limit: Option<usize>,
+) -> CommandResult<Vec<SearchHit>> {
+    let index = browser
+        .index
+        .read()
+        .map_err(|_| "search index is poisoned".to_string())?;
+    let config = browser
+        .config
+        .read()
+        .map_err(|_| "loaded config is poisoned".to_string())?;
+    Ok(index.search(&query, config.as_ref(), limit.unwrap_or(DEFAULT_LIMIT)))
+}
+
+/// Output stream of the running build, managed as Tauri state
+#[derive(Debug, Clone, Default)]
+pub struct BuildOutput {
+    batcher: Arc<Mutex<OutputBatcher>>,
+}
+
+impl BuildOutput {
+    /// Forwards build events to the frontend until the build's sender is dropped
+    /// Lines are batched on a timer and held back while the frontend has not acknowledged
+    /// earlier batches, so a fast build cannot flood the webview
+    pub fn forward<R: Runtime>(
+        &self,
+        app: AppHandle<R>,
+        mut events: UnboundedReceiver<BuildEvent>,
+        settings: StreamSettings,
+    ) {
+        let batcher = Arc::clone(&self.batcher);
+        if let Ok(mut current) = batcher.lock() {
+            *current = OutputBatcher::new(settings.clone());
+        }
+        tokio::spawn(async move {
+            let mut ticker = time::interval(settings.interval);
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Some(BuildEvent::Line(line)) => {
+                            if let Ok(mut batcher) = batcher.lock() {
+                                batcher.push(line);
+                            }
+                        }
+                        Some(event) => {
+                            let _ = app.emit(BUILD_PROGRESS_EVENT, event);
+                        }
+                        None => break,
+                    },
+                    _ = ticker.tick() => {
+                        while let Some(batch) = take_batch(&batcher, OutputBatcher::take) {
+                            let _ = app.emit(BUILD_OUTPUT_EVENT, batch);
+                        }
+                    }
+                }
+            }
+            if let Some(batch) = take_batch(&batcher, OutputBatcher::flush) {
+                let _ = app.emit(BUILD_OUTPUT_EVENT, batch);
+            }
+        });
+    }
+}
+
+fn take_batch(
+    batcher: &Mutex<OutputBatcher>,
+    take: fn(&mut OutputBatcher) -> Option<OutputBatch>,
+) -> Option<OutputBatch> {
+    batcher
+        .lock()
+        .ok()
+        .and_then(|mut batcher| take(&mut batcher))
+}
+
+/// Acknowledges a rendered output batch so the next ones can be sent
+#[tauri::command]
+pub fn ack_build_output(output: State<'_, BuildOutput>, seq: u64) -> CommandResult<()> {
+    output
+        .batcher
+        .lock()
+        .map_err(|_| "build output stream is poisoned".to_string())?
+        .ack(seq);
+    Ok(())
+}
+
+/// Channel to the running build's watchdog, managed as Tauri state
+#[derive(Debug, Default)]
+pub struct BuildControl {
+    actions: Mutex<Option<UnboundedSender<StallAction>>>,
+}
+
+impl BuildControl {
+    /// Opens the channel for a new build; pass the receiver to `BuildExecutor::run_supervised`
+    pub fn attach(&self) -> UnboundedReceiver<StallAction> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Ok(mut current) = self.actions.lock() {
+            *current = Some(sender);
+        }
+        receiver
+    }
+}
+
+/// Answers a `BuildEvent::Stalled`: kill the build, retry it or keep waiting
+#[tauri::command]
+pub fn resolve_build_stall(
+    control: State<'_, BuildControl>,
+    action: StallAction,
+) -> CommandResult<()> {
+    let actions = control
+        .actions
+        .lock()
+        .map_err(|_| "build control is poisoned".to_string())?;
+    actions
+        .as_ref()
+        .ok_or_else(|| "no build is running".to_string())?
+        .send(action)
+        .map_err(|_| "the build has already finished".to_string())
+}
+
+/// Series and per-kernel comparison behind the Results dashboard
+#[tauri::command]
+pub fn results_dashboard(paths: State<'_, Paths>) -> CommandResult<Dashboard> {
+    let stats = StatsStore::open(&paths).map_err(|error| format!("{:#}", error))?;
+    let results = ResultsStore::open(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(Dashboard::build(&stats, &results))
+}
+
+/// Keys trusted to sign sources, bundled and user-added
+#[tauri::command]
+pub fn trusted_keys(paths: State<'_, Paths>) -> CommandResult<Vec<TrustedKey>> {
+    let keyring = Keyring::open(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(keyring.keys().to_vec())
+}
+
+/// Signature status of every cached artifact and the key that verified it
+#[tauri::command]
+pub fn artifact_signatures(paths: State<'_, Paths>) -> CommandResult<Vec<ArtifactSignature>> {
+    let keyring = Keyring::open(&paths).map_err(|error| format!("{:#}", error))?;
+    let index = CacheIndex::open(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(keyring.artifact_signatures(&index))
+}
+
+/// Boots the kernel just built in `source_dir` under virtme-ng and runs `exec` in it
+#[tauri::command]
+pub async fn virtme_boot(source_dir: PathBuf, exec: Option<String>) -> CommandResult<VirtmeReport> {
+    tokio::task::spawn_blocking(move || {
+        boot_in_virtme(host_system().as_ref(), &source_dir, exec.as_deref())
+            .map_err(|error| format!("{:#}", error))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Catalog versions with built, installed, running and last-build badges, in one call
+/// The catalog's age is included so the UI can say how current the list is
+#[tauri::command]
+pub async fn kernel_versions(
+    paths: State<'_, Paths>,
+    settings: Option<CatalogSettings>,
+) -> CommandResult<AnnotatedCatalog> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    let catalog = VersionCatalog::load(&client, &paths, &settings.unwrap_or_default())
+        .await
+        .map_err(|error| format!("{:#}", error))?;
+    let stats = StatsStore::open(&paths).map_err(|error| format!("{:#}", error))?;
+    let installs = InstallTransaction::list(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(catalog.annotate(host_system().as_ref(), &stats, &installs))
+}
+
+/// Versions offered by each source provider, kernel.org from the catalog and git trees
+/// listed live
+#[tauri::command]
+pub async fn source_versions(paths: State<'_, Paths>) -> CommandResult<Vec<ProviderListing>> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    let catalog = VersionCatalog::load(&client, &paths, &CatalogSettings::default())
+        .await
+        .map_err(|error| format!("{:#}", error))?;
+    let providers = SourceProviders::load(&paths).map_err(|error| format!("{:#}", error))?;
+    tokio::task::spawn_blocking(move || providers.list(host_system().as_ref(), &catalog))
+        .await
+        .map_err(|error| error.to_string())
+}
+
+/// Description and diffstat of each selected patch, fetched for review before applying
+#[tauri::command]
+pub async fn preview_selected_patches(
+    patches: Vec<PatchSource>,
+) -> CommandResult<Vec<PatchPreview>> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    Ok(preview_patches(&client, &patches).await)
+}
+
+/// Compares each patch's pin with what its URL serves now, with a diff for drifted ones
+#[tauri::command]
+pub async fn check_patch_pins(
+    paths: State<'_, Paths>,
+    patches: Vec<PatchSource>,
+) -> CommandResult<Vec<PinCheck>> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    let mut index = CacheIndex::open(&paths).map_err(|error| format!("{:#}", error))?;
+    check_pins(&client, host_system().as_ref(), &mut index, &patches)
+        .await
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// Which manifest patches support each kernel series in the catalog
+#[tauri::command]
+pub async fn patch_matrix(paths: State<'_, Paths>) -> CommandResult<CompatibilityMatrix> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    let catalog = VersionCatalog::load(&client, &paths, &CatalogSettings::default())
+        .await
+        .map_err(|error| format!("{:#}", error))?;
+    Ok(PatchManifest::bundled().catalog_matrix(&catalog))
+}
+
+/// Saved first-run wizard, with the removal groups offered on its review page
+#[tauri::command]
+pub fn wizard_state(paths: State<'_, Paths>) -> CommandResult<WizardView> {
+    let wizard = Wizard::load(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(WizardView::new(wizard, Vec::new()))
+}
+
+/// Applies one wizard action and saves the result; a refused action leaves the saved
+/// state untouched
+#[tauri::command]
+pub async fn wizard_advance(
+    paths: State<'_, Paths>,
+    action: WizardAction,
+) -> CommandResult<WizardView> {
+    let mut wizard = Wizard::load(&paths).map_err(|error| format!("{:#}", error))?;
+    let catalog = match action {
+        WizardAction::PickVersion { .. } => {
+            let client =
+                http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+            let catalog = VersionCatalog::load(&client, &paths, &CatalogSettings::default())
+                .await
+                .map_err(|error| format!("{:#}", error))?;
+            Some(catalog)
+        }
+        _ => None,
+    };
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let advisories = wizard
+            .apply(
+                action,
+                host_system().as_ref(),
+                catalog.as_ref(),
+                &QuirkDatabase::load(&paths),
+            )
+            .and_then(|advisories| wizard.save(&paths).map(|_| advisories))
+            .map_err(|error| format!("{:#}", error))?;
+        Ok(WizardView::new(wizard, advisories))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Plain-language summary of what a plan changes against the running kernel and why
+#[tauri::command]
+pub async fn explain_plan(plan: BuildPlan) -> CommandResult<PlanExplanation> {
+    tokio::task::spawn_blocking(move || {
+        let current =
+            distro_config(host_system().as_ref()).map_err(|error| format!("{:#}", error))?;
+        let mut explanation = PlanExplanation::build(&plan, &current, None, &[]);
+        if let Some(rgb) = RgbSetup::detect(host_system().as_ref()) {
+            explanation.keep(rgb.explanation(&plan.config));
+        }
+        Ok(explanation)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Shell script users run on a machine without KernelForge to capture its hardware
+#[tauri::command]
+pub fn machine_capture_script() -> String {
+    capture_script()
+}
+
+/// Hardware snapshot of another machine from the capture script's JSON output
+#[tauri::command]
+pub fn import_machine_capture(capture: String) -> CommandResult<HardwareSnapshot> {
+    import_capture(&capture).map_err(|error| format!("{:#}", error))
+}
+
+/// Single-board computers a plan can target
+#[tauri::command]
+pub fn sbc_boards() -> Vec<SbcBoard> {
+    SbcBoard::available().copied().collect()
+}
+
+/// Device tree blobs a source tree can build for an architecture, for the board picker
+#[tauri::command]
+pub fn available_device_trees(source_dir: PathBuf, arch: TargetArch) -> CommandResult<Vec<String>> {
+    available_dtbs(&source_dir, arch).map_err(|error| format!("{:#}", error))
+}
+
+/// Compares the running kernel's /proc/config.gz with the plan it should have been built from
+#[tauri::command]
+pub async fn verify_running_config(plan: BuildPlan) -> CommandResult<ConfigVerification> {
+    tokio::task::spawn_blocking(move || {
+        verify_running(host_system().as_ref(), &plan).map_err(|error| format!("{:#}", error))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// The modprobe.d file a plan's module policy installs with kernel `version`, for review
+/// before installing
+#[tauri::command]
+pub fn preview_module_policy(policy: ModulePolicy, version: String) -> CommandResult<String> {
+    policy
+        .render(&version)
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// Imports the current kernel command line as the baseline for `additions`
+#[tauri::command]
+pub async fn capture_cmdline(additions: Vec<String>) -> CommandResult<CmdlinePlan> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let bootloader = Bootloader::detect(system.as_ref());
+        let mut plan = CmdlinePlan::capture(system.as_ref(), &bootloader)
+            .map_err(|error| format!("{:#}", error))?;
+        for param in &additions {
+            plan.add(param);
+        }
+        Ok(plan)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// What a command line plan adds, replaces and refuses to touch
+#[tauri::command]
+pub fn diff_cmdline(plan: CmdlinePlan) -> Vec<CmdlineChange> {
+    plan.diff()
+}
+
+/// Hibernation findings for a plan, none when the machine does not hibernate; `cmdline`
+/// is the baseline `prepare_boot_entry` gets, checked as the new entry will carry it
+#[tauri::command]
+pub async fn check_hibernation(
+    plan: BuildPlan,
+    cmdline: CmdlinePlan,
+) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let Some(setup) = HibernateSetup::detect(system.as_ref()) else {
+            return Vec::new();
+        };
+        let cmdline = entry_cmdline(system.as_ref(), &plan, cmdline);
+        setup.check(&plan.config, &cmdline.render())
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine running containers, none when it runs none
+#[tauri::command]
+pub async fn check_containers(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let runtimes = ContainerRuntime::detect(host_system().as_ref());
+        containers::check(&runtimes, &plan.config)
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine with a Thunderbolt controller, none without
+#[tauri::command]
+pub async fn check_thunderbolt(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        ThunderboltSetup::detect(host_system().as_ref())
+            .map(|setup| setup.check(&plan.config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine with hwmon sensor chips, none without
+#[tauri::command]
+pub async fn check_sensors(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        SensorSetup::detect(host_system().as_ref())
+            .map(|setup| setup.check(&plan.config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine with OpenRGB, liquidctl or ckb-next, none without
+#[tauri::command]
+pub async fn check_rgb(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        RgbSetup::detect(host_system().as_ref())
+            .map(|setup| setup.check(&plan.config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan against the VPNs, firewall ruleset and sockets of this machine
+#[tauri::command]
+pub async fn check_networking(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        NetworkingSetup::detect(host_system().as_ref())
+            .map(|setup| setup.check(&plan.config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// The networking preset for this machine, to stack on a profile
+#[tauri::command]
+pub async fn networking_preset() -> CommandResult<ConfigLayer> {
+    tokio::task::spawn_blocking(move || {
+        NetworkingSetup::detect(host_system().as_ref())
+            .unwrap_or_default()
+            .preset()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine with a Bluetooth controller, none without
+#[tauri::command]
+pub async fn check_bluetooth_audio(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        BluetoothSetup::detect(host_system().as_ref())
+            .map(|setup| setup.check(&plan.config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// The Bluetooth audio preset for this machine's controllers, to stack on a profile;
+/// plans leave Bluetooth to the profile without it. None without a controller
+#[tauri::command]
+pub async fn bluetooth_audio_preset() -> CommandResult<Option<ConfigLayer>> {
+    tokio::task::spawn_blocking(move || {
+        BluetoothSetup::detect(host_system().as_ref()).map(|setup| setup.layer())
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Input devices attached now, with the options each needs
+#[tauri::command]
+pub async fn input_device_matrix() -> CommandResult<Vec<InputAnalysis>> {
+    tokio::task::spawn_blocking(move || {
+        input::analyze(&input::detect_input_devices(host_system().as_ref()))
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan that would leave attached input devices without their drivers
+#[tauri::command]
+pub async fn check_input_devices(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let devices = input::detect_input_devices(host_system().as_ref());
+        devices.check(&plan.config)
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Findings for a plan on a machine with cameras or camera programs, none without
+#[tauri::command]
+pub async fn check_camera(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let hardware =
+            HardwareSnapshot::capture(system.as_ref()).map_err(|error| format!("{:#}", error))?;
+        Ok(hardware
+            .camera
+            .map(|camera| camera.check(&plan.config))
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Findings for a plan on a machine that prints or scans, none when it does neither
+#[tauri::command]
+pub async fn check_printing(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let hardware =
+            HardwareSnapshot::capture(system.as_ref()).map_err(|error| format!("{:#}", error))?;
+        Ok(hardware
+            .printing
+            .map(|printing| printing.check(&plan.config))
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Quirks of this machine, after fetching the published list if it is newer
+/// Offline, or when the list cannot be verified, the loaded or bundled quirks are used
+#[tauri::command]
+pub async fn hardware_quirks(paths: State<'_, Paths>) -> CommandResult<Vec<Quirk>> {
+    let client =
+        http_client(&NetworkSettings::default()).map_err(|error| format!("{:#}", error))?;
+    let mut quirks = QuirkDatabase::load(&paths);
+    if let Ok(keyring) = Keyring::open(&paths) {
+        let _ = quirks
+            .update(
+                &client,
+                host_system().as_ref(),
+                &keyring,
+                DEFAULT_QUIRKS_URL,
+                &paths,
+            )
+            .await;
+    }
+    tokio::task::spawn_blocking(move || {
+        let hardware = HardwareSnapshot::capture(host_system().as_ref())
+            .map_err(|error| format!("{:#}", error))?;
+        Ok(quirks.matching(&hardware).into_iter().cloned().collect())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Reduces a plan for fast edit-build-boot cycles; `previous` is the config of the last
+/// build in the same tree, to list the options that changed since
+#[tauri::command]
+pub fn quick_build_plan(
+    plan: BuildPlan,
+    previous: Option<KernelConfig>,
+    settings: Option<QuickBuild>,
+) -> CommandResult<QuickBuildPlan> {
+    let quick = settings
+        .unwrap_or_default()
+        .prepare(&plan, previous.as_ref());
+    quick
+        .plan
+        .validate()
+        .map_err(|error| format!("{:#}", error))?;
+    Ok(quick)
+}
+
+/// Builds a quick build plan in `source_dir`, streaming output through `BuildOutput` and
+/// taking stall answers through `BuildControl`; the time box flags a build that stopped
+/// being quick
+/// The config goes through the configure hooks first; the workspace watch skips the build
+/// and the tree is snapshotted once it is done
+#[tauri::command]
+pub async fn run_quick_build<R: Runtime>(
+    app: AppHandle<R>,
+    paths: State<'_, Paths>,
+    output: State<'_, BuildOutput>,
+    control: State<'_, BuildControl>,
+    watch: State<'_, WorkspaceWatch>,
+    quick: QuickBuildPlan,
+    source_dir: PathBuf,
+) -> CommandResult<BuildOutcome> {
+    let system = host_system();
+    let paths = paths.inner().clone();
+    watch.building.store(true, Ordering::Relaxed);
+    let options = {
+        let (system, paths, source_dir) = (Arc::clone(&system), paths.clone(), source_dir.clone());
+        tokio::task::spawn_blocking(move || -> anyhow::Result<BuildOptions> {
+            let toolchain = CrossToolchain::detect(system.as_ref(), quick.plan.arch)?;
+            let mut options = BuildOptions {
+                jobs: std::thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+                ..BuildOptions::default()
+            };
+            quick.apply(&mut options, &toolchain);
+            let run = configure_tree(
+                &paths,
+                system.as_ref(),
+                quick.plan,
+                &source_dir,
+                &options.env,
+            )?;
+            // Scripts must not become a way around the organization's policy
+            run.plan.validate()?;
+            Ok(options)
+        })
+        .await
+        .map_err(|error| error.to_string())
+        .and_then(|options| options.map_err(|error| format!("{:#}", error)))
+    };
+    let options = match options {
+        Ok(options) => options,
+        Err(error) => {
+            watch.building.store(false, Ordering::Relaxed);
+            return Err(error);
+        }
+    };
+    let (events, receiver) = mpsc::unbounded_channel();
+    output.forward(app, receiver, StreamSettings::default());
+    let mut actions = control.attach();
+    let outcome = BuildExecutor::new(source_dir.clone(), options, system)
+        .run_supervised(&events, &mut actions)
+        .await;
+    // Whatever the build generated is KernelForge's own doing, so the snapshot takes it in
+    let snapshot = tokio::task::spawn_blocking(move || {
+        WorkspaceSnapshot::take(&source_dir).and_then(|snapshot| snapshot.save(&paths))
+    })
+    .await;
+    watch.building.store(false, Ordering::Relaxed);
+    snapshot
+        .map_err(|error| error.to_string())?
+        .map_err(|error| format!("{:#}", error))?;
+    outcome.map_err(|error| format!("{:#}", error))
+}
+
+/// Records the source tree as KernelForge left it; call after every step that changes it
+#[tauri::command]
+pub async fn snapshot_workspace(paths: State<'_, Paths>, source_dir: PathBuf) -> CommandResult<()> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        WorkspaceSnapshot::take(&source_dir)
+            .and_then(|snapshot| snapshot.save(&paths))
+            .map_err(|error| format!("{:#}", error))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Marks `plan` dirty with the edits made to `source_dir` outside KernelForge, or clean
+/// when there are none; a tree without a snapshot leaves the plan as it is
+#[tauri::command]
+pub async fn check_workspace(
+    paths: State<'_, Paths>,
+    source_dir: PathBuf,
+    mut plan: BuildPlan,
+) -> CommandResult<BuildPlan> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let changes =
+            outside_changes(&paths, &source_dir).map_err(|error| format!("{:#}", error))?;
+        if let Some(changes) = changes {
+            plan.outside_changes = Some(changes).filter(OutsideChanges::is_dirty);
+        }
+        Ok(plan)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Polling task watching the open source tree, managed as Tauri state
+#[derive(Debug, Default)]
+pub struct WorkspaceWatch {
+    task: Mutex<Option<JoinHandle<()>>>,
+    /// Set while KernelForge builds, as the files kbuild generates are no outside edits
+    building: Arc<AtomicBool>,
+}
+
+/// Watches `source_dir` for outside edits, replacing the previous watch, and emits
+/// `WORKSPACE_CHANGED_EVENT` each time the set of edits changes
+#[tauri::command]
+pub fn watch_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    watch: State<'_, WorkspaceWatch>,
+    paths: State<'_, Paths>,
+    source_dir: PathBuf,
+) -> CommandResult<()> {
+    let paths = paths.inner().clone();
+    let building = Arc::clone(&watch.building);
+    let task = tauri::async_runtime::spawn(async move {
+        let mut ticker = time::interval(WORKSPACE_POLL);
+        let mut last = OutsideChanges::default();
+        loop {
+            ticker.tick().await;
+            if building.load(Ordering::Relaxed) {
+                continue;
+            }
+            let (paths, source_dir) = (paths.clone(), source_dir.clone());
+            // The snapshot is reloaded every time, as KernelForge retakes it after its own steps
+            let scan =
+                tokio::task::spawn_blocking(move || outside_changes(&paths, &source_dir)).await;
+            let Ok(Ok(Some(changes))) = scan else {
+                continue;
+            };
+            if changes.modified != last.modified
+                || changes.added != last.added
+                || changes.removed != last.removed
+            {
+                let _ = app.emit(WORKSPACE_CHANGED_EVENT, &changes);
+                last = changes;
+            }
+        }
+    });
+    let mut current = watch
+        .task
+        .lock()
+        .map_err(|_| "workspace watch is poisoned".to_string())?;
+    if let Some(previous) = current.replace(task) {
+        previous.abort();
+    }
+    Ok(())
+}
+
+/// Archives a fetched, patched and configured tree so rebuilding it with another config
+/// can skip the preparation
+#[tauri::command]
+pub async fn create_checkpoint(
+    paths: State<'_, Paths>,
+    key: CheckpointKey,
+    source_dir: PathBuf,
+) -> CommandResult<Checkpoint> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mut index = CacheIndex::open(&paths).map_err(|error| format!("{:#}", error))?;
+        Checkpoint::create(host_system().as_ref(), &mut index, key, &source_dir)
+            .map_err(|error| format!("{:#}", error))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Restores the checkpoint of `key` into `destination` and records the tree as
+/// KernelForge left it; None when no checkpoint exists and the tree must be prepared
+#[tauri::command]
+pub async fn restore_checkpoint(
+    paths: State<'_, Paths>,
+    key: CheckpointKey,
+    destination: PathBuf,
+) -> CommandResult<Option<Checkpoint>> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mut index = CacheIndex::open(&paths).map_err(|error| format!("{:#}", error))?;
+        let Some(checkpoint) =
+            Checkpoint::find(&index, &key).map_err(|error| format!("{:#}", error))?
+        else {
+            return Ok(None);
+        };
+        checkpoint
+            .restore(host_system().as_ref(), &mut index, &destination)
+            .and_then(|()| WorkspaceSnapshot::take(&destination))
+            .and_then(|snapshot| snapshot.save(&paths))
+            .map_err(|error| format!("{:#}", error))?;
+        Ok(Some(checkpoint))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Switches the plan's image and module compression
+#[tauri::command]
+pub fn apply_compression(
+    mut plan: BuildPlan,
+    choice: CompressionChoice,
+) -> CommandResult<BuildPlan> {
+    for (symbol, value) in choice.layer().assignments {
+        plan.config.set(&symbol, value);
+    }
+    plan.validate().map_err(|error| format!("{:#}", error))?;
+    Ok(plan)
+}
+
+/// Whether this machine can build, load and boot the plan's compression choice
+#[tauri::command]
+pub async fn check_compression(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        CompressionChoice::of(&plan.config).check(host_system().as_ref(), plan.arch, &plan.config)
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Size and decompression time of each image format, measured on the uncompressed
+/// image of the last build in `source_dir`
+#[tauri::command]
+pub async fn compression_tradeoffs(
+    paths: State<'_, Paths>,
+    source_dir: PathBuf,
+    arch: TargetArch,
+) -> CommandResult<Vec<CompressionTradeoff>> {
+    let scratch = paths.cache_dir().join("compression");
+    tokio::task::spawn_blocking(move || {
+        measure_tradeoffs(
+            host_system().as_ref(),
+            &source_dir.join(uncompressed_image(arch)),
+            &scratch,
+        )
+        .map_err(|error| format!("{:#}", error))
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Holds the plan to an image format, setting the EFI stub options to match
+#[tauri::command]
+pub fn set_image_format(mut plan: BuildPlan, format: ImageFormat) -> CommandResult<BuildPlan> {
+    if !format.is_available(plan.arch) {
+        return Err(format!(
+            "{:?} images cannot be built for {}",
+            format,
+            plan.arch.make_arch()
+        ));
+    }
+    for (symbol, value) in format.layer().assignments {
+        plan.config.set(&symbol, value);
+    }
+    plan.image_format = format;
+    plan.validate().map_err(|error| format!("{:#}", error))?;
+    Ok(plan)
+}
+
+/// Whether this machine's firmware and boot loader can start the image the plan builds
+#[tauri::command]
+pub async fn check_image_format(plan: BuildPlan) -> CommandResult<Vec<Advisory>> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let bootloader = Bootloader::detect(system.as_ref());
+        ImageFormat::check(
+            plan.arch,
+            &plan.config,
+            Firmware::detect(system.as_ref()),
+            &bootloader,
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Where the image the plan builds is installed and what the machine boots it from
+#[tauri::command]
+pub async fn image_placement(plan: BuildPlan, version: String) -> CommandResult<ImagePlacement> {
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        ImagePlacement::of(
+            &plan,
+            &version,
+            InstallTemplate::detect(system.as_ref()),
+            &Bootloader::detect(system.as_ref()),
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())
+}
+
+/// Built-in and custom tuning profiles with what each extends
+#[tauri::command]
+pub fn list_profiles(paths: State<'_, Paths>) -> CommandResult<Vec<ProfileEntry>> {
+    let library = ProfileLibrary::load(&paths).map_err(|error| format!("{:#}", error))?;
+    Ok(library.entries())
+}
+
+/// Adds or replaces a custom profile after checking its inheritance
+#[tauri::command]
+pub fn define_profile(paths: State<'_, Paths>, profile: CustomProfile) -> CommandResult<()> {
+    let mut library = ProfileLibrary::load(&paths).map_err(|error| format!("{:#}", error))?;
+    library
+        .define(profile)
+        .and_then(|_| library.save(&paths))
+        .map_err(|error| format!("{:#}", error))
+}
+
+#[tauri::command]
+pub fn remove_profile(paths: State<'_, Paths>, name: String) -> CommandResult<()> {
+    let mut library = ProfileLibrary::load(&paths).map_err(|error| format!("{:#}", error))?;
+    library
+        .remove(&name)
+        .and_then(|_| library.save(&paths))
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// Applies a profile and everything it extends over the plan's config
+#[tauri::command]
+pub fn apply_profile(
+    paths: State<'_, Paths>,
+    mut plan: BuildPlan,
+    name: String,
+) -> CommandResult<BuildPlan> {
+    let library = ProfileLibrary::load(&paths).map_err(|error| format!("{:#}", error))?;
+    let layers = library
+        .layers(&name)
+        .map_err(|error| format!("{:#}", error))?;
+    plan.config = layers
+        .into_iter()
+        .fold(
+            ConfigGenerator::from_config("plan", &plan.config),
+            |generator, layer| generator.with_layer(layer),
+        )
+        .generate();
+    plan.validate().map_err(|error| format!("{:#}", error))?;
+    Ok(plan)
+}
+
+/// Hook scripts installed for each hook point, by file name in run order
+#[tauri::command]
+pub fn list_hook_scripts(paths: State<'_, Paths>) -> CommandResult<Vec<(HookPoint, Vec<String>)>> {
+    HookPoint::ALL
+        .into_iter()
+        .map(|point| {
+            let scripts = hook_scripts(&paths, point)?
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect();
+            Ok((point, scripts))
+        })
+        .collect::<anyhow::Result<_>>()
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// Saves a hook script after checking it parses
+#[tauri::command]
+pub fn save_hook_script(
+    paths: State<'_, Paths>,
+    point: HookPoint,
+    name: String,
+    source: String,
+) -> CommandResult<PathBuf> {
+    save_script(&paths, point, &name, &source).map_err(|error| format!("{:#}", error))
+}
+
+/// Runs the scripts of a hook point over the plan and, before install, the command line
+#[tauri::command]
+pub async fn run_hook_scripts(
+    paths: State<'_, Paths>,
+    point: HookPoint,
+    plan: BuildPlan,
+    cmdline: Option<CmdlinePlan>,
+) -> CommandResult<HookRun> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let run = run_hooks(&paths, point, plan, cmdline)?;
+        // Scripts must not become a way around the organization's policy
+        run.plan.validate()?;
+        Ok(run)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error: anyhow::Error| format!("{:#}", error))
+}
+
+/// Boot entry for a built kernel, its command line carrying the plan's parameters through
+/// the pre-install hooks
+#[tauri::command]
+pub async fn prepare_boot_entry(
+    paths: State<'_, Paths>,
+    plan: BuildPlan,
+    version: String,
+    kernel_image: PathBuf,
+    initramfs: Option<PathBuf>,
+    cmdline: CmdlinePlan,
+) -> CommandResult<(BootEntryRequest, Vec<HookReport>)> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        boot_entry(
+            &paths,
+            host_system().as_ref(),
+            &plan,
+            &version,
+            &kernel_image,
+            initramfs.as_deref(),
+            cmdline,
+        )
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error| format!("{:#}", error))
+}
+
+/// Installed plugins with what each was granted, and plugins whose manifest is unusable
+#[tauri::command]
+pub fn list_plugins(paths: State<'_, Paths>) -> CommandResult<PluginDiscovery> {
+    discover(&paths).map_err(|error| format!("{:#}", error))
+}
+
+/// Sets the capabilities a plugin may use; an empty list disables it
+#[tauri::command]
+pub fn grant_plugin(
+    paths: State<'_, Paths>,
+    id: String,
+    capabilities: Vec<Capability>,
+) -> CommandResult<()> {
+    let plugin = find_plugin(&paths, &id).map_err(|error| format!("{:#}", error))?;
+    if let Some(undeclared) = capabilities
+        .iter()
+        .find(|capability| !plugin.manifest.capabilities.contains(capability))
+    {
+        return Err(format!(
+            "plugin {} does not declare {}",
+            id,
+            undeclared.method()
+        ));
+    }
+    let mut grants = PluginGrants::load(&paths).map_err(|error| format!("{:#}", error))?;
+    grants.set(&id, capabilities);
+    grants.save(&paths).map_err(|error| format!("{:#}", error))
+}
+
+/// Patches a plugin offers for a kernel version
+#[tauri::command]
+pub async fn plugin_patch_sources(
+    paths: State<'_, Paths>,
+    id: String,
+    version: String,
+) -> CommandResult<Vec<PatchSource>> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let plugin = find_plugin(&paths, &id)?;
+        PluginSession::start(host_system().as_ref(), &plugin)?.patch_sources(&version)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error| format!("{:#}", error))
+}
+
+/// Profiles a plugin offers, for `define_profile` to import
+#[tauri::command]
+pub async fn plugin_profiles(
+    paths: State<'_, Paths>,
+    id: String,
+) -> CommandResult<Vec<CustomProfile>> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let plugin = find_plugin(&paths, &id)?;
+        PluginSession::start(host_system().as_ref(), &plugin)?.profiles()
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error| format!("{:#}", error))
+}
+
+/// Findings of an analyzer plugin for the plan on this machine
+#[tauri::command]
+pub async fn plugin_analyze(
+    paths: State<'_, Paths>,
+    id: String,
+    plan: BuildPlan,
+) -> CommandResult<Vec<Advisory>> {
+    let paths = paths.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let system = host_system();
+        let plugin = find_plugin(&paths, &id)?;
+        let hardware = HardwareSnapshot::capture(system.as_ref())?;
+        PluginSession::start(system.as_ref(), &plugin)?.analyze(&plan, &hardware)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error| format!("{:#}", error))
+}
+
+/// Checks a plan before it is built, including the organization's policy
+#[tauri::command]
+pub async fn validate_plan(plan: BuildPlan) -> CommandResult<()> {
+    tokio::task::spawn_blocking(move || plan.validate())
+        .await
+        .map_err(|error| error.to_string())?
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// The installed organization policy, None when the machine has none
+#[tauri::command]
+pub async fn installed_policy() -> CommandResult<Option<Policy>> {
+    tokio::task::spawn_blocking(|| Policy::installed(host_system().as_ref()))
+        .await
+        .map_err(|error| error.to_string())?
+        .map_err(|error| format!("{:#}", error))
+}
+
+/// Options of the plan breaking the installed policy, for the editor to mark
+#[tauri::command]
+pub async fn policy_violations(plan: BuildPlan) -> CommandResult<Vec<PolicyViolation>> {
+    tokio::task::spawn_blocking(move || {
+        let policy = Policy::installed(host_system().as_ref())?;
+        Ok(policy
+            .map(|policy| policy.violations(&plan))
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+    .map_err(|error: anyhow::Error| format!("{:#}", error))
+}
+
+/// Manages the state the commands above rely on and registers every command with the IPC layer
+/// The version catalog is refreshed in the background from startup on
+pub fn register<R: Runtime>(builder: Builder<R>, paths: Paths) -> Builder<R> {
+    let catalog_paths = paths.clone();
+    builder
+        .setup(move |_app| {
+            let client = http_client(&NetworkSettings::default())?;
+            tauri::async_runtime::spawn(async move {
+                let _ = VersionCatalog::spawn_refresh(
+                    client,
+                    catalog_paths,
+                    CatalogSettings::default(),
+                )
+                .await;
+            });
+            Ok(())
+        })
+        .manage(paths)
+        .manage(ConfigBrowser::default())
+        .manage(BuildOutput::default())
+        .manage(BuildControl::default())
+        .manage(WorkspaceWatch::default())
+        .invoke_handler(tauri::generate_handler![
+            search_config,
+            ack_build_output,
+            resolve_build_stall,
+            results_dashboard,
+            trusted_keys,
+            artifact_signatures,
+            virtme_boot,
+            kernel_versions,
+            source_versions,
+            preview_selected_patches,
+            check_patch_pins,
+            patch_matrix,
+            wizard_state,
+            wizard_advance,
+            explain_plan,
+            machine_capture_script,
+            import_machine_capture,
+            sbc_boards,
+            available_device_trees,
+            verify_running_config,
+            preview_module_policy,
+            capture_cmdline,
+            diff_cmdline,
+            check_hibernation,
+            check_containers,
+            check_thunderbolt,
+            check_sensors,
+            check_rgb,
+            check_networking,
+            networking_preset,
+            check_bluetooth_audio,
+            bluetooth_audio_preset,
+            input_device_matrix,
+            check_input_devices,
+            check_camera,
+            check_printing,
+            hardware_quirks,
+            quick_build_plan,
+            run_quick_build,
+            snapshot_workspace,
+            check_workspace,
+            watch_workspace,
+            create_checkpoint,
+            restore_checkpoint,
+            apply_compression,
+            check_compression,
+            compression_tradeoffs,
+            set_image_format,
+            check_image_format,
+            image_placement,
+            list_profiles,
+            define_profile,
+            remove_profile,
+            apply_profile,
+            list_hook_scripts,
+            save_hook_script,
+            run_hook_scripts,
+            prepare_boot_entry,
+            list_plugins,
+            grant_plugin,
+            plugin_patch_sources,
+            plugin_profiles,
+            plugin_analyze,
+            validate_plan,
+            installed_policy,
+            policy_violations,
+        ])
+}
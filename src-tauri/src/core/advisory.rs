@@ -0,0 +1,50 @@
+// src-tauri/src/core/advisory.rs
+
+use serde::{Deserialize, Serialize};
+
+use super::config::ConfigSuggestion;
+use super::i18n::Message;
+
+/// How strongly an advisory should be surfaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    /// The plan must not proceed until this is resolved
+    Blocker,
+}
+
+/// A finding from one of the compatibility advisors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Advisory {
+    pub severity: Severity,
+    pub title: Message,
+    pub detail: Message,
+    /// Config changes that would resolve the finding
+    pub suggestions: Vec<ConfigSuggestion>,
+}
+
+impl Advisory {
+    /// Creates an advisory from the catalog entries `key` (title) and `key-detail`
+    pub fn new(severity: Severity, key: &str, args: &[(&str, String)]) -> Self {
+        Advisory {
+            severity,
+            title: Message::new(key, args),
+            detail: Message::new(&format!("{}-detail", key), args),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Attaches a config change that resolves the finding
+    pub fn suggest(mut self, suggestion: ConfigSuggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+}
+
+/// Whether any advisory blocks the plan
+pub fn has_blocker(advisories: &[Advisory]) -> bool {
+    advisories
+        .iter()
+        .any(|advisory| advisory.severity == Severity::Blocker)
+}
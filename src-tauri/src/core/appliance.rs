@@ -0,0 +1,446 @@
+// src-tauri/src/core/appliance.rs
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::generator::{BaseConfig, ConfigGenerator};
+use super::hardware::{Firmware, HardwareSnapshot};
+use super::resolver::ConfigLayer;
+use super::system::SystemFacade;
+
+/// Image size an appliance kernel is expected to stay under
+pub const APPLIANCE_SIZE_TARGET: u64 = 10 * 1024 * 1024;
+
+/// Symbols every appliance needs to boot to a userspace init
+const BOOT_CORE: &[&str] = &[
+    "CONFIG_64BIT",
+    "CONFIG_PRINTK",
+    "CONFIG_BINFMT_ELF",
+    "CONFIG_BINFMT_SCRIPT",
+    "CONFIG_MULTIUSER",
+    "CONFIG_FUTEX",
+    "CONFIG_EPOLL",
+    "CONFIG_SIGNALFD",
+    "CONFIG_TIMERFD",
+    "CONFIG_EVENTFD",
+    "CONFIG_SHMEM",
+    "CONFIG_INOTIFY_USER",
+    "CONFIG_FHANDLE",
+    "CONFIG_BLOCK",
+    "CONFIG_PROC_FS",
+    "CONFIG_SYSFS",
+    "CONFIG_TMPFS",
+    "CONFIG_DEVTMPFS",
+    "CONFIG_DEVTMPFS_MOUNT",
+    "CONFIG_TTY",
+    "CONFIG_UNIX",
+];
+
+/// Choices that keep the image small; the kernel boots without them, just larger
+const SIZE_CHOICES: &[&str] = &["CONFIG_CC_OPTIMIZE_FOR_SIZE", "CONFIG_KERNEL_XZ"];
+
+/// Kernel drivers found on the host and the symbols that build them in, driver last
+const DRIVER_SYMBOLS: &[(&str, &[&str])] = &[
+    (
+        "virtio_pci",
+        &["CONFIG_VIRTIO_MENU", "CONFIG_PCI", "CONFIG_VIRTIO_PCI"],
+    ),
+    (
+        "virtio_net",
+        &["CONFIG_NETDEVICES", "CONFIG_NET_CORE", "CONFIG_VIRTIO_NET"],
+    ),
+    ("virtio_blk", &["CONFIG_VIRTIO_BLK"]),
+    (
+        "virtio_scsi",
+        &["CONFIG_SCSI", "CONFIG_BLK_DEV_SD", "CONFIG_SCSI_VIRTIO"],
+    ),
+    ("virtio_console", &["CONFIG_VIRTIO_CONSOLE"]),
+    ("virtio_balloon", &["CONFIG_VIRTIO_BALLOON"]),
+    ("virtio_gpu", &["CONFIG_DRM", "CONFIG_DRM_VIRTIO_GPU"]),
+    ("bochs", &["CONFIG_DRM", "CONFIG_DRM_BOCHS"]),
+    ("vmxnet3", &["CONFIG_NETDEVICES", "CONFIG_VMXNET3"]),
+    (
+        "vmw_pvscsi",
+        &["CONFIG_SCSI", "CONFIG_BLK_DEV_SD", "CONFIG_VMWARE_PVSCSI"],
+    ),
+    ("hv_netvsc", &["CONFIG_HYPERV", "CONFIG_HYPERV_NET"]),
+    (
+        "hv_storvsc",
+        &[
+            "CONFIG_HYPERV",
+            "CONFIG_BLK_DEV_SD",
+            "CONFIG_HYPERV_STORAGE",
+        ],
+    ),
+    (
+        "ahci",
+        &["CONFIG_ATA", "CONFIG_BLK_DEV_SD", "CONFIG_SATA_AHCI"],
+    ),
+    (
+        "ata_piix",
+        &["CONFIG_ATA", "CONFIG_BLK_DEV_SD", "CONFIG_ATA_PIIX"],
+    ),
+    ("nvme", &["CONFIG_BLK_DEV_NVME"]),
+    ("e1000", &["CONFIG_NETDEVICES", "CONFIG_E1000"]),
+    ("e1000e", &["CONFIG_NETDEVICES", "CONFIG_E1000E"]),
+    ("igb", &["CONFIG_NETDEVICES", "CONFIG_IGB"]),
+    ("igc", &["CONFIG_NETDEVICES", "CONFIG_IGC"]),
+    ("r8169", &["CONFIG_NETDEVICES", "CONFIG_R8169"]),
+    (
+        "xhci_hcd",
+        &["CONFIG_USB_SUPPORT", "CONFIG_USB", "CONFIG_USB_XHCI_HCD"],
+    ),
+    (
+        "ehci_pci",
+        &["CONFIG_USB_SUPPORT", "CONFIG_USB", "CONFIG_USB_EHCI_HCD"],
+    ),
+    (
+        "usbhid",
+        &["CONFIG_HID", "CONFIG_HID_GENERIC", "CONFIG_USB_HID"],
+    ),
+    (
+        "i8042",
+        &[
+            "CONFIG_INPUT",
+            "CONFIG_SERIO_I8042",
+            "CONFIG_KEYBOARD_ATKBD",
+        ],
+    ),
+    ("i915", &["CONFIG_DRM", "CONFIG_DRM_I915"]),
+    ("amdgpu", &["CONFIG_DRM", "CONFIG_DRM_AMDGPU"]),
+];
+
+/// Drivers able to expose the root block device
+const STORAGE_DRIVERS: &[&str] = &[
+    "virtio_blk",
+    "virtio_scsi",
+    "vmw_pvscsi",
+    "hv_storvsc",
+    "ahci",
+    "ata_piix",
+    "nvme",
+];
+
+/// Filesystems the root may live on and the symbol for each
+const ROOT_FILESYSTEMS: &[(&str, &str)] = &[
+    ("ext4", "CONFIG_EXT4_FS"),
+    ("xfs", "CONFIG_XFS_FS"),
+    ("btrfs", "CONFIG_BTRFS_FS"),
+    ("f2fs", "CONFIG_F2FS_FS"),
+    ("squashfs", "CONFIG_SQUASHFS"),
+    ("erofs", "CONFIG_EROFS_FS"),
+];
+
+/// Optional capabilities the user ticks on the appliance checklist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ApplianceFeature {
+    Networking,
+    Ipv6,
+    Firewall,
+    SerialConsole,
+    GraphicalConsole,
+    Usb,
+    Containers,
+    Initramfs,
+    LoadableModules,
+}
+
+impl ApplianceFeature {
+    pub const ALL: [ApplianceFeature; 9] = [
+        ApplianceFeature::Networking,
+        ApplianceFeature::Ipv6,
+        ApplianceFeature::Firewall,
+        ApplianceFeature::SerialConsole,
+        ApplianceFeature::GraphicalConsole,
+        ApplianceFeature::Usb,
+        ApplianceFeature::Containers,
+        ApplianceFeature::Initramfs,
+        ApplianceFeature::LoadableModules,
+    ];
+
+    /// Symbols the feature turns on
+    pub fn symbols(self) -> &'static [&'static str] {
+        match self {
+            ApplianceFeature::Networking => &["CONFIG_NET", "CONFIG_INET", "CONFIG_PACKET"],
+            ApplianceFeature::Ipv6 => &["CONFIG_NET", "CONFIG_INET", "CONFIG_IPV6"],
+            ApplianceFeature::Firewall => &[
+                "CONFIG_NETFILTER",
+                "CONFIG_NF_CONNTRACK",
+                "CONFIG_NF_TABLES",
+                "CONFIG_NF_TABLES_INET",
+            ],
+            ApplianceFeature::SerialConsole => {
+                &["CONFIG_SERIAL_8250", "CONFIG_SERIAL_8250_CONSOLE"]
+            }
+            ApplianceFeature::GraphicalConsole => &[
+                "CONFIG_VT",
+                "CONFIG_VT_CONSOLE",
+                "CONFIG_FB",
+                "CONFIG_FRAMEBUFFER_CONSOLE",
+                "CONFIG_DRM_SIMPLEDRM",
+            ],
+            ApplianceFeature::Usb => &["CONFIG_USB_SUPPORT", "CONFIG_USB"],
+            ApplianceFeature::Containers => &[
+                "CONFIG_CGROUPS",
+                "CONFIG_NAMESPACES",
+                "CONFIG_USER_NS",
+                "CONFIG_PID_NS",
+                "CONFIG_NET_NS",
+                "CONFIG_OVERLAY_FS",
+                "CONFIG_VETH",
+                "CONFIG_BRIDGE",
+            ],
+            ApplianceFeature::Initramfs => &["CONFIG_BLK_DEV_INITRD", "CONFIG_RD_ZSTD"],
+            ApplianceFeature::LoadableModules => &["CONFIG_MODULES", "CONFIG_MODULE_UNLOAD"],
+        }
+    }
+}
+
+/// Minimal kernel for a VM or kiosk: allnoconfig plus the detected hardware
+/// and the checked features, nothing else
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliancePlan {
+    pub features: BTreeSet<ApplianceFeature>,
+    /// Filesystem of the root device, defaults to the host's own root
+    pub root_fstype: String,
+    pub firmware: Firmware,
+    /// Host drivers that map to a known symbol
+    pub drivers: BTreeSet<String>,
+}
+
+impl AppliancePlan {
+    /// Plans an appliance for the snapshotted machine
+    pub fn new(snapshot: &HardwareSnapshot, features: BTreeSet<ApplianceFeature>) -> Self {
+        let drivers = snapshot
+            .drivers()
+            .into_iter()
+            .filter(|driver| DRIVER_SYMBOLS.iter().any(|(name, _)| name == driver))
+            .collect();
+        AppliancePlan {
+            features,
+            root_fstype: snapshot
+                .root_fstype
+                .clone()
+                .unwrap_or_else(|| "ext4".to_string()),
+            firmware: snapshot.firmware,
+            drivers,
+        }
+    }
+
+    /// Whether hardware drivers end up as modules instead of built in
+    fn modular(&self) -> bool {
+        self.features.contains(&ApplianceFeature::LoadableModules)
+            && self.features.contains(&ApplianceFeature::Initramfs)
+    }
+
+    /// Layer enabling exactly what the plan needs on top of allnoconfig
+    pub fn layer(&self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("appliance");
+        for symbol in BOOT_CORE.iter().chain(SIZE_CHOICES) {
+            layer.set(symbol, ConfigValue::yes());
+        }
+        if self.firmware == Firmware::Uefi {
+            layer.set("CONFIG_EFI", ConfigValue::yes());
+            layer.set("CONFIG_EFI_STUB", ConfigValue::yes());
+        }
+        if let Some(symbol) = root_filesystem_symbol(&self.root_fstype) {
+            layer.set(symbol, ConfigValue::yes());
+        }
+        for feature in &self.features {
+            for symbol in feature.symbols() {
+                layer.set(symbol, ConfigValue::yes());
+            }
+        }
+        // The root device driver must stay built in unless an initramfs can load it
+        let driver_value = if self.modular() {
+            ConfigValue::module()
+        } else {
+            ConfigValue::yes()
+        };
+        for (driver, symbols) in DRIVER_SYMBOLS {
+            if !self.drivers.contains(*driver) {
+                continue;
+            }
+            let (leaf, parents) = symbols.split_last().expect("driver maps to a symbol");
+            for symbol in parents {
+                layer.set(symbol, ConfigValue::yes());
+            }
+            layer.set(leaf, driver_value.clone());
+        }
+        layer
+    }
+
+    /// Generator starting from `make allnoconfig` in the given tree
+    pub fn generator(
+        &self,
+        system: &dyn SystemFacade,
+        source_dir: &Path,
+        output_dir: &Path,
+    ) -> Result<ConfigGenerator> {
+        Ok(
+            ConfigGenerator::from_base(BaseConfig::Allnoconfig, system, source_dir, output_dir)?
+                .with_layer(self.layer()),
+        )
+    }
+
+    /// Checks that a resolved config can still mount the root and reach init
+    pub fn validate(&self, config: &KernelConfig) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        let builtin = |symbol: &str| config.get(symbol).and_then(ConfigValue::as_str) == Some("y");
+
+        let missing: Vec<&str> = BOOT_CORE
+            .iter()
+            .copied()
+            .filter(|symbol| !config.is_enabled(symbol))
+            .collect();
+        if !missing.is_empty() {
+            let mut advisory = Advisory::new(
+                Severity::Blocker,
+                "appliance-missing-core",
+                &[("symbols", missing.join(", "))],
+            );
+            for symbol in missing {
+                advisory = advisory.suggest(ConfigSuggestion::new(
+                    symbol,
+                    ConfigValue::yes(),
+                    "required to boot to init",
+                ));
+            }
+            advisories.push(advisory);
+        }
+
+        let larger: Vec<&str> = SIZE_CHOICES
+            .iter()
+            .copied()
+            .filter(|symbol| !config.is_enabled(symbol))
+            .collect();
+        if !larger.is_empty() {
+            let mut advisory = Advisory::new(
+                Severity::Info,
+                "appliance-not-size-optimized",
+                &[("symbols", larger.join(", "))],
+            );
+            for symbol in larger {
+                advisory = advisory.suggest(ConfigSuggestion::new(
+                    symbol,
+                    ConfigValue::yes(),
+                    "keeps the appliance image small",
+                ));
+            }
+            advisories.push(advisory);
+        }
+
+        match root_filesystem_symbol(&self.root_fstype) {
+            Some(symbol) if !builtin(symbol) && !self.modular() => advisories.push(
+                Advisory::new(
+                    Severity::Blocker,
+                    "appliance-root-fs",
+                    &[("fstype", self.root_fstype.clone())],
+                )
+                .suggest(ConfigSuggestion::new(
+                    symbol,
+                    ConfigValue::yes(),
+                    "the root filesystem must be built in without an initramfs",
+                )),
+            ),
+            Some(_) => {}
+            None => advisories.push(Advisory::new(
+                Severity::Blocker,
+                "appliance-root-fs-unknown",
+                &[("fstype", self.root_fstype.clone())],
+            )),
+        }
+
+        let storage = DRIVER_SYMBOLS
+            .iter()
+            .filter(|(driver, _)| {
+                STORAGE_DRIVERS.contains(driver) && self.drivers.contains(*driver)
+            })
+            .filter_map(|(_, symbols)| symbols.last())
+            .any(|symbol| builtin(symbol) || (self.modular() && config.is_enabled(symbol)));
+        if !storage {
+            advisories.push(Advisory::new(
+                Severity::Blocker,
+                "appliance-no-storage",
+                &[],
+            ));
+        }
+
+        if self.features.contains(&ApplianceFeature::Initramfs)
+            && !config.is_enabled("CONFIG_BLK_DEV_INITRD")
+        {
+            advisories.push(
+                Advisory::new(Severity::Blocker, "appliance-no-initrd", &[]).suggest(
+                    ConfigSuggestion::new(
+                        "CONFIG_BLK_DEV_INITRD",
+                        ConfigValue::yes(),
+                        "the appliance boots through an initramfs",
+                    ),
+                ),
+            );
+        }
+
+        if self.firmware == Firmware::Uefi && !config.is_enabled("CONFIG_EFI_STUB") {
+            advisories.push(
+                Advisory::new(Severity::Warning, "appliance-no-efi-stub", &[]).suggest(
+                    ConfigSuggestion::new(
+                        "CONFIG_EFI_STUB",
+                        ConfigValue::yes(),
+                        "lets UEFI firmware start the kernel directly",
+                    ),
+                ),
+            );
+        }
+
+        let console = [
+            "CONFIG_SERIAL_8250_CONSOLE",
+            "CONFIG_VT_CONSOLE",
+            "CONFIG_VIRTIO_CONSOLE",
+        ]
+        .iter()
+        .any(|symbol| config.is_enabled(symbol));
+        if !console {
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "appliance-no-console",
+                &[],
+            ));
+        }
+
+        advisories
+    }
+
+    /// Warns when a built image exceeds the appliance size target
+    pub fn check_image(&self, image: &Path) -> Result<Option<Advisory>> {
+        let size = fs::metadata(image)
+            .with_context(|| format!("reading {}", image.display()))?
+            .len();
+        if size <= APPLIANCE_SIZE_TARGET {
+            return Ok(None);
+        }
+        Ok(Some(Advisory::new(
+            Severity::Warning,
+            "appliance-oversize",
+            &[
+                ("size", format!("{:.1}", size as f64 / (1024.0 * 1024.0))),
+                (
+                    "target",
+                    (APPLIANCE_SIZE_TARGET / (1024 * 1024)).to_string(),
+                ),
+            ],
+        )))
+    }
+}
+
+fn root_filesystem_symbol(fstype: &str) -> Option<&'static str> {
+    ROOT_FILESYSTEMS
+        .iter()
+        .find(|(name, _)| *name == fstype)
+        .map(|(_, symbol)| *symbol)
+}
@@ -0,0 +1,339 @@
+// src-tauri/src/core/attestation.rs
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::{sha256_file, SignatureStatus};
+use super::cflags::CompilerFlags;
+use super::clock::unix_now;
+use super::keyring::{Keyring, TrustedKey};
+use super::paths::Paths;
+use super::pipeline::PipelineStage;
+use super::state_file;
+use super::system::SystemFacade;
+use super::workspace::{outside_changes, OutsideChanges};
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+
+/// Link hash of the first step's predecessor
+const CHAIN_START: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A file and its digests, as in-toto names subjects and materials
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDescriptor {
+    pub name: String,
+    pub digest: BTreeMap<String, String>,
+}
+
+impl ResourceDescriptor {
+    /// Hashes `path`, naming it by its file name
+    pub fn of(path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("{} has no file name", path.display()))?
+            .to_string_lossy()
+            .to_string();
+        Ok(ResourceDescriptor {
+            name,
+            digest: BTreeMap::from([("sha256".to_string(), sha256_file(path)?)]),
+        })
+    }
+
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.get("sha256").map(String::as_str)
+    }
+}
+
+/// Who ran a step and what it consumed and produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepLink {
+    pub stage: PipelineStage,
+    /// `user@host` that ran the step
+    pub operator: String,
+    pub command: Vec<String>,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub materials: Vec<ResourceDescriptor>,
+    pub products: Vec<ResourceDescriptor>,
+    /// Link hash of the step before, so no step can be dropped or reordered unnoticed
+    pub previous: String,
+}
+
+impl StepLink {
+    /// sha256 of the step's canonical JSON
+    pub fn link_hash(&self) -> String {
+        let bytes = serde_json::to_vec(self).expect("step links serialize");
+        hex::encode(Sha256::digest(bytes))
+    }
+}
+
+/// SLSA provenance predicate: the build and every step leading to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub builder: String,
+    pub version: String,
+    pub plan: String,
+    /// Extra flags the kernel was compiled with, as handed to make
+    #[serde(default)]
+    pub compiler_flags: CompilerFlags,
+    pub steps: Vec<StepLink>,
+    /// Edits made to the source tree outside KernelForge, so a dirty build says so
+    #[serde(default)]
+    pub outside_changes: Option<OutsideChanges>,
+}
+
+/// in-toto statement binding the built artifacts to their provenance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attestation {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<ResourceDescriptor>,
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    pub predicate: Provenance,
+}
+
+impl Attestation {
+    /// Writes the statement to `path` and a detached armored signature next to it, made
+    /// with the user's own GnuPG key `signing_key`; returns the signature path
+    pub fn write_signed(
+        &self,
+        system: &dyn SystemFacade,
+        path: &Path,
+        signing_key: &str,
+    ) -> Result<PathBuf> {
+        state_file::write(path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        let signature = signature_path(path);
+        let output = system
+            .run(
+                "gpg",
+                &[
+                    "--batch",
+                    "--yes",
+                    "--armor",
+                    "--local-user",
+                    signing_key,
+                    "--output",
+                    &signature.to_string_lossy(),
+                    "--detach-sign",
+                    &path.to_string_lossy(),
+                ],
+            )
+            .context("running gpg")?;
+        if !output.success() {
+            bail!(
+                "signing {} failed: {}",
+                path.display(),
+                output.stderr.trim()
+            );
+        }
+        Ok(signature)
+    }
+
+    /// Problems in the step chain: broken links, or a step using something other than
+    /// what the step before produced
+    pub fn chain_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut previous = CHAIN_START.to_string();
+        let mut produced: BTreeMap<&str, &str> = BTreeMap::new();
+        for step in &self.predicate.steps {
+            if step.previous != previous {
+                problems.push(format!(
+                    "{} step is not linked to the step before it",
+                    step.stage.name()
+                ));
+            }
+            for material in &step.materials {
+                let known = produced.get(material.name.as_str());
+                if known.is_some_and(|digest| Some(*digest) != material.sha256()) {
+                    problems.push(format!(
+                        "{} step used a {} that differs from the one produced earlier",
+                        step.stage.name(),
+                        material.name
+                    ));
+                }
+            }
+            for product in &step.products {
+                if let Some(digest) = product.sha256() {
+                    produced.insert(&product.name, digest);
+                }
+            }
+            previous = step.link_hash();
+        }
+        for subject in &self.subject {
+            if produced.get(subject.name.as_str()).copied() != subject.sha256() {
+                problems.push(format!(
+                    "{} was not produced by any recorded step",
+                    subject.name
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// Records steps as the pipeline runs them
+#[derive(Debug, Clone)]
+pub struct AttestationBuilder {
+    provenance: Provenance,
+    operator: String,
+}
+
+impl AttestationBuilder {
+    pub fn new(system: &dyn SystemFacade, version: &str, plan: &str) -> Self {
+        let host = system
+            .read_trimmed(Path::new("/etc/hostname"))
+            .unwrap_or_else(|| "localhost".to_string());
+        let user = env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+        AttestationBuilder {
+            provenance: Provenance {
+                builder: format!("kernelforge/{}", env!("CARGO_PKG_VERSION")),
+                version: version.to_string(),
+                plan: plan.to_string(),
+                compiler_flags: CompilerFlags::default(),
+                steps: Vec::new(),
+                outside_changes: None,
+            },
+            operator: format!("{}@{}", user, host),
+        }
+    }
+
+    /// Records the final KCFLAGS/KCPPFLAGS of the build
+    pub fn record_compiler_flags(&mut self, flags: &CompilerFlags) {
+        self.provenance.compiler_flags = flags.clone();
+    }
+
+    /// Records edits made to the tree outside KernelForge; a clean tree records nothing
+    fn record_outside_changes(&mut self, changes: &OutsideChanges) {
+        self.provenance.outside_changes = Some(changes.clone()).filter(OutsideChanges::is_dirty);
+    }
+
+    /// Appends a finished step, hashing its inputs and outputs now
+    pub fn record_step(
+        &mut self,
+        stage: PipelineStage,
+        command: &[&str],
+        started_at: u64,
+        materials: &[&Path],
+        products: &[&Path],
+    ) -> Result<&StepLink> {
+        let describe = |paths: &[&Path]| {
+            paths
+                .iter()
+                .map(|path| ResourceDescriptor::of(path))
+                .collect::<Result<Vec<_>>>()
+        };
+        let previous = self
+            .provenance
+            .steps
+            .last()
+            .map_or_else(|| CHAIN_START.to_string(), StepLink::link_hash);
+        self.provenance.steps.push(StepLink {
+            stage,
+            operator: self.operator.clone(),
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            started_at,
+            finished_at: unix_now(),
+            materials: describe(materials)?,
+            products: describe(products)?,
+            previous,
+        });
+        Ok(self.provenance.steps.last().expect("step was just pushed"))
+    }
+
+    /// Statement naming `artifacts` as the subjects, built from `source_dir`
+    /// The tree is compared against its snapshot last, so edits made at any point before the
+    /// artifacts were produced end up in the provenance
+    pub fn finish(
+        mut self,
+        paths: &Paths,
+        source_dir: &Path,
+        artifacts: &[&Path],
+    ) -> Result<Attestation> {
+        if let Some(changes) = outside_changes(paths, source_dir)? {
+            self.record_outside_changes(&changes);
+        }
+        let subject = artifacts
+            .iter()
+            .map(|path| ResourceDescriptor::of(path))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Attestation {
+            statement_type: STATEMENT_TYPE.to_string(),
+            subject,
+            predicate_type: PREDICATE_TYPE.to_string(),
+            predicate: self.provenance,
+        })
+    }
+}
+
+/// A verified attestation and the key that signed it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedAttestation {
+    pub attestation: Attestation,
+    pub signer: TrustedKey,
+}
+
+/// Checks an attestation on any machine: the signature against the keyring, the step chain,
+/// and every subject found in `artifacts_dir` against its digest
+/// The builder's public key must have been imported into the keyring beforehand
+pub fn verify_attestation(
+    system: &dyn SystemFacade,
+    keyring: &Keyring,
+    statement: &Path,
+    artifacts_dir: &Path,
+) -> Result<VerifiedAttestation> {
+    let verification = keyring.verify(system, statement, &signature_path(statement))?;
+    let signer = match (verification.status, verification.key) {
+        (SignatureStatus::Valid { .. }, Some(key)) => key,
+        _ => bail!("{} is not signed by a trusted key", statement.display()),
+    };
+    let attestation: Attestation = serde_json::from_str(
+        &fs::read_to_string(statement)
+            .with_context(|| format!("reading {}", statement.display()))?,
+    )
+    .with_context(|| format!("parsing {}", statement.display()))?;
+    if attestation.statement_type != STATEMENT_TYPE || attestation.predicate_type != PREDICATE_TYPE
+    {
+        bail!(
+            "{} is not a KernelForge provenance statement",
+            statement.display()
+        );
+    }
+    let mut problems = attestation.chain_problems();
+    for subject in &attestation.subject {
+        let file = artifacts_dir.join(&subject.name);
+        if !file.exists() {
+            problems.push(format!("{} is missing", subject.name));
+        } else if Some(sha256_file(&file)?.as_str()) != subject.sha256() {
+            problems.push(format!(
+                "{} does not match its recorded digest",
+                subject.name
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        bail!(
+            "{} failed verification: {}",
+            statement.display(),
+            problems.join("; ")
+        );
+    }
+    Ok(VerifiedAttestation {
+        attestation,
+        signer,
+    })
+}
+
+/// Detached signature written next to a statement
+pub fn signature_path(statement: &Path) -> PathBuf {
+    let mut name = statement.as_os_str().to_os_string();
+    name.push(".asc");
+    PathBuf::from(name)
+}
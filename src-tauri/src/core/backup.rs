@@ -0,0 +1,288 @@
+// src-tauri/src/core/backup.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::system::SystemFacade;
+
+/// Manifest at the root of every backup
+pub const BACKUP_MANIFEST: &str = "backup.json";
+const BACKUP_FORMAT: u32 = 1;
+
+/// Leftovers of interrupted writes and downloads, and writer locks, never worth restoring
+const SKIPPED_SUFFIXES: &[&str] = &[".tmp", ".part", ".staged", ".lock"];
+
+/// A directory of KernelForge state; the cache is deliberately not one of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BackupSection {
+    /// Plans, pins, history, statistics and results
+    Data,
+    /// Transaction logs and pipeline runs
+    State,
+    /// Settings and keys
+    Config,
+}
+
+impl BackupSection {
+    pub const ALL: [BackupSection; 3] = [
+        BackupSection::Data,
+        BackupSection::State,
+        BackupSection::Config,
+    ];
+
+    /// Directory name inside the archive
+    pub fn name(self) -> &'static str {
+        match self {
+            BackupSection::Data => "data",
+            BackupSection::State => "state",
+            BackupSection::Config => "config",
+        }
+    }
+
+    fn dir(self, paths: &Paths) -> &Path {
+        match self {
+            BackupSection::Data => paths.data_dir(),
+            BackupSection::State => paths.state_dir(),
+            BackupSection::Config => paths.config_dir(),
+        }
+    }
+}
+
+/// Size of one section in a backup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSummary {
+    pub section: BackupSection,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Describes a backup archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: u32,
+    /// KernelForge version that wrote the backup
+    pub app_version: String,
+    pub created_at: u64,
+    pub sections: Vec<SectionSummary>,
+}
+
+/// Archives data, state and config into one tar file, leaving caches out
+pub fn create_backup(
+    system: &dyn SystemFacade,
+    paths: &Paths,
+    output: &Path,
+    staging: &Path,
+) -> Result<BackupManifest> {
+    reset_dir(staging)?;
+    let mut sections = Vec::new();
+    for section in BackupSection::ALL {
+        let mut summary = SectionSummary {
+            section,
+            files: 0,
+            bytes: 0,
+        };
+        let dir = section.dir(paths);
+        if dir.is_dir() {
+            copy_tree(dir, &staging.join(section.name()), &mut summary)?;
+        }
+        sections.push(summary);
+    }
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: unix_now(),
+        sections,
+    };
+    fs::write(
+        staging.join(BACKUP_MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    run_tar(
+        system,
+        &[
+            "-czf",
+            &output.to_string_lossy(),
+            "-C",
+            &staging.to_string_lossy(),
+            ".",
+        ],
+    )?;
+    let _ = fs::remove_dir_all(staging);
+    Ok(manifest)
+}
+
+/// Restores a backup over the current state
+/// Every section is copied next to its directory and checked before any directory is
+/// replaced; each existing directory is then renamed to `<dir>.before-restore-<time>` and
+/// the copy renamed into place, so an interrupted restore never leaves a half-written one
+pub fn restore_backup(
+    system: &dyn SystemFacade,
+    paths: &Paths,
+    archive: &Path,
+    staging: &Path,
+) -> Result<BackupManifest> {
+    reset_dir(staging)?;
+    run_tar(
+        system,
+        &[
+            "-xzf",
+            &archive.to_string_lossy(),
+            "-C",
+            &staging.to_string_lossy(),
+        ],
+    )?;
+    let manifest_path = staging.join(BACKUP_MANIFEST);
+    let manifest: BackupManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("{} is not a KernelForge backup", archive.display()))?,
+    )
+    .with_context(|| format!("parsing {}", manifest_path.display()))?;
+    if manifest.format > BACKUP_FORMAT {
+        bail!(
+            "{} was written by KernelForge {} in a newer format",
+            archive.display(),
+            manifest.app_version
+        );
+    }
+
+    let stamp = unix_now();
+    // Copies sit beside their target so the final rename stays on one filesystem
+    let mut prepared: Vec<(PathBuf, &Path)> = Vec::new();
+    let result = prepare_sections(&manifest, staging, paths, archive, stamp, &mut prepared)
+        .and_then(|()| swap_sections(&prepared, stamp));
+    if result.is_err() {
+        for (copy, _) in &prepared {
+            let _ = fs::remove_dir_all(copy);
+        }
+    }
+    let _ = fs::remove_dir_all(staging);
+    result?;
+    Ok(manifest)
+}
+
+/// Copies every section of the extracted backup beside its directory and checks it
+fn prepare_sections<'a>(
+    manifest: &BackupManifest,
+    staging: &Path,
+    paths: &'a Paths,
+    archive: &Path,
+    stamp: u64,
+    prepared: &mut Vec<(PathBuf, &'a Path)>,
+) -> Result<()> {
+    for summary in &manifest.sections {
+        let source = staging.join(summary.section.name());
+        if !source.is_dir() {
+            continue;
+        }
+        let target = summary.section.dir(paths);
+        let copy = sibling_path(target, &format!(".restoring-{}", stamp));
+        prepared.push((copy.clone(), target));
+        reset_dir(&copy)?;
+        let mut restored = SectionSummary {
+            section: summary.section,
+            files: 0,
+            bytes: 0,
+        };
+        copy_tree(&source, &copy, &mut restored)?;
+        if restored.files != summary.files {
+            bail!(
+                "{} holds {} of the {} {} files it lists",
+                archive.display(),
+                restored.files,
+                summary.files,
+                summary.section.name()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Moves the current directories aside and the checked copies into place, putting the
+/// old directories back when a rename fails
+fn swap_sections(prepared: &[(PathBuf, &Path)], stamp: u64) -> Result<()> {
+    let mut swapped: Vec<(&Path, Option<PathBuf>)> = Vec::new();
+    for (copy, target) in prepared {
+        let result = (|| -> Result<Option<PathBuf>> {
+            let aside = if target.exists() {
+                let aside = sibling_path(target, &format!(".before-restore-{}", stamp));
+                fs::rename(target, &aside).with_context(|| {
+                    format!("moving {} to {}", target.display(), aside.display())
+                })?;
+                Some(aside)
+            } else {
+                None
+            };
+            if let Err(error) = fs::rename(copy, target) {
+                if let Some(aside) = &aside {
+                    let _ = fs::rename(aside, target);
+                }
+                return Err(error)
+                    .with_context(|| format!("moving {} to {}", copy.display(), target.display()));
+            }
+            Ok(aside)
+        })();
+        match result {
+            Ok(aside) => swapped.push((target, aside)),
+            Err(error) => {
+                for (target, aside) in swapped.into_iter().rev() {
+                    let _ = fs::remove_dir_all(target);
+                    if let Some(aside) = aside {
+                        let _ = fs::rename(aside, target);
+                    }
+                }
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn sibling_path(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    dir.with_file_name(name)
+}
+
+fn reset_dir(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("clearing {}", dir.display()))?;
+    }
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path, summary: &mut SectionSummary) -> Result<()> {
+    fs::create_dir_all(to).with_context(|| format!("creating {}", to.display()))?;
+    for entry in fs::read_dir(from).with_context(|| format!("reading {}", from.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            copy_tree(&path, &target, summary)?;
+        } else if kind.is_file() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if SKIPPED_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+                continue;
+            }
+            summary.bytes +=
+                fs::copy(&path, &target).with_context(|| format!("copying {}", path.display()))?;
+            summary.files += 1;
+        }
+    }
+    Ok(())
+}
+
+fn run_tar(system: &dyn SystemFacade, args: &[&str]) -> Result<()> {
+    let output = system.run("tar", args).context("running tar")?;
+    if !output.success() {
+        bail!("tar failed ({}): {}", output.status, output.stderr.trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,281 @@
+// src-tauri/src/core/benchmark.rs
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::advisory::{Advisory, Severity};
+use super::clock::unix_now;
+use super::power::read_cpu_temperature;
+use super::results::KernelTag;
+use super::system::{CommandSpec, SystemFacade};
+
+const SYSFS_CPU: &str = "/sys/devices/system/cpu";
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+/// Temperature around which most desktop and laptop CPUs start to clock down
+const HOT_TEMP_C: f32 = 90.0;
+/// Share of the maximum frequency under which a hot CPU counts as throttled
+const THROTTLED_FREQ_RATIO: f32 = 0.75;
+
+/// A command timed repeatedly on the running kernel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchmarkSpec {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub runs: usize,
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+}
+
+/// CPU temperature and clocks at one point of a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalSample {
+    pub elapsed_ms: u64,
+    pub temp_c: Option<f32>,
+    /// Mean current frequency over all CPUs
+    pub freq_mhz: Option<u32>,
+    /// `freq_mhz` over the mean maximum frequency
+    pub freq_ratio: Option<f32>,
+}
+
+/// Evidence that the CPU throttled during a benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ThrottleSignal {
+    /// The kernel's thermal_throttle counters went up (Intel)
+    Counters { events: u64 },
+    /// Clocks fell well below maximum while the CPU was hot
+    FrequencyDrop { temp_c: f32, freq_ratio: f32 },
+}
+
+/// Temperature and frequency over a whole benchmark
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThermalSummary {
+    pub samples: Vec<ThermalSample>,
+    pub peak_temp_c: Option<f32>,
+    pub lowest_freq_ratio: Option<f32>,
+    pub signals: Vec<ThrottleSignal>,
+}
+
+impl ThermalSummary {
+    fn new(samples: Vec<ThermalSample>, counter_events: u64) -> Self {
+        let peak_temp_c = samples
+            .iter()
+            .filter_map(|sample| sample.temp_c)
+            .reduce(f32::max);
+        let lowest_freq_ratio = samples
+            .iter()
+            .filter_map(|sample| sample.freq_ratio)
+            .reduce(f32::min);
+        let mut signals = Vec::new();
+        if counter_events > 0 {
+            signals.push(ThrottleSignal::Counters {
+                events: counter_events,
+            });
+        }
+        // The lowest clock reached while hot, one signal per benchmark
+        let drop = samples
+            .iter()
+            .filter_map(|sample| Some((sample.temp_c?, sample.freq_ratio?)))
+            .filter(|(temp_c, ratio)| *temp_c >= HOT_TEMP_C && *ratio < THROTTLED_FREQ_RATIO)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((temp_c, freq_ratio)) = drop {
+            signals.push(ThrottleSignal::FrequencyDrop { temp_c, freq_ratio });
+        }
+        ThermalSummary {
+            samples,
+            peak_temp_c,
+            lowest_freq_ratio,
+            signals,
+        }
+    }
+
+    pub fn throttled(&self) -> bool {
+        !self.signals.is_empty()
+    }
+}
+
+/// Timings of a benchmark on one kernel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub name: String,
+    pub kernel: KernelTag,
+    pub recorded_at: u64,
+    pub durations_ms: Vec<u64>,
+    pub thermal: ThermalSummary,
+}
+
+impl BenchmarkResult {
+    pub fn mean_ms(&self) -> Option<f64> {
+        if self.durations_ms.is_empty() {
+            return None;
+        }
+        let total: u64 = self.durations_ms.iter().sum();
+        Some(total as f64 / self.durations_ms.len() as f64)
+    }
+
+    pub fn median_ms(&self) -> Option<u64> {
+        let mut sorted = self.durations_ms.clone();
+        sorted.sort_unstable();
+        sorted.get(sorted.len() / 2).copied()
+    }
+
+    /// Whether the timings can be compared with other kernels; throttled runs measure the cooler
+    pub fn comparable(&self) -> bool {
+        !self.thermal.throttled()
+    }
+
+    /// Explains why the result should not be compared, if it should not
+    pub fn advisories(&self) -> Vec<Advisory> {
+        self.thermal
+            .signals
+            .iter()
+            .map(|signal| match signal {
+                ThrottleSignal::Counters { events } => Advisory::new(
+                    Severity::Warning,
+                    "benchmark-throttle-events",
+                    &[("name", self.name.clone()), ("events", events.to_string())],
+                ),
+                ThrottleSignal::FrequencyDrop { temp_c, freq_ratio } => Advisory::new(
+                    Severity::Warning,
+                    "benchmark-frequency-drop",
+                    &[
+                        ("name", self.name.clone()),
+                        ("temp", format!("{:.0}", temp_c)),
+                        ("percent", format!("{:.0}", freq_ratio * 100.0)),
+                    ],
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Runs the benchmark `spec.runs` times, sampling temperature and clocks throughout
+pub async fn run_benchmark(
+    system: &dyn SystemFacade,
+    spec: &BenchmarkSpec,
+    kernel: KernelTag,
+) -> Result<BenchmarkResult> {
+    if spec.runs == 0 {
+        bail!("benchmark {} has no runs", spec.name);
+    }
+    let counters_before = throttle_events(system);
+    let started = Instant::now();
+    let mut samples = vec![sample(system, started)];
+    let mut durations_ms = Vec::with_capacity(spec.runs);
+    for _ in 0..spec.runs {
+        let prepared = system.prepare_command(CommandSpec {
+            program: spec.program.clone(),
+            args: spec.args.clone(),
+            cwd: spec.cwd.clone(),
+            ..CommandSpec::default()
+        });
+        let mut command = Command::new(&prepared.program);
+        command
+            .args(&prepared.args)
+            .envs(prepared.env.iter().map(|(key, value)| (key, value)));
+        for name in &prepared.env_remove {
+            command.env_remove(name);
+        }
+        if let Some(cwd) = &prepared.cwd {
+            command.current_dir(cwd);
+        }
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        let run_started = Instant::now();
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("starting benchmark {}", spec.name))?;
+        let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+        ticker.tick().await;
+        let status = loop {
+            tokio::select! {
+                status = child.wait() => break status?,
+                _ = ticker.tick() => samples.push(sample(system, started)),
+            }
+        };
+        if !status.success() {
+            bail!("benchmark {} failed with {}", spec.name, status);
+        }
+        durations_ms.push(run_started.elapsed().as_millis() as u64);
+    }
+    samples.push(sample(system, started));
+
+    let counter_events = throttle_events(system).saturating_sub(counters_before);
+    Ok(BenchmarkResult {
+        name: spec.name.clone(),
+        kernel,
+        recorded_at: unix_now(),
+        durations_ms,
+        thermal: ThermalSummary::new(samples, counter_events),
+    })
+}
+
+fn sample(system: &dyn SystemFacade, started: Instant) -> ThermalSample {
+    let (freq_mhz, freq_ratio) = match read_frequencies(system) {
+        Some((current, max)) => (
+            Some(current / 1000),
+            (max > 0).then(|| current as f32 / max as f32),
+        ),
+        None => (None, None),
+    };
+    ThermalSample {
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        temp_c: read_cpu_temperature(system),
+        freq_mhz,
+        freq_ratio,
+    }
+}
+
+/// Mean current and maximum cpufreq frequency in kHz
+fn read_frequencies(system: &dyn SystemFacade) -> Option<(u32, u32)> {
+    let read = |path: &Path| -> Option<u64> { system.read_trimmed(path)?.parse().ok() };
+    let (mut current, mut max, mut count) = (0u64, 0u64, 0u64);
+    for cpu in cpu_dirs(system) {
+        let cpufreq = cpu.join("cpufreq");
+        if let (Some(cur), Some(top)) = (
+            read(&cpufreq.join("scaling_cur_freq")),
+            read(&cpufreq.join("cpuinfo_max_freq")),
+        ) {
+            current += cur;
+            max += top;
+            count += 1;
+        }
+    }
+    (count > 0).then(|| ((current / count) as u32, (max / count) as u32))
+}
+
+/// Sum of the per-CPU thermal throttle counters; zero where the kernel does not expose them
+fn throttle_events(system: &dyn SystemFacade) -> u64 {
+    cpu_dirs(system)
+        .iter()
+        .flat_map(|cpu| {
+            ["core_throttle_count", "package_throttle_count"]
+                .map(|counter| cpu.join("thermal_throttle").join(counter))
+        })
+        .filter_map(|path| system.read_trimmed(&path)?.parse::<u64>().ok())
+        .sum()
+}
+
+/// `/sys/devices/system/cpu/cpuN` directories
+fn cpu_dirs(system: &dyn SystemFacade) -> Vec<PathBuf> {
+    system
+        .read_dir(Path::new(SYSFS_CPU))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .collect()
+}
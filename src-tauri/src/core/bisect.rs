@@ -0,0 +1,248 @@
+// src-tauri/src/core/bisect.rs
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::boot_test::{QemuBootTest, TestRootfs};
+use super::config::{ConfigValue, KernelConfig};
+use super::system::{run_spec, CommandSpec, SystemFacade};
+use super::toolchain::CrossToolchain;
+
+/// One option whose value differs between the known-good and the broken config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub symbol: String,
+    /// None when the symbol is absent from that config
+    pub good: Option<ConfigValue>,
+    pub bad: Option<ConfigValue>,
+}
+
+/// One tested configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BisectStep {
+    /// Changes applied on top of the known-good config for this step
+    pub applied: Vec<String>,
+    pub booted: bool,
+}
+
+/// Builds and boot-tests one configuration; true when it booted
+pub trait BisectProbe {
+    fn test(&mut self, config: &KernelConfig) -> Result<bool>;
+}
+
+/// Binary search over the options changed since a known-good config, for the one that
+/// stops the kernel from booting
+/// Invariant: `good` plus `accepted` boots, and adding every suspect on top does not
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigBisection {
+    pub good: KernelConfig,
+    pub changes: Vec<ConfigChange>,
+    /// Changes proven harmless together
+    pub accepted: Vec<String>,
+    /// Changes one of which breaks the boot
+    pub suspects: Vec<String>,
+    pub steps: Vec<BisectStep>,
+}
+
+impl ConfigBisection {
+    /// Starts from a config that boots and one that failed the boot test
+    pub fn new(good: &KernelConfig, bad: &KernelConfig) -> Result<Self> {
+        let symbols: BTreeSet<&str> = good
+            .iter()
+            .chain(bad.iter())
+            .map(|(name, _)| name)
+            .collect();
+        let changes: Vec<ConfigChange> = symbols
+            .into_iter()
+            .filter(|symbol| good.get(symbol) != bad.get(symbol))
+            .map(|symbol| ConfigChange {
+                symbol: symbol.to_string(),
+                good: good.get(symbol).cloned(),
+                bad: bad.get(symbol).cloned(),
+            })
+            .collect();
+        if changes.is_empty() {
+            bail!("the configs are identical; nothing to bisect");
+        }
+        Ok(ConfigBisection {
+            good: good.clone(),
+            suspects: changes.iter().map(|change| change.symbol.clone()).collect(),
+            changes,
+            accepted: Vec::new(),
+            steps: Vec::new(),
+        })
+    }
+
+    /// Builds left in the worst case
+    pub fn remaining_steps(&self) -> u32 {
+        self.suspects
+            .len()
+            .max(1)
+            .next_power_of_two()
+            .trailing_zeros()
+    }
+
+    /// The offending change, once a single suspect is left
+    pub fn culprit(&self) -> Option<&ConfigChange> {
+        match self.suspects.as_slice() {
+            [symbol] => self.changes.iter().find(|change| &change.symbol == symbol),
+            _ => None,
+        }
+    }
+
+    /// Configuration to test next: the accepted changes plus the first half of the suspects
+    pub fn next_candidate(&self) -> Option<(Vec<String>, KernelConfig)> {
+        if self.culprit().is_some() {
+            return None;
+        }
+        let half = self.suspects[..self.suspects.len() / 2].to_vec();
+        let mut applied = self.accepted.clone();
+        applied.extend(half.iter().cloned());
+        Some((half, self.apply(&applied)))
+    }
+
+    /// Narrows the suspects from the result of testing `half`
+    pub fn record(&mut self, half: Vec<String>, booted: bool) {
+        let mut applied = self.accepted.clone();
+        applied.extend(half.iter().cloned());
+        self.steps.push(BisectStep { applied, booted });
+        if booted {
+            self.suspects.retain(|symbol| !half.contains(symbol));
+            self.accepted.extend(half);
+        } else {
+            self.suspects = half;
+        }
+    }
+
+    /// Tests both ends before searching: a good config that does not boot or a bad one that
+    /// does would send the search to an arbitrary change
+    pub fn verify_endpoints(&mut self, probe: &mut dyn BisectProbe) -> Result<()> {
+        let booted = probe.test(&self.good)?;
+        self.steps.push(BisectStep {
+            applied: Vec::new(),
+            booted,
+        });
+        if !booted {
+            bail!("the known-good config does not boot either; nothing to bisect");
+        }
+        let everything: Vec<String> = self
+            .changes
+            .iter()
+            .map(|change| change.symbol.clone())
+            .collect();
+        let booted = probe.test(&self.apply(&everything))?;
+        self.steps.push(BisectStep {
+            applied: everything,
+            booted,
+        });
+        if booted {
+            bail!("the broken config boots in the test; the failure is not in the config");
+        }
+        Ok(())
+    }
+
+    /// Tests candidates until one change is left
+    pub fn run(&mut self, probe: &mut dyn BisectProbe) -> Result<ConfigChange> {
+        while let Some((half, config)) = self.next_candidate() {
+            let booted = probe.test(&config)?;
+            self.record(half, booted);
+        }
+        self.culprit()
+            .cloned()
+            .context("bisection ended without a culprit")
+    }
+
+    /// The known-good config with the broken config's value for each of `symbols`
+    fn apply(&self, symbols: &[String]) -> KernelConfig {
+        let mut config = self.good.clone();
+        for change in self
+            .changes
+            .iter()
+            .filter(|change| symbols.contains(&change.symbol))
+        {
+            match &change.bad {
+                Some(value) => config.set(&change.symbol, value.clone()),
+                None => {
+                    config.remove(&change.symbol);
+                }
+            }
+        }
+        config
+    }
+}
+
+/// Rebuilds a source tree with ccache for each step and boots it with the QEMU boot test
+/// Boots without an initramfs, so the test rootfs drivers are forced built in
+pub struct BuildAndBootProbe<'a> {
+    pub system: &'a dyn SystemFacade,
+    pub source_dir: PathBuf,
+    pub jobs: usize,
+    /// Build environment, e.g. from `CacheQuotas::ccache_env`
+    pub env: Vec<(String, String)>,
+    /// Compiler of the plan being bisected, e.g. from `CrossToolchain::detect` for its arch
+    pub toolchain: CrossToolchain,
+    pub rootfs: TestRootfs,
+}
+
+impl BuildAndBootProbe<'_> {
+    fn make(&self, args: &[&str]) -> Result<()> {
+        let mut spec = CommandSpec::new("make", args);
+        spec.env = self.env.clone();
+        spec.env.push((
+            String::from("ARCH"),
+            self.toolchain.arch.make_arch().to_string(),
+        ));
+        spec.env.extend(self.toolchain.env());
+        spec.cwd = Some(self.source_dir.clone());
+        let output = run_spec(&self.system.prepare_command(spec)).context("running make")?;
+        if !output.success() {
+            let tail: Vec<&str> = output.stderr.lines().rev().take(20).collect();
+            bail!(
+                "make {} failed: {}",
+                args.join(" "),
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl BisectProbe for BuildAndBootProbe<'_> {
+    fn test(&mut self, config: &KernelConfig) -> Result<bool> {
+        let (target, image) = match std::env::consts::ARCH {
+            "x86_64" => ("bzImage", "arch/x86/boot/bzImage"),
+            "aarch64" => ("Image", "arch/arm64/boot/Image"),
+            other => bail!("no boot test for {}", other),
+        };
+        let mut config = config.clone();
+        QemuBootTest::force_builtin(&mut config);
+        let dot_config = self.source_dir.join(".config");
+        fs::write(&dot_config, config.emit())
+            .with_context(|| format!("writing {}", dot_config.display()))?;
+        self.make(&["olddefconfig"])?;
+        let jobs = format!("-j{}", self.jobs.max(1));
+        let cc = format!("CC=ccache {}", self.toolchain.cc());
+        self.make(&[&jobs, &cc, target])?;
+        let test = QemuBootTest::new(&self.source_dir.join(image), None, self.rootfs.clone());
+        Ok(test.run(self.system)?.passed())
+    }
+}
+
+/// Bisects with `BuildAndBootProbe` over the tree in `source_dir`
+pub fn bisect_boot_failure(
+    probe: &mut BuildAndBootProbe<'_>,
+    good: &KernelConfig,
+    bad: &KernelConfig,
+) -> Result<(ConfigChange, ConfigBisection)> {
+    if !probe.source_dir.join("Makefile").exists() {
+        bail!("{} is not a kernel tree", probe.source_dir.display());
+    }
+    let mut bisection = ConfigBisection::new(good, bad)?;
+    bisection.verify_endpoints(probe)?;
+    let culprit = bisection.run(probe)?;
+    Ok((culprit, bisection))
+}
@@ -0,0 +1,237 @@
+// src-tauri/src/core/bluetooth.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue};
+use super::requirements::{self, MachineRequirements};
+use super::system::SystemFacade;
+
+const SYS_BLUETOOTH: &str = "/sys/class/bluetooth";
+
+/// Controller drivers, by the name in the controller's uevent, and their options
+const CONTROLLER_DRIVERS: &[(&str, &str)] = &[
+    ("btusb", "CONFIG_BT_HCIBTUSB"),
+    ("hci_uart", "CONFIG_BT_HCIUART"),
+    ("btsdio", "CONFIG_BT_HCIBTSDIO"),
+    ("btmtksdio", "CONFIG_BT_MTKSDIO"),
+    ("btnxpuart", "CONFIG_BT_NXPUART"),
+];
+
+/// `btmgmt info` settings of controllers that can carry LE Audio streams
+const ISO_SETTINGS: &[&str] = &["cis-central", "cis-peripheral", "iso-broadcaster"];
+
+/// A Bluetooth controller and what `btmgmt info` says it supports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BluetoothController {
+    /// `hci0` and so on
+    pub name: String,
+    pub driver: Option<String>,
+    /// LMP version, 11 for Bluetooth 5.2
+    pub version: Option<u8>,
+    /// Supported settings such as `le` or `wide-band-speech`, empty when btmgmt could
+    /// not be asked
+    pub settings: Vec<String>,
+}
+
+impl BluetoothController {
+    /// Core specification version, e.g. `5.3`
+    pub fn core_version(&self) -> Option<&'static str> {
+        let name = match self.version? {
+            6 => "4.0",
+            7 => "4.1",
+            8 => "4.2",
+            9 => "5.0",
+            10 => "5.1",
+            11 => "5.2",
+            12 => "5.3",
+            13 => "5.4",
+            14 => "6.0",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Whether the controller supports `setting`; unknown settings count as supported
+    /// so a controller btmgmt could not read keeps everything
+    fn supports(&self, setting: &str) -> bool {
+        self.settings.is_empty() || self.settings.iter().any(|known| known == setting)
+    }
+
+    /// Bluetooth Low Energy, which LE Audio builds on
+    pub fn low_energy(&self) -> bool {
+        self.supports("le")
+    }
+
+    /// LE Audio: isochronous channels, Bluetooth 5.2 and later
+    pub fn le_audio(&self) -> bool {
+        ISO_SETTINGS.iter().any(|setting| self.supports(setting))
+    }
+}
+
+/// The Bluetooth controllers of the machine, for keeping headsets working
+/// Plans keep its options only when the user stacks its layer, the audio preset, on
+/// them: plenty of machines have a controller nobody uses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BluetoothSetup {
+    pub controllers: Vec<BluetoothController>,
+}
+
+impl BluetoothSetup {
+    /// Reads the controllers and asks btmgmt what they support, None without a controller
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let mut names: Vec<String> = system
+            .read_dir(Path::new(SYS_BLUETOOTH))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+            .filter(|name| name.starts_with("hci") && !name.contains(':'))
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        names.sort();
+
+        let info = system
+            .run("btmgmt", &["info"])
+            .ok()
+            .filter(|output| output.success())
+            .map(|output| parse_btmgmt_info(&output.stdout))
+            .unwrap_or_default();
+        let controllers = names
+            .into_iter()
+            .map(|name| {
+                let driver = system
+                    .read_to_string(&Path::new(SYS_BLUETOOTH).join(&name).join("device/uevent"))
+                    .ok()
+                    .and_then(|uevent| {
+                        uevent
+                            .lines()
+                            .find_map(|line| line.strip_prefix("DRIVER=").map(str::to_string))
+                    });
+                let known = info.iter().find(|controller| controller.name == name);
+                BluetoothController {
+                    driver,
+                    version: known.and_then(|controller| controller.version),
+                    settings: known
+                        .map(|controller| controller.settings.clone())
+                        .unwrap_or_default(),
+                    name,
+                }
+            })
+            .collect();
+        Some(BluetoothSetup { controllers })
+    }
+}
+
+impl MachineRequirements for BluetoothSetup {
+    fn name(&self) -> &'static str {
+        "bluetooth"
+    }
+
+    /// Options headset audio needs on these controllers: A2DP and the headset profile
+    /// always, LE and LE Audio where a controller can do them
+    /// aptX, LDAC and mSBC are encoded by PipeWire; the kernel only carries the streams
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let mut required = vec![
+            ConfigSuggestion::new("CONFIG_BT", ConfigValue::module(), "the Bluetooth stack"),
+            ConfigSuggestion::new(
+                "CONFIG_BT_BREDR",
+                ConfigValue::yes(),
+                "classic Bluetooth, which carries A2DP music and SCO calls",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_BT_RFCOMM",
+                ConfigValue::module(),
+                "the control channel of the headset and hands-free profiles",
+            ),
+        ];
+        for controller in &self.controllers {
+            let driver = controller.driver.as_deref().unwrap_or_default();
+            if let Some((_, symbol)) = CONTROLLER_DRIVERS
+                .iter()
+                .find(|(name, _)| driver.starts_with(name))
+            {
+                required.push(ConfigSuggestion::new(
+                    symbol,
+                    ConfigValue::module(),
+                    &format!("the {} driver of {}", driver, controller.name),
+                ));
+            }
+        }
+        if self.controllers.iter().any(BluetoothController::low_energy) {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_BT_LE",
+                ConfigValue::yes(),
+                "Bluetooth Low Energy, and the isochronous sockets LE Audio streams over",
+            ));
+        }
+        if self.controllers.iter().any(BluetoothController::le_audio) {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_BT_LE_L2CAP_ECRED",
+                ConfigValue::yes(),
+                "enhanced credit based channels, which LE Audio headsets open",
+            ));
+        }
+        requirements::unique(required)
+    }
+
+    /// Warns when the plan leaves out what headsets on these controllers need
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        let controllers: Vec<&str> = self
+            .controllers
+            .iter()
+            .map(|controller| controller.name.as_str())
+            .collect();
+        let mut advisory = Advisory::new(
+            Severity::Warning,
+            "bluetooth-audio-missing-config",
+            &[
+                ("controllers", controllers.join(", ")),
+                ("symbols", symbols.join(", ")),
+            ],
+        );
+        for requirement in missing {
+            advisory = advisory.suggest(requirement);
+        }
+        vec![advisory]
+    }
+}
+
+/// Controllers in `btmgmt info` output, with their version and supported settings
+pub fn parse_btmgmt_info(text: &str) -> Vec<BluetoothController> {
+    let mut controllers: Vec<BluetoothController> = Vec::new();
+    for line in text.lines() {
+        if let Some((name, _)) = line.split_once(':') {
+            if name.starts_with("hci") && !line.starts_with(char::is_whitespace) {
+                controllers.push(BluetoothController {
+                    name: name.to_string(),
+                    driver: None,
+                    version: None,
+                    settings: Vec::new(),
+                });
+                continue;
+            }
+        }
+        let Some(controller) = controllers.last_mut() else {
+            continue;
+        };
+        let line = line.trim();
+        if let Some(settings) = line.strip_prefix("supported settings:") {
+            controller.settings = settings.split_whitespace().map(str::to_string).collect();
+        } else if line.starts_with("addr ") {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            controller.version = words
+                .windows(2)
+                .find(|pair| pair[0] == "version")
+                .and_then(|pair| pair[1].parse().ok());
+        }
+    }
+    controllers
+}
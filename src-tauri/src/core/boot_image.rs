@@ -0,0 +1,155 @@
+// src-tauri/src/core/boot_image.rs
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::bootloader::Bootloader;
+use super::compression::enabled;
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::distro::InstallTemplate;
+use super::hardware::Firmware;
+use super::plan::{BuildPlan, TargetArch};
+use super::resolver::ConfigLayer;
+
+/// Kind of kernel image the build produces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFormat {
+    /// Whatever the config selects
+    #[default]
+    Auto,
+    /// The architecture's image without the EFI stub, for boot loaders only
+    Plain,
+    /// The same image with the EFI stub, which firmware can also start directly; on x86
+    /// the bzImage still boots from BIOS
+    EfiStub,
+    /// arm64 and RISC-V `vmlinuz.efi`: a compressed image inside an EFI decompressor,
+    /// bootable only through UEFI
+    Zboot,
+}
+
+impl ImageFormat {
+    /// Format a config produces for `arch`
+    pub fn of(arch: TargetArch, config: &KernelConfig) -> Self {
+        if supports_zboot(arch) && config.is_enabled("EFI_ZBOOT") {
+            ImageFormat::Zboot
+        } else if config.is_enabled("EFI_STUB") {
+            ImageFormat::EfiStub
+        } else {
+            ImageFormat::Plain
+        }
+    }
+
+    /// Whether `arch` can produce the format
+    pub fn is_available(self, arch: TargetArch) -> bool {
+        self != ImageFormat::Zboot || supports_zboot(arch)
+    }
+
+    /// Whether only UEFI firmware can start the image
+    pub fn is_efi_only(self) -> bool {
+        self == ImageFormat::Zboot
+    }
+
+    /// Layer selecting the format, empty for Auto
+    pub fn layer(self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("image-format");
+        let (stub, zboot) = match self {
+            ImageFormat::Auto => return layer,
+            ImageFormat::Plain => (false, false),
+            ImageFormat::EfiStub => (true, false),
+            ImageFormat::Zboot => (true, true),
+        };
+        if stub {
+            layer.set("EFI", ConfigValue::yes());
+        }
+        layer.set("EFI_STUB", enabled(stub));
+        layer.set("EFI_ZBOOT", enabled(zboot));
+        layer
+    }
+
+    /// Make target building the image of a config, e.g. `bzImage` or `vmlinuz.efi`
+    pub fn image_target(arch: TargetArch, config: &KernelConfig) -> &'static str {
+        if ImageFormat::of(arch, config) == ImageFormat::Zboot {
+            "vmlinuz.efi"
+        } else {
+            arch.image_target()
+        }
+    }
+
+    /// Findings for booting the image of `config` on this machine
+    pub fn check(
+        arch: TargetArch,
+        config: &KernelConfig,
+        firmware: Firmware,
+        bootloader: &Bootloader,
+    ) -> Vec<Advisory> {
+        let format = ImageFormat::of(arch, config);
+        let mut advisories = Vec::new();
+        if firmware == Firmware::Bios && format.is_efi_only() {
+            advisories.push(
+                Advisory::new(Severity::Blocker, "image-efi-only", &[]).suggest(
+                    ConfigSuggestion::new(
+                        "CONFIG_EFI_ZBOOT",
+                        ConfigValue::NotSet,
+                        "a zboot image cannot start without UEFI",
+                    ),
+                ),
+            );
+        }
+        // These start the kernel as an EFI application rather than through a boot protocol
+        let starts_efi_application = matches!(
+            bootloader,
+            Bootloader::SystemdBoot { .. } | Bootloader::Kernelstub | Bootloader::Efibootmgr { .. }
+        );
+        if starts_efi_application && format == ImageFormat::Plain {
+            advisories.push(
+                Advisory::new(
+                    Severity::Blocker,
+                    "image-needs-efi-stub",
+                    &[("bootloader", bootloader.name().to_string())],
+                )
+                .suggest(ConfigSuggestion::new(
+                    "CONFIG_EFI_STUB",
+                    ConfigValue::yes(),
+                    "the boot loader starts the kernel as an EFI application",
+                )),
+            );
+        }
+        advisories
+    }
+}
+
+/// Where the image of a plan goes, from the build tree to what the machine boots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImagePlacement {
+    /// Make target, `vmlinuz.efi` for a zboot image
+    pub target: String,
+    /// Where the build leaves it, relative to the source tree
+    pub built: PathBuf,
+    /// Where the install template puts it, e.g. /boot/vmlinuz-<version>
+    pub installed: PathBuf,
+    /// Copy on the ESP that the firmware or boot manager loads, None when the boot loader
+    /// reads `installed`
+    pub esp: Option<PathBuf>,
+}
+
+impl ImagePlacement {
+    pub fn of(
+        plan: &BuildPlan,
+        version: &str,
+        template: InstallTemplate,
+        bootloader: &Bootloader,
+    ) -> Self {
+        ImagePlacement {
+            target: plan.image_target().to_string(),
+            built: plan.image_path(),
+            installed: template.kernel_path(version),
+            esp: bootloader.esp_kernel_path(version),
+        }
+    }
+}
+
+fn supports_zboot(arch: TargetArch) -> bool {
+    matches!(arch, TargetArch::Arm64 | TargetArch::Riscv64)
+}
@@ -0,0 +1,277 @@
+// src-tauri/src/core/boot_test.rs
+
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigValue, KernelConfig};
+use super::paths::Paths;
+use super::system::SystemFacade;
+
+/// Printed by the test rootfs init as soon as it runs
+const INIT_MARKER: &str = "KERNELFORGE-BOOT-TEST: init running";
+/// Printed once init confirmed it runs from the test rootfs, mounted read-write
+const ROOT_MARKER: &str = "KERNELFORGE-BOOT-TEST: root mounted";
+/// File only the test rootfs carries, so the init can tell it is not still in the initramfs
+const ROOT_STAMP: &str = "kernelforge-boot-test";
+const KERNEL_PANIC: &str = "Kernel panic - not syncing";
+
+/// Exit status of coreutils `timeout` when the limit was hit
+const TIMEOUT_STATUS: i32 = 124;
+const CONSOLE_TAIL: usize = 60;
+const ROOTFS_SIZE: &str = "16M";
+const BUSYBOX_APPLETS: &[&str] = &["sh", "mount", "grep", "poweroff"];
+
+/// Drivers the kernel needs to find the test root and print to the serial console
+const REQUIRED_SYMBOLS: &[&str] = &["VIRTIO_PCI", "VIRTIO_BLK", "EXT4_FS", "SERIAL_8250_CONSOLE"];
+
+const INIT_SCRIPT: &str = r#"#!/bin/sh
+/bin/busybox mount -t proc proc /proc
+/bin/busybox mount -t devtmpfs devtmpfs /dev 2>/dev/null
+echo "KERNELFORGE-BOOT-TEST: init running"
+if [ -f /kernelforge-boot-test ] && /bin/busybox grep -q ' / ext4 rw' /proc/mounts; then
+    echo "KERNELFORGE-BOOT-TEST: root mounted"
+fi
+/bin/busybox poweroff -f
+"#;
+
+/// Tiny ext4 image holding a static busybox and an init that reports back over the console
+/// Booted with a throwaway snapshot, so tests never change it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestRootfs {
+    pub image: PathBuf,
+}
+
+impl TestRootfs {
+    /// Builds the image into the cache unless it already exists
+    pub fn ensure(system: &dyn SystemFacade, paths: &Paths) -> Result<Self> {
+        let dir = Paths::ensure(paths.cache_dir().join("boot-test"))?;
+        let image = dir.join("rootfs.img");
+        if !image.exists() {
+            build_rootfs(system, &dir.join("rootfs"), &image)?;
+        }
+        Ok(TestRootfs { image })
+    }
+}
+
+fn build_rootfs(system: &dyn SystemFacade, staging: &Path, image: &Path) -> Result<()> {
+    let busybox = ["/bin/busybox", "/usr/bin/busybox", "/usr/sbin/busybox"]
+        .into_iter()
+        .map(Path::new)
+        .find(|path| system.exists(path))
+        .context("the boot test needs busybox installed on the host")?;
+    // A dynamic busybox would need the host's libraries copied into the image as well
+    let linkage = system
+        .run("ldd", &[&busybox.to_string_lossy()])
+        .context("running ldd")?;
+    let report = format!("{}{}", linkage.stdout, linkage.stderr);
+    if !report.contains("not a dynamic executable") && !report.contains("statically linked") {
+        bail!(
+            "{} is dynamically linked; install a static busybox (e.g. busybox-static)",
+            busybox.display()
+        );
+    }
+
+    if staging.exists() {
+        fs::remove_dir_all(staging).with_context(|| format!("removing {}", staging.display()))?;
+    }
+    for dir in ["bin", "sbin", "proc", "dev"] {
+        fs::create_dir_all(staging.join(dir))
+            .with_context(|| format!("creating {}", staging.join(dir).display()))?;
+    }
+    fs::copy(busybox, staging.join("bin/busybox"))
+        .with_context(|| format!("copying {}", busybox.display()))?;
+    for applet in BUSYBOX_APPLETS {
+        symlink("busybox", staging.join("bin").join(applet))
+            .with_context(|| format!("linking {}", applet))?;
+    }
+    let init = staging.join("sbin/init");
+    fs::write(&init, INIT_SCRIPT).with_context(|| format!("writing {}", init.display()))?;
+    fs::set_permissions(&init, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("making {} executable", init.display()))?;
+    fs::write(staging.join(ROOT_STAMP), "")
+        .with_context(|| format!("writing the {} stamp", ROOT_STAMP))?;
+
+    let temporary = image.with_extension("img.tmp");
+    let output = system
+        .run(
+            "mke2fs",
+            &[
+                "-q",
+                "-F",
+                "-t",
+                "ext4",
+                "-d",
+                &staging.to_string_lossy(),
+                &temporary.to_string_lossy(),
+                ROOTFS_SIZE,
+            ],
+        )
+        .context("running mke2fs")?;
+    if !output.success() {
+        bail!("mke2fs failed: {}", output.stderr.trim());
+    }
+    fs::rename(&temporary, image).with_context(|| format!("replacing {}", image.display()))?;
+    fs::remove_dir_all(staging).with_context(|| format!("removing {}", staging.display()))
+}
+
+/// Direct kernel boot of a freshly built bzImage in QEMU, before anything is installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QemuBootTest {
+    pub kernel_image: PathBuf,
+    pub initramfs: Option<PathBuf>,
+    pub rootfs: TestRootfs,
+    pub memory_mb: u32,
+    pub timeout_secs: u64,
+}
+
+impl QemuBootTest {
+    pub fn new(kernel_image: &Path, initramfs: Option<&Path>, rootfs: TestRootfs) -> Self {
+        QemuBootTest {
+            kernel_image: kernel_image.to_path_buf(),
+            initramfs: initramfs.map(Path::to_path_buf),
+            rootfs,
+            memory_mb: 1024,
+            timeout_secs: 180,
+        }
+    }
+
+    /// Symbols the built config lacks for the test to have a chance, before spending a boot on it
+    /// Without an initramfs there is nothing to load modules from, so `=m` counts as missing
+    pub fn missing_symbols(&self, config: &KernelConfig) -> Vec<&'static str> {
+        REQUIRED_SYMBOLS
+            .iter()
+            .copied()
+            .filter(|symbol| match &self.initramfs {
+                Some(_) => !config.is_enabled(symbol),
+                None => config.get(symbol) != Some(&ConfigValue::yes()),
+            })
+            .collect()
+    }
+
+    /// Builds the required drivers in, for boots without an initramfs to load them from
+    pub fn force_builtin(config: &mut KernelConfig) {
+        for symbol in REQUIRED_SYMBOLS {
+            config.set(symbol, ConfigValue::yes());
+        }
+    }
+
+    /// Emulator and arguments for this host's architecture
+    /// KVM is used when available; otherwise the boot is emulated, just slower
+    pub fn command(&self, system: &dyn SystemFacade) -> Result<(String, Vec<String>)> {
+        let (program, machine, console) = match std::env::consts::ARCH {
+            "x86_64" => ("qemu-system-x86_64", "q35", "ttyS0"),
+            "aarch64" => ("qemu-system-aarch64", "virt", "ttyAMA0"),
+            other => bail!("no QEMU boot test for {}", other),
+        };
+        let kvm = system.exists(Path::new("/dev/kvm"));
+        let mut args = vec![
+            String::from("-machine"),
+            format!("{}{}", machine, if kvm { ",accel=kvm" } else { "" }),
+            String::from("-cpu"),
+            String::from(if kvm { "host" } else { "max" }),
+            String::from("-m"),
+            self.memory_mb.to_string(),
+            String::from("-smp"),
+            String::from("2"),
+            String::from("-nographic"),
+            String::from("-no-reboot"),
+            String::from("-nic"),
+            String::from("none"),
+            String::from("-kernel"),
+            self.kernel_image.to_string_lossy().into_owned(),
+        ];
+        if let Some(initramfs) = &self.initramfs {
+            args.push(String::from("-initrd"));
+            args.push(initramfs.to_string_lossy().into_owned());
+        }
+        args.push(String::from("-drive"));
+        args.push(format!(
+            "file={},if=virtio,format=raw,snapshot=on",
+            self.rootfs.image.display()
+        ));
+        // panic=-1 reboots at once, which -no-reboot turns into QEMU exiting
+        args.push(String::from("-append"));
+        args.push(format!(
+            "console={} root=/dev/vda rootfstype=ext4 rw init=/sbin/init panic=-1",
+            console
+        ));
+        Ok((program.to_string(), args))
+    }
+
+    /// Boots the kernel and reads the serial console for how far it got
+    pub fn run(&self, system: &dyn SystemFacade) -> Result<BootTestReport> {
+        let (program, args) = self.command(system)?;
+        if !system.has_program(&program) {
+            bail!("{} is not installed", program);
+        }
+        let limit = self.timeout_secs.to_string();
+        let mut command: Vec<&str> = vec!["--kill-after=10", &limit, &program];
+        command.extend(args.iter().map(String::as_str));
+        let started = Instant::now();
+        let output = system
+            .run("timeout", &command)
+            .with_context(|| format!("running {}", program))?;
+        let console = format!("{}{}", output.stdout, output.stderr);
+        Ok(BootTestReport::from_console(
+            &console,
+            output.status == TIMEOUT_STATUS,
+            started.elapsed().as_secs(),
+        ))
+    }
+}
+
+/// How far the kernel got, read from its serial console
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootTestReport {
+    pub reached_init: bool,
+    pub mounted_root: bool,
+    /// The panic line, when the kernel panicked
+    pub panic: Option<String>,
+    pub timed_out: bool,
+    pub duration_secs: u64,
+    pub console_tail: Vec<String>,
+}
+
+impl BootTestReport {
+    pub fn from_console(console: &str, timed_out: bool, duration_secs: u64) -> Self {
+        let lines: Vec<&str> = console.lines().collect();
+        let start = lines.len().saturating_sub(CONSOLE_TAIL);
+        BootTestReport {
+            reached_init: console.contains(INIT_MARKER),
+            mounted_root: console.contains(ROOT_MARKER),
+            panic: lines
+                .iter()
+                .find(|line| line.contains(KERNEL_PANIC))
+                .map(|line| line.trim().to_string()),
+            timed_out,
+            duration_secs,
+            console_tail: lines[start..].iter().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    /// A hang after both markers is only a failed power-off, not a failed boot
+    pub fn passed(&self) -> bool {
+        self.reached_init && self.mounted_root && self.panic.is_none()
+    }
+
+    /// Why the test failed, for the stage error
+    pub fn failure(&self) -> Option<String> {
+        if self.passed() {
+            return None;
+        }
+        Some(if let Some(panic) = &self.panic {
+            panic.clone()
+        } else if self.reached_init {
+            String::from("init ran but the test root was not mounted read-write")
+        } else if self.timed_out {
+            format!("init was not reached within {}s", self.duration_secs)
+        } else {
+            String::from("QEMU exited before init was reached")
+        })
+    }
+}
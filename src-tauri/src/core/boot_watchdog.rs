@@ -0,0 +1,225 @@
+// src-tauri/src/core/boot_watchdog.rs
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::KernelConfig;
+use super::privilege::PrivilegedAction;
+use super::system::SystemFacade;
+
+const WATCHDOG_CLASS: &str = "/sys/class/watchdog";
+const SYSTEMD_DROP_IN: &str = "/etc/systemd/system.conf.d/50-kernelforge-watchdog.conf";
+const SUCCESS_UNIT: &str = "/etc/systemd/system/kernelforge-boot-success.service";
+const SUCCESS_UNIT_NAME: &str = "kernelforge-boot-success.service";
+
+/// Written by the success unit once userspace is up, holding the release that booted
+pub const BOOT_SUCCESS_MARKER: &str = "/var/lib/kernelforge/boot-success";
+
+/// Hardware watchdog drivers and the symbols that build them
+/// softdog is absent on purpose: it dies with the kernel it is meant to watch
+const WATCHDOG_DRIVERS: &[(&str, &str)] = &[
+    ("iTCO_wdt", "ITCO_WDT"),
+    ("sp5100_tco", "SP5100_TCO"),
+    ("wdat_wdt", "WDAT_WDT"),
+    ("i6300esb", "I6300ESB_WDT"),
+];
+
+/// A watchdog device the running kernel exposes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchdogDevice {
+    /// e.g. watchdog0
+    pub name: String,
+    pub identity: Option<String>,
+    pub driver: Option<String>,
+    pub timeout_secs: Option<u32>,
+}
+
+impl WatchdogDevice {
+    pub fn detect(system: &dyn SystemFacade) -> Vec<Self> {
+        let Ok(entries) = system.read_dir(Path::new(WATCHDOG_CLASS)) else {
+            return Vec::new();
+        };
+        let mut devices: Vec<WatchdogDevice> = entries
+            .into_iter()
+            .map(|dir| {
+                let driver = driver_name(system, &dir);
+                WatchdogDevice {
+                    name: dir
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    identity: system.read_trimmed(&dir.join("identity")),
+                    driver,
+                    timeout_secs: system
+                        .read_trimmed(&dir.join("timeout"))
+                        .and_then(|value| value.parse().ok()),
+                }
+            })
+            .collect();
+        devices.sort_by(|left, right| left.name.cmp(&right.name));
+        devices
+    }
+}
+
+/// Resets a machine that hangs while booting a trial kernel, so the one-shot entry is spent
+/// and the firmware falls back to the default kernel without anyone at the console
+/// The kernel pings an already running hardware watchdog only until userspace opens it; if
+/// userspace never comes up within `open_timeout_secs`, the board resets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootWatchdog {
+    /// Userspace must open the watchdog within this time
+    pub open_timeout_secs: u32,
+    /// Interval systemd pings the hardware watchdog at once running
+    pub runtime_secs: u32,
+    /// Reset if a reboot itself hangs for this long
+    pub reboot_secs: u32,
+    /// Reboot this many seconds after a panic instead of sitting on the panic screen
+    pub panic_secs: u32,
+}
+
+impl Default for BootWatchdog {
+    fn default() -> Self {
+        BootWatchdog {
+            open_timeout_secs: 300,
+            runtime_secs: 30,
+            reboot_secs: 600,
+            panic_secs: 10,
+        }
+    }
+}
+
+impl BootWatchdog {
+    /// Parameters for the trial entry's command line: turn hangs and oopses into panics,
+    /// and panics into reboots
+    pub fn cmdline_args(&self) -> Vec<String> {
+        vec![
+            format!("panic={}", self.panic_secs),
+            String::from("oops=panic"),
+            String::from("softlockup_panic=1"),
+            String::from("hung_task_panic=1"),
+            String::from("watchdog.handle_boot_enabled=1"),
+            format!("watchdog.open_timeout={}", self.open_timeout_secs),
+        ]
+    }
+
+    /// `cmdline` with the watchdog parameters appended, replacing any earlier values
+    pub fn with_cmdline(&self, cmdline: &str) -> String {
+        let args = self.cmdline_args();
+        let keys: Vec<&str> = args
+            .iter()
+            .map(|arg| arg.split('=').next().unwrap_or(arg))
+            .collect();
+        let mut kept: Vec<String> = cmdline
+            .split_whitespace()
+            .filter(|arg| !keys.contains(&arg.split('=').next().unwrap_or(arg)))
+            .map(str::to_string)
+            .collect();
+        kept.extend(args);
+        kept.join(" ")
+    }
+
+    /// Steps that make systemd drive the hardware watchdog and record a successful boot
+    /// The watchdog only covers the trial boot: once the marker is written the success unit
+    /// removes the drop-in and re-executes systemd, which stops driving the watchdog
+    pub fn setup_actions(&self) -> Vec<PrivilegedAction> {
+        vec![
+            PrivilegedAction::WriteFile {
+                path: PathBuf::from(SYSTEMD_DROP_IN),
+                contents: format!(
+                    "# Generated by KernelForge for unattended trial boots\n[Manager]\nRuntimeWatchdogSec={}s\nRebootWatchdogSec={}s\n",
+                    self.runtime_secs, self.reboot_secs
+                ),
+                mode: 0o644,
+            },
+            PrivilegedAction::WriteFile {
+                path: PathBuf::from(SUCCESS_UNIT),
+                contents: format!(
+                    "# Generated by KernelForge\n[Unit]\nDescription=Record that this kernel reached userspace\nAfter=multi-user.target\n\n[Service]\nType=oneshot\nExecStart=/bin/sh -c 'mkdir -p /var/lib/kernelforge && uname -r > {}'\nExecStartPost=/bin/rm -f {}\nExecStartPost=/bin/systemctl --no-block daemon-reexec\n\n[Install]\nWantedBy=multi-user.target\n",
+                    BOOT_SUCCESS_MARKER, SYSTEMD_DROP_IN
+                ),
+                mode: 0o644,
+            },
+            run("systemctl", &["daemon-reload"]),
+            run("systemctl", &["enable", SUCCESS_UNIT_NAME]),
+            PrivilegedAction::RemoveFile {
+                path: PathBuf::from(BOOT_SUCCESS_MARKER),
+            },
+        ]
+    }
+
+    /// Steps that undo `setup_actions`
+    pub fn teardown_actions() -> Vec<PrivilegedAction> {
+        vec![
+            run("systemctl", &["disable", SUCCESS_UNIT_NAME]),
+            PrivilegedAction::RemoveFile {
+                path: PathBuf::from(SUCCESS_UNIT),
+            },
+            PrivilegedAction::RemoveFile {
+                path: PathBuf::from(SYSTEMD_DROP_IN),
+            },
+            run("systemctl", &["daemon-reload"]),
+        ]
+    }
+
+    /// Problems that would leave a hung trial kernel without a reset
+    pub fn advisories(config: &KernelConfig, devices: &[WatchdogDevice]) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        if devices.is_empty() {
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "boot-watchdog-missing",
+                &[],
+            ));
+            return advisories;
+        }
+        if !config.is_enabled("WATCHDOG_CORE") {
+            advisories.push(Advisory::new(Severity::Blocker, "boot-watchdog-core", &[]));
+        }
+        for device in devices {
+            let Some(driver) = device.driver.as_deref() else {
+                continue;
+            };
+            let Some((_, symbol)) = WATCHDOG_DRIVERS.iter().find(|(name, _)| *name == driver)
+            else {
+                continue;
+            };
+            if !config.is_enabled(symbol) {
+                advisories.push(Advisory::new(
+                    Severity::Blocker,
+                    "boot-watchdog-driver",
+                    &[
+                        ("driver", driver.to_string()),
+                        ("symbol", symbol.to_string()),
+                    ],
+                ));
+            }
+        }
+        advisories
+    }
+}
+
+/// Whether userspace marked `release` as booted since the watchdog was set up
+pub fn boot_marked_successful(system: &dyn SystemFacade, release: &str) -> bool {
+    system
+        .read_trimmed(Path::new(BOOT_SUCCESS_MARKER))
+        .is_some_and(|booted| booted == release)
+}
+
+/// Driver bound to the watchdog's parent device
+fn driver_name(system: &dyn SystemFacade, dir: &Path) -> Option<String> {
+    let uevent = system.read_to_string(&dir.join("device/uevent")).ok()?;
+    uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DRIVER="))
+        .map(str::to_string)
+}
+
+fn run(program: &str, args: &[&str]) -> PrivilegedAction {
+    PrivilegedAction::Run {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    }
+}
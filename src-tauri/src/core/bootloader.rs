@@ -0,0 +1,330 @@
+// src-tauri/src/core/bootloader.rs
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::privilege::PrivilegedAction;
+use super::system::SystemFacade;
+
+/// Candidate mount points for the EFI system partition, in preference order
+const ESP_CANDIDATES: &[&str] = &["/efi", "/boot/efi", "/boot"];
+
+/// Label prefix of every boot entry KernelForge creates
+pub const ENTRY_LABEL: &str = "KernelForge";
+
+/// Location of the EFI system partition
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EspLocation {
+    pub mount: PathBuf,
+    /// Whole disk holding the ESP, e.g. /dev/nvme0n1
+    pub disk: String,
+    pub partition: u32,
+}
+
+/// Boot manager in charge of the machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Bootloader {
+    Grub { config: PathBuf, mkconfig: String },
+    SystemdBoot { esp: PathBuf },
+    /// Pop!_OS manages its ESP copy and loader entry through kernelstub
+    Kernelstub,
+    /// Direct UEFI entries pointing at an EFI-stub kernel
+    Efibootmgr { esp: EspLocation },
+    Unknown,
+}
+
+/// Kernel to register with the boot manager
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootEntryRequest {
+    pub version: String,
+    pub kernel_image: PathBuf,
+    pub initramfs: Option<PathBuf>,
+    pub cmdline: String,
+}
+
+impl Bootloader {
+    /// Detects the boot manager, preferring the most specific tooling present
+    pub fn detect(system: &dyn SystemFacade) -> Bootloader {
+        if system.exists(Path::new("/etc/kernelstub/configuration")) && system.has_program("kernelstub") {
+            return Bootloader::Kernelstub;
+        }
+
+        let systemd_boot = system
+            .run("bootctl", &["is-installed"])
+            .map(|output| output.success())
+            .unwrap_or(false);
+        if systemd_boot {
+            if let Some(esp) = find_esp_mount(system, |mount| system.exists(&mount.join("loader"))) {
+                return Bootloader::SystemdBoot { esp };
+            }
+        }
+
+        for (config, mkconfig) in [
+            ("/boot/grub/grub.cfg", "grub-mkconfig"),
+            ("/boot/grub2/grub.cfg", "grub2-mkconfig"),
+        ] {
+            if system.exists(Path::new(config)) {
+                return Bootloader::Grub {
+                    config: PathBuf::from(config),
+                    mkconfig: String::from(mkconfig),
+                };
+            }
+        }
+
+        if system.exists(Path::new("/sys/firmware/efi")) && system.has_program("efibootmgr") {
+            if let Some(esp) = locate_esp(system) {
+                return Bootloader::Efibootmgr { esp };
+            }
+        }
+
+        Bootloader::Unknown
+    }
+
+    /// Short name for logs and the UI
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bootloader::Grub { .. } => "grub",
+            Bootloader::SystemdBoot { .. } => "systemd-boot",
+            Bootloader::Kernelstub => "kernelstub",
+            Bootloader::Efibootmgr { .. } => "efibootmgr",
+            Bootloader::Unknown => "unknown",
+        }
+    }
+
+    /// Copy of the kernel image on the ESP that the firmware or boot manager loads, None
+    /// when the boot loader reads it from where the install template put it
+    /// kernelstub picks its own ESP path, known only to its configuration
+    pub fn esp_kernel_path(&self, version: &str) -> Option<PathBuf> {
+        match self {
+            Bootloader::SystemdBoot { esp } => Some(esp.join("kernelforge").join(version).join("vmlinuz")),
+            Bootloader::Efibootmgr { esp } => Some(
+                esp.mount
+                    .join("EFI/kernelforge")
+                    .join(format!("vmlinuz-{}.efi", version)),
+            ),
+            Bootloader::Grub { .. } | Bootloader::Kernelstub | Bootloader::Unknown => None,
+        }
+    }
+
+    /// Privileged steps that make `entry` bootable
+    /// The kernel and initramfs are expected to already sit in /boot
+    pub fn install_actions(&self, entry: &BootEntryRequest) -> Vec<PrivilegedAction> {
+        match self {
+            // GRUB discovers /boot/vmlinuz-* itself; its command line comes from /etc/default/grub
+            Bootloader::Grub { config, mkconfig } => {
+                vec![run(mkconfig, &["-o", &config.to_string_lossy()])]
+            }
+            Bootloader::SystemdBoot { esp } => {
+                let dir = format!("kernelforge/{}", entry.version);
+                let mut actions = vec![copy(&entry.kernel_image, &esp.join(&dir).join("vmlinuz"))];
+                let mut conf = format!(
+                    "title   {} {}\nversion {}\nlinux   /{}/vmlinuz\n",
+                    ENTRY_LABEL, entry.version, entry.version, dir
+                );
+                if let Some(initramfs) = &entry.initramfs {
+                    actions.push(copy(initramfs, &esp.join(&dir).join("initramfs.img")));
+                    conf.push_str(&format!("initrd  /{}/initramfs.img\n", dir));
+                }
+                conf.push_str(&format!("options {}\n", entry.cmdline));
+                actions.push(PrivilegedAction::WriteFile {
+                    path: esp.join("loader/entries").join(entry_file(&entry.version)),
+                    contents: conf,
+                    mode: 0o644,
+                });
+                actions
+            }
+            Bootloader::Kernelstub => {
+                let mut args = vec![
+                    String::from("-v"),
+                    String::from("-k"),
+                    entry.kernel_image.to_string_lossy().into_owned(),
+                ];
+                if let Some(initramfs) = &entry.initramfs {
+                    args.push(String::from("-i"));
+                    args.push(initramfs.to_string_lossy().into_owned());
+                }
+                vec![PrivilegedAction::Run {
+                    program: String::from("kernelstub"),
+                    args,
+                }]
+            }
+            Bootloader::Efibootmgr { esp } => {
+                let dir = esp.mount.join("EFI/kernelforge");
+                let kernel_name = format!("vmlinuz-{}.efi", entry.version);
+                let mut actions = vec![copy(&entry.kernel_image, &dir.join(&kernel_name))];
+                let mut cmdline = entry.cmdline.clone();
+                if let Some(initramfs) = &entry.initramfs {
+                    let initrd_name = format!("initramfs-{}.img", entry.version);
+                    actions.push(copy(initramfs, &dir.join(&initrd_name)));
+                    cmdline.push_str(&format!(" initrd=\\EFI\\kernelforge\\{}", initrd_name));
+                }
+                actions.push(run(
+                    "efibootmgr",
+                    &[
+                        "--create",
+                        "--disk",
+                        &esp.disk,
+                        "--part",
+                        &esp.partition.to_string(),
+                        "--label",
+                        &format!("{} {}", ENTRY_LABEL, entry.version),
+                        "--loader",
+                        &format!("\\EFI\\kernelforge\\{}", kernel_name),
+                        "--unicode",
+                        cmdline.trim(),
+                    ],
+                ));
+                actions
+            }
+            Bootloader::Unknown => Vec::new(),
+        }
+    }
+
+    /// Privileged steps that remove the entry for `version`
+    pub fn remove_actions(&self, version: &str, system: &dyn SystemFacade) -> Vec<PrivilegedAction> {
+        match self {
+            Bootloader::Grub { config, mkconfig } => vec![run(mkconfig, &["-o", &config.to_string_lossy()])],
+            Bootloader::SystemdBoot { esp } => vec![
+                PrivilegedAction::RemoveFile {
+                    path: esp.join("loader/entries").join(entry_file(version)),
+                },
+                run("rm", &["-rf", &esp.join("kernelforge").join(version).to_string_lossy()]),
+            ],
+            // kernelstub only tracks the current kernel; the caller re-points it at the previous one
+            Bootloader::Kernelstub => Vec::new(),
+            Bootloader::Efibootmgr { esp } => {
+                let label = format!("{} {}", ENTRY_LABEL, version);
+                let mut actions: Vec<PrivilegedAction> = efi_boot_numbers(system, &label)
+                    .into_iter()
+                    .map(|number| run("efibootmgr", &["--bootnum", &number, "--delete-bootnum"]))
+                    .collect();
+                let dir = esp.mount.join("EFI/kernelforge");
+                actions.push(PrivilegedAction::RemoveFile {
+                    path: dir.join(format!("vmlinuz-{}.efi", version)),
+                });
+                actions.push(PrivilegedAction::RemoveFile {
+                    path: dir.join(format!("initramfs-{}.img", version)),
+                });
+                actions
+            }
+            Bootloader::Unknown => Vec::new(),
+        }
+    }
+
+    /// Whether entries can be booted once and made default by name, which A/B slots rely on
+    /// GRUB orders its menu by version instead, so it is left out
+    pub fn supports_slots(&self) -> bool {
+        matches!(self, Bootloader::SystemdBoot { .. } | Bootloader::Efibootmgr { .. })
+    }
+
+    /// Privileged steps that boot the entry for `version` on the next boot only
+    pub fn boot_once_actions(&self, version: &str, system: &dyn SystemFacade) -> Vec<PrivilegedAction> {
+        match self {
+            Bootloader::SystemdBoot { .. } => vec![run("bootctl", &["set-oneshot", &entry_file(version)])],
+            Bootloader::Efibootmgr { .. } => efi_boot_numbers(system, &format!("{} {}", ENTRY_LABEL, version))
+                .into_iter()
+                .take(1)
+                .map(|number| run("efibootmgr", &["--bootnext", &number]))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Privileged steps that make the entry for `version` the default
+    pub fn set_default_actions(&self, version: &str, system: &dyn SystemFacade) -> Vec<PrivilegedAction> {
+        match self {
+            Bootloader::SystemdBoot { .. } => vec![run("bootctl", &["set-default", &entry_file(version)])],
+            Bootloader::Efibootmgr { .. } => {
+                let Some(number) = efi_boot_numbers(system, &format!("{} {}", ENTRY_LABEL, version)).into_iter().next()
+                else {
+                    return Vec::new();
+                };
+                let mut order = vec![number.clone()];
+                order.extend(efi_boot_order(system).into_iter().filter(|entry| *entry != number));
+                vec![run("efibootmgr", &["--bootorder", &order.join(",")])]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn entry_file(version: &str) -> String {
+    format!("kernelforge-{}.conf", version)
+}
+
+fn run(program: &str, args: &[&str]) -> PrivilegedAction {
+    PrivilegedAction::Run {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    }
+}
+
+fn copy(from: &Path, to: &Path) -> PrivilegedAction {
+    run("install", &["-D", "-m", "644", &from.to_string_lossy(), &to.to_string_lossy()])
+}
+
+/// Boot numbers of UEFI entries whose label matches exactly
+fn efi_boot_numbers(system: &dyn SystemFacade, label: &str) -> Vec<String> {
+    let Ok(output) = system.run("efibootmgr", &[]) else {
+        return Vec::new();
+    };
+    output
+        .stdout
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("Boot")?;
+            let (number, description) = rest.split_at(rest.find(|c: char| !c.is_ascii_hexdigit())?);
+            let description = description.trim_start_matches('*').trim();
+            let name = description.split('\t').next().unwrap_or(description).trim();
+            (number.len() == 4 && name == label).then(|| number.to_string())
+        })
+        .collect()
+}
+
+/// Current BootOrder, as hex boot numbers
+fn efi_boot_order(system: &dyn SystemFacade) -> Vec<String> {
+    let Ok(output) = system.run("efibootmgr", &[]) else {
+        return Vec::new();
+    };
+    output
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("BootOrder:"))
+        .map(|order| order.trim().split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn find_esp_mount(system: &dyn SystemFacade, accept: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    ESP_CANDIDATES
+        .iter()
+        .map(PathBuf::from)
+        .find(|mount| system.exists(&mount.join("EFI")) && accept(mount))
+}
+
+/// Finds the ESP mount and the disk/partition efibootmgr needs
+fn locate_esp(system: &dyn SystemFacade) -> Option<EspLocation> {
+    let mount = find_esp_mount(system, |_| true)?;
+    let source = system
+        .run("findmnt", &["-no", "SOURCE", &mount.to_string_lossy()])
+        .ok()?
+        .stdout
+        .trim()
+        .to_string();
+    let device = source.strip_prefix("/dev/")?;
+    let parent = system
+        .run("lsblk", &["-no", "PKNAME", &source])
+        .ok()?
+        .stdout
+        .trim()
+        .to_string();
+    let partition = system
+        .read_trimmed(&Path::new("/sys/class/block").join(device).join("partition"))?
+        .parse()
+        .ok()?;
+    Some(EspLocation {
+        mount,
+        disk: format!("/dev/{}", parent),
+        partition,
+    })
+}
@@ -0,0 +1,145 @@
+// src-tauri/src/core/boottime.rs
+
+use std::cmp::Reverse;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::results::KernelTag;
+use super::system::SystemFacade;
+
+/// Slowest units kept from `systemd-analyze blame`
+const BLAME_ENTRIES: usize = 15;
+
+/// Time a unit took to start
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameEntry {
+    pub unit: String,
+    pub ms: u64,
+}
+
+/// Boot phases of one boot as reported by systemd-analyze, in milliseconds
+/// Firmware and loader times are only known on UEFI machines with a loader that reports them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootTime {
+    pub kernel: KernelTag,
+    pub recorded_at: u64,
+    pub firmware_ms: Option<u64>,
+    pub loader_ms: Option<u64>,
+    pub kernel_ms: u64,
+    pub initrd_ms: Option<u64>,
+    pub userspace_ms: u64,
+    pub blame: Vec<BlameEntry>,
+}
+
+impl BootTime {
+    /// Time from the kernel taking over until userspace finished starting up
+    pub fn total_ms(&self) -> u64 {
+        self.kernel_ms + self.initrd_ms.unwrap_or(0) + self.userspace_ms
+    }
+
+    /// Part the kernel itself is responsible for, including the initrd it unpacks and runs
+    pub fn kernel_side_ms(&self) -> u64 {
+        self.kernel_ms + self.initrd_ms.unwrap_or(0)
+    }
+
+    /// Parses `systemd-analyze time`, e.g.
+    /// `Startup finished in 5.1s (firmware) + 2.3s (loader) + 1.2s (kernel) + 3.4s (initrd) + 8.9s (userspace) = 20.9s`
+    pub fn parse_time(kernel: KernelTag, text: &str) -> Result<Self> {
+        let line = text
+            .lines()
+            .find(|line| line.starts_with("Startup finished in "))
+            .context("systemd-analyze reported no finished startup")?;
+        let phases = line["Startup finished in ".len()..]
+            .split('=')
+            .next()
+            .unwrap_or_default();
+        let mut boot = BootTime {
+            kernel,
+            recorded_at: unix_now(),
+            firmware_ms: None,
+            loader_ms: None,
+            kernel_ms: 0,
+            initrd_ms: None,
+            userspace_ms: 0,
+            blame: Vec::new(),
+        };
+        for phase in phases.split(" + ") {
+            let Some((duration, name)) = phase.trim().rsplit_once(" (") else {
+                continue;
+            };
+            let ms = parse_duration(duration)
+                .with_context(|| format!("unrecognized duration {:?}", duration))?;
+            match name.trim_end_matches(')') {
+                "firmware" => boot.firmware_ms = Some(ms),
+                "loader" => boot.loader_ms = Some(ms),
+                "kernel" => boot.kernel_ms = ms,
+                "initrd" => boot.initrd_ms = Some(ms),
+                "userspace" => boot.userspace_ms = ms,
+                _ => {}
+            }
+        }
+        if boot.kernel_ms == 0 {
+            bail!("systemd-analyze reported no kernel time: {}", line);
+        }
+        Ok(boot)
+    }
+
+    /// Parses `systemd-analyze blame`, keeping the slowest units
+    pub fn parse_blame(text: &str) -> Vec<BlameEntry> {
+        let mut entries: Vec<BlameEntry> = text
+            .lines()
+            .filter_map(|line| {
+                // The unit name follows the last duration token
+                let (duration, unit) = line.trim().rsplit_once(' ')?;
+                Some(BlameEntry {
+                    unit: unit.to_string(),
+                    ms: parse_duration(duration.trim())?,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|entry| Reverse(entry.ms));
+        entries.truncate(BLAME_ENTRIES);
+        entries
+    }
+}
+
+/// Reads the current boot's timings; fails while startup is still in progress
+pub fn collect_boot_time(system: &dyn SystemFacade, kernel: KernelTag) -> Result<BootTime> {
+    let output = system
+        .run("systemd-analyze", &["time"])
+        .context("running systemd-analyze")?;
+    if !output.success() {
+        bail!("systemd-analyze time failed: {}", output.stderr.trim());
+    }
+    let mut boot = BootTime::parse_time(kernel, &output.stdout)?;
+    if let Ok(blame) = system.run("systemd-analyze", &["blame", "--no-pager"]) {
+        if blame.success() {
+            boot.blame = BootTime::parse_blame(&blame.stdout);
+        }
+    }
+    Ok(boot)
+}
+
+/// Converts systemd's timespan format, e.g. `1min 2.345s`, `789ms` or `12us`, to milliseconds
+fn parse_duration(text: &str) -> Option<u64> {
+    let mut total = 0.0;
+    let mut parts = 0;
+    for part in text.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (number, unit) = part.split_at(split);
+        let value: f64 = number.parse().ok()?;
+        let factor = match unit {
+            "h" => 3_600_000.0,
+            "min" => 60_000.0,
+            "s" => 1000.0,
+            "ms" => 1.0,
+            "us" | "µs" => 0.001,
+            _ => return None,
+        };
+        total += value * factor;
+        parts += 1;
+    }
+    (parts > 0).then(|| total.round() as u64)
+}
@@ -0,0 +1,582 @@
+// src-tauri/src/core/build.rs
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio::time;
+
+use super::advisory::{Advisory, Severity};
+use super::cgroup::{BuildScope, ResourceLimits, ScopeUsage};
+use super::memory_guard::{self, MemoryGuard, MemoryPressure, MAX_LINK_PAUSE};
+use super::power::{PowerState, ThrottleDecision, ThrottlePolicy};
+use super::stats::{BuildRecord, WarningRegression, WarningSummary};
+use super::system::{CommandSpec, SystemFacade};
+use super::watchdog::{OutputTail, Stall, StallAction, StageWatchdog};
+
+/// How often the watchdog looks at the elapsed time and the output
+const WATCHDOG_POLL: Duration = Duration::from_secs(5);
+
+/// How long to wait for a sandboxed build to report its host-side process group
+const PGID_POLL: Duration = Duration::from_millis(100);
+const PGID_ATTEMPTS: usize = 50;
+
+/// How often a confined build's resource usage is reported
+const USAGE_POLL: Duration = Duration::from_secs(2);
+
+/// Settings for one `make` invocation in a prepared source tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildOptions {
+    pub jobs: usize,
+    pub targets: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Battery and temperature throttling, None to always run at full speed
+    pub throttle: Option<ThrottlePolicy>,
+    /// Timeout and silence limits of the stage this build belongs to, None to never flag a stall
+    #[serde(default)]
+    pub watchdog: Option<StageWatchdog>,
+    /// Feed make an endless stream of empty lines, like `yes "" | make`, so a Kconfig prompt
+    /// takes its default instead of blocking the build
+    #[serde(default)]
+    pub answer_prompts: bool,
+    /// Runs make in a transient systemd scope with these limits, None to run it unconfined
+    #[serde(default)]
+    pub confinement: Option<ResourceLimits>,
+    /// Lowers parallelism under memory pressure and serializes the LTO link, None to leave
+    /// memory to the kernel
+    #[serde(default)]
+    pub memory_guard: Option<MemoryGuard>,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        BuildOptions {
+            jobs: 1,
+            targets: Vec::new(),
+            env: Vec::new(),
+            throttle: None,
+            watchdog: None,
+            answer_prompts: false,
+            confinement: None,
+            memory_guard: None,
+        }
+    }
+}
+
+/// Progress notifications emitted while building
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BuildEvent {
+    Started { jobs: usize },
+    Line(String),
+    Throttled { decision: ThrottleDecision, state: PowerState },
+    /// The watchdog flagged the build; it keeps running until a `StallAction` arrives
+    Stalled(Stall),
+    /// Usage of the confined build's scope; `cpu_percent` is relative to one CPU
+    Resources { usage: ScopeUsage, cpu_percent: f32 },
+    /// The memory guard restarts make with `jobs` to keep the OOM killer away
+    MemoryPressure { pressure: MemoryPressure, jobs: usize },
+    /// The memory guard paused the vmlinux link, or resumed it once the pressure eased
+    LinkPaused { pressure: MemoryPressure, paused: bool },
+    Finished { success: bool },
+}
+
+/// Result of a completed build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildOutcome {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    /// How often make was restarted, to change parallelism or after a stall
+    pub restarts: u32,
+    pub warnings: WarningSummary,
+    /// The stall the build was killed for
+    #[serde(default)]
+    pub stall: Option<Stall>,
+}
+
+/// What the user sees once a build has finished
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildReport {
+    pub outcome: BuildOutcome,
+    /// Warning changes against the previous build of the same plan
+    pub regression: Option<WarningRegression>,
+    pub advisories: Vec<Advisory>,
+}
+
+impl BuildReport {
+    /// Builds the report from the recorded build and the regression the stats store found
+    pub fn new(outcome: BuildOutcome, record: &BuildRecord, regression: Option<WarningRegression>) -> Self {
+        let mut advisories = Vec::new();
+        if let Some(regression) = regression.as_ref().filter(|regression| regression.is_regression()) {
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "build-warning-regression",
+                &[
+                    ("categories", regression.new_categories.join(", ")),
+                    ("before", regression.total_before.to_string()),
+                    ("after", regression.total_after.to_string()),
+                ],
+            ));
+        }
+        if let Some(budget) = record.warning_budget.filter(|_| record.over_budget()) {
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "build-warning-budget",
+                &[
+                    ("count", record.warnings.total.to_string()),
+                    ("budget", budget.to_string()),
+                ],
+            ));
+        }
+        BuildReport {
+            outcome,
+            regression,
+            advisories,
+        }
+    }
+}
+
+/// How supervision of one make process ended
+enum Supervision {
+    Exited,
+    /// Parallelism changed
+    Restart,
+    /// Memory ran short and make has to continue with this many jobs
+    Reduce(usize),
+    /// The user retried a stalled build
+    Retry,
+    /// The user gave up on a stalled build
+    Kill(Stall),
+}
+
+/// Runs kernel builds and applies the throttle policy while they run
+pub struct BuildExecutor {
+    source_dir: PathBuf,
+    options: BuildOptions,
+    system: Arc<dyn SystemFacade>,
+}
+
+impl BuildExecutor {
+    /// Creates an executor for a prepared source tree
+    pub fn new(source_dir: PathBuf, options: BuildOptions, system: Arc<dyn SystemFacade>) -> Self {
+        BuildExecutor {
+            source_dir,
+            options,
+            system,
+        }
+    }
+
+    /// Runs make to completion, pausing or restarting it with fewer jobs as the policy demands
+    /// Nobody can be asked about a stall here, so a stalled build is killed
+    pub async fn run(&self, events: &UnboundedSender<BuildEvent>) -> Result<BuildOutcome> {
+        let (_, mut actions) = mpsc::unbounded_channel();
+        self.run_supervised(events, &mut actions).await
+    }
+
+    /// Like `run`, but reports stalls as `BuildEvent::Stalled` and waits on `actions` for what to do
+    pub async fn run_supervised(
+        &self,
+        events: &UnboundedSender<BuildEvent>,
+        actions: &mut UnboundedReceiver<StallAction>,
+    ) -> Result<BuildOutcome> {
+        let full_jobs = self.options.jobs.max(1);
+        let mut decision = match &self.options.throttle {
+            Some(policy) => policy.decide(&PowerState::read(self.system.as_ref()), ThrottleDecision::Full),
+            None => ThrottleDecision::Full,
+        };
+        let mut restarts = 0;
+        // Every make gets a scope of its own, whether it restarts a phase or starts the next one
+        let mut invocations = 0;
+        let warnings = Arc::new(Mutex::new(WarningSummary::default()));
+        let phases = match &self.options.memory_guard {
+            Some(guard) => guard.phases(&self.options.targets),
+            None => vec![self.options.targets.clone()],
+        };
+        let mut phase = 0;
+        let mut memory_cap = full_jobs;
+
+        loop {
+            let jobs = ThrottlePolicy::jobs_for(decision, full_jobs).min(memory_cap);
+            let tail_lines = self.options.watchdog.as_ref().map_or(0, |watchdog| watchdog.tail_lines);
+            let tail = Arc::new(Mutex::new(OutputTail::new(tail_lines)));
+            let scope = self.scope(invocations);
+            invocations += 1;
+            let mut process = self.spawn(jobs, &phases[phase], scope, events, &warnings, &tail)?;
+            let monitor = process
+                .scope
+                .clone()
+                .map(|scope| monitor_usage(scope, Arc::clone(&self.system), events.clone()));
+            let _ = events.send(BuildEvent::Started { jobs });
+            if decision == ThrottleDecision::Paused {
+                process.signal("STOP").await?;
+            }
+
+            let supervision = self.supervise(&mut process, &mut decision, jobs, &tail, events, actions).await;
+            if let Some(monitor) = monitor {
+                monitor.abort();
+            }
+            match supervision? {
+                Supervision::Exited => {}
+                Supervision::Reduce(jobs) => {
+                    memory_cap = jobs.max(1);
+                    process.signal("CONT").await?;
+                    process.signal("TERM").await?;
+                    process.child.wait().await?;
+                    restarts += 1;
+                    continue;
+                }
+                Supervision::Restart | Supervision::Retry => {
+                    process.signal("CONT").await?;
+                    process.signal("TERM").await?;
+                    process.child.wait().await?;
+                    restarts += 1;
+                    continue;
+                }
+                Supervision::Kill(stall) => {
+                    process.signal("CONT").await?;
+                    process.signal("KILL").await?;
+                    let status = process.child.wait().await?;
+                    let _ = events.send(BuildEvent::Finished { success: false });
+                    return Ok(BuildOutcome {
+                        success: false,
+                        exit_code: status.code(),
+                        restarts,
+                        warnings: warnings.lock().map(|summary| summary.clone()).unwrap_or_default(),
+                        stall: Some(stall),
+                    });
+                }
+            }
+
+            let status = process.child.wait().await?;
+            if status.success() && phase + 1 < phases.len() {
+                phase += 1;
+                continue;
+            }
+            let success = status.success();
+            let _ = events.send(BuildEvent::Finished { success });
+            return Ok(BuildOutcome {
+                success,
+                exit_code: status.code(),
+                restarts,
+                warnings: warnings.lock().map(|summary| summary.clone()).unwrap_or_default(),
+                stall: None,
+            });
+        }
+    }
+
+    /// Polls the power state, memory pressure and the watchdog until make exits or has to be stopped
+    async fn supervise(
+        &self,
+        process: &mut BuildProcess,
+        decision: &mut ThrottleDecision,
+        running_jobs: usize,
+        tail: &Mutex<OutputTail>,
+        events: &UnboundedSender<BuildEvent>,
+        actions: &mut UnboundedReceiver<StallAction>,
+    ) -> Result<Supervision> {
+        let throttle_poll = self.options.throttle.as_ref().map_or(WATCHDOG_POLL, |policy| policy.poll_interval);
+        let mut throttle_ticker = time::interval(throttle_poll);
+        let mut watchdog_ticker = time::interval(WATCHDOG_POLL);
+        let memory_poll = self.options.memory_guard.as_ref().map_or(WATCHDOG_POLL, |guard| guard.poll_interval);
+        let mut memory_ticker = time::interval(memory_poll);
+        let started = Instant::now();
+        let mut deadline = self.options.watchdog.as_ref().and_then(StageWatchdog::deadline);
+        // The link is a single process; under pressure it waits instead of being restarted,
+        // for MAX_LINK_PAUSE at a stretch; a link held that long is not paused again
+        let mut link_paused_at: Option<Instant> = None;
+        let mut link_pause_spent = false;
+        loop {
+            tokio::select! {
+                _ = process.child.wait() => return Ok(Supervision::Exited),
+                _ = throttle_ticker.tick(), if self.options.throttle.is_some() => {
+                    let Some(policy) = &self.options.throttle else {
+                        continue;
+                    };
+                    let state = PowerState::read(self.system.as_ref());
+                    let next = policy.decide(&state, *decision);
+                    if next == *decision {
+                        continue;
+                    }
+                    let _ = events.send(BuildEvent::Throttled { decision: next, state });
+                    let previous = std::mem::replace(decision, next);
+                    if next == ThrottleDecision::Paused {
+                        process.signal("STOP").await?;
+                        continue;
+                    }
+                    if previous == ThrottleDecision::Paused && link_paused_at.is_none() {
+                        process.signal("CONT").await?;
+                    }
+                    if ThrottlePolicy::jobs_for(next, self.options.jobs.max(1)) != running_jobs {
+                        return Ok(Supervision::Restart);
+                    }
+                }
+                _ = memory_ticker.tick(), if self.options.memory_guard.is_some() => {
+                    let Some(guard) = &self.options.memory_guard else {
+                        continue;
+                    };
+                    // A paused build allocates nothing, so the pressure is someone else's
+                    if *decision == ThrottleDecision::Paused {
+                        continue;
+                    }
+                    // The scope's own PSI leaves out whatever else the machine is doing
+                    let pressure = process
+                        .scope
+                        .as_mut()
+                        .and_then(|scope| scope.memory_pressure(self.system.as_ref()))
+                        .or_else(|| MemoryPressure::read(self.system.as_ref()));
+                    let Some(pressure) = pressure else {
+                        continue;
+                    };
+                    if process.linking.load(Ordering::Relaxed) {
+                        if link_paused_at.is_some_and(|paused_at| paused_at.elapsed() >= MAX_LINK_PAUSE) {
+                            link_pause_spent = true;
+                        }
+                        let pressed = !link_pause_spent && guard.should_pause_link(&pressure);
+                        if pressed != link_paused_at.is_some() {
+                            process.signal(if pressed { "STOP" } else { "CONT" }).await?;
+                            link_paused_at = pressed.then(Instant::now);
+                            let _ = events.send(BuildEvent::LinkPaused { pressure, paused: pressed });
+                        }
+                        continue;
+                    }
+                    if let Some(jobs) = guard.reduce(&pressure, running_jobs) {
+                        let _ = events.send(BuildEvent::MemoryPressure { pressure, jobs });
+                        return Ok(Supervision::Reduce(jobs));
+                    }
+                }
+                _ = watchdog_ticker.tick(), if self.options.watchdog.is_some() => {
+                    let Some(watchdog) = &self.options.watchdog else {
+                        continue;
+                    };
+                    let stall = {
+                        let Ok(mut recent) = tail.lock() else {
+                            continue;
+                        };
+                        // A paused build is silent on purpose and its pause does not count against the limit
+                        if *decision == ThrottleDecision::Paused || link_paused_at.is_some() {
+                            recent.touch();
+                            deadline = deadline.map(|deadline| deadline + WATCHDOG_POLL);
+                            continue;
+                        }
+                        let Some(kind) = watchdog.check(deadline, &recent) else {
+                            continue;
+                        };
+                        Stall {
+                            stage: watchdog.stage,
+                            kind,
+                            elapsed_secs: started.elapsed().as_secs(),
+                            last_output: recent.lines(),
+                        }
+                    };
+                    let _ = events.send(BuildEvent::Stalled(stall.clone()));
+                    tokio::select! {
+                        _ = process.child.wait() => return Ok(Supervision::Exited),
+                        action = actions.recv() => match action.unwrap_or(StallAction::Kill) {
+                            StallAction::Kill => return Ok(Supervision::Kill(stall)),
+                            StallAction::Retry => return Ok(Supervision::Retry),
+                            StallAction::KeepWaiting => {
+                                deadline = watchdog.deadline();
+                                if let Ok(mut recent) = tail.lock() {
+                                    recent.touch();
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    /// Scope for one make invocation, None when unconfined or cgroups are unavailable
+    fn scope(&self, attempt: u32) -> Option<BuildScope> {
+        self.options
+            .confinement
+            .as_ref()
+            .filter(|_| ResourceLimits::is_supported(self.system.as_ref()))
+            .map(|_| BuildScope::new(attempt))
+    }
+
+    fn spawn(
+        &self,
+        jobs: usize,
+        targets: &[String],
+        scope: Option<BuildScope>,
+        events: &UnboundedSender<BuildEvent>,
+        warnings: &Arc<Mutex<WarningSummary>>,
+        tail: &Arc<Mutex<OutputTail>>,
+    ) -> Result<BuildProcess> {
+        let mut args = vec![format!("-j{}", jobs)];
+        args.extend(targets.iter().cloned());
+        let mut spec = CommandSpec {
+            program: String::from("make"),
+            args,
+            env: self.options.env.clone(),
+            env_remove: Vec::new(),
+            cwd: Some(self.source_dir.clone()),
+        };
+        if let (Some(limits), Some(scope)) = (&self.options.confinement, &scope) {
+            spec = limits.wrap(spec, &scope.unit);
+        }
+        let (spec, pgid_file) = self.system.prepare_group_command(spec);
+        let mut command = Command::new(&spec.program);
+        command.args(&spec.args).envs(spec.env.iter().map(|(key, value)| (key, value)));
+        for name in &spec.env_remove {
+            command.env_remove(name);
+        }
+        if let Some(cwd) = &spec.cwd {
+            command.current_dir(cwd);
+        }
+        let stdin = if self.options.answer_prompts { Stdio::piped() } else { Stdio::null() };
+        command
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // Own process group so pause/resume reaches every compiler make spawned
+            .process_group(0)
+            .kill_on_drop(true);
+        let linking = Arc::new(AtomicBool::new(false));
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("starting make in {}", self.source_dir.display()))?;
+        if let Some(stdin) = child.stdin.take() {
+            answer_prompts(stdin);
+        }
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, events.clone(), Arc::clone(warnings), Arc::clone(tail), Arc::clone(&linking));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, events.clone(), Arc::clone(warnings), Arc::clone(tail), Arc::clone(&linking));
+        }
+        Ok(BuildProcess {
+            child,
+            system: Arc::clone(&self.system),
+            pgid_file,
+            scope,
+            linking,
+        })
+    }
+}
+
+/// Reports the scope's usage until aborted; the first report has no CPU rate yet
+fn monitor_usage(
+    mut scope: BuildScope,
+    system: Arc<dyn SystemFacade>,
+    events: UnboundedSender<BuildEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = time::interval(USAGE_POLL);
+        let mut last: Option<(Instant, u64)> = None;
+        loop {
+            ticker.tick().await;
+            let Some(usage) = scope.usage(system.as_ref()) else {
+                continue;
+            };
+            let now = Instant::now();
+            let cpu_percent = last.map_or(0.0, |(at, cpu_usec)| {
+                let wall_usec = now.duration_since(at).as_micros().max(1) as f32;
+                usage.cpu_usec.saturating_sub(cpu_usec) as f32 * 100.0 / wall_usec
+            });
+            last = Some((now, usage.cpu_usec));
+            if events.send(BuildEvent::Resources { usage, cpu_percent }).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+fn forward_lines(
+    stream: impl AsyncRead + Unpin + Send + 'static,
+    events: UnboundedSender<BuildEvent>,
+    warnings: Arc<Mutex<WarningSummary>>,
+    tail: Arc<Mutex<OutputTail>>,
+    linking: Arc<AtomicBool>,
+) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if memory_guard::starts_link(&line) {
+                linking.store(true, Ordering::Relaxed);
+            }
+            if let Ok(mut summary) = warnings.lock() {
+                summary.observe(&line);
+            }
+            if let Ok(mut tail) = tail.lock() {
+                tail.observe(&line);
+            }
+            if events.send(BuildEvent::Line(line)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Presses Enter on every prompt until make exits and closes the pipe
+fn answer_prompts(mut stdin: impl AsyncWrite + Unpin + Send + 'static) {
+    tokio::spawn(async move {
+        let newlines = [b'\n'; 256];
+        while stdin.write_all(&newlines).await.is_ok() {}
+    });
+}
+
+/// A running make, whose process group also holds every compiler it spawned
+struct BuildProcess {
+    child: Child,
+    system: Arc<dyn SystemFacade>,
+    /// Where a sandboxed facade has the host-side group id written; without it the child
+    /// leads the group itself
+    pgid_file: Option<PathBuf>,
+    /// The scope make runs in when confined
+    scope: Option<BuildScope>,
+    /// Set once make has started linking vmlinux
+    linking: Arc<AtomicBool>,
+}
+
+impl BuildProcess {
+    /// Group id to signal; waits briefly for a host-side command to report it
+    async fn pgid(&self) -> Option<u32> {
+        let Some(file) = &self.pgid_file else {
+            return self.child.id();
+        };
+        for _ in 0..PGID_ATTEMPTS {
+            if let Ok(text) = tokio::fs::read_to_string(file).await {
+                if let Ok(pgid) = text.trim().parse() {
+                    return Some(pgid);
+                }
+            }
+            time::sleep(PGID_POLL).await;
+        }
+        None
+    }
+
+    /// Sends a signal to make and all of its children, on the host when make runs there
+    async fn signal(&self, signal: &str) -> Result<()> {
+        let Some(pgid) = self.pgid().await else {
+            return Ok(());
+        };
+        let spec = self.system.prepare_command(CommandSpec::new(
+            "kill",
+            &[&format!("-{}", signal), "--", &format!("-{}", pgid)],
+        ));
+        Command::new(&spec.program)
+            .args(&spec.args)
+            .status()
+            .await
+            .with_context(|| format!("sending SIG{} to build", signal))?;
+        Ok(())
+    }
+}
+
+impl Drop for BuildProcess {
+    fn drop(&mut self) {
+        if let Some(file) = &self.pgid_file {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
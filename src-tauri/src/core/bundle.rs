@@ -0,0 +1,265 @@
+// src-tauri/src/core/bundle.rs
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::cache::{sha256_file, CacheCategory, CacheIndex, SignatureStatus};
+use super::clock::unix_now;
+use super::config::KernelConfig;
+use super::system::SystemFacade;
+
+/// Manifest at the root of every bundle
+pub const BUNDLE_MANIFEST: &str = "bundle.json";
+const BUNDLE_CONFIG: &str = "config";
+const BUNDLE_FORMAT: u32 = 1;
+
+/// A program the build machine needs, since an offline machine cannot fetch it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRequirement {
+    pub program: String,
+    pub purpose: String,
+}
+
+impl ToolRequirement {
+    fn new(program: &str, purpose: &str) -> Self {
+        ToolRequirement {
+            program: program.to_string(),
+            purpose: purpose.to_string(),
+        }
+    }
+}
+
+/// Tools a build of `config` runs
+pub fn toolchain_requirements(config: &KernelConfig) -> Vec<ToolRequirement> {
+    let clang = config.is_enabled("CONFIG_CC_IS_CLANG") || config.is_enabled("CONFIG_LTO_CLANG");
+    let mut tools = vec![
+        ToolRequirement::new("make", "drives the build"),
+        ToolRequirement::new(if clang { "clang" } else { "gcc" }, "compiles the kernel"),
+        ToolRequirement::new(if clang { "ld.lld" } else { "ld" }, "links the kernel"),
+        ToolRequirement::new("flex", "generates the Kconfig lexer"),
+        ToolRequirement::new("bison", "generates the Kconfig parser"),
+        ToolRequirement::new("bc", "computes timer constants"),
+        ToolRequirement::new("perl", "runs build scripts"),
+        ToolRequirement::new("openssl", "signs modules and builds sign-file"),
+    ];
+    let optional = [
+        (
+            "CONFIG_DEBUG_INFO_BTF",
+            "pahole",
+            "generates BTF type information",
+        ),
+        ("CONFIG_MODULE_COMPRESS_ZSTD", "zstd", "compresses modules"),
+        ("CONFIG_MODULE_COMPRESS_XZ", "xz", "compresses modules"),
+        ("CONFIG_KERNEL_ZSTD", "zstd", "compresses the kernel image"),
+        ("CONFIG_RUST", "rustc", "compiles Rust kernel code"),
+        ("CONFIG_RUST", "bindgen", "generates Rust bindings"),
+    ];
+    for (symbol, program, purpose) in optional {
+        if config.is_enabled(symbol) && !tools.iter().any(|tool| tool.program == program) {
+            tools.push(ToolRequirement::new(program, purpose));
+        }
+    }
+    tools
+}
+
+/// A source tarball or patch carried in the bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path inside the bundle, `<category>/<file name>`
+    pub path: PathBuf,
+    pub category: CacheCategory,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Describes a bundle: what it builds and with which inputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub plan: String,
+    pub version: String,
+    pub created_at: u64,
+    pub files: Vec<BundleFile>,
+    pub toolchain: Vec<ToolRequirement>,
+}
+
+impl BundleManifest {
+    /// Required programs the local machine lacks
+    pub fn missing_tools(&self, system: &dyn SystemFacade) -> Vec<&ToolRequirement> {
+        self.toolchain
+            .iter()
+            .filter(|tool| !system.has_program(&tool.program))
+            .collect()
+    }
+}
+
+/// What an import placed in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedBundle {
+    pub manifest: BundleManifest,
+    pub config: KernelConfig,
+    /// Cached locations of the bundle's files, in manifest order
+    pub files: Vec<PathBuf>,
+}
+
+/// What goes into an exported bundle
+#[derive(Debug, Clone, Copy)]
+pub struct BundleContents<'a> {
+    pub plan: &'a str,
+    pub version: &'a str,
+    pub config: &'a KernelConfig,
+    /// Cached source tarballs and patches
+    pub files: &'a [PathBuf],
+}
+
+/// Packs cached sources and patches, the config and a manifest into one tar archive
+/// Every file must be tracked by the cache and still match its recorded digest
+pub fn export_bundle(
+    system: &dyn SystemFacade,
+    index: &CacheIndex,
+    contents: BundleContents<'_>,
+    output: &Path,
+    staging: &Path,
+) -> Result<BundleManifest> {
+    if staging.exists() {
+        fs::remove_dir_all(staging).with_context(|| format!("clearing {}", staging.display()))?;
+    }
+    fs::create_dir_all(staging).with_context(|| format!("creating {}", staging.display()))?;
+
+    let mut bundled = Vec::new();
+    for file in contents.files {
+        let entry = index
+            .get(file)
+            .with_context(|| format!("{} is not in the cache", file.display()))?;
+        if let Some(problem) = index.check(entry)? {
+            bail!("{} cannot be bundled: {:?}", file.display(), problem);
+        }
+        let name = file
+            .file_name()
+            .with_context(|| format!("{} has no file name", file.display()))?;
+        let path = Path::new(entry.category.dir_name()).join(name);
+        fs::create_dir_all(staging.join(entry.category.dir_name()))?;
+        fs::copy(index.file_path(entry), staging.join(&path))
+            .with_context(|| format!("copying {}", file.display()))?;
+        bundled.push(BundleFile {
+            path,
+            category: entry.category,
+            size: entry.size,
+            sha256: entry.sha256.clone(),
+        });
+    }
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        plan: contents.plan.to_string(),
+        version: contents.version.to_string(),
+        created_at: unix_now(),
+        files: bundled,
+        toolchain: toolchain_requirements(contents.config),
+    };
+    fs::write(staging.join(BUNDLE_CONFIG), contents.config.emit())?;
+    fs::write(
+        staging.join(BUNDLE_MANIFEST),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    run_tar(
+        system,
+        &[
+            "-cf",
+            &output.to_string_lossy(),
+            "-C",
+            &staging.to_string_lossy(),
+            ".",
+        ],
+    )?;
+    let _ = fs::remove_dir_all(staging);
+    Ok(manifest)
+}
+
+/// Unpacks a bundle, checks every file against the manifest and adds it to the cache
+/// Needs no network; the returned config and cached files are enough to build
+pub fn import_bundle(
+    system: &dyn SystemFacade,
+    index: &mut CacheIndex,
+    archive: &Path,
+    staging: &Path,
+) -> Result<ImportedBundle> {
+    if staging.exists() {
+        fs::remove_dir_all(staging).with_context(|| format!("clearing {}", staging.display()))?;
+    }
+    fs::create_dir_all(staging).with_context(|| format!("creating {}", staging.display()))?;
+    run_tar(
+        system,
+        &[
+            "-xf",
+            &archive.to_string_lossy(),
+            "-C",
+            &staging.to_string_lossy(),
+        ],
+    )?;
+
+    let manifest_path = staging.join(BUNDLE_MANIFEST);
+    let manifest: BundleManifest = serde_json::from_str(
+        &fs::read_to_string(&manifest_path)
+            .with_context(|| format!("{} has no {}", archive.display(), BUNDLE_MANIFEST))?,
+    )
+    .with_context(|| format!("parsing {}", manifest_path.display()))?;
+    if manifest.format > BUNDLE_FORMAT {
+        bail!(
+            "{} uses bundle format {}, this version reads up to {}",
+            archive.display(),
+            manifest.format,
+            BUNDLE_FORMAT
+        );
+    }
+    let config = KernelConfig::parse(
+        &fs::read_to_string(staging.join(BUNDLE_CONFIG)).context("reading bundled config")?,
+    )?;
+
+    // Verify everything before touching the cache
+    for file in &manifest.files {
+        let unpacked = staging.join(&file.path);
+        let contained = file
+            .path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !contained {
+            bail!("bundle entry {} escapes the bundle", file.path.display());
+        }
+        let digest = sha256_file(&unpacked)?;
+        if !digest.eq_ignore_ascii_case(&file.sha256) {
+            bail!(
+                "checksum mismatch for {}: expected {}, got {}",
+                file.path.display(),
+                file.sha256,
+                digest
+            );
+        }
+    }
+    let mut files = Vec::new();
+    for file in &manifest.files {
+        let name = file.path.file_name().context("bundle entry has no name")?;
+        let destination = index.category_dir(file.category)?.join(name);
+        fs::copy(staging.join(&file.path), &destination)
+            .with_context(|| format!("copying {} into the cache", file.path.display()))?;
+        index.record(&destination, file.category, SignatureStatus::Unchecked)?;
+        files.push(destination);
+    }
+    let _ = fs::remove_dir_all(staging);
+    Ok(ImportedBundle {
+        manifest,
+        config,
+        files,
+    })
+}
+
+fn run_tar(system: &dyn SystemFacade, args: &[&str]) -> Result<()> {
+    let output = system.run("tar", args).context("running tar")?;
+    if !output.success() {
+        bail!("tar failed ({}): {}", output.status, output.stderr.trim());
+    }
+    Ok(())
+}
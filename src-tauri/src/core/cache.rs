@@ -0,0 +1,461 @@
+// src-tauri/src/core/cache.rs
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::state_file;
+
+const INDEX_FILE: &str = "index.json";
+
+const GIB: u64 = 1 << 30;
+
+/// Kinds of data kept in the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CacheCategory {
+    Sources,
+    Patches,
+    /// Built kernel packages and images
+    Artifacts,
+    /// Compiler cache directory, managed by ccache itself rather than the index
+    Ccache,
+    /// Archives of prepared source trees, restored instead of fetching and patching again
+    Checkpoints,
+}
+
+impl CacheCategory {
+    pub const ALL: [CacheCategory; 5] = [
+        CacheCategory::Sources,
+        CacheCategory::Patches,
+        CacheCategory::Artifacts,
+        CacheCategory::Ccache,
+        CacheCategory::Checkpoints,
+    ];
+
+    /// Subdirectory of the cache holding this category
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            CacheCategory::Sources => "sources",
+            CacheCategory::Patches => "patches",
+            CacheCategory::Artifacts => "artifacts",
+            CacheCategory::Ccache => "ccache",
+            CacheCategory::Checkpoints => "checkpoints",
+        }
+    }
+}
+
+/// Size limits per category in bytes; a missing category is unlimited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheQuotas {
+    pub limits: BTreeMap<CacheCategory, u64>,
+}
+
+impl Default for CacheQuotas {
+    fn default() -> Self {
+        CacheQuotas {
+            limits: BTreeMap::from([
+                (CacheCategory::Sources, 20 * GIB),
+                (CacheCategory::Patches, GIB),
+                (CacheCategory::Artifacts, 20 * GIB),
+                (CacheCategory::Ccache, 10 * GIB),
+                (CacheCategory::Checkpoints, 10 * GIB),
+            ]),
+        }
+    }
+}
+
+impl CacheQuotas {
+    /// Limit of a category, None when unlimited
+    pub fn limit(&self, category: CacheCategory) -> Option<u64> {
+        self.limits.get(&category).copied()
+    }
+
+    /// Environment for builds so ccache enforces its quota with its own LRU cleanup
+    pub fn ccache_env(&self, index: &CacheIndex) -> Result<Vec<(String, String)>> {
+        let mut env = vec![(
+            String::from("CCACHE_DIR"),
+            index
+                .category_dir(CacheCategory::Ccache)?
+                .to_string_lossy()
+                .into_owned(),
+        )];
+        if let Some(limit) = self.limit(CacheCategory::Ccache) {
+            env.push((String::from("CCACHE_MAXSIZE"), format!("{}M", limit >> 20)));
+        }
+        Ok(env)
+    }
+}
+
+/// Disk usage of one category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryUsage {
+    pub category: CacheCategory,
+    pub bytes: u64,
+    pub files: usize,
+    pub quota: Option<u64>,
+}
+
+/// Files removed by eviction or cleaning
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvictionReport {
+    pub removed: Vec<PathBuf>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Outcome of the detached signature check done when the file was fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    Unchecked,
+    /// No signature is published for this file
+    Unsigned,
+    Valid {
+        signer: String,
+    },
+    Invalid,
+}
+
+/// Integrity record of one cached file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Path relative to the cache directory
+    pub path: PathBuf,
+    pub category: CacheCategory,
+    pub size: u64,
+    pub sha256: String,
+    pub signature: SignatureStatus,
+    pub fetched_at: u64,
+    /// Last time a build used the file, drives LRU eviction
+    #[serde(default)]
+    pub last_used: u64,
+}
+
+/// Why `verify_cache` discarded an entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheProblem {
+    Missing,
+    SizeMismatch { expected: u64, actual: u64 },
+    ChecksumMismatch,
+    BadSignature,
+}
+
+/// Result of re-validating the cache
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub pruned: Vec<(PathBuf, CacheProblem)>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Checksum database for everything under the cache directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheIndex {
+    root: PathBuf,
+    entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+impl CacheIndex {
+    /// Opens the index of the user's cache, starting empty when none exists
+    pub fn open(paths: &Paths) -> Result<Self> {
+        let root = Paths::ensure(paths.cache_dir())?;
+        let index_path = root.join(INDEX_FILE);
+        let entries = if index_path.exists() {
+            let text = fs::read_to_string(&index_path)
+                .with_context(|| format!("reading {}", index_path.display()))?;
+            let entries: Vec<CacheEntry> = serde_json::from_str(&text)
+                .with_context(|| format!("parsing {}", index_path.display()))?;
+            entries
+                .into_iter()
+                .map(|entry| (entry.path.clone(), entry))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        Ok(CacheIndex { root, entries })
+    }
+
+    /// Directory a category's files are downloaded into
+    pub fn category_dir(&self, category: CacheCategory) -> Result<PathBuf> {
+        Paths::ensure(self.root.join(category.dir_name()))
+    }
+
+    /// Hashes a freshly downloaded file and records it
+    pub fn record(
+        &mut self,
+        file: &Path,
+        category: CacheCategory,
+        signature: SignatureStatus,
+    ) -> Result<&CacheEntry> {
+        let path = self.relative(file)?;
+        let size = fs::metadata(file)
+            .with_context(|| format!("reading metadata of {}", file.display()))?
+            .len();
+        let now = unix_now();
+        let entry = CacheEntry {
+            path: path.clone(),
+            category,
+            size,
+            sha256: sha256_file(file)?,
+            signature,
+            fetched_at: now,
+            last_used: now,
+        };
+        self.entries.insert(path.clone(), entry);
+        self.save()?;
+        Ok(&self.entries[&path])
+    }
+
+    /// Record of a cached file, if it is tracked
+    pub fn get(&self, file: &Path) -> Option<&CacheEntry> {
+        let path = self.relative(file).ok()?;
+        self.entries.get(&path)
+    }
+
+    /// Absolute location of a tracked file
+    pub fn file_path(&self, entry: &CacheEntry) -> PathBuf {
+        self.root.join(&entry.path)
+    }
+
+    /// All tracked files
+    pub fn entries(&self) -> impl Iterator<Item = &CacheEntry> {
+        self.entries.values()
+    }
+
+    /// Checks one cached file against its record before it is used
+    pub fn check(&self, entry: &CacheEntry) -> Result<Option<CacheProblem>> {
+        let file = self.root.join(&entry.path);
+        let Ok(metadata) = fs::metadata(&file) else {
+            return Ok(Some(CacheProblem::Missing));
+        };
+        if metadata.len() != entry.size {
+            return Ok(Some(CacheProblem::SizeMismatch {
+                expected: entry.size,
+                actual: metadata.len(),
+            }));
+        }
+        if entry.signature == SignatureStatus::Invalid {
+            return Ok(Some(CacheProblem::BadSignature));
+        }
+        if sha256_file(&file)? != entry.sha256 {
+            return Ok(Some(CacheProblem::ChecksumMismatch));
+        }
+        Ok(None)
+    }
+
+    /// Re-validates every entry, deleting corrupt files and dropping their records
+    pub fn verify_cache(&mut self) -> Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+        let entries: Vec<CacheEntry> = self.entries.values().cloned().collect();
+        for entry in entries {
+            report.checked += 1;
+            let Some(problem) = self.check(&entry)? else {
+                continue;
+            };
+            let file = self.root.join(&entry.path);
+            if let Ok(metadata) = fs::metadata(&file) {
+                fs::remove_file(&file).with_context(|| format!("removing {}", file.display()))?;
+                report.reclaimed_bytes += metadata.len();
+            }
+            self.entries.remove(&entry.path);
+            report.pruned.push((entry.path, problem));
+        }
+        self.save()?;
+        Ok(report)
+    }
+
+    /// Records the outcome of checking a cached file's detached signature
+    pub fn set_signature(&mut self, file: &Path, signature: SignatureStatus) -> Result<()> {
+        let path = self.relative(file)?;
+        let entry = self
+            .entries
+            .get_mut(&path)
+            .with_context(|| format!("{} is not in the cache", file.display()))?;
+        entry.signature = signature;
+        self.save()
+    }
+
+    /// Marks a cached file as used so eviction keeps it longer
+    pub fn touch(&mut self, file: &Path) -> Result<()> {
+        let path = self.relative(file)?;
+        if let Some(entry) = self.entries.get_mut(&path) {
+            entry.last_used = unix_now();
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Bytes and file counts per category, including files the index does not track
+    pub fn usage(&self, quotas: &CacheQuotas) -> Result<Vec<CategoryUsage>> {
+        let mut usage = Vec::new();
+        for category in CacheCategory::ALL {
+            let (bytes, files) = dir_usage(&self.root.join(category.dir_name()))?;
+            usage.push(CategoryUsage {
+                category,
+                bytes,
+                files,
+                quota: quotas.limit(category),
+            });
+        }
+        Ok(usage)
+    }
+
+    /// Evicts least recently used files until each tracked category fits its quota
+    /// Files in `keep` are never evicted, even when that leaves a category over quota
+    pub fn enforce_quotas(
+        &mut self,
+        quotas: &CacheQuotas,
+        keep: &[PathBuf],
+    ) -> Result<EvictionReport> {
+        let keep = self.keep_set(keep);
+        let mut report = EvictionReport::default();
+        for category in CacheCategory::ALL {
+            // ccache trims itself to CCACHE_MAXSIZE
+            if category == CacheCategory::Ccache {
+                continue;
+            }
+            let Some(limit) = quotas.limit(category) else {
+                continue;
+            };
+            let mut candidates: Vec<CacheEntry> = self
+                .entries
+                .values()
+                .filter(|entry| entry.category == category)
+                .cloned()
+                .collect();
+            let mut total: u64 = candidates.iter().map(|entry| entry.size).sum();
+            candidates.sort_by_key(|entry| entry.last_used);
+            for entry in candidates {
+                if total <= limit {
+                    break;
+                }
+                if keep.contains(&entry.path) {
+                    continue;
+                }
+                self.remove_entry(&entry.path, &mut report)?;
+                total = total.saturating_sub(entry.size);
+            }
+        }
+        self.save()?;
+        Ok(report)
+    }
+
+    /// Deletes every cached file except those in `keep`, typically the current plan's
+    /// sources, patches and artifacts; the compiler cache is emptied as well
+    pub fn clean_except(&mut self, keep: &[PathBuf]) -> Result<EvictionReport> {
+        let keep = self.keep_set(keep);
+        let mut report = EvictionReport::default();
+        for category in CacheCategory::ALL {
+            let dir = self.root.join(category.dir_name());
+            if !dir.exists() {
+                continue;
+            }
+            for entry in fs::read_dir(&dir).with_context(|| format!("listing {}", dir.display()))? {
+                let path = entry?.path();
+                let relative = self.relative(&path)?;
+                if keep.contains(&relative) {
+                    continue;
+                }
+                let (bytes, _) = dir_usage(&path)?;
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)
+                } else {
+                    fs::remove_file(&path)
+                }
+                .with_context(|| format!("removing {}", path.display()))?;
+                self.entries.remove(&relative);
+                report.reclaimed_bytes += bytes;
+                report.removed.push(relative);
+            }
+        }
+        self.entries.retain(|path, _| keep.contains(path));
+        self.save()?;
+        Ok(report)
+    }
+
+    /// Stops tracking a file, e.g. after it was deleted
+    pub fn forget(&mut self, file: &Path) -> Result<()> {
+        let path = self.relative(file)?;
+        if self.entries.remove(&path).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn keep_set(&self, keep: &[PathBuf]) -> Vec<PathBuf> {
+        keep.iter()
+            .filter_map(|path| self.relative(path).ok())
+            .collect()
+    }
+
+    fn remove_entry(&mut self, path: &Path, report: &mut EvictionReport) -> Result<()> {
+        let file = self.root.join(path);
+        if let Ok(metadata) = fs::metadata(&file) {
+            fs::remove_file(&file).with_context(|| format!("removing {}", file.display()))?;
+            report.reclaimed_bytes += metadata.len();
+        }
+        self.entries.remove(path);
+        report.removed.push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn relative(&self, file: &Path) -> Result<PathBuf> {
+        let relative = if file.is_relative() {
+            file.to_path_buf()
+        } else {
+            file.strip_prefix(&self.root)
+                .map(Path::to_path_buf)
+                .with_context(|| format!("{} is outside the cache", file.display()))?
+        };
+        // `..` would let an entry point anywhere once joined back onto the root
+        if relative.components().any(|component| !matches!(component, Component::Normal(_) | Component::CurDir)) {
+            bail!("{} is outside the cache", file.display());
+        }
+        Ok(relative)
+    }
+
+    fn save(&self) -> Result<()> {
+        let entries: Vec<&CacheEntry> = self.entries.values().collect();
+        let text = serde_json::to_string_pretty(&entries)?;
+        state_file::write(&self.root.join(INDEX_FILE), text.as_bytes())
+    }
+}
+
+/// Total size and file count below a path, without following symlinks
+fn dir_usage(path: &Path) -> Result<(u64, usize)> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok((0, 0));
+    };
+    if !metadata.is_dir() {
+        return Ok((metadata.len(), 1));
+    }
+    let mut total = (0, 0);
+    for entry in fs::read_dir(path).with_context(|| format!("listing {}", path.display()))? {
+        let (bytes, files) = dir_usage(&entry?.path())?;
+        total.0 += bytes;
+        total.1 += files;
+    }
+    Ok(total)
+}
+
+/// Streams a file through SHA-256, returning the lowercase hex digest
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 1 << 20];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("reading {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
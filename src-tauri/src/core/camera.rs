@@ -0,0 +1,225 @@
+// src-tauri/src/core/camera.rs
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::hardware::Device;
+use super::requirements::MachineRequirements;
+use super::system::SystemFacade;
+
+/// USB interface class of video devices, `ic0E` in an interface modalias
+const VIDEO_CLASS: &str = "ic0E";
+
+/// Programs that film with a webcam; one being installed means a camera is expected
+/// even while none is plugged in
+const CAMERA_APPS: &[&str] = &[
+    "cheese",
+    "snapshot",
+    "guvcview",
+    "kamoso",
+    "webcamoid",
+    "obs",
+    "zoom",
+    "skypeforlinux",
+    "teams-for-linux",
+];
+
+/// How a camera reaches V4L2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraKind {
+    /// USB Video Class webcam, the kind almost every external and built-in webcam is
+    Uvc,
+    /// Older USB webcam with a vendor protocol, driven by a gspca subdriver
+    Gspca,
+    /// MIPI sensor behind the Intel IPU6 found in recent laptops
+    Ipu6,
+}
+
+/// A camera found on one of the scanned buses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Camera {
+    /// sysfs name such as `1-5:1.0`
+    pub device: String,
+    pub kind: CameraKind,
+}
+
+impl Camera {
+    fn of(device: &Device) -> Option<Self> {
+        let driver = device.driver.as_deref().unwrap_or_default();
+        let kind = if device.bus == "usb"
+            && device
+                .modalias
+                .as_deref()
+                .is_some_and(|modalias| modalias.contains(VIDEO_CLASS))
+        {
+            CameraKind::Uvc
+        } else if device.bus == "usb" && driver.starts_with("gspca") {
+            CameraKind::Gspca
+        } else if device.bus == "pci" && driver.replace('-', "_") == "intel_ipu6" {
+            CameraKind::Ipu6
+        } else {
+            return None;
+        };
+        Some(Camera {
+            device: device.name.clone(),
+            kind,
+        })
+    }
+}
+
+/// Cameras of the machine and the programs that use them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CameraSetup {
+    pub cameras: Vec<Camera>,
+    /// Installed camera programs from [`CAMERA_APPS`]
+    pub apps: Vec<String>,
+}
+
+impl CameraSetup {
+    /// Detects cameras among `devices` and installed camera programs, None when there
+    /// are neither
+    pub fn detect(system: &dyn SystemFacade, devices: &[Device]) -> Option<Self> {
+        let setup = CameraSetup {
+            cameras: devices.iter().filter_map(Camera::of).collect(),
+            apps: CAMERA_APPS
+                .iter()
+                .filter(|app| system.has_program(app))
+                .map(|app| app.to_string())
+                .collect(),
+        };
+        if setup.cameras.is_empty() && setup.apps.is_empty() {
+            return None;
+        }
+        Some(setup)
+    }
+
+    fn has(&self, kind: CameraKind) -> bool {
+        self.cameras.iter().any(|camera| camera.kind == kind)
+    }
+
+    /// What keeps the cameras in use, for the removal review: devices, then programs
+    pub fn users(&self) -> Vec<String> {
+        self.cameras
+            .iter()
+            .map(|camera| camera.device.clone())
+            .chain(self.apps.iter().cloned())
+            .collect()
+    }
+}
+
+impl MachineRequirements for CameraSetup {
+    fn name(&self) -> &'static str {
+        "camera"
+    }
+
+    /// Options the cameras need, V4L2 and the media controller first
+    /// With only apps installed, the UVC driver is kept for the webcam they expect
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let mut required = vec![
+            ConfigSuggestion::new(
+                "CONFIG_MEDIA_SUPPORT",
+                ConfigValue::module(),
+                "the media subsystem every camera driver lives in",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_MEDIA_CAMERA_SUPPORT",
+                ConfigValue::yes(),
+                "offers the camera drivers",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_VIDEO_DEV",
+                ConfigValue::module(),
+                "V4L2, the /dev/video* interface camera programs capture through",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_MEDIA_CONTROLLER",
+                ConfigValue::yes(),
+                "libcamera and PipeWire find camera pipelines through the media controller",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_VIDEO_V4L2_SUBDEV_API",
+                ConfigValue::yes(),
+                "lets libcamera configure the sensor of a camera pipeline",
+            ),
+        ];
+        let uvc = self.has(CameraKind::Uvc) || self.cameras.is_empty();
+        if uvc || self.has(CameraKind::Gspca) {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_MEDIA_USB_SUPPORT",
+                ConfigValue::yes(),
+                "offers the USB camera drivers",
+            ));
+        }
+        if uvc {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_USB_VIDEO_CLASS",
+                ConfigValue::module(),
+                "uvcvideo, the driver of USB Video Class webcams",
+            ));
+        }
+        if self.has(CameraKind::Gspca) {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_USB_GSPCA",
+                ConfigValue::module(),
+                "the core of the drivers for older USB webcams",
+            ));
+        }
+        if self.has(CameraKind::Ipu6) {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_MEDIA_PCI_SUPPORT",
+                ConfigValue::yes(),
+                "offers the PCI camera drivers",
+            ));
+            required.push(ConfigSuggestion::new(
+                "CONFIG_VIDEO_INTEL_IPU6",
+                ConfigValue::module(),
+                "drives the Intel IPU6 image processor the laptop camera is behind",
+            ));
+            required.push(ConfigSuggestion::new(
+                "CONFIG_IPU_BRIDGE",
+                ConfigValue::module(),
+                "connects the IPU6 to the sensors the ACPI tables describe",
+            ));
+        }
+        required
+    }
+
+    /// Warns when the plan leaves out what the cameras need
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        let mut advisory = Advisory::new(
+            Severity::Warning,
+            "camera-missing-config",
+            &[
+                ("users", self.users().join(", ")),
+                ("symbols", symbols.join(", ")),
+            ],
+        );
+        for requirement in missing {
+            advisory = advisory.suggest(requirement);
+        }
+        vec![advisory]
+    }
+
+    /// Refuses a config that drops the cameras without the user having asked for that
+    fn enforce(&self, config: &KernelConfig) -> Result<()> {
+        let missing = self.missing(config);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        bail!(
+            "the cameras of this machine ({}) need {}; remove the camera group to build without them",
+            self.users().join(", "),
+            symbols.join(", ")
+        );
+    }
+}
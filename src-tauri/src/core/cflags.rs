@@ -0,0 +1,155 @@
+// src-tauri/src/core/cflags.rs
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+
+/// A flag, or a family of flags when the pattern ends in `*`, and what is known about it
+struct KnownFlag {
+    pattern: &'static str,
+    /// None for flags vetted as safe
+    severity: Option<Severity>,
+    /// Catalog entry explaining the problem
+    key: &'static str,
+}
+
+const fn safe(pattern: &'static str) -> KnownFlag {
+    KnownFlag {
+        pattern,
+        severity: None,
+        key: "",
+    }
+}
+
+const fn flagged(pattern: &'static str, severity: Severity, key: &'static str) -> KnownFlag {
+    KnownFlag {
+        pattern,
+        severity: Some(severity),
+        key,
+    }
+}
+
+/// First match wins, so specific entries go before the families that contain them
+const KNOWN_FLAGS: &[KnownFlag] = &[
+    safe("-O2"),
+    safe("-Os"),
+    safe("-pipe"),
+    safe("-falign-functions=*"),
+    safe("-falign-jumps=*"),
+    safe("-falign-loops=*"),
+    safe("-D*"),
+    safe("-U*"),
+    flagged("-O0", Severity::Blocker, "cflag-o0"),
+    flagged("-Ofast", Severity::Blocker, "cflag-fast-math"),
+    flagged("-ffast-math", Severity::Blocker, "cflag-fast-math"),
+    flagged("-O3", Severity::Warning, "cflag-o3"),
+    flagged("-mavx*", Severity::Blocker, "cflag-simd"),
+    flagged("-msse*", Severity::Blocker, "cflag-simd"),
+    flagged("-mmmx", Severity::Blocker, "cflag-simd"),
+    flagged("-mfpmath=*", Severity::Blocker, "cflag-simd"),
+    flagged("-flto*", Severity::Blocker, "cflag-lto"),
+    flagged("-fPIC", Severity::Blocker, "cflag-pic"),
+    flagged("-fpic", Severity::Blocker, "cflag-pic"),
+    flagged("-fPIE", Severity::Blocker, "cflag-pic"),
+    flagged("-fpie", Severity::Blocker, "cflag-pic"),
+    flagged("-march=*", Severity::Warning, "cflag-march"),
+    flagged("-mtune=*", Severity::Warning, "cflag-march"),
+    flagged("-g*", Severity::Warning, "cflag-debug"),
+    flagged("-Werror*", Severity::Warning, "cflag-werror"),
+    flagged(
+        "-fstack-protector*",
+        Severity::Warning,
+        "cflag-stack-protector",
+    ),
+    flagged(
+        "-fno-stack-protector",
+        Severity::Warning,
+        "cflag-stack-protector",
+    ),
+];
+
+fn lookup(flag: &str) -> Option<&'static KnownFlag> {
+    KNOWN_FLAGS
+        .iter()
+        .find(|known| match known.pattern.strip_suffix('*') {
+            Some(prefix) => flag.starts_with(prefix),
+            None => flag == known.pattern,
+        })
+}
+
+/// Extra flags appended to the kernel's own through KCFLAGS and KCPPFLAGS
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerFlags {
+    pub kcflags: Vec<String>,
+    pub kcppflags: Vec<String>,
+}
+
+impl CompilerFlags {
+    /// Splits space-separated flag lists; anything that is not a flag is refused
+    pub fn parse(kcflags: &str, kcppflags: &str) -> Result<Self> {
+        let split = |text: &str| -> Result<Vec<String>> {
+            text.split_whitespace()
+                .map(|flag| {
+                    if !flag.starts_with('-') || flag.len() < 2 {
+                        bail!("{} is not a compiler flag", flag);
+                    }
+                    Ok(flag.to_string())
+                })
+                .collect()
+        };
+        Ok(CompilerFlags {
+            kcflags: split(kcflags)?,
+            kcppflags: split(kcppflags)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kcflags.is_empty() && self.kcppflags.is_empty()
+    }
+
+    fn all(&self) -> impl Iterator<Item = &String> {
+        self.kcflags.iter().chain(self.kcppflags.iter())
+    }
+
+    /// A finding per flag known to cause trouble, and a note for flags nobody vetted
+    pub fn advisories(&self) -> Vec<Advisory> {
+        self.all()
+            .filter_map(|flag| match lookup(flag) {
+                Some(KnownFlag { severity: None, .. }) => None,
+                Some(KnownFlag {
+                    severity: Some(severity),
+                    key,
+                    ..
+                }) => Some(Advisory::new(*severity, key, &[("flag", flag.clone())])),
+                None => Some(Advisory::new(
+                    Severity::Info,
+                    "cflag-unknown",
+                    &[("flag", flag.clone())],
+                )),
+            })
+            .collect()
+    }
+
+    /// Flags known to break the kernel
+    pub fn blocked(&self) -> Vec<&str> {
+        self.all()
+            .filter(|flag| {
+                lookup(flag).is_some_and(|known| known.severity == Some(Severity::Blocker))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Variables for make, which appends them after the kernel's own flags
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if !self.kcflags.is_empty() {
+            env.push((String::from("KCFLAGS"), self.kcflags.join(" ")));
+        }
+        if !self.kcppflags.is_empty() {
+            env.push((String::from("KCPPFLAGS"), self.kcppflags.join(" ")));
+        }
+        env
+    }
+}
@@ -0,0 +1,181 @@
+// src-tauri/src/core/cgroup.rs
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::memory_guard::MemoryPressure;
+use super::system::{CommandSpec, SystemFacade};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Scheduling class handed to ionice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoClass {
+    /// Shares the disk at a priority level from 0 (highest) to 7
+    BestEffort { level: u8 },
+    /// Only gets the disk when nothing else wants it
+    Idle,
+}
+
+/// Share of the machine a build may take, so the desktop stays responsive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// cgroup CPU weight from 1 to 10000; 100 is what every other unit gets
+    pub cpu_weight: Option<u32>,
+    /// Hard memory limit; the kernel reclaims and then OOM-kills inside the build only
+    pub memory_max_mb: Option<u64>,
+    pub io_class: Option<IoClass>,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        ResourceLimits {
+            cpu_weight: Some(20),
+            memory_max_mb: None,
+            io_class: Some(IoClass::BestEffort { level: 7 }),
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// Whether builds can be confined: a unified cgroup hierarchy and systemd-run
+    pub fn is_supported(system: &dyn SystemFacade) -> bool {
+        system.exists(&Path::new(CGROUP_ROOT).join("cgroup.controllers"))
+            && system.has_program("systemd-run")
+    }
+
+    /// Runs `spec` in a transient user scope named `unit` carrying the limits
+    /// systemd-run execs the command itself, so the child stays make's process
+    pub fn wrap(&self, spec: CommandSpec, unit: &str) -> CommandSpec {
+        let mut args = vec![
+            String::from("--user"),
+            String::from("--scope"),
+            String::from("--quiet"),
+            String::from("--collect"),
+            format!("--unit={}", unit),
+        ];
+        if let Some(weight) = self.cpu_weight {
+            args.push(format!("--property=CPUWeight={}", weight.clamp(1, 10000)));
+        }
+        if let Some(megabytes) = self.memory_max_mb {
+            args.push(format!("--property=MemoryMax={}M", megabytes));
+            // Without a swap cap the build swaps the desktop out instead of hitting the limit
+            args.push(String::from("--property=MemorySwapMax=0"));
+        }
+        args.push(String::from("--"));
+        // User managers rarely get the io controller delegated, so IO priority goes
+        // through ionice rather than IOWeight
+        match self.io_class {
+            Some(IoClass::BestEffort { level }) => args.extend([
+                String::from("ionice"),
+                String::from("-c2"),
+                format!("-n{}", level.min(7)),
+            ]),
+            Some(IoClass::Idle) => args.extend([String::from("ionice"), String::from("-c3")]),
+            None => {}
+        }
+        args.push(spec.program);
+        args.extend(spec.args);
+        CommandSpec {
+            program: String::from("systemd-run"),
+            args,
+            ..spec
+        }
+    }
+}
+
+/// Resource use of a build scope at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScopeUsage {
+    pub memory_bytes: u64,
+    /// Highest memory use so far, on kernels reporting memory.peak
+    pub memory_peak_bytes: Option<u64>,
+    /// CPU time of every process in the scope since it started
+    pub cpu_usec: u64,
+    pub tasks: u64,
+}
+
+/// A transient scope a build runs in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildScope {
+    pub unit: String,
+    /// Directory under /sys/fs/cgroup, found once the scope exists
+    pub cgroup: Option<PathBuf>,
+}
+
+impl BuildScope {
+    /// Unique unit name for one make invocation; restarts and later phases get a scope of their own
+    pub fn new(attempt: u32) -> Self {
+        BuildScope {
+            unit: format!("kernelforge-build-{}-{}.scope", std::process::id(), attempt),
+            cgroup: None,
+        }
+    }
+
+    /// Directory of the scope's cgroup, looked up on first use
+    pub fn cgroup_dir(&mut self, system: &dyn SystemFacade) -> Option<&Path> {
+        if self.cgroup.is_none() {
+            let output = system
+                .run(
+                    "systemctl",
+                    &[
+                        "--user",
+                        "show",
+                        "--property=ControlGroup",
+                        "--value",
+                        &self.unit,
+                    ],
+                )
+                .ok()
+                .filter(|output| output.success())?;
+            let path = output.stdout.trim().trim_start_matches('/');
+            if path.is_empty() {
+                return None;
+            }
+            self.cgroup = Some(Path::new(CGROUP_ROOT).join(path));
+        }
+        self.cgroup.as_deref()
+    }
+
+    /// Current usage, looking the cgroup up on first use
+    pub fn usage(&mut self, system: &dyn SystemFacade) -> Option<ScopeUsage> {
+        let dir = self.cgroup_dir(system)?.to_path_buf();
+        let number = |file: &str| {
+            system
+                .read_trimmed(&dir.join(file))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let cpu_usec = system
+            .read_to_string(&dir.join("cpu.stat"))
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("usage_usec "))
+            .and_then(|value| value.trim().parse().ok())?;
+        Some(ScopeUsage {
+            memory_bytes: number("memory.current")?,
+            memory_peak_bytes: number("memory.peak"),
+            cpu_usec,
+            tasks: number("pids.current").unwrap_or(0),
+        })
+    }
+
+    /// Memory pressure of the build alone, from the scope's own PSI file
+    /// Available memory is what is left below MemoryMax, or the machine's when unlimited
+    pub fn memory_pressure(&mut self, system: &dyn SystemFacade) -> Option<MemoryPressure> {
+        let dir = self.cgroup_dir(system)?.to_path_buf();
+        let text = system.read_to_string(&dir.join("memory.pressure")).ok()?;
+        let limit_kb = system
+            .read_trimmed(&dir.join("memory.max"))
+            .and_then(|max| max.parse::<u64>().ok())
+            .and_then(|max| {
+                let current = system
+                    .read_trimmed(&dir.join("memory.current"))?
+                    .parse::<u64>()
+                    .ok()?;
+                Some(max.saturating_sub(current) / 1024)
+            });
+        let available_kb = limit_kb.unwrap_or_else(|| MemoryPressure::available_kb(system));
+        MemoryPressure::parse(&text, available_kb)
+    }
+}
@@ -0,0 +1,256 @@
+// src-tauri/src/core/checkpoint.rs
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::{CacheCategory, CacheIndex, SignatureStatus};
+use super::clock::unix_now;
+use super::state_file;
+use super::system::SystemFacade;
+
+/// What an earlier build left in the tree; a checkpoint is restored to build another config,
+/// so objects from this one would only take space and confuse make's timestamps
+const BUILD_PRODUCTS: &[&str] = &[
+    "*.o",
+    "*.ko",
+    "*.a",
+    "*.mod",
+    "*.mod.c",
+    "*.cmd",
+    ".*.d",
+    "*.dtb",
+    "*.dtbo",
+    "*.order",
+    "*.symvers",
+    ".tmp_*",
+    "vmlinux",
+    "vmlinux.unstripped",
+    "System.map",
+    "bzImage",
+    "Image",
+    "Image.gz",
+    "zImage",
+    "include/generated",
+    "include/config",
+];
+
+/// How a checkpoint archive is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Fast to pack and unpack; used when zstd is installed
+    Zstd,
+    Gzip,
+}
+
+impl Compression {
+    /// zstd when available, gzip otherwise since tar always handles it
+    pub fn detect(system: &dyn SystemFacade) -> Self {
+        if system.has_program("zstd") {
+            Compression::Zstd
+        } else {
+            Compression::Gzip
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Compression::Zstd => "tar.zst",
+            Compression::Gzip => "tar.gz",
+        }
+    }
+
+    fn tar_flag(self) -> &'static str {
+        match self {
+            Compression::Zstd => "--zstd",
+            Compression::Gzip => "--gzip",
+        }
+    }
+}
+
+/// What a prepared tree was made from; trees made from the same inputs are identical, so
+/// a checkpoint serves every plan that differs only in its config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointKey {
+    pub version: String,
+    /// Digest of the source tarball
+    pub source_sha256: String,
+    /// Digests of the applied patches, in the order they were applied
+    pub patches: Vec<String>,
+}
+
+impl CheckpointKey {
+    /// Stable identifier, used as the archive's file name
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.version.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.source_sha256.as_bytes());
+        for patch in &self.patches {
+            hasher.update([0]);
+            hasher.update(patch.as_bytes());
+        }
+        let digest = hex::encode(hasher.finalize());
+        format!("{}-{}", self.version, &digest[..16])
+    }
+}
+
+/// An archived prepared tree in the cache
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub key: CheckpointKey,
+    /// Archive location inside the cache
+    pub archive: PathBuf,
+    pub compression: Compression,
+    pub size: u64,
+    pub created_at: u64,
+}
+
+impl Checkpoint {
+    /// Archives a fetched and patched tree under `key`, replacing an older checkpoint of it
+    /// The archive holds sources and the config; objects of a build already run are left out
+    pub fn create(
+        system: &dyn SystemFacade,
+        index: &mut CacheIndex,
+        key: CheckpointKey,
+        source_dir: &Path,
+    ) -> Result<Self> {
+        if !source_dir.join("Makefile").exists() {
+            bail!("{} is not a kernel source tree", source_dir.display());
+        }
+        let dir = index.category_dir(CacheCategory::Checkpoints)?;
+        let compression = Compression::detect(system);
+        let archive = dir.join(format!("{}.{}", key.id(), compression.extension()));
+        if let Some(previous) = Checkpoint::find(index, &key)? {
+            if previous.archive != archive {
+                let _ = fs::remove_file(&previous.archive);
+                index.forget(&previous.archive)?;
+            }
+        }
+        // Named per write, so checkpoints of the same key taken at once do not share a file
+        let partial = tempfile::Builder::new()
+            .prefix(&format!("{}.", key.id()))
+            .suffix(".partial")
+            .tempfile_in(&dir)?;
+        let mut args = vec![
+            String::from("--create"),
+            String::from(compression.tar_flag()),
+            String::from("--file"),
+            partial.path().to_string_lossy().to_string(),
+            // Version control data is never needed to build
+            String::from("--exclude-vcs"),
+        ];
+        args.extend(
+            BUILD_PRODUCTS
+                .iter()
+                .map(|pattern| format!("--exclude={}", pattern)),
+        );
+        args.extend([
+            String::from("--directory"),
+            source_dir.to_string_lossy().to_string(),
+            String::from("."),
+        ]);
+        run_tar(system, &args.iter().map(String::as_str).collect::<Vec<_>>())?;
+        partial
+            .persist(&archive)
+            .with_context(|| format!("moving {} into place", archive.display()))?;
+        let size = index
+            .record(
+                &archive,
+                CacheCategory::Checkpoints,
+                SignatureStatus::Unsigned,
+            )?
+            .size;
+
+        let checkpoint = Checkpoint {
+            key,
+            archive,
+            compression,
+            size,
+            created_at: unix_now(),
+        };
+        let text = serde_json::to_string_pretty(&checkpoint)?;
+        state_file::write(&manifest_path(&dir, &checkpoint.key), text.as_bytes())?;
+        Ok(checkpoint)
+    }
+
+    /// The checkpoint for `key`, None when there is none or its archive was evicted
+    pub fn find(index: &CacheIndex, key: &CheckpointKey) -> Result<Option<Self>> {
+        let dir = index.category_dir(CacheCategory::Checkpoints)?;
+        let path = manifest_path(&dir, key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let checkpoint: Checkpoint =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        if checkpoint.key != *key || index.get(&checkpoint.archive).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Unpacks the tree into `destination`, which must not exist yet
+    /// The archive is checked against its recorded digest first, like any cached file
+    pub fn restore(
+        &self,
+        system: &dyn SystemFacade,
+        index: &mut CacheIndex,
+        destination: &Path,
+    ) -> Result<()> {
+        let entry = index
+            .get(&self.archive)
+            .with_context(|| format!("{} is not in the cache", self.archive.display()))?;
+        if let Some(problem) = index.check(entry)? {
+            bail!(
+                "checkpoint {} cannot be restored: {:?}",
+                self.key.id(),
+                problem
+            );
+        }
+        if destination.exists() {
+            bail!("{} already exists", destination.display());
+        }
+        let parent = destination
+            .parent()
+            .with_context(|| format!("{} has no parent directory", destination.display()))?;
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        let partial = tempfile::Builder::new()
+            .prefix(".kernelforge-restore-")
+            .tempdir_in(parent)?;
+        // Temporary directories are private; the tree is not
+        fs::set_permissions(partial.path(), fs::Permissions::from_mode(0o755))?;
+        run_tar(
+            system,
+            &[
+                "--extract",
+                self.compression.tar_flag(),
+                "--file",
+                &self.archive.to_string_lossy(),
+                "--directory",
+                &partial.path().to_string_lossy(),
+            ],
+        )?;
+        fs::rename(partial.path(), destination)
+            .with_context(|| format!("moving {} into place", destination.display()))?;
+        index.touch(&self.archive)
+    }
+}
+
+/// Manifest next to the archive, found by key without listing the directory
+fn manifest_path(dir: &Path, key: &CheckpointKey) -> PathBuf {
+    dir.join(format!("{}.json", key.id()))
+}
+
+fn run_tar(system: &dyn SystemFacade, args: &[&str]) -> Result<()> {
+    let output = system.run("tar", args).context("running tar")?;
+    if !output.success() {
+        bail!("tar failed ({}): {}", output.status, output.stderr.trim());
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+// src-tauri/src/core/cmdline.rs
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::bootloader::Bootloader;
+use super::system::SystemFacade;
+
+const PROC_CMDLINE: &str = "/proc/cmdline";
+const GRUB_DEFAULT: &str = "/etc/default/grub";
+const KERNEL_CMDLINE: &str = "/etc/kernel/cmdline";
+const KERNELSTUB_CONFIG: &str = "/etc/kernelstub/configuration";
+
+/// Parameters the bootloader adds for the kernel it booted, never part of a baseline
+const BOOTLOADER_PARAMS: &[&str] = &["BOOT_IMAGE", "initrd"];
+
+/// Parameters that find and unlock the root filesystem; KernelForge never changes them,
+/// a wrong value leaves the machine unbootable
+const PROTECTED_PARAMS: &[&str] = &[
+    "root",
+    "rootflags",
+    "rootfstype",
+    "ro",
+    "rw",
+    "cryptdevice",
+    "cryptkey",
+    "resume",
+    "resume_offset",
+    "init",
+    "systemd.volatile",
+    "zfs",
+];
+
+/// Prefixes of protected parameter families, e.g. `rd.luks.uuid`
+const PROTECTED_PREFIXES: &[&str] = &["rd.luks.", "luks.", "rd.lvm.", "rd.md.", "rd.zfs."];
+
+/// Where a captured baseline came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CmdlineSource {
+    /// GRUB_CMDLINE_LINUX and GRUB_CMDLINE_LINUX_DEFAULT in /etc/default/grub
+    GrubDefault,
+    /// /etc/kernel/cmdline, read by kernel-install and ukify
+    KernelCmdline,
+    /// kernel_options of kernelstub's user configuration
+    Kernelstub,
+    /// The running kernel's command line, when the bootloader keeps none of its own
+    ProcCmdline,
+}
+
+/// What adding a parameter does to the baseline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CmdlineChange {
+    Add {
+        param: String,
+    },
+    Replace {
+        from: String,
+        to: String,
+    },
+    /// Already on the command line with the wanted value
+    Unchanged {
+        param: String,
+    },
+    /// Would set or change a root or encryption parameter; the baseline is kept as it is,
+    /// `current` being None when the baseline does not set it
+    Protected {
+        current: Option<String>,
+        wanted: String,
+    },
+}
+
+/// Kernel command line for a new kernel: the user's existing parameters plus what
+/// KernelForge adds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CmdlinePlan {
+    pub source: CmdlineSource,
+    pub baseline: Vec<String>,
+    /// Parameters of the running kernel, to spot a bootloader config edited since boot
+    pub running: Vec<String>,
+    pub additions: Vec<String>,
+}
+
+impl CmdlinePlan {
+    /// Imports the current command line as the baseline, preferring the bootloader's
+    /// configuration over /proc/cmdline so edits not yet booted are kept
+    pub fn capture(system: &dyn SystemFacade, bootloader: &Bootloader) -> Result<Self> {
+        let running = system
+            .read_trimmed(Path::new(PROC_CMDLINE))
+            .map(|cmdline| without_bootloader_params(split(&cmdline)))
+            .unwrap_or_default();
+        let (source, baseline) = match configured(system, bootloader) {
+            Some((source, params)) => (source, without_bootloader_params(params)),
+            None => (CmdlineSource::ProcCmdline, running.clone()),
+        };
+        // GRUB adds root= itself, so only an empty running command line is suspicious
+        if source == CmdlineSource::ProcCmdline && baseline.is_empty() {
+            bail!("{} is empty or unreadable", PROC_CMDLINE);
+        }
+        Ok(CmdlinePlan {
+            source,
+            baseline,
+            running,
+            additions: Vec::new(),
+        })
+    }
+
+    /// Queues `param`, replacing an earlier addition with the same key
+    pub fn add(&mut self, param: &str) {
+        self.additions.retain(|added| key(added) != key(param));
+        self.additions.push(param.to_string());
+    }
+
+    /// Baseline parameters missing from, or differing in, the running command line
+    pub fn drift(&self) -> Vec<String> {
+        self.baseline
+            .iter()
+            .filter(|param| !self.running.contains(param))
+            .cloned()
+            .collect()
+    }
+
+    /// How each addition changes the baseline
+    pub fn diff(&self) -> Vec<CmdlineChange> {
+        self.additions
+            .iter()
+            .map(|wanted| {
+                let current = self.baseline.iter().find(|param| key(param) == key(wanted));
+                match current {
+                    Some(current) if current == wanted => CmdlineChange::Unchanged {
+                        param: wanted.clone(),
+                    },
+                    _ if is_protected(wanted) => CmdlineChange::Protected {
+                        current: current.cloned(),
+                        wanted: wanted.clone(),
+                    },
+                    None => CmdlineChange::Add {
+                        param: wanted.clone(),
+                    },
+                    Some(current) => CmdlineChange::Replace {
+                        from: current.clone(),
+                        to: wanted.clone(),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Final command line: the baseline in its order with replacements applied in place,
+    /// then new parameters; protected parameters keep their baseline value
+    pub fn render(&self) -> String {
+        let mut params = self.baseline.clone();
+        for change in self.diff() {
+            match change {
+                CmdlineChange::Add { param } => params.push(param),
+                CmdlineChange::Replace { from, to } => {
+                    if let Some(slot) = params.iter_mut().find(|param| **param == from) {
+                        *slot = to;
+                    }
+                }
+                CmdlineChange::Unchanged { .. } | CmdlineChange::Protected { .. } => {}
+            }
+        }
+        params
+            .iter()
+            .map(|param| quote(param))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Whether KernelForge must leave `param` as the user set it
+pub fn is_protected(param: &str) -> bool {
+    let key = key(param);
+    PROTECTED_PARAMS.contains(&key)
+        || PROTECTED_PREFIXES
+            .iter()
+            .any(|prefix| key.starts_with(prefix))
+}
+
+/// The bootloader's own record of the command line, if it keeps one
+fn configured(
+    system: &dyn SystemFacade,
+    bootloader: &Bootloader,
+) -> Option<(CmdlineSource, Vec<String>)> {
+    match bootloader {
+        Bootloader::Grub { .. } => {
+            let text = system.read_to_string(Path::new(GRUB_DEFAULT)).ok()?;
+            let mut params = Vec::new();
+            for variable in ["GRUB_CMDLINE_LINUX", "GRUB_CMDLINE_LINUX_DEFAULT"] {
+                if let Some(value) = shell_variable(&text, variable) {
+                    params.extend(split(&value));
+                }
+            }
+            Some((CmdlineSource::GrubDefault, params))
+        }
+        Bootloader::SystemdBoot { .. } | Bootloader::Efibootmgr { .. } => system
+            .read_trimmed(Path::new(KERNEL_CMDLINE))
+            .map(|cmdline| (CmdlineSource::KernelCmdline, split(&cmdline))),
+        Bootloader::Kernelstub => {
+            let text = system.read_to_string(Path::new(KERNELSTUB_CONFIG)).ok()?;
+            let config: serde_json::Value = serde_json::from_str(&text).ok()?;
+            let options = config.get("user")?.get("kernel_options")?.as_array()?;
+            Some((
+                CmdlineSource::Kernelstub,
+                options
+                    .iter()
+                    .filter_map(|option| option.as_str())
+                    .map(str::to_string)
+                    .collect(),
+            ))
+        }
+        Bootloader::Unknown => None,
+    }
+}
+
+/// Value of the last `NAME=...` assignment in a shell-style config, unquoted
+pub fn shell_variable(text: &str, name: &str) -> Option<String> {
+    text.lines()
+        .filter_map(|line| line.trim().strip_prefix(name)?.strip_prefix('='))
+        .next_back()
+        .map(|value| {
+            let value = value.trim();
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value)
+                .to_string()
+        })
+}
+
+/// Splits a command line the way the kernel does: on whitespace, except inside double quotes
+fn split(cmdline: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in cmdline.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    params.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        params.push(current);
+    }
+    params
+}
+
+fn without_bootloader_params(params: Vec<String>) -> Vec<String> {
+    params
+        .into_iter()
+        .filter(|param| !BOOTLOADER_PARAMS.contains(&key(param)))
+        .collect()
+}
+
+fn key(param: &str) -> &str {
+    param.split('=').next().unwrap_or(param)
+}
+
+/// Quotes a value holding spaces, unless it already carries its quotes
+fn quote(param: &str) -> String {
+    match param.split_once('=') {
+        Some((key, value)) if value.contains(char::is_whitespace) && !value.starts_with('"') => {
+            format!("{}=\"{}\"", key, value)
+        }
+        _ => param.to_string(),
+    }
+}
@@ -0,0 +1,467 @@
+// src-tauri/src/core/compression.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::bootloader::Bootloader;
+use super::cmdline::shell_variable;
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::distro::InstallTemplate;
+use super::hardware::Firmware;
+use super::plan::TargetArch;
+use super::resolver::ConfigLayer;
+use super::system::SystemFacade;
+
+/// First GRUB release that hands arm64 and RISC-V kernels to the firmware's LoadImage
+/// instead of parsing the bare image header, which a zboot image does not have
+const GRUB_ZBOOT: (u32, u32) = (2, 12);
+
+/// Algorithm compressing the kernel image or modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CompressionFormat {
+    None,
+    Gzip,
+    Lz4,
+    Xz,
+    Zstd,
+}
+
+impl CompressionFormat {
+    /// Formats offered for the kernel image; the image is always compressed
+    pub const IMAGE: [CompressionFormat; 4] = [
+        CompressionFormat::Gzip,
+        CompressionFormat::Lz4,
+        CompressionFormat::Xz,
+        CompressionFormat::Zstd,
+    ];
+
+    /// Formats kbuild can compress modules with
+    pub const MODULES: [CompressionFormat; 4] = [
+        CompressionFormat::None,
+        CompressionFormat::Gzip,
+        CompressionFormat::Xz,
+        CompressionFormat::Zstd,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CompressionFormat::None => "none",
+            CompressionFormat::Gzip => "gzip",
+            CompressionFormat::Lz4 => "lz4",
+            CompressionFormat::Xz => "xz",
+            CompressionFormat::Zstd => "zstd",
+        }
+    }
+
+    /// Host program kbuild runs to compress, None for no compression
+    pub fn program(self) -> Option<&'static str> {
+        match self {
+            CompressionFormat::None => None,
+            CompressionFormat::Gzip => Some("gzip"),
+            CompressionFormat::Lz4 => Some("lz4"),
+            CompressionFormat::Xz => Some("xz"),
+            CompressionFormat::Zstd => Some("zstd"),
+        }
+    }
+
+    fn image_symbol(self) -> Option<&'static str> {
+        match self {
+            CompressionFormat::None => None,
+            CompressionFormat::Gzip => Some("KERNEL_GZIP"),
+            CompressionFormat::Lz4 => Some("KERNEL_LZ4"),
+            CompressionFormat::Xz => Some("KERNEL_XZ"),
+            CompressionFormat::Zstd => Some("KERNEL_ZSTD"),
+        }
+    }
+
+    fn module_symbol(self) -> Option<&'static str> {
+        match self {
+            CompressionFormat::None => Some("MODULE_COMPRESS_NONE"),
+            CompressionFormat::Gzip => Some("MODULE_COMPRESS_GZIP"),
+            CompressionFormat::Lz4 => None,
+            CompressionFormat::Xz => Some("MODULE_COMPRESS_XZ"),
+            CompressionFormat::Zstd => Some("MODULE_COMPRESS_ZSTD"),
+        }
+    }
+
+    /// Feature kmod lists in `kmod --version` when it can load modules in this format
+    fn kmod_feature(self) -> Option<&'static str> {
+        match self {
+            CompressionFormat::None | CompressionFormat::Lz4 => None,
+            CompressionFormat::Gzip => Some("+ZLIB"),
+            CompressionFormat::Xz => Some("+XZ"),
+            CompressionFormat::Zstd => Some("+ZSTD"),
+        }
+    }
+
+    /// Arguments compressing a file in place like kbuild does, producing `<file>.<suffix>`
+    fn compress_args(self) -> &'static [&'static str] {
+        match self {
+            CompressionFormat::None => &[],
+            CompressionFormat::Gzip => &["-k", "-f", "-n", "-9"],
+            CompressionFormat::Lz4 => &["-f", "-m", "-l", "-9"],
+            CompressionFormat::Xz => &["-k", "-f", "--check=crc32", "--lzma2=dict=32MiB"],
+            CompressionFormat::Zstd => &["-k", "-f", "-q", "--ultra", "-22"],
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            CompressionFormat::None => "",
+            CompressionFormat::Gzip => "gz",
+            CompressionFormat::Lz4 => "lz4",
+            CompressionFormat::Xz => "xz",
+            CompressionFormat::Zstd => "zst",
+        }
+    }
+}
+
+/// Compression of the kernel image and of the modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressionChoice {
+    pub image: CompressionFormat,
+    pub modules: CompressionFormat,
+}
+
+impl CompressionChoice {
+    /// Choice a config makes, Kconfig's defaults filling in what it leaves out
+    pub fn of(config: &KernelConfig) -> Self {
+        let pick = |formats: &[CompressionFormat],
+                    symbol: fn(CompressionFormat) -> Option<&'static str>,
+                    default| {
+            formats
+                .iter()
+                .copied()
+                .find(|format| symbol(*format).is_some_and(|symbol| config.is_enabled(symbol)))
+                .unwrap_or(default)
+        };
+        CompressionChoice {
+            image: pick(
+                &CompressionFormat::IMAGE,
+                CompressionFormat::image_symbol,
+                CompressionFormat::Gzip,
+            ),
+            modules: pick(
+                &CompressionFormat::MODULES,
+                CompressionFormat::module_symbol,
+                CompressionFormat::None,
+            ),
+        }
+    }
+
+    /// Layer selecting the choice and deselecting every alternative
+    pub fn layer(&self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("compression");
+        for format in CompressionFormat::IMAGE {
+            if let Some(symbol) = format.image_symbol() {
+                layer.set(symbol, enabled(format == self.image));
+            }
+        }
+        for format in CompressionFormat::MODULES {
+            if let Some(symbol) = format.module_symbol() {
+                layer.set(symbol, enabled(format == self.modules));
+            }
+        }
+        layer
+    }
+
+    /// Problems building, loading or booting with the choice on this machine
+    pub fn check(
+        &self,
+        system: &dyn SystemFacade,
+        arch: TargetArch,
+        config: &KernelConfig,
+    ) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        let mut formats = vec![self.image, self.modules];
+        formats.dedup();
+        for format in formats {
+            let Some(program) = format.program() else {
+                continue;
+            };
+            if !system.has_program(program) {
+                advisories.push(Advisory::new(
+                    Severity::Blocker,
+                    "compression-tool-missing",
+                    &[
+                        ("format", format.name().to_string()),
+                        ("program", program.to_string()),
+                    ],
+                ));
+            }
+        }
+
+        // modprobe, depmod and every initramfs generator load modules through libkmod
+        if let Some(feature) = self.modules.kmod_feature() {
+            let features = system
+                .run("kmod", &["--version"])
+                .ok()
+                .filter(|output| output.success())
+                .map(|output| output.stdout);
+            if features.is_some_and(|features| !features.contains(feature)) {
+                advisories.push(Advisory::new(
+                    Severity::Blocker,
+                    "compression-kmod-unsupported",
+                    &[("format", self.modules.name().to_string())],
+                ));
+            }
+        }
+
+        // x86 and 32-bit Arm images decompress themselves; arm64 and RISC-V images only
+        // do inside the EFI zboot wrapper, without which the compression options are absent
+        if matches!(arch, TargetArch::Arm64 | TargetArch::Riscv64) {
+            if !config.is_enabled("EFI_ZBOOT") {
+                advisories.push(
+                    Advisory::new(
+                        Severity::Warning,
+                        "compression-image-zboot",
+                        &[("format", self.image.name().to_string())],
+                    )
+                    .suggest(ConfigSuggestion::new(
+                        "CONFIG_EFI_ZBOOT",
+                        ConfigValue::yes(),
+                        "wraps the compressed image in an EFI decompressor",
+                    )),
+                );
+            } else if Firmware::detect(system) == Firmware::Bios {
+                advisories.push(Advisory::new(
+                    Severity::Warning,
+                    "compression-zboot-no-efi",
+                    &[],
+                ));
+            } else if let Bootloader::Grub { mkconfig, .. } = Bootloader::detect(system) {
+                if let Some(version) =
+                    grub_version(system, &mkconfig).filter(|version| *version < GRUB_ZBOOT)
+                {
+                    advisories.push(Advisory::new(
+                        Severity::Blocker,
+                        "compression-zboot-grub",
+                        &[("version", format!("{}.{:02}", version.0, version.1))],
+                    ));
+                }
+            }
+        }
+
+        // The kernel unpacks the initramfs itself, with the decompressors CONFIG_RD_* builds in
+        if let Some((tool, compressor)) =
+            initramfs_compressor(system, InstallTemplate::detect(system))
+        {
+            if let Some(symbol) = rd_symbol(&compressor).filter(|symbol| !config.is_enabled(symbol))
+            {
+                advisories.push(
+                    Advisory::new(
+                        Severity::Blocker,
+                        "compression-initramfs-unsupported",
+                        &[
+                            ("tool", tool.to_string()),
+                            ("format", compressor),
+                            ("symbol", symbol.to_string()),
+                        ],
+                    )
+                    .suggest(ConfigSuggestion::new(
+                        &format!("CONFIG_{}", symbol),
+                        ConfigValue::yes(),
+                        "lets the kernel unpack the distro's initramfs",
+                    )),
+                );
+            }
+        }
+        advisories
+    }
+}
+
+/// The install template's initramfs generator and the compressor its config names,
+/// None when the template generates no initramfs
+fn initramfs_compressor(
+    system: &dyn SystemFacade,
+    template: InstallTemplate,
+) -> Option<(&'static str, String)> {
+    // Each tool's default when its configs leave the variable unset
+    let (tool, variable, default, mut files) = match template {
+        InstallTemplate::Arch => (
+            "mkinitcpio",
+            "COMPRESSION",
+            "zstd",
+            vec![PathBuf::from("/etc/mkinitcpio.conf")],
+        ),
+        InstallTemplate::Fedora => (
+            "dracut",
+            "compress",
+            "gzip",
+            vec![PathBuf::from("/etc/dracut.conf")],
+        ),
+        InstallTemplate::Debian => (
+            "initramfs-tools",
+            "COMPRESS",
+            "gzip",
+            vec![PathBuf::from("/etc/initramfs-tools/initramfs.conf")],
+        ),
+        InstallTemplate::Generic => return None,
+    };
+    files.extend(match template {
+        InstallTemplate::Arch => drop_ins(system, &["/etc/mkinitcpio.conf.d"], ".conf"),
+        InstallTemplate::Fedora => drop_ins(
+            system,
+            &["/usr/lib/dracut/dracut.conf.d", "/etc/dracut.conf.d"],
+            ".conf",
+        ),
+        _ => drop_ins(system, &["/etc/initramfs-tools/conf.d"], ""),
+    });
+    let configured = files
+        .iter()
+        .filter_map(|file| system.read_to_string(file).ok())
+        .filter_map(|text| shell_variable(&text, variable))
+        .next_back()
+        .unwrap_or_else(|| default.to_string());
+    // dracut takes a whole command line, possibly with a path
+    let program = configured.split_whitespace().next().unwrap_or(default);
+    Some((
+        tool,
+        program.rsplit('/').next().unwrap_or(program).to_string(),
+    ))
+}
+
+/// Drop-in files of `dirs` in name order; a file in a later directory replaces one of the
+/// same name in an earlier one
+fn drop_ins(system: &dyn SystemFacade, dirs: &[&str], suffix: &str) -> Vec<PathBuf> {
+    let mut files = BTreeMap::new();
+    for dir in dirs {
+        for path in system.read_dir(Path::new(dir)).unwrap_or_default() {
+            if let Some(name) = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .filter(|name| name.ends_with(suffix))
+            {
+                files.insert(name, path);
+            }
+        }
+    }
+    files.into_values().collect()
+}
+
+/// Decompressor a kernel needs for an initramfs made by `compressor`, None for none
+fn rd_symbol(compressor: &str) -> Option<&'static str> {
+    match compressor {
+        "gzip" | "pigz" => Some("RD_GZIP"),
+        "bzip2" | "lbzip2" | "pbzip2" => Some("RD_BZIP2"),
+        "lzma" => Some("RD_LZMA"),
+        "xz" => Some("RD_XZ"),
+        "lzop" | "lzo" => Some("RD_LZO"),
+        "lz4" => Some("RD_LZ4"),
+        "zstd" => Some("RD_ZSTD"),
+        _ => None,
+    }
+}
+
+/// Major and minor release of the installed GRUB, from `grub-mkconfig --version`
+fn grub_version(system: &dyn SystemFacade, mkconfig: &str) -> Option<(u32, u32)> {
+    let output = system
+        .run(mkconfig, &["--version"])
+        .ok()
+        .filter(|output| output.success())?;
+    let release = output.stdout.split_whitespace().next_back()?;
+    let mut numbers = release
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|number| number.parse::<u32>().ok());
+    Some((numbers.next()??, numbers.next()??))
+}
+
+/// Uncompressed image a build of `arch` leaves in the source tree, what the image
+/// formats are measured on
+pub fn uncompressed_image(arch: TargetArch) -> &'static str {
+    match arch {
+        TargetArch::X86_64 => "arch/x86/boot/compressed/vmlinux.bin",
+        TargetArch::Arm64 => "arch/arm64/boot/Image",
+        TargetArch::Arm => "arch/arm/boot/Image",
+        TargetArch::Riscv64 => "arch/riscv/boot/Image",
+    }
+}
+
+/// Size and decompression time of one format, measured on an image of this build
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionTradeoff {
+    pub format: CompressionFormat,
+    pub size_bytes: u64,
+    /// Compressed size relative to the uncompressed image
+    pub ratio: f32,
+    /// Time to decompress on this machine, close to what the kernel spends at boot
+    pub decompress_ms: u64,
+}
+
+/// Compresses `image`, typically the uncompressed `vmlinux.bin` of a finished build, with
+/// each image format whose tool is installed, working on copies in `scratch`
+pub fn measure_tradeoffs(
+    system: &dyn SystemFacade,
+    image: &Path,
+    scratch: &Path,
+) -> Result<Vec<CompressionTradeoff>> {
+    let original = fs::metadata(image)
+        .with_context(|| format!("reading {}", image.display()))?
+        .len();
+    if original == 0 {
+        bail!("{} is empty", image.display());
+    }
+    fs::create_dir_all(scratch).with_context(|| format!("creating {}", scratch.display()))?;
+    let sample = scratch.join("image");
+    let mut tradeoffs = Vec::new();
+    for format in CompressionFormat::IMAGE {
+        let Some(program) = format
+            .program()
+            .filter(|program| system.has_program(program))
+        else {
+            continue;
+        };
+        fs::copy(image, &sample).with_context(|| format!("copying {}", image.display()))?;
+        let mut args = format.compress_args().to_vec();
+        let sample_arg = sample.to_string_lossy();
+        args.push(&sample_arg);
+        let output = system
+            .run(program, &args)
+            .with_context(|| format!("running {}", program))?;
+        if !output.success() {
+            bail!("{} failed: {}", program, output.stderr.trim());
+        }
+        let compressed = scratch.join(format!("image.{}", format.suffix()));
+        let size_bytes = fs::metadata(&compressed)
+            .with_context(|| format!("reading {}", compressed.display()))?
+            .len();
+        // Testing an archive decompresses it fully without writing the result
+        let compressed_arg = compressed.to_string_lossy();
+        let started = Instant::now();
+        let output = system
+            .run(program, &["-t", &compressed_arg])
+            .with_context(|| format!("running {}", program))?;
+        let decompress_ms = started.elapsed().as_millis() as u64;
+        if !output.success() {
+            bail!(
+                "{} could not read back its output: {}",
+                program,
+                output.stderr.trim()
+            );
+        }
+        let _ = fs::remove_file(&compressed);
+        tradeoffs.push(CompressionTradeoff {
+            format,
+            size_bytes,
+            ratio: size_bytes as f32 / original as f32,
+            decompress_ms,
+        });
+    }
+    let _ = fs::remove_file(&sample);
+    Ok(tradeoffs)
+}
+
+/// `y` when selected, not set otherwise
+pub fn enabled(selected: bool) -> ConfigValue {
+    if selected {
+        ConfigValue::yes()
+    } else {
+        ConfigValue::NotSet
+    }
+}
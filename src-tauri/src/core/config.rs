@@ -0,0 +1,272 @@
+// src-tauri/src/core/config.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::kconfig::{KconfigTree, SymbolType, Tristate};
+use super::symbols::bare_name;
+
+/// Value assigned to a symbol in a .config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigValue {
+    /// `CONFIG_FOO=<value>`, kept verbatim (quotes included for strings)
+    Set(String),
+    /// `# CONFIG_FOO is not set`
+    NotSet,
+}
+
+impl ConfigValue {
+    /// Shorthand for `Set("y")`
+    pub fn yes() -> Self {
+        ConfigValue::Set(String::from("y"))
+    }
+
+    /// Shorthand for `Set("m")`
+    pub fn module() -> Self {
+        ConfigValue::Set(String::from("m"))
+    }
+
+    /// Raw value as written after the `=`, None when not set
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ConfigValue::Set(value) => Some(value),
+            ConfigValue::NotSet => None,
+        }
+    }
+
+    /// Whether the value enables the symbol (built in or as a module)
+    pub fn is_enabled(&self) -> bool {
+        matches!(self.as_str(), Some(value) if value != "n")
+    }
+
+    /// Decimal value for `int` symbols
+    pub fn int(value: i64) -> Self {
+        TypedValue::Int(value).into()
+    }
+
+    /// Hexadecimal value for `hex` symbols
+    pub fn hex(value: u64) -> Self {
+        TypedValue::Hex(value).into()
+    }
+
+    /// Quoted and escaped value for `string` symbols
+    pub fn string(value: &str) -> Self {
+        TypedValue::String(value.to_string()).into()
+    }
+
+    /// Interprets the raw value according to the symbol's Kconfig type
+    pub fn typed(&self, kind: SymbolType) -> Result<Option<TypedValue>> {
+        match self {
+            ConfigValue::Set(raw) => TypedValue::parse(kind, raw).map(Some),
+            ConfigValue::NotSet => Ok(None),
+        }
+    }
+}
+
+/// A value checked against the type of its symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypedValue {
+    Bool(bool),
+    Tristate(Tristate),
+    Int(i64),
+    Hex(u64),
+    String(String),
+    /// Symbols whose type the tree does not declare are kept verbatim
+    Raw(String),
+}
+
+impl TypedValue {
+    /// Parses a raw .config value, rejecting values the type cannot hold such as HZ=y
+    pub fn parse(kind: SymbolType, raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        Ok(match kind {
+            SymbolType::Bool => match raw {
+                "y" => TypedValue::Bool(true),
+                "n" => TypedValue::Bool(false),
+                _ => bail!("bool symbols take y or n, not {}", raw),
+            },
+            SymbolType::Tristate => match raw {
+                "y" | "m" | "n" => TypedValue::Tristate(Tristate::from_value(raw)),
+                _ => bail!("tristate symbols take y, m or n, not {}", raw),
+            },
+            SymbolType::Int => match raw.parse() {
+                Ok(value) => TypedValue::Int(value),
+                Err(_) => bail!("int symbols take a decimal number, not {}", raw),
+            },
+            SymbolType::Hex => {
+                let digits = raw
+                    .strip_prefix("0x")
+                    .or_else(|| raw.strip_prefix("0X"))
+                    .unwrap_or(raw);
+                match u64::from_str_radix(digits, 16) {
+                    Ok(value) => TypedValue::Hex(value),
+                    Err(_) => bail!("hex symbols take a hexadecimal number, not {}", raw),
+                }
+            }
+            SymbolType::String => {
+                let Some(inner) = raw.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) else {
+                    bail!("string values must be double-quoted, got {}", raw);
+                };
+                let mut value = String::with_capacity(inner.len());
+                let mut chars = inner.chars();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '\\' => value.extend(chars.next()),
+                        '"' => bail!("unescaped quote in string value {}", raw),
+                        _ => value.push(ch),
+                    }
+                }
+                TypedValue::String(value)
+            }
+            SymbolType::Unknown => TypedValue::Raw(raw.to_string()),
+        })
+    }
+
+    /// Spelling after the `=` in a .config file
+    pub fn to_raw(&self) -> String {
+        match self {
+            TypedValue::Bool(true) => String::from("y"),
+            TypedValue::Bool(false) => String::from("n"),
+            TypedValue::Tristate(state) => state.as_str().to_string(),
+            TypedValue::Int(value) => value.to_string(),
+            TypedValue::Hex(value) => format!("0x{:x}", value),
+            TypedValue::String(value) => {
+                format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            TypedValue::Raw(value) => value.clone(),
+        }
+    }
+}
+
+impl From<TypedValue> for ConfigValue {
+    fn from(value: TypedValue) -> Self {
+        match value {
+            TypedValue::Bool(false) | TypedValue::Tristate(Tristate::No) => ConfigValue::NotSet,
+            value => ConfigValue::Set(value.to_raw()),
+        }
+    }
+}
+
+/// Checks an assignment against the symbol's declared type in `tree`
+/// Symbols the tree does not declare pass unchanged, they may come from another kernel version
+pub fn validate(tree: &KconfigTree, name: &str, value: &ConfigValue) -> Result<()> {
+    let Some(def) = tree.def_by_name(bare_name(name)) else {
+        return Ok(());
+    };
+    if let Err(error) = value.typed(def.kind) {
+        bail!("invalid value for CONFIG_{}: {}", bare_name(name), error);
+    }
+    Ok(())
+}
+
+/// A complete kernel configuration keyed by bare symbol name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelConfig {
+    values: BTreeMap<String, ConfigValue>,
+}
+
+impl KernelConfig {
+    /// Creates an empty configuration
+    pub fn new() -> Self {
+        KernelConfig::default()
+    }
+
+    /// Parses the contents of a .config file
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config = KernelConfig::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                if let Some(name) = comment.trim().strip_suffix(" is not set") {
+                    config.set(name, ConfigValue::NotSet);
+                }
+                continue;
+            }
+            match line.split_once('=') {
+                Some((name, value)) if name.starts_with("CONFIG_") => {
+                    config.set(name, ConfigValue::Set(value.to_string()));
+                }
+                _ => bail!("line {}: unrecognised .config entry: {}", number + 1, line),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Renders the configuration in .config syntax, sorted by symbol name
+    pub fn emit(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.values {
+            match value {
+                ConfigValue::Set(value) => out.push_str(&format!("CONFIG_{}={}\n", name, value)),
+                ConfigValue::NotSet => out.push_str(&format!("# CONFIG_{} is not set\n", name)),
+            }
+        }
+        out
+    }
+
+    /// Assigns a symbol, accepting names with or without the CONFIG_ prefix
+    pub fn set(&mut self, name: &str, value: ConfigValue) {
+        self.values.insert(bare_name(name).to_string(), value);
+    }
+
+    /// Assigns a symbol after checking the value against its Kconfig type
+    pub fn set_checked(&mut self, tree: &KconfigTree, name: &str, value: ConfigValue) -> Result<()> {
+        validate(tree, name, &value)?;
+        self.set(name, value);
+        Ok(())
+    }
+
+    /// Removes a symbol entirely
+    pub fn remove(&mut self, name: &str) -> Option<ConfigValue> {
+        self.values.remove(bare_name(name))
+    }
+
+    /// Value of a symbol, if present
+    pub fn get(&self, name: &str) -> Option<&ConfigValue> {
+        self.values.get(bare_name(name))
+    }
+
+    /// Whether the symbol is set to y or m
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.get(name).is_some_and(ConfigValue::is_enabled)
+    }
+
+    /// Iterates over all entries in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ConfigValue)> {
+        self.values.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Number of symbols present
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the configuration has no entries
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// A recommended assignment together with the reason shown to the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigSuggestion {
+    pub symbol: String,
+    pub value: ConfigValue,
+    pub reason: String,
+}
+
+impl ConfigSuggestion {
+    /// Creates a suggestion for `symbol`
+    pub fn new(symbol: &str, value: ConfigValue, reason: &str) -> Self {
+        ConfigSuggestion {
+            symbol: symbol.to_string(),
+            value,
+            reason: reason.to_string(),
+        }
+    }
+}
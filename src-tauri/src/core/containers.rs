@@ -0,0 +1,273 @@
+// src-tauri/src/core/containers.rs
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::resolver::ConfigLayer;
+use super::system::SystemFacade;
+
+/// Parent of the users' home directories
+pub const HOMES: &str = "/home";
+
+/// Where rootless podman keeps images and containers, under a home directory
+pub const ROOTLESS_STORAGE: &str = ".local/share/containers";
+
+/// A container engine or orchestrator set up on the machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    /// LXC and the LXD and Incus managers built on it
+    Lxc,
+    /// A kubelet, including the one embedded in k3s
+    Kubernetes,
+}
+
+impl ContainerRuntime {
+    pub const ALL: [ContainerRuntime; 4] = [
+        ContainerRuntime::Docker,
+        ContainerRuntime::Podman,
+        ContainerRuntime::Lxc,
+        ContainerRuntime::Kubernetes,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Lxc => "lxc",
+            ContainerRuntime::Kubernetes => "kubernetes",
+        }
+    }
+
+    /// Programs on PATH, then state directories, that show the runtime is in use
+    fn markers(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            ContainerRuntime::Docker => (&["dockerd"], &["/var/lib/docker"]),
+            ContainerRuntime::Podman => (&["podman"], &["/var/lib/containers/storage"]),
+            ContainerRuntime::Lxc => (
+                &["lxc-start", "lxd", "incus"],
+                &["/var/lib/lxc", "/var/lib/lxd", "/var/lib/incus"],
+            ),
+            ContainerRuntime::Kubernetes => (&["kubelet", "k3s"], &["/var/lib/kubelet"]),
+        }
+    }
+
+    /// Runtimes installed or holding state on this machine, including podman run rootless
+    /// by any user
+    pub fn detect(system: &dyn SystemFacade) -> BTreeSet<ContainerRuntime> {
+        ContainerRuntime::ALL
+            .into_iter()
+            .filter(|runtime| {
+                let (programs, dirs) = runtime.markers();
+                programs.iter().any(|program| system.has_program(program))
+                    || dirs.iter().any(|dir| system.exists(Path::new(dir)))
+                    || (*runtime == ContainerRuntime::Podman && rootless_podman(system))
+            })
+            .collect()
+    }
+}
+
+fn rootless_podman(system: &dyn SystemFacade) -> bool {
+    system
+        .read_dir(Path::new(HOMES))
+        .unwrap_or_default()
+        .iter()
+        .any(|home| system.exists(&home.join(ROOTLESS_STORAGE)))
+}
+
+/// What containers need from the kernel, so a finding names the feature that breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ContainerFeature {
+    /// The cgroup v2 controllers runtimes limit containers with; device access is
+    /// filtered by BPF programs attached to the cgroup
+    Cgroups,
+    Namespaces,
+    /// Image layers are stacked with overlayfs
+    Overlay,
+    /// NAT and filtering plus the bridge and veth pairs container networks are built from
+    Netfilter,
+    /// CRIU needs it to checkpoint and migrate running containers
+    CheckpointRestore,
+}
+
+impl ContainerFeature {
+    pub fn name(self) -> &'static str {
+        match self {
+            ContainerFeature::Cgroups => "cgroup v2",
+            ContainerFeature::Namespaces => "namespaces",
+            ContainerFeature::Overlay => "overlayfs",
+            ContainerFeature::Netfilter => "netfilter",
+            ContainerFeature::CheckpointRestore => "checkpoint/restore",
+        }
+    }
+}
+
+/// One option runtimes need
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerRequirement {
+    pub feature: ContainerFeature,
+    pub symbol: &'static str,
+    /// Building it as a module is enough, runtimes load it on demand
+    pub loadable: bool,
+}
+
+impl ContainerRequirement {
+    /// Value meeting the requirement with as little built in as possible
+    pub fn value(&self) -> ConfigValue {
+        if self.loadable {
+            ConfigValue::module()
+        } else {
+            ConfigValue::yes()
+        }
+    }
+}
+
+const fn require(
+    feature: ContainerFeature,
+    symbol: &'static str,
+    loadable: bool,
+) -> ContainerRequirement {
+    ContainerRequirement {
+        feature,
+        symbol,
+        loadable,
+    }
+}
+
+const REQUIREMENTS: &[ContainerRequirement] = &[
+    require(ContainerFeature::Cgroups, "CONFIG_CGROUPS", false),
+    require(ContainerFeature::Cgroups, "CONFIG_MEMCG", false),
+    require(ContainerFeature::Cgroups, "CONFIG_CGROUP_PIDS", false),
+    require(ContainerFeature::Cgroups, "CONFIG_CGROUP_SCHED", false),
+    require(ContainerFeature::Cgroups, "CONFIG_CPUSETS", false),
+    require(ContainerFeature::Cgroups, "CONFIG_BLK_CGROUP", false),
+    require(ContainerFeature::Cgroups, "CONFIG_CGROUP_FREEZER", false),
+    require(ContainerFeature::Cgroups, "CONFIG_BPF_SYSCALL", false),
+    require(ContainerFeature::Cgroups, "CONFIG_CGROUP_BPF", false),
+    require(ContainerFeature::Namespaces, "CONFIG_NAMESPACES", false),
+    require(ContainerFeature::Namespaces, "CONFIG_UTS_NS", false),
+    require(ContainerFeature::Namespaces, "CONFIG_IPC_NS", false),
+    require(ContainerFeature::Namespaces, "CONFIG_PID_NS", false),
+    require(ContainerFeature::Namespaces, "CONFIG_NET_NS", false),
+    require(ContainerFeature::Namespaces, "CONFIG_USER_NS", false),
+    require(ContainerFeature::Overlay, "CONFIG_OVERLAY_FS", true),
+    require(ContainerFeature::Netfilter, "CONFIG_NETFILTER", false),
+    require(ContainerFeature::Netfilter, "CONFIG_NF_CONNTRACK", true),
+    require(ContainerFeature::Netfilter, "CONFIG_NF_NAT", true),
+    require(ContainerFeature::Netfilter, "CONFIG_NF_TABLES", true),
+    require(ContainerFeature::Netfilter, "CONFIG_IP_NF_IPTABLES", true),
+    require(ContainerFeature::Netfilter, "CONFIG_IP_NF_NAT", true),
+    require(
+        ContainerFeature::Netfilter,
+        "CONFIG_IP_NF_TARGET_MASQUERADE",
+        true,
+    ),
+    require(
+        ContainerFeature::Netfilter,
+        "CONFIG_NETFILTER_XT_MATCH_ADDRTYPE",
+        true,
+    ),
+    require(
+        ContainerFeature::Netfilter,
+        "CONFIG_NETFILTER_XT_MATCH_CONNTRACK",
+        true,
+    ),
+    require(ContainerFeature::Netfilter, "CONFIG_BRIDGE", true),
+    require(ContainerFeature::Netfilter, "CONFIG_BRIDGE_NETFILTER", true),
+    require(ContainerFeature::Netfilter, "CONFIG_VETH", true),
+    require(
+        ContainerFeature::CheckpointRestore,
+        "CONFIG_CHECKPOINT_RESTORE",
+        false,
+    ),
+];
+
+/// The requirement on `symbol`, None when containers do not depend on it
+pub fn required_by(symbol: &str) -> Option<&'static ContainerRequirement> {
+    REQUIREMENTS
+        .iter()
+        .find(|requirement| requirement.symbol == symbol)
+}
+
+/// Requirements the config leaves out
+pub fn missing(config: &KernelConfig) -> Vec<&'static ContainerRequirement> {
+    REQUIREMENTS
+        .iter()
+        .filter(|requirement| !config.is_enabled(requirement.symbol))
+        .collect()
+}
+
+/// Every required option, as modules where that is enough; the container-workload
+/// profile is built from it
+pub fn layer() -> ConfigLayer {
+    let mut layer = ConfigLayer::new("containers");
+    for requirement in REQUIREMENTS {
+        layer.set(requirement.symbol, requirement.value());
+    }
+    layer
+}
+
+fn runtime_names(runtimes: &BTreeSet<ContainerRuntime>) -> String {
+    runtimes
+        .iter()
+        .map(|runtime| runtime.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Findings for a plan meant for a machine running `runtimes`, none when it runs none
+pub fn check(runtimes: &BTreeSet<ContainerRuntime>, config: &KernelConfig) -> Vec<Advisory> {
+    let missing = missing(config);
+    if runtimes.is_empty() || missing.is_empty() {
+        return Vec::new();
+    }
+    let mut features: Vec<&str> = missing
+        .iter()
+        .map(|requirement| requirement.feature.name())
+        .collect();
+    features.dedup();
+    let symbols: Vec<&str> = missing
+        .iter()
+        .map(|requirement| requirement.symbol)
+        .collect();
+    let mut advisory = Advisory::new(
+        Severity::Blocker,
+        "containers-missing-config",
+        &[
+            ("runtimes", runtime_names(runtimes)),
+            ("features", features.join(", ")),
+            ("symbols", symbols.join(", ")),
+        ],
+    );
+    for requirement in missing {
+        advisory = advisory.suggest(ConfigSuggestion::new(
+            requirement.symbol,
+            requirement.value(),
+            &format!("containers need {}", requirement.feature.name()),
+        ));
+    }
+    vec![advisory]
+}
+
+/// Refuses a config that would stop the machine's containers from starting
+pub fn enforce(runtimes: &BTreeSet<ContainerRuntime>, config: &KernelConfig) -> Result<()> {
+    let missing = missing(config);
+    if runtimes.is_empty() || missing.is_empty() {
+        return Ok(());
+    }
+    let symbols: Vec<&str> = missing
+        .iter()
+        .map(|requirement| requirement.symbol)
+        .collect();
+    bail!(
+        "{} would not start containers without {}",
+        runtime_names(runtimes),
+        symbols.join(", ")
+    );
+}
@@ -0,0 +1,287 @@
+// src-tauri/src/core/cpu.rs
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::procfs::{parse_cpuinfo, PROC_CPUINFO};
+use super::system::SystemFacade;
+
+const SYSFS_CPU: &str = "/sys/devices/system/cpu";
+
+/// CPU manufacturer as reported by cpuinfo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+    Arm,
+    Unknown,
+}
+
+/// Microarchitecture, either a known core design or an x86-64 feature level fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Microarch {
+    Zen,
+    Zen2,
+    Zen3,
+    Zen4,
+    Zen5,
+    Skylake,
+    SkylakeAvx512,
+    IceLakeClient,
+    IceLakeServer,
+    TigerLake,
+    AlderLake,
+    RaptorLake,
+    MeteorLake,
+    SapphireRapids,
+    X86_64V1,
+    X86_64V2,
+    X86_64V3,
+    X86_64V4,
+    Generic,
+}
+
+impl Microarch {
+    /// Value for GCC/Clang `-march=`
+    pub fn march(self) -> &'static str {
+        match self {
+            Microarch::Zen => "znver1",
+            Microarch::Zen2 => "znver2",
+            Microarch::Zen3 => "znver3",
+            Microarch::Zen4 => "znver4",
+            Microarch::Zen5 => "znver5",
+            Microarch::Skylake => "skylake",
+            Microarch::SkylakeAvx512 => "skylake-avx512",
+            Microarch::IceLakeClient => "icelake-client",
+            Microarch::IceLakeServer => "icelake-server",
+            Microarch::TigerLake => "tigerlake",
+            Microarch::AlderLake => "alderlake",
+            Microarch::RaptorLake => "raptorlake",
+            Microarch::MeteorLake => "meteorlake",
+            Microarch::SapphireRapids => "sapphirerapids",
+            Microarch::X86_64V1 => "x86-64",
+            Microarch::X86_64V2 => "x86-64-v2",
+            Microarch::X86_64V3 => "x86-64-v3",
+            Microarch::X86_64V4 => "x86-64-v4",
+            Microarch::Generic => "native",
+        }
+    }
+}
+
+/// One CPU cache as described in sysfs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub level: u8,
+    /// Data, Instruction or Unified
+    pub kind: String,
+    pub size_kb: u64,
+}
+
+/// Processor description used for -march selection, job planning and profile conditionals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuInfo {
+    pub vendor: CpuVendor,
+    pub model_name: String,
+    pub family: Option<u32>,
+    pub model: Option<u32>,
+    pub microarch: Microarch,
+    pub logical_cpus: usize,
+    pub physical_cores: usize,
+    pub packages: usize,
+    pub caches: Vec<CacheInfo>,
+    pub flags: BTreeSet<String>,
+}
+
+impl CpuInfo {
+    /// Detects the host CPU from /proc/cpuinfo and sysfs topology
+    pub fn detect(system: &dyn SystemFacade) -> Result<Self> {
+        let text = system
+            .read_to_string(Path::new(PROC_CPUINFO))
+            .context("reading /proc/cpuinfo")?;
+        let entries = parse_cpuinfo(&text);
+        let first = entries.first().context("no processors listed in /proc/cpuinfo")?;
+
+        let vendor = match first.get("vendor_id") {
+            Some("GenuineIntel") => CpuVendor::Intel,
+            Some("AuthenticAMD") => CpuVendor::Amd,
+            _ if first.get("CPU implementer").is_some() => CpuVendor::Arm,
+            _ => CpuVendor::Unknown,
+        };
+        let model_name = first
+            .get("model name")
+            .or_else(|| first.get("Hardware"))
+            .unwrap_or("unknown")
+            .to_string();
+        let family = first.get("cpu family").and_then(|value| value.parse().ok());
+        let model = first.get("model").and_then(|value| value.parse().ok());
+        let flags: BTreeSet<String> = first.flags().map(str::to_string).collect();
+
+        let (logical_cpus, physical_cores, packages) = read_topology(system, entries.len());
+        let caches = read_caches(system);
+        let microarch = classify(vendor, family, model, &flags);
+
+        Ok(CpuInfo {
+            vendor,
+            model_name,
+            family,
+            model,
+            microarch,
+            logical_cpus,
+            physical_cores,
+            packages,
+            caches,
+            flags,
+        })
+    }
+
+    /// Whether a cpuinfo flag such as `avx2` is present
+    pub fn has_feature(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    /// Whether simultaneous multithreading is active
+    pub fn has_smt(&self) -> bool {
+        self.logical_cpus > self.physical_cores
+    }
+
+    /// Largest cache size in kB, typically the shared L3
+    pub fn last_level_cache_kb(&self) -> u64 {
+        self.caches
+            .iter()
+            .max_by_key(|cache| (cache.level, cache.size_kb))
+            .map(|cache| cache.size_kb)
+            .unwrap_or(0)
+    }
+
+    /// Highest x86-64 psABI level supported by the flags (1-4), 0 on other architectures
+    pub fn x86_64_level(&self) -> u8 {
+        x86_64_level(&self.flags)
+    }
+}
+
+fn x86_64_level(flags: &BTreeSet<String>) -> u8 {
+    let has_all = |names: &[&str]| names.iter().all(|name| flags.contains(*name));
+    if !has_all(&["lm", "cmov", "cx8", "fpu", "fxsr", "mmx", "sse", "sse2"]) {
+        return 0;
+    }
+    if !has_all(&["cx16", "lahf_lm", "popcnt", "sse4_1", "sse4_2", "ssse3"]) {
+        return 1;
+    }
+    if !has_all(&["avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "abm", "movbe", "xsave"]) {
+        return 2;
+    }
+    if !has_all(&["avx512f", "avx512bw", "avx512cd", "avx512dq", "avx512vl"]) {
+        return 3;
+    }
+    4
+}
+
+fn classify(
+    vendor: CpuVendor,
+    family: Option<u32>,
+    model: Option<u32>,
+    flags: &BTreeSet<String>,
+) -> Microarch {
+    let known = match (vendor, family, model) {
+        (CpuVendor::Amd, Some(0x17), Some(model)) if model < 0x30 => Some(Microarch::Zen),
+        (CpuVendor::Amd, Some(0x17), Some(_)) => Some(Microarch::Zen2),
+        (CpuVendor::Amd, Some(0x19), Some(model))
+            if (0x10..=0x1f).contains(&model)
+                || (0x60..=0x7f).contains(&model)
+                || (0xa0..=0xaf).contains(&model) =>
+        {
+            Some(Microarch::Zen4)
+        }
+        (CpuVendor::Amd, Some(0x19), Some(_)) => Some(Microarch::Zen3),
+        (CpuVendor::Amd, Some(0x1a), Some(_)) => Some(Microarch::Zen5),
+        (CpuVendor::Intel, Some(6), Some(model)) => match model {
+            0x4e | 0x5e | 0x8e | 0x9e | 0xa5 | 0xa6 => Some(Microarch::Skylake),
+            0x55 => Some(Microarch::SkylakeAvx512),
+            0x7d | 0x7e => Some(Microarch::IceLakeClient),
+            0x6a | 0x6c => Some(Microarch::IceLakeServer),
+            0x8c | 0x8d => Some(Microarch::TigerLake),
+            0x97 | 0x9a => Some(Microarch::AlderLake),
+            0xb7 | 0xba | 0xbf => Some(Microarch::RaptorLake),
+            0xaa | 0xac => Some(Microarch::MeteorLake),
+            0x8f => Some(Microarch::SapphireRapids),
+            _ => None,
+        },
+        _ => None,
+    };
+    known.unwrap_or(match x86_64_level(flags) {
+        1 => Microarch::X86_64V1,
+        2 => Microarch::X86_64V2,
+        3 => Microarch::X86_64V3,
+        4 => Microarch::X86_64V4,
+        _ => Microarch::Generic,
+    })
+}
+
+/// Counts logical CPUs, distinct cores and packages from sysfs topology files
+fn read_topology(system: &dyn SystemFacade, fallback_logical: usize) -> (usize, usize, usize) {
+    let cpus: Vec<_> = system
+        .read_dir(Path::new(SYSFS_CPU))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("cpu"))
+                .is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect();
+
+    let mut cores = HashSet::new();
+    let mut packages = HashSet::new();
+    for cpu in &cpus {
+        let topology = cpu.join("topology");
+        let package = system.read_trimmed(&topology.join("physical_package_id"));
+        let core = system.read_trimmed(&topology.join("core_id"));
+        if let (Some(package), Some(core)) = (package, core) {
+            cores.insert((package.clone(), core));
+            packages.insert(package);
+        }
+    }
+
+    let logical = if cpus.is_empty() { fallback_logical } else { cpus.len() };
+    let physical = if cores.is_empty() { logical } else { cores.len() };
+    (logical.max(1), physical.max(1), packages.len().max(1))
+}
+
+/// Reads cpu0's cache hierarchy, which is representative on non-hybrid parts
+fn read_caches(system: &dyn SystemFacade) -> Vec<CacheInfo> {
+    let root = Path::new(SYSFS_CPU).join("cpu0/cache");
+    system
+        .read_dir(&root)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("index"))
+        })
+        .filter_map(|index| {
+            let level = system.read_trimmed(&index.join("level"))?.parse().ok()?;
+            let kind = system.read_trimmed(&index.join("type"))?;
+            let size = system.read_trimmed(&index.join("size"))?;
+            Some(CacheInfo {
+                level,
+                kind,
+                size_kb: parse_size_kb(&size)?,
+            })
+        })
+        .collect()
+}
+
+/// Parses sysfs sizes such as `32K` or `16M`
+fn parse_size_kb(size: &str) -> Option<u64> {
+    let size = size.trim();
+    match size.chars().last()? {
+        'K' => size[..size.len() - 1].parse().ok(),
+        'M' => size[..size.len() - 1].parse::<u64>().ok().map(|value| value * 1024),
+        _ => size.parse::<u64>().ok().map(|bytes| bytes / 1024),
+    }
+}
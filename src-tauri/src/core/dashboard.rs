@@ -0,0 +1,246 @@
+// src-tauri/src/core/dashboard.rs
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::generator::KernelProfile;
+use super::latency::LatencyLoad;
+use super::results::{KernelTag, ResultsStore};
+use super::stats::StatsStore;
+
+/// Quantities the results dashboard can chart
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Metric {
+    BuildDuration,
+    ImageSize,
+    /// Kernel and initrd part of the boot
+    BootKernel,
+    BootUserspace,
+    BootTotal,
+    /// Mean run time of a named benchmark
+    Benchmark(String),
+    LatencyP99(LatencyLoad),
+    LatencyScore(LatencyLoad),
+    /// Average frame rate of a named game capture
+    AverageFps(String),
+    /// 1% low frame rate of a named game capture
+    LowFps(String),
+}
+
+impl Metric {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Metric::BuildDuration => "s",
+            Metric::ImageSize => "bytes",
+            Metric::BootKernel | Metric::BootUserspace | Metric::BootTotal => "ms",
+            Metric::Benchmark(_) => "ms",
+            Metric::LatencyP99(_) => "µs",
+            Metric::LatencyScore(_) => "score",
+            Metric::AverageFps(_) | Metric::LowFps(_) => "fps",
+        }
+    }
+
+    /// Whether a larger value is an improvement, so charts can colour changes
+    pub fn higher_is_better(&self) -> bool {
+        matches!(
+            self,
+            Metric::LatencyScore(_) | Metric::AverageFps(_) | Metric::LowFps(_)
+        )
+    }
+}
+
+/// One measurement, placed on the time axis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    pub at: u64,
+    pub version: String,
+    pub value: f64,
+    /// Measured under conditions that make it unfit for comparison, e.g. thermal throttling
+    pub flagged: bool,
+}
+
+/// A metric over time, oldest point first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    pub metric: Metric,
+    pub unit: String,
+    pub higher_is_better: bool,
+    pub points: Vec<SeriesPoint>,
+}
+
+/// Latest comparable value of every metric for one kernel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KernelSummary {
+    pub version: String,
+    pub profile: Option<KernelProfile>,
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Everything the Results view draws: series for charts and a table comparing kernels
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub series: Vec<Series>,
+    pub kernels: Vec<KernelSummary>,
+}
+
+impl Dashboard {
+    /// Collects build statistics and measurements into chart-ready series
+    pub fn build(stats: &StatsStore, results: &ResultsStore) -> Self {
+        let mut collector = Collector::default();
+        for build in stats.builds().iter().filter(|build| build.success) {
+            let kernel = KernelTag {
+                version: build.version.clone(),
+                profile: None,
+            };
+            let finished_at = build.started_at + build.duration_secs;
+            collector.add(
+                Metric::BuildDuration,
+                &kernel,
+                finished_at,
+                build.duration_secs as f64,
+                false,
+            );
+            if let Some(size) = build.image_size {
+                collector.add(Metric::ImageSize, &kernel, finished_at, size as f64, false);
+            }
+        }
+        for boot in results.boots() {
+            for (metric, value) in [
+                (Metric::BootKernel, boot.kernel_side_ms()),
+                (Metric::BootUserspace, boot.userspace_ms),
+                (Metric::BootTotal, boot.total_ms()),
+            ] {
+                collector.add(metric, &boot.kernel, boot.recorded_at, value as f64, false);
+            }
+        }
+        for benchmark in results.benchmarks() {
+            if let Some(mean) = benchmark.mean_ms() {
+                collector.add(
+                    Metric::Benchmark(benchmark.name.clone()),
+                    &benchmark.kernel,
+                    benchmark.recorded_at,
+                    mean,
+                    !benchmark.comparable(),
+                );
+            }
+        }
+        for latency in results.latency() {
+            collector.add(
+                Metric::LatencyP99(latency.load),
+                &latency.kernel,
+                latency.recorded_at,
+                latency.p99_us as f64,
+                false,
+            );
+            collector.add(
+                Metric::LatencyScore(latency.load),
+                &latency.kernel,
+                latency.recorded_at,
+                latency.score,
+                false,
+            );
+        }
+        for game in results.games() {
+            collector.add(
+                Metric::AverageFps(game.name.clone()),
+                &game.kernel,
+                game.recorded_at,
+                game.stats.avg_fps,
+                false,
+            );
+            collector.add(
+                Metric::LowFps(game.name.clone()),
+                &game.kernel,
+                game.recorded_at,
+                game.stats.low_1_fps,
+                false,
+            );
+        }
+        collector.finish()
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    series: BTreeMap<Metric, Vec<SeriesPoint>>,
+    latest: BTreeMap<String, Latest>,
+}
+
+/// Latest unflagged value per metric for one kernel, with the profile seen for it
+#[derive(Default)]
+struct Latest {
+    profile: Option<KernelProfile>,
+    values: BTreeMap<Metric, (u64, f64)>,
+}
+
+impl Collector {
+    fn add(&mut self, metric: Metric, kernel: &KernelTag, at: u64, value: f64, flagged: bool) {
+        if !flagged {
+            let latest = self.latest.entry(kernel.version.clone()).or_default();
+            if kernel.profile.is_some() {
+                latest.profile = kernel.profile;
+            }
+            let entry = latest.values.entry(metric.clone()).or_insert((at, value));
+            if at >= entry.0 {
+                *entry = (at, value);
+            }
+        }
+        self.series.entry(metric).or_default().push(SeriesPoint {
+            at,
+            version: kernel.version.clone(),
+            value,
+            flagged,
+        });
+    }
+
+    fn finish(self) -> Dashboard {
+        let series = self
+            .series
+            .into_iter()
+            .map(|(metric, mut points)| {
+                points.sort_by_key(|point| point.at);
+                Series {
+                    unit: metric.unit().to_string(),
+                    higher_is_better: metric.higher_is_better(),
+                    metric,
+                    points,
+                }
+            })
+            .collect();
+        let kernels = self
+            .latest
+            .into_iter()
+            .map(|(version, latest)| KernelSummary {
+                version,
+                profile: latest.profile,
+                values: latest
+                    .values
+                    .into_iter()
+                    .map(|(metric, (_, value))| (metric_key(&metric), value))
+                    .collect(),
+            })
+            .collect();
+        Dashboard { series, kernels }
+    }
+}
+
+/// Flat column name for the comparison table, e.g. `benchmark:kernel-compile`
+pub fn metric_key(metric: &Metric) -> String {
+    let load = |load: &LatencyLoad| match load {
+        LatencyLoad::Idle => "idle",
+        LatencyLoad::Stress => "stress",
+    };
+    match metric {
+        Metric::BuildDuration => "build-duration".to_string(),
+        Metric::ImageSize => "image-size".to_string(),
+        Metric::BootKernel => "boot-kernel".to_string(),
+        Metric::BootUserspace => "boot-userspace".to_string(),
+        Metric::BootTotal => "boot-total".to_string(),
+        Metric::Benchmark(name) => format!("benchmark:{}", name),
+        Metric::LatencyP99(kind) => format!("latency-p99:{}", load(kind)),
+        Metric::LatencyScore(kind) => format!("latency-score:{}", load(kind)),
+        Metric::AverageFps(name) => format!("fps:{}", name),
+        Metric::LowFps(name) => format!("fps-low:{}", name),
+    }
+}
@@ -0,0 +1,243 @@
+// src-tauri/src/core/distro.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::privilege::PrivilegedAction;
+use super::system::SystemFacade;
+
+const OS_RELEASE: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+/// Fields of os-release used to pick an install template
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsRelease {
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub name: String,
+    pub version_id: Option<String>,
+}
+
+impl OsRelease {
+    /// Reads /etc/os-release, falling back to /usr/lib/os-release
+    pub fn detect(system: &dyn SystemFacade) -> Result<Self> {
+        let text = OS_RELEASE
+            .iter()
+            .find_map(|path| system.read_to_string(Path::new(path)).ok())
+            .context("reading os-release")?;
+        Ok(OsRelease::parse(&text))
+    }
+
+    /// Parses the KEY=value format, unquoting values
+    pub fn parse(text: &str) -> Self {
+        let mut release = OsRelease::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            let value = value.trim_matches(|c| c == '"' || c == '\'');
+            match key {
+                "ID" => release.id = value.to_string(),
+                "ID_LIKE" => {
+                    release.id_like = value.split_whitespace().map(str::to_string).collect()
+                }
+                "NAME" => release.name = value.to_string(),
+                "VERSION_ID" => release.version_id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        release
+    }
+
+    /// Whether the distro is `id` or declares itself like it
+    pub fn is_like(&self, id: &str) -> bool {
+        self.id == id || self.id_like.iter().any(|like| like == id)
+    }
+}
+
+/// Build outputs an install template places
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelArtifacts {
+    /// bzImage from the build tree
+    pub image: PathBuf,
+    pub system_map: Option<PathBuf>,
+    pub config: Option<PathBuf>,
+}
+
+/// Where and how a distro's tooling expects kernels to be installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallTemplate {
+    /// /boot/vmlinuz-<pkgbase> with a mkinitcpio preset
+    Arch,
+    /// kernel-install from /lib/modules, producing BLS entries and a dracut initramfs
+    Fedora,
+    /// /boot/vmlinuz-<version>, update-initramfs and update-grub
+    Debian,
+    /// Files in /boot only; initramfs and boot entry are left to the bootloader step
+    Generic,
+}
+
+impl InstallTemplate {
+    /// Template matching the running distro or one it derives from
+    pub fn for_os(release: &OsRelease) -> Self {
+        if release.is_like("arch") {
+            InstallTemplate::Arch
+        } else if release.is_like("fedora") || release.is_like("rhel") {
+            InstallTemplate::Fedora
+        } else if release.is_like("debian") || release.is_like("ubuntu") {
+            InstallTemplate::Debian
+        } else {
+            InstallTemplate::Generic
+        }
+    }
+
+    /// Detects the template from os-release, Generic when it cannot be read
+    pub fn detect(system: &dyn SystemFacade) -> Self {
+        OsRelease::detect(system)
+            .map(|release| InstallTemplate::for_os(&release))
+            .unwrap_or(InstallTemplate::Generic)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InstallTemplate::Arch => "arch",
+            InstallTemplate::Fedora => "fedora",
+            InstallTemplate::Debian => "debian",
+            InstallTemplate::Generic => "generic",
+        }
+    }
+
+    /// Whether the template's tooling also creates the boot entry
+    pub fn manages_boot_entry(self) -> bool {
+        matches!(self, InstallTemplate::Fedora | InstallTemplate::Debian)
+    }
+
+    /// Installed kernel image
+    pub fn kernel_path(self, version: &str) -> PathBuf {
+        match self {
+            InstallTemplate::Arch => {
+                PathBuf::from(format!("/boot/vmlinuz-{}", arch_pkgbase(version)))
+            }
+            InstallTemplate::Fedora => PathBuf::from(format!("/lib/modules/{}/vmlinuz", version)),
+            InstallTemplate::Debian | InstallTemplate::Generic => {
+                PathBuf::from(format!("/boot/vmlinuz-{}", version))
+            }
+        }
+    }
+
+    /// Initramfs the template's tooling generates, None when it creates none
+    pub fn initramfs_path(self, version: &str) -> Option<PathBuf> {
+        match self {
+            InstallTemplate::Arch => Some(PathBuf::from(format!(
+                "/boot/initramfs-{}.img",
+                arch_pkgbase(version)
+            ))),
+            InstallTemplate::Fedora => {
+                Some(PathBuf::from(format!("/boot/initramfs-{}.img", version)))
+            }
+            InstallTemplate::Debian => Some(PathBuf::from(format!("/boot/initrd.img-{}", version))),
+            InstallTemplate::Generic => None,
+        }
+    }
+
+    /// Build outputs and their destinations
+    pub fn files(self, version: &str, artifacts: &KernelArtifacts) -> Vec<(PathBuf, PathBuf)> {
+        let mut files = vec![(artifacts.image.clone(), self.kernel_path(version))];
+        let dir = match self {
+            InstallTemplate::Fedora => PathBuf::from(format!("/lib/modules/{}", version)),
+            _ => PathBuf::from("/boot"),
+        };
+        let suffixed = |name: &str| match self {
+            InstallTemplate::Fedora => dir.join(name),
+            _ => dir.join(format!("{}-{}", name, version)),
+        };
+        if let Some(system_map) = &artifacts.system_map {
+            files.push((system_map.clone(), suffixed("System.map")));
+        }
+        if let Some(config) = &artifacts.config {
+            files.push((config.clone(), suffixed("config")));
+        }
+        files
+    }
+
+    /// Distro-specific steps run once the files are in place
+    pub fn hook_actions(self, version: &str) -> Vec<PrivilegedAction> {
+        match self {
+            InstallTemplate::Arch => {
+                let pkgbase = arch_pkgbase(version);
+                vec![
+                    PrivilegedAction::WriteFile {
+                        path: arch_preset(version),
+                        contents: format!(
+                            "# mkinitcpio preset written by KernelForge\n\
+                             ALL_kver=\"/boot/vmlinuz-{pkgbase}\"\n\
+                             PRESETS=('default')\n\
+                             default_image=\"/boot/initramfs-{pkgbase}.img\"\n"
+                        ),
+                        mode: 0o644,
+                    },
+                    run("mkinitcpio", &["-p", &pkgbase]),
+                ]
+            }
+            InstallTemplate::Fedora => vec![run(
+                "kernel-install",
+                &["add", version, &self.kernel_path(version).to_string_lossy()],
+            )],
+            InstallTemplate::Debian => vec![
+                run("update-initramfs", &["-c", "-k", version]),
+                run("update-grub", &[]),
+            ],
+            InstallTemplate::Generic => Vec::new(),
+        }
+    }
+
+    /// Reverses the hook steps; tolerant of hooks that only partly ran
+    pub fn remove_actions(self, version: &str, system: &dyn SystemFacade) -> Vec<PrivilegedAction> {
+        match self {
+            InstallTemplate::Arch => {
+                let mut actions = vec![PrivilegedAction::RemoveFile {
+                    path: arch_preset(version),
+                }];
+                if let Some(initramfs) = self.initramfs_path(version) {
+                    actions.push(PrivilegedAction::RemoveFile { path: initramfs });
+                }
+                actions
+            }
+            InstallTemplate::Fedora => vec![run("kernel-install", &["remove", version])],
+            // The image goes first so update-grub no longer lists it; update-initramfs -d
+            // fails when the install stopped before the initramfs was generated
+            InstallTemplate::Debian => {
+                let mut actions = Vec::new();
+                if self.initramfs_path(version).is_some_and(|initramfs| system.exists(&initramfs)) {
+                    actions.push(run("update-initramfs", &["-d", "-k", version]));
+                }
+                actions.push(PrivilegedAction::RemoveFile {
+                    path: self.kernel_path(version),
+                });
+                actions.push(run("update-grub", &[]));
+                actions
+            }
+            InstallTemplate::Generic => Vec::new(),
+        }
+    }
+}
+
+/// Arch package-style name, keeping each KernelForge kernel apart from distro kernels
+fn arch_pkgbase(version: &str) -> String {
+    format!("linux-kernelforge-{}", version)
+}
+
+fn arch_preset(version: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "/etc/mkinitcpio.d/{}.preset",
+        arch_pkgbase(version)
+    ))
+}
+
+fn run(program: &str, args: &[&str]) -> PrivilegedAction {
+    PrivilegedAction::Run {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    }
+}
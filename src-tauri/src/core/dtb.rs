@@ -0,0 +1,82 @@
+// src-tauri/src/core/dtb.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use super::plan::{BuildPlan, TargetArch};
+
+/// Directory holding an architecture's device tree sources and, after a build, its blobs
+pub fn dts_dir(source_dir: &Path, arch: TargetArch) -> PathBuf {
+    source_dir.join(format!("arch/{}/boot/dts", arch.make_arch()))
+}
+
+/// Blobs the tree can build, named as plans select them, e.g. `broadcom/bcm2711-rpi-4-b.dtb`
+/// Listed from the .dts sources, so it works before the first build
+pub fn available_dtbs(source_dir: &Path, arch: TargetArch) -> Result<Vec<String>> {
+    if !arch.uses_device_trees() {
+        bail!("{} kernels do not use device trees", arch.make_arch());
+    }
+    let root = dts_dir(source_dir, arch);
+    let mut sources = Vec::new();
+    find_sources(&root, &mut sources)?;
+    let mut names: Vec<String> = sources
+        .iter()
+        .filter_map(|source| source.strip_prefix(&root).ok())
+        .map(|relative| {
+            relative
+                .with_extension("dtb")
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+fn find_sources(dir: &Path, found: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_sources(&path, found)?;
+        } else if path.extension().is_some_and(|ext| ext == "dts") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Built blobs of the plan's selection, failing when one is missing
+pub fn selected_blobs(plan: &BuildPlan, source_dir: &Path) -> Result<Vec<PathBuf>> {
+    let root = dts_dir(source_dir, plan.arch);
+    let mut blobs = Vec::new();
+    for dtb in plan.selected_dtbs() {
+        let blob = root.join(&dtb);
+        if !blob.exists() {
+            bail!("{} was not built", dtb);
+        }
+        blobs.push(blob);
+    }
+    Ok(blobs)
+}
+
+/// Copies the selected blobs to `destination/dtbs`, keeping the vendor directories
+/// bootloaders and packages expect
+pub fn stage_dtbs(plan: &BuildPlan, source_dir: &Path, destination: &Path) -> Result<Vec<PathBuf>> {
+    let root = dts_dir(source_dir, plan.arch);
+    let mut staged = Vec::new();
+    for blob in selected_blobs(plan, source_dir)? {
+        let relative = blob.strip_prefix(&root).unwrap_or(&blob);
+        let target = destination.join("dtbs").join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        }
+        fs::copy(&blob, &target).with_context(|| format!("copying {}", blob.display()))?;
+        staged.push(target);
+    }
+    Ok(staged)
+}
@@ -0,0 +1,327 @@
+// src-tauri/src/core/explain.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::Advisory;
+use super::config::{ConfigValue, KernelConfig};
+use super::kconfig::KconfigTree;
+use super::plan::BuildPlan;
+use super::resolver::ConfigLayer;
+use super::review::{ReviewReport, Subsystem};
+
+/// A change the report explains in words when the plan makes it
+struct Rule {
+    /// Any of these changing fires the rule, bare names
+    symbols: &'static [&'static str],
+    /// Fires when the plan enables the symbol, or when it disables it
+    enables: bool,
+    headline: &'static str,
+    why: &'static str,
+    benefits: &'static [&'static str],
+    risks: &'static [&'static str],
+}
+
+/// Hand-written explanations for the options profiles and analyzers change most
+const RULES: &[Rule] = &[
+    Rule {
+        symbols: &["HZ_1000"],
+        enables: true,
+        headline: "Timer frequency raised to 1000 Hz",
+        why: "A faster tick lets the scheduler switch tasks sooner, which games and audio notice.",
+        benefits: &["Lower input and frame latency"],
+        risks: &["Slightly more power use and timer overhead"],
+    },
+    Rule {
+        symbols: &["HZ_100"],
+        enables: true,
+        headline: "Timer frequency lowered to 100 Hz",
+        why: "Fewer ticks leave more CPU time for long-running work.",
+        benefits: &["Better throughput for batch and server loads"],
+        risks: &["Desktops feel less responsive under load"],
+    },
+    Rule {
+        symbols: &["PREEMPT"],
+        enables: true,
+        headline: "Fully preemptible kernel",
+        why: "Kernel code can be interrupted almost anywhere so interactive tasks run sooner.",
+        benefits: &["Smoother desktop and fewer audio dropouts"],
+        risks: &["A few percent less throughput on heavy workloads"],
+    },
+    Rule {
+        symbols: &["PREEMPT_NONE"],
+        enables: true,
+        headline: "No forced preemption",
+        why: "Tasks keep the CPU until they yield, the classic server setting.",
+        benefits: &["Highest throughput"],
+        risks: &["Long latency spikes on interactive use"],
+    },
+    Rule {
+        symbols: &["PREEMPT_RT"],
+        enables: true,
+        headline: "Real-time preemption",
+        why: "Interrupts run in threads and spinlocks sleep, so deadlines can be bounded.",
+        benefits: &["Predictable worst-case latency"],
+        risks: &[
+            "Lower throughput",
+            "Out-of-tree modules such as NVIDIA's may not build",
+        ],
+    },
+    Rule {
+        symbols: &["NO_HZ_FULL"],
+        enables: true,
+        headline: "Tickless operation on isolated CPUs",
+        why: "CPUs running a single task can stop their timer tick entirely.",
+        benefits: &["Less jitter for pinned game or RT threads"],
+        risks: &["Small syscall overhead on every CPU, even without nohz_full="],
+    },
+    Rule {
+        symbols: &["SCHED_BORE"],
+        enables: true,
+        headline: "BORE scheduler",
+        why: "Burst-oriented response enhancer favours tasks that run in short bursts, like UI threads.",
+        benefits: &["Snappier desktop and games under background load"],
+        risks: &["Needs the BORE patch, which is not in mainline"],
+    },
+    Rule {
+        symbols: &["TRANSPARENT_HUGEPAGE"],
+        enables: true,
+        headline: "Transparent huge pages",
+        why: "Large memory users get 2 MiB pages without asking for them.",
+        benefits: &["Fewer TLB misses for games, VMs and databases"],
+        risks: &["Occasional compaction stalls and higher memory use"],
+    },
+    Rule {
+        symbols: &["CPU_FREQ_DEFAULT_GOV_PERFORMANCE"],
+        enables: true,
+        headline: "Performance CPU governor by default",
+        why: "Cores run at their highest frequency instead of ramping up on demand.",
+        benefits: &["No frequency ramp-up delay"],
+        risks: &["More power draw, heat and fan noise, worse battery life"],
+    },
+    Rule {
+        symbols: &["KASAN", "UBSAN", "PROVE_LOCKING", "KCOV"],
+        enables: true,
+        headline: "Kernel sanitizers and lock checking",
+        why: "Memory, undefined-behaviour and locking bugs are caught as they happen.",
+        benefits: &["Bugs are reported with a precise trace"],
+        risks: &["Several times slower and much larger, never for daily use"],
+    },
+    Rule {
+        symbols: &["RANDOMIZE_BASE"],
+        enables: false,
+        headline: "Kernel address randomisation off",
+        why: "A fixed kernel address keeps crash traces and fuzzing reproducible.",
+        benefits: &["Reproducible addresses in oopses"],
+        risks: &["Exploits no longer have to guess where the kernel is"],
+    },
+    Rule {
+        symbols: &["CPU_MITIGATIONS", "SPECULATION_MITIGATIONS"],
+        enables: false,
+        headline: "CPU vulnerability mitigations removed",
+        why: "Spectre-class mitigations cost performance on older CPUs.",
+        benefits: &["Faster syscalls and context switches"],
+        risks: &["Untrusted code, including browser JavaScript, can read kernel memory"],
+    },
+    Rule {
+        symbols: &["LTO_CLANG_THIN", "LTO_CLANG_FULL"],
+        enables: true,
+        headline: "Link-time optimisation",
+        why: "Clang optimises across source files when linking the kernel.",
+        benefits: &["Slightly faster and smaller kernel"],
+        risks: &["Needs Clang and LLVM, and builds take longer"],
+    },
+    Rule {
+        symbols: &["MODULE_SIG"],
+        enables: false,
+        headline: "Module signing off",
+        why: "Modules built later, such as DKMS drivers, load without a signing key.",
+        benefits: &["Out-of-tree modules work without key setup"],
+        risks: &["Secure Boot lockdown refuses unsigned modules"],
+    },
+];
+
+/// One major change, why it was made and what it means
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Explanation {
+    pub headline: String,
+    pub why: String,
+    pub benefits: Vec<String>,
+    pub risks: Vec<String>,
+    /// Options behind the change, bare names
+    pub symbols: Vec<String>,
+    /// Layers that asked for it, e.g. `gaming`; empty when it follows from dependencies
+    pub layers: Vec<String>,
+}
+
+/// Changes no rule covers, counted per subsystem
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OtherChanges {
+    pub enabled: usize,
+    pub disabled: usize,
+    pub changed: usize,
+}
+
+/// Plain-language report on what a plan changes against the running kernel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanExplanation {
+    pub plan: String,
+    pub version: String,
+    /// Total options that differ from the running kernel
+    pub changed_options: usize,
+    pub changes: Vec<Explanation>,
+    pub other: BTreeMap<Subsystem, OtherChanges>,
+    /// Patch names, applied on top of the source
+    pub patches: Vec<String>,
+    /// Findings of the analyzers, e.g. compiler flags
+    pub advisories: Vec<Advisory>,
+    /// Options kept for software on this machine that pruning would otherwise drop
+    #[serde(default)]
+    pub kept: Vec<Explanation>,
+}
+
+impl PlanExplanation {
+    /// Explains `plan` against `current`, the config of the running kernel
+    /// `layers` name who set each option; the tree adds option metadata when available
+    pub fn build(
+        plan: &BuildPlan,
+        current: &KernelConfig,
+        tree: Option<&KconfigTree>,
+        layers: &[ConfigLayer],
+    ) -> Self {
+        let review = ReviewReport::build(&plan.name, current, &plan.config, tree, layers);
+        let mut explained = BTreeSet::new();
+        let mut changes = Vec::new();
+        for rule in RULES {
+            let matching: Vec<_> = review
+                .sections
+                .values()
+                .flatten()
+                .filter(|deviation| {
+                    // Plans list only what they set, so an absent option is not a removal
+                    rule.symbols.contains(&deviation.symbol.as_str())
+                        && planned_enabled(deviation.planned.as_deref()) == rule.enables
+                        && plan.config.get(&deviation.symbol).is_some()
+                })
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+            let mut symbols = Vec::new();
+            let mut origins = BTreeSet::new();
+            for deviation in matching {
+                explained.insert(deviation.symbol.clone());
+                symbols.push(deviation.symbol.clone());
+                origins.extend(deviation.layer.clone());
+            }
+            changes.push(Explanation {
+                headline: rule.headline.to_string(),
+                why: rule.why.to_string(),
+                benefits: rule.benefits.iter().map(|text| text.to_string()).collect(),
+                risks: rule.risks.iter().map(|text| text.to_string()).collect(),
+                symbols,
+                layers: origins.into_iter().collect(),
+            });
+        }
+
+        let mut other: BTreeMap<Subsystem, OtherChanges> = BTreeMap::new();
+        for (subsystem, deviations) in &review.sections {
+            for deviation in deviations {
+                if explained.contains(&deviation.symbol) {
+                    continue;
+                }
+                let counts = other.entry(*subsystem).or_default();
+                match (
+                    planned_enabled(deviation.distro.as_deref()),
+                    planned_enabled(deviation.planned.as_deref()),
+                ) {
+                    (false, true) => counts.enabled += 1,
+                    (true, false) => counts.disabled += 1,
+                    _ => counts.changed += 1,
+                }
+            }
+        }
+
+        PlanExplanation {
+            plan: plan.name.clone(),
+            version: plan.version.clone(),
+            changed_options: review.len(),
+            changes,
+            other,
+            patches: plan
+                .patches
+                .iter()
+                .map(|patch| patch.name.clone())
+                .collect(),
+            advisories: plan.compiler_flags.advisories(),
+            kept: Vec::new(),
+        }
+    }
+
+    /// Records options the plan keeps for software detected on the machine
+    pub fn keep(&mut self, explanation: Explanation) {
+        self.kept.push(explanation);
+    }
+
+    /// Renders the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# What {} changes\n", self.plan);
+        let _ = writeln!(
+            out,
+            "Linux {} with {} options set differently from the running kernel.",
+            self.version, self.changed_options
+        );
+        if !self.patches.is_empty() {
+            let _ = writeln!(out, "Patches applied: {}.", self.patches.join(", "));
+        }
+        if !self.changes.is_empty() {
+            let _ = writeln!(out, "\n## Major changes");
+        }
+        for change in &self.changes {
+            let _ = writeln!(out, "\n### {}\n", change.headline);
+            let _ = write!(out, "{}", change.why);
+            if !change.layers.is_empty() {
+                let _ = write!(out, " Requested by: {}.", change.layers.join(", "));
+            }
+            let _ = writeln!(out);
+            for benefit in &change.benefits {
+                let _ = writeln!(out, "- Benefit: {}", benefit);
+            }
+            for risk in &change.risks {
+                let _ = writeln!(out, "- Risk: {}", risk);
+            }
+        }
+        if !self.other.is_empty() {
+            let _ = writeln!(out, "\n## Other changes\n");
+        }
+        for (subsystem, counts) in &self.other {
+            let _ = writeln!(
+                out,
+                "- {}: {} enabled, {} disabled, {} changed",
+                subsystem.title(),
+                counts.enabled,
+                counts.disabled,
+                counts.changed
+            );
+        }
+        if !self.kept.is_empty() {
+            let _ = writeln!(out, "\n## Kept for this machine");
+        }
+        for kept in &self.kept {
+            let _ = writeln!(out, "\n### {}\n", kept.headline);
+            let _ = writeln!(out, "{} Options: {}.", kept.why, kept.symbols.join(", "));
+            for benefit in &kept.benefits {
+                let _ = writeln!(out, "- Benefit: {}", benefit);
+            }
+        }
+        out
+    }
+}
+
+/// Whether a raw value, as the review reports it, turns the option on
+fn planned_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|raw| ConfigValue::Set(raw.to_string()).is_enabled())
+}
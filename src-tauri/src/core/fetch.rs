@@ -0,0 +1,261 @@
+// src-tauri/src/core/fetch.rs
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+#[cfg(feature = "torrent")]
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use reqwest::header::RANGE;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::cache::{CacheCategory, CacheEntry, CacheIndex, SignatureStatus};
+use super::mirror::Mirror;
+#[cfg(feature = "torrent")]
+use super::system::SystemFacade;
+
+/// Future returned by fetchers, resolving to the downloaded file
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<PathBuf>> + Send + 'a>>;
+
+/// A file to download, `path` being relative to the mirror root, e.g. `v6.x/linux-6.9.tar.xz`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchRequest {
+    pub path: String,
+    /// Digest published alongside the file, checked after download
+    pub sha256: Option<String>,
+}
+
+impl FetchRequest {
+    pub fn new(path: &str) -> Self {
+        FetchRequest {
+            path: path.to_string(),
+            sha256: None,
+        }
+    }
+
+    /// Name of the file once downloaded
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+/// Download progress reported to the UI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FetchProgress {
+    Started { backend: String, total: Option<u64> },
+    Bytes { received: u64, total: Option<u64> },
+    Finished,
+}
+
+/// A way of obtaining kernel files; every backend feeds the same verification afterwards
+pub trait Fetcher: Send + Sync {
+    /// Short backend name shown in progress and logs
+    fn name(&self) -> &'static str;
+
+    /// Downloads `request` into `dir`, returning the path of the complete file
+    fn fetch<'a>(
+        &'a self,
+        request: &'a FetchRequest,
+        dir: &'a Path,
+        progress: &'a UnboundedSender<FetchProgress>,
+    ) -> FetchFuture<'a>;
+}
+
+/// Plain HTTPS download from a mirror, resuming partial files with range requests
+#[derive(Debug, Clone)]
+pub struct HttpsFetcher {
+    client: Client,
+    mirror: Mirror,
+}
+
+impl HttpsFetcher {
+    pub fn new(client: Client, mirror: Mirror) -> Self {
+        HttpsFetcher { client, mirror }
+    }
+
+    async fn download(
+        &self,
+        request: &FetchRequest,
+        dir: &Path,
+        progress: &UnboundedSender<FetchProgress>,
+    ) -> Result<PathBuf> {
+        let destination = dir.join(request.file_name());
+        let partial = dir.join(format!("{}.part", request.file_name()));
+        let url = self.mirror.join(&request.path);
+
+        let mut received = fs::metadata(&partial)
+            .await
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        let mut builder = self.client.get(&url);
+        if received > 0 {
+            builder = builder.header(RANGE, format!("bytes={}-", received));
+        }
+        let mut response = builder
+            .send()
+            .await
+            .with_context(|| format!("requesting {}", url))?;
+        let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            response = response
+                .error_for_status()
+                .with_context(|| format!("downloading {}", url))?;
+            received = 0;
+        }
+        let total = response.content_length().map(|length| length + received);
+        let _ = progress.send(FetchProgress::Started {
+            backend: self.name().to_string(),
+            total,
+        });
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&partial)
+            .await
+            .with_context(|| format!("opening {}", partial.display()))?;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .with_context(|| format!("downloading {}", url))?
+        {
+            file.write_all(&chunk)
+                .await
+                .with_context(|| format!("writing {}", partial.display()))?;
+            received += chunk.len() as u64;
+            let _ = progress.send(FetchProgress::Bytes { received, total });
+        }
+        file.flush().await?;
+        drop(file);
+
+        fs::rename(&partial, &destination)
+            .await
+            .with_context(|| format!("renaming {}", partial.display()))?;
+        let _ = progress.send(FetchProgress::Finished);
+        Ok(destination)
+    }
+}
+
+impl Fetcher for HttpsFetcher {
+    fn name(&self) -> &'static str {
+        "https"
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        request: &'a FetchRequest,
+        dir: &'a Path,
+        progress: &'a UnboundedSender<FetchProgress>,
+    ) -> FetchFuture<'a> {
+        Box::pin(self.download(request, dir, progress))
+    }
+}
+
+/// Downloads through the `.torrent` published next to a tarball, using aria2c on the host
+#[cfg(feature = "torrent")]
+#[derive(Clone)]
+pub struct TorrentFetcher {
+    system: Arc<dyn SystemFacade>,
+    mirror: Mirror,
+}
+
+#[cfg(feature = "torrent")]
+impl TorrentFetcher {
+    pub fn new(system: Arc<dyn SystemFacade>, mirror: Mirror) -> Self {
+        TorrentFetcher { system, mirror }
+    }
+
+    /// Whether aria2c is installed
+    pub fn available(&self) -> bool {
+        self.system.has_program("aria2c")
+    }
+}
+
+#[cfg(feature = "torrent")]
+impl Fetcher for TorrentFetcher {
+    fn name(&self) -> &'static str {
+        "torrent"
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        request: &'a FetchRequest,
+        dir: &'a Path,
+        progress: &'a UnboundedSender<FetchProgress>,
+    ) -> FetchFuture<'a> {
+        Box::pin(async move {
+            let torrent = self.mirror.join(&format!("{}.torrent", request.path));
+            let dir_arg = dir.to_string_lossy().into_owned();
+            let system = Arc::clone(&self.system);
+            let _ = progress.send(FetchProgress::Started {
+                backend: self.name().to_string(),
+                total: None,
+            });
+            // Seeding stops as soon as the download completes
+            let output = tokio::task::spawn_blocking(move || {
+                system.run(
+                    "aria2c",
+                    &[
+                        "--seed-time=0",
+                        "--follow-torrent=mem",
+                        "--summary-interval=0",
+                        "--dir",
+                        &dir_arg,
+                        &torrent,
+                    ],
+                )
+            })
+            .await
+            .context("waiting for aria2c")?
+            .context("running aria2c")?;
+            if !output.success() {
+                bail!("aria2c failed: {}", output.stderr.trim());
+            }
+            let destination = dir.join(request.file_name());
+            if !destination.exists() {
+                bail!("torrent did not produce {}", destination.display());
+            }
+            let _ = progress.send(FetchProgress::Finished);
+            Ok(destination)
+        })
+    }
+}
+
+/// Fetches into the cache with any backend, then records and verifies the file
+/// A digest mismatch deletes the download so a corrupt file is never reused
+pub async fn fetch_into_cache(
+    fetcher: &dyn Fetcher,
+    index: &mut CacheIndex,
+    category: CacheCategory,
+    request: &FetchRequest,
+    progress: &UnboundedSender<FetchProgress>,
+) -> Result<CacheEntry> {
+    let dir = index.category_dir(category)?;
+    let file = fetcher
+        .fetch(request, &dir, progress)
+        .await
+        .with_context(|| format!("fetching {} via {}", request.path, fetcher.name()))?;
+    let entry = index
+        .record(&file, category, SignatureStatus::Unchecked)?
+        .clone();
+    if let Some(expected) = &request.sha256 {
+        if !entry.sha256.eq_ignore_ascii_case(expected) {
+            index.forget(&file)?;
+            fs::remove_file(&file).await.ok();
+            bail!(
+                "checksum mismatch for {}: expected {}, got {}",
+                request.path,
+                expected,
+                entry.sha256
+            );
+        }
+    }
+    Ok(entry)
+}
@@ -0,0 +1,385 @@
+// src-tauri/src/core/fleet.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::cache::sha256_file;
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::state_file;
+use super::system::SystemFacade;
+
+/// Creates a directory only the deploying user can write on the target, for the upload
+const REMOTE_STAGING: &str = "mktemp -d /var/tmp/kernelforge.XXXXXXXX";
+
+/// Runs as root ahead of the install script with the staged file as `$1` and its sha256 as
+/// `$2`: copies the file where the deploying user cannot change it, checks the hash on that
+/// copy and hands the copy to the install script as `$1`
+const VERIFY_STAGED: &str = r#"staged=$1
+dir=$(mktemp -d) || exit 1
+trap 'rm -rf "$dir" "$(dirname "$staged")"' EXIT
+copy="$dir/$(basename "$staged")"
+cat -- "$staged" > "$copy" || exit 1
+printf '%s  %s\n' "$2" "$copy" | sha256sum -c --status - || { echo "$staged does not match its sha256" >&2; exit 1; }
+set -- "$copy"
+"#;
+
+/// A machine kernels are pushed to over SSH
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FleetHost {
+    /// Label shown in the UI, unique within the inventory
+    pub name: String,
+    /// `user@host` or an alias from ~/.ssh/config
+    pub address: String,
+    pub port: Option<u16>,
+    pub identity: Option<PathBuf>,
+}
+
+impl FleetHost {
+    /// Refuses addresses ssh would parse as an option
+    fn check_address(&self) -> Result<()> {
+        if self.address.is_empty() || self.address.starts_with('-') {
+            bail!("{} has an invalid address {:?}", self.name, self.address);
+        }
+        Ok(())
+    }
+
+    fn ssh_options(&self, port_flag: &str) -> Vec<String> {
+        let mut options = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "ConnectTimeout=15".to_string(),
+        ];
+        if let Some(port) = self.port {
+            options.push(port_flag.to_string());
+            options.push(port.to_string());
+        }
+        if let Some(identity) = &self.identity {
+            options.push("-i".to_string());
+            options.push(identity.to_string_lossy().to_string());
+        }
+        options
+    }
+}
+
+/// Hosts the user manages, kept with the settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FleetInventory {
+    pub hosts: Vec<FleetHost>,
+}
+
+impl FleetInventory {
+    pub fn load(paths: &Paths) -> Result<Self> {
+        let path = paths.fleet_inventory_path();
+        if !path.exists() {
+            return Ok(FleetInventory::default());
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.config_dir())?;
+        state_file::write(
+            &paths.fleet_inventory_path(),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    pub fn host(&self, name: &str) -> Option<&FleetHost> {
+        self.hosts.iter().find(|host| host.name == name)
+    }
+}
+
+/// What gets deployed, which decides how the target installs it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArtifactKind {
+    Deb,
+    Rpm,
+    /// Arch package, installed with pacman
+    Pacman,
+    /// Unified kernel image placed in the ESP, picked up by systemd-boot
+    Uki,
+}
+
+impl ArtifactKind {
+    pub fn of(path: &Path) -> Result<Self> {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.ends_with(".deb") {
+            Ok(ArtifactKind::Deb)
+        } else if name.ends_with(".rpm") {
+            Ok(ArtifactKind::Rpm)
+        } else if name.ends_with(".pkg.tar.zst") || name.ends_with(".pkg.tar.xz") {
+            Ok(ArtifactKind::Pacman)
+        } else if name.ends_with(".efi") {
+            Ok(ArtifactKind::Uki)
+        } else {
+            bail!("{} is not a package or unified kernel image", name)
+        }
+    }
+
+    /// Script run as root on the target with the staged file as `$1`
+    fn install_script(self) -> &'static str {
+        match self {
+            ArtifactKind::Deb => {
+                "apt-get install -y \"$1\" || dpkg -i \"$1\"; status=$?; rm -f \"$1\"; exit $status"
+            }
+            ArtifactKind::Rpm => {
+                "if command -v dnf >/dev/null; then dnf install -y \"$1\"; \
+                 elif command -v zypper >/dev/null; then zypper --non-interactive install \"$1\"; \
+                 else rpm -Uvh \"$1\"; fi; status=$?; rm -f \"$1\"; exit $status"
+            }
+            ArtifactKind::Pacman => {
+                "pacman -U --noconfirm \"$1\"; status=$?; rm -f \"$1\"; exit $status"
+            }
+            // Type #2 entries in EFI/Linux need no loader entry; grub setups get regenerated
+            ArtifactKind::Uki => {
+                "esp=$(bootctl --print-esp-path 2>/dev/null || echo /boot/efi); \
+                 install -D -m 0644 \"$1\" \"$esp/EFI/Linux/$(basename \"$1\")\" || exit $?; \
+                 rm -f \"$1\"; \
+                 if command -v update-grub >/dev/null; then update-grub; \
+                 elif command -v grub-mkconfig >/dev/null && [ -d /boot/grub ]; then grub-mkconfig -o /boot/grub/grub.cfg; \
+                 elif command -v grub2-mkconfig >/dev/null && [ -d /boot/grub2 ]; then grub2-mkconfig -o /boot/grub2/grub.cfg; fi"
+            }
+        }
+    }
+}
+
+/// How hosts are grouped into waves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RolloutPolicy {
+    /// Hosts in the first wave, deployed alone before anything else
+    pub canaries: usize,
+    /// Hosts per wave after the canaries
+    pub wave_size: usize,
+}
+
+impl Default for RolloutPolicy {
+    fn default() -> Self {
+        RolloutPolicy {
+            canaries: 1,
+            wave_size: 3,
+        }
+    }
+}
+
+impl RolloutPolicy {
+    /// Splits hosts into waves, canaries first
+    pub fn waves(&self, hosts: &[String]) -> Vec<Vec<String>> {
+        let canaries = self.canaries.min(hosts.len());
+        let mut waves = Vec::new();
+        if canaries > 0 {
+            waves.push(hosts[..canaries].to_vec());
+        }
+        for chunk in hosts[canaries..].chunks(self.wave_size.max(1)) {
+            waves.push(chunk.to_vec());
+        }
+        waves
+    }
+}
+
+/// Progress of one host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostStatus {
+    Pending,
+    Copying,
+    Installing,
+    Installed {
+        at: u64,
+    },
+    Failed {
+        error: String,
+    },
+    /// Not attempted because an earlier wave failed
+    Skipped,
+}
+
+/// One rollout of one artifact, persisted after every status change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FleetDeployment {
+    pub id: String,
+    pub artifact: PathBuf,
+    pub kind: ArtifactKind,
+    pub started_at: u64,
+    pub policy: RolloutPolicy,
+    pub waves: Vec<Vec<String>>,
+    pub hosts: BTreeMap<String, HostStatus>,
+    #[serde(skip)]
+    state_path: PathBuf,
+}
+
+impl FleetDeployment {
+    /// Plans a rollout of `artifact` to the named hosts of the inventory
+    pub fn plan(
+        paths: &Paths,
+        inventory: &FleetInventory,
+        artifact: &Path,
+        hosts: &[String],
+        policy: RolloutPolicy,
+    ) -> Result<Self> {
+        let kind = ArtifactKind::of(artifact)?;
+        for name in hosts {
+            inventory
+                .host(name)
+                .with_context(|| format!("{} is not in the fleet inventory", name))?
+                .check_address()?;
+        }
+        let started_at = unix_now();
+        let id = format!(
+            "{}-{}",
+            started_at,
+            artifact.file_name().unwrap_or_default().to_string_lossy()
+        );
+        let dir = Paths::ensure(paths.fleet_dir())?;
+        let deployment = FleetDeployment {
+            state_path: dir.join(format!("{}.json", id)),
+            id,
+            artifact: artifact.to_path_buf(),
+            kind,
+            started_at,
+            waves: policy.waves(hosts),
+            policy,
+            hosts: hosts
+                .iter()
+                .map(|name| (name.clone(), HostStatus::Pending))
+                .collect(),
+        };
+        deployment.save()?;
+        Ok(deployment)
+    }
+
+    /// Loads a persisted rollout
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut deployment: FleetDeployment =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        deployment.state_path = path.to_path_buf();
+        Ok(deployment)
+    }
+
+    pub fn failures(&self) -> usize {
+        self.hosts
+            .values()
+            .filter(|status| matches!(status, HostStatus::Failed { .. }))
+            .count()
+    }
+
+    /// Deploys wave by wave; a wave starts only once every host of the previous one is
+    /// installed, otherwise the remaining waves are skipped
+    /// Hosts already installed are left alone, so a stopped rollout can be run again
+    pub fn run(&mut self, system: &dyn SystemFacade, inventory: &FleetInventory) -> Result<()> {
+        let waves = self.waves.clone();
+        let mut previous_installed = true;
+        for wave in &waves {
+            if !previous_installed {
+                for name in wave {
+                    self.set_status(name, HostStatus::Skipped)?;
+                }
+                continue;
+            }
+            for name in wave {
+                if matches!(self.hosts.get(name), Some(HostStatus::Installed { .. })) {
+                    continue;
+                }
+                let host = inventory
+                    .host(name)
+                    .with_context(|| format!("{} is not in the fleet inventory", name))?;
+                let status = match self.deploy_to(system, host) {
+                    Ok(()) => HostStatus::Installed { at: unix_now() },
+                    Err(error) => HostStatus::Failed {
+                        error: format!("{:#}", error),
+                    },
+                };
+                self.set_status(name, status)?;
+            }
+            previous_installed = wave
+                .iter()
+                .all(|name| matches!(self.hosts.get(name), Some(HostStatus::Installed { .. })));
+        }
+        Ok(())
+    }
+
+    fn deploy_to(&mut self, system: &dyn SystemFacade, host: &FleetHost) -> Result<()> {
+        host.check_address()?;
+        let file_name = self
+            .artifact
+            .file_name()
+            .context("artifact has no file name")?
+            .to_string_lossy()
+            .to_string();
+        let digest = sha256_file(&self.artifact)?;
+
+        self.set_status(&host.name, HostStatus::Copying)?;
+        let mut args = host.ssh_options("-p");
+        args.push(String::from("--"));
+        args.push(host.address.clone());
+        args.push(String::from(REMOTE_STAGING));
+        let staging = run_checked(system, "ssh", &args)?.trim().to_string();
+        if !staging.starts_with("/var/tmp/kernelforge.") {
+            bail!(
+                "{} returned an unexpected staging directory {:?}",
+                host.name,
+                staging
+            );
+        }
+        let remote = format!("{}/{}", staging, file_name);
+        let mut args = host.ssh_options("-P");
+        args.push(String::from("--"));
+        args.push(self.artifact.to_string_lossy().to_string());
+        args.push(format!("{}:{}", host.address, remote));
+        run_checked(system, "scp", &args)?;
+
+        self.set_status(&host.name, HostStatus::Installing)?;
+        let mut args = host.ssh_options("-p");
+        args.push(String::from("--"));
+        args.push(host.address.clone());
+        // ssh joins its arguments into one remote command line, so the script is quoted once
+        args.push(format!(
+            "sudo -n sh -c {} sh {} {}",
+            shell_quote(&format!("{}{}", VERIFY_STAGED, self.kind.install_script())),
+            shell_quote(&remote),
+            digest
+        ));
+        run_checked(system, "ssh", &args).map(drop)
+    }
+
+    fn set_status(&mut self, name: &str, status: HostStatus) -> Result<()> {
+        self.hosts.insert(name.to_string(), status);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        state_file::write(
+            &self.state_path,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+}
+
+/// Runs `program` and returns its output, failing on a non-zero exit
+fn run_checked(system: &dyn SystemFacade, program: &str, args: &[String]) -> Result<String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = system
+        .run(program, &args)
+        .with_context(|| format!("running {}", program))?;
+    if !output.success() {
+        bail!(
+            "{} failed ({}): {}",
+            program,
+            output.status,
+            output.stderr.trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
@@ -0,0 +1,182 @@
+// src-tauri/src/core/fuzz.rs
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigValue, KernelConfig};
+use super::kconfig::SymbolType;
+use super::resolver::{ConfigLayer, ResolverIndex};
+use super::system::{run_spec, CommandSpec, SystemFacade};
+
+/// Small deterministic generator so a failing case can be replayed from its seed
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound.max(1)
+    }
+}
+
+/// A symbol the kernel's tooling settled differently than the engine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mismatch {
+    pub symbol: String,
+    /// None when the symbol is absent or not set
+    pub ours: Option<String>,
+    pub kernel: Option<String>,
+}
+
+/// Result of one randomized config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzCase {
+    pub seed: u64,
+    /// Whether parsing the emitted config and emitting it again gave the same text
+    pub emit_stable: bool,
+    /// Differences after `make olddefconfig` rewrote the emitted config
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl FuzzCase {
+    pub fn passed(&self) -> bool {
+        self.emit_stable && self.mismatches.is_empty()
+    }
+}
+
+/// Developer mode that throws random but valid configs at the Kconfig engine and checks
+/// its output against the kernel's own olddefconfig, to catch resolver drift
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigFuzzer {
+    pub seed: u64,
+    /// Share of user-visible bool/tristate symbols given a random request, in percent
+    pub toggle_percent: u8,
+}
+
+impl ConfigFuzzer {
+    pub fn new(seed: u64) -> Self {
+        ConfigFuzzer {
+            seed,
+            toggle_percent: 20,
+        }
+    }
+
+    /// Random requests resolved through the dependency graph, so every result is a config
+    /// the kernel would accept
+    pub fn generate(&self, index: &ResolverIndex, case_seed: u64) -> KernelConfig {
+        let tree = index.tree();
+        let mut random = SplitMix64(case_seed);
+        let mut layer = ConfigLayer::new("fuzz");
+        for def in tree.defs() {
+            if def.prompt.is_none() || random.below(100) >= u64::from(self.toggle_percent) {
+                continue;
+            }
+            let choices: &[&str] = match def.kind {
+                SymbolType::Bool => &["y", "n"],
+                SymbolType::Tristate => &["y", "m", "n"],
+                _ => continue,
+            };
+            let value = choices[random.below(choices.len() as u64) as usize];
+            let name = tree.symbols().name(def.id);
+            layer.set(
+                name,
+                if value == "n" {
+                    ConfigValue::NotSet
+                } else {
+                    ConfigValue::Set(value.to_string())
+                },
+            );
+        }
+        index.resolve(&[layer]).to_config(tree)
+    }
+
+    /// Generates `cases` configs and round-trips each through emit/parse and olddefconfig
+    /// `build_dir` is used as the O= output directory, so the source tree stays clean
+    pub fn run(
+        &self,
+        system: &dyn SystemFacade,
+        index: &ResolverIndex,
+        source_dir: &Path,
+        build_dir: &Path,
+        cases: u32,
+    ) -> Result<Vec<FuzzCase>> {
+        let mut seeds = SplitMix64(self.seed);
+        let mut results = Vec::new();
+        for _ in 0..cases {
+            let seed = seeds.next();
+            let config = self.generate(index, seed);
+            results.push(round_trip(system, seed, &config, source_dir, build_dir)?);
+        }
+        Ok(results)
+    }
+}
+
+/// Checks `config`, generated from `seed`, survives emit/parse unchanged and olddefconfig
+/// keeps every value
+pub fn round_trip(
+    system: &dyn SystemFacade,
+    seed: u64,
+    config: &KernelConfig,
+    source_dir: &Path,
+    build_dir: &Path,
+) -> Result<FuzzCase> {
+    let emitted = config.emit();
+    let emit_stable = KernelConfig::parse(&emitted)
+        .map(|parsed| parsed.emit() == emitted)
+        .unwrap_or(false);
+
+    fs::create_dir_all(build_dir).with_context(|| format!("creating {}", build_dir.display()))?;
+    let dot_config = build_dir.join(".config");
+    fs::write(&dot_config, &emitted)
+        .with_context(|| format!("writing {}", dot_config.display()))?;
+    let output_dir = format!("O={}", build_dir.display());
+    let mut spec = CommandSpec::new("make", &[&output_dir, "olddefconfig"]);
+    spec.cwd = Some(source_dir.to_path_buf());
+    let output = run_spec(&system.prepare_command(spec)).context("running make olddefconfig")?;
+    if !output.success() {
+        bail!("make olddefconfig failed: {}", output.stderr.trim());
+    }
+    let text = fs::read_to_string(&dot_config)
+        .with_context(|| format!("reading {}", dot_config.display()))?;
+    let kernel = KernelConfig::parse(&text).context("parsing the olddefconfig output")?;
+
+    Ok(FuzzCase {
+        seed,
+        emit_stable,
+        mismatches: mismatches(config, &kernel),
+    })
+}
+
+fn mismatches(ours: &KernelConfig, kernel: &KernelConfig) -> Vec<Mismatch> {
+    // Not set and absent mean the same to Kconfig
+    let value = |config: &KernelConfig, name: &str| match config.get(name) {
+        Some(ConfigValue::Set(value)) if value != "n" => Some(value.clone()),
+        _ => None,
+    };
+    let names: BTreeSet<&str> = ours
+        .iter()
+        .chain(kernel.iter())
+        .map(|(name, _)| name)
+        .collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (ours, kernel) = (value(ours, name), value(kernel, name));
+            (ours != kernel).then(|| Mismatch {
+                symbol: name.to_string(),
+                ours,
+                kernel,
+            })
+        })
+        .collect()
+}
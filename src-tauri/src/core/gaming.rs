@@ -0,0 +1,236 @@
+// src-tauri/src/core/gaming.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::clock::unix_now;
+use super::results::KernelTag;
+use super::system::{CommandSpec, SystemFacade};
+
+/// A user-supplied game or demo that runs a fixed scene and exits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameBenchmark {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Wrap the command in gamemoderun when it is installed
+    #[serde(default)]
+    pub gamemode: bool,
+    /// Stop MangoHud logging after this long; the whole run is logged otherwise
+    #[serde(default)]
+    pub log_duration_secs: Option<u64>,
+}
+
+/// Where frame times came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameSource {
+    /// A log the user recorded with MangoHud
+    MangoHudLog { path: PathBuf },
+    /// A GameBenchmark KernelForge ran, by name
+    Benchmark { name: String },
+}
+
+/// Frame rate and frame time percentiles of one capture
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub avg_fps: f64,
+    /// Frame rate matching the slowest 1% and 0.1% of frames
+    pub low_1_fps: f64,
+    pub low_01_fps: f64,
+    pub frametime_p50_ms: f64,
+    pub frametime_p99_ms: f64,
+    pub frametime_max_ms: f64,
+}
+
+impl FrameStats {
+    /// Summarizes frame times in milliseconds, None without any valid frame
+    pub fn from_frametimes(frametimes: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = frametimes
+            .iter()
+            .copied()
+            .filter(|ms| ms.is_finite() && *ms > 0.0)
+            .collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |fraction: f64| {
+            let index = ((sorted.len() as f64 * fraction).ceil() as usize).clamp(1, sorted.len());
+            sorted[index - 1]
+        };
+        let total_ms: f64 = sorted.iter().sum();
+        Some(FrameStats {
+            frames: sorted.len(),
+            avg_fps: 1000.0 * sorted.len() as f64 / total_ms,
+            low_1_fps: 1000.0 / percentile(0.99),
+            low_01_fps: 1000.0 / percentile(0.999),
+            frametime_p50_ms: percentile(0.5),
+            frametime_p99_ms: percentile(0.99),
+            frametime_max_ms: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Frame times with the kernel they were captured on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub name: String,
+    pub kernel: KernelTag,
+    pub recorded_at: u64,
+    pub source: FrameSource,
+    pub stats: FrameStats,
+}
+
+/// Contents of a MangoHud CSV log
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MangoHudLog {
+    /// Kernel MangoHud reported in the log's system header
+    pub kernel: Option<String>,
+    pub frametimes_ms: Vec<f64>,
+}
+
+impl MangoHudLog {
+    /// Parses the system info header and the `fps,frametime,...` table that follows it
+    pub fn parse(text: &str) -> Self {
+        let mut log = MangoHudLog::default();
+        let mut lines = text.lines();
+        let mut frametime_column: Option<usize> = None;
+        while let Some(line) = lines.next() {
+            let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+            if let Some(column) = frametime_column {
+                if let Some(ms) = columns
+                    .get(column)
+                    .and_then(|value| value.parse::<f64>().ok())
+                {
+                    log.frametimes_ms.push(ms);
+                }
+            } else if columns.first() == Some(&"os") {
+                // The next line holds the values for this header
+                if let (Some(index), Some(values)) = (
+                    columns.iter().position(|column| *column == "kernel"),
+                    lines.next(),
+                ) {
+                    log.kernel = values
+                        .split(',')
+                        .nth(index)
+                        .map(|kernel| kernel.trim().to_string())
+                        .filter(|kernel| !kernel.is_empty());
+                }
+            } else if columns.first() == Some(&"fps") {
+                frametime_column = columns.iter().position(|column| *column == "frametime");
+            }
+        }
+        log
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Ok(MangoHudLog::parse(&text))
+    }
+}
+
+/// Imports a MangoHud log recorded outside KernelForge
+/// Logs whose header names another kernel are refused, as they would be credited to the wrong one
+pub fn import_mangohud_log(name: &str, path: &Path, kernel: KernelTag) -> Result<GameResult> {
+    let log = MangoHudLog::read(path)?;
+    if let Some(logged) = log.kernel.as_deref() {
+        if !kernel.version.is_empty() && logged != kernel.version {
+            bail!(
+                "{} was recorded on kernel {}, not {}",
+                path.display(),
+                logged,
+                kernel.version
+            );
+        }
+    }
+    let stats = FrameStats::from_frametimes(&log.frametimes_ms)
+        .with_context(|| format!("{} has no frame times", path.display()))?;
+    Ok(GameResult {
+        name: name.to_string(),
+        kernel,
+        recorded_at: unix_now(),
+        source: FrameSource::MangoHudLog {
+            path: path.to_path_buf(),
+        },
+        stats,
+    })
+}
+
+/// Runs the benchmark with MangoHud logging into `log_dir` and summarizes the log it wrote
+pub async fn run_game_benchmark(
+    system: &dyn SystemFacade,
+    benchmark: &GameBenchmark,
+    kernel: KernelTag,
+    log_dir: &Path,
+) -> Result<GameResult> {
+    fs::create_dir_all(log_dir).with_context(|| format!("creating {}", log_dir.display()))?;
+    let before = newest_log(log_dir);
+
+    let mut mangohud_config = format!("output_folder={},autostart_log=1", log_dir.display());
+    if let Some(duration) = benchmark.log_duration_secs {
+        mangohud_config.push_str(&format!(",log_duration={}", duration));
+    }
+    let (program, mut args) = if benchmark.gamemode && system.has_program("gamemoderun") {
+        ("gamemoderun".to_string(), vec![benchmark.program.clone()])
+    } else {
+        (benchmark.program.clone(), Vec::new())
+    };
+    args.extend(benchmark.args.iter().cloned());
+    let spec = system.prepare_command(CommandSpec {
+        program,
+        args,
+        env: vec![
+            ("MANGOHUD".to_string(), "1".to_string()),
+            ("MANGOHUD_CONFIG".to_string(), mangohud_config),
+        ],
+        ..CommandSpec::default()
+    });
+    let mut command = Command::new(&spec.program);
+    command
+        .args(&spec.args)
+        .envs(spec.env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    for name in &spec.env_remove {
+        command.env_remove(name);
+    }
+    if let Some(cwd) = &spec.cwd {
+        command.current_dir(cwd);
+    }
+    let status = command
+        .status()
+        .await
+        .with_context(|| format!("running {}", benchmark.name))?;
+    if !status.success() {
+        bail!("{} failed with {}", benchmark.name, status);
+    }
+
+    let log = newest_log(log_dir)
+        .filter(|log| Some(log) != before.as_ref())
+        .with_context(|| format!("{} produced no MangoHud log", benchmark.name))?;
+    let mut result = import_mangohud_log(&benchmark.name, &log, kernel)?;
+    result.source = FrameSource::Benchmark {
+        name: benchmark.name.clone(),
+    };
+    Ok(result)
+}
+
+/// Most recently modified CSV in `dir`
+fn newest_log(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "csv"))
+        .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .max()
+        .map(|(_, path)| path)
+}
@@ -0,0 +1,398 @@
+// src-tauri/src/core/generator.rs
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigValue, KernelConfig};
+use super::containers;
+use super::resolver::{ConfigLayer, Resolution, ResolverIndex};
+use super::sbc::SbcBoard;
+use super::symbols::bare_name;
+use super::system::SystemFacade;
+
+/// Mutually exclusive x86 Kconfig choices, used when flattening layers without a source tree
+/// With a tree the resolver reads the real `choice` blocks instead
+pub const CHOICE_GROUPS: &[&[&str]] = &[
+    &["CONFIG_HZ_100", "CONFIG_HZ_250", "CONFIG_HZ_300", "CONFIG_HZ_1000"],
+    &[
+        "CONFIG_PREEMPT_NONE",
+        "CONFIG_PREEMPT_VOLUNTARY",
+        "CONFIG_PREEMPT",
+        "CONFIG_PREEMPT_LAZY",
+        "CONFIG_PREEMPT_RT",
+    ],
+    &["CONFIG_HZ_PERIODIC", "CONFIG_NO_HZ_IDLE", "CONFIG_NO_HZ_FULL"],
+];
+
+/// Tuning profiles layered on top of the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KernelProfile {
+    Desktop,
+    Gaming,
+    Server,
+    /// Server tuning plus everything Docker, Podman, LXC and Kubernetes need
+    Containers,
+    Rt,
+    /// Sanitizers, lockdep and coverage for kernel development and fuzzing, never for daily use
+    Debug,
+}
+
+impl KernelProfile {
+    pub const ALL: [KernelProfile; 6] = [
+        KernelProfile::Desktop,
+        KernelProfile::Gaming,
+        KernelProfile::Server,
+        KernelProfile::Containers,
+        KernelProfile::Rt,
+        KernelProfile::Debug,
+    ];
+
+    /// Human readable profile name, also used as the layer name
+    pub fn name(self) -> &'static str {
+        match self {
+            KernelProfile::Desktop => "desktop",
+            KernelProfile::Gaming => "gaming",
+            KernelProfile::Server => "server",
+            KernelProfile::Containers => "containers",
+            KernelProfile::Rt => "rt",
+            KernelProfile::Debug => "debug",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        KernelProfile::ALL.into_iter().find(|profile| profile.name() == name)
+    }
+
+    /// Profile this one builds on, None for profiles applied straight over the baseline
+    pub fn parent(self) -> Option<KernelProfile> {
+        match self {
+            KernelProfile::Gaming => Some(KernelProfile::Desktop),
+            KernelProfile::Containers => Some(KernelProfile::Server),
+            _ => None,
+        }
+    }
+
+    /// Layers of the profile and its ancestors, the furthest ancestor first so each
+    /// profile overrides what it inherits
+    pub fn layers(self) -> Vec<ConfigLayer> {
+        let mut layers = vec![self.layer()];
+        let mut current = self;
+        while let Some(parent) = current.parent() {
+            layers.insert(0, parent.layer());
+            current = parent;
+        }
+        layers
+    }
+
+    /// Assignments the profile itself applies over its parent or the baseline
+    /// Every profile embeds its config so the running kernel can be checked against the plan
+    pub fn layer(self) -> ConfigLayer {
+        let layer = ConfigLayer::new(self.name())
+            .with("CONFIG_IKCONFIG", ConfigValue::yes())
+            .with("CONFIG_IKCONFIG_PROC", ConfigValue::yes());
+        match self {
+            KernelProfile::Desktop => layer
+                .with("CONFIG_HZ_300", ConfigValue::yes())
+                .with("CONFIG_HZ", ConfigValue::int(300))
+                .with("CONFIG_PREEMPT", ConfigValue::yes())
+                .with("CONFIG_TRANSPARENT_HUGEPAGE", ConfigValue::yes()),
+            KernelProfile::Gaming => layer
+                .with("CONFIG_HZ_1000", ConfigValue::yes())
+                .with("CONFIG_HZ", ConfigValue::int(1000))
+                .with("CONFIG_NO_HZ_FULL", ConfigValue::yes())
+                .with("CONFIG_SCHED_BORE", ConfigValue::yes())
+                .with("CONFIG_FUTEX", ConfigValue::yes())
+                .with("CONFIG_CPU_FREQ_DEFAULT_GOV_PERFORMANCE", ConfigValue::yes()),
+            KernelProfile::Server => layer
+                .with("CONFIG_HZ_100", ConfigValue::yes())
+                .with("CONFIG_HZ", ConfigValue::int(100))
+                .with("CONFIG_PREEMPT_NONE", ConfigValue::yes())
+                .with("CONFIG_NO_HZ_IDLE", ConfigValue::yes()),
+            KernelProfile::Containers => containers::layer()
+                .assignments
+                .into_iter()
+                .fold(layer, |layer, (symbol, value)| layer.with(&symbol, value)),
+            KernelProfile::Rt => layer
+                .with("CONFIG_HZ_1000", ConfigValue::yes())
+                .with("CONFIG_HZ", ConfigValue::int(1000))
+                .with("CONFIG_PREEMPT_RT", ConfigValue::yes())
+                .with("CONFIG_NO_HZ_FULL", ConfigValue::yes()),
+            KernelProfile::Debug => layer
+                .with("CONFIG_DEBUG_KERNEL", ConfigValue::yes())
+                .with("CONFIG_DEBUG_INFO_DWARF5", ConfigValue::yes())
+                .with("CONFIG_KALLSYMS_ALL", ConfigValue::yes())
+                .with("CONFIG_FRAME_POINTER", ConfigValue::yes())
+                .with("CONFIG_DEBUG_FS", ConfigValue::yes())
+                .with("CONFIG_KASAN", ConfigValue::yes())
+                .with("CONFIG_KASAN_GENERIC", ConfigValue::yes())
+                .with("CONFIG_KASAN_INLINE", ConfigValue::yes())
+                .with("CONFIG_UBSAN", ConfigValue::yes())
+                .with("CONFIG_UBSAN_BOUNDS", ConfigValue::yes())
+                .with("CONFIG_PROVE_LOCKING", ConfigValue::yes())
+                .with("CONFIG_DEBUG_ATOMIC_SLEEP", ConfigValue::yes())
+                .with("CONFIG_DEBUG_LIST", ConfigValue::yes())
+                .with("CONFIG_SLUB_DEBUG", ConfigValue::yes())
+                .with("CONFIG_FAULT_INJECTION", ConfigValue::yes())
+                .with("CONFIG_FAILSLAB", ConfigValue::yes())
+                .with("CONFIG_FAIL_PAGE_ALLOC", ConfigValue::yes())
+                // syzkaller needs coverage with comparison operands and configfs for its setup
+                .with("CONFIG_KCOV", ConfigValue::yes())
+                .with("CONFIG_KCOV_INSTRUMENT_ALL", ConfigValue::yes())
+                .with("CONFIG_KCOV_ENABLE_COMPARISONS", ConfigValue::yes())
+                .with("CONFIG_CONFIGFS_FS", ConfigValue::yes())
+                .with("CONFIG_SECURITYFS", ConfigValue::yes())
+                .with("CONFIG_RANDOMIZE_BASE", ConfigValue::NotSet),
+        }
+    }
+
+    /// Cautions shown when the profile is picked
+    pub fn advisories(self) -> Vec<Advisory> {
+        match self {
+            KernelProfile::Debug => vec![
+                Advisory::new(Severity::Warning, "profile-debug-performance", &[]),
+                Advisory::new(Severity::Info, "profile-debug-size", &[]),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Configuration the overlay layers start from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BaseConfig {
+    /// KernelForge's own conservative x86_64 selection
+    X86_64Baseline,
+    /// The architecture's `make defconfig`, broad hardware support
+    Defconfig,
+    /// `make tinyconfig`, the smallest configuration that builds
+    Tinyconfig,
+    /// `make allnoconfig`, every prompt answered no, used by appliance builds
+    Allnoconfig,
+}
+
+impl BaseConfig {
+    /// Make target producing the base, None for the built-in baseline
+    pub fn make_target(self) -> Option<&'static str> {
+        match self {
+            BaseConfig::X86_64Baseline => None,
+            BaseConfig::Defconfig => Some("defconfig"),
+            BaseConfig::Tinyconfig => Some("tinyconfig"),
+            BaseConfig::Allnoconfig => Some("allnoconfig"),
+        }
+    }
+}
+
+/// Builds the layer stack that describes a kernel configuration
+#[derive(Debug, Clone, Default)]
+pub struct ConfigGenerator {
+    layers: Vec<ConfigLayer>,
+}
+
+impl ConfigGenerator {
+    /// Starts from a conservative x86_64 baseline that boots on most PCs
+    pub fn x86_64_baseline() -> Self {
+        let mut baseline = ConfigLayer::new("x86_64_baseline");
+        for symbol in [
+            "CONFIG_64BIT",
+            "CONFIG_X86_64",
+            "CONFIG_SMP",
+            "CONFIG_MODULES",
+            "CONFIG_MODULE_UNLOAD",
+            "CONFIG_BLK_DEV_INITRD",
+            "CONFIG_DEVTMPFS",
+            "CONFIG_DEVTMPFS_MOUNT",
+            "CONFIG_EFI",
+            "CONFIG_EFI_STUB",
+            "CONFIG_EXT4_FS",
+            "CONFIG_TMPFS",
+            "CONFIG_PROC_FS",
+            "CONFIG_SYSFS",
+            "CONFIG_CGROUPS",
+            "CONFIG_NAMESPACES",
+            "CONFIG_HZ_250",
+            "CONFIG_PREEMPT_VOLUNTARY",
+            "CONFIG_NO_HZ_IDLE",
+        ] {
+            baseline.set(symbol, ConfigValue::yes());
+        }
+        baseline.set("CONFIG_HZ", ConfigValue::int(250));
+        ConfigGenerator {
+            layers: vec![baseline],
+        }
+    }
+
+    /// Starts from a finished configuration, e.g. a plan's, to layer more changes on it
+    pub fn from_config(name: &str, config: &KernelConfig) -> Self {
+        ConfigGenerator {
+            layers: vec![ConfigLayer::from_config(name, config)],
+        }
+    }
+
+    /// Starts from a minimal riscv64 selection for boards booted by OpenSBI and U-Boot
+    #[cfg(feature = "experimental")]
+    pub fn riscv64_baseline() -> Self {
+        let mut baseline = ConfigLayer::new("riscv64_baseline");
+        for symbol in [
+            "CONFIG_64BIT",
+            "CONFIG_ARCH_RV64I",
+            "CONFIG_RISCV_ISA_C",
+            "CONFIG_FPU",
+            "CONFIG_CMODEL_MEDANY",
+            "CONFIG_SMP",
+            "CONFIG_MODULES",
+            "CONFIG_MODULE_UNLOAD",
+            "CONFIG_BLK_DEV_INITRD",
+            "CONFIG_DEVTMPFS",
+            "CONFIG_DEVTMPFS_MOUNT",
+            "CONFIG_OF",
+            "CONFIG_EFI",
+            "CONFIG_SERIAL_8250",
+            "CONFIG_SERIAL_8250_CONSOLE",
+            "CONFIG_SERIAL_OF_PLATFORM",
+            "CONFIG_EXT4_FS",
+            "CONFIG_TMPFS",
+            "CONFIG_PROC_FS",
+            "CONFIG_SYSFS",
+            "CONFIG_HZ_250",
+            "CONFIG_PREEMPT_VOLUNTARY",
+            "CONFIG_NO_HZ_IDLE",
+        ] {
+            baseline.set(symbol, ConfigValue::yes());
+        }
+        baseline.set("CONFIG_HZ", ConfigValue::int(250));
+        ConfigGenerator {
+            layers: vec![baseline],
+        }
+    }
+
+    /// Starts from the chosen base; kernel-generated bases run make in `source_dir`
+    /// with `O=output_dir` so the workspace's own .config is left alone
+    pub fn from_base(
+        base: BaseConfig,
+        system: &dyn SystemFacade,
+        source_dir: &Path,
+        output_dir: &Path,
+    ) -> Result<Self> {
+        match base.make_target() {
+            None => Ok(ConfigGenerator::x86_64_baseline()),
+            Some(target) => ConfigGenerator::from_make_target(system, source_dir, output_dir, target),
+        }
+    }
+
+    /// Starts from the .config written by a kernel config target such as `defconfig`
+    pub fn from_make_target(
+        system: &dyn SystemFacade,
+        source_dir: &Path,
+        output_dir: &Path,
+        target: &str,
+    ) -> Result<Self> {
+        ConfigGenerator::from_make(system, source_dir, output_dir, target, &[])
+    }
+
+    /// Starts from a board's vendor defconfig with the board's required options on top
+    pub fn for_board(
+        board: &SbcBoard,
+        system: &dyn SystemFacade,
+        source_dir: &Path,
+        output_dir: &Path,
+    ) -> Result<Self> {
+        let arch = format!("ARCH={}", board.arch.make_arch());
+        let generator =
+            ConfigGenerator::from_make(system, source_dir, output_dir, board.defconfig, &[&arch])?;
+        Ok(generator.with_layer(board.layer()))
+    }
+
+    /// Adds a defconfig shipped outside the tree, e.g. by a board vendor's BSP
+    pub fn with_vendor_defconfig(self, system: &dyn SystemFacade, path: &Path) -> Result<Self> {
+        let text = system
+            .read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let config = KernelConfig::parse(&text)?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("vendor-defconfig"));
+        Ok(self.with_layer(ConfigLayer::from_config(&name, &config)))
+    }
+
+    fn from_make(
+        system: &dyn SystemFacade,
+        source_dir: &Path,
+        output_dir: &Path,
+        target: &str,
+        variables: &[&str],
+    ) -> Result<Self> {
+        fs::create_dir_all(output_dir).with_context(|| format!("creating {}", output_dir.display()))?;
+        let source = source_dir.to_string_lossy();
+        let output = format!("O={}", output_dir.display());
+        let mut args = vec!["-C", &source, &output];
+        args.extend_from_slice(variables);
+        args.push(target);
+        let output = system
+            .run("make", &args)
+            .with_context(|| format!("running make {}", target))?;
+        if !output.success() {
+            bail!("make {} failed: {}", target, output.stderr.trim());
+        }
+        let config_path = output_dir.join(".config");
+        let text = system
+            .read_to_string(&config_path)
+            .with_context(|| format!("reading {}", config_path.display()))?;
+        let config = KernelConfig::parse(&text)?;
+        Ok(ConfigGenerator {
+            layers: vec![ConfigLayer::from_config(target, &config)],
+        })
+    }
+
+    /// Adds the layers of a tuning profile and the profiles it extends
+    pub fn with_profile(self, profile: KernelProfile) -> Self {
+        profile
+            .layers()
+            .into_iter()
+            .fold(self, |generator, layer| generator.with_layer(layer))
+    }
+
+    /// Adds an arbitrary layer such as hardware findings or user overrides
+    pub fn with_layer(mut self, layer: ConfigLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Layers in application order
+    pub fn layers(&self) -> &[ConfigLayer] {
+        &self.layers
+    }
+
+    /// Flattens the layers without Kconfig rules, last assignment wins
+    /// Enabling a member of a known choice group marks the other members as not set
+    pub fn generate(&self) -> KernelConfig {
+        let mut config = KernelConfig::new();
+        for layer in &self.layers {
+            for (symbol, value) in &layer.assignments {
+                if value.is_enabled() {
+                    let name = bare_name(symbol);
+                    let siblings = CHOICE_GROUPS
+                        .iter()
+                        .find(|group| group.iter().any(|member| bare_name(member) == name))
+                        .into_iter()
+                        .flat_map(|group| group.iter())
+                        .filter(|member| bare_name(member) != name);
+                    for sibling in siblings {
+                        config.set(sibling, ConfigValue::NotSet);
+                    }
+                }
+                config.set(symbol, value.clone());
+            }
+        }
+        config
+    }
+
+    /// Resolves the layers against a Kconfig tree, honouring dependencies and selects
+    pub fn resolve(&self, index: &ResolverIndex) -> Resolution {
+        index.resolve(&self.layers)
+    }
+}
@@ -0,0 +1,240 @@
+// src-tauri/src/core/hardware.rs
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::bluetooth::BluetoothSetup;
+use super::camera::CameraSetup;
+use super::containers::ContainerRuntime;
+use super::cpu::CpuInfo;
+use super::input::{detect_input_devices, InputDevice};
+use super::lsm::active_lsms;
+use super::memory::MemoryInfo;
+use super::networking::NetworkingSetup;
+use super::printing::PrintingSetup;
+use super::procfs::{parse_modules, parse_mounts, PROC_MODULES, PROC_MOUNTS};
+use super::requirements::MachineRequirements;
+use super::rgb::RgbSetup;
+use super::sensors::SensorSetup;
+use super::system::SystemFacade;
+use super::thunderbolt::ThunderboltSetup;
+
+const SYS_BUS: &str = "/sys/bus";
+const SYS_EFI: &str = "/sys/firmware/efi";
+const SYS_DMI: &str = "/sys/class/dmi/id";
+
+/// Buses whose devices are listed in the snapshot
+pub const BUSES: &[&str] = &["pci", "usb", "virtio", "hid", "serio", "platform"];
+
+/// A device on one of the scanned buses, from its sysfs uevent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Device {
+    pub bus: String,
+    /// sysfs name such as `0000:00:1f.2`
+    pub name: String,
+    /// Driver bound to the device, None when unclaimed
+    pub driver: Option<String>,
+    pub modalias: Option<String>,
+    /// PCI class code such as `30000` for a VGA controller, PCI devices only
+    #[serde(default)]
+    pub class: Option<String>,
+}
+
+/// Firmware interface the machine booted through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Firmware {
+    Uefi,
+    /// Legacy BIOS or a UEFI compatibility module
+    Bios,
+}
+
+impl Firmware {
+    pub fn detect(system: &dyn SystemFacade) -> Self {
+        if system.exists(Path::new(SYS_EFI)) {
+            Firmware::Uefi
+        } else {
+            Firmware::Bios
+        }
+    }
+}
+
+/// Everything known about the machine a kernel is being configured for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardwareSnapshot {
+    pub cpu: CpuInfo,
+    pub memory: MemoryInfo,
+    pub firmware: Firmware,
+    /// DMI system vendor and product, e.g. `QEMU` and `Standard PC (Q35 + ICH9, 2009)`
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub devices: Vec<Device>,
+    pub loaded_modules: BTreeSet<String>,
+    /// Filesystem types currently mounted
+    pub filesystems: BTreeSet<String>,
+    pub root_fstype: Option<String>,
+    /// Security modules active in the running kernel, e.g. `selinux`; empty in snapshots
+    /// taken before they were recorded
+    #[serde(default)]
+    pub lsms: BTreeSet<String>,
+    /// Container runtimes set up on the machine, whose kernel requirements plans keep
+    #[serde(default)]
+    pub container_runtimes: BTreeSet<ContainerRuntime>,
+    /// USB printers and scanners with CUPS and SANE, None when the machine does neither
+    #[serde(default)]
+    pub printing: Option<PrintingSetup>,
+    /// Webcams and camera programs, None when there are neither
+    #[serde(default)]
+    pub camera: Option<CameraSetup>,
+    /// Keyboards, pointing devices, tablets and other input devices attached now
+    #[serde(default)]
+    pub input_devices: Vec<InputDevice>,
+    /// Thunderbolt and USB4 controllers with their docks, None without a controller
+    #[serde(default)]
+    pub thunderbolt: Option<ThunderboltSetup>,
+    /// hwmon sensor chips with the lm-sensors and fancontrol setup, None without a
+    /// chip whose driver a config could drop
+    #[serde(default)]
+    pub sensors: Option<SensorSetup>,
+    /// OpenRGB, liquidctl and ckb-next, None when none is installed
+    #[serde(default)]
+    pub rgb: Option<RgbSetup>,
+    /// VPNs, the firewall ruleset and open exotic protocols, None when there are none
+    #[serde(default)]
+    pub networking: Option<NetworkingSetup>,
+    /// Bluetooth controllers and what they support, None without a controller; not among
+    /// the setups plans keep, as the audio preset is opt-in
+    #[serde(default)]
+    pub bluetooth: Option<BluetoothSetup>,
+}
+
+impl HardwareSnapshot {
+    /// Reads CPU, memory, bus devices, loaded modules, mounts, active security modules,
+    /// container runtimes, printing, cameras, input devices, Thunderbolt,
+    /// sensors, RGB tools, networking and Bluetooth
+    pub fn capture(system: &dyn SystemFacade) -> Result<Self> {
+        let cpu = CpuInfo::detect(system)?;
+        let memory = MemoryInfo::detect(system)?;
+
+        let mut devices = Vec::new();
+        for bus in BUSES {
+            let dir = Path::new(SYS_BUS).join(bus).join("devices");
+            for device in system.read_dir(&dir).unwrap_or_default() {
+                let Some(name) = device.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                let uevent = system
+                    .read_to_string(&device.join("uevent"))
+                    .unwrap_or_default();
+                devices.push(Device {
+                    bus: bus.to_string(),
+                    name: name.to_string(),
+                    driver: uevent_value(&uevent, "DRIVER"),
+                    modalias: uevent_value(&uevent, "MODALIAS"),
+                    class: uevent_value(&uevent, "PCI_CLASS"),
+                });
+            }
+        }
+
+        let printing = PrintingSetup::detect(system, &devices);
+        let camera = CameraSetup::detect(system, &devices);
+
+        let modules = system
+            .read_to_string(Path::new(PROC_MODULES))
+            .unwrap_or_default();
+        let loaded_modules = parse_modules(&modules)
+            .into_iter()
+            .map(|module| module.name.to_string())
+            .collect();
+
+        let mounts = system
+            .read_to_string(Path::new(PROC_MOUNTS))
+            .context("reading /proc/mounts")?;
+        let mounts = parse_mounts(&mounts);
+        let filesystems = mounts
+            .iter()
+            .map(|mount| mount.fstype.to_string())
+            .collect();
+        // The last mount on / wins, earlier ones are shadowed
+        let root_fstype = mounts
+            .iter()
+            .rev()
+            .find(|mount| mount.target == "/")
+            .map(|mount| mount.fstype.to_string());
+
+        Ok(HardwareSnapshot {
+            cpu,
+            memory,
+            firmware: Firmware::detect(system),
+            vendor: system.read_trimmed(&Path::new(SYS_DMI).join("sys_vendor")),
+            product: system.read_trimmed(&Path::new(SYS_DMI).join("product_name")),
+            devices,
+            loaded_modules,
+            filesystems,
+            root_fstype,
+            lsms: active_lsms(system),
+            container_runtimes: ContainerRuntime::detect(system),
+            printing,
+            camera,
+            input_devices: detect_input_devices(system),
+            thunderbolt: ThunderboltSetup::detect(system),
+            sensors: SensorSetup::detect(system),
+            rgb: RgbSetup::detect(system),
+            networking: NetworkingSetup::detect(system),
+            bluetooth: BluetoothSetup::detect(system),
+        })
+    }
+
+    /// What is set up on the machine whose options plans keep, in the order their layers
+    /// apply: printing, cameras, Thunderbolt, sensors, RGB tools, networking and the input
+    /// devices last
+    pub fn setups(&self) -> Vec<&dyn MachineRequirements> {
+        let detected: [Option<&dyn MachineRequirements>; 6] = [
+            self.printing.as_ref().map(|setup| setup as _),
+            self.camera.as_ref().map(|setup| setup as _),
+            self.thunderbolt.as_ref().map(|setup| setup as _),
+            self.sensors.as_ref().map(|setup| setup as _),
+            self.rgb.as_ref().map(|setup| setup as _),
+            self.networking.as_ref().map(|setup| setup as _),
+        ];
+        let mut setups: Vec<&dyn MachineRequirements> = detected.into_iter().flatten().collect();
+        setups.push(&self.input_devices);
+        setups
+    }
+
+    /// Whether the machine is a virtual machine guest
+    pub fn is_virtual(&self) -> bool {
+        self.cpu.has_feature("hypervisor")
+    }
+
+    /// Display controllers, PCI base class 0x03
+    pub fn display_devices(&self) -> impl Iterator<Item = &Device> {
+        self.devices.iter().filter(|device| {
+            device
+                .class
+                .as_deref()
+                .and_then(|class| u32::from_str_radix(class, 16).ok())
+                .is_some_and(|class| class >> 16 == 0x03)
+        })
+    }
+
+    /// Drivers bound to any device, plus loaded modules, with `-` normalized to `_`
+    pub fn drivers(&self) -> BTreeSet<String> {
+        self.devices
+            .iter()
+            .filter_map(|device| device.driver.as_deref())
+            .chain(self.loaded_modules.iter().map(String::as_str))
+            .map(|driver| driver.replace('-', "_"))
+            .collect()
+    }
+}
+
+/// Value of a `KEY=value` line of a sysfs uevent
+pub fn uevent_value(uevent: &str, key: &str) -> Option<String> {
+    uevent.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        (name == key).then(|| value.to_string())
+    })
+}
@@ -0,0 +1,127 @@
+// src-tauri/src/core/headers.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Where installed header trees live, one `linux-headers-<version>` directory each
+pub const HEADERS_ROOT: &str = "/usr/src";
+
+/// Single files external module builds read from the top of the tree
+const TOP_FILES: &[&str] = &[
+    ".config",
+    "Makefile",
+    "Module.symvers",
+    "System.map",
+    "include/config/kernel.release",
+    "arch/x86/Makefile",
+    "arch/x86/Makefile_32.cpu",
+    "tools/objtool/objtool",
+    "tools/bpf/resolve_btfids/resolve_btfids",
+];
+
+/// Directories copied whole
+const TREES: &[&str] = &[
+    "include",
+    "scripts",
+    "arch/x86/include",
+    "arch/x86/entry/syscalls",
+];
+
+/// Build intermediates that DKMS never needs
+const SKIPPED_EXTENSIONS: &[&str] = &["o", "cmd", "a", "d", "tmp"];
+
+/// Installed location of the headers for `version`
+pub fn headers_dir(version: &str) -> PathBuf {
+    Path::new(HEADERS_ROOT).join(format!("linux-headers-{}", version))
+}
+
+/// Copies what out-of-tree module builds need into `staging`
+/// `source_dir` holds the kernel sources and `build_dir` the `O=` output, which may be the same;
+/// generated files from the build dir overlay the sources
+pub fn stage_headers(source_dir: &Path, build_dir: &Path, staging: &Path) -> Result<PathBuf> {
+    if !build_dir.join("Module.symvers").exists() {
+        bail!(
+            "{} has no Module.symvers; build the kernel and modules first",
+            build_dir.display()
+        );
+    }
+    if staging.exists() {
+        fs::remove_dir_all(staging).with_context(|| format!("clearing {}", staging.display()))?;
+    }
+    fs::create_dir_all(staging).with_context(|| format!("creating {}", staging.display()))?;
+
+    let mut roots = vec![source_dir];
+    if build_dir != source_dir {
+        roots.push(build_dir);
+    }
+    for root in roots {
+        for file in TOP_FILES {
+            let from = root.join(file);
+            if from.is_file() {
+                copy_file(&from, &staging.join(file))?;
+            }
+        }
+        for tree in TREES {
+            copy_tree(&root.join(tree), &staging.join(tree))?;
+        }
+        // Every Kbuild and Kconfig file, so the top Makefile can parse the tree
+        copy_build_files(root, root, staging)?;
+    }
+    Ok(staging.to_path_buf())
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::copy(from, to).with_context(|| format!("copying {}", from.display()))?;
+    Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    if !from.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(from).with_context(|| format!("reading {}", from.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            copy_tree(&path, &target)?;
+        } else if kind.is_file() && !skipped(&path) {
+            copy_file(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_build_files(root: &Path, dir: &Path, staging: &Path) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            copy_build_files(root, &path, staging)?;
+        } else if kind.is_file() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("Kconfig") || name.starts_with("Kbuild") {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                copy_file(&path, &staging.join(relative))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn skipped(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| SKIPPED_EXTENSIONS.contains(&extension))
+}
@@ -0,0 +1,334 @@
+// src-tauri/src/core/hibernate.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::cmdline::CmdlinePlan;
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::procfs::{parse_meminfo, PROC_MEMINFO};
+use super::system::SystemFacade;
+
+const PROC_CMDLINE: &str = "/proc/cmdline";
+const PROC_SWAPS: &str = "/proc/swaps";
+const IMAGE_SIZE: &str = "/sys/power/image_size";
+const POWER_DISK: &str = "/sys/power/disk";
+const LOCKDOWN: &str = "/sys/kernel/security/lockdown";
+
+/// Options every hibernating kernel needs
+const REQUIRED: &[&str] = &["CONFIG_HIBERNATION", "CONFIG_SWAP", "CONFIG_PM_SLEEP"];
+
+/// An active swap area from /proc/swaps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapArea {
+    pub path: String,
+    /// Swap file rather than a partition; resuming from it needs resume_offset=
+    pub is_file: bool,
+    pub size_kb: u64,
+    /// For a swap file, the device of the filesystem holding it
+    #[serde(default)]
+    pub device: Option<String>,
+    /// For a swap file, the resume_offset= of its first block, when the file could be mapped
+    #[serde(default)]
+    pub resume_offset: Option<String>,
+}
+
+/// How the machine hibernates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HibernateSetup {
+    /// resume= and resume_offset= of the running kernel
+    pub resume: String,
+    pub resume_offset: Option<String>,
+    /// resume= resolved to a device node, when it could be
+    pub resume_device: Option<String>,
+    pub swaps: Vec<SwapArea>,
+    /// Size the kernel tries to shrink the image to
+    pub image_size_bytes: Option<u64>,
+    /// Memory in use, which bounds the image once caches are dropped
+    pub used_kb: u64,
+    /// Whether lockdown forbids hibernation on the running kernel
+    pub locked_down: bool,
+}
+
+impl HibernateSetup {
+    /// Detects hibernation from resume= on the running command line, None when absent
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let cmdline = system.read_trimmed(Path::new(PROC_CMDLINE))?;
+        let param = |name: &str| {
+            cmdline
+                .split_whitespace()
+                .filter_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+                .next_back()
+                .map(str::to_string)
+        };
+        let resume = param("resume")?;
+        let resume_offset = param("resume_offset");
+
+        let mut swaps = system
+            .read_to_string(Path::new(PROC_SWAPS))
+            .map(|text| parse_swaps(&text))
+            .unwrap_or_default();
+        for swap in swaps.iter_mut().filter(|swap| swap.is_file) {
+            swap.device = file_device(system, &swap.path);
+            swap.resume_offset = file_offset(system, &swap.path);
+        }
+        let used_kb = system
+            .read_to_string(Path::new(PROC_MEMINFO))
+            .map(|text| {
+                let meminfo = parse_meminfo(&text);
+                meminfo.total_kb().saturating_sub(meminfo.available_kb())
+            })
+            .unwrap_or_default();
+        let lockdown = system.read_trimmed(Path::new(LOCKDOWN)).unwrap_or_default();
+
+        Some(HibernateSetup {
+            resume_device: resolve_device(system, &resume),
+            resume,
+            resume_offset,
+            swaps,
+            image_size_bytes: system
+                .read_trimmed(Path::new(IMAGE_SIZE))
+                .and_then(|size| size.parse().ok()),
+            used_kb,
+            locked_down: lockdown.contains("[integrity]")
+                || lockdown.contains("[confidentiality]")
+                || system
+                    .read_trimmed(Path::new(POWER_DISK))
+                    .is_some_and(|modes| modes.contains("[disabled]")),
+        })
+    }
+
+    /// Parameters the new kernel's boot entry needs to find the image
+    pub fn resume_params(&self) -> Vec<String> {
+        let mut params = vec![format!("resume={}", self.resume)];
+        if let Some(offset) = &self.resume_offset {
+            params.push(format!("resume_offset={}", offset));
+        }
+        params
+    }
+
+    /// Adds the resume parameters to a command line plan that lost them, e.g. because
+    /// the bootloader config omits what the initramfs generator put on the running one
+    pub fn carry_resume(&self, cmdline: &mut CmdlinePlan) {
+        for param in self.resume_params() {
+            cmdline.add(&param);
+        }
+    }
+
+    /// Swap space able to hold the image: partitions matching resume=, or the file on the
+    /// resume= device that starts at resume_offset=
+    /// A file whose device or offset could not be read is given the benefit of the doubt
+    pub fn resume_swap_kb(&self) -> Option<u64> {
+        let matching: Vec<&SwapArea> = self
+            .swaps
+            .iter()
+            .filter(|swap| {
+                if self.resume_offset.is_some() {
+                    swap.is_file
+                        && swap
+                            .device
+                            .as_deref()
+                            .zip(self.resume_device.as_deref())
+                            .is_none_or(|(device, resume)| device == resume)
+                        && swap
+                            .resume_offset
+                            .as_ref()
+                            .is_none_or(|offset| self.resume_offset.as_ref() == Some(offset))
+                } else {
+                    !swap.is_file && self.resume_device.as_deref() == Some(swap.path.as_str())
+                }
+            })
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        Some(matching.iter().map(|swap| swap.size_kb).max().unwrap_or(0))
+    }
+
+    /// Findings for a plan meant to keep hibernating; `cmdline` is the new entry's
+    pub fn check(&self, config: &KernelConfig, cmdline: &str) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+
+        let mut missing: Vec<ConfigSuggestion> = REQUIRED
+            .iter()
+            .filter(|symbol| !config.is_enabled(symbol))
+            .map(|symbol| {
+                ConfigSuggestion::new(symbol, ConfigValue::yes(), "needed to hibernate and resume")
+            })
+            .collect();
+        for (symbol, reason) in self.device_drivers() {
+            if !config.is_enabled(symbol) {
+                missing.push(ConfigSuggestion::new(symbol, ConfigValue::yes(), reason));
+            }
+        }
+        if !missing.is_empty() {
+            let symbols: Vec<&str> = missing
+                .iter()
+                .map(|suggestion| suggestion.symbol.as_str())
+                .collect();
+            let mut advisory = Advisory::new(
+                Severity::Warning,
+                "hibernate-missing-config",
+                &[("symbols", symbols.join(", "))],
+            );
+            for suggestion in missing {
+                advisory = advisory.suggest(suggestion);
+            }
+            advisories.push(advisory);
+        }
+        if config.is_enabled("CONFIG_LOCK_DOWN_KERNEL_FORCE_INTEGRITY")
+            || config.is_enabled("CONFIG_LOCK_DOWN_KERNEL_FORCE_CONFIDENTIALITY")
+        {
+            advisories.push(Advisory::new(Severity::Warning, "hibernate-lockdown", &[]));
+        }
+
+        let dropped: Vec<String> = self
+            .resume_params()
+            .into_iter()
+            .filter(|param| !cmdline.split_whitespace().any(|kept| kept == param))
+            .collect();
+        if !dropped.is_empty() {
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "hibernate-resume-dropped",
+                &[("params", dropped.join(" "))],
+            ));
+        }
+
+        match self.resume_swap_kb() {
+            None => advisories.push(Advisory::new(
+                Severity::Warning,
+                "hibernate-no-swap",
+                &[("resume", self.resume.clone())],
+            )),
+            Some(swap_kb) => {
+                // The kernel shrinks the image towards image_size but never below the memory
+                // it cannot reclaim, so the larger of the two is what swap must hold
+                let image_kb = self
+                    .image_size_bytes
+                    .map_or(0, |bytes| bytes / 1024)
+                    .max(self.used_kb);
+                if swap_kb < image_kb {
+                    advisories.push(Advisory::new(
+                        Severity::Warning,
+                        "hibernate-swap-small",
+                        &[
+                            ("swap", format_gib(swap_kb)),
+                            ("image", format_gib(image_kb)),
+                        ],
+                    ));
+                }
+            }
+        }
+        if self.locked_down {
+            advisories.push(Advisory::new(Severity::Info, "hibernate-disabled", &[]));
+        }
+        advisories
+    }
+
+    /// Block drivers the resume device sits behind; the initramfs loads them before
+    /// resuming, so modules are enough
+    fn device_drivers(&self) -> Vec<(&'static str, &'static str)> {
+        let device = self.resume_device.as_deref().unwrap_or(&self.resume);
+        let mut drivers = Vec::new();
+        if device.starts_with("/dev/dm-") || device.starts_with("/dev/mapper/") {
+            drivers.push(("CONFIG_BLK_DEV_DM", "the resume device is device-mapper"));
+            drivers.push((
+                "CONFIG_DM_CRYPT",
+                "swap behind device-mapper is usually encrypted",
+            ));
+        } else if device.starts_with("/dev/nvme") {
+            drivers.push(("CONFIG_BLK_DEV_NVME", "the resume device is NVMe"));
+        } else if device.starts_with("/dev/sd") {
+            drivers.push((
+                "CONFIG_BLK_DEV_SD",
+                "the resume device is a SCSI or SATA disk",
+            ));
+        } else if device.starts_with("/dev/vd") {
+            drivers.push(("CONFIG_VIRTIO_BLK", "the resume device is a virtio disk"));
+        } else if device.starts_with("/dev/md") {
+            drivers.push(("CONFIG_BLK_DEV_MD", "the resume device is software RAID"));
+        }
+        drivers
+    }
+}
+
+/// Active swap areas; sizes are in KiB
+fn parse_swaps(text: &str) -> Vec<SwapArea> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let path = fields.next()?;
+            let kind = fields.next()?;
+            let size_kb = fields.next()?.parse().ok()?;
+            Some(SwapArea {
+                // Spaces in paths are octal-escaped, as in /proc/mounts
+                path: path.replace("\\040", " "),
+                is_file: kind == "file",
+                size_kb,
+                device: None,
+                resume_offset: None,
+            })
+        })
+        .collect()
+}
+
+/// Device node resume= names, following UUID=, PARTUUID= and /dev/mapper links
+fn resolve_device(system: &dyn SystemFacade, resume: &str) -> Option<String> {
+    let path = if let Some(uuid) = resume.strip_prefix("UUID=") {
+        format!("/dev/disk/by-uuid/{}", uuid)
+    } else if let Some(uuid) = resume.strip_prefix("PARTUUID=") {
+        format!("/dev/disk/by-partuuid/{}", uuid)
+    } else if resume.starts_with('/') {
+        resume.to_string()
+    } else {
+        return None;
+    };
+    system
+        .run("readlink", &["-f", &path])
+        .ok()
+        .filter(|output| output.success())
+        .map(|output| output.stdout.trim().to_string())
+        .filter(|device| !device.is_empty())
+}
+
+/// Device of the filesystem holding `path`, resolved like resume=
+fn file_device(system: &dyn SystemFacade, path: &str) -> Option<String> {
+    let output = system
+        .run("findmnt", &["-n", "-o", "SOURCE", "--target", path])
+        .ok()
+        .filter(|output| output.success())?;
+    // btrfs sources carry the subvolume, e.g. /dev/nvme0n1p2[/@swap]
+    let source = output.stdout.trim();
+    let source = source.split('[').next().unwrap_or(source);
+    resolve_device(system, source)
+}
+
+/// resume_offset= of a swap file: btrfs maps it itself; elsewhere it is the physical start
+/// of the first extent, as `filefrag -v` reports it
+/// Mapping needs read access to the file, which is usually root-only
+fn file_offset(system: &dyn SystemFacade, path: &str) -> Option<String> {
+    if system.has_program("btrfs") {
+        let output = system.run("btrfs", &["inspect-internal", "map-swapfile", "-r", path]);
+        if let Some(output) = output.ok().filter(|output| output.success()) {
+            return Some(output.stdout.trim().to_string());
+        }
+    }
+    let output = system
+        .run("filefrag", &["-v", path])
+        .ok()
+        .filter(|output| output.success())?;
+    // "   0:        0..       0:      34816..     34816:      1:"
+    output.stdout.lines().find_map(|line| {
+        let mut fields = line.trim_start().strip_prefix("0:")?.split(':');
+        fields.next()?;
+        let start = fields.next()?.split("..").next()?.trim();
+        (!start.is_empty()).then(|| start.to_string())
+    })
+}
+
+fn format_gib(kb: u64) -> String {
+    format!("{:.1} GiB", kb as f64 / (1024.0 * 1024.0))
+}
@@ -0,0 +1,214 @@
+// src-tauri/src/core/host.rs
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use super::system::{run_spec, CommandOutput, CommandSpec, LocalSystem, SystemFacade};
+
+/// Variables AppImage runtimes inject that break host toolchains
+const APPIMAGE_VARIABLES: &[&str] = &["LD_LIBRARY_PATH", "LD_PRELOAD", "PYTHONHOME", "PYTHONPATH", "PERLLIB", "GSETTINGS_SCHEMA_DIR"];
+
+/// Paths whose sandbox view matches the host closely enough to read directly
+/// /proc/mounts is excluded: it lists the sandbox's mount namespace
+const SHARED_PREFIXES: &[&str] = &["/sys/", "/proc/cpuinfo", "/proc/meminfo", "/proc/modules", "/proc/cmdline", "/proc/sys/"];
+
+/// Distinguishes the group id files of commands spawned by this process
+static GROUP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Makes the host-side command lead a new process group and writes its id to the file in $0
+const LEAD_GROUP: &str = r#"echo "$$" > "$0"; exec "$@""#;
+
+/// How the application was packaged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostEnvironment {
+    Native,
+    Flatpak,
+    AppImage,
+}
+
+impl HostEnvironment {
+    /// Detects the packaging from the markers each runtime leaves
+    pub fn detect() -> Self {
+        if Path::new("/.flatpak-info").exists() || env::var_os("FLATPAK_ID").is_some() {
+            HostEnvironment::Flatpak
+        } else if env::var_os("APPIMAGE").is_some() {
+            HostEnvironment::AppImage
+        } else {
+            HostEnvironment::Native
+        }
+    }
+}
+
+/// Picks the facade matching the current packaging
+pub fn host_system() -> Arc<dyn SystemFacade> {
+    match HostEnvironment::detect() {
+        HostEnvironment::Native => Arc::new(LocalSystem),
+        HostEnvironment::Flatpak => Arc::new(FlatpakHost::new()),
+        HostEnvironment::AppImage => Arc::new(AppImageHost),
+    }
+}
+
+/// Local access with the AppImage runtime's library overrides removed from child processes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppImageHost;
+
+impl SystemFacade for AppImageHost {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        LocalSystem.read_to_string(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        LocalSystem.read_dir(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        LocalSystem.exists(path)
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        run_spec(&self.prepare_command(CommandSpec::new(program, args)))
+    }
+
+    fn prepare_command(&self, mut spec: CommandSpec) -> CommandSpec {
+        spec.env_remove
+            .extend(APPIMAGE_VARIABLES.iter().map(|name| name.to_string()));
+        spec
+    }
+}
+
+/// Host access from inside a Flatpak sandbox
+/// Commands and host-only files go through `flatpak-spawn --host`; files the user picked
+/// through the FileChooser portal live under the document portal mount and are read directly
+#[derive(Debug, Clone)]
+pub struct FlatpakHost {
+    document_portal: Option<PathBuf>,
+    /// $XDG_RUNTIME_DIR/app/<id>, visible at the same path inside the sandbox and on the host
+    shared_dir: Option<PathBuf>,
+}
+
+impl FlatpakHost {
+    /// Creates the facade, locating the document portal mount and the app's shared runtime
+    /// directory for the current user
+    pub fn new() -> Self {
+        let runtime = env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+        let document_portal = runtime.as_ref().map(|runtime| runtime.join("doc"));
+        let shared_dir = runtime
+            .zip(env::var_os("FLATPAK_ID"))
+            .map(|(runtime, id)| runtime.join("app").join(id));
+        FlatpakHost {
+            document_portal,
+            shared_dir,
+        }
+    }
+
+    /// Whether a path can be accessed without leaving the sandbox
+    pub fn is_sandbox_local(&self, path: &Path) -> bool {
+        if let Some(portal) = &self.document_portal {
+            if path.starts_with(portal) {
+                return true;
+            }
+        }
+        let text = path.to_string_lossy();
+        path.starts_with("/app") || SHARED_PREFIXES.iter().any(|prefix| text.starts_with(prefix))
+    }
+
+    fn host(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        run_spec(&self.prepare_command(CommandSpec::new(program, args)))
+    }
+}
+
+impl Default for FlatpakHost {
+    fn default() -> Self {
+        FlatpakHost::new()
+    }
+}
+
+impl SystemFacade for FlatpakHost {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        if self.is_sandbox_local(path) {
+            return fs::read_to_string(path);
+        }
+        let output = self.host("cat", &["--", &path.to_string_lossy()])?;
+        if !output.success() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, output.stderr.trim().to_string()));
+        }
+        Ok(output.stdout)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        if self.is_sandbox_local(path) {
+            return LocalSystem.read_dir(path);
+        }
+        let output = self.host("ls", &["-1A", "--", &path.to_string_lossy()])?;
+        if !output.success() {
+            return Err(io::Error::new(io::ErrorKind::NotFound, output.stderr.trim().to_string()));
+        }
+        let mut entries: Vec<PathBuf> = output.stdout.lines().map(|name| path.join(name)).collect();
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        if self.is_sandbox_local(path) {
+            return path.exists();
+        }
+        self.host("test", &["-e", &path.to_string_lossy()])
+            .map(|output| output.success())
+            .unwrap_or(false)
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        self.host(program, args)
+    }
+
+    fn prepare_command(&self, spec: CommandSpec) -> CommandSpec {
+        let mut args = vec![String::from("--host"), String::from("--watch-bus")];
+        if let Some(cwd) = &spec.cwd {
+            args.push(format!("--directory={}", cwd.display()));
+        }
+        args.extend(spec.env.iter().map(|(key, value)| format!("--env={}={}", key, value)));
+        args.extend(spec.env_remove.iter().map(|name| format!("--unsetenv={}", name)));
+        args.push(spec.program);
+        args.extend(spec.args);
+        CommandSpec {
+            program: String::from("flatpak-spawn"),
+            args,
+            ..CommandSpec::default()
+        }
+    }
+
+    /// The child seen from here is flatpak-spawn, so the host command starts its own group
+    /// through setsid and reports the group id in the shared directory
+    fn prepare_group_command(&self, spec: CommandSpec) -> (CommandSpec, Option<PathBuf>) {
+        let pgid_file = self.staging_dir().join(format!(
+            "kernelforge-{}-{}.pgid",
+            process::id(),
+            GROUP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut args = vec![
+            String::from("sh"),
+            String::from("-c"),
+            String::from(LEAD_GROUP),
+            pgid_file.to_string_lossy().into_owned(),
+            spec.program.clone(),
+        ];
+        args.extend(spec.args.iter().cloned());
+        let wrapped = CommandSpec {
+            program: String::from("setsid"),
+            args,
+            ..spec
+        };
+        (self.prepare_command(wrapped), Some(pgid_file))
+    }
+
+    fn staging_dir(&self) -> PathBuf {
+        self.shared_dir.clone().unwrap_or_else(env::temp_dir)
+    }
+}
@@ -0,0 +1,82 @@
+// src-tauri/src/core/i18n.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+/// Fluent source for the English defaults, also served to the frontend as the reference catalog
+pub const ENGLISH_CATALOG: &str = include_str!("../../locales/en-US/kernelforge.ftl");
+
+static ENGLISH: OnceLock<FluentBundle<FluentResource>> = OnceLock::new();
+
+fn english() -> &'static FluentBundle<FluentResource> {
+    ENGLISH.get_or_init(|| {
+        let resource = FluentResource::try_new(ENGLISH_CATALOG.to_string())
+            .expect("bundled English catalog is valid Fluent");
+        let locale: LanguageIdentifier = "en-US".parse().expect("valid language identifier");
+        let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+        // Isolation marks would leak into logs and CLI output
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .expect("bundled English catalog has no duplicate keys");
+        bundle
+    })
+}
+
+/// User-facing text: a stable key and its arguments, plus the English rendering
+/// The frontend localizes from `key` and `args`; `text` is the fallback and what logs show
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub key: String,
+    pub args: BTreeMap<String, String>,
+    pub text: String,
+}
+
+impl Message {
+    /// Renders `key` from the English catalog with `args`
+    pub fn new(key: &str, args: &[(&str, String)]) -> Self {
+        let args: BTreeMap<String, String> = args
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let text = render_english(key, &args);
+        Message {
+            key: key.to_string(),
+            args,
+            text,
+        }
+    }
+
+    /// Message without arguments
+    pub fn plain(key: &str) -> Self {
+        Message::new(key, &[])
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// English text for a key, or the key itself when the catalog lacks it
+pub fn render_english(key: &str, args: &BTreeMap<String, String>) -> String {
+    let bundle = english();
+    let Some(pattern) = bundle.get_message(key).and_then(|message| message.value()) else {
+        return key.to_string();
+    };
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(name.as_str(), value.as_str());
+    }
+    let mut errors = Vec::new();
+    bundle
+        .format_pattern(pattern, Some(&fluent_args), &mut errors)
+        .into_owned()
+}
@@ -0,0 +1,113 @@
+// src-tauri/src/core/ikconfig.rs
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigValue, KernelConfig};
+use super::plan::BuildPlan;
+use super::system::SystemFacade;
+
+/// Config embedded in the running kernel by CONFIG_IKCONFIG_PROC
+pub const PROC_CONFIG_GZ: &str = "/proc/config.gz";
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// Configuration of the running kernel, from /proc/config.gz
+pub fn running_config(system: &dyn SystemFacade) -> Result<KernelConfig> {
+    if !system.exists(Path::new(PROC_CONFIG_GZ)) {
+        bail!(
+            "{} is missing: the kernel was built without CONFIG_IKCONFIG_PROC, or its configs module is not loaded",
+            PROC_CONFIG_GZ
+        );
+    }
+    let output = system
+        .run("gzip", &["-dc", PROC_CONFIG_GZ])
+        .context("running gzip")?;
+    if !output.success() {
+        bail!(
+            "decompressing {} failed: {}",
+            PROC_CONFIG_GZ,
+            output.stderr.trim()
+        );
+    }
+    KernelConfig::parse(&output.stdout)
+}
+
+/// A planned option the running kernel ended up with a different value for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigDivergence {
+    /// Bare symbol name
+    pub symbol: String,
+    /// None when the plan disables it
+    pub planned: Option<String>,
+    /// None when disabled or absent, e.g. dropped by olddefconfig for an unmet dependency
+    pub running: Option<String>,
+}
+
+/// Outcome of comparing the running kernel's embedded config with a plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigVerification {
+    /// Release of the running kernel
+    pub release: Option<String>,
+    /// Whether that release is the plan's version, so the comparison is meaningful
+    pub release_matches: bool,
+    /// Planned options holding the planned value
+    pub matched: usize,
+    pub diverged: Vec<ConfigDivergence>,
+}
+
+impl ConfigVerification {
+    /// Whether the running kernel is the plan's version and carries every planned value
+    pub fn is_clean(&self) -> bool {
+        self.release_matches && self.diverged.is_empty()
+    }
+}
+
+/// Compares each option the plan sets with `running`; options the plan leaves alone are
+/// whatever Kconfig defaulted them to and are not reported
+pub fn compare(planned: &KernelConfig, running: &KernelConfig) -> (usize, Vec<ConfigDivergence>) {
+    let mut matched = 0;
+    let mut diverged = Vec::new();
+    for (symbol, value) in planned.iter() {
+        let planned = effective(Some(value));
+        let running = effective(running.get(symbol));
+        if planned == running {
+            matched += 1;
+        } else {
+            diverged.push(ConfigDivergence {
+                symbol: symbol.to_string(),
+                planned,
+                running,
+            });
+        }
+    }
+    (matched, diverged)
+}
+
+/// Value with `n`, not set and absent all meaning disabled
+fn effective(value: Option<&ConfigValue>) -> Option<String> {
+    value
+        .and_then(ConfigValue::as_str)
+        .filter(|value| *value != "n")
+        .map(str::to_string)
+}
+
+/// Checks that the running kernel was built from `plan`'s configuration
+pub fn verify_running(system: &dyn SystemFacade, plan: &BuildPlan) -> Result<ConfigVerification> {
+    let running = running_config(system)?;
+    let release = system.read_trimmed(Path::new(OSRELEASE));
+    let release_matches = release.as_deref().is_some_and(|release| {
+        release == plan.version
+            || release
+                .strip_prefix(plan.version.as_str())
+                .is_some_and(|rest| rest.starts_with(['-', '+']))
+    });
+    let (matched, diverged) = compare(&plan.config, &running);
+    Ok(ConfigVerification {
+        release,
+        release_matches,
+        matched,
+        diverged,
+    })
+}
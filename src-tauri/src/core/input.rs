@@ -0,0 +1,719 @@
+// src-tauri/src/core/input.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::hardware::uevent_value;
+use super::requirements::{self, MachineRequirements};
+use super::system::SystemFacade;
+
+pub const PROC_INPUT_DEVICES: &str = "/proc/bus/input/devices";
+
+const SYS_PLATFORM_DEVICES: &str = "/sys/bus/platform/devices";
+
+/// Bus numbers from linux/input.h
+const BUS_USB: u16 = 0x03;
+const BUS_BLUETOOTH: u16 = 0x05;
+const BUS_I8042: u16 = 0x11;
+const BUS_I2C: u16 = 0x18;
+
+const VENDOR_APPLE: u16 = 0x05ac;
+const VENDOR_LOGITECH: u16 = 0x046d;
+const VENDOR_MICROSOFT: u16 = 0x045e;
+const VENDOR_WACOM: u16 = 0x056a;
+
+/// Drivers an input device can be bound to or sit behind, by the name in their uevent with
+/// `-` as `_`, and their options
+const DRIVERS: &[(&str, &str, &str)] = &[
+    (
+        "usbhid",
+        "CONFIG_USB_HID",
+        "usbhid carries USB keyboards, mice and pads",
+    ),
+    (
+        "hid_generic",
+        "CONFIG_HID_GENERIC",
+        "the HID driver for devices without a dedicated one",
+    ),
+    (
+        "hid_multitouch",
+        "CONFIG_HID_MULTITOUCH",
+        "drives multitouch pads and screens",
+    ),
+    (
+        "wacom",
+        "CONFIG_HID_WACOM",
+        "drives Wacom tablets and pens with pressure and tilt",
+    ),
+    (
+        "logitech_djreceiver",
+        "CONFIG_HID_LOGITECH_DJ",
+        "pairs devices behind a Unifying or Bolt receiver",
+    ),
+    (
+        "logitech_hidpp_device",
+        "CONFIG_HID_LOGITECH_HIDPP",
+        "drives Logitech devices paired to a receiver",
+    ),
+    (
+        "apple",
+        "CONFIG_HID_APPLE",
+        "maps Apple keyboard function keys",
+    ),
+    (
+        "magicmouse",
+        "CONFIG_HID_MAGICMOUSE",
+        "drives the Apple Magic Mouse and Trackpad",
+    ),
+    (
+        "microsoft",
+        "CONFIG_HID_MICROSOFT",
+        "fixes Microsoft keyboard and mouse reports",
+    ),
+    (
+        "i2c_hid_acpi",
+        "CONFIG_I2C_HID_ACPI",
+        "i2c-hid drives laptop touchpads and touchscreens on I2C",
+    ),
+    (
+        "i2c_hid_of",
+        "CONFIG_I2C_HID_OF",
+        "i2c-hid drives touchpads and touchscreens described by the device tree",
+    ),
+    (
+        "elan_i2c",
+        "CONFIG_MOUSE_ELAN_I2C",
+        "drives Elan touchpads on I2C",
+    ),
+    (
+        "rmi4_i2c",
+        "CONFIG_RMI4_I2C",
+        "drives Synaptics RMI4 touchpads on I2C",
+    ),
+    (
+        "rmi4_smbus",
+        "CONFIG_RMI4_SMB",
+        "drives Synaptics pads on SMBus",
+    ),
+    (
+        "xpad",
+        "CONFIG_JOYSTICK_XPAD",
+        "drives Xbox and compatible gamepads",
+    ),
+    (
+        "uvcvideo",
+        "CONFIG_USB_VIDEO_CLASS",
+        "the webcam driver also reports the camera's button",
+    ),
+    (
+        "atkbd",
+        "CONFIG_KEYBOARD_ATKBD",
+        "drives the built-in keyboard",
+    ),
+    (
+        "psmouse",
+        "CONFIG_MOUSE_PS2",
+        "drives PS/2 mice and touchpads",
+    ),
+    (
+        "i8042",
+        "CONFIG_SERIO_I8042",
+        "the PS/2 controller built-in keyboards and touchpads hang off",
+    ),
+    (
+        "btusb",
+        "CONFIG_BT_HCIBTUSB",
+        "the Bluetooth controller the device is paired to",
+    ),
+    (
+        "xhci_hcd",
+        "CONFIG_USB_XHCI_HCD",
+        "the USB controller the device is plugged into",
+    ),
+    (
+        "ehci_pci",
+        "CONFIG_USB_EHCI_PCI",
+        "the USB 2 controller the device is plugged into",
+    ),
+    (
+        "i2c_designware",
+        "CONFIG_I2C_DESIGNWARE_PLATFORM",
+        "the I2C controller the device sits behind",
+    ),
+    (
+        "i2c_designware_pci",
+        "CONFIG_I2C_DESIGNWARE_PCI",
+        "the I2C controller the device sits behind",
+    ),
+    (
+        "i2c_i801",
+        "CONFIG_I2C_I801",
+        "the SMBus controller the device sits behind",
+    ),
+    (
+        "intel_lpss",
+        "CONFIG_MFD_INTEL_LPSS_PCI",
+        "exposes the I2C controllers of Intel laptops",
+    ),
+];
+
+/// Drivers of the built-in keyboard, which has to work before modules load, e.g. to type
+/// the disk passphrase
+const EARLY_DRIVERS: &[&str] = &["i8042", "atkbd"];
+
+/// Pin controllers, by driver name, whose GPIOs carry the interrupt of I2C touchpads and
+/// touchscreens; without them the device probes but never reports a touch
+const PIN_CONTROLLERS: &[(&str, &str)] = &[
+    ("amd_gpio", "CONFIG_PINCTRL_AMD"),
+    ("byt_gpio", "CONFIG_PINCTRL_BAYTRAIL"),
+    ("cherryview_pinctrl", "CONFIG_PINCTRL_CHERRYVIEW"),
+    ("broxton_pinctrl", "CONFIG_PINCTRL_BROXTON"),
+    ("geminilake_pinctrl", "CONFIG_PINCTRL_GEMINILAKE"),
+    ("sunrisepoint_pinctrl", "CONFIG_PINCTRL_SUNRISEPOINT"),
+    ("cannonlake_pinctrl", "CONFIG_PINCTRL_CANNONLAKE"),
+    ("icelake_pinctrl", "CONFIG_PINCTRL_ICELAKE"),
+    ("jasperlake_pinctrl", "CONFIG_PINCTRL_JASPERLAKE"),
+    ("elkhartlake_pinctrl", "CONFIG_PINCTRL_ELKHARTLAKE"),
+    ("tigerlake_pinctrl", "CONFIG_PINCTRL_TIGERLAKE"),
+    ("alderlake_pinctrl", "CONFIG_PINCTRL_ALDERLAKE"),
+    ("meteorlake_pinctrl", "CONFIG_PINCTRL_METEORLAKE"),
+    ("lakefield_pinctrl", "CONFIG_PINCTRL_LAKEFIELD"),
+];
+
+/// What an input device is, as far as its name and handlers tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputKind {
+    Keyboard,
+    Mouse,
+    Touchpad,
+    Touchscreen,
+    /// Drawing tablets and pens
+    Tablet,
+    /// Anything else with an event device: macropads, game controllers, remotes
+    Other,
+}
+
+impl InputKind {
+    /// Whether losing the device leaves the user unable to operate the machine
+    pub fn is_essential(self) -> bool {
+        matches!(
+            self,
+            InputKind::Keyboard | InputKind::Mouse | InputKind::Touchpad
+        )
+    }
+}
+
+/// One entry of /proc/bus/input/devices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDevice {
+    pub name: String,
+    pub bus: u16,
+    pub vendor: u16,
+    pub product: u16,
+    /// sysfs path such as `/devices/platform/i8042/serio1/input/input5`
+    pub sysfs: String,
+    /// Handlers such as `mouse0` and `event5`
+    pub handlers: Vec<String>,
+    /// Drivers bound along the sysfs path, the device's own first, e.g. `hid_multitouch`,
+    /// `i2c_hid_acpi`, `i2c_designware`; I2C devices also list the pin controllers. Empty
+    /// when sysfs was not read
+    #[serde(default)]
+    pub drivers: Vec<String>,
+}
+
+/// Parses /proc/bus/input/devices, one blank-line separated block per device
+pub fn parse_input_devices(text: &str) -> Vec<InputDevice> {
+    let mut devices = Vec::new();
+    for block in text.split("\n\n") {
+        let mut device = InputDevice {
+            name: String::new(),
+            bus: 0,
+            vendor: 0,
+            product: 0,
+            sysfs: String::new(),
+            handlers: Vec::new(),
+            drivers: Vec::new(),
+        };
+        let mut identified = false;
+        for line in block.lines() {
+            let Some((tag, rest)) = line.split_once(": ") else {
+                continue;
+            };
+            match tag {
+                "I" => {
+                    for field in rest.split_whitespace() {
+                        let Some((key, value)) = field.split_once('=') else {
+                            continue;
+                        };
+                        let Ok(value) = u16::from_str_radix(value, 16) else {
+                            continue;
+                        };
+                        match key {
+                            "Bus" => {
+                                device.bus = value;
+                                identified = true;
+                            }
+                            "Vendor" => device.vendor = value,
+                            "Product" => device.product = value,
+                            _ => {}
+                        }
+                    }
+                }
+                "N" => {
+                    device.name = rest
+                        .strip_prefix("Name=")
+                        .unwrap_or(rest)
+                        .trim_matches('"')
+                        .to_string()
+                }
+                "S" => device.sysfs = rest.strip_prefix("Sysfs=").unwrap_or(rest).to_string(),
+                "H" => {
+                    device.handlers = rest
+                        .strip_prefix("Handlers=")
+                        .unwrap_or(rest)
+                        .split_whitespace()
+                        .map(str::to_string)
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        if identified {
+            devices.push(device);
+        }
+    }
+    devices
+}
+
+/// Devices attached to the machine with the drivers they are bound to, skipping virtual
+/// ones such as uinput and buttons of the platform itself, which no config choice removes
+pub fn detect_input_devices(system: &dyn SystemFacade) -> Vec<InputDevice> {
+    let text = system
+        .read_to_string(Path::new(PROC_INPUT_DEVICES))
+        .unwrap_or_default();
+    let pin_controllers = pin_controllers(system);
+    parse_input_devices(&text)
+        .into_iter()
+        .filter(|device| {
+            matches!(device.bus, BUS_USB | BUS_BLUETOOTH | BUS_I8042 | BUS_I2C)
+                && !device.sysfs.starts_with("/devices/virtual/")
+        })
+        .map(|mut device| {
+            device.drivers = bound_drivers(system, &device.sysfs);
+            if device.bus == BUS_I2C {
+                device.drivers.extend(pin_controllers.iter().cloned());
+            }
+            device
+        })
+        .collect()
+}
+
+/// Drivers bound to the devices above an input node, nearest first, from the `DRIVER=`
+/// line each parent's uevent carries while its `driver` link is bound
+fn bound_drivers(system: &dyn SystemFacade, sysfs: &str) -> Vec<String> {
+    let node = PathBuf::from(format!("/sys{}", sysfs));
+    let mut drivers: Vec<String> = Vec::new();
+    for dir in node
+        .ancestors()
+        .take_while(|dir| dir.starts_with("/sys/devices") && *dir != Path::new("/sys/devices"))
+    {
+        let Some(driver) = system
+            .read_to_string(&dir.join("uevent"))
+            .ok()
+            .and_then(|uevent| uevent_value(&uevent, "DRIVER"))
+        else {
+            continue;
+        };
+        let driver = driver.replace('-', "_");
+        if !drivers.contains(&driver) {
+            drivers.push(driver);
+        }
+    }
+    drivers
+}
+
+/// Pin controller drivers bound on the platform bus
+fn pin_controllers(system: &dyn SystemFacade) -> Vec<String> {
+    let mut drivers: Vec<String> = system
+        .read_dir(Path::new(SYS_PLATFORM_DEVICES))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|device| system.read_to_string(&device.join("uevent")).ok())
+        .filter_map(|uevent| uevent_value(&uevent, "DRIVER"))
+        .map(|driver| driver.replace('-', "_"))
+        .filter(|driver| PIN_CONTROLLERS.iter().any(|(name, _)| name == driver))
+        .collect();
+    drivers.sort();
+    drivers.dedup();
+    drivers
+}
+
+/// Option of a driver from [`DRIVERS`] or [`PIN_CONTROLLERS`], None for drivers no config
+/// choice of ours removes
+fn driver_requirement(driver: &str) -> Option<ConfigSuggestion> {
+    if let Some((_, symbol, reason)) = DRIVERS.iter().find(|(name, _, _)| *name == driver) {
+        let value = if EARLY_DRIVERS.contains(&driver) {
+            ConfigValue::yes()
+        } else {
+            ConfigValue::module()
+        };
+        return Some(ConfigSuggestion::new(symbol, value, reason));
+    }
+    let (_, symbol) = PIN_CONTROLLERS.iter().find(|(name, _)| *name == driver)?;
+    // Pin controllers probe early and most cannot be modules
+    Some(ConfigSuggestion::new(
+        symbol,
+        ConfigValue::yes(),
+        "the pin controller the I2C device's interrupt line comes from",
+    ))
+}
+
+impl InputDevice {
+    pub fn kind(&self) -> InputKind {
+        let name = self.name.to_lowercase();
+        let handler = |prefix: &str| {
+            self.handlers
+                .iter()
+                .any(|handler| handler.starts_with(prefix))
+        };
+        if name.contains("touchpad") || name.contains("trackpad") {
+            InputKind::Touchpad
+        } else if name.contains("touchscreen") {
+            InputKind::Touchscreen
+        } else if self.vendor == VENDOR_WACOM
+            || name.contains("wacom")
+            || name
+                .split_whitespace()
+                .any(|word| word == "pen" || word == "stylus")
+        {
+            InputKind::Tablet
+        } else if handler("mouse") {
+            InputKind::Mouse
+        } else if handler("kbd") {
+            InputKind::Keyboard
+        } else {
+            InputKind::Other
+        }
+    }
+
+    /// Whether the device speaks HID; HID over I2C devices sit under a `0018:` HID node
+    fn is_hid(&self) -> bool {
+        match self.bus {
+            BUS_USB | BUS_BLUETOOTH => true,
+            BUS_I2C => self.sysfs.contains("/0018:"),
+            _ => false,
+        }
+    }
+
+    /// Options the device needs to keep working: those of the drivers bound along its
+    /// sysfs path when they were read, else guessed from its bus, vendor and name
+    pub fn requirements(&self) -> Vec<ConfigSuggestion> {
+        let evdev = ConfigSuggestion::new(
+            "CONFIG_INPUT_EVDEV",
+            ConfigValue::yes(),
+            "libinput and the display server read every device through evdev",
+        );
+        let bound: Vec<ConfigSuggestion> = if self.drivers.is_empty() {
+            self.guessed_requirements()
+        } else {
+            self.drivers
+                .iter()
+                .filter_map(|driver| driver_requirement(driver))
+                .collect()
+        };
+        // The controllers behind an I2C device need the bus core itself
+        let bus = (self.bus == BUS_I2C).then(|| {
+            ConfigSuggestion::new(
+                "CONFIG_I2C",
+                ConfigValue::yes(),
+                "the I2C core laptop touchpads and touchscreens sit on",
+            )
+        });
+        requirements::unique(
+            std::iter::once(evdev)
+                .chain(bus)
+                .chain(bound)
+                .chain(self.ps2_protocols()),
+        )
+    }
+
+    /// Options of a PS/2 touchpad's protocol, which psmouse picks by probing; without
+    /// them the pad is a basic PS/2 mouse
+    fn ps2_protocols(&self) -> Vec<ConfigSuggestion> {
+        let mut required = Vec::new();
+        if self.bus != BUS_I8042 {
+            return required;
+        }
+        let name = self.name.to_lowercase();
+        let protocols = [
+            ("synaptics", "CONFIG_MOUSE_PS2_SYNAPTICS"),
+            ("elantech", "CONFIG_MOUSE_PS2_ELANTECH"),
+            ("alps", "CONFIG_MOUSE_PS2_ALPS"),
+            ("trackpoint", "CONFIG_MOUSE_PS2_TRACKPOINT"),
+        ];
+        for (vendor, symbol) in protocols {
+            if name.contains(vendor) {
+                required.push(ConfigSuggestion::new(
+                    symbol,
+                    ConfigValue::yes(),
+                    "the touchpad protocol, without it the pad is a basic PS/2 mouse",
+                ));
+            }
+        }
+        // Newer Synaptics pads are reached over SMBus once identified on PS/2
+        if name.contains("synaptics") {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_MOUSE_PS2_SYNAPTICS_SMBUS",
+                ConfigValue::yes(),
+                "switches Synaptics pads to their SMBus interface",
+            ));
+            required.push(ConfigSuggestion::new(
+                "CONFIG_RMI4_SMB",
+                ConfigValue::module(),
+                "drives Synaptics pads on SMBus",
+            ));
+        }
+        required
+    }
+
+    /// Drivers guessed from the bus, vendor and name, for devices whose sysfs was not read
+    fn guessed_requirements(&self) -> Vec<ConfigSuggestion> {
+        let name = self.name.to_lowercase();
+        let kind = self.kind();
+        let mut required = Vec::new();
+        let mut push = |symbol: &str, value: ConfigValue, reason: &str| {
+            required.push(ConfigSuggestion::new(symbol, value, reason))
+        };
+        match self.bus {
+            BUS_USB => push(
+                "CONFIG_USB_HID",
+                ConfigValue::module(),
+                "usbhid carries USB keyboards, mice and pads",
+            ),
+            BUS_BLUETOOTH => {
+                push(
+                    "CONFIG_BT_HIDP",
+                    ConfigValue::module(),
+                    "connects Bluetooth keyboards and mice",
+                );
+                push(
+                    "CONFIG_UHID",
+                    ConfigValue::module(),
+                    "BlueZ hands Bluetooth LE input devices to the kernel through uhid",
+                );
+            }
+            BUS_I2C if self.is_hid() => push(
+                "CONFIG_I2C_HID_ACPI",
+                ConfigValue::module(),
+                "i2c-hid drives laptop touchpads and touchscreens on I2C",
+            ),
+            BUS_I2C if name.contains("elan") => push(
+                "CONFIG_MOUSE_ELAN_I2C",
+                ConfigValue::module(),
+                "drives Elan touchpads on I2C",
+            ),
+            BUS_I2C if name.contains("synaptics") => push(
+                "CONFIG_RMI4_I2C",
+                ConfigValue::module(),
+                "drives Synaptics RMI4 touchpads on I2C",
+            ),
+            BUS_I8042 => {
+                push(
+                    "CONFIG_SERIO_I8042",
+                    ConfigValue::yes(),
+                    "the PS/2 controller built-in keyboards and touchpads hang off",
+                );
+                if kind == InputKind::Keyboard {
+                    push(
+                        "CONFIG_KEYBOARD_ATKBD",
+                        ConfigValue::yes(),
+                        "drives the built-in keyboard",
+                    );
+                } else {
+                    push(
+                        "CONFIG_MOUSE_PS2",
+                        ConfigValue::module(),
+                        "drives PS/2 mice and touchpads",
+                    );
+                }
+            }
+            _ => {}
+        }
+        if self.is_hid() {
+            push(
+                "CONFIG_HID_GENERIC",
+                ConfigValue::module(),
+                "the HID driver for devices without a dedicated one",
+            );
+            if matches!(kind, InputKind::Touchpad | InputKind::Touchscreen) {
+                push(
+                    "CONFIG_HID_MULTITOUCH",
+                    ConfigValue::module(),
+                    "drives multitouch pads and screens; hid-generic only sees a mouse",
+                );
+            }
+            if self.vendor == VENDOR_WACOM || name.contains("wacom") {
+                push(
+                    "CONFIG_HID_WACOM",
+                    ConfigValue::module(),
+                    "drives Wacom tablets and pens with pressure and tilt",
+                );
+            }
+            match self.vendor {
+                VENDOR_LOGITECH => {
+                    push(
+                        "CONFIG_HID_LOGITECH",
+                        ConfigValue::module(),
+                        "drives Logitech devices",
+                    );
+                    push(
+                        "CONFIG_HID_LOGITECH_DJ",
+                        ConfigValue::module(),
+                        "pairs devices behind a Unifying or Bolt receiver",
+                    );
+                    push(
+                        "CONFIG_HID_LOGITECH_HIDPP",
+                        ConfigValue::module(),
+                        "reports battery and high resolution scrolling",
+                    );
+                }
+                VENDOR_APPLE => push(
+                    "CONFIG_HID_APPLE",
+                    ConfigValue::module(),
+                    "maps Apple keyboard function keys",
+                ),
+                VENDOR_MICROSOFT => push(
+                    "CONFIG_HID_MICROSOFT",
+                    ConfigValue::module(),
+                    "fixes Microsoft keyboard and mouse reports",
+                ),
+                _ => {}
+            }
+        }
+        required
+    }
+}
+
+/// A device with what it was taken for and the options it needs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputAnalysis {
+    pub device: InputDevice,
+    pub kind: InputKind,
+    pub requirements: Vec<ConfigSuggestion>,
+}
+
+/// Maps every device to its kind and options, for the input matrix
+pub fn analyze(devices: &[InputDevice]) -> Vec<InputAnalysis> {
+    devices
+        .iter()
+        .map(|device| InputAnalysis {
+            device: device.clone(),
+            kind: device.kind(),
+            requirements: device.requirements(),
+        })
+        .collect()
+}
+
+/// The attached devices, each needing its own driver
+impl MachineRequirements for Vec<InputDevice> {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
+    /// Options the devices need, each symbol once with the first reason given for it
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        requirements::unique(self.iter().flat_map(InputDevice::requirements))
+    }
+
+    /// Names of the devices needing `symbol`
+    fn users_of(&self, symbol: &str) -> Vec<String> {
+        self.iter()
+            .filter(|device| {
+                device
+                    .requirements()
+                    .iter()
+                    .any(|requirement| requirement.symbol == symbol)
+            })
+            .map(|device| device.name.clone())
+            .collect()
+    }
+
+    /// One finding per device the plan breaks; a blocker for keyboards and pointing devices
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        broken(self, &missing)
+            .into_iter()
+            .map(|(device, missing)| {
+                let severity = if device.kind().is_essential() {
+                    Severity::Blocker
+                } else {
+                    Severity::Warning
+                };
+                let symbols: Vec<&str> = missing
+                    .iter()
+                    .map(|requirement| requirement.symbol.as_str())
+                    .collect();
+                let mut advisory = Advisory::new(
+                    severity,
+                    "input-missing-config",
+                    &[
+                        ("device", device.name.clone()),
+                        ("symbols", symbols.join(", ")),
+                    ],
+                );
+                for requirement in missing {
+                    advisory = advisory.suggest(requirement);
+                }
+                advisory
+            })
+            .collect()
+    }
+
+    /// Refuses a config leaving a keyboard or pointing device without its driver; other
+    /// devices are only warned about
+    fn enforce(&self, config: &KernelConfig) -> Result<()> {
+        let essential: Vec<String> = broken(self, &self.missing(config))
+            .into_iter()
+            .filter(|(device, _)| device.kind().is_essential())
+            .map(|(device, missing)| {
+                let symbols: Vec<&str> = missing
+                    .iter()
+                    .map(|requirement| requirement.symbol.as_str())
+                    .collect();
+                format!("{} needs {}", device.name, symbols.join(", "))
+            })
+            .collect();
+        if !essential.is_empty() {
+            bail!(
+                "the plan would disable input devices: {}",
+                essential.join("; ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Devices left without a driver when `missing` is left out, with what each misses
+fn broken<'a>(
+    devices: &'a [InputDevice],
+    missing: &[ConfigSuggestion],
+) -> Vec<(&'a InputDevice, Vec<ConfigSuggestion>)> {
+    devices
+        .iter()
+        .filter_map(|device| {
+            let lacking: Vec<ConfigSuggestion> = device
+                .requirements()
+                .into_iter()
+                .filter(|requirement| {
+                    missing
+                        .iter()
+                        .any(|absent| absent.symbol == requirement.symbol)
+                })
+                .collect();
+            (!lacking.is_empty()).then_some((device, lacking))
+        })
+        .collect()
+}
@@ -0,0 +1,455 @@
+// src-tauri/src/core/install.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::bootloader::{BootEntryRequest, Bootloader};
+use super::clock::unix_now;
+use super::distro::{InstallTemplate, KernelArtifacts};
+use super::headers::headers_dir;
+use super::module_policy::ModulePolicy;
+use super::modules::ModuleIndex;
+use super::paths::Paths;
+use super::privilege::{PrivilegeBroker, PrivilegedAction};
+use super::state_file;
+use super::system::SystemFacade;
+
+/// Filesystem snapshot tools KernelForge can drive before touching /boot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotTool {
+    Snapper,
+    Timeshift,
+}
+
+/// One change made to the system, with enough detail to undo it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallStep {
+    /// A file was placed; `backup` holds the previous file if one was overwritten
+    CopiedFile { destination: PathBuf, backup: Option<PathBuf> },
+    /// A directory tree that did not exist before, such as /lib/modules/<version>
+    CreatedTree { path: PathBuf },
+    BootEntry { bootloader: Bootloader, version: String },
+    /// Distro hooks such as mkinitcpio presets, kernel-install or update-initramfs
+    TemplateHooks { template: InstallTemplate, version: String },
+    Depmod { version: String },
+    /// DKMS builds land in the module tree; its own records are cleaned with `dkms remove`
+    DkmsAutoinstall { version: String },
+    /// Snapshots are kept on rollback; restoring one would undo more than this install
+    Snapshot { tool: SnapshotTool, id: String },
+}
+
+/// A step together with whether it completed
+/// Steps are written before they run so a crash leaves a record of what was attempted;
+/// only completed steps are undone on rollback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedStep {
+    pub step: InstallStep,
+    pub completed: bool,
+}
+
+/// Where the modules of the new kernel come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModuleSource {
+    /// `make modules_install` in the build tree
+    BuildTree(PathBuf),
+    /// A directory staged with INSTALL_MOD_PATH, containing lib/modules/<version>
+    Staged(PathBuf),
+}
+
+/// Lifecycle of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionState {
+    InProgress,
+    Committed,
+    RolledBack,
+}
+
+/// Persistent log of everything one kernel installation changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallTransaction {
+    pub id: String,
+    pub version: String,
+    pub started_at: u64,
+    pub state: TransactionState,
+    pub steps: Vec<RecordedStep>,
+    #[serde(skip)]
+    log_path: PathBuf,
+}
+
+impl InstallTransaction {
+    /// Starts a new transaction and writes its log
+    pub fn begin(paths: &Paths, version: &str) -> Result<Self> {
+        let started_at = unix_now();
+        let id = format!("{}-{}", started_at, version);
+        let dir = Paths::ensure(paths.transactions_dir())?;
+        let transaction = InstallTransaction {
+            log_path: dir.join(format!("{}.json", id)),
+            id,
+            version: version.to_string(),
+            started_at,
+            state: TransactionState::InProgress,
+            steps: Vec::new(),
+        };
+        transaction.save()?;
+        Ok(transaction)
+    }
+
+    /// Loads a transaction log
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut transaction: InstallTransaction =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        transaction.log_path = path.to_path_buf();
+        Ok(transaction)
+    }
+
+    /// All recorded transactions, oldest first
+    pub fn list(paths: &Paths) -> Result<Vec<Self>> {
+        let dir = paths.transactions_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut transactions = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                transactions.push(InstallTransaction::load(&path)?);
+            }
+        }
+        transactions.sort_by_key(|transaction| transaction.started_at);
+        Ok(transactions)
+    }
+
+    /// Transactions that never reached commit or rollback, e.g. after a crash
+    pub fn unfinished(paths: &Paths) -> Result<Vec<Self>> {
+        Ok(InstallTransaction::list(paths)?
+            .into_iter()
+            .filter(|transaction| transaction.state == TransactionState::InProgress)
+            .collect())
+    }
+
+    /// Copies a file into place, backing up whatever it replaces
+    pub fn copy_file(
+        &mut self,
+        broker: &PrivilegeBroker,
+        system: &dyn SystemFacade,
+        from: &Path,
+        to: &Path,
+    ) -> Result<()> {
+        let backup = system
+            .exists(to)
+            .then(|| PathBuf::from(format!("{}.kforig-{}", to.display(), self.id)));
+        let mut actions = Vec::new();
+        if let Some(backup) = &backup {
+            actions.push(run("cp", &["-a", &to.to_string_lossy(), &backup.to_string_lossy()]));
+        }
+        actions.push(run("install", &["-D", "-m", "644", &from.to_string_lossy(), &to.to_string_lossy()]));
+        self.perform(
+            broker,
+            InstallStep::CopiedFile {
+                destination: to.to_path_buf(),
+                backup,
+            },
+            &actions,
+        )
+    }
+
+    /// Installs the module policy as this version's modprobe.d file; rolled back like any
+    /// copied file
+    fn install_module_policy(
+        &mut self,
+        broker: &PrivilegeBroker,
+        system: &dyn SystemFacade,
+        policy: &ModulePolicy,
+    ) -> Result<()> {
+        if policy.is_empty() {
+            return Ok(());
+        }
+        let staged = self.log_path.with_extension("modprobe.conf");
+        fs::write(&staged, policy.render(&self.version)?)
+            .with_context(|| format!("writing {}", staged.display()))?;
+        let result = self.copy_file(broker, system, &staged, &ModulePolicy::path(&self.version));
+        let _ = fs::remove_file(&staged);
+        result
+    }
+
+    /// Records a directory tree created by an external step such as `make modules_install`
+    pub fn created_tree(&mut self, path: &Path) -> Result<()> {
+        self.steps.push(RecordedStep {
+            step: InstallStep::CreatedTree {
+                path: path.to_path_buf(),
+            },
+            completed: true,
+        });
+        self.save()
+    }
+
+    /// Registers the kernel with the boot manager
+    pub fn add_boot_entry(
+        &mut self,
+        broker: &PrivilegeBroker,
+        bootloader: &Bootloader,
+        entry: &BootEntryRequest,
+    ) -> Result<()> {
+        self.perform(
+            broker,
+            InstallStep::BootEntry {
+                bootloader: bootloader.clone(),
+                version: entry.version.clone(),
+            },
+            &bootloader.install_actions(entry),
+        )
+    }
+
+    /// Places the build outputs and the plan's module policy where the distro expects them
+    /// and runs its hooks
+    /// Returns the installed image and initramfs for a boot entry, unless the template made one
+    pub fn apply_template(
+        &mut self,
+        broker: &PrivilegeBroker,
+        system: &dyn SystemFacade,
+        template: InstallTemplate,
+        artifacts: &KernelArtifacts,
+        module_policy: &ModulePolicy,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        let version = self.version.clone();
+        for (from, to) in template.files(&version, artifacts) {
+            self.copy_file(broker, system, &from, &to)?;
+        }
+        // Before the hooks, so the initramfs they generate carries the policy
+        self.install_module_policy(broker, system, module_policy)?;
+        self.perform(
+            broker,
+            InstallStep::TemplateHooks {
+                template,
+                version: version.clone(),
+            },
+            &template.hook_actions(&version),
+        )?;
+        Ok((template.kernel_path(&version), template.initramfs_path(&version)))
+    }
+
+    /// Installs /lib/modules/<version>, recorded so a rollback removes the whole tree
+    pub fn install_modules(&mut self, broker: &PrivilegeBroker, system: &dyn SystemFacade, source: &ModuleSource) -> Result<()> {
+        let destination = ModuleIndex::dir(&self.version);
+        if system.exists(&destination) {
+            bail!("{} already exists; remove it or pick another version", destination.display());
+        }
+        let action = match source {
+            ModuleSource::BuildTree(dir) => run("make", &["-C", &dir.to_string_lossy(), "modules_install"]),
+            ModuleSource::Staged(dir) => {
+                let staged = dir.join("lib/modules").join(&self.version);
+                if !system.exists(&staged) {
+                    bail!("{} does not exist", staged.display());
+                }
+                run("cp", &["-a", "--", &staged.to_string_lossy(), &destination.to_string_lossy()])
+            }
+        };
+        self.perform(broker, InstallStep::CreatedTree { path: destination }, &[action])
+    }
+
+    /// Generates module dependency files for the new version and checks modules.dep was written
+    pub fn run_depmod(&mut self, broker: &PrivilegeBroker, system: &dyn SystemFacade) -> Result<ModuleIndex> {
+        let version = self.version.clone();
+        self.perform(
+            broker,
+            InstallStep::Depmod {
+                version: version.clone(),
+            },
+            &[run("depmod", &["-a", &version])],
+        )?;
+        ModuleIndex::load(system, &version).context("depmod did not produce a usable modules.dep")
+    }
+
+    /// Installs a staged headers tree and points /lib/modules/<version>/build at it for DKMS
+    pub fn install_headers(&mut self, broker: &PrivilegeBroker, system: &dyn SystemFacade, staged: &Path) -> Result<()> {
+        let destination = headers_dir(&self.version);
+        if system.exists(&destination) {
+            bail!("{} already exists", destination.display());
+        }
+        let link = ModuleIndex::dir(&self.version).join("build");
+        self.perform(
+            broker,
+            InstallStep::CreatedTree {
+                path: destination.clone(),
+            },
+            &[
+                run("cp", &["-a", "--", &staged.to_string_lossy(), &destination.to_string_lossy()]),
+                run("ln", &["-sfn", &destination.to_string_lossy(), &link.to_string_lossy()]),
+            ],
+        )
+    }
+
+    /// Rebuilds every registered DKMS module (NVIDIA, ZFS, ...) for the new version
+    pub fn run_dkms_autoinstall(&mut self, broker: &PrivilegeBroker) -> Result<()> {
+        let version = self.version.clone();
+        self.perform(
+            broker,
+            InstallStep::DkmsAutoinstall {
+                version: version.clone(),
+            },
+            &[run("dkms", &["autoinstall", "-k", &version])],
+        )
+    }
+
+    /// Fails when any of `critical` cannot be loaded or is not built in; run before the boot entry
+    pub fn verify_modules(&self, system: &dyn SystemFacade, critical: &[String]) -> Result<()> {
+        let index = ModuleIndex::load(system, &self.version)?;
+        let missing = index.missing(critical);
+        if !missing.is_empty() {
+            bail!(
+                "critical modules missing from {}: {}",
+                ModuleIndex::dir(&self.version).display(),
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Takes a filesystem snapshot with snapper or timeshift when either is installed
+    pub fn snapshot(&mut self, system: &dyn SystemFacade) -> Result<Option<String>> {
+        let description = format!("KernelForge before installing {}", self.version);
+        let (tool, output) = if system.has_program("snapper") {
+            let output = system.run(
+                "snapper",
+                &["create", "--type", "single", "--print-number", "--description", &description],
+            )?;
+            (SnapshotTool::Snapper, output)
+        } else if system.has_program("timeshift") {
+            let output = system.run("timeshift", &["--create", "--comments", &description])?;
+            (SnapshotTool::Timeshift, output)
+        } else {
+            return Ok(None);
+        };
+        if !output.success() {
+            bail!("snapshot failed: {}", output.stderr.trim());
+        }
+        let id = match tool {
+            SnapshotTool::Snapper => output.stdout.trim().to_string(),
+            // timeshift prints "Tagged snapshot '<name>': ondemand"
+            SnapshotTool::Timeshift => output
+                .stdout
+                .lines()
+                .find_map(|line| line.split('\'').nth(1))
+                .unwrap_or_default()
+                .to_string(),
+        };
+        self.steps.push(RecordedStep {
+            step: InstallStep::Snapshot { tool, id: id.clone() },
+            completed: true,
+        });
+        self.save()?;
+        Ok(Some(id))
+    }
+
+    /// Marks the installation as done
+    pub fn commit(&mut self) -> Result<()> {
+        self.state = TransactionState::Committed;
+        self.save()
+    }
+
+    /// Undoes every completed step in reverse order
+    /// A failing undo does not stop the others; every failure is reported together and the
+    /// transaction stays in progress so the remaining steps can be retried
+    pub fn rollback(&mut self, broker: &PrivilegeBroker, system: &dyn SystemFacade) -> Result<()> {
+        if self.state == TransactionState::RolledBack {
+            return Ok(());
+        }
+        let mut failures = Vec::new();
+        for recorded in self.steps.iter_mut().rev().filter(|recorded| recorded.completed) {
+            let undone = undo_actions(&recorded.step, system)
+                .iter()
+                .try_for_each(|action| broker.execute(action));
+            match undone {
+                Ok(()) => recorded.completed = false,
+                Err(error) => failures.push(format!("{:?}: {:#}", recorded.step, error)),
+            }
+        }
+        if failures.is_empty() {
+            self.state = TransactionState::RolledBack;
+        }
+        self.save()?;
+        if !failures.is_empty() {
+            bail!("rolling back {} failed:\n{}", self.id, failures.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Snapshots taken during the install, listed so users can restore them by hand
+    pub fn snapshots(&self) -> Vec<(SnapshotTool, &str)> {
+        self.steps
+            .iter()
+            .filter_map(|recorded| match &recorded.step {
+                InstallStep::Snapshot { tool, id } => Some((*tool, id.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn perform(&mut self, broker: &PrivilegeBroker, step: InstallStep, actions: &[PrivilegedAction]) -> Result<()> {
+        self.steps.push(RecordedStep { step, completed: false });
+        self.save()?;
+        broker.execute_all(actions)?;
+        if let Some(last) = self.steps.last_mut() {
+            last.completed = true;
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        state_file::write(&self.log_path, text.as_bytes())
+    }
+}
+
+/// Actions reversing one step; all of them tolerate the step having only partly run
+fn undo_actions(step: &InstallStep, system: &dyn SystemFacade) -> Vec<PrivilegedAction> {
+    match step {
+        InstallStep::CopiedFile { destination, backup } => match backup {
+            Some(backup) if system.exists(backup) => vec![run(
+                "mv",
+                &["-f", &backup.to_string_lossy(), &destination.to_string_lossy()],
+            )],
+            // The original was never backed up, so the destination still holds it
+            Some(_) => Vec::new(),
+            // Nothing was there before; the transaction created the file
+            None => vec![PrivilegedAction::RemoveFile {
+                path: destination.clone(),
+            }],
+        },
+        InstallStep::CreatedTree { path } => vec![run("rm", &["-rf", "--", &path.to_string_lossy()])],
+        InstallStep::BootEntry { bootloader, version } => bootloader.remove_actions(version, system),
+        InstallStep::TemplateHooks { template, version } => template.remove_actions(version, system),
+        InstallStep::DkmsAutoinstall { version } => dkms_installed(system, version)
+            .into_iter()
+            .map(|module| run("dkms", &["remove", &module, "-k", version]))
+            .collect(),
+        // depmod output lives inside the module tree, which is removed with it
+        InstallStep::Depmod { .. } | InstallStep::Snapshot { .. } => Vec::new(),
+    }
+}
+
+/// `name/version` of DKMS modules built for a kernel, from lines like
+/// `nvidia/550.78, 6.9.3-kf, x86_64: installed`
+fn dkms_installed(system: &dyn SystemFacade, version: &str) -> Vec<String> {
+    let Ok(output) = system.run("dkms", &["status", "-k", version]) else {
+        return Vec::new();
+    };
+    output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|module| module.trim().to_string())
+        .filter(|module| module.contains('/'))
+        .collect()
+}
+
+fn run(program: &str, args: &[&str]) -> PrivilegedAction {
+    PrivilegedAction::Run {
+        program: program.to_string(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+    }
+}
@@ -0,0 +1,47 @@
+// src-tauri/src/core/jobs.rs
+
+use serde::{Deserialize, Serialize};
+
+use super::cpu::CpuInfo;
+use super::memory::MemoryInfo;
+
+/// Peak resident memory of a single compiler job on a typical kernel build
+pub const DEFAULT_KB_PER_JOB: u64 = 1536 * 1024;
+
+/// What capped the number of parallel jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobLimit {
+    Cpu,
+    Memory,
+}
+
+/// Parallelism chosen for `make -j`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobPlan {
+    pub jobs: usize,
+    pub limited_by: JobLimit,
+}
+
+impl JobPlan {
+    /// One job per logical CPU unless available RAM cannot hold that many compilers
+    pub fn for_machine(cpu: &CpuInfo, memory: &MemoryInfo) -> Self {
+        JobPlan::with_budget(cpu.logical_cpus, memory.available_kb, DEFAULT_KB_PER_JOB)
+    }
+
+    /// Same as [`JobPlan::for_machine`] with an explicit per-job memory estimate
+    pub fn with_budget(logical_cpus: usize, available_kb: u64, kb_per_job: u64) -> Self {
+        let by_memory = (available_kb / kb_per_job.max(1)).max(1) as usize;
+        let by_cpu = logical_cpus.max(1);
+        if by_memory < by_cpu {
+            JobPlan {
+                jobs: by_memory,
+                limited_by: JobLimit::Memory,
+            }
+        } else {
+            JobPlan {
+                jobs: by_cpu,
+                limited_by: JobLimit::Cpu,
+            }
+        }
+    }
+}
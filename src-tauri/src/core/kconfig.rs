@@ -0,0 +1,775 @@
+// src-tauri/src/core/kconfig.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::symbols::{SymbolId, SymbolTable};
+
+/// Three-state value used when evaluating Kconfig expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Tristate {
+    No,
+    Module,
+    Yes,
+}
+
+impl Tristate {
+    /// Interprets a raw .config value, anything other than y/m counts as n
+    pub fn from_value(value: &str) -> Tristate {
+        match value {
+            "y" => Tristate::Yes,
+            "m" => Tristate::Module,
+            _ => Tristate::No,
+        }
+    }
+
+    /// Spelling used in .config files
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tristate::No => "n",
+            Tristate::Module => "m",
+            Tristate::Yes => "y",
+        }
+    }
+
+    fn not(self) -> Tristate {
+        match self {
+            Tristate::No => Tristate::Yes,
+            Tristate::Module => Tristate::Module,
+            Tristate::Yes => Tristate::No,
+        }
+    }
+}
+
+/// Declared type of a Kconfig symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymbolType {
+    Bool,
+    Tristate,
+    Int,
+    Hex,
+    String,
+    Unknown,
+}
+
+/// Operand of a comparison inside an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Symbol(SymbolId),
+    Literal(String),
+}
+
+/// Comparison operators supported in Kconfig expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+}
+
+/// Parsed `depends on` / `if` / `default ... if` expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(Tristate),
+    Symbol(SymbolId),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(CompareOp, Operand, Operand),
+}
+
+impl Expr {
+    /// Evaluates the expression given the raw value of each symbol
+    pub fn eval<'v>(&self, value: &dyn Fn(SymbolId) -> Option<&'v str>) -> Tristate {
+        match self {
+            Expr::Const(state) => *state,
+            Expr::Symbol(id) => value(*id).map(Tristate::from_value).unwrap_or(Tristate::No),
+            Expr::Not(inner) => inner.eval(value).not(),
+            Expr::And(left, right) => left.eval(value).min(right.eval(value)),
+            Expr::Or(left, right) => left.eval(value).max(right.eval(value)),
+            Expr::Compare(op, left, right) => {
+                let resolve = |operand: &Operand| -> String {
+                    match operand {
+                        Operand::Symbol(id) => value(*id).unwrap_or("n").to_string(),
+                        Operand::Literal(text) => text.clone(),
+                    }
+                };
+                let (left, right) = (resolve(left), resolve(right));
+                let ordering = match (parse_number(&left), parse_number(&right)) {
+                    (Some(l), Some(r)) => l.cmp(&r),
+                    _ => left.cmp(&right),
+                };
+                let holds = match op {
+                    CompareOp::Eq => ordering.is_eq(),
+                    CompareOp::NotEq => !ordering.is_eq(),
+                    CompareOp::Less => ordering.is_lt(),
+                    CompareOp::LessEq => ordering.is_le(),
+                    CompareOp::Greater => ordering.is_gt(),
+                    CompareOp::GreaterEq => ordering.is_ge(),
+                };
+                if holds {
+                    Tristate::Yes
+                } else {
+                    Tristate::No
+                }
+            }
+        }
+    }
+
+    /// Calls `visit` for every symbol referenced by the expression
+    pub fn for_each_symbol(&self, visit: &mut dyn FnMut(SymbolId)) {
+        match self {
+            Expr::Const(_) => {}
+            Expr::Symbol(id) => visit(*id),
+            Expr::Not(inner) => inner.for_each_symbol(visit),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.for_each_symbol(visit);
+                right.for_each_symbol(visit);
+            }
+            Expr::Compare(_, left, right) => {
+                for operand in [left, right] {
+                    if let Operand::Symbol(id) = operand {
+                        visit(*id);
+                    }
+                }
+            }
+        }
+    }
+
+    fn and(left: Option<Expr>, right: Option<Expr>) -> Option<Expr> {
+        match (left, right) {
+            (Some(l), Some(r)) => Some(Expr::And(Box::new(l), Box::new(r))),
+            (l, r) => l.or(r),
+        }
+    }
+
+    fn or(left: Option<Expr>, right: Option<Expr>) -> Option<Expr> {
+        match (left, right) {
+            (Some(l), Some(r)) => Some(Expr::Or(Box::new(l), Box::new(r))),
+            // A definition without dependencies makes the symbol unconditionally available
+            _ => None,
+        }
+    }
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// One `config`/`menuconfig` entry after merging all of its definitions
+#[derive(Debug, Clone)]
+pub struct SymbolDef {
+    pub id: SymbolId,
+    pub kind: SymbolType,
+    pub prompt: Option<String>,
+    pub depends: Option<Expr>,
+    pub selects: Vec<(SymbolId, Option<Expr>)>,
+    pub implies: Vec<(SymbolId, Option<Expr>)>,
+    pub defaults: Vec<(String, Option<Expr>)>,
+    pub help: String,
+    /// Kconfig file of the first definition, relative to the source tree
+    pub file: Option<String>,
+}
+
+impl SymbolDef {
+    fn new(id: SymbolId) -> Self {
+        SymbolDef {
+            id,
+            kind: SymbolType::Unknown,
+            prompt: None,
+            depends: None,
+            selects: Vec::new(),
+            implies: Vec::new(),
+            defaults: Vec::new(),
+            help: String::new(),
+            file: None,
+        }
+    }
+}
+
+/// A `choice` block; when visible exactly one member is `y`, or none if `optional`
+#[derive(Debug, Clone, Default)]
+pub struct ChoiceGroup {
+    pub prompt: Option<String>,
+    pub members: Vec<SymbolId>,
+    pub defaults: Vec<(SymbolId, Option<Expr>)>,
+    pub optional: bool,
+}
+
+/// Enclosing `if`/`menu`/`choice` block while parsing
+struct Block {
+    depends: Option<Expr>,
+    choice: Option<usize>,
+}
+
+/// Entry whose attribute lines are currently being read
+enum Entry {
+    None,
+    Symbol(SymbolDef),
+    Block,
+    Choice(usize),
+}
+
+/// All symbols parsed from a kernel source tree
+#[derive(Debug, Default, Clone)]
+pub struct KconfigTree {
+    symbols: SymbolTable,
+    defs: Vec<SymbolDef>,
+    index: HashMap<SymbolId, usize>,
+    choices: Vec<ChoiceGroup>,
+    choice_of: HashMap<SymbolId, usize>,
+    /// File being parsed by `load`, recorded on the definitions it contains
+    current_file: Option<String>,
+}
+
+impl KconfigTree {
+    /// Creates an empty tree
+    pub fn new() -> Self {
+        KconfigTree::default()
+    }
+
+    /// Parses the whole tree starting at `<srctree>/Kconfig` for the given SRCARCH
+    pub fn load(srctree: &Path, arch: &str) -> Result<Self> {
+        let mut tree = KconfigTree::new();
+        let mut pending = vec![(srctree.join("Kconfig"), false, None)];
+        while let Some((path, optional, inherited)) = pending.pop() {
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(_) if optional => continue,
+                Err(err) => {
+                    return Err(err).with_context(|| format!("reading {}", path.display()))
+                }
+            };
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            tree.current_file = path
+                .strip_prefix(srctree)
+                .ok()
+                .map(|relative| relative.to_string_lossy().into_owned());
+            let mut sources = tree.parse_nested(&text, inherited);
+            // Sources are processed in file order, the stack pops from the end
+            sources.reverse();
+            for source in sources {
+                let file = source.path.replace("$(SRCARCH)", arch).replace("$SRCARCH", arch);
+                let resolved: PathBuf = if source.relative {
+                    dir.join(&file)
+                } else {
+                    srctree.join(&file)
+                };
+                pending.push((resolved, source.optional, source.depends));
+            }
+        }
+        tree.current_file = None;
+        Ok(tree)
+    }
+
+    /// Interner shared by every symbol in the tree
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// Mutable access to the interner, used when layers mention unknown symbols
+    pub fn symbols_mut(&mut self) -> &mut SymbolTable {
+        &mut self.symbols
+    }
+
+    /// Definition of a symbol, if the tree declares it
+    pub fn def(&self, id: SymbolId) -> Option<&SymbolDef> {
+        self.index.get(&id).map(|index| &self.defs[*index])
+    }
+
+    /// Definition looked up by name
+    pub fn def_by_name(&self, name: &str) -> Option<&SymbolDef> {
+        self.symbols.lookup(name).and_then(|id| self.def(id))
+    }
+
+    /// All defined symbols in declaration order
+    pub fn defs(&self) -> &[SymbolDef] {
+        &self.defs
+    }
+
+    /// All choice groups in declaration order
+    pub fn choices(&self) -> &[ChoiceGroup] {
+        &self.choices
+    }
+
+    /// Choice group a symbol belongs to
+    pub fn choice_of(&self, id: SymbolId) -> Option<&ChoiceGroup> {
+        self.choice_of.get(&id).map(|index| &self.choices[*index])
+    }
+
+    /// Parses one Kconfig file and returns the files it sources
+    pub fn parse_str(&mut self, text: &str) -> Vec<SourceDirective> {
+        self.parse_nested(text, None)
+    }
+
+    /// Parses a file sourced from inside `if`/`menu` blocks carrying `inherited` dependencies
+    fn parse_nested(&mut self, text: &str, inherited: Option<Expr>) -> Vec<SourceDirective> {
+        let mut sources = Vec::new();
+        let mut blocks = vec![Block {
+            depends: inherited,
+            choice: None,
+        }];
+        let mut entry = Entry::None;
+        let lines = join_continuations(text);
+        let mut cursor = 0;
+
+        while cursor < lines.len() {
+            let raw = &lines[cursor];
+            cursor += 1;
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (keyword, rest) = split_keyword(line);
+
+            match keyword {
+                "config" | "menuconfig" => {
+                    self.finish(&mut entry, &blocks);
+                    let id = self.symbols.intern(rest.trim());
+                    entry = Entry::Symbol(SymbolDef::new(id));
+                }
+                "menu" => {
+                    self.finish(&mut entry, &blocks);
+                    blocks.push(Block {
+                        depends: None,
+                        choice: None,
+                    });
+                    entry = Entry::Block;
+                }
+                "choice" => {
+                    self.finish(&mut entry, &blocks);
+                    let index = self.choices.len();
+                    self.choices.push(ChoiceGroup::default());
+                    blocks.push(Block {
+                        depends: None,
+                        choice: Some(index),
+                    });
+                    entry = Entry::Choice(index);
+                }
+                "if" => {
+                    self.finish(&mut entry, &blocks);
+                    let depends = parse_expr(rest, &mut self.symbols);
+                    blocks.push(Block {
+                        depends,
+                        choice: None,
+                    });
+                }
+                "endmenu" | "endchoice" | "endif" => {
+                    self.finish(&mut entry, &blocks);
+                    // The first block holds inherited dependencies and is never closed
+                    if blocks.len() > 1 {
+                        blocks.pop();
+                    }
+                }
+                "comment" | "mainmenu" => {
+                    self.finish(&mut entry, &blocks);
+                    entry = Entry::None;
+                }
+                "source" | "osource" | "rsource" | "orsource" => {
+                    self.finish(&mut entry, &blocks);
+                    sources.push(SourceDirective {
+                        path: unquote(rest.trim()).to_string(),
+                        optional: keyword.starts_with('o'),
+                        relative: keyword.contains("rsource"),
+                        depends: blocks
+                            .iter()
+                            .fold(None, |acc, block| Expr::and(acc, block.depends.clone())),
+                    });
+                }
+                "help" | "---help---" => {
+                    let help = read_help(&lines, &mut cursor);
+                    if let Entry::Symbol(def) = &mut entry {
+                        def.help = help;
+                    }
+                }
+                "depends" => {
+                    let condition = rest.trim_start();
+                    let condition = condition.strip_prefix("on").unwrap_or(condition);
+                    let expr = parse_expr(condition, &mut self.symbols);
+                    match &mut entry {
+                        Entry::Symbol(def) => def.depends = Expr::and(def.depends.take(), expr),
+                        Entry::Block | Entry::Choice(_) => {
+                            if let Some(block) = blocks.last_mut() {
+                                block.depends = Expr::and(block.depends.take(), expr);
+                            }
+                        }
+                        Entry::None => {}
+                    }
+                }
+                _ => match &mut entry {
+                    Entry::Symbol(def) => parse_attribute(def, keyword, rest, &mut self.symbols),
+                    Entry::Choice(index) => {
+                        parse_choice_attribute(&mut self.choices[*index], keyword, rest, &mut self.symbols)
+                    }
+                    Entry::None | Entry::Block => {}
+                },
+            }
+        }
+        self.finish(&mut entry, &blocks);
+        sources
+    }
+
+    fn finish(&mut self, entry: &mut Entry, blocks: &[Block]) {
+        let Entry::Symbol(mut def) = std::mem::replace(entry, Entry::None) else {
+            return;
+        };
+        for block in blocks.iter().rev() {
+            def.depends = Expr::and(block.depends.clone(), def.depends.take());
+        }
+        if let Some(choice) = blocks.iter().rev().find_map(|block| block.choice) {
+            let members = &mut self.choices[choice].members;
+            if !members.contains(&def.id) {
+                members.push(def.id);
+            }
+            self.choice_of.insert(def.id, choice);
+        }
+        match self.index.get(&def.id) {
+            Some(index) => {
+                let existing = &mut self.defs[*index];
+                if existing.kind == SymbolType::Unknown {
+                    existing.kind = def.kind;
+                }
+                if existing.prompt.is_none() {
+                    existing.prompt = def.prompt;
+                }
+                if existing.help.is_empty() {
+                    existing.help = def.help;
+                }
+                existing.depends = Expr::or(existing.depends.take(), def.depends);
+                existing.selects.extend(def.selects);
+                existing.implies.extend(def.implies);
+                existing.defaults.extend(def.defaults);
+            }
+            None => {
+                def.file = self.current_file.clone();
+                self.index.insert(def.id, self.defs.len());
+                self.defs.push(def);
+            }
+        }
+    }
+}
+
+/// A `source` line found while parsing
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceDirective {
+    pub path: String,
+    pub optional: bool,
+    pub relative: bool,
+    /// Dependencies of the enclosing blocks, inherited by the sourced file
+    pub depends: Option<Expr>,
+}
+
+fn parse_attribute(def: &mut SymbolDef, keyword: &str, rest: &str, symbols: &mut SymbolTable) {
+    let (value, condition) = split_condition(rest, symbols);
+    let kind = match keyword {
+        "bool" | "boolean" | "def_bool" => Some(SymbolType::Bool),
+        "tristate" | "def_tristate" => Some(SymbolType::Tristate),
+        "int" => Some(SymbolType::Int),
+        "hex" => Some(SymbolType::Hex),
+        "string" => Some(SymbolType::String),
+        _ => None,
+    };
+    if let Some(kind) = kind {
+        def.kind = kind;
+        if keyword.starts_with("def_") {
+            def.defaults.push((value.to_string(), condition));
+        } else if !value.is_empty() {
+            def.prompt = Some(unquote(value).to_string());
+        }
+        return;
+    }
+    match keyword {
+        "prompt" => def.prompt = Some(unquote(value).to_string()),
+        "default" => def.defaults.push((unquote(value).to_string(), condition)),
+        "select" => def.selects.push((symbols.intern(value), condition)),
+        "imply" => def.implies.push((symbols.intern(value), condition)),
+        _ => {}
+    }
+}
+
+fn parse_choice_attribute(choice: &mut ChoiceGroup, keyword: &str, rest: &str, symbols: &mut SymbolTable) {
+    let (value, condition) = split_condition(rest, symbols);
+    match keyword {
+        "prompt" | "bool" | "boolean" | "tristate" if !value.is_empty() => {
+            choice.prompt = Some(unquote(value).to_string())
+        }
+        "default" => choice.defaults.push((symbols.intern(value), condition)),
+        "optional" => choice.optional = true,
+        _ => {}
+    }
+}
+
+/// Splits `value if expr` into the value and its parsed condition
+fn split_condition<'a>(rest: &'a str, symbols: &mut SymbolTable) -> (&'a str, Option<Expr>) {
+    let rest = rest.trim();
+    let mut in_quotes = false;
+    let bytes = rest.as_bytes();
+    for index in 0..bytes.len() {
+        match bytes[index] {
+            b'"' => in_quotes = !in_quotes,
+            b' ' | b'\t' if !in_quotes && rest[index..].trim_start().starts_with("if ") => {
+                let condition = rest[index..].trim_start()[3..].trim();
+                return (rest[..index].trim(), parse_expr(condition, symbols));
+            }
+            _ => {}
+        }
+    }
+    (rest, None)
+}
+
+fn split_keyword(line: &str) -> (&str, &str) {
+    match line.find(char::is_whitespace) {
+        Some(index) => (&line[..index], &line[index..]),
+        None => (line, ""),
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (index, ch) in line.char_indices() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn join_continuations(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending = String::new();
+    for line in text.lines() {
+        if let Some(head) = line.strip_suffix('\\') {
+            pending.push_str(head);
+            continue;
+        }
+        pending.push_str(line);
+        lines.push(std::mem::take(&mut pending));
+    }
+    if !pending.is_empty() {
+        lines.push(pending);
+    }
+    lines
+}
+
+fn indentation(line: &str) -> usize {
+    let mut width = 0;
+    for ch in line.chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width = (width / 8 + 1) * 8,
+            _ => break,
+        }
+    }
+    width
+}
+
+/// Collects an indented help block, stopping at the first line indented less than its first line
+fn read_help(lines: &[String], cursor: &mut usize) -> String {
+    let mut help = Vec::new();
+    let mut block_indent = None;
+    while *cursor < lines.len() {
+        let line = &lines[*cursor];
+        if line.trim().is_empty() {
+            help.push("");
+            *cursor += 1;
+            continue;
+        }
+        let indent = indentation(line);
+        let required = *block_indent.get_or_insert(indent);
+        if indent < required || indent == 0 {
+            break;
+        }
+        help.push(line.trim());
+        *cursor += 1;
+    }
+    help.join("\n").trim().to_string()
+}
+
+/// Parses a Kconfig expression, returning None for empty or malformed input
+pub fn parse_expr(text: &str, symbols: &mut SymbolTable) -> Option<Expr> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return None;
+    }
+    let mut parser = ExprParser { tokens, position: 0, symbols };
+    let expr = parser.parse_or()?;
+    (parser.position == parser.tokens.len()).then_some(expr)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+    Compare(CompareOp),
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let next = chars.get(index + 1).copied();
+        match ch {
+            c if c.is_whitespace() => index += 1,
+            '(' => {
+                tokens.push(Token::Open);
+                index += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                index += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                index += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                index += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Compare(CompareOp::NotEq));
+                index += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                index += 1;
+            }
+            '=' => {
+                tokens.push(Token::Compare(CompareOp::Eq));
+                index += 1;
+            }
+            '<' | '>' => {
+                let op = match (ch, next == Some('=')) {
+                    ('<', true) => CompareOp::LessEq,
+                    ('<', false) => CompareOp::Less,
+                    (_, true) => CompareOp::GreaterEq,
+                    (_, false) => CompareOp::Greater,
+                };
+                tokens.push(Token::Compare(op));
+                index += if next == Some('=') { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let end = chars[index + 1..].iter().position(|c| *c == ch)? + index + 1;
+                tokens.push(Token::Quoted(chars[index + 1..end].iter().collect()));
+                index = end + 1;
+            }
+            _ => {
+                let start = index;
+                while index < chars.len()
+                    && (chars[index].is_alphanumeric() || matches!(chars[index], '_' | '-' | '.' | '$' | '{' | '}'))
+                {
+                    index += 1;
+                }
+                if start == index {
+                    return None;
+                }
+                tokens.push(Token::Word(chars[start..index].iter().collect()));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+struct ExprParser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    symbols: &'a mut SymbolTable,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Option<Expr> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Some(left)
+    }
+
+    fn parse_and(&mut self) -> Option<Expr> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Some(left)
+    }
+
+    fn parse_unary(&mut self) -> Option<Expr> {
+        match self.peek()?.clone() {
+            Token::Not => {
+                self.position += 1;
+                Some(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Token::Open => {
+                self.position += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return None;
+                }
+                self.position += 1;
+                Some(inner)
+            }
+            _ => {
+                let left = self.parse_operand()?;
+                if let Some(Token::Compare(op)) = self.peek().cloned() {
+                    self.position += 1;
+                    let right = self.parse_operand()?;
+                    return Some(Expr::Compare(op, left, right));
+                }
+                Some(match left {
+                    Operand::Symbol(id) => Expr::Symbol(id),
+                    Operand::Literal(text) => Expr::Const(Tristate::from_value(&text)),
+                })
+            }
+        }
+    }
+
+    fn parse_operand(&mut self) -> Option<Operand> {
+        let token = self.peek()?.clone();
+        self.position += 1;
+        match token {
+            Token::Quoted(text) => Some(Operand::Literal(text)),
+            Token::Word(word) => match word.as_str() {
+                "y" | "m" | "n" => Some(Operand::Literal(word)),
+                _ if word.chars().next()?.is_ascii_digit() || word.starts_with('-') => {
+                    Some(Operand::Literal(word))
+                }
+                _ => Some(Operand::Symbol(self.symbols.intern(&word))),
+            },
+            _ => None,
+        }
+    }
+}
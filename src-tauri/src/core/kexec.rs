@@ -0,0 +1,113 @@
+// src-tauri/src/core/kexec.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::privilege::PrivilegedAction;
+use super::system::SystemFacade;
+
+const PROC_CMDLINE: &str = "/proc/cmdline";
+const KEXEC_LOADED: &str = "/sys/kernel/kexec_loaded";
+const KEXEC_DISABLED: &str = "/proc/sys/kernel/kexec_load_disabled";
+const LOCKDOWN: &str = "/sys/kernel/security/lockdown";
+
+/// Parameters the bootloader adds for the kernel it booted, meaningless to a kexec'd one
+const BOOTLOADER_PARAMS: &[&str] = &["BOOT_IMAGE", "initrd"];
+
+/// A freshly built kernel to try without going through firmware and the bootloader
+/// Nothing is installed: a normal reboot afterwards brings back the default kernel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KexecPlan {
+    pub kernel_image: PathBuf,
+    pub initramfs: Option<PathBuf>,
+    /// The running kernel's command line, minus bootloader-specific parameters
+    pub cmdline: String,
+}
+
+impl KexecPlan {
+    pub fn new(
+        system: &dyn SystemFacade,
+        kernel_image: &Path,
+        initramfs: Option<&Path>,
+    ) -> Result<Self> {
+        if !system.has_program("kexec") {
+            bail!("kexec-tools is not installed");
+        }
+        let current = system
+            .read_trimmed(Path::new(PROC_CMDLINE))
+            .unwrap_or_default();
+        let cmdline = current
+            .split_whitespace()
+            .filter(|param| {
+                let key = param.split('=').next().unwrap_or(param);
+                !BOOTLOADER_PARAMS.contains(&key)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(KexecPlan {
+            kernel_image: kernel_image.to_path_buf(),
+            initramfs: initramfs.map(Path::to_path_buf),
+            cmdline,
+        })
+    }
+
+    /// Stages the kernel in memory; nothing happens until `exec_action` runs
+    /// Uses kexec_file_load so lockdown can check the image's signature
+    pub fn load_action(&self) -> PrivilegedAction {
+        let mut args = vec![
+            String::from("--kexec-file-syscall"),
+            String::from("--load"),
+            self.kernel_image.to_string_lossy().into_owned(),
+        ];
+        if let Some(initramfs) = &self.initramfs {
+            args.push(format!("--initrd={}", initramfs.display()));
+        }
+        args.push(format!("--command-line={}", self.cmdline));
+        PrivilegedAction::Run {
+            program: String::from("kexec"),
+            args,
+        }
+    }
+
+    /// Stops services cleanly and jumps into the staged kernel
+    pub fn exec_action() -> PrivilegedAction {
+        PrivilegedAction::Run {
+            program: String::from("systemctl"),
+            args: vec![String::from("kexec")],
+        }
+    }
+
+    /// Drops a staged kernel so the next reboot is a normal one
+    pub fn unload_action() -> PrivilegedAction {
+        PrivilegedAction::Run {
+            program: String::from("kexec"),
+            args: vec![String::from("--unload")],
+        }
+    }
+
+    /// Whether a kernel is staged and would be entered by the next `systemctl kexec`
+    pub fn is_loaded(system: &dyn SystemFacade) -> bool {
+        system.read_trimmed(Path::new(KEXEC_LOADED)).as_deref() == Some("1")
+    }
+
+    /// What the user must know before trying: always the firmware caveat, plus anything that
+    /// stops kexec from working on this machine
+    pub fn advisories(system: &dyn SystemFacade) -> Vec<Advisory> {
+        let mut advisories = vec![Advisory::new(
+            Severity::Warning,
+            "kexec-skips-firmware",
+            &[],
+        )];
+        if system.read_trimmed(Path::new(KEXEC_DISABLED)).as_deref() == Some("1") {
+            advisories.push(Advisory::new(Severity::Blocker, "kexec-disabled", &[]));
+        }
+        let lockdown = system.read_trimmed(Path::new(LOCKDOWN)).unwrap_or_default();
+        if lockdown.contains("[integrity]") || lockdown.contains("[confidentiality]") {
+            advisories.push(Advisory::new(Severity::Warning, "kexec-lockdown", &[]));
+        }
+        advisories
+    }
+}
@@ -0,0 +1,493 @@
+// src-tauri/src/core/keyring.rs
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::cache::{CacheIndex, SignatureStatus};
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::state_file;
+use super::system::SystemFacade;
+
+const KEYS_FILE: &str = "keys.json";
+
+/// Warn this long before a trusted key expires
+const EXPIRY_WARNING_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// kernel.org signers shipped with every release: fingerprint, address
+/// Their keys are fetched through WKD and only accepted when the fingerprint matches
+pub const KERNEL_ORG_SIGNERS: &[(&str, &str)] = &[
+    (
+        "ABAF11C65A2970B130ABE3C479BE3E4300411886",
+        "torvalds@kernel.org",
+    ),
+    (
+        "647F28654894E3BD457199BE38DBBDC86092693E",
+        "gregkh@kernel.org",
+    ),
+    (
+        "B8868C80BA62A1FFFAF5FDA9632D3A06589DA6B1",
+        "autosigner@kernel.org",
+    ),
+];
+
+/// Where a trusted key came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeySource {
+    Bundled,
+    User,
+}
+
+/// A key allowed to sign sources and patches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedKey {
+    /// Primary key fingerprint, upper-case hex without spaces
+    pub fingerprint: String,
+    pub uid: String,
+    pub source: KeySource,
+    /// A pinned key stays trusted only under this fingerprint; a signature by any other key
+    /// claiming the same identity is rejected
+    pub pinned: bool,
+    pub expires_at: Option<u64>,
+    /// Whether the key material is in the GnuPG home yet
+    pub imported: bool,
+}
+
+/// Result of checking one detached signature
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    pub file: PathBuf,
+    pub status: SignatureStatus,
+    /// Trusted key that made the signature
+    pub key: Option<TrustedKey>,
+}
+
+/// Which key vouched for a cached file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactSignature {
+    pub path: PathBuf,
+    pub status: SignatureStatus,
+    pub key: Option<TrustedKey>,
+}
+
+/// Keys trusted for verification, kept in a GnuPG home of its own so the user's keyring
+/// and trust database never influence what KernelForge accepts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keyring {
+    keys: Vec<TrustedKey>,
+    #[serde(skip)]
+    home: PathBuf,
+}
+
+impl Keyring {
+    /// Opens the keyring, adding the bundled kernel.org signers when missing
+    pub fn open(paths: &Paths) -> Result<Self> {
+        let home = Paths::ensure(paths.keyring_dir())?;
+        // GnuPG refuses a home other users can read
+        fs::set_permissions(&home, fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("restricting {}", home.display()))?;
+        let path = home.join(KEYS_FILE);
+        let mut keyring = if path.exists() {
+            let text =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            Keyring {
+                keys: Vec::new(),
+                home: PathBuf::new(),
+            }
+        };
+        keyring.home = home;
+        for (fingerprint, uid) in KERNEL_ORG_SIGNERS {
+            if keyring.key(fingerprint).is_none() {
+                keyring.keys.push(TrustedKey {
+                    fingerprint: fingerprint.to_string(),
+                    uid: uid.to_string(),
+                    source: KeySource::Bundled,
+                    pinned: true,
+                    expires_at: None,
+                    imported: false,
+                });
+            }
+        }
+        Ok(keyring)
+    }
+
+    pub fn keys(&self) -> &[TrustedKey] {
+        &self.keys
+    }
+
+    pub fn key(&self, fingerprint: &str) -> Option<&TrustedKey> {
+        let fingerprint = normalize(fingerprint);
+        self.keys.iter().find(|key| key.fingerprint == fingerprint)
+    }
+
+    /// Fetches the bundled signers' keys through WKD, keeping only the pinned fingerprints
+    /// WKD may serve other keys for the same addresses; those are deleted again right away
+    pub fn refresh_bundled(&mut self, system: &dyn SystemFacade) -> Result<()> {
+        let addresses: Vec<&str> = KERNEL_ORG_SIGNERS.iter().map(|(_, uid)| *uid).collect();
+        let mut args = vec!["--auto-key-locate", "clear,nodefault,wkd", "--locate-keys"];
+        args.extend(&addresses);
+        self.gpg(system, &args)?;
+        for found in self.list(system)? {
+            if self.key(&found.key.fingerprint).is_none() {
+                self.gpg(
+                    system,
+                    &["--yes", "--delete-keys", found.key.fingerprint.as_str()],
+                )?;
+            }
+        }
+        self.sync(system)
+    }
+
+    /// Imports keys from an armored or binary key file and trusts them
+    /// A key whose identity matches a pinned key under a different fingerprint is refused
+    pub fn import(&mut self, system: &dyn SystemFacade, file: &Path) -> Result<Vec<TrustedKey>> {
+        let found = self.scan(system, &["--import-options", "show-only", "--import"], file)?;
+        for scanned in &found {
+            // Any uid of the key may claim a pinned identity, not only the primary one
+            if let Some(pinned) = self.keys.iter().find(|trusted| {
+                trusted.pinned
+                    && trusted.fingerprint != scanned.key.fingerprint
+                    && scanned
+                        .uids
+                        .iter()
+                        .any(|uid| address(uid) == address(&trusted.uid))
+            }) {
+                bail!(
+                    "{} is pinned to {}, refusing key {}",
+                    pinned.uid,
+                    pinned.fingerprint,
+                    scanned.key.fingerprint
+                );
+            }
+        }
+        self.gpg(system, &["--import", &file.to_string_lossy()])?;
+        for scanned in &found {
+            if self.key(&scanned.key.fingerprint).is_none() {
+                self.keys.push(TrustedKey {
+                    source: KeySource::User,
+                    ..scanned.key.clone()
+                });
+            }
+        }
+        self.sync(system)?;
+        Ok(found.into_iter().map(|scanned| scanned.key).collect())
+    }
+
+    /// Stops trusting a key the user added; bundled keys can only be unpinned
+    pub fn remove(&mut self, system: &dyn SystemFacade, fingerprint: &str) -> Result<()> {
+        let fingerprint = normalize(fingerprint);
+        let Some(position) = self
+            .keys
+            .iter()
+            .position(|key| key.fingerprint == fingerprint)
+        else {
+            bail!("{} is not in the keyring", fingerprint);
+        };
+        if self.keys[position].source == KeySource::Bundled {
+            bail!(
+                "{} ships with KernelForge and cannot be removed",
+                fingerprint
+            );
+        }
+        if self.keys[position].imported {
+            self.gpg(system, &["--yes", "--delete-keys", fingerprint.as_str()])?;
+        }
+        self.keys.remove(position);
+        self.save()
+    }
+
+    /// Pins or unpins a key to its fingerprint
+    pub fn set_pinned(&mut self, fingerprint: &str, pinned: bool) -> Result<()> {
+        let fingerprint = normalize(fingerprint);
+        let key = self
+            .keys
+            .iter_mut()
+            .find(|key| key.fingerprint == fingerprint)
+            .with_context(|| format!("{} is not in the keyring", fingerprint))?;
+        key.pinned = pinned;
+        self.save()
+    }
+
+    /// Keys that expired or expire within a month
+    pub fn expiry_advisories(&self) -> Vec<Advisory> {
+        let now = unix_now();
+        self.keys
+            .iter()
+            .filter_map(|key| {
+                let expires_at = key.expires_at?;
+                let args = [
+                    ("uid", key.uid.clone()),
+                    ("fingerprint", key.fingerprint.clone()),
+                    (
+                        "days",
+                        (expires_at.saturating_sub(now) / 86_400).to_string(),
+                    ),
+                ];
+                if expires_at <= now {
+                    Some(Advisory::new(Severity::Blocker, "key-expired", &args))
+                } else if expires_at - now <= EXPIRY_WARNING_SECS {
+                    Some(Advisory::new(Severity::Warning, "key-expiring", &args))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Checks a detached signature against the trusted keys only
+    /// Signatures by expired or revoked keys, and expired signatures, are invalid
+    /// kernel.org signs the uncompressed tarball, so `.xz` and `.gz` files are decompressed first
+    pub fn verify(
+        &self,
+        system: &dyn SystemFacade,
+        file: &Path,
+        signature: &Path,
+    ) -> Result<Verification> {
+        let home = self.home.to_string_lossy();
+        let decompressor = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("xz") => Some("xz"),
+            Some("gz") => Some("gzip"),
+            _ => None,
+        };
+        let output = match decompressor {
+            Some(tool) => system.run(
+                "sh",
+                &[
+                    "-c",
+                    "\"$1\" -dc \"$2\" | gpg --homedir \"$3\" --batch --status-fd 1 --verify \"$4\" -",
+                    "sh",
+                    tool,
+                    &file.to_string_lossy(),
+                    &home,
+                    &signature.to_string_lossy(),
+                ],
+            ),
+            None => system.run(
+                "gpg",
+                &[
+                    "--homedir",
+                    &home,
+                    "--batch",
+                    "--status-fd",
+                    "1",
+                    "--verify",
+                    &signature.to_string_lossy(),
+                    &file.to_string_lossy(),
+                ],
+            ),
+        }
+        .context("running gpg")?;
+
+        // VALIDSIG <fpr> <date> <ts> <expire> <ver> <res> <pk> <hash> <class> <primary fpr>
+        let primary = output
+            .stdout
+            .lines()
+            .filter_map(|line| line.strip_prefix("[GNUPG:] VALIDSIG "))
+            .find_map(|rest| rest.split_whitespace().nth(9).map(normalize));
+        let key = primary
+            .as_deref()
+            .and_then(|fingerprint| self.key(fingerprint))
+            .cloned();
+        // gpg still prints VALIDSIG and exits 0 for these
+        let lapsed = output.stdout.lines().any(|line| {
+            ["EXPKEYSIG ", "REVKEYSIG ", "EXPSIG "]
+                .iter()
+                .any(|status| line.starts_with(&format!("[GNUPG:] {}", status)))
+        });
+        let status = match (&key, output.success() && !lapsed) {
+            (Some(key), true) => SignatureStatus::Valid {
+                signer: key.fingerprint.clone(),
+            },
+            _ => SignatureStatus::Invalid,
+        };
+        Ok(Verification {
+            file: file.to_path_buf(),
+            status,
+            key,
+        })
+    }
+
+    /// Verifies a cached file and records the outcome in the cache index
+    pub fn verify_cached(
+        &self,
+        system: &dyn SystemFacade,
+        index: &mut CacheIndex,
+        file: &Path,
+        signature: &Path,
+    ) -> Result<Verification> {
+        let verification = self.verify(system, file, signature)?;
+        index.set_signature(file, verification.status.clone())?;
+        Ok(verification)
+    }
+
+    /// Signature status of every cached file with the key that verified it
+    pub fn artifact_signatures(&self, index: &CacheIndex) -> Vec<ArtifactSignature> {
+        index
+            .entries()
+            .map(|entry| {
+                let key = match &entry.signature {
+                    SignatureStatus::Valid { signer } => self.key(signer).cloned(),
+                    _ => None,
+                };
+                ArtifactSignature {
+                    path: entry.path.clone(),
+                    status: entry.signature.clone(),
+                    key,
+                }
+            })
+            .collect()
+    }
+
+    /// Keys in the GnuPG home
+    fn list(&self, system: &dyn SystemFacade) -> Result<Vec<ScannedKey>> {
+        let home = self.home.to_string_lossy().to_string();
+        let output = system
+            .run(
+                "gpg",
+                &[
+                    "--homedir",
+                    &home,
+                    "--batch",
+                    "--with-colons",
+                    "--fingerprint",
+                    "--list-keys",
+                ],
+            )
+            .context("running gpg")?;
+        Ok(parse_colons(&output.stdout))
+    }
+
+    /// Refreshes expiry dates and the imported flag from the GnuPG home and saves
+    fn sync(&mut self, system: &dyn SystemFacade) -> Result<()> {
+        let present = self.list(system)?;
+        for key in &mut self.keys {
+            match present
+                .iter()
+                .find(|found| found.key.fingerprint == key.fingerprint)
+            {
+                Some(found) => {
+                    key.imported = true;
+                    key.expires_at = found.key.expires_at;
+                }
+                None => key.imported = false,
+            }
+        }
+        self.save()
+    }
+
+    /// Keys contained in `file` without importing them
+    fn scan(
+        &self,
+        system: &dyn SystemFacade,
+        options: &[&str],
+        file: &Path,
+    ) -> Result<Vec<ScannedKey>> {
+        let home = self.home.to_string_lossy();
+        let mut args = vec!["--homedir", &home, "--batch", "--with-colons"];
+        args.extend(options);
+        let file = file.to_string_lossy();
+        args.push(&file);
+        let output = system.run("gpg", &args).context("running gpg")?;
+        if !output.success() {
+            bail!("gpg could not read {}: {}", file, output.stderr.trim());
+        }
+        let keys = parse_colons(&output.stdout);
+        if keys.is_empty() {
+            bail!("{} contains no public keys", file);
+        }
+        Ok(keys)
+    }
+
+    fn gpg(&self, system: &dyn SystemFacade, args: &[&str]) -> Result<()> {
+        let home = self.home.to_string_lossy();
+        let mut full = vec!["--homedir", &home, "--batch"];
+        full.extend(args);
+        let output = system.run("gpg", &full).context("running gpg")?;
+        if !output.success() {
+            bail!("gpg failed ({}): {}", output.status, output.stderr.trim());
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        state_file::write(
+            &self.home.join(KEYS_FILE),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+}
+
+/// A key listed by gpg with every user id, the first one also in `key.uid`
+struct ScannedKey {
+    key: TrustedKey,
+    uids: Vec<String>,
+}
+
+/// Primary keys from `gpg --with-colons` output
+fn parse_colons(text: &str) -> Vec<ScannedKey> {
+    let mut keys: Vec<ScannedKey> = Vec::new();
+    let mut want_fingerprint = false;
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split(':').collect();
+        let field = |index: usize| fields.get(index).copied().unwrap_or("");
+        match field(0) {
+            "pub" => {
+                keys.push(ScannedKey {
+                    key: TrustedKey {
+                        fingerprint: String::new(),
+                        uid: String::new(),
+                        source: KeySource::User,
+                        pinned: false,
+                        expires_at: field(6).parse().ok(),
+                        imported: false,
+                    },
+                    uids: Vec::new(),
+                });
+                want_fingerprint = true;
+            }
+            // Subkeys have fingerprints too; only the one right after `pub` is the primary
+            "sub" => want_fingerprint = false,
+            "fpr" if want_fingerprint => {
+                if let Some(scanned) = keys.last_mut() {
+                    scanned.key.fingerprint = normalize(field(9));
+                }
+                want_fingerprint = false;
+            }
+            "uid" => {
+                if let Some(scanned) = keys.last_mut() {
+                    if scanned.key.uid.is_empty() {
+                        scanned.key.uid = field(9).to_string();
+                    }
+                    scanned.uids.push(field(9).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    keys.retain(|scanned| !scanned.key.fingerprint.is_empty());
+    keys
+}
+
+/// Mail address of a user id, `Name <address>` or a bare address
+fn address(uid: &str) -> String {
+    uid.rsplit_once('<')
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .map_or(uid, |(address, _)| address)
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn normalize(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
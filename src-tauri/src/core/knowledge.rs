@@ -0,0 +1,149 @@
+// src-tauri/src/core/knowledge.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::keyring::Keyring;
+use super::paths::Paths;
+use super::remote_manifest::{self, RemoteManifest};
+use super::system::SystemFacade;
+
+/// Rules shipped with this release
+const BUNDLED_RULES: &str = include_str!("../../data/known-issues.json");
+
+/// Where newer rule sets are published; the bundled file on the main branch
+/// A detached signature is published next to it with an `.asc` suffix
+pub const DEFAULT_MANIFEST_URL: &str =
+    "https://raw.githubusercontent.com/linuxiano85/KernelForge/main/src-tauri/data/known-issues.json";
+
+/// One test on the planned config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Condition {
+    Enabled { symbol: String },
+    Disabled { symbol: String },
+    Equals { symbol: String, value: String },
+    AnyEnabled { symbols: Vec<String> },
+}
+
+impl Condition {
+    fn holds(&self, config: &KernelConfig) -> bool {
+        match self {
+            Condition::Enabled { symbol } => config.is_enabled(symbol),
+            Condition::Disabled { symbol } => !config.is_enabled(symbol),
+            Condition::Equals { symbol, value } => config
+                .get(symbol)
+                .and_then(ConfigValue::as_str)
+                .is_some_and(|current| current == value),
+            Condition::AnyEnabled { symbols } => {
+                symbols.iter().any(|symbol| config.is_enabled(symbol))
+            }
+        }
+    }
+}
+
+/// A combination of options known to cause trouble
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownIssue {
+    pub id: String,
+    pub severity: Severity,
+    /// Every condition must hold for the issue to apply
+    pub when: Vec<Condition>,
+    pub title: String,
+    pub explanation: String,
+    /// Assignments that avoid the problem
+    #[serde(default)]
+    pub fix: BTreeMap<String, ConfigValue>,
+}
+
+impl KnownIssue {
+    pub fn applies(&self, config: &KernelConfig) -> bool {
+        !self.when.is_empty() && self.when.iter().all(|condition| condition.holds(config))
+    }
+}
+
+/// Versioned set of known issues; a higher revision replaces a lower one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnowledgeBase {
+    pub revision: u64,
+    pub issues: Vec<KnownIssue>,
+}
+
+impl KnowledgeBase {
+    /// Rules compiled into this release
+    pub fn bundled() -> Self {
+        KnowledgeBase::parse(BUNDLED_RULES).expect("bundled known issues are valid")
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(text).context("parsing known issues")
+    }
+
+    /// The downloaded rule set when it is newer than the bundled one
+    pub fn load(paths: &Paths) -> Self {
+        remote_manifest::load(KnowledgeBase::bundled(), &paths.known_issues_path())
+    }
+
+    /// Fetches the published rule set and keeps it if newer and signed by a pinned key,
+    /// returning whether it changed
+    pub async fn update(
+        &mut self,
+        client: &Client,
+        system: &dyn SystemFacade,
+        keyring: &Keyring,
+        url: &str,
+        paths: &Paths,
+    ) -> Result<bool> {
+        remote_manifest::update(
+            self,
+            client,
+            system,
+            keyring,
+            url,
+            &paths.known_issues_path(),
+            paths,
+        )
+        .await
+    }
+
+    /// Advisories for every known issue the config runs into
+    pub fn check(&self, config: &KernelConfig) -> Vec<Advisory> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.applies(config))
+            .map(|issue| {
+                let mut advisory = Advisory::new(
+                    issue.severity,
+                    "known-issue",
+                    &[
+                        ("title", issue.title.clone()),
+                        ("explanation", issue.explanation.clone()),
+                    ],
+                );
+                for (symbol, value) in &issue.fix {
+                    advisory = advisory.suggest(ConfigSuggestion::new(
+                        symbol,
+                        value.clone(),
+                        &issue.title,
+                    ));
+                }
+                advisory
+            })
+            .collect()
+    }
+}
+
+impl RemoteManifest for KnowledgeBase {
+    fn parse(text: &str) -> Result<Self> {
+        KnowledgeBase::parse(text)
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+}
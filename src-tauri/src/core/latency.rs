@@ -0,0 +1,249 @@
+// src-tauri/src/core/latency.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::privilege::{PrivilegeBroker, PrivilegedAction};
+use super::results::KernelTag;
+use super::system::{CommandSpec, SystemFacade};
+
+/// Tail latency, in microseconds, that earns a full score
+const REFERENCE_LATENCY_US: f64 = 10.0;
+
+/// Standardized cyclictest parameters, so scores from different kernels are comparable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CyclictestPreset {
+    pub duration_secs: u64,
+    pub interval_us: u32,
+    pub priority: u8,
+    /// Histogram buckets in microseconds; slower wakeups count as overflows
+    pub histogram_us: u32,
+}
+
+impl Default for CyclictestPreset {
+    fn default() -> Self {
+        CyclictestPreset {
+            duration_secs: 60,
+            interval_us: 200,
+            priority: 95,
+            histogram_us: 1000,
+        }
+    }
+}
+
+impl CyclictestPreset {
+    /// One measuring thread per CPU, memory locked, histogram written to `histfile`
+    pub fn action(&self, histfile: &Path) -> PrivilegedAction {
+        PrivilegedAction::Run {
+            program: "cyclictest".to_string(),
+            args: vec![
+                "--mlockall".to_string(),
+                "--smp".to_string(),
+                "--quiet".to_string(),
+                format!("--priority={}", self.priority),
+                format!("--interval={}", self.interval_us),
+                "--distance=0".to_string(),
+                format!("--duration={}", self.duration_secs),
+                format!("--histogram={}", self.histogram_us),
+                format!("--histfile={}", histfile.display()),
+            ],
+        }
+    }
+
+    /// stress-ng load on every CPU plus I/O and memory pressure, outlasting the measurement
+    pub fn stress_command(&self) -> CommandSpec {
+        CommandSpec::new(
+            "stress-ng",
+            &[
+                "--cpu",
+                "0",
+                "--io",
+                "2",
+                "--vm",
+                "2",
+                "--vm-bytes",
+                "25%",
+                "--timeout",
+                &format!("{}s", self.duration_secs + 10),
+                "--quiet",
+            ],
+        )
+    }
+}
+
+/// What else the machine does while latency is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LatencyLoad {
+    Idle,
+    Stress,
+}
+
+/// Wakeup latencies of all CPUs combined
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Samples per microsecond bucket, empty buckets omitted
+    pub buckets: BTreeMap<u32, u64>,
+    /// Samples beyond the last bucket
+    pub overflows: u64,
+    pub min_us: u32,
+    pub avg_us: f64,
+    pub max_us: u32,
+}
+
+impl LatencyHistogram {
+    /// Parses a cyclictest `--histfile`: one row per bucket with a count per CPU, then `#` totals
+    pub fn parse(text: &str) -> Self {
+        let mut histogram = LatencyHistogram::default();
+        let mut averages = Vec::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix('#') {
+                let Some((label, values)) = comment.split_once(':') else {
+                    continue;
+                };
+                let values = values
+                    .split_whitespace()
+                    .filter_map(|value| value.parse::<f64>().ok());
+                match label.trim() {
+                    "Min Latencies" => {
+                        histogram.min_us = values.reduce(f64::min).unwrap_or(0.0) as u32
+                    }
+                    "Avg Latencies" => averages.extend(values),
+                    "Max Latencies" => {
+                        histogram.max_us = values.reduce(f64::max).unwrap_or(0.0) as u32
+                    }
+                    "Histogram Overflows" => histogram.overflows = values.sum::<f64>() as u64,
+                    _ => {}
+                }
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let Some(bucket) = fields.next().and_then(|bucket| bucket.parse::<u32>().ok()) else {
+                continue;
+            };
+            let count: u64 = fields.filter_map(|count| count.parse::<u64>().ok()).sum();
+            if count > 0 {
+                *histogram.buckets.entry(bucket).or_default() += count;
+            }
+        }
+        if !averages.is_empty() {
+            histogram.avg_us = averages.iter().sum::<f64>() / averages.len() as f64;
+        }
+        histogram
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.buckets.values().sum::<u64>() + self.overflows
+    }
+
+    /// Latency under which `fraction` of the samples fall; overflowed percentiles report the maximum
+    pub fn percentile(&self, fraction: f64) -> u32 {
+        let target = (self.samples() as f64 * fraction).ceil() as u64;
+        let mut seen = 0;
+        for (bucket, count) in &self.buckets {
+            seen += count;
+            if seen >= target {
+                return *bucket;
+            }
+        }
+        self.max_us
+    }
+}
+
+/// One cyclictest run on one kernel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyResult {
+    pub kernel: KernelTag,
+    pub load: LatencyLoad,
+    pub recorded_at: u64,
+    pub preset: CyclictestPreset,
+    pub histogram: LatencyHistogram,
+    pub p99_us: u32,
+    pub p999_us: u32,
+    pub score: f64,
+}
+
+impl LatencyResult {
+    fn new(
+        kernel: KernelTag,
+        load: LatencyLoad,
+        preset: CyclictestPreset,
+        histogram: LatencyHistogram,
+    ) -> Self {
+        let p99_us = histogram.percentile(0.99);
+        let p999_us = histogram.percentile(0.999);
+        LatencyResult {
+            kernel,
+            load,
+            recorded_at: unix_now(),
+            preset,
+            score: latency_score(p999_us, histogram.max_us),
+            histogram,
+            p99_us,
+            p999_us,
+        }
+    }
+}
+
+/// Score out of 100: full at a 10 µs tail, halving each time the tail doubles
+/// The tail is the geometric mean of p99.9 and the maximum, so one bad spike still costs points
+pub fn latency_score(p999_us: u32, max_us: u32) -> f64 {
+    let tail = (p999_us.max(1) as f64 * max_us.max(p999_us).max(1) as f64).sqrt();
+    (100.0 * REFERENCE_LATENCY_US / tail).min(100.0)
+}
+
+/// Runs cyclictest with `preset`, under stress-ng when `load` asks for it
+/// cyclictest needs real-time priority, so it goes through the broker; this blocks for the
+/// whole duration
+pub fn measure_latency(
+    broker: &PrivilegeBroker,
+    system: &dyn SystemFacade,
+    preset: &CyclictestPreset,
+    load: LatencyLoad,
+    kernel: KernelTag,
+    scratch: &Path,
+) -> Result<LatencyResult> {
+    if !system.has_program("cyclictest") {
+        bail!("cyclictest is not installed");
+    }
+    if load == LatencyLoad::Stress && !system.has_program("stress-ng") {
+        bail!("stress-ng is not installed");
+    }
+    fs::create_dir_all(scratch).with_context(|| format!("creating {}", scratch.display()))?;
+    let histfile = scratch.join("cyclictest.hist");
+    let _ = fs::remove_file(&histfile);
+
+    let mut stress = match load {
+        LatencyLoad::Idle => None,
+        LatencyLoad::Stress => {
+            let spec = system.prepare_command(preset.stress_command());
+            let child = Command::new(&spec.program)
+                .args(&spec.args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .context("starting stress-ng")?;
+            Some(child)
+        }
+    };
+    let measured = broker.execute(&preset.action(&histfile));
+    if let Some(child) = stress.as_mut() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    measured?;
+
+    let text =
+        fs::read_to_string(&histfile).with_context(|| format!("reading {}", histfile.display()))?;
+    let histogram = LatencyHistogram::parse(&text);
+    if histogram.samples() == 0 {
+        bail!("cyclictest recorded no samples");
+    }
+    Ok(LatencyResult::new(kernel, load, preset.clone(), histogram))
+}
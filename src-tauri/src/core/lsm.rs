@@ -0,0 +1,69 @@
+// src-tauri/src/core/lsm.rs
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::system::SystemFacade;
+
+/// Security modules the running kernel initialized, comma separated in init order,
+/// e.g. `lockdown,capability,landlock,yama,apparmor`
+pub const SYS_LSM: &str = "/sys/kernel/security/lsm";
+
+/// A major LSM, the kind distributions build their boot policy on: Fedora labels every
+/// file for SELinux, Ubuntu confines services with AppArmor profiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SecurityModule {
+    SeLinux,
+    AppArmor,
+    Smack,
+    Tomoyo,
+}
+
+impl SecurityModule {
+    pub const ALL: [SecurityModule; 4] = [
+        SecurityModule::SeLinux,
+        SecurityModule::AppArmor,
+        SecurityModule::Smack,
+        SecurityModule::Tomoyo,
+    ];
+
+    /// Name the kernel lists it under in /sys/kernel/security/lsm and `lsm=`
+    pub fn name(self) -> &'static str {
+        match self {
+            SecurityModule::SeLinux => "selinux",
+            SecurityModule::AppArmor => "apparmor",
+            SecurityModule::Smack => "smack",
+            SecurityModule::Tomoyo => "tomoyo",
+        }
+    }
+
+    /// Kconfig symbol building it in
+    pub fn symbol(self) -> &'static str {
+        match self {
+            SecurityModule::SeLinux => "CONFIG_SECURITY_SELINUX",
+            SecurityModule::AppArmor => "CONFIG_SECURITY_APPARMOR",
+            SecurityModule::Smack => "CONFIG_SECURITY_SMACK",
+            SecurityModule::Tomoyo => "CONFIG_SECURITY_TOMOYO",
+        }
+    }
+}
+
+/// Names of the LSMs active in the running kernel, empty when securityfs is not mounted
+/// or the kernel predates the list
+pub fn active_lsms(system: &dyn SystemFacade) -> BTreeSet<String> {
+    system
+        .read_trimmed(Path::new(SYS_LSM))
+        .map(|list| parse_lsm_list(&list))
+        .unwrap_or_default()
+}
+
+/// Splits a list in the /sys/kernel/security/lsm format
+pub fn parse_lsm_list(list: &str) -> BTreeSet<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,333 @@
+// src-tauri/src/core/machine_capture.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::containers::{HOMES, ROOTLESS_STORAGE};
+use super::hardware::{HardwareSnapshot, BUSES};
+use super::system::{CommandOutput, SystemFacade};
+
+/// Version of the capture JSON; bumped when the script records different files
+/// Captures in any other format are refused, so a snapshot never silently lacks a setup
+/// the script did not record yet
+pub const CAPTURE_FORMAT: u32 = 6;
+
+/// Programs `HardwareSnapshot::capture` looks for on PATH: container runtimes, SANE,
+/// camera apps, RGB tools and VPNs
+const PROGRAMS: &[&str] = &[
+    "dockerd",
+    "podman",
+    "lxc-start",
+    "lxd",
+    "incus",
+    "kubelet",
+    "k3s",
+    "scanimage",
+    "cheese",
+    "snapshot",
+    "guvcview",
+    "kamoso",
+    "webcamoid",
+    "obs",
+    "zoom",
+    "skypeforlinux",
+    "teams-for-linux",
+    "openrgb",
+    "liquidctl",
+    "ckb-next",
+    "ckb-next-daemon",
+    "wg",
+    "openvpn",
+    "tailscaled",
+];
+
+/// Directories whose presence shows a container runtime, SANE, lm-sensors or a VPN is
+/// set up
+const MARKER_DIRS: &[&str] = &[
+    "/var/lib/docker",
+    "/var/lib/containers/storage",
+    "/var/lib/lxc",
+    "/var/lib/lxd",
+    "/var/lib/incus",
+    "/var/lib/kubelet",
+    "/etc/sane.d",
+    "/etc/sensors.d",
+    "/etc/wireguard",
+    "/etc/openvpn",
+    "/var/lib/tailscale",
+];
+
+/// Commands whose output `HardwareSnapshot::capture` reads: the print queues, the
+/// firewall ruleset, the Bluetooth controllers and sockets of the exotic protocols
+const COMMANDS: &[&str] = &[
+    "lpstat -v",
+    "nft list ruleset",
+    "iptables-save",
+    "btmgmt info",
+    "ss -H -a -n --sctp",
+    "ss -H -a -n --dccp",
+    "ss -H -a -n --tipc",
+];
+
+/// Script body after the lists above; reads /proc, /sys and the setup under /etc, looks
+/// programs up on PATH, runs the read-only [`COMMANDS`] and prints JSON on stdout
+const SCRIPT_BODY: &str = r#"set -u
+
+sep=''
+
+# JSON string body on stdout: escapes, drops control characters, joins lines with \n
+TAB=$(printf '\t')
+esc() {
+    tr -d '\000-\010\013-\037' |
+        sed -e 's/\\/\\\\/g' -e 's/"/\\"/g' -e "s/$TAB/\\\\t/g" |
+        awk 'BEGIN { ORS = "" } { print $0 "\\n" }'
+}
+
+file() {
+    [ -r "$1" ] || return 0
+    content=$(cat "$1" 2>/dev/null | esc) || return 0
+    printf '%s"%s":"%s"' "$sep" "$1" "$content"
+    sep=','
+}
+
+dir() {
+    [ -d "$1" ] || return 0
+    printf '%s"%s":[' "$sep" "$1"
+    entry_sep=''
+    for entry in "$1"/*; do
+        [ -e "$entry" ] || [ -L "$entry" ] || continue
+        printf '%s"%s"' "$entry_sep" "${entry##*/}"
+        entry_sep=','
+    done
+    printf ']'
+    sep=','
+}
+
+# Output of a command that succeeded, keyed by its command line
+output() {
+    content=$("$@" 2>/dev/null) || return 0
+    [ -n "$content" ] || return 0
+    printf '%s"%s":"%s"' "$sep" "$*" "$(printf '%s\n' "$content" | esc)"
+    sep=','
+}
+
+printf '{"format":%s,"files":{' "$FORMAT"
+for path in /proc/cpuinfo /proc/meminfo /proc/modules /proc/mounts \
+    /proc/bus/input/devices /sys/kernel/security/lsm \
+    /sys/class/dmi/id/sys_vendor /sys/class/dmi/id/product_name \
+    /sys/module/zswap/parameters/enabled \
+    /etc/cups/printers.conf /etc/conf.d/lm_sensors /etc/sysconfig/lm_sensors /etc/modules \
+    /etc/sensors3.conf /etc/fancontrol \
+    /etc/nftables.conf /etc/sysconfig/nftables.conf \
+    /etc/iptables/rules.v4 /etc/iptables/iptables.rules /etc/sysconfig/iptables \
+    /etc/modules-load.d/*; do
+    file "$path"
+done
+for cpu in /sys/devices/system/cpu/cpu[0-9]*; do
+    file "$cpu/topology/physical_package_id"
+    file "$cpu/topology/core_id"
+done
+for index in /sys/devices/system/cpu/cpu0/cache/index*; do
+    file "$index/level"
+    file "$index/type"
+    file "$index/size"
+done
+for device in /sys/block/zram*; do
+    file "$device/disksize"
+    file "$device/comp_algorithm"
+done
+for bus in $BUSES; do
+    for device in /sys/bus/"$bus"/devices/*; do
+        file "$device/uevent"
+    done
+done
+for device in /sys/bus/thunderbolt/devices/*; do
+    file "$device/security"
+    file "$device/vendor_name"
+    file "$device/device_name"
+    file "$device/authorized"
+done
+# Every device above an input node, for the drivers it is bound to
+seen=''
+while IFS= read -r node; do
+    dir=/sys$node
+    while [ "${dir#/sys/devices/}" != "$dir" ]; do
+        case " $seen " in *" $dir "*) break ;; esac
+        seen="$seen $dir"
+        file "$dir/uevent"
+        dir=${dir%/*}
+    done
+done <<EOF
+$(sed -n 's/^S: Sysfs=//p' /proc/bus/input/devices 2>/dev/null)
+EOF
+for connector in /sys/class/drm/*; do
+    file "$connector/status"
+done
+for chip in /sys/class/hwmon/*; do
+    file "$chip/name"
+    file "$chip/device/modalias"
+    file "$chip/device/../name"
+done
+for adapter in /sys/bus/i2c/devices/*; do
+    file "$adapter/name"
+done
+for interface in /sys/class/net/*; do
+    file "$interface/uevent"
+done
+for controller in /sys/class/bluetooth/*; do
+    file "$controller/device/uevent"
+done
+printf '},"dirs":{'
+sep=''
+for path in /sys/devices/system/cpu /sys/devices/system/cpu/cpu0/cache /sys/block \
+    /sys/firmware/efi /sys/bus/thunderbolt/devices /sys/class/drm /sys/class/hwmon \
+    /sys/bus/i2c/devices /sys/class/net /sys/class/bluetooth /etc/modules-load.d \
+    $MARKER_DIRS "$HOMES"; do
+    dir "$path"
+done
+for home in "$HOMES"/*; do
+    dir "$home/$ROOTLESS_STORAGE"
+done
+for bus in $BUSES; do
+    dir /sys/bus/"$bus"/devices
+done
+printf '},"programs":['
+sep=''
+for program in $PROGRAMS; do
+    command -v "$program" >/dev/null 2>&1 || continue
+    printf '%s"%s"' "$sep" "$program"
+    sep=','
+done
+printf '],"commands":{'
+sep=''
+while IFS= read -r command; do
+    [ -n "$command" ] && output $command
+done <<EOF
+$COMMANDS
+EOF
+printf '}}\n'
+"#;
+
+/// Standalone POSIX shell script recording everything `HardwareSnapshot::capture` reads,
+/// for machines KernelForge is not installed on. Its output is imported with
+/// `import_capture`
+pub fn capture_script() -> String {
+    format!(
+        "#!/bin/sh\n\
+         # KernelForge machine capture, format {format}\n\
+         # Usage: sh kernelforge-capture.sh > machine.json\n\
+         # Only reads files and lists rulesets and controllers; nothing is changed and\n\
+         # nothing leaves the machine\n\n\
+         FORMAT={format}\n\
+         BUSES='{buses}'\n\
+         MARKER_DIRS='{marker_dirs}'\n\
+         HOMES='{homes}'\n\
+         ROOTLESS_STORAGE='{rootless_storage}'\n\
+         PROGRAMS='{programs}'\n\
+         COMMANDS='{commands}'\n\n\
+         {body}",
+        format = CAPTURE_FORMAT,
+        buses = BUSES.join(" "),
+        marker_dirs = MARKER_DIRS.join(" "),
+        homes = HOMES,
+        rootless_storage = ROOTLESS_STORAGE,
+        programs = PROGRAMS.join(" "),
+        commands = COMMANDS.join("\n"),
+        body = SCRIPT_BODY
+    )
+}
+
+/// Files and directory listings recorded by the capture script
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MachineCapture {
+    pub format: u32,
+    pub files: BTreeMap<PathBuf, String>,
+    /// Entry names per directory
+    pub dirs: BTreeMap<PathBuf, Vec<String>>,
+    /// Programs from [`PROGRAMS`] found on PATH
+    pub programs: BTreeSet<String>,
+    /// Output of the commands from [`COMMANDS`] that succeeded, keyed by command line
+    pub commands: BTreeMap<String, String>,
+}
+
+impl MachineCapture {
+    /// Older formats lack what later versions read and are refused rather than defaulted
+    pub fn parse(text: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            format: u32,
+        }
+        let header: Header = serde_json::from_str(text).context("parsing the machine capture")?;
+        if header.format != CAPTURE_FORMAT {
+            bail!(
+                "the capture has format {}, this version reads format {}; rerun the script it generates",
+                header.format,
+                CAPTURE_FORMAT
+            );
+        }
+        serde_json::from_str(text).context("parsing the machine capture")
+    }
+}
+
+/// Serves a capture through the system facade, so the snapshot is built by the same code
+/// that scans the local machine
+impl SystemFacade for MachineCapture {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "not in the capture"))
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.dirs
+            .get(path)
+            .map(|entries| entries.iter().map(|entry| path.join(entry)).collect())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "not in the capture"))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.dirs.contains_key(path)
+    }
+
+    /// Answers the recorded commands with their output, as a failure when the command
+    /// failed on the machine; anything else was never captured
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let line = std::iter::once(program)
+            .chain(args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !COMMANDS.contains(&line.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} cannot run against a captured machine", program),
+            ));
+        }
+        Ok(match self.commands.get(&line) {
+            Some(stdout) => CommandOutput {
+                status: 0,
+                stdout: stdout.clone(),
+                stderr: String::new(),
+            },
+            None => CommandOutput {
+                status: 1,
+                stdout: String::new(),
+                stderr: format!("{} failed on the captured machine", line),
+            },
+        })
+    }
+
+    fn has_program(&self, program: &str) -> bool {
+        self.programs.contains(program)
+    }
+}
+
+/// Snapshot of the machine the capture script ran on
+pub fn import_capture(text: &str) -> Result<HardwareSnapshot> {
+    let capture = MachineCapture::parse(text)?;
+    HardwareSnapshot::capture(&capture).context("reading the captured machine")
+}
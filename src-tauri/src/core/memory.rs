@@ -0,0 +1,157 @@
+// src-tauri/src/core/memory.rs
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigSuggestion, ConfigValue};
+use super::procfs::{parse_meminfo, PROC_MEMINFO};
+use super::system::SystemFacade;
+
+const ZSWAP_ENABLED: &str = "/sys/module/zswap/parameters/enabled";
+const SYS_BLOCK: &str = "/sys/block";
+const GIB_KB: u64 = 1024 * 1024;
+
+/// A configured zram block device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZramDevice {
+    pub name: String,
+    pub disksize_kb: u64,
+    pub algorithm: Option<String>,
+}
+
+/// RAM and swap layout of the machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryInfo {
+    pub total_kb: u64,
+    pub available_kb: u64,
+    pub swap_total_kb: u64,
+    pub swap_free_kb: u64,
+    pub zram_devices: Vec<ZramDevice>,
+    /// None when the running kernel has no zswap support
+    pub zswap_enabled: Option<bool>,
+}
+
+impl MemoryInfo {
+    /// Reads /proc/meminfo, zram devices and the zswap parameter
+    pub fn detect(system: &dyn SystemFacade) -> Result<Self> {
+        let text = system
+            .read_to_string(Path::new(PROC_MEMINFO))
+            .context("reading /proc/meminfo")?;
+        let meminfo = parse_meminfo(&text);
+
+        let zram_devices = system
+            .read_dir(Path::new(SYS_BLOCK))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|device| {
+                let name = device.file_name()?.to_str()?.to_string();
+                if !name.starts_with("zram") {
+                    return None;
+                }
+                let disksize: u64 = system.read_trimmed(&device.join("disksize"))?.parse().ok()?;
+                // comp_algorithm lists every choice with the active one in brackets
+                let algorithm = system
+                    .read_trimmed(&device.join("comp_algorithm"))
+                    .and_then(|choices| {
+                        let start = choices.find('[')?;
+                        let end = choices[start..].find(']')? + start;
+                        Some(choices[start + 1..end].to_string())
+                    });
+                Some(ZramDevice {
+                    name,
+                    disksize_kb: disksize / 1024,
+                    algorithm,
+                })
+            })
+            .filter(|device| device.disksize_kb > 0)
+            .collect();
+
+        let zswap_enabled = system
+            .read_trimmed(Path::new(ZSWAP_ENABLED))
+            .map(|value| value == "Y" || value == "1");
+
+        Ok(MemoryInfo {
+            total_kb: meminfo.total_kb(),
+            available_kb: meminfo.available_kb(),
+            swap_total_kb: meminfo.get("SwapTotal").unwrap_or(0),
+            swap_free_kb: meminfo.get("SwapFree").unwrap_or(0),
+            zram_devices,
+            zswap_enabled,
+        })
+    }
+
+    /// Installed RAM in whole GiB, rounded to nearest
+    pub fn total_gib(&self) -> u64 {
+        (self.total_kb + GIB_KB / 2) / GIB_KB
+    }
+
+    /// Whether any zram device is configured
+    pub fn uses_zram(&self) -> bool {
+        !self.zram_devices.is_empty()
+    }
+
+    /// Swap capacity that lives on disk rather than in zram
+    pub fn disk_swap_kb(&self) -> u64 {
+        let zram: u64 = self.zram_devices.iter().map(|device| device.disksize_kb).sum();
+        self.swap_total_kb.saturating_sub(zram)
+    }
+
+    /// Config recommendations for compressed swap and transparent hugepages
+    pub fn suggestions(&self) -> Vec<ConfigSuggestion> {
+        let mut suggestions = Vec::new();
+        let gib = self.total_gib();
+
+        if self.uses_zram() {
+            suggestions.push(ConfigSuggestion::new(
+                "CONFIG_ZRAM",
+                ConfigValue::module(),
+                "zram swap devices are configured on this machine",
+            ));
+        } else if self.swap_total_kb == 0 && gib <= 16 {
+            suggestions.push(ConfigSuggestion::new(
+                "CONFIG_ZRAM",
+                ConfigValue::module(),
+                "no swap is configured, zram gives cheap headroom on machines with 16 GiB or less",
+            ));
+        }
+
+        if self.zswap_enabled == Some(true) || (self.disk_swap_kb() > 0 && gib <= 16) {
+            suggestions.push(ConfigSuggestion::new(
+                "CONFIG_ZSWAP",
+                ConfigValue::yes(),
+                "disk swap is in use, zswap keeps hot pages compressed in RAM",
+            ));
+            suggestions.push(ConfigSuggestion::new(
+                "CONFIG_ZSWAP_DEFAULT_ON",
+                ConfigValue::yes(),
+                "enable zswap without a boot parameter",
+            ));
+            suggestions.push(ConfigSuggestion::new(
+                "CONFIG_ZSWAP_COMPRESSOR_DEFAULT_ZSTD",
+                ConfigValue::yes(),
+                "zstd gives the best ratio for swapped pages",
+            ));
+        }
+
+        let (policy, reason) = if gib >= 32 {
+            (
+                "CONFIG_TRANSPARENT_HUGEPAGE_ALWAYS",
+                "plenty of RAM, always-on hugepages reduce TLB pressure",
+            )
+        } else {
+            (
+                "CONFIG_TRANSPARENT_HUGEPAGE_MADVISE",
+                "limited RAM, only back hugepages where applications ask for them",
+            )
+        };
+        suggestions.push(ConfigSuggestion::new(
+            "CONFIG_TRANSPARENT_HUGEPAGE",
+            ConfigValue::yes(),
+            "required for either hugepage policy",
+        ));
+        suggestions.push(ConfigSuggestion::new(policy, ConfigValue::yes(), reason));
+        suggestions
+    }
+}
@@ -0,0 +1,166 @@
+// src-tauri/src/core/memory_guard.rs
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::config::KernelConfig;
+use super::procfs::{parse_meminfo, PROC_MEMINFO};
+use super::system::SystemFacade;
+
+/// Pressure stall information for memory, present when the kernel has CONFIG_PSI
+const PROC_PRESSURE_MEMORY: &str = "/proc/pressure/memory";
+
+/// Make goal ending in the vmlinux link, where LTO does its work
+const LINK_GOAL: &str = "vmlinux";
+
+/// Goals that end in the bootable image, and so in the vmlinux link
+const IMAGE_GOALS: &[&str] = &[
+    "all",
+    "bzImage",
+    "Image",
+    "Image.gz",
+    "zImage",
+    "uImage",
+    "vmlinuz.efi",
+];
+
+/// Longest the link is held stopped; past it the link resumes and finishes under whatever
+/// pressure there is, rather than waiting on pressure it may be causing itself
+pub const MAX_LINK_PAUSE: Duration = Duration::from_secs(300);
+
+/// How hard the machine is fighting for memory
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryPressure {
+    /// Share of the last 10 seconds some task waited on memory, in percent
+    pub some_avg10: f32,
+    /// Share of the last 10 seconds every task waited on memory, in percent
+    pub full_avg10: f32,
+    pub available_kb: u64,
+}
+
+impl MemoryPressure {
+    /// Reads the machine's PSI and MemAvailable, None on kernels without PSI
+    pub fn read(system: &dyn SystemFacade) -> Option<Self> {
+        let text = system
+            .read_to_string(Path::new(PROC_PRESSURE_MEMORY))
+            .ok()?;
+        MemoryPressure::parse(&text, MemoryPressure::available_kb(system))
+    }
+
+    /// The machine's MemAvailable, unlimited when unreadable
+    pub fn available_kb(system: &dyn SystemFacade) -> u64 {
+        system
+            .read_to_string(Path::new(PROC_MEMINFO))
+            .map(|text| parse_meminfo(&text).available_kb())
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Parses the `some` and `full` lines of a PSI file
+    pub fn parse(text: &str, available_kb: u64) -> Option<Self> {
+        let avg10 = |kind: &str| {
+            text.lines()
+                .find_map(|line| line.strip_prefix(kind)?.strip_prefix(' '))?
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("avg10="))?
+                .parse::<f32>()
+                .ok()
+        };
+        Some(MemoryPressure {
+            some_avg10: avg10("some")?,
+            // Kernels before 5.13 have no full line for memory in the root cgroup
+            full_avg10: avg10("full").unwrap_or(0.0),
+            available_kb,
+        })
+    }
+}
+
+/// Keeps memory-hungry builds, LTO above all, clear of the OOM killer
+/// Killing a compiler and restarting make with fewer jobs costs seconds, while the OOM
+/// killer hitting the linker at the end throws away the whole link; once the link has
+/// started there is nothing to parallelize, so it is paused until the pressure eases instead
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryGuard {
+    /// Halve the jobs once tasks waited on memory for this share of the last 10 seconds
+    pub some_limit: f32,
+    /// Same for time every task waited, the sign of thrashing
+    pub full_limit: f32,
+    /// Halve the jobs once less than this is available, whatever PSI says
+    pub min_available_mb: u64,
+    /// Build vmlinux on its own before everything else, so the final link does not share
+    /// memory with compilers working on modules
+    pub serialize_link: bool,
+    pub poll_interval: Duration,
+}
+
+impl Default for MemoryGuard {
+    fn default() -> Self {
+        MemoryGuard {
+            some_limit: 20.0,
+            full_limit: 5.0,
+            min_available_mb: 512,
+            serialize_link: false,
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+impl MemoryGuard {
+    /// Guard for a config; the link is serialized when it uses Clang LTO
+    pub fn for_config(config: &KernelConfig) -> Self {
+        MemoryGuard {
+            serialize_link: uses_lto(config),
+            ..MemoryGuard::default()
+        }
+    }
+
+    pub fn is_under_pressure(&self, pressure: &MemoryPressure) -> bool {
+        pressure.some_avg10 >= self.some_limit
+            || pressure.full_avg10 >= self.full_limit
+            || pressure.available_kb < self.min_available_mb * 1024
+    }
+
+    /// Whether to hold the link under `pressure`: only tasks stalling on memory count
+    /// A short MemAvailable is the link's own doing more often than not, and a stopped
+    /// link keeps its memory, so it would never come back
+    pub fn should_pause_link(&self, pressure: &MemoryPressure) -> bool {
+        pressure.some_avg10 >= self.some_limit || pressure.full_avg10 >= self.full_limit
+    }
+
+    /// Jobs to continue with under `pressure`, None when the build can keep its pace
+    /// Parallelism only ever goes down during a build; PSI lags, and raising it again as
+    /// soon as the average settles would bring the pressure straight back
+    pub fn reduce(&self, pressure: &MemoryPressure, running_jobs: usize) -> Option<usize> {
+        if running_jobs <= 1 || !self.is_under_pressure(pressure) {
+            return None;
+        }
+        Some(running_jobs / 2)
+    }
+
+    /// Goal lists to run make with one after the other; an empty list is make's default
+    /// The link gets a phase of its own only when the build ends in the image; headers,
+    /// modules, device trees or single objects never link vmlinux
+    pub fn phases(&self, targets: &[String]) -> Vec<Vec<String>> {
+        let builds_image = targets.is_empty()
+            || targets
+                .iter()
+                .any(|target| IMAGE_GOALS.contains(&target.as_str()));
+        if !self.serialize_link || !builds_image {
+            return vec![targets.to_vec()];
+        }
+        vec![vec![String::from(LINK_GOAL)], targets.to_vec()]
+    }
+}
+
+/// Whether `line` is kbuild's quiet output for linking vmlinux.o, the LTO step; from there
+/// on the build is a chain of single-process links
+pub fn starts_link(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    words.next() == Some("LD") && words.next() == Some("vmlinux.o")
+}
+
+/// Whether the config links with Clang LTO, thin or full
+pub fn uses_lto(config: &KernelConfig) -> bool {
+    config.is_enabled("LTO_CLANG_THIN") || config.is_enabled("LTO_CLANG_FULL")
+}
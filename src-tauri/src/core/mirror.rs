@@ -0,0 +1,142 @@
+// src-tauri/src/core/mirror.rs
+
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+/// How long a single probe may take before the mirror counts as unreachable
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A kernel.org mirror, `url` being the base that `v6.x/linux-6.9.tar.xz` is appended to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mirror {
+    pub name: String,
+    pub url: String,
+}
+
+impl Mirror {
+    pub fn new(name: &str, url: &str) -> Self {
+        Mirror {
+            name: name.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Full URL of a file below the mirror base
+    pub fn join(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+}
+
+/// User-editable mirror list and an optional pin that bypasses probing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorSettings {
+    pub mirrors: Vec<Mirror>,
+    /// Name of the mirror to always use
+    pub pinned: Option<String>,
+}
+
+impl Default for MirrorSettings {
+    fn default() -> Self {
+        MirrorSettings {
+            mirrors: vec![
+                Mirror::new("kernel.org CDN", "https://cdn.kernel.org/pub/linux/kernel"),
+                Mirror::new(
+                    "kernel.org edge",
+                    "https://mirrors.edge.kernel.org/pub/linux/kernel",
+                ),
+                Mirror::new("kernel.org", "https://mirrors.kernel.org/pub/linux/kernel"),
+                Mirror::new("www.kernel.org", "https://www.kernel.org/pub/linux/kernel"),
+            ],
+            pinned: None,
+        }
+    }
+}
+
+/// Timing of one HEAD request, shown to users deciding whether to pin a mirror
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorProbe {
+    pub mirror: Mirror,
+    pub latency_ms: Option<u64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl MirrorProbe {
+    /// Whether the mirror answered with a success status in time
+    pub fn reachable(&self) -> bool {
+        self.latency_ms.is_some() && self.status.is_some_and(|status| status < 400)
+    }
+}
+
+/// Sends a HEAD request to every mirror concurrently, fastest first
+pub async fn probe_mirrors(client: &Client, mirrors: &[Mirror]) -> Vec<MirrorProbe> {
+    let mut probes = JoinSet::new();
+    for mirror in mirrors.iter().cloned() {
+        let client = client.clone();
+        probes.spawn(async move { probe(&client, mirror).await });
+    }
+    let mut results = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(probe) = result {
+            results.push(probe);
+        }
+    }
+    results.sort_by_key(|probe| (!probe.reachable(), probe.latency_ms.unwrap_or(u64::MAX)));
+    results
+}
+
+async fn probe(client: &Client, mirror: Mirror) -> MirrorProbe {
+    let started = Instant::now();
+    let response = client
+        .head(mirror.join("/"))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await;
+    match response {
+        Ok(response) => MirrorProbe {
+            mirror,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            status: Some(response.status().as_u16()),
+            error: None,
+        },
+        Err(error) => MirrorProbe {
+            mirror,
+            latency_ms: None,
+            status: None,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+/// Mirror to download from: the pinned one, else the fastest reachable, else the first listed
+pub fn select_mirror<'a>(
+    settings: &'a MirrorSettings,
+    probes: &[MirrorProbe],
+) -> Option<&'a Mirror> {
+    if let Some(pinned) = &settings.pinned {
+        if let Some(mirror) = settings
+            .mirrors
+            .iter()
+            .find(|mirror| &mirror.name == pinned)
+        {
+            return Some(mirror);
+        }
+    }
+    probes
+        .iter()
+        .filter(|probe| probe.reachable())
+        .find_map(|probe| {
+            settings
+                .mirrors
+                .iter()
+                .find(|mirror| **mirror == probe.mirror)
+        })
+        .or_else(|| settings.mirrors.first())
+}
@@ -0,0 +1,108 @@
+// src-tauri/src/core/mod.rs
+
+pub mod advisory;
+pub mod appliance;
+pub mod attestation;
+pub mod backup;
+pub mod benchmark;
+pub mod bisect;
+pub mod bluetooth;
+pub mod boot_image;
+pub mod boot_test;
+pub mod boot_watchdog;
+pub mod bootloader;
+pub mod boottime;
+pub mod build;
+pub mod bundle;
+pub mod cache;
+pub mod camera;
+pub mod cflags;
+pub mod cgroup;
+pub mod checkpoint;
+pub mod clock;
+pub mod cmdline;
+pub mod compression;
+pub mod config;
+pub mod containers;
+pub mod cpu;
+pub mod dashboard;
+pub mod distro;
+pub mod dtb;
+pub mod explain;
+pub mod fetch;
+pub mod fleet;
+pub mod fuzz;
+pub mod gaming;
+pub mod generator;
+pub mod hardware;
+pub mod headers;
+pub mod hibernate;
+pub mod host;
+pub mod i18n;
+pub mod ikconfig;
+pub mod input;
+pub mod install;
+pub mod jobs;
+pub mod kconfig;
+pub mod kexec;
+pub mod keyring;
+pub mod knowledge;
+pub mod latency;
+pub mod lsm;
+pub mod machine_capture;
+pub mod memory;
+pub mod memory_guard;
+pub mod mirror;
+pub mod module_policy;
+pub mod modules;
+pub mod net;
+pub mod networking;
+pub mod nvidia;
+pub mod output;
+pub mod patch_manifest;
+pub mod patches;
+pub mod paths;
+pub mod pipeline;
+pub mod plan;
+pub mod plugins;
+pub mod policy;
+pub mod popularity;
+pub mod power;
+pub mod prebuilt;
+pub mod printing;
+pub mod privilege;
+pub mod procfs;
+pub mod profiles;
+pub mod prompts;
+pub mod provisioning;
+pub mod quick_build;
+pub mod quirks;
+pub mod release;
+pub mod remote_manifest;
+pub mod requirements;
+pub mod resolver;
+pub mod results;
+pub mod review;
+pub mod rgb;
+pub mod sbc;
+pub mod scripting;
+pub mod search;
+pub mod sensors;
+pub mod slots;
+pub mod smoke;
+pub mod sources;
+pub mod state_file;
+pub mod stats;
+pub mod suspend;
+pub mod symbols;
+pub mod sysctl;
+pub mod system;
+pub mod telemetry;
+pub mod thunderbolt;
+pub mod toolchain;
+pub mod version_catalog;
+pub mod virtme;
+pub mod watchdog;
+pub mod wizard;
+pub mod workspace;
+pub mod zfs;
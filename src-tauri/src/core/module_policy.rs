@@ -0,0 +1,199 @@
+// src-tauri/src/core/module_policy.rs
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Directory the policy files are installed in; modprobe reads it in name order
+const MODPROBE_DIR: &str = "/etc/modprobe.d";
+
+/// A module kept out of automatic loading
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlacklistedModule {
+    pub module: String,
+    /// Written as a comment so whoever reads the file knows why
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Parameters passed whenever a module loads
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleOptions {
+    pub module: String,
+    /// `name=value` pairs, e.g. `modeset=1`
+    pub options: Vec<String>,
+}
+
+/// Drivers built but not loaded, and module parameters, installed as a modprobe.d file
+/// alongside the kernel
+/// modprobe.d is shared by every installed kernel, so each entry becomes an `install` command
+/// that only acts when the running kernel is the one the policy was installed with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModulePolicy {
+    #[serde(default)]
+    pub blacklist: Vec<BlacklistedModule>,
+    #[serde(default)]
+    pub options: Vec<ModuleOptions>,
+}
+
+impl ModulePolicy {
+    /// Keeps nouveau away from the proprietary NVIDIA driver and enables its KMS
+    pub fn nvidia_proprietary() -> Self {
+        ModulePolicy {
+            blacklist: vec![BlacklistedModule {
+                module: String::from("nouveau"),
+                reason: Some(String::from("the proprietary NVIDIA driver drives the GPU")),
+            }],
+            options: vec![
+                ModuleOptions {
+                    module: String::from("nouveau"),
+                    options: vec![String::from("modeset=0")],
+                },
+                ModuleOptions {
+                    module: String::from("nvidia_drm"),
+                    options: vec![String::from("modeset=1")],
+                },
+            ],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blacklist.is_empty() && self.options.is_empty()
+    }
+
+    /// Blacklists `module`, replacing an earlier entry for it
+    pub fn blacklist(&mut self, module: &str, reason: Option<&str>) {
+        self.blacklist.retain(|entry| entry.module != module);
+        self.blacklist.push(BlacklistedModule {
+            module: module.to_string(),
+            reason: reason.map(str::to_string),
+        });
+    }
+
+    /// Whether `module` is kept from loading automatically; modprobe treats - and _ alike
+    pub fn is_blacklisted(&self, module: &str) -> bool {
+        self.blacklist
+            .iter()
+            .any(|entry| normalize(&entry.module) == normalize(module))
+    }
+
+    /// Rejects names and parameters modprobe or the shell running its `install` commands
+    /// would misread, since the file is installed as root
+    pub fn validate(&self) -> Result<()> {
+        let modules = self
+            .blacklist
+            .iter()
+            .map(|entry| &entry.module)
+            .chain(self.options.iter().map(|entry| &entry.module));
+        for module in modules {
+            if module.is_empty()
+                || !module
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                bail!("{:?} is not a module name", module);
+            }
+        }
+        for entry in &self.options {
+            if entry.options.is_empty() {
+                bail!("no options given for {}", entry.module);
+            }
+            for option in &entry.options {
+                let valid = option.split_once('=').is_some_and(|(name, value)| {
+                    !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+                        && value
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || "_.,:/+-".contains(c))
+                });
+                if !valid {
+                    bail!(
+                        "{:?} is not a name=value option for {}",
+                        option,
+                        entry.module
+                    );
+                }
+            }
+        }
+        for entry in &self.blacklist {
+            if entry
+                .reason
+                .as_deref()
+                .is_some_and(|reason| reason.contains('\n'))
+            {
+                bail!("the reason for blacklisting {} spans lines", entry.module);
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the policy of kernel `version` is installed, apart from other kernels' policies
+    pub fn path(version: &str) -> PathBuf {
+        PathBuf::from(MODPROBE_DIR).join(format!("kernelforge-{}.conf", version))
+    }
+
+    /// Contents of the modprobe.d file for kernel `version`
+    /// A blacklisted module fails to load under that kernel, explicitly or as a dependency;
+    /// options are added to the ones given on the command line. Other kernels load the
+    /// module as if the file did not exist
+    pub fn render(&self, version: &str) -> Result<String> {
+        self.validate()?;
+        if version.is_empty()
+            || !version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_.+-".contains(c))
+        {
+            bail!("{:?} is not a kernel version", version);
+        }
+        let mut text = format!(
+            "# Generated by KernelForge for {}; changes are lost on the next install\n",
+            version
+        );
+        let passthrough = |module: &str, extra: &str| {
+            format!(
+                "exec /sbin/modprobe --ignore-install {} $CMDLINE_OPTS{}",
+                module, extra
+            )
+        };
+        for entry in &self.blacklist {
+            text.push('\n');
+            if let Some(reason) = &entry.reason {
+                text.push_str(&format!("# {}\n", reason));
+            }
+            text.push_str(&format!(
+                "install {} if [ \"$(uname -r)\" = \"{}\" ]; then exit 1; fi; {}\n",
+                entry.module,
+                version,
+                passthrough(&entry.module, "")
+            ));
+        }
+        // A blacklisted module never loads under this kernel, so its options are moot and a
+        // second install command for it would replace the first
+        let options: Vec<&ModuleOptions> = self
+            .options
+            .iter()
+            .filter(|entry| !self.is_blacklisted(&entry.module))
+            .collect();
+        if !options.is_empty() {
+            text.push('\n');
+        }
+        for entry in options {
+            let extra = format!(" {}", entry.options.join(" "));
+            text.push_str(&format!(
+                "install {} if [ \"$(uname -r)\" = \"{}\" ]; then {}; fi; {}\n",
+                entry.module,
+                version,
+                passthrough(&entry.module, &extra),
+                passthrough(&entry.module, "")
+            ));
+        }
+        Ok(text)
+    }
+}
+
+fn normalize(module: &str) -> String {
+    module.replace('-', "_")
+}
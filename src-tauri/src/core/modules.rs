@@ -0,0 +1,160 @@
+// src-tauri/src/core/modules.rs
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::system::SystemFacade;
+
+const MODULES_ROOT: &str = "/lib/modules";
+
+/// Modules that, when missing, leave the machine unable to mount its root or unlock it
+const CRITICAL_PREFIXES: &[&str] = &[
+    "nvme",
+    "ahci",
+    "sd_mod",
+    "virtio_blk",
+    "virtio_scsi",
+    "virtio_pci",
+    "hv_storvsc",
+    "vmw_pvscsi",
+    "mpt3sas",
+    "megaraid",
+    "dm_mod",
+    "dm_crypt",
+    "raid",
+    "md_mod",
+    "ext4",
+    "btrfs",
+    "xfs",
+    "f2fs",
+    "zfs",
+    "usbhid",
+    "hid_generic",
+    "atkbd",
+    "i8042",
+    "xhci",
+    "ehci",
+];
+
+/// Modules without which the system boots but is crippled: display, network
+const IMPORTANT_PREFIXES: &[&str] = &[
+    "i915",
+    "amdgpu",
+    "radeon",
+    "nouveau",
+    "nvidia",
+    "e1000",
+    "igb",
+    "igc",
+    "r8169",
+    "iwlwifi",
+    "iwlmvm",
+    "ath",
+    "mt76",
+    "rtw",
+    "virtio_net",
+    "vmxnet3",
+    "hv_netvsc",
+];
+
+/// How much a module matters for getting a usable system after a reboot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ModuleTier {
+    /// Storage, root filesystem and early input; a missing one means no boot
+    Critical,
+    /// Display and network drivers
+    Important,
+    Optional,
+}
+
+impl ModuleTier {
+    /// Classifies a module by name, `-` and `_` being equivalent
+    pub fn of(module: &str) -> Self {
+        let name = module.replace('-', "_");
+        let matches = |prefixes: &[&str]| prefixes.iter().any(|prefix| name.starts_with(prefix));
+        if matches(CRITICAL_PREFIXES) {
+            ModuleTier::Critical
+        } else if matches(IMPORTANT_PREFIXES) {
+            ModuleTier::Important
+        } else {
+            ModuleTier::Optional
+        }
+    }
+}
+
+/// Modules of `tier` among the given ones, typically those loaded in the running kernel
+pub fn modules_in_tier<'a>(
+    modules: impl IntoIterator<Item = &'a String>,
+    tier: ModuleTier,
+) -> Vec<String> {
+    modules
+        .into_iter()
+        .filter(|module| ModuleTier::of(module) == tier)
+        .cloned()
+        .collect()
+}
+
+/// Modules available to a kernel version, loadable or built in
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleIndex {
+    pub version: String,
+    pub loadable: BTreeSet<String>,
+    pub builtin: BTreeSet<String>,
+}
+
+impl ModuleIndex {
+    /// Directory holding the modules of `version`
+    pub fn dir(version: &str) -> PathBuf {
+        Path::new(MODULES_ROOT).join(version)
+    }
+
+    /// Reads modules.dep and modules.builtin; fails when depmod has not produced modules.dep
+    pub fn load(system: &dyn SystemFacade, version: &str) -> Result<Self> {
+        let dir = ModuleIndex::dir(version);
+        let dep_path = dir.join("modules.dep");
+        let dep = system
+            .read_to_string(&dep_path)
+            .with_context(|| format!("reading {}", dep_path.display()))?;
+        let builtin = system
+            .read_to_string(&dir.join("modules.builtin"))
+            .unwrap_or_default();
+        let loadable: BTreeSet<String> = dep
+            .lines()
+            .filter_map(|line| line.split(':').next())
+            .filter_map(module_name)
+            .collect();
+        if loadable.is_empty() && builtin.trim().is_empty() {
+            bail!("{} lists no modules", dep_path.display());
+        }
+        Ok(ModuleIndex {
+            version: version.to_string(),
+            loadable,
+            builtin: builtin.lines().filter_map(module_name).collect(),
+        })
+    }
+
+    /// Whether a module can be loaded or is compiled in
+    pub fn provides(&self, module: &str) -> bool {
+        let name = module.replace('-', "_");
+        self.loadable.contains(&name) || self.builtin.contains(&name)
+    }
+
+    /// Modules from `wanted` the kernel cannot provide
+    pub fn missing<'a>(&self, wanted: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+        wanted
+            .into_iter()
+            .filter(|module| !self.provides(module))
+            .cloned()
+            .collect()
+    }
+}
+
+/// `kernel/drivers/nvme/host/nvme.ko.zst` to `nvme`
+fn module_name(path: &str) -> Option<String> {
+    let file = path.trim().rsplit('/').next()?;
+    let stem = file.split(".ko").next()?;
+    (!stem.is_empty()).then(|| stem.replace('-', "_"))
+}
@@ -0,0 +1,201 @@
+// src-tauri/src/core/net.rs
+
+use std::error::Error as _;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use reqwest::{Certificate, Client, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+
+/// Identifies KernelForge to kernel.org and mirrors
+pub const USER_AGENT: &str = concat!("KernelForge/", env!("CARGO_PKG_VERSION"));
+
+/// Connect timeout for every request; downloads themselves are not time-limited
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Timeout of each request made by the connectivity self-test
+pub const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Proxy used for outgoing requests
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProxySetting {
+    /// HTTP_PROXY, HTTPS_PROXY, ALL_PROXY and NO_PROXY from the environment
+    #[default]
+    System,
+    /// Connect directly, ignoring the environment
+    Direct,
+    /// An http://, https://, socks5:// or socks5h:// proxy URL, credentials allowed
+    Manual {
+        url: String,
+        no_proxy: Option<String>,
+    },
+}
+
+/// Network options applied to every HTTP client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    pub proxy: ProxySetting,
+    /// PEM bundle trusted in addition to the built-in roots, for TLS-inspecting proxies
+    pub ca_bundle: Option<PathBuf>,
+}
+
+/// HTTP client shared by the version catalog, metadata lookups and downloads
+pub fn http_client(settings: &NetworkSettings) -> Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT);
+
+    match &settings.proxy {
+        ProxySetting::System => {}
+        ProxySetting::Direct => builder = builder.no_proxy(),
+        ProxySetting::Manual { url, no_proxy } => {
+            let scheme = url.split("://").next().unwrap_or_default();
+            if !matches!(scheme, "http" | "https" | "socks5" | "socks5h") {
+                bail!("unsupported proxy scheme in {}", url);
+            }
+            let proxy = Proxy::all(url.as_str())
+                .with_context(|| format!("invalid proxy URL {}", url))?
+                .no_proxy(no_proxy.as_deref().and_then(NoProxy::from_string));
+            builder = builder.proxy(proxy);
+        }
+    }
+
+    if let Some(bundle) = &settings.ca_bundle {
+        let pem =
+            fs::read(bundle).with_context(|| format!("reading CA bundle {}", bundle.display()))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("parsing CA bundle {}", bundle.display()))?;
+        if certificates.is_empty() {
+            bail!("CA bundle {} contains no certificates", bundle.display());
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder.build().context("creating HTTP client")
+}
+
+/// A host KernelForge needs to reach
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub name: String,
+    pub url: String,
+}
+
+impl Endpoint {
+    pub fn new(name: &str, url: &str) -> Self {
+        Endpoint {
+            name: name.to_string(),
+            url: url.to_string(),
+        }
+    }
+}
+
+/// Endpoints checked by the self-test
+pub fn default_endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new("version catalog", "https://www.kernel.org/releases.json"),
+        Endpoint::new(
+            "source downloads",
+            "https://cdn.kernel.org/pub/linux/kernel/",
+        ),
+        Endpoint::new("patches", "https://git.kernel.org/"),
+    ]
+}
+
+/// Layer at which an endpoint check failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureKind {
+    Proxy,
+    Dns,
+    Connect,
+    Tls,
+    Timeout,
+    HttpStatus,
+    Other,
+}
+
+/// Result of contacting one endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointCheck {
+    pub endpoint: Endpoint,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub failure: Option<FailureKind>,
+    /// Full error chain, innermost cause last
+    pub error: Option<String>,
+}
+
+impl EndpointCheck {
+    pub fn ok(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Contacts each endpoint in turn and reports which ones fail and why
+pub async fn self_test(client: &Client, endpoints: &[Endpoint]) -> Vec<EndpointCheck> {
+    let mut checks = Vec::new();
+    for endpoint in endpoints {
+        let started = Instant::now();
+        let response = client
+            .head(&endpoint.url)
+            .timeout(SELF_TEST_TIMEOUT)
+            .send()
+            .await;
+        let check = match response {
+            Ok(response) => {
+                let status = response.status();
+                let failed = status.is_client_error() || status.is_server_error();
+                EndpointCheck {
+                    endpoint: endpoint.clone(),
+                    status: Some(status.as_u16()),
+                    latency_ms: Some(started.elapsed().as_millis() as u64),
+                    failure: failed.then_some(FailureKind::HttpStatus),
+                    error: failed.then(|| status.to_string()),
+                }
+            }
+            Err(error) => EndpointCheck {
+                endpoint: endpoint.clone(),
+                status: None,
+                latency_ms: None,
+                failure: Some(classify(&error)),
+                error: Some(error_chain(&error)),
+            },
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+/// Maps a reqwest error to the layer that failed by inspecting its source chain
+fn classify(error: &reqwest::Error) -> FailureKind {
+    if error.is_timeout() {
+        return FailureKind::Timeout;
+    }
+    let chain = error_chain(error).to_lowercase();
+    if chain.contains("proxy") || chain.contains("socks") {
+        FailureKind::Proxy
+    } else if chain.contains("dns") || chain.contains("failed to lookup address") {
+        FailureKind::Dns
+    } else if chain.contains("certificate") || chain.contains("tls") {
+        FailureKind::Tls
+    } else if error.is_connect() {
+        FailureKind::Connect
+    } else {
+        FailureKind::Other
+    }
+}
+
+fn error_chain(error: &reqwest::Error) -> String {
+    let mut text = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        text.push_str(": ");
+        text.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    text
+}
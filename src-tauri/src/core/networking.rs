@@ -0,0 +1,660 @@
+// src-tauri/src/core/networking.rs
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::procfs::{parse_modules, PROC_MODULES};
+use super::requirements::{self, MachineRequirements};
+use super::resolver::ConfigLayer;
+use super::system::SystemFacade;
+
+const SYS_NET: &str = "/sys/class/net";
+
+/// Saved nftables rulesets, read when `nft list ruleset` is not allowed
+const NFT_RULESETS: &[&str] = &["/etc/nftables.conf", "/etc/sysconfig/nftables.conf"];
+
+/// Saved iptables rulesets, read when `iptables-save` is not allowed
+const IPTABLES_RULESETS: &[&str] = &[
+    "/etc/iptables/rules.v4",
+    "/etc/iptables/iptables.rules",
+    "/etc/sysconfig/iptables",
+];
+
+/// nft statements and expressions, and the options they load
+const NFT_EXPRESSIONS: &[(&str, &str)] = &[
+    ("ct", "CONFIG_NFT_CT"),
+    ("masquerade", "CONFIG_NFT_MASQ"),
+    ("snat", "CONFIG_NFT_NAT"),
+    ("dnat", "CONFIG_NFT_NAT"),
+    ("redirect", "CONFIG_NFT_REDIR"),
+    ("reject", "CONFIG_NFT_REJECT"),
+    ("fib", "CONFIG_NFT_FIB_INET"),
+    ("limit", "CONFIG_NFT_LIMIT"),
+    ("log", "CONFIG_NFT_LOG"),
+    ("queue", "CONFIG_NFT_QUEUE"),
+    ("quota", "CONFIG_NFT_QUOTA"),
+    ("socket", "CONFIG_NFT_SOCKET"),
+    ("tproxy", "CONFIG_NFT_TPROXY"),
+    ("flowtable", "CONFIG_NFT_FLOW_OFFLOAD"),
+];
+
+/// nft table families and the options that provide them
+const NFT_FAMILIES: &[(&str, &str)] = &[
+    ("inet", "CONFIG_NF_TABLES_INET"),
+    ("ip", "CONFIG_NF_TABLES_IPV4"),
+    ("ip6", "CONFIG_NF_TABLES_IPV6"),
+    ("bridge", "CONFIG_NF_TABLES_BRIDGE"),
+    ("netdev", "CONFIG_NF_TABLES_NETDEV"),
+];
+
+/// iptables matches (`-m`) and targets (`-j`), and the options they load
+const XT_EXTENSIONS: &[(&str, &str)] = &[
+    ("conntrack", "CONFIG_NETFILTER_XT_MATCH_CONNTRACK"),
+    ("state", "CONFIG_NETFILTER_XT_MATCH_STATE"),
+    ("multiport", "CONFIG_NETFILTER_XT_MATCH_MULTIPORT"),
+    ("comment", "CONFIG_NETFILTER_XT_MATCH_COMMENT"),
+    ("addrtype", "CONFIG_NETFILTER_XT_MATCH_ADDRTYPE"),
+    ("limit", "CONFIG_NETFILTER_XT_MATCH_LIMIT"),
+    ("recent", "CONFIG_NETFILTER_XT_MATCH_RECENT"),
+    ("owner", "CONFIG_NETFILTER_XT_MATCH_OWNER"),
+    ("physdev", "CONFIG_NETFILTER_XT_MATCH_PHYSDEV"),
+    ("iprange", "CONFIG_NETFILTER_XT_MATCH_IPRANGE"),
+    ("set", "CONFIG_NETFILTER_XT_SET"),
+    ("mark", "CONFIG_NETFILTER_XT_MARK"),
+    ("MARK", "CONFIG_NETFILTER_XT_MARK"),
+    ("connmark", "CONFIG_NETFILTER_XT_CONNMARK"),
+    ("CONNMARK", "CONFIG_NETFILTER_XT_CONNMARK"),
+    ("MASQUERADE", "CONFIG_NETFILTER_XT_TARGET_MASQUERADE"),
+    ("SNAT", "CONFIG_NETFILTER_XT_NAT"),
+    ("DNAT", "CONFIG_NETFILTER_XT_NAT"),
+    ("REDIRECT", "CONFIG_NETFILTER_XT_TARGET_REDIRECT"),
+    ("REJECT", "CONFIG_IP_NF_TARGET_REJECT"),
+    ("LOG", "CONFIG_NETFILTER_XT_TARGET_LOG"),
+    ("TCPMSS", "CONFIG_NETFILTER_XT_TARGET_TCPMSS"),
+    ("CHECKSUM", "CONFIG_NETFILTER_XT_TARGET_CHECKSUM"),
+    ("NFQUEUE", "CONFIG_NETFILTER_XT_TARGET_NFQUEUE"),
+];
+
+/// Netfilter modules whose option is not named after an nft expression or an iptables
+/// extension; `xt_*` modules go through [`XT_EXTENSIONS`]
+const NETFILTER_MODULES: &[(&str, &str)] = &[
+    ("nf_tables", "CONFIG_NF_TABLES"),
+    ("nf_conntrack", "CONFIG_NF_CONNTRACK"),
+    ("nf_nat", "CONFIG_NF_NAT"),
+    ("nft_ct", "CONFIG_NFT_CT"),
+    ("nft_masq", "CONFIG_NFT_MASQ"),
+    ("nft_nat", "CONFIG_NFT_NAT"),
+    ("nft_chain_nat", "CONFIG_NFT_NAT"),
+    ("nft_redir", "CONFIG_NFT_REDIR"),
+    ("nft_reject", "CONFIG_NFT_REJECT"),
+    ("nft_reject_inet", "CONFIG_NFT_REJECT_INET"),
+    ("nft_fib", "CONFIG_NFT_FIB"),
+    ("nft_fib_inet", "CONFIG_NFT_FIB_INET"),
+    ("nft_limit", "CONFIG_NFT_LIMIT"),
+    ("nft_log", "CONFIG_NFT_LOG"),
+    ("nft_queue", "CONFIG_NFT_QUEUE"),
+    ("nft_quota", "CONFIG_NFT_QUOTA"),
+    ("nft_socket", "CONFIG_NFT_SOCKET"),
+    ("nft_tproxy", "CONFIG_NFT_TPROXY"),
+    ("nft_flow_offload", "CONFIG_NFT_FLOW_OFFLOAD"),
+    ("nft_compat", "CONFIG_NFT_COMPAT"),
+    ("x_tables", "CONFIG_NETFILTER_XTABLES"),
+    ("xt_nat", "CONFIG_NETFILTER_XT_NAT"),
+    ("ip_tables", "CONFIG_IP_NF_IPTABLES"),
+    ("iptable_filter", "CONFIG_IP_NF_FILTER"),
+    ("iptable_nat", "CONFIG_IP_NF_NAT"),
+    ("iptable_mangle", "CONFIG_IP_NF_MANGLE"),
+    ("ipt_REJECT", "CONFIG_IP_NF_TARGET_REJECT"),
+];
+
+/// nft objects declared as `<keyword> <name> {`, whose name is not an expression
+const NFT_DECLARATIONS: &[&str] = &[
+    "chain",
+    "set",
+    "map",
+    "flowtable",
+    "counter",
+    "quota",
+    "limit",
+    "secmark",
+    "synproxy",
+];
+
+/// Conntrack helpers, by the name nft and the `nf_conntrack_*` modules use
+const CONNTRACK_HELPERS: &[(&str, &str)] = &[
+    ("ftp", "CONFIG_NF_CONNTRACK_FTP"),
+    ("tftp", "CONFIG_NF_CONNTRACK_TFTP"),
+    ("sip", "CONFIG_NF_CONNTRACK_SIP"),
+    ("h323", "CONFIG_NF_CONNTRACK_H323"),
+    ("irc", "CONFIG_NF_CONNTRACK_IRC"),
+    ("pptp", "CONFIG_NF_CONNTRACK_PPTP"),
+    ("amanda", "CONFIG_NF_CONNTRACK_AMANDA"),
+    ("netbios_ns", "CONFIG_NF_CONNTRACK_NETBIOS_NS"),
+    ("snmp", "CONFIG_NF_CONNTRACK_SNMP"),
+    ("sane", "CONFIG_NF_CONNTRACK_SANE"),
+];
+
+/// A VPN the machine connects through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Vpn {
+    WireGuard,
+    OpenVpn,
+    /// Tailscale, whose wireguard-go runs over a tun device
+    Tailscale,
+}
+
+impl Vpn {
+    pub const ALL: [Vpn; 3] = [Vpn::WireGuard, Vpn::OpenVpn, Vpn::Tailscale];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Vpn::WireGuard => "WireGuard",
+            Vpn::OpenVpn => "OpenVPN",
+            Vpn::Tailscale => "Tailscale",
+        }
+    }
+
+    /// Whether the VPN is installed, configured or has an interface up
+    fn detect(self, system: &dyn SystemFacade, devtypes: &BTreeSet<String>) -> bool {
+        match self {
+            Vpn::WireGuard => {
+                system.has_program("wg")
+                    || system.exists(Path::new("/etc/wireguard"))
+                    || devtypes.contains("wireguard")
+            }
+            Vpn::OpenVpn => {
+                system.has_program("openvpn") || system.exists(Path::new("/etc/openvpn"))
+            }
+            Vpn::Tailscale => {
+                system.has_program("tailscaled") || system.exists(Path::new("/var/lib/tailscale"))
+            }
+        }
+    }
+
+    fn requirement(self) -> ConfigSuggestion {
+        match self {
+            Vpn::WireGuard => ConfigSuggestion::new(
+                "CONFIG_WIREGUARD",
+                ConfigValue::module(),
+                "the in-kernel WireGuard tunnel",
+            ),
+            Vpn::OpenVpn => ConfigSuggestion::new(
+                "CONFIG_TUN",
+                ConfigValue::module(),
+                "OpenVPN carries its tunnel through a tun or tap device",
+            ),
+            Vpn::Tailscale => ConfigSuggestion::new(
+                "CONFIG_TUN",
+                ConfigValue::module(),
+                "Tailscale carries its tunnel through a tun device",
+            ),
+        }
+    }
+}
+
+/// Protocol families few machines use, which the networking preset prunes unless
+/// something on the machine has them open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ExoticProtocol {
+    Sctp,
+    Dccp,
+    Tipc,
+    Rds,
+    Atm,
+    Phonet,
+    Ieee802154,
+}
+
+impl ExoticProtocol {
+    pub const ALL: [ExoticProtocol; 7] = [
+        ExoticProtocol::Sctp,
+        ExoticProtocol::Dccp,
+        ExoticProtocol::Tipc,
+        ExoticProtocol::Rds,
+        ExoticProtocol::Atm,
+        ExoticProtocol::Phonet,
+        ExoticProtocol::Ieee802154,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ExoticProtocol::Sctp => "CONFIG_IP_SCTP",
+            ExoticProtocol::Dccp => "CONFIG_IP_DCCP",
+            ExoticProtocol::Tipc => "CONFIG_TIPC",
+            ExoticProtocol::Rds => "CONFIG_RDS",
+            ExoticProtocol::Atm => "CONFIG_ATM",
+            ExoticProtocol::Phonet => "CONFIG_PHONET",
+            ExoticProtocol::Ieee802154 => "CONFIG_IEEE802154",
+        }
+    }
+
+    /// Module the protocol loads as
+    pub fn module(self) -> &'static str {
+        match self {
+            ExoticProtocol::Sctp => "sctp",
+            ExoticProtocol::Dccp => "dccp",
+            ExoticProtocol::Tipc => "tipc",
+            ExoticProtocol::Rds => "rds",
+            ExoticProtocol::Atm => "atm",
+            ExoticProtocol::Phonet => "phonet",
+            ExoticProtocol::Ieee802154 => "ieee802154",
+        }
+    }
+
+    /// Socket filter of `ss` listing the protocol's sockets, None when ss has none
+    fn ss_filter(self) -> Option<&'static str> {
+        match self {
+            ExoticProtocol::Sctp => Some("--sctp"),
+            ExoticProtocol::Dccp => Some("--dccp"),
+            ExoticProtocol::Tipc => Some("--tipc"),
+            _ => None,
+        }
+    }
+
+    /// Whether the running kernel has the protocol loaded or `ss` shows sockets of it
+    fn in_use(self, system: &dyn SystemFacade, modules: &BTreeSet<String>) -> bool {
+        if modules.contains(self.module()) {
+            return true;
+        }
+        let Some(filter) = self.ss_filter() else {
+            return false;
+        };
+        system
+            .run("ss", &["-H", "-a", "-n", filter])
+            .is_ok_and(|output| output.success() && !output.stdout.trim().is_empty())
+    }
+}
+
+/// VPNs, the live firewall ruleset and the protocols the machine has open
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkingSetup {
+    pub vpns: Vec<Vpn>,
+    /// Options the loaded nftables or iptables ruleset needs, or the loaded netfilter
+    /// modules when the ruleset cannot be dumped
+    pub firewall: Vec<String>,
+    /// Conntrack helpers the ruleset assigns or the running kernel has loaded
+    pub conntrack_helpers: Vec<String>,
+    pub protocols_in_use: Vec<ExoticProtocol>,
+}
+
+impl NetworkingSetup {
+    /// Reads VPNs, the firewall ruleset and open protocols, None when there is no VPN or
+    /// ruleset to keep
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let devtypes: BTreeSet<String> = system
+            .read_dir(Path::new(SYS_NET))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| system.read_to_string(&path.join("uevent")).ok())
+            .filter_map(|uevent| {
+                uevent
+                    .lines()
+                    .find_map(|line| line.strip_prefix("DEVTYPE=").map(str::to_string))
+            })
+            .collect();
+        let modules: BTreeSet<String> = system
+            .read_to_string(Path::new(PROC_MODULES))
+            .map(|text| {
+                parse_modules(&text)
+                    .iter()
+                    .map(|module| module.name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let nft_live = live_dump(system, "nft", &["list", "ruleset"]);
+        let iptables_live = live_dump(system, "iptables-save", &[]);
+        let mut firewall = Vec::new();
+        // Dumping needs root; firewalld and ufw keep their live rules outside the saved
+        // rulesets, so the modules the ruleset loaded are what is known of it
+        if nft_live.is_none() && iptables_live.is_none() {
+            firewall.extend(module_symbols(&modules));
+        }
+        let nft = nft_live.or_else(|| saved_ruleset(system, NFT_RULESETS));
+        let iptables = iptables_live.or_else(|| saved_ruleset(system, IPTABLES_RULESETS));
+        firewall.extend(nft.as_deref().map(nft_symbols).unwrap_or_default());
+        firewall.extend(
+            iptables
+                .as_deref()
+                .map(iptables_symbols)
+                .unwrap_or_default(),
+        );
+        firewall.sort();
+        firewall.dedup();
+
+        let mut conntrack_helpers: Vec<String> = CONNTRACK_HELPERS
+            .iter()
+            .map(|(helper, _)| *helper)
+            .filter(|helper| {
+                modules.contains(&format!("nf_conntrack_{}", helper))
+                    || nft
+                        .as_deref()
+                        .is_some_and(|ruleset| ruleset.contains(&format!("type \"{}\"", helper)))
+            })
+            .map(str::to_string)
+            .collect();
+        conntrack_helpers.sort();
+
+        let setup = NetworkingSetup {
+            vpns: Vpn::ALL
+                .into_iter()
+                .filter(|vpn| vpn.detect(system, &devtypes))
+                .collect(),
+            firewall,
+            conntrack_helpers,
+            protocols_in_use: ExoticProtocol::ALL
+                .into_iter()
+                .filter(|protocol| protocol.in_use(system, &modules))
+                .collect(),
+        };
+        if setup.required().is_empty() && setup.protocols_in_use.is_empty() {
+            return None;
+        }
+        Some(setup)
+    }
+
+    fn helper_symbols(&self) -> Vec<(&'static str, &'static str)> {
+        CONNTRACK_HELPERS
+            .iter()
+            .filter(|(helper, _)| self.conntrack_helpers.iter().any(|name| name == helper))
+            .copied()
+            .collect()
+    }
+
+    /// Whether the ruleset or its helpers need `symbol`
+    fn firewall_needs(&self, symbol: &str) -> bool {
+        self.firewall.iter().any(|needed| needed == symbol)
+            || self
+                .helper_symbols()
+                .iter()
+                .any(|(_, needed)| *needed == symbol)
+    }
+
+    /// The networking preset: WireGuard, tun/tap, nftables with conntrack and NAT, what
+    /// the machine uses now, and the exotic protocols it does not use pruned
+    pub fn preset(&self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("networking")
+            .with("CONFIG_WIREGUARD", ConfigValue::module())
+            .with("CONFIG_TUN", ConfigValue::module())
+            .with("CONFIG_NF_TABLES", ConfigValue::module())
+            .with("CONFIG_NF_TABLES_INET", ConfigValue::yes())
+            .with("CONFIG_NF_CONNTRACK", ConfigValue::module())
+            .with("CONFIG_NFT_CT", ConfigValue::module())
+            .with("CONFIG_NFT_NAT", ConfigValue::module())
+            .with("CONFIG_NFT_MASQ", ConfigValue::module())
+            .with("CONFIG_NFT_REJECT", ConfigValue::module())
+            .with("CONFIG_NFT_FIB_INET", ConfigValue::module());
+        for requirement in self.required() {
+            layer.set(&requirement.symbol, requirement.value);
+        }
+        for protocol in ExoticProtocol::ALL {
+            if !self.protocols_in_use.contains(&protocol) {
+                layer.set(protocol.symbol(), ConfigValue::NotSet);
+            }
+        }
+        layer
+    }
+}
+
+impl MachineRequirements for NetworkingSetup {
+    fn name(&self) -> &'static str {
+        "networking"
+    }
+
+    /// Options the VPNs, the ruleset and its helpers need, each symbol once
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let vpns = self.vpns.iter().map(|vpn| vpn.requirement());
+        let firewall = self.firewall.iter().map(|symbol| {
+            let value =
+                if symbol.starts_with("CONFIG_NF_TABLES_") && symbol != "CONFIG_NF_TABLES_BRIDGE" {
+                    ConfigValue::yes()
+                } else {
+                    ConfigValue::module()
+                };
+            ConfigSuggestion::new(symbol, value, "the firewall ruleset loaded now uses it")
+        });
+        let helpers = self.helper_symbols().into_iter().map(|(helper, symbol)| {
+            ConfigSuggestion::new(
+                symbol,
+                ConfigValue::module(),
+                &format!("the {} conntrack helper the firewall relies on", helper),
+            )
+        });
+        let protocols = self.protocols_in_use.iter().map(|protocol| {
+            ConfigSuggestion::new(
+                protocol.symbol(),
+                ConfigValue::module(),
+                &format!("{} sockets are open on this machine", protocol.module()),
+            )
+        });
+        requirements::unique(vpns.chain(firewall).chain(helpers).chain(protocols))
+    }
+
+    /// Checks the plan against the live state: a ruleset that could no longer load is a
+    /// blocker, since the machine would boot without its firewall
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let (firewall, other): (Vec<ConfigSuggestion>, Vec<ConfigSuggestion>) = missing
+            .into_iter()
+            .partition(|requirement| self.firewall_needs(&requirement.symbol));
+        let mut advisories = Vec::new();
+        if !firewall.is_empty() {
+            let symbols: Vec<&str> = firewall
+                .iter()
+                .map(|requirement| requirement.symbol.as_str())
+                .collect();
+            let mut advisory = Advisory::new(
+                Severity::Blocker,
+                "firewall-missing-config",
+                &[("symbols", symbols.join(", "))],
+            );
+            for requirement in firewall {
+                advisory = advisory.suggest(requirement);
+            }
+            advisories.push(advisory);
+        }
+        if !other.is_empty() {
+            let symbols: Vec<&str> = other
+                .iter()
+                .map(|requirement| requirement.symbol.as_str())
+                .collect();
+            let mut users: Vec<&str> = self.vpns.iter().map(|vpn| vpn.name()).collect();
+            users.extend(
+                self.protocols_in_use
+                    .iter()
+                    .map(|protocol| protocol.module()),
+            );
+            let mut advisory = Advisory::new(
+                Severity::Warning,
+                "networking-missing-config",
+                &[("users", users.join(", ")), ("symbols", symbols.join(", "))],
+            );
+            for requirement in other {
+                advisory = advisory.suggest(requirement);
+            }
+            advisories.push(advisory);
+        }
+        advisories
+    }
+
+    /// Refuses a config the live firewall ruleset could not load on
+    fn enforce(&self, config: &KernelConfig) -> Result<()> {
+        let missing: Vec<String> = self
+            .missing(config)
+            .into_iter()
+            .map(|requirement| requirement.symbol)
+            .filter(|symbol| self.firewall_needs(symbol))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "the firewall ruleset loaded now needs {}; the new kernel would boot without it",
+            missing.join(", ")
+        );
+    }
+}
+
+/// Output of the live dump, None when it is not allowed or shows nothing
+fn live_dump(system: &dyn SystemFacade, program: &str, args: &[&str]) -> Option<String> {
+    let output = system.run(program, args).ok()?;
+    (output.success() && !output.stdout.trim().is_empty()).then_some(output.stdout)
+}
+
+/// The first saved ruleset with rules in it
+fn saved_ruleset(system: &dyn SystemFacade, saved: &[&str]) -> Option<String> {
+    saved
+        .iter()
+        .find_map(|path| system.read_to_string(Path::new(path)).ok())
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// Options of the loaded netfilter modules, which the live ruleset pulled in
+pub fn module_symbols(modules: &BTreeSet<String>) -> Vec<String> {
+    let mut symbols: BTreeSet<&str> = BTreeSet::new();
+    for module in modules {
+        if let Some((_, symbol)) = NETFILTER_MODULES.iter().find(|(name, _)| name == module) {
+            symbols.insert(*symbol);
+        } else if let Some(extension) = module.strip_prefix("xt_") {
+            if let Some((_, symbol)) = XT_EXTENSIONS.iter().find(|(name, _)| *name == extension) {
+                symbols.insert(*symbol);
+            }
+        }
+    }
+    symbols.into_iter().map(str::to_string).collect()
+}
+
+/// Words of a ruleset line in statement or expression position: comments, quoted
+/// strings, declared names, chains jumped to and set references are left out, so a chain
+/// called `log` or a comment saying `reject` loads nothing
+fn nft_words(line: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut quoted = false;
+    let mut start = None;
+    for (index, c) in line.char_indices() {
+        let boundary = c == '"' || c.is_whitespace() || matches!(c, ';' | '{' | '}' | ',');
+        if !boundary && !quoted && c == '#' {
+            break;
+        }
+        if boundary || quoted {
+            if let Some(begin) = start.take() {
+                words.push(&line[begin..index]);
+            }
+            if c == '"' {
+                quoted = !quoted;
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(begin) = start {
+        words.push(&line[begin..]);
+    }
+    // `chain input {`, `limit lim_ssh {` and `ct helper ftp-std {` declare names
+    let declared = match words.as_slice() {
+        ["ct", _, _, ..] if line.trim_end().ends_with('{') => Some(2),
+        [keyword, _, ..]
+            if NFT_DECLARATIONS.contains(keyword) && line.trim_end().ends_with('{') =>
+        {
+            Some(1)
+        }
+        _ => None,
+    };
+    let mut statement = Vec::new();
+    let mut previous = "";
+    for (index, word) in words.into_iter().enumerate() {
+        let name =
+            Some(index) == declared || matches!(previous, "jump" | "goto") || word.starts_with('@');
+        if !name {
+            statement.push(word);
+        }
+        previous = word;
+    }
+    statement
+}
+
+/// Options an `nft list ruleset` dump or nftables.conf uses
+pub fn nft_symbols(ruleset: &str) -> Vec<String> {
+    let mut symbols: BTreeSet<&str> = BTreeSet::new();
+    for line in ruleset.lines() {
+        let words = nft_words(line);
+        if let ["table", rest @ ..] = words.as_slice() {
+            symbols.insert("CONFIG_NF_TABLES");
+            // Tables without a family are ip tables
+            let family = match rest {
+                [family, _, ..] => family,
+                _ => "ip",
+            };
+            if let Some((_, symbol)) = NFT_FAMILIES.iter().find(|(name, _)| *name == family) {
+                symbols.insert(*symbol);
+            }
+            continue;
+        }
+        for word in &words {
+            if let Some((_, symbol)) = NFT_EXPRESSIONS.iter().find(|(name, _)| name == word) {
+                symbols.insert(*symbol);
+            }
+        }
+    }
+    if symbols.contains("CONFIG_NFT_MASQ") {
+        symbols.insert("CONFIG_NFT_NAT");
+    }
+    if symbols.contains("CONFIG_NFT_CT") || symbols.contains("CONFIG_NFT_NAT") {
+        symbols.insert("CONFIG_NF_CONNTRACK");
+    }
+    symbols.into_iter().map(str::to_string).collect()
+}
+
+/// Options an `iptables-save` dump uses, through the nftables compat layer when it
+/// was written by iptables-nft
+pub fn iptables_symbols(dump: &str) -> Vec<String> {
+    let mut symbols: BTreeSet<&str> = BTreeSet::new();
+    let mut tables = BTreeSet::new();
+    let mut rules = false;
+    for line in dump.lines() {
+        let line = line.trim();
+        if let Some(table) = line.strip_prefix('*') {
+            tables.insert(table);
+        }
+        if !line.starts_with("-A ") {
+            continue;
+        }
+        rules = true;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        for pair in words.windows(2) {
+            if !matches!(pair[0], "-m" | "-j" | "--match" | "--jump") {
+                continue;
+            }
+            if let Some((_, symbol)) = XT_EXTENSIONS.iter().find(|(name, _)| *name == pair[1]) {
+                symbols.insert(*symbol);
+            }
+        }
+    }
+    if !rules {
+        return Vec::new();
+    }
+    symbols.insert("CONFIG_NETFILTER_XTABLES");
+    let nat = tables.contains("nat");
+    if dump.contains("iptables-nft") || dump.contains("nf_tables") {
+        symbols.insert("CONFIG_NF_TABLES");
+        symbols.insert("CONFIG_NF_TABLES_IPV4");
+        symbols.insert("CONFIG_NFT_COMPAT");
+        if nat {
+            symbols.insert("CONFIG_NFT_NAT");
+        }
+    } else {
+        symbols.insert("CONFIG_IP_NF_IPTABLES");
+        symbols.insert("CONFIG_IP_NF_FILTER");
+        if nat {
+            symbols.insert("CONFIG_IP_NF_NAT");
+        }
+        if tables.contains("mangle") {
+            symbols.insert("CONFIG_IP_NF_MANGLE");
+        }
+    }
+    symbols.into_iter().map(str::to_string).collect()
+}
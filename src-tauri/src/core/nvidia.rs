@@ -0,0 +1,206 @@
+// src-tauri/src/core/nvidia.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::release::ReleaseNumber;
+use super::system::SystemFacade;
+
+/// Newest kernel series each driver branch is known to build against, maintained by hand
+/// Branches missing from the table that are newer than its last row are reported as unknown
+const BRANCH_SUPPORT: &[(u32, (u32, u32))] = &[
+    (390, (6, 0)),
+    (470, (6, 10)),
+    (535, (6, 11)),
+    (550, (6, 12)),
+    (560, (6, 12)),
+    (565, (6, 13)),
+    (570, (6, 15)),
+    (575, (6, 15)),
+    (580, (6, 17)),
+];
+
+/// First branch whose modules load with indirect branch tracking enabled
+const IBT_SAFE_BRANCH: u32 = 530;
+
+/// First branch providing its own fbdev emulation (`nvidia-drm.fbdev=1`)
+const FBDEV_BRANCH: u32 = 545;
+
+/// Where the driver version was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriverSource {
+    Loaded,
+    Modinfo,
+    Dkms,
+}
+
+/// Installed proprietary or open NVIDIA kernel driver
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NvidiaDriver {
+    pub version: String,
+    pub branch: u32,
+    pub open_modules: bool,
+    pub source: DriverSource,
+}
+
+/// Verdict of the compatibility table for one driver and target kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriverCompatibility {
+    Supported,
+    Unsupported { newest_series: (u32, u32) },
+    Unknown,
+}
+
+impl NvidiaDriver {
+    /// Finds the driver from the loaded module, modinfo or DKMS, in that order
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let (version, source) = system
+            .read_trimmed(Path::new("/sys/module/nvidia/version"))
+            .map(|version| (version, DriverSource::Loaded))
+            .or_else(|| {
+                let output = system.run("modinfo", &["-F", "version", "nvidia"]).ok()?;
+                let version = output.stdout.trim().to_string();
+                (output.success() && !version.is_empty()).then_some((version, DriverSource::Modinfo))
+            })
+            .or_else(|| dkms_version(system).map(|version| (version, DriverSource::Dkms)))?;
+        let branch = version.split('.').next()?.parse().ok()?;
+        let open_modules = system
+            .run("modinfo", &["-F", "license", "nvidia"])
+            .map(|output| output.stdout.contains("MIT"))
+            .unwrap_or(false);
+        Some(NvidiaDriver {
+            version,
+            branch,
+            open_modules,
+            source,
+        })
+    }
+
+    /// Looks the driver branch up in the compatibility table
+    pub fn compatibility(&self, target: &ReleaseNumber) -> DriverCompatibility {
+        match BRANCH_SUPPORT.iter().find(|(branch, _)| *branch == self.branch) {
+            Some((_, newest)) if target.series() <= *newest => DriverCompatibility::Supported,
+            Some((_, newest)) => DriverCompatibility::Unsupported {
+                newest_series: *newest,
+            },
+            None => match BRANCH_SUPPORT.last() {
+                Some((latest, _)) if self.branch > *latest => DriverCompatibility::Unknown,
+                // An unlisted old branch falls back to the closest older branch we know
+                _ => BRANCH_SUPPORT
+                    .iter()
+                    .rev()
+                    .find(|(branch, _)| *branch < self.branch)
+                    .map(|(_, newest)| {
+                        if target.series() <= *newest {
+                            DriverCompatibility::Supported
+                        } else {
+                            DriverCompatibility::Unsupported {
+                                newest_series: *newest,
+                            }
+                        }
+                    })
+                    .unwrap_or(DriverCompatibility::Unsupported { newest_series: (0, 0) }),
+            },
+        }
+    }
+
+    /// Warnings for building `target` with `config` while this driver is installed
+    pub fn check(&self, target: &ReleaseNumber, config: &KernelConfig) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+
+        match self.compatibility(target) {
+            DriverCompatibility::Supported => {}
+            DriverCompatibility::Unsupported { newest_series } => advisories.push(Advisory::new(
+                Severity::Warning,
+                "nvidia-unsupported",
+                &[
+                    ("version", self.version.clone()),
+                    ("branch", self.branch.to_string()),
+                    ("newest", format!("{}.{}", newest_series.0, newest_series.1)),
+                    ("target", target.to_string()),
+                ],
+            )),
+            DriverCompatibility::Unknown => advisories.push(Advisory::new(
+                Severity::Info,
+                "nvidia-unknown",
+                &[("version", self.version.clone()), ("target", target.to_string())],
+            )),
+        }
+
+        let missing: Vec<&str> = ["CONFIG_MODULES", "CONFIG_DRM", "CONFIG_DRM_KMS_HELPER"]
+            .into_iter()
+            .filter(|symbol| !config.is_enabled(symbol))
+            .collect();
+        if !missing.is_empty() {
+            let mut advisory = Advisory::new(
+                Severity::Warning,
+                "nvidia-missing-config",
+                &[("symbols", missing.join(", "))],
+            );
+            for symbol in missing {
+                let value = if symbol == "CONFIG_MODULES" { ConfigValue::yes() } else { ConfigValue::module() };
+                advisory = advisory.suggest(ConfigSuggestion::new(symbol, value, "required by nvidia-drm"));
+            }
+            advisories.push(advisory);
+        }
+
+        if self.branch < IBT_SAFE_BRANCH && !self.open_modules && config.is_enabled("CONFIG_X86_KERNEL_IBT") {
+            advisories.push(
+                Advisory::new(
+                    Severity::Warning,
+                    "nvidia-ibt",
+                    &[("branch", self.branch.to_string())],
+                )
+                .suggest(ConfigSuggestion::new(
+                    "CONFIG_X86_KERNEL_IBT",
+                    ConfigValue::NotSet,
+                    "driver branch is not IBT clean",
+                )),
+            );
+        }
+
+        let simpledrm = config.is_enabled("CONFIG_DRM_SIMPLEDRM");
+        if self.branch >= FBDEV_BRANCH && simpledrm && !config.is_enabled("CONFIG_DRM_FBDEV_EMULATION") {
+            advisories.push(
+                Advisory::new(
+                    Severity::Info,
+                    "nvidia-fbdev",
+                    &[],
+                )
+                .suggest(ConfigSuggestion::new(
+                    "CONFIG_DRM_FBDEV_EMULATION",
+                    ConfigValue::yes(),
+                    "lets nvidia-drm provide the console",
+                )),
+            );
+        } else if self.branch < FBDEV_BRANCH && !simpledrm {
+            advisories.push(
+                Advisory::new(
+                    Severity::Info,
+                    "nvidia-no-simpledrm",
+                    &[("branch", self.branch.to_string())],
+                )
+                .suggest(ConfigSuggestion::new(
+                    "CONFIG_DRM_SIMPLEDRM",
+                    ConfigValue::yes(),
+                    "keeps a console before the NVIDIA driver loads",
+                )),
+            );
+        }
+
+        advisories
+    }
+}
+
+/// Parses `dkms status` lines such as `nvidia/550.78, 6.9.3-arch1-1, x86_64: installed`
+fn dkms_version(system: &dyn SystemFacade) -> Option<String> {
+    let output = system.run("dkms", &["status", "nvidia"]).ok()?;
+    output.stdout.lines().find_map(|line| {
+        let module = line.split([',', ':']).next()?.trim();
+        let version = module.strip_prefix("nvidia/").or_else(|| module.strip_prefix("nvidia, "))?;
+        Some(version.trim().to_string())
+    })
+}
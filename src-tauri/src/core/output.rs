@@ -0,0 +1,119 @@
+// src-tauri/src/core/output.rs
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Limits for streaming raw build output to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSettings {
+    /// How often pending lines are flushed as one batch
+    pub interval: Duration,
+    pub max_batch_lines: usize,
+    /// Lines kept while the frontend is behind; older ones are dropped first
+    pub max_pending_lines: usize,
+    /// Batches sent but not yet acknowledged before sending pauses
+    pub window: u64,
+}
+
+impl Default for StreamSettings {
+    fn default() -> Self {
+        StreamSettings {
+            interval: Duration::from_millis(100),
+            max_batch_lines: 500,
+            max_pending_lines: 5000,
+            window: 4,
+        }
+    }
+}
+
+/// Lines delivered in one event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputBatch {
+    /// Acknowledged by the frontend once rendered
+    pub seq: u64,
+    pub lines: Vec<String>,
+    /// Lines discarded since the previous batch because the frontend fell behind
+    pub dropped: u64,
+}
+
+/// Buffers build output and hands out batches no faster than the frontend acknowledges them
+#[derive(Debug, Clone)]
+pub struct OutputBatcher {
+    settings: StreamSettings,
+    pending: VecDeque<String>,
+    dropped: u64,
+    next_seq: u64,
+    acked: u64,
+}
+
+impl OutputBatcher {
+    pub fn new(settings: StreamSettings) -> Self {
+        OutputBatcher {
+            settings,
+            pending: VecDeque::new(),
+            dropped: 0,
+            next_seq: 1,
+            acked: 0,
+        }
+    }
+
+    pub fn settings(&self) -> &StreamSettings {
+        &self.settings
+    }
+
+    /// Queues a line, dropping the oldest pending one when the buffer is full
+    pub fn push(&mut self, line: String) {
+        if self.pending.len() >= self.settings.max_pending_lines.max(1) {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(line);
+    }
+
+    /// Records that the frontend rendered every batch up to `seq`
+    pub fn ack(&mut self, seq: u64) {
+        self.acked = self.acked.max(seq.min(self.next_seq - 1));
+    }
+
+    /// Batches sent but not acknowledged
+    pub fn in_flight(&self) -> u64 {
+        self.next_seq - 1 - self.acked
+    }
+
+    /// Next batch, None when nothing is pending or the window is full
+    pub fn take(&mut self) -> Option<OutputBatch> {
+        if self.pending.is_empty() || self.in_flight() >= self.settings.window {
+            return None;
+        }
+        let count = self.pending.len().min(self.settings.max_batch_lines.max(1));
+        let batch = OutputBatch {
+            seq: self.next_seq,
+            lines: self.pending.drain(..count).collect(),
+            dropped: std::mem::take(&mut self.dropped),
+        };
+        self.next_seq += 1;
+        Some(batch)
+    }
+
+    /// Remaining lines regardless of the window, sent once the build has finished
+    pub fn flush(&mut self) -> Option<OutputBatch> {
+        if self.pending.is_empty() && self.dropped == 0 {
+            return None;
+        }
+        let batch = OutputBatch {
+            seq: self.next_seq,
+            lines: self.pending.drain(..).collect(),
+            dropped: std::mem::take(&mut self.dropped),
+        };
+        self.next_seq += 1;
+        Some(batch)
+    }
+}
+
+impl Default for OutputBatcher {
+    fn default() -> Self {
+        OutputBatcher::new(StreamSettings::default())
+    }
+}
@@ -0,0 +1,148 @@
+// src-tauri/src/core/patch_manifest.rs
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::release::ReleaseNumber;
+use super::version_catalog::VersionCatalog;
+
+/// Patches known to KernelForge and the kernel series each one supports
+const BUNDLED_MANIFEST: &str = include_str!("../../data/patches.json");
+
+/// Whether a patch applies to a series, as recorded in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Compatibility {
+    Supported,
+    Unsupported {
+        reason: String,
+    },
+    /// The manifest says nothing about the series
+    Unknown,
+}
+
+/// Manifest entry for one series
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesSupport {
+    /// Major.minor, e.g. `6.12`
+    pub series: String,
+    #[serde(flatten)]
+    pub compatibility: Compatibility,
+    /// Where the claim comes from, e.g. the patch's repository
+    pub source: String,
+    /// Patch for this series, when it has a fixed location
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// A patch offered in the plan editor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestPatch {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub homepage: String,
+    pub series: Vec<SeriesSupport>,
+}
+
+impl ManifestPatch {
+    /// Manifest entry for the series `release` belongs to
+    pub fn support(&self, release: &ReleaseNumber) -> Option<&SeriesSupport> {
+        self.series.iter().find(|support| {
+            support
+                .series
+                .parse::<ReleaseNumber>()
+                .is_ok_and(|series| series.series() == release.series())
+        })
+    }
+}
+
+/// One cell of the matrix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixCell {
+    pub patch: String,
+    pub series: String,
+    pub compatibility: Compatibility,
+    /// None for unknown cells
+    pub source: Option<String>,
+}
+
+/// Patch × kernel series table, so the UI can grey out patches when the target changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityMatrix {
+    /// Column order, newest first
+    pub series: Vec<String>,
+    /// Row order
+    pub patches: Vec<String>,
+    /// Row by row
+    pub cells: Vec<MatrixCell>,
+}
+
+impl CompatibilityMatrix {
+    pub fn cell(&self, patch: &str, series: &str) -> Option<&MatrixCell> {
+        self.cells
+            .iter()
+            .find(|cell| cell.patch == patch && cell.series == series)
+    }
+}
+
+/// Versioned list of patches; a higher revision replaces a lower one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchManifest {
+    pub revision: u64,
+    pub patches: Vec<ManifestPatch>,
+}
+
+impl PatchManifest {
+    /// Manifest compiled into this release
+    pub fn bundled() -> Self {
+        PatchManifest::parse(BUNDLED_MANIFEST).expect("bundled patch manifest is valid")
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(text).context("parsing the patch manifest")
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ManifestPatch> {
+        self.patches.iter().find(|patch| patch.id == id)
+    }
+
+    /// Compatibility of every patch with every series in `series`
+    pub fn matrix(&self, series: &[(u32, u32)]) -> CompatibilityMatrix {
+        let mut cells = Vec::new();
+        for patch in &self.patches {
+            for &(major, minor) in series {
+                let support = patch.support(&ReleaseNumber::new(major, minor, 0));
+                cells.push(MatrixCell {
+                    patch: patch.id.clone(),
+                    series: format!("{}.{}", major, minor),
+                    compatibility: support
+                        .map(|support| support.compatibility.clone())
+                        .unwrap_or(Compatibility::Unknown),
+                    source: support.map(|support| support.source.clone()),
+                });
+            }
+        }
+        CompatibilityMatrix {
+            series: series
+                .iter()
+                .map(|(major, minor)| format!("{}.{}", major, minor))
+                .collect(),
+            patches: self.patches.iter().map(|patch| patch.id.clone()).collect(),
+            cells,
+        }
+    }
+
+    /// The matrix over every series the catalog currently offers
+    pub fn catalog_matrix(&self, catalog: &VersionCatalog) -> CompatibilityMatrix {
+        let mut series: Vec<(u32, u32)> = catalog
+            .versions
+            .iter()
+            .filter_map(|version| version.release)
+            .map(|release| release.series())
+            .collect();
+        series.sort_unstable_by(|a, b| b.cmp(a));
+        series.dedup();
+        self.matrix(&series)
+    }
+}
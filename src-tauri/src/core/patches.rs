@@ -0,0 +1,434 @@
+// src-tauri/src/core/patches.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::{CacheCategory, CacheIndex, SignatureStatus};
+use super::system::SystemFacade;
+
+/// An external patch selected for the build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchSource {
+    pub name: String,
+    pub url: String,
+    /// Content the plan was reviewed with; a different download is refused until re-pinned
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl PatchSource {
+    pub fn new(name: &str, url: &str) -> Self {
+        PatchSource {
+            name: name.to_string(),
+            url: url.to_string(),
+            sha256: None,
+        }
+    }
+
+    /// Accepts `sha256` as the reviewed content, e.g. after looking at a drift diff
+    pub fn pin(&mut self, sha256: &str) {
+        self.sha256 = Some(sha256.to_string());
+    }
+
+    /// Downloads the patch as served, byte for byte, so its hash matches what gets applied
+    pub async fn download(&self, client: &Client) -> Result<Vec<u8>> {
+        let bytes = client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("requesting {}", self.url))?
+            .bytes()
+            .await
+            .with_context(|| format!("downloading {}", self.url))?;
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Lines one file gains and loses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStat {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// What a patch says about itself and what it changes, shown before it is applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchSummary {
+    /// From the mail header; None for bare diffs
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    /// Commit message below the subject, or the text preceding a bare diff
+    pub description: String,
+    pub files: Vec<FileStat>,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub sha256: String,
+}
+
+impl PatchSummary {
+    /// Parses `git format-patch` mails and bare unified diffs; several mails in one file
+    /// are summarised together
+    pub fn parse(patch: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(patch);
+        let lines: Vec<&str> = text.lines().collect();
+        let diff_start = lines
+            .iter()
+            .enumerate()
+            .position(|(index, line)| {
+                line.starts_with("diff ")
+                    || (line.starts_with("--- ")
+                        && lines
+                            .get(index + 1)
+                            .is_some_and(|next| next.starts_with("+++ ")))
+            })
+            .unwrap_or(lines.len());
+        let (author, subject, description) = parse_header(&lines[..diff_start]);
+        let files = diffstat(&lines[diff_start..]);
+        PatchSummary {
+            author,
+            subject,
+            description,
+            insertions: files.iter().map(|file| file.insertions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+            files,
+            sha256: sha256_bytes(patch),
+        }
+    }
+}
+
+/// Author, subject and message of a mail header; the diffstat after `---` is dropped
+fn parse_header(lines: &[&str]) -> (Option<String>, Option<String>, String) {
+    let mut author = None;
+    let mut subject: Option<String> = None;
+    let mut in_headers = lines.first().is_some_and(|line| line.starts_with("From "));
+    let mut last_header = "";
+    let mut body = Vec::new();
+    for line in lines.iter().skip(usize::from(in_headers)) {
+        if in_headers {
+            if line.is_empty() {
+                in_headers = false;
+            } else if line.starts_with([' ', '\t']) {
+                // Folded header line
+                if last_header == "subject" {
+                    if let Some(subject) = subject.as_mut() {
+                        subject.push(' ');
+                        subject.push_str(line.trim());
+                    }
+                }
+            } else if let Some(value) = line.strip_prefix("From: ") {
+                author = Some(value.trim().to_string());
+                last_header = "from";
+            } else if let Some(value) = line.strip_prefix("Subject: ") {
+                subject = Some(value.trim().to_string());
+                last_header = "subject";
+            } else {
+                last_header = "";
+            }
+            continue;
+        }
+        if *line == "---" {
+            break;
+        }
+        body.push(*line);
+    }
+    let subject = subject.map(|subject| strip_patch_tag(&subject).to_string());
+    (author, subject, body.join("\n").trim().to_string())
+}
+
+/// `[PATCH v2 1/3] sched: ...` without the bracketed tag
+fn strip_patch_tag(subject: &str) -> &str {
+    match subject
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((_, rest)) => rest.trim_start(),
+        None => subject,
+    }
+}
+
+/// Per-file line counts, following hunk headers so `---` and `+++` inside a hunk count
+fn diffstat(lines: &[&str]) -> Vec<FileStat> {
+    let mut files: Vec<FileStat> = Vec::new();
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+    let mut pending_old: Option<String> = None;
+    for line in lines {
+        if old_left > 0 || new_left > 0 {
+            let Some(file) = files.last_mut() else {
+                break;
+            };
+            match line.as_bytes().first() {
+                Some(b'+') => {
+                    file.insertions += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                Some(b'-') => {
+                    file.deletions += 1;
+                    old_left = old_left.saturating_sub(1);
+                }
+                Some(b'\\') => {}
+                _ => {
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+            }
+            continue;
+        }
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            let path = paths
+                .rsplit_once(" b/")
+                .map_or(paths, |(_, path)| path)
+                .to_string();
+            files.push(FileStat {
+                path,
+                insertions: 0,
+                deletions: 0,
+            });
+            pending_old = None;
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            pending_old = Some(diff_path(path));
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let new = diff_path(path);
+            let path = if new == "/dev/null" {
+                pending_old.take().unwrap_or(new)
+            } else {
+                new
+            };
+            // A git diff already opened the entry
+            let opened = files.last().is_some_and(|file| {
+                file.path == path && file.insertions == 0 && file.deletions == 0
+            });
+            if !opened {
+                files.push(FileStat {
+                    path,
+                    insertions: 0,
+                    deletions: 0,
+                });
+            }
+        } else if let Some(ranges) = line.strip_prefix("@@ ") {
+            let mut counts = ranges.split_whitespace().take(2).map(|range| {
+                range
+                    .get(1..)
+                    .unwrap_or_default()
+                    .split_once(',')
+                    .map_or(Some(1), |(_, count)| count.parse().ok())
+                    .unwrap_or(0)
+            });
+            old_left = counts.next().unwrap_or(0);
+            new_left = counts.next().unwrap_or(0);
+        }
+    }
+    files
+}
+
+/// Path from a `---`/`+++` line as `patch -p1` sees it: without the first directory,
+/// such as a/ or linux-6.9/, and without any timestamp
+fn diff_path(text: &str) -> String {
+    let path = text.split('\t').next().unwrap_or(text).trim();
+    if path == "/dev/null" {
+        return path.to_string();
+    }
+    path.split_once('/')
+        .map_or(path, |(_, rest)| rest)
+        .to_string()
+}
+
+/// Preview of one selected patch; a patch that cannot be fetched reports why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatchPreview {
+    pub patch: PatchSource,
+    pub summary: Option<PatchSummary>,
+    pub error: Option<String>,
+}
+
+/// Fetches and summarises every patch, so the user can review them before applying
+pub async fn preview_patches(client: &Client, patches: &[PatchSource]) -> Vec<PatchPreview> {
+    let mut previews = Vec::new();
+    for patch in patches {
+        let (summary, error) = match patch.download(client).await {
+            Ok(text) => (Some(PatchSummary::parse(&text)), None),
+            Err(error) => (None, Some(format!("{:#}", error))),
+        };
+        previews.push(PatchPreview {
+            patch: patch.clone(),
+            summary,
+            error,
+        });
+    }
+    previews
+}
+
+fn sha256_bytes(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Every patch version ever downloaded, by content hash, in the patches cache
+/// Builds apply the pinned copy, so a changed URL cannot slip new code into a rebuild
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchStore {
+    dir: PathBuf,
+}
+
+impl PatchStore {
+    pub fn open(index: &CacheIndex) -> Result<Self> {
+        Ok(PatchStore {
+            dir: index.category_dir(CacheCategory::Patches)?,
+        })
+    }
+
+    pub fn path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(format!("{}.patch", sha256))
+    }
+
+    /// Keeps `patch` and returns its hash
+    pub fn store(&self, index: &mut CacheIndex, patch: &[u8]) -> Result<String> {
+        let sha256 = sha256_bytes(patch);
+        let path = self.path(&sha256);
+        if !path.exists() {
+            fs::write(&path, patch).with_context(|| format!("writing {}", path.display()))?;
+            index.record(&path, CacheCategory::Patches, SignatureStatus::Unsigned)?;
+        }
+        Ok(sha256)
+    }
+
+    /// The stored version with this hash, None when it was evicted or never fetched
+    pub fn load(&self, sha256: &str) -> Result<Option<String>> {
+        let path = self.path(sha256);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&path)
+            .map(Some)
+            .with_context(|| format!("reading {}", path.display()))
+    }
+}
+
+/// How the content served at a patch's URL compares with its pin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PinStatus {
+    /// Never pinned; `served` is what a pin would record
+    Unpinned {
+        served: String,
+    },
+    Matches,
+    /// Upstream changed the patch, e.g. a new BORE revision under the same URL
+    Drifted {
+        pinned: String,
+        served: String,
+        /// Unified diff from the pinned to the served patch, None when the pinned copy
+        /// is no longer cached
+        diff: Option<String>,
+    },
+    /// The patch could not be fetched or compared
+    Unavailable {
+        error: String,
+    },
+}
+
+/// Result of checking one patch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinCheck {
+    pub patch: PatchSource,
+    pub status: PinStatus,
+}
+
+/// Downloads each patch, keeps the served version and compares it with the pin
+/// A patch that cannot be checked is reported as unavailable; the others are still checked
+pub async fn check_pins(
+    client: &Client,
+    system: &dyn SystemFacade,
+    index: &mut CacheIndex,
+    patches: &[PatchSource],
+) -> Result<Vec<PinCheck>> {
+    let store = PatchStore::open(index)?;
+    let mut checks = Vec::new();
+    for patch in patches {
+        let status = match check_pin(client, system, index, &store, patch).await {
+            Ok(status) => status,
+            Err(error) => PinStatus::Unavailable {
+                error: format!("{:#}", error),
+            },
+        };
+        checks.push(PinCheck {
+            patch: patch.clone(),
+            status,
+        });
+    }
+    Ok(checks)
+}
+
+async fn check_pin(
+    client: &Client,
+    system: &dyn SystemFacade,
+    index: &mut CacheIndex,
+    store: &PatchStore,
+    patch: &PatchSource,
+) -> Result<PinStatus> {
+    let served = store.store(index, &patch.download(client).await?)?;
+    Ok(match &patch.sha256 {
+        None => PinStatus::Unpinned { served },
+        Some(pinned) if *pinned == served => PinStatus::Matches,
+        Some(pinned) => PinStatus::Drifted {
+            diff: patch_diff(system, &store.path(pinned), &store.path(&served))?,
+            pinned: pinned.clone(),
+            served,
+        },
+    })
+}
+
+/// Pinned copies of the plan's patches to apply, in order, after checking every pin
+/// The Patch stage applies these files rather than whatever the URLs serve by then
+pub async fn fetch_pinned(
+    client: &Client,
+    system: &dyn SystemFacade,
+    index: &mut CacheIndex,
+    patches: &[PatchSource],
+) -> Result<Vec<PathBuf>> {
+    let checks = check_pins(client, system, index, patches).await?;
+    ensure_pinned(&checks)?;
+    let store = PatchStore::open(index)?;
+    Ok(patches
+        .iter()
+        .filter_map(|patch| patch.sha256.as_deref())
+        .map(|sha256| store.path(sha256))
+        .collect())
+}
+
+/// `diff -u` between two stored versions
+fn patch_diff(system: &dyn SystemFacade, old: &Path, new: &Path) -> Result<Option<String>> {
+    if !old.exists() {
+        return Ok(None);
+    }
+    let (old, new) = (old.to_string_lossy(), new.to_string_lossy());
+    let output = system
+        .run("diff", &["-u", &old, &new])
+        .context("running diff")?;
+    // 1 means the files differ, anything above is trouble
+    if output.status > 1 {
+        bail!("diff failed: {}", output.stderr.trim());
+    }
+    Ok(Some(output.stdout))
+}
+
+/// Refuses to go on while a patch is unpinned or has drifted; the user re-pins after
+/// reviewing the change
+pub fn ensure_pinned(checks: &[PinCheck]) -> Result<()> {
+    let unsettled: Vec<&str> = checks
+        .iter()
+        .filter(|check| check.status != PinStatus::Matches)
+        .map(|check| check.patch.name.as_str())
+        .collect();
+    if !unsettled.is_empty() {
+        bail!(
+            "patches changed upstream, were never pinned or could not be checked, review and re-pin them: {}",
+            unsettled.join(", ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,307 @@
+// src-tauri/src/core/paths.rs
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::privilege::PrivilegedAction;
+
+const APP_DIR: &str = "kernelforge";
+
+/// Root of the shared layout in system-wide mode
+pub const SYSTEM_ROOT: &str = "/var/lib/kernelforge";
+
+/// The organization's policy in system-wide mode, owned by root and readable by everyone;
+/// the group's own settings live in the shared layout
+pub const SYSTEM_CONFIG: &str = "/etc/kernelforge";
+
+/// Members of this group share workspaces and caches in system-wide mode
+pub const SYSTEM_GROUP: &str = "kernelforge";
+
+/// setgid bit: new entries inherit the directory's group
+const SETGID: u32 = 0o2000;
+
+/// Argument creating the shared layout as root before a system-wide start
+pub const SETUP_ARG: &str = "--setup-system";
+
+/// Whether state is private to the user or shared by everyone in the group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallMode {
+    /// XDG directories in the user's home
+    #[default]
+    PerUser,
+    /// /var/lib/kernelforge, group-writable for the kernelforge group
+    SystemWide,
+}
+
+impl InstallMode {
+    /// Parses `user` or `system`, as given on the command line or in KERNELFORGE_MODE
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "user" | "per-user" => Ok(InstallMode::PerUser),
+            "system" | "system-wide" => Ok(InstallMode::SystemWide),
+            other => bail!("unknown install mode {:?}, expected user or system", other),
+        }
+    }
+
+    /// Mode from KERNELFORGE_MODE, per-user when unset
+    pub fn from_env() -> Result<Self> {
+        match env::var("KERNELFORGE_MODE") {
+            Ok(value) => InstallMode::parse(value.trim()),
+            Err(_) => Ok(InstallMode::PerUser),
+        }
+    }
+
+    /// Mode chosen when the daemon starts: `--mode <user|system>` among its arguments,
+    /// otherwise KERNELFORGE_MODE
+    pub fn at_startup(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if let Some(value) = arg.strip_prefix("--mode=") {
+                return InstallMode::parse(value);
+            }
+            if arg == "--mode" {
+                let value = args.next().context("--mode needs user or system")?;
+                return InstallMode::parse(&value);
+            }
+        }
+        InstallMode::from_env()
+    }
+}
+
+/// Where KernelForge keeps its files, following the XDG base directory layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    data: PathBuf,
+    state: PathBuf,
+    cache: PathBuf,
+    config: PathBuf,
+}
+
+impl Paths {
+    /// Resolves the per-user directories from XDG variables, falling back to the spec defaults
+    pub fn user() -> Result<Self> {
+        let home = env::var_os("HOME")
+            .map(PathBuf::from)
+            .context("HOME is not set")?;
+        let xdg = |variable: &str, fallback: &str| {
+            env::var_os(variable)
+                .map(PathBuf::from)
+                .filter(|path| path.is_absolute())
+                .unwrap_or_else(|| home.join(fallback))
+                .join(APP_DIR)
+        };
+        Ok(Paths {
+            data: xdg("XDG_DATA_HOME", ".local/share"),
+            state: xdg("XDG_STATE_HOME", ".local/state"),
+            cache: xdg("XDG_CACHE_HOME", ".cache"),
+            config: xdg("XDG_CONFIG_HOME", ".config"),
+        })
+    }
+
+    /// Shared layout under /var/lib/kernelforge; the settings are there too, so every
+    /// member of the group can save profiles, hooks, sources and keys
+    pub fn system_wide() -> Self {
+        Paths::rooted(Path::new(SYSTEM_ROOT))
+    }
+
+    /// Layout for the mode chosen at startup
+    pub fn for_mode(mode: InstallMode) -> Result<Self> {
+        match mode {
+            InstallMode::PerUser => Paths::user(),
+            InstallMode::SystemWide => Ok(Paths::system_wide()),
+        }
+    }
+
+    /// Fails when the shared layout was not set up by root, so the daemon does not start
+    /// writing into directories the group cannot use
+    pub fn check_system_layout(&self) -> Result<()> {
+        for dir in [&self.data, &self.state, &self.cache, &self.config] {
+            fs::metadata(dir).with_context(|| {
+                format!(
+                    "{} is missing, start once with --mode system {} to set it up",
+                    dir.display(),
+                    SETUP_ARG
+                )
+            })?;
+            if !Paths::is_shared(dir) {
+                bail!(
+                    "{} is not a shared directory, start once with --mode system {} to set it up",
+                    dir.display(),
+                    SETUP_ARG
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Root actions creating the shared layout: the group, setgid group-writable data,
+    /// state, cache and settings directories, and the policy directory only root can change
+    pub fn system_setup_actions(&self) -> Vec<PrivilegedAction> {
+        let run = |program: &str, args: &[&str]| PrivilegedAction::Run {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
+        let mut actions = vec![run("groupadd", &["-f", "-r", SYSTEM_GROUP])];
+        for dir in [&self.data, &self.state, &self.cache, &self.config] {
+            actions.push(run(
+                "install",
+                &[
+                    "-d",
+                    "-m",
+                    "2775",
+                    "-g",
+                    SYSTEM_GROUP,
+                    &dir.to_string_lossy(),
+                ],
+            ));
+        }
+        actions.push(run("install", &["-d", "-m", "0755", SYSTEM_CONFIG]));
+        actions
+    }
+
+    /// Places every directory under one root, used for portable setups and fixtures
+    pub fn rooted(root: &Path) -> Self {
+        Paths {
+            data: root.join("data"),
+            state: root.join("state"),
+            cache: root.join("cache"),
+            config: root.join("config"),
+        }
+    }
+
+    /// Long-lived user data: plans, workspaces, history
+    pub fn data_dir(&self) -> &Path {
+        &self.data
+    }
+
+    /// Mutable runtime state such as transaction logs
+    pub fn state_dir(&self) -> &Path {
+        &self.state
+    }
+
+    /// Re-downloadable data: tarballs, patches, metadata
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache
+    }
+
+    /// User settings
+    pub fn config_dir(&self) -> &Path {
+        &self.config
+    }
+
+    /// Install transaction logs
+    pub fn transactions_dir(&self) -> PathBuf {
+        self.state.join("transactions")
+    }
+
+    /// Persisted pipeline runs
+    pub fn pipelines_dir(&self) -> PathBuf {
+        self.state.join("pipelines")
+    }
+
+    /// A/B boot slot assignments
+    pub fn slots_path(&self) -> PathBuf {
+        self.state.join("slots.json")
+    }
+
+    /// Persisted fleet rollouts
+    pub fn fleet_dir(&self) -> PathBuf {
+        self.state.join("fleet")
+    }
+
+    /// Machines kernels can be pushed to
+    pub fn fleet_inventory_path(&self) -> PathBuf {
+        self.config.join("fleet.json")
+    }
+
+    /// Kernel source providers the user added: distro trees and forks
+    pub fn sources_path(&self) -> PathBuf {
+        self.config.join("sources.json")
+    }
+
+    /// Tuning profiles the user defined on top of the built-in ones
+    pub fn profiles_path(&self) -> PathBuf {
+        self.config.join("profiles.json")
+    }
+
+    /// User scripts run at hook points, one directory per point
+    pub fn hooks_dir(&self) -> PathBuf {
+        self.config.join("hooks")
+    }
+
+    /// Capabilities the user granted each plugin
+    pub fn plugin_grants_path(&self) -> PathBuf {
+        self.config.join("plugin-grants.json")
+    }
+
+    /// Answers given in the first-run wizard so far
+    pub fn wizard_path(&self) -> PathBuf {
+        self.state.join("wizard.json")
+    }
+
+    /// Snapshots of source trees, to spot edits made outside KernelForge
+    pub fn workspaces_dir(&self) -> PathBuf {
+        self.state.join("workspaces")
+    }
+
+    /// Build statistics history
+    pub fn stats_path(&self) -> PathBuf {
+        self.data.join("stats.json")
+    }
+
+    /// Known-issue rules downloaded since the last release
+    pub fn known_issues_path(&self) -> PathBuf {
+        self.cache.join("known-issues.json")
+    }
+
+    /// Hardware quirks downloaded since the last release
+    pub fn quirks_path(&self) -> PathBuf {
+        self.cache.join("quirks.json")
+    }
+
+    /// kernel.org release list from the last refresh
+    pub fn catalog_path(&self) -> PathBuf {
+        self.cache.join("version-catalog.json")
+    }
+
+    /// Installed plugins, one directory each holding its manifest
+    pub fn plugins_dir(&self) -> PathBuf {
+        self.data.join("plugins")
+    }
+
+    /// Benchmark, latency and boot measurements per kernel
+    pub fn results_path(&self) -> PathBuf {
+        self.data.join("results.json")
+    }
+
+    /// GnuPG home holding the keys trusted to sign sources
+    pub fn keyring_dir(&self) -> PathBuf {
+        self.config.join("keyring")
+    }
+
+    /// Whether `dir` is part of a shared tree, i.e. setgid so entries keep the group
+    pub fn is_shared(dir: &Path) -> bool {
+        fs::metadata(dir).is_ok_and(|metadata| metadata.permissions().mode() & SETGID != 0)
+    }
+
+    /// Creates `dir` if needed and returns it
+    /// Directories created inside a shared (setgid) tree are made group-writable like their parent
+    pub fn ensure(dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        let mode = fs::metadata(dir)
+            .with_context(|| format!("reading metadata of {}", dir.display()))?
+            .permissions()
+            .mode();
+        if mode & SETGID != 0 && mode & 0o070 != 0o070 {
+            fs::set_permissions(dir, fs::Permissions::from_mode((mode | 0o070) & 0o7777))
+                .with_context(|| format!("sharing {} with the group", dir.display()))?;
+        }
+        Ok(dir.to_path_buf())
+    }
+}
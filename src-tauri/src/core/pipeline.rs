@@ -0,0 +1,307 @@
+// src-tauri/src/core/pipeline.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::state_file;
+
+/// Stages of a kernel build, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PipelineStage {
+    Fetch,
+    Verify,
+    Extract,
+    Patch,
+    Configure,
+    Build,
+    Package,
+    /// Boots the built kernel in QEMU before it goes anywhere near the bootloader
+    BootTest,
+    Install,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 9] = [
+        PipelineStage::Fetch,
+        PipelineStage::Verify,
+        PipelineStage::Extract,
+        PipelineStage::Patch,
+        PipelineStage::Configure,
+        PipelineStage::Build,
+        PipelineStage::Package,
+        PipelineStage::BootTest,
+        PipelineStage::Install,
+    ];
+
+    /// Stable identifier used in logs and by the frontend
+    pub fn name(self) -> &'static str {
+        match self {
+            PipelineStage::Fetch => "fetch",
+            PipelineStage::Verify => "verify",
+            PipelineStage::Extract => "extract",
+            PipelineStage::Patch => "patch",
+            PipelineStage::Configure => "configure",
+            PipelineStage::Build => "build",
+            PipelineStage::Package => "package",
+            PipelineStage::BootTest => "boot-test",
+            PipelineStage::Install => "install",
+        }
+    }
+}
+
+/// Progress of one stage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StageStatus {
+    Pending,
+    /// Found on load when the process died while the stage ran
+    Running,
+    Completed,
+    Failed {
+        error: String,
+    },
+}
+
+/// A stage with its status and timestamps
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageRecord {
+    pub stage: PipelineStage,
+    pub status: StageStatus,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+}
+
+/// Runs the work of each stage; implemented by the application layer
+pub trait StageRunner {
+    /// Performs `stage`, recording outputs later stages need in `run.artifacts`
+    /// Stages may be re-run after a crash, so they must tolerate leftovers from a partial run
+    fn run_stage(&mut self, stage: PipelineStage, run: &mut PipelineRun) -> Result<()>;
+}
+
+/// Persistent record of one end-to-end build, rewritten after every state change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub id: String,
+    pub version: String,
+    pub started_at: u64,
+    pub stages: Vec<StageRecord>,
+    /// Outputs handed between stages, e.g. "tarball" or "source_dir"
+    pub artifacts: BTreeMap<String, PathBuf>,
+    #[serde(skip)]
+    state_path: PathBuf,
+}
+
+impl PipelineRun {
+    /// Creates a run with every stage pending and writes it
+    pub fn begin(paths: &Paths, version: &str) -> Result<Self> {
+        let started_at = unix_now();
+        let id = format!("{}-{}", started_at, version);
+        let dir = Paths::ensure(paths.pipelines_dir())?;
+        let run = PipelineRun {
+            state_path: dir.join(format!("{}.json", id)),
+            id,
+            version: version.to_string(),
+            started_at,
+            stages: PipelineStage::ALL
+                .into_iter()
+                .map(|stage| StageRecord {
+                    stage,
+                    status: StageStatus::Pending,
+                    started_at: None,
+                    finished_at: None,
+                })
+                .collect(),
+            artifacts: BTreeMap::new(),
+        };
+        run.save()?;
+        Ok(run)
+    }
+
+    /// Loads a persisted run
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut run: PipelineRun =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        run.state_path = path.to_path_buf();
+        run.add_missing_stages();
+        Ok(run)
+    }
+
+    /// Runs persisted before a stage existed get it inserted in order, counted as completed
+    /// when the stage after it already completed so finished runs stay finished
+    fn add_missing_stages(&mut self) {
+        for (index, stage) in PipelineStage::ALL.into_iter().enumerate() {
+            if self.stages.get(index).is_some_and(|record| record.stage == stage) {
+                continue;
+            }
+            let next_completed = self
+                .stages
+                .get(index)
+                .is_some_and(|record| record.status == StageStatus::Completed);
+            self.stages.insert(
+                index,
+                StageRecord {
+                    stage,
+                    status: if next_completed {
+                        StageStatus::Completed
+                    } else {
+                        StageStatus::Pending
+                    },
+                    started_at: None,
+                    finished_at: None,
+                },
+            );
+        }
+    }
+
+    /// All persisted runs, oldest first
+    pub fn list(paths: &Paths) -> Result<Vec<Self>> {
+        let dir = paths.pipelines_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut runs = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                runs.push(PipelineRun::load(&path)?);
+            }
+        }
+        runs.sort_by_key(|run| run.started_at);
+        Ok(runs)
+    }
+
+    /// Runs that stopped before finishing, shown on launch so the user can resume them
+    pub fn unfinished(paths: &Paths) -> Result<Vec<Self>> {
+        Ok(PipelineRun::list(paths)?
+            .into_iter()
+            .filter(|run| !run.is_finished())
+            .collect())
+    }
+
+    /// Whether every stage completed
+    pub fn is_finished(&self) -> bool {
+        self.stages
+            .iter()
+            .all(|record| record.status == StageStatus::Completed)
+    }
+
+    /// Stage the run stopped at: the first one not completed
+    pub fn resume_point(&self) -> Option<PipelineStage> {
+        self.stages
+            .iter()
+            .find(|record| record.status != StageStatus::Completed)
+            .map(|record| record.stage)
+    }
+
+    /// Last stage that completed
+    pub fn last_completed(&self) -> Option<PipelineStage> {
+        self.stages
+            .iter()
+            .take_while(|record| record.status == StageStatus::Completed)
+            .last()
+            .map(|record| record.stage)
+    }
+
+    /// Status of one stage
+    pub fn status(&self, stage: PipelineStage) -> &StageStatus {
+        &self.record(stage).status
+    }
+
+    /// Records an output for later stages and persists it
+    pub fn set_artifact(&mut self, name: &str, path: PathBuf) -> Result<()> {
+        self.artifacts.insert(name.to_string(), path);
+        self.save()
+    }
+
+    /// Output recorded by an earlier stage
+    pub fn artifact(&self, name: &str) -> Option<&Path> {
+        self.artifacts.get(name).map(PathBuf::as_path)
+    }
+
+    /// Runs the remaining stages from the resume point, stopping at the first failure
+    pub fn drive(&mut self, runner: &mut dyn StageRunner) -> Result<()> {
+        while let Some(stage) = self.resume_point() {
+            self.start(stage)?;
+            match runner.run_stage(stage, self) {
+                Ok(()) => self.finish(stage, StageStatus::Completed)?,
+                Err(error) => {
+                    self.finish(
+                        stage,
+                        StageStatus::Failed {
+                            error: format!("{:#}", error),
+                        },
+                    )?;
+                    return Err(error.context(format!("{} stage failed", stage.name())));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets a stage's result so it runs again, along with everything after it
+    pub fn reset_from(&mut self, stage: PipelineStage) -> Result<()> {
+        for record in self
+            .stages
+            .iter_mut()
+            .filter(|record| record.stage >= stage)
+        {
+            record.status = StageStatus::Pending;
+            record.started_at = None;
+            record.finished_at = None;
+        }
+        self.save()
+    }
+
+    /// Deletes the persisted state of a run
+    pub fn discard(self) -> Result<()> {
+        fs::remove_file(&self.state_path)
+            .with_context(|| format!("removing {}", self.state_path.display()))
+    }
+
+    fn start(&mut self, stage: PipelineStage) -> Result<()> {
+        if let Some(earlier) = self
+            .stages
+            .iter()
+            .find(|record| record.stage < stage && record.status != StageStatus::Completed)
+        {
+            bail!(
+                "cannot start {} before {} completes",
+                stage.name(),
+                earlier.stage.name()
+            );
+        }
+        let record = self.record_mut(stage);
+        record.status = StageStatus::Running;
+        record.started_at = Some(unix_now());
+        record.finished_at = None;
+        self.save()
+    }
+
+    fn finish(&mut self, stage: PipelineStage, status: StageStatus) -> Result<()> {
+        let record = self.record_mut(stage);
+        record.status = status;
+        record.finished_at = Some(unix_now());
+        self.save()
+    }
+
+    fn record(&self, stage: PipelineStage) -> &StageRecord {
+        &self.stages[stage as usize]
+    }
+
+    fn record_mut(&mut self, stage: PipelineStage) -> &mut StageRecord {
+        &mut self.stages[stage as usize]
+    }
+
+    /// Replaced atomically so a crash never leaves a torn state file
+    fn save(&self) -> Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        state_file::write(&self.state_path, text.as_bytes())
+    }
+}
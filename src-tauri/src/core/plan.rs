@@ -0,0 +1,510 @@
+// src-tauri/src/core/plan.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::boot_image::ImageFormat;
+use super::build::BuildOptions;
+use super::cflags::CompilerFlags;
+use super::cmdline::CmdlinePlan;
+use super::config::KernelConfig;
+use super::host::host_system;
+use super::memory_guard::{uses_lto, MemoryGuard};
+use super::module_policy::ModulePolicy;
+use super::patches::PatchSource;
+use super::pipeline::PipelineRun;
+use super::policy::Policy;
+use super::sbc::SbcBoard;
+use super::toolchain::CrossToolchain;
+use super::workspace::OutsideChanges;
+
+/// Architectures a plan can target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetArch {
+    X86_64,
+    Arm64,
+    Arm,
+    /// Experimental, needs the `experimental` feature to build
+    Riscv64,
+}
+
+impl TargetArch {
+    /// Architecture of the machine KernelForge runs on, None when unsupported
+    pub fn host() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "x86_64" => Some(TargetArch::X86_64),
+            "aarch64" => Some(TargetArch::Arm64),
+            "arm" => Some(TargetArch::Arm),
+            "riscv64" => Some(TargetArch::Riscv64),
+            _ => None,
+        }
+    }
+
+    /// Value of make's ARCH variable
+    pub fn make_arch(self) -> &'static str {
+        match self {
+            TargetArch::X86_64 => "x86",
+            TargetArch::Arm64 => "arm64",
+            TargetArch::Arm => "arm",
+            TargetArch::Riscv64 => "riscv",
+        }
+    }
+
+    /// Make target building just the bootable image
+    pub fn image_target(self) -> &'static str {
+        match self {
+            TargetArch::X86_64 => "bzImage",
+            TargetArch::Arm64 => "Image",
+            TargetArch::Arm => "zImage",
+            TargetArch::Riscv64 => "Image",
+        }
+    }
+
+    /// Where the image lands, relative to the source tree
+    pub fn image_path(self) -> PathBuf {
+        PathBuf::from(format!(
+            "arch/{}/boot/{}",
+            self.make_arch(),
+            self.image_target()
+        ))
+    }
+
+    /// Whether the architecture describes its boards with device trees
+    pub fn uses_device_trees(self) -> bool {
+        matches!(
+            self,
+            TargetArch::Arm64 | TargetArch::Arm | TargetArch::Riscv64
+        )
+    }
+
+    /// Whether support is still experimental and disabled in regular builds
+    pub fn is_experimental(self) -> bool {
+        self == TargetArch::Riscv64
+    }
+
+    /// Whether this build of KernelForge can target the architecture
+    pub fn is_available(self) -> bool {
+        !self.is_experimental() || cfg!(feature = "experimental")
+    }
+}
+
+/// Part of the build a plan can ask for; an empty selection means make's default target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BuildTarget {
+    /// bzImage, Image or zImage, without modules
+    KernelImage,
+    Modules,
+    /// Sanitised UAPI headers under usr/include, for building userspace against the kernel
+    HeadersInstall,
+    /// Device tree blobs, ARM and RISC-V only
+    Dtbs,
+}
+
+impl BuildTarget {
+    pub fn name(self) -> &'static str {
+        match self {
+            BuildTarget::KernelImage => "kernel-image",
+            BuildTarget::Modules => "modules",
+            BuildTarget::HeadersInstall => "headers",
+            BuildTarget::Dtbs => "dtbs",
+        }
+    }
+
+    fn make_target(self, plan: &BuildPlan) -> &'static str {
+        match self {
+            BuildTarget::KernelImage => plan.image_target(),
+            BuildTarget::Modules => "modules",
+            BuildTarget::HeadersInstall => "headers_install",
+            BuildTarget::Dtbs => "dtbs",
+        }
+    }
+}
+
+/// Files one target produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetArtifacts {
+    pub target: BuildTarget,
+    pub files: Vec<PathBuf>,
+}
+
+/// What to build: version, architecture, configuration and which parts of the tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildPlan {
+    /// Name builds of this plan are tracked under in the stats and provenance
+    pub name: String,
+    pub version: String,
+    pub arch: TargetArch,
+    pub config: KernelConfig,
+    /// Partial builds for advanced users; empty builds everything
+    #[serde(default)]
+    pub targets: Vec<BuildTarget>,
+    /// Extra KCFLAGS/KCPPFLAGS, checked against the known-flags database
+    #[serde(default)]
+    pub compiler_flags: CompilerFlags,
+    /// External patches, each pinned to the content that was reviewed
+    #[serde(default)]
+    pub patches: Vec<PatchSource>,
+    /// Single-board computer the plan is for, by `SbcBoard` id
+    #[serde(default)]
+    pub board: Option<String>,
+    /// Device tree blobs to ship, relative to arch/<arch>/boot/dts, e.g.
+    /// `rockchip/rk3588-rock-5b.dtb`; the board's blob is always included
+    #[serde(default)]
+    pub dtbs: Vec<String>,
+    /// Modules to keep from auto-loading and their parameters, installed with the kernel
+    #[serde(default)]
+    pub module_policy: ModulePolicy,
+    /// Edits found in the source tree that KernelForge did not make; a plan with them is
+    /// dirty, as the tree no longer matches what the plan describes
+    #[serde(default)]
+    pub outside_changes: Option<OutsideChanges>,
+    /// Image format the config is held to; Auto leaves it to the config
+    #[serde(default)]
+    pub image_format: ImageFormat,
+    /// Parameters the new kernel's command line needs, e.g. from hardware quirks
+    #[serde(default)]
+    pub cmdline: Vec<String>,
+}
+
+impl BuildPlan {
+    pub fn new(name: &str, version: &str, arch: TargetArch, config: KernelConfig) -> Self {
+        BuildPlan {
+            name: name.to_string(),
+            version: version.to_string(),
+            arch,
+            config,
+            targets: Vec::new(),
+            compiler_flags: CompilerFlags::default(),
+            patches: Vec::new(),
+            board: None,
+            dtbs: Vec::new(),
+            module_policy: ModulePolicy::default(),
+            outside_changes: None,
+            image_format: ImageFormat::default(),
+            cmdline: Vec::new(),
+        }
+    }
+
+    /// Adds the plan's parameters to the command line of its boot entry
+    pub fn carry_cmdline(&self, cmdline: &mut CmdlinePlan) {
+        for param in &self.cmdline {
+            cmdline.add(param);
+        }
+    }
+
+    /// Make target building the bootable image
+    pub fn image_target(&self) -> &'static str {
+        ImageFormat::image_target(self.arch, &self.config)
+    }
+
+    /// Where the image lands, relative to the source tree
+    pub fn image_path(&self) -> PathBuf {
+        PathBuf::from(format!(
+            "arch/{}/boot/{}",
+            self.arch.make_arch(),
+            self.image_target()
+        ))
+    }
+
+    /// Whether the source tree was edited outside KernelForge
+    pub fn is_dirty(&self) -> bool {
+        self.outside_changes
+            .as_ref()
+            .is_some_and(OutsideChanges::is_dirty)
+    }
+
+    /// Whether only some parts of the tree are built
+    pub fn is_partial(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// Blobs the user picked plus the board's, in selection order
+    pub fn selected_dtbs(&self) -> Vec<String> {
+        let mut selected: Vec<String> = self
+            .board
+            .as_deref()
+            .and_then(SbcBoard::get)
+            .map(|board| board.dtb.to_string())
+            .into_iter()
+            .collect();
+        for dtb in &self.dtbs {
+            if !selected.contains(dtb) {
+                selected.push(dtb.clone());
+            }
+        }
+        selected
+    }
+
+    /// Targets whose outputs are tracked: the selection, or image and modules for a full build
+    pub fn tracked_targets(&self) -> Vec<BuildTarget> {
+        if self.is_partial() {
+            let mut targets = self.targets.clone();
+            targets.sort();
+            targets.dedup();
+            return targets;
+        }
+        let mut targets = vec![BuildTarget::KernelImage, BuildTarget::Modules];
+        if self.arch.uses_device_trees() {
+            targets.push(BuildTarget::Dtbs);
+        }
+        targets
+    }
+
+    /// Checks the plan can be built as described and meets the organization's policy when
+    /// one is installed; every command handing out a plan to build goes through here
+    pub fn validate(&self) -> Result<()> {
+        self.validate_with_policy(Policy::installed(host_system().as_ref())?.as_ref())
+    }
+
+    /// [`BuildPlan::validate`] against a policy the caller already loaded
+    pub fn validate_with_policy(&self, policy: Option<&Policy>) -> Result<()> {
+        self.check_buildable()?;
+        match policy {
+            Some(policy) => policy.enforce(self),
+            None => Ok(()),
+        }
+    }
+
+    fn check_buildable(&self) -> Result<()> {
+        if !self.arch.is_available() {
+            bail!(
+                "{} support is experimental, enable the experimental feature to use it",
+                self.arch.make_arch()
+            );
+        }
+        if let Some(id) = &self.board {
+            let board = SbcBoard::get(id).with_context(|| format!("unknown board {}", id))?;
+            if board.arch != self.arch {
+                bail!(
+                    "{} is an {} board, not {}",
+                    board.name,
+                    board.arch.make_arch(),
+                    self.arch.make_arch()
+                );
+            }
+        }
+        if self.board.is_some() && ImageFormat::of(self.arch, &self.config).is_efi_only() {
+            bail!("boards boot the plain image through U-Boot, disable CONFIG_EFI_ZBOOT");
+        }
+        if !self.image_format.is_available(self.arch) {
+            bail!(
+                "{:?} images cannot be built for {}",
+                self.image_format,
+                self.arch.make_arch()
+            );
+        }
+        if self.image_format != ImageFormat::Auto
+            && ImageFormat::of(self.arch, &self.config) != self.image_format
+        {
+            bail!(
+                "the config builds a {:?} image, but the plan asks for {:?}",
+                ImageFormat::of(self.arch, &self.config),
+                self.image_format
+            );
+        }
+        if self.targets.contains(&BuildTarget::Dtbs) && !self.arch.uses_device_trees() {
+            bail!(
+                "dtbs can only be built for device-tree targets, not {}",
+                self.arch.make_arch()
+            );
+        }
+        let selected = self.selected_dtbs();
+        if !selected.is_empty() {
+            if !self.arch.uses_device_trees() {
+                bail!("{} kernels do not use device trees", self.arch.make_arch());
+            }
+            if self.is_partial() && !self.targets.contains(&BuildTarget::Dtbs) {
+                bail!("device trees are selected but the dtbs target is not built");
+            }
+            let invalid: Vec<&str> = selected
+                .iter()
+                .map(String::as_str)
+                .filter(|dtb| {
+                    !dtb.ends_with(".dtb")
+                        || dtb.starts_with('/')
+                        || dtb.split('/').any(|part| part == "..")
+                })
+                .collect();
+            if !invalid.is_empty() {
+                bail!("not device tree blob names: {}", invalid.join(", "));
+            }
+        }
+        if self.targets.contains(&BuildTarget::Modules) && !self.config.is_enabled("MODULES") {
+            bail!("a modules-only build needs CONFIG_MODULES enabled");
+        }
+        let unpinned: Vec<&str> = self
+            .patches
+            .iter()
+            .filter(|patch| patch.sha256.is_none())
+            .map(|patch| patch.name.as_str())
+            .collect();
+        if !unpinned.is_empty() {
+            bail!("patches without a pinned checksum: {}", unpinned.join(", "));
+        }
+        let blocked = self.compiler_flags.blocked();
+        if !blocked.is_empty() {
+            bail!(
+                "compiler flags known to break the kernel: {}",
+                blocked.join(" ")
+            );
+        }
+        if !self.module_policy.is_empty() && !self.config.is_enabled("MODULES") {
+            bail!("a module policy needs CONFIG_MODULES enabled");
+        }
+        self.module_policy.validate()?;
+        Ok(())
+    }
+
+    /// Points make at the selected targets, architecture, compiler and extra compiler flags
+    /// `toolchain` comes from `CrossToolchain::detect` for the plan's arch
+    pub fn apply(&self, options: &mut BuildOptions, toolchain: &CrossToolchain) {
+        options.targets = if self.is_partial() {
+            let selected = self.selected_dtbs();
+            self.tracked_targets()
+                .into_iter()
+                .flat_map(|target| match target {
+                    // Each blob is its own make target, so only the selection is compiled
+                    BuildTarget::Dtbs if !selected.is_empty() => selected.clone(),
+                    _ => vec![target.make_target(self).to_string()],
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        options
+            .env
+            .retain(|(key, _)| !matches!(key.as_str(), "ARCH" | "KCFLAGS" | "KCPPFLAGS"));
+        options
+            .env
+            .push((String::from("ARCH"), self.arch.make_arch().to_string()));
+        // ARCH alone would have the host compiler build for the target
+        toolchain.apply(options);
+        options.env.extend(self.compiler_flags.env());
+        // The LTO link is the memory peak of a build and the OOM killer's favourite victim
+        if options.memory_guard.is_none() && uses_lto(&self.config) {
+            options.memory_guard = Some(MemoryGuard::for_config(&self.config));
+        }
+    }
+
+    /// Pins `name` to `sha256` after the user reviewed its drift
+    pub fn repin(&mut self, name: &str, sha256: &str) -> Result<()> {
+        let patch = self
+            .patches
+            .iter_mut()
+            .find(|patch| patch.name == name)
+            .with_context(|| format!("the plan has no patch named {}", name))?;
+        patch.pin(sha256);
+        Ok(())
+    }
+
+    /// Outputs of each tracked target in `source_dir`, failing when a target produced nothing
+    pub fn collect_artifacts(&self, source_dir: &Path) -> Result<Vec<TargetArtifacts>> {
+        let mut collected = Vec::new();
+        for target in self.tracked_targets() {
+            let files = match target {
+                BuildTarget::KernelImage => {
+                    let image = source_dir.join(self.image_path());
+                    if image.exists() {
+                        vec![image]
+                    } else {
+                        Vec::new()
+                    }
+                }
+                BuildTarget::Modules => built_modules(source_dir)?,
+                BuildTarget::HeadersInstall => {
+                    let headers = source_dir.join("usr/include");
+                    if headers.join("linux/version.h").exists() {
+                        vec![headers]
+                    } else {
+                        Vec::new()
+                    }
+                }
+                BuildTarget::Dtbs => {
+                    let dts = source_dir.join(format!("arch/{}/boot/dts", self.arch.make_arch()));
+                    let selected = self.selected_dtbs();
+                    if selected.is_empty() {
+                        let mut blobs = Vec::new();
+                        find_files(&dts, "dtb", &mut blobs)?;
+                        blobs.sort();
+                        blobs
+                    } else {
+                        let missing: Vec<&str> = selected
+                            .iter()
+                            .filter(|dtb| !dts.join(dtb).exists())
+                            .map(String::as_str)
+                            .collect();
+                        if !missing.is_empty() {
+                            bail!(
+                                "the build produced no {}; check that the SoC is enabled in the config",
+                                missing.join(", ")
+                            );
+                        }
+                        selected.iter().map(|dtb| dts.join(dtb)).collect()
+                    }
+                }
+            };
+            if files.is_empty() {
+                bail!("the {} target produced no output", target.name());
+            }
+            collected.push(TargetArtifacts { target, files });
+        }
+        Ok(collected)
+    }
+
+    /// Records where each target's output lives so later stages can find it
+    pub fn record_artifacts(
+        &self,
+        run: &mut PipelineRun,
+        artifacts: &[TargetArtifacts],
+    ) -> Result<()> {
+        for artifact in artifacts {
+            let path = match artifact.target {
+                // Blobs sit in per-vendor directories below boot/dts
+                BuildTarget::Dtbs => artifact.files[0]
+                    .ancestors()
+                    .find(|dir| dir.ends_with("boot/dts"))
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| artifact.files[0].clone()),
+                _ => artifact.files[0].clone(),
+            };
+            run.set_artifact(artifact.target.name(), path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Module files listed in modules.order
+/// The list itself is not an output: a tree with an empty one built no modules
+fn built_modules(source_dir: &Path) -> Result<Vec<PathBuf>> {
+    let order = source_dir.join("modules.order");
+    if !order.exists() {
+        return Ok(Vec::new());
+    }
+    let text =
+        fs::read_to_string(&order).with_context(|| format!("reading {}", order.display()))?;
+    // Newer kernels list objects rather than the linked .ko files
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| source_dir.join(line.trim()).with_extension("ko"))
+        .filter(|module| module.exists())
+        .collect())
+}
+
+fn find_files(dir: &Path, extension: &str, found: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_files(&path, extension, found)?;
+        } else if path.extension().is_some_and(|ext| ext == extension) {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
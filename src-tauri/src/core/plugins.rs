@@ -0,0 +1,587 @@
+// src-tauri/src/core/plugins.rs
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::advisory::{Advisory, Severity};
+use super::config::ConfigSuggestion;
+use super::hardware::HardwareSnapshot;
+use super::patches::PatchSource;
+use super::paths::Paths;
+use super::plan::BuildPlan;
+use super::profiles::CustomProfile;
+use super::state_file;
+use super::system::{CommandSpec, SystemFacade};
+
+/// Oldest and newest plugin protocol this release speaks
+pub const PROTOCOL: ProtocolRange = ProtocolRange { min: 1, max: 1 };
+
+/// Manifest every plugin directory holds
+const MANIFEST_FILE: &str = "plugin.json";
+
+/// How long a plugin may take to answer one request
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Lines of a plugin's stderr kept to explain a failure
+const STDERR_LINES: usize = 20;
+
+/// Host paths a plugin's sandbox can read besides its own directory: programs, libraries
+/// and the files under /etc needed to resolve names and check certificates
+const SANDBOX_READ_ONLY: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib64",
+    "/etc/alternatives",
+    "/etc/ld.so.cache",
+    "/etc/localtime",
+    "/etc/nsswitch.conf",
+    "/etc/hosts",
+    "/etc/resolv.conf",
+    "/etc/ssl",
+    "/etc/pki",
+    "/etc/ca-certificates",
+];
+
+/// Variables a plugin sees; nothing of the user's session, such as SSH_AUTH_SOCK or proxy
+/// credentials, is passed on
+const SANDBOX_ENV: &[(&str, &str)] = &[
+    ("PATH", "/usr/local/bin:/usr/bin:/bin"),
+    ("HOME", "/tmp"),
+    ("LANG", "C.UTF-8"),
+];
+
+/// What a plugin can add to KernelForge; each is granted by the user separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Offers patches for a kernel version
+    PatchSources,
+    /// Offers tuning profiles
+    Profiles,
+    /// Reviews a plan against the machine; the only capability sent the hardware snapshot
+    Analyzer,
+}
+
+impl Capability {
+    /// Protocol method serving the capability
+    pub fn method(self) -> &'static str {
+        match self {
+            Capability::PatchSources => "patch-sources",
+            Capability::Profiles => "profiles",
+            Capability::Analyzer => "analyze",
+        }
+    }
+}
+
+/// Inclusive range of protocol versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl ProtocolRange {
+    pub fn contains(&self, version: u32) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+
+    /// Newest version both sides speak
+    pub fn negotiate(&self, other: &ProtocolRange) -> Option<u32> {
+        let newest = self.max.min(other.max);
+        (newest >= self.min.max(other.min)).then_some(newest)
+    }
+}
+
+/// `plugin.json`, describing a plugin before it is ever started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Must match the plugin's directory name
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    /// Program and arguments; the program is relative to the plugin's directory
+    pub command: Vec<String>,
+    pub protocol: ProtocolRange,
+    pub capabilities: Vec<Capability>,
+}
+
+impl PluginManifest {
+    fn validate(&self, dir: &Path) -> Result<()> {
+        let dir_name = dir.file_name().map(|name| name.to_string_lossy());
+        if dir_name.as_deref() != Some(self.id.as_str()) {
+            bail!("plugin id {} does not match its directory", self.id);
+        }
+        if self.command.is_empty() {
+            bail!("plugin {} declares no command", self.id);
+        }
+        if Path::new(&self.command[0]).is_absolute() || self.command[0].contains("..") {
+            bail!("plugin {} must run a program inside its directory", self.id);
+        }
+        if self.protocol.min > self.protocol.max {
+            bail!("plugin {} declares an empty protocol range", self.id);
+        }
+        if self.capabilities.is_empty() {
+            bail!("plugin {} declares no capabilities", self.id);
+        }
+        Ok(())
+    }
+}
+
+/// A plugin found in the plugins directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+    /// Declared capabilities the user allowed; the plugin is never started without one
+    pub granted: Vec<Capability>,
+    /// Protocol version to speak, None when this release and the plugin share none
+    pub protocol: Option<u32>,
+}
+
+/// A plugin directory whose manifest could not be used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenPlugin {
+    pub dir: PathBuf,
+    pub error: String,
+}
+
+/// Everything in the plugins directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDiscovery {
+    pub plugins: Vec<InstalledPlugin>,
+    pub broken: Vec<BrokenPlugin>,
+}
+
+/// Reads every plugin manifest; one broken plugin does not hide the others
+pub fn discover(paths: &Paths) -> Result<PluginDiscovery> {
+    let mut discovery = PluginDiscovery::default();
+    let dir = paths.plugins_dir();
+    if !dir.exists() {
+        return Ok(discovery);
+    }
+    let grants = PluginGrants::load(paths)?;
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    for dir in dirs {
+        match read_manifest(&dir) {
+            Ok(manifest) => {
+                let granted = grants
+                    .granted(&manifest.id)
+                    .iter()
+                    .copied()
+                    .filter(|capability| manifest.capabilities.contains(capability))
+                    .collect();
+                discovery.plugins.push(InstalledPlugin {
+                    protocol: PROTOCOL.negotiate(&manifest.protocol),
+                    manifest,
+                    dir,
+                    granted,
+                });
+            }
+            Err(error) => discovery.broken.push(BrokenPlugin {
+                dir,
+                error: format!("{:#}", error),
+            }),
+        }
+    }
+    Ok(discovery)
+}
+
+/// The installed plugin with `id`, which must have a usable manifest
+pub fn find(paths: &Paths, id: &str) -> Result<InstalledPlugin> {
+    let discovery = discover(paths)?;
+    if let Some(plugin) = discovery
+        .plugins
+        .into_iter()
+        .find(|plugin| plugin.manifest.id == id)
+    {
+        return Ok(plugin);
+    }
+    match discovery
+        .broken
+        .iter()
+        .find(|broken| broken.dir.file_name().is_some_and(|name| name == id))
+    {
+        Some(broken) => bail!("plugin {} is broken: {}", id, broken.error),
+        None => bail!("no plugin named {} is installed", id),
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<PluginManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+    manifest.validate(dir)?;
+    Ok(manifest)
+}
+
+/// Capabilities the user granted, by plugin id, kept with the settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginGrants {
+    pub grants: BTreeMap<String, Vec<Capability>>,
+}
+
+impl PluginGrants {
+    pub fn load(paths: &Paths) -> Result<Self> {
+        let path = paths.plugin_grants_path();
+        if !path.exists() {
+            return Ok(PluginGrants::default());
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.config_dir())?;
+        state_file::write(
+            &paths.plugin_grants_path(),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    pub fn granted(&self, id: &str) -> &[Capability] {
+        self.grants.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Replaces what `id` may do; an empty list disables the plugin
+    pub fn set(&mut self, id: &str, mut capabilities: Vec<Capability>) {
+        capabilities.sort();
+        capabilities.dedup();
+        if capabilities.is_empty() {
+            self.grants.remove(id);
+        } else {
+            self.grants.insert(id.to_string(), capabilities);
+        }
+    }
+}
+
+/// A finding as an analyzer plugin reports it; its text is the plugin's own, as plugin
+/// findings have no catalog entries to localize from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginFinding {
+    pub severity: Severity,
+    pub title: String,
+    pub detail: String,
+    #[serde(default)]
+    pub suggestions: Vec<ConfigSuggestion>,
+}
+
+/// One request line sent to a plugin
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+/// One reply line read back; exactly one of `result` and `error` is set
+#[derive(Debug, Deserialize)]
+struct Reply {
+    id: u64,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// The plugin's answer to the handshake
+#[derive(Debug, Deserialize)]
+struct Handshake {
+    protocol: u32,
+    capabilities: Vec<Capability>,
+}
+
+/// A running plugin, speaking one JSON object per line over its stdin and stdout
+/// The process is stopped when the session is dropped
+pub struct PluginSession {
+    id: String,
+    granted: Vec<Capability>,
+    protocol: u32,
+    child: Child,
+    stdin: ChildStdin,
+    replies: Receiver<String>,
+    stderr: Arc<Mutex<VecDeque<String>>>,
+    next_id: u64,
+}
+
+impl PluginSession {
+    /// Starts a plugin and agrees on a protocol version with it
+    /// The plugin runs in its own directory under bubblewrap, see `sandboxed`, and only learns
+    /// which capabilities it was granted
+    pub fn start(system: &dyn SystemFacade, plugin: &InstalledPlugin) -> Result<Self> {
+        let manifest = &plugin.manifest;
+        if plugin.granted.is_empty() {
+            bail!("plugin {} has not been granted any capability", manifest.id);
+        }
+        let Some(protocol) = plugin.protocol else {
+            bail!(
+                "plugin {} speaks protocol {}-{}, this release speaks {}-{}",
+                manifest.id,
+                manifest.protocol.min,
+                manifest.protocol.max,
+                PROTOCOL.min,
+                PROTOCOL.max
+            );
+        };
+        if !system.has_program("bwrap") {
+            bail!(
+                "plugin {} cannot be sandboxed; install bubblewrap (bwrap) to run plugins",
+                manifest.id
+            );
+        }
+        let spec = system.prepare_command(sandboxed(plugin));
+        let mut command = Command::new(&spec.program);
+        command
+            .args(&spec.args)
+            .envs(spec.env.iter().map(|(key, value)| (key, value)));
+        for name in &spec.env_remove {
+            command.env_remove(name);
+        }
+        if let Some(cwd) = &spec.cwd {
+            command.current_dir(cwd);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("starting plugin {}", manifest.id))?;
+        let stdin = child.stdin.take().context("plugin stdin is not piped")?;
+        let stdout = child.stdout.take().context("plugin stdout is not piped")?;
+        let stderr = child.stderr.take().context("plugin stderr is not piped")?;
+
+        // Readers run on their own threads so a silent plugin cannot block past the timeout
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let tail = Arc::new(Mutex::new(VecDeque::new()));
+        let writer = tail.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                let mut tail = writer
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                if tail.len() == STDERR_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+        });
+
+        let mut session = PluginSession {
+            id: manifest.id.clone(),
+            granted: plugin.granted.clone(),
+            protocol,
+            child,
+            stdin,
+            replies,
+            stderr: tail,
+            next_id: 0,
+        };
+        let handshake: Handshake = session.request(
+            "handshake",
+            json!({
+                "protocol": PROTOCOL,
+                "app_version": env!("CARGO_PKG_VERSION"),
+                "granted": session.granted,
+            }),
+        )?;
+        if handshake.protocol != protocol {
+            bail!(
+                "plugin {} answered with protocol {} instead of the agreed {}",
+                session.id,
+                handshake.protocol,
+                protocol
+            );
+        }
+        if let Some(missing) = session
+            .granted
+            .iter()
+            .find(|capability| !handshake.capabilities.contains(capability))
+        {
+            bail!(
+                "plugin {} no longer offers {}",
+                session.id,
+                missing.method()
+            );
+        }
+        Ok(session)
+    }
+
+    /// Protocol version agreed in the handshake
+    pub fn protocol(&self) -> u32 {
+        self.protocol
+    }
+
+    /// Patches the plugin offers for `version`
+    pub fn patch_sources(&mut self, version: &str) -> Result<Vec<PatchSource>> {
+        self.call(Capability::PatchSources, json!({ "version": version }))
+    }
+
+    /// Profiles the plugin offers, to be imported into the user's profiles
+    pub fn profiles(&mut self) -> Result<Vec<CustomProfile>> {
+        self.call(Capability::Profiles, json!({}))
+    }
+
+    /// The plugin's findings for a plan on this machine
+    pub fn analyze(
+        &mut self,
+        plan: &BuildPlan,
+        hardware: &HardwareSnapshot,
+    ) -> Result<Vec<Advisory>> {
+        let findings: Vec<PluginFinding> = self.call(
+            Capability::Analyzer,
+            json!({ "plan": plan, "hardware": hardware }),
+        )?;
+        Ok(findings
+            .into_iter()
+            .map(|finding| {
+                let mut advisory = Advisory::new(
+                    finding.severity,
+                    "plugin-finding",
+                    &[
+                        ("plugin", self.id.clone()),
+                        ("title", finding.title),
+                        ("detail", finding.detail),
+                    ],
+                );
+                advisory.suggestions = finding.suggestions;
+                advisory
+            })
+            .collect())
+    }
+
+    fn call<T: DeserializeOwned>(&mut self, capability: Capability, params: Value) -> Result<T> {
+        if !self.granted.contains(&capability) {
+            bail!("plugin {} was not granted {}", self.id, capability.method());
+        }
+        self.request(capability.method(), params)
+    }
+
+    fn request<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> Result<T> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut line = serde_json::to_string(&Request { id, method, params })?;
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .with_context(|| format!("writing to plugin {}{}", self.id, self.stderr_tail()))?;
+        let text = match self.replies.recv_timeout(REPLY_TIMEOUT) {
+            Ok(text) => text,
+            Err(RecvTimeoutError::Timeout) => bail!(
+                "plugin {} did not answer {} within {}s",
+                self.id,
+                method,
+                REPLY_TIMEOUT.as_secs()
+            ),
+            Err(RecvTimeoutError::Disconnected) => {
+                bail!("plugin {} exited{}", self.id, self.stderr_tail())
+            }
+        };
+        let reply: Reply = serde_json::from_str(&text)
+            .with_context(|| format!("plugin {} sent an invalid reply", self.id))?;
+        if reply.id != id {
+            bail!(
+                "plugin {} answered request {} while {} was pending",
+                self.id,
+                reply.id,
+                id
+            );
+        }
+        if let Some(error) = reply.error {
+            bail!("plugin {} failed {}: {}", self.id, method, error);
+        }
+        serde_json::from_value(reply.result.unwrap_or(Value::Null))
+            .with_context(|| format!("plugin {} sent an invalid {} result", self.id, method))
+    }
+
+    fn stderr_tail(&self) -> String {
+        let tail = self
+            .stderr
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if tail.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", tail.iter().cloned().collect::<Vec<_>>().join("\n"))
+        }
+    }
+}
+
+impl Drop for PluginSession {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Command running the plugin under bubblewrap with no home directory, /sys or session
+/// environment, and a private /tmp; only a patch source keeps the network to list what it
+/// offers, the others work on what KernelForge sends them
+fn sandboxed(plugin: &InstalledPlugin) -> CommandSpec {
+    let dir = plugin.dir.to_string_lossy().to_string();
+    let mut args: Vec<String> = [
+        "--unshare-all",
+        "--die-with-parent",
+        "--new-session",
+        "--clearenv",
+        "--proc",
+        "/proc",
+        "--dev",
+        "/dev",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    if plugin.granted.contains(&Capability::PatchSources) {
+        args.push(String::from("--share-net"));
+    }
+    for path in SANDBOX_READ_ONLY {
+        args.extend(["--ro-bind-try", path, path].map(str::to_string));
+    }
+    for (name, value) in SANDBOX_ENV {
+        args.extend(["--setenv", name, value].map(str::to_string));
+    }
+    args.extend(["--ro-bind", &dir, &dir, "--chdir", &dir].map(str::to_string));
+    args.push(
+        plugin
+            .dir
+            .join(&plugin.manifest.command[0])
+            .to_string_lossy()
+            .to_string(),
+    );
+    args.extend(plugin.manifest.command[1..].iter().cloned());
+    CommandSpec {
+        program: String::from("bwrap"),
+        args,
+        ..CommandSpec::default()
+    }
+}
@@ -0,0 +1,260 @@
+// src-tauri/src/core/policy.rs
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigValue, KernelConfig};
+use super::paths::SYSTEM_CONFIG;
+use super::plan::BuildPlan;
+use super::resolver::ConfigLayer;
+use super::symbols::bare_name;
+use super::system::SystemFacade;
+
+/// Policy an administrator installed, applying to every user of the machine whatever the
+/// install mode
+pub const POLICY_FILE: &str = "policy.json";
+
+/// Detached signature of the policy
+pub const SIGNATURE_FILE: &str = "policy.json.sig";
+
+/// Where the package ships the keys allowed to sign the policy, apart from
+/// /etc/kernelforge so whoever can replace the policy cannot also replace its signers
+pub const TRUST_DIR: &str = "/usr/lib/kernelforge";
+
+/// Binary keyring (`gpg --export`) of the keys allowed to sign the policy
+pub const SIGNERS_FILE: &str = "policy-signers.gpg";
+
+/// Fingerprints of the signing keys, one per line; a key in the keyring that is not listed
+/// here cannot sign the policy
+pub const FINGERPRINTS_FILE: &str = "policy-signers.txt";
+
+/// Comma-separated fingerprints set when building KernelForge, replacing
+/// [`FINGERPRINTS_FILE`] so the pin cannot be changed on the machine at all
+const BUILT_IN_SIGNERS: Option<&str> = option_env!("KERNELFORGE_POLICY_SIGNERS");
+
+/// What a rule requires of one option
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Requirement {
+    /// Built in or a module, e.g. an LSM that may not be turned off
+    Enabled,
+    /// Not set, e.g. /dev/mem access
+    Disabled,
+    /// Exactly this raw value, e.g. `integrity` for the forced lockdown mode
+    Equals { value: String },
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Enabled => f.write_str("enabled"),
+            Requirement::Disabled => f.write_str("disabled"),
+            Requirement::Equals { value } => write!(f, "set to {}", value),
+        }
+    }
+}
+
+/// One option the organization forces or forbids
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub symbol: String,
+    pub requirement: Requirement,
+    /// Shown with a violation so users know why they cannot change it
+    pub reason: String,
+}
+
+impl PolicyRule {
+    fn is_met(&self, config: &KernelConfig) -> bool {
+        let value = config.get(&self.symbol);
+        match &self.requirement {
+            Requirement::Enabled => value.is_some_and(ConfigValue::is_enabled),
+            Requirement::Disabled => !value.is_some_and(ConfigValue::is_enabled),
+            Requirement::Equals { value: wanted } => {
+                value.and_then(ConfigValue::as_str) == Some(wanted.as_str())
+            }
+        }
+    }
+}
+
+/// A plan option breaking a policy rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub rule: PolicyRule,
+    /// The plan's raw value, None when not set
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CONFIG_{} must be {} but is {} ({})",
+            bare_name(&self.rule.symbol),
+            self.rule.requirement,
+            self.actual.as_deref().unwrap_or("not set"),
+            self.rule.reason
+        )
+    }
+}
+
+/// Options an organization forces or forbids on the kernels its machines build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Policy {
+    pub organization: String,
+    /// Raised on every change, shown so users can tell which policy they are under
+    pub revision: u64,
+    pub rules: Vec<PolicyRule>,
+}
+
+impl Policy {
+    /// The policy installed in /etc/kernelforge, None when there is none
+    pub fn installed(system: &dyn SystemFacade) -> Result<Option<Self>> {
+        Policy::load(system, Path::new(SYSTEM_CONFIG), Path::new(TRUST_DIR))
+    }
+
+    /// Reads the policy in `dir` after checking it was signed by one of the pinned keys
+    /// in `trust_dir`
+    /// A policy that is present but unsigned or tampered with is an error, never skipped,
+    /// so removing the signature cannot lift the policy
+    pub fn load(system: &dyn SystemFacade, dir: &Path, trust_dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(POLICY_FILE);
+        if !system.exists(&path) {
+            return Ok(None);
+        }
+        let signature = dir.join(SIGNATURE_FILE);
+        let signers = trust_dir.join(SIGNERS_FILE);
+        if !system.exists(&signature) {
+            bail!(
+                "{} is not signed, ask the administrator to install {}",
+                path.display(),
+                SIGNATURE_FILE
+            );
+        }
+        if !system.exists(&signers) {
+            bail!(
+                "no policy signers are installed in {}, ask the administrator to install {}",
+                trust_dir.display(),
+                SIGNERS_FILE
+            );
+        }
+        let pinned = pinned_signers(system, trust_dir)?;
+        // gpgv trusts exactly the keys in the given keyring and nothing from any GnuPG home
+        let output = system
+            .run(
+                "gpgv",
+                &[
+                    "--status-fd",
+                    "1",
+                    "--keyring",
+                    &signers.to_string_lossy(),
+                    &signature.to_string_lossy(),
+                    &path.to_string_lossy(),
+                ],
+            )
+            .context("running gpgv")?;
+        // VALIDSIG <fpr> <date> <ts> <expire> <ver> <res> <pk> <hash> <class> <primary fpr>
+        let valid = output
+            .stdout
+            .lines()
+            .filter_map(|line| line.strip_prefix("[GNUPG:] VALIDSIG "))
+            .any(|rest| {
+                // The signing subkey or its primary key may be the pinned one
+                rest.split_whitespace()
+                    .enumerate()
+                    .filter(|(field, _)| *field == 0 || *field == 9)
+                    .any(|(_, fingerprint)| pinned.contains(&fingerprint.to_ascii_uppercase()))
+            });
+        if !output.success() || !valid {
+            bail!(
+                "the signature of {} does not verify: {}",
+                path.display(),
+                output.stderr.trim()
+            );
+        }
+        let text = system
+            .read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let policy =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(Some(policy))
+    }
+
+    /// Rules the plan's config breaks
+    pub fn violations(&self, plan: &BuildPlan) -> Vec<PolicyViolation> {
+        self.rules
+            .iter()
+            .filter(|rule| !rule.is_met(&plan.config))
+            .map(|rule| PolicyViolation {
+                rule: rule.clone(),
+                actual: plan
+                    .config
+                    .get(&rule.symbol)
+                    .and_then(ConfigValue::as_str)
+                    .map(str::to_string),
+            })
+            .collect()
+    }
+
+    /// Fails with every violation when the plan breaks any rule
+    pub fn enforce(&self, plan: &BuildPlan) -> Result<()> {
+        let violations = self.violations(plan);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let list: Vec<String> = violations
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        bail!(
+            "the plan violates the policy of {} (revision {}): {}",
+            self.organization,
+            self.revision,
+            list.join("; ")
+        );
+    }
+
+    /// Layer meeting every rule, applied last when KernelForge generates a config itself
+    pub fn layer(&self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("policy");
+        for rule in &self.rules {
+            let value = match &rule.requirement {
+                Requirement::Enabled => ConfigValue::yes(),
+                Requirement::Disabled => ConfigValue::NotSet,
+                Requirement::Equals { value } => ConfigValue::Set(value.clone()),
+            };
+            layer.set(&format!("CONFIG_{}", bare_name(&rule.symbol)), value);
+        }
+        layer
+    }
+}
+
+/// Fingerprints allowed to sign the policy, upper case without spaces
+fn pinned_signers(system: &dyn SystemFacade, trust_dir: &Path) -> Result<Vec<String>> {
+    let text = match BUILT_IN_SIGNERS {
+        Some(list) => list.replace(',', "\n"),
+        None => {
+            let path = trust_dir.join(FINGERPRINTS_FILE);
+            system
+                .read_to_string(&path)
+                .with_context(|| format!("reading {}", path.display()))?
+        }
+    };
+    let pinned: Vec<String> = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .map(|line| {
+            line.chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+                .to_ascii_uppercase()
+        })
+        .filter(|fingerprint| !fingerprint.is_empty())
+        .collect();
+    if pinned.is_empty() {
+        bail!("no policy signer fingerprints are pinned");
+    }
+    Ok(pinned)
+}
@@ -0,0 +1,142 @@
+// src-tauri/src/core/popularity.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::KernelConfig;
+use super::i18n::Message;
+
+/// Counts shipped with this release
+const BUNDLED_DATASET: &str = include_str!("../../data/distro-options.json");
+
+/// How many of the surveyed distributions enable an option
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionPopularity {
+    pub enabled_by: u32,
+    pub distros: u32,
+    /// e.g. "3 of 4 major distros enable this"
+    pub hint: Message,
+}
+
+/// One distribution kernel whose config is surveyed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SurveyedKernel {
+    /// Short name the config is passed under to the generator, e.g. `fedora`
+    pub id: String,
+    pub name: String,
+    /// Package shipping the config, and where it installs it
+    pub package: String,
+    pub config: String,
+}
+
+/// Inputs of the dataset: the kernel series, the distributions surveyed and the options
+/// counted; `examples/distro_options.rs` turns it and the configs into the dataset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PopularitySurvey {
+    pub kernel: String,
+    pub kernels: Vec<SurveyedKernel>,
+    /// Bare symbol names
+    pub symbols: Vec<String>,
+}
+
+impl PopularitySurvey {
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(text).context("parsing the popularity survey")
+    }
+
+    /// Counts the surveyed options across `configs`, given by surveyed kernel id
+    pub fn generate(&self, configs: &BTreeMap<String, KernelConfig>) -> Result<PopularityDataset> {
+        let mut surveyed = Vec::new();
+        for kernel in &self.kernels {
+            let config = configs
+                .get(&kernel.id)
+                .with_context(|| format!("no config given for {}", kernel.id))?;
+            surveyed.push(config.clone());
+        }
+        if let Some(unknown) = configs
+            .keys()
+            .find(|id| !self.kernels.iter().any(|kernel| &kernel.id == *id))
+        {
+            bail!("{} is not in the survey", unknown);
+        }
+        let mut dataset = PopularityDataset::from_configs(&self.kernel, &surveyed);
+        dataset
+            .enabled
+            .retain(|symbol, _| self.symbols.contains(symbol));
+        dataset.sources = self
+            .kernels
+            .iter()
+            .map(|kernel| kernel.name.clone())
+            .collect();
+        Ok(dataset)
+    }
+}
+
+/// Per-option counts of how many major distributions enable it, without saying which
+/// Generated offline from the distributions' shipped configs with `PopularitySurvey::generate`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PopularityDataset {
+    /// Kernel series the configs were taken from
+    pub kernel: String,
+    pub distros: u32,
+    /// Distributions surveyed, e.g. `Fedora`
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Bare symbol names; 0 for options every distro has but none enables
+    pub enabled: BTreeMap<String, u32>,
+}
+
+impl PopularityDataset {
+    /// Dataset compiled into this release
+    pub fn bundled() -> Self {
+        PopularityDataset::parse(BUNDLED_DATASET).expect("bundled popularity dataset is valid")
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(text).context("parsing the popularity dataset")
+    }
+
+    /// Counts options across distro configs; an option missing from a config counts as
+    /// disabled there
+    pub fn from_configs(kernel: &str, configs: &[KernelConfig]) -> Self {
+        let mut enabled: BTreeMap<String, u32> = BTreeMap::new();
+        for config in configs {
+            for (symbol, value) in config.iter() {
+                let count = enabled.entry(symbol.to_string()).or_default();
+                if value.is_enabled() {
+                    *count += 1;
+                }
+            }
+        }
+        PopularityDataset {
+            kernel: kernel.to_string(),
+            distros: configs.len() as u32,
+            sources: Vec::new(),
+            enabled,
+        }
+    }
+
+    /// Popularity of `symbol`, None when the surveyed configs do not know it
+    pub fn get(&self, symbol: &str) -> Option<OptionPopularity> {
+        let symbol = symbol.strip_prefix("CONFIG_").unwrap_or(symbol);
+        let enabled_by = *self.enabled.get(symbol)?;
+        let key = match enabled_by {
+            0 => "popularity-none",
+            count if count >= self.distros => "popularity-all",
+            _ => "popularity-some",
+        };
+        Some(OptionPopularity {
+            enabled_by,
+            distros: self.distros,
+            hint: Message::new(
+                key,
+                &[
+                    ("enabled", enabled_by.to_string()),
+                    ("distros", self.distros.to_string()),
+                ],
+            ),
+        })
+    }
+}
@@ -0,0 +1,148 @@
+// src-tauri/src/core/power.rs
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::system::SystemFacade;
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+const HWMON: &str = "/sys/class/hwmon";
+
+/// hwmon drivers that report package or core temperatures
+const CPU_SENSORS: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal", "acpitz"];
+
+/// Degrees below the threshold a hot machine has to cool to before work resumes
+const TEMP_HYSTERESIS_C: f32 = 5.0;
+
+/// Snapshot of power source and temperature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerState {
+    pub has_battery: bool,
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    pub cpu_temp_c: Option<f32>,
+}
+
+impl PowerState {
+    /// Reads /sys/class/power_supply and the CPU hwmon sensors
+    pub fn read(system: &dyn SystemFacade) -> Self {
+        let mut has_battery = false;
+        let mut mains_online = None;
+        let mut discharging = false;
+        let mut battery_percent = None;
+
+        for supply in system.read_dir(Path::new(POWER_SUPPLY)).unwrap_or_default() {
+            match system.read_trimmed(&supply.join("type")).as_deref() {
+                Some("Mains") => {
+                    let online = system.read_trimmed(&supply.join("online")).as_deref() == Some("1");
+                    mains_online = Some(mains_online.unwrap_or(false) || online);
+                }
+                Some("Battery") => {
+                    has_battery = true;
+                    discharging |= system.read_trimmed(&supply.join("status")).as_deref() == Some("Discharging");
+                    battery_percent = system
+                        .read_trimmed(&supply.join("capacity"))
+                        .and_then(|value| value.parse().ok());
+                }
+                _ => {}
+            }
+        }
+
+        PowerState {
+            has_battery,
+            on_battery: has_battery && (mains_online == Some(false) || discharging),
+            battery_percent,
+            cpu_temp_c: read_cpu_temperature(system),
+        }
+    }
+}
+
+/// Hottest CPU sensor in degrees Celsius
+pub fn read_cpu_temperature(system: &dyn SystemFacade) -> Option<f32> {
+    let mut hottest: Option<f32> = None;
+    for hwmon in system.read_dir(Path::new(HWMON)).unwrap_or_default() {
+        let name = system.read_trimmed(&hwmon.join("name")).unwrap_or_default();
+        if !CPU_SENSORS.contains(&name.as_str()) {
+            continue;
+        }
+        for entry in system.read_dir(&hwmon).unwrap_or_default() {
+            let is_input = entry
+                .file_name()
+                .and_then(|file| file.to_str())
+                .is_some_and(|file| file.starts_with("temp") && file.ends_with("_input"));
+            if !is_input {
+                continue;
+            }
+            if let Some(millidegrees) = system.read_trimmed(&entry).and_then(|value| value.parse::<f32>().ok()) {
+                let celsius = millidegrees / 1000.0;
+                hottest = Some(hottest.map_or(celsius, |current| current.max(celsius)));
+            }
+        }
+    }
+    hottest
+}
+
+/// What the build should do while running on battery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatteryAction {
+    Ignore,
+    ReduceTo(usize),
+    Pause,
+}
+
+/// Opt-in throttling rules for laptops
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThrottlePolicy {
+    pub on_battery: BatteryAction,
+    /// Pause the build above this CPU temperature
+    pub max_temp_c: Option<f32>,
+    pub poll_interval: Duration,
+}
+
+impl Default for ThrottlePolicy {
+    fn default() -> Self {
+        ThrottlePolicy {
+            on_battery: BatteryAction::ReduceTo(2),
+            max_temp_c: Some(95.0),
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Parallelism the build executor should run with right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThrottleDecision {
+    Full,
+    Reduced(usize),
+    Paused,
+}
+
+impl ThrottlePolicy {
+    /// Decides how the build should run, `previous` provides hysteresis for the temperature rule
+    pub fn decide(&self, state: &PowerState, previous: ThrottleDecision) -> ThrottleDecision {
+        if let (Some(limit), Some(temp)) = (self.max_temp_c, state.cpu_temp_c) {
+            let still_hot = previous == ThrottleDecision::Paused && temp > limit - TEMP_HYSTERESIS_C;
+            if temp >= limit || still_hot {
+                return ThrottleDecision::Paused;
+            }
+        }
+        if !state.on_battery {
+            return ThrottleDecision::Full;
+        }
+        match self.on_battery {
+            BatteryAction::Ignore => ThrottleDecision::Full,
+            BatteryAction::ReduceTo(jobs) => ThrottleDecision::Reduced(jobs.max(1)),
+            BatteryAction::Pause => ThrottleDecision::Paused,
+        }
+    }
+
+    /// Job count for a decision given the unthrottled job count
+    pub fn jobs_for(decision: ThrottleDecision, full_jobs: usize) -> usize {
+        match decision {
+            ThrottleDecision::Full | ThrottleDecision::Paused => full_jobs,
+            ThrottleDecision::Reduced(jobs) => jobs.min(full_jobs),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// src-tauri/src/core/prebuilt.rs
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::KernelConfig;
+use super::cpu::CpuInfo;
+use super::generator::KernelProfile;
+
+/// CPU scheduler a kernel ships with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scheduler {
+    /// Mainline's default since 6.6
+    Eevdf,
+    Bore,
+    /// Project C schedulers
+    Pds,
+    Bmq,
+}
+
+/// Preemption model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Preemption {
+    None,
+    Voluntary,
+    Full,
+    Rt,
+}
+
+/// Clang link-time optimisation mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Lto {
+    None,
+    Thin,
+    Full,
+}
+
+/// The settings that set gaming kernels apart, as one comparable row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelFeatures {
+    pub scheduler: Scheduler,
+    pub hz: u32,
+    pub preemption: Preemption,
+    pub lto: Lto,
+    /// CPU vulnerability mitigations compiled in
+    pub mitigations: bool,
+    /// x86-64 psABI level the kernel is compiled for, 1 for generic builds
+    pub x86_64_level: u8,
+}
+
+impl KernelFeatures {
+    /// Reads the feature row off a planned or installed config
+    pub fn of(config: &KernelConfig) -> Self {
+        let scheduler = if config.is_enabled("SCHED_BORE") {
+            Scheduler::Bore
+        } else if config.is_enabled("SCHED_PDS") {
+            Scheduler::Pds
+        } else if config.is_enabled("SCHED_BMQ") {
+            Scheduler::Bmq
+        } else {
+            Scheduler::Eevdf
+        };
+        let preemption = if config.is_enabled("PREEMPT_RT") {
+            Preemption::Rt
+        } else if config.is_enabled("PREEMPT") {
+            Preemption::Full
+        } else if config.is_enabled("PREEMPT_VOLUNTARY") {
+            Preemption::Voluntary
+        } else {
+            Preemption::None
+        };
+        let lto = if config.is_enabled("LTO_CLANG_FULL") {
+            Lto::Full
+        } else if config.is_enabled("LTO_CLANG_THIN") {
+            Lto::Thin
+        } else {
+            Lto::None
+        };
+        let raw = |name: &str| config.get(name).and_then(|value| value.as_str());
+        KernelFeatures {
+            scheduler,
+            hz: raw("HZ").and_then(|hz| hz.parse().ok()).unwrap_or(250),
+            preemption,
+            lto,
+            // Renamed from SPECULATION_MITIGATIONS in 6.9; either being set means they are built
+            mitigations: config.is_enabled("CPU_MITIGATIONS")
+                || config.is_enabled("SPECULATION_MITIGATIONS")
+                || (config.get("CPU_MITIGATIONS").is_none()
+                    && config.get("SPECULATION_MITIGATIONS").is_none()),
+            x86_64_level: raw("X86_64_VERSION")
+                .and_then(|level| level.parse().ok())
+                .unwrap_or(1),
+        }
+    }
+
+    /// Matrix rows where the two kernels disagree
+    pub fn differences(&self, other: &KernelFeatures) -> Vec<FeatureDifference> {
+        let mut differences = Vec::new();
+        let mut compare = |feature: &str, ours: String, theirs: String| {
+            if ours != theirs {
+                differences.push(FeatureDifference {
+                    feature: feature.to_string(),
+                    ours,
+                    theirs,
+                });
+            }
+        };
+        compare(
+            "scheduler",
+            format!("{:?}", self.scheduler),
+            format!("{:?}", other.scheduler),
+        );
+        compare("hz", self.hz.to_string(), other.hz.to_string());
+        compare(
+            "preemption",
+            format!("{:?}", self.preemption),
+            format!("{:?}", other.preemption),
+        );
+        compare("lto", format!("{:?}", self.lto), format!("{:?}", other.lto));
+        compare(
+            "mitigations",
+            self.mitigations.to_string(),
+            other.mitigations.to_string(),
+        );
+        compare(
+            "x86_64_level",
+            self.x86_64_level.to_string(),
+            other.x86_64_level.to_string(),
+        );
+        differences
+    }
+}
+
+/// One row of the feature matrix where the plan and a prebuilt kernel disagree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureDifference {
+    pub feature: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// A kernel that can be downloaded ready-built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PrebuiltKernel {
+    pub name: &'static str,
+    pub url: &'static str,
+    pub features: KernelFeatures,
+    /// Profile the kernel is tuned for
+    pub profile: KernelProfile,
+}
+
+/// Default flavour of each project as published, maintained by hand
+const PREBUILT: &[PrebuiltKernel] = &[
+    PrebuiltKernel {
+        name: "XanMod (x64v3)",
+        url: "https://xanmod.org",
+        features: KernelFeatures {
+            scheduler: Scheduler::Eevdf,
+            hz: 500,
+            preemption: Preemption::Full,
+            lto: Lto::Thin,
+            mitigations: true,
+            x86_64_level: 3,
+        },
+        profile: KernelProfile::Gaming,
+    },
+    PrebuiltKernel {
+        name: "Liquorix",
+        url: "https://liquorix.net",
+        features: KernelFeatures {
+            scheduler: Scheduler::Pds,
+            hz: 1000,
+            preemption: Preemption::Full,
+            lto: Lto::None,
+            mitigations: true,
+            x86_64_level: 1,
+        },
+        profile: KernelProfile::Gaming,
+    },
+    PrebuiltKernel {
+        name: "linux-zen",
+        url: "https://github.com/zen-kernel/zen-kernel",
+        features: KernelFeatures {
+            scheduler: Scheduler::Eevdf,
+            hz: 1000,
+            preemption: Preemption::Full,
+            lto: Lto::None,
+            mitigations: true,
+            x86_64_level: 1,
+        },
+        profile: KernelProfile::Desktop,
+    },
+    PrebuiltKernel {
+        name: "CachyOS (x86-64-v3)",
+        url: "https://cachyos.org",
+        features: KernelFeatures {
+            scheduler: Scheduler::Bore,
+            hz: 1000,
+            preemption: Preemption::Full,
+            lto: Lto::Thin,
+            mitigations: true,
+            x86_64_level: 3,
+        },
+        profile: KernelProfile::Gaming,
+    },
+];
+
+/// How one prebuilt kernel measures up against the plan on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrebuiltComparison {
+    pub kernel: PrebuiltKernel,
+    pub differences: Vec<FeatureDifference>,
+    /// Whether the CPU supports the psABI level the kernel is compiled for
+    pub runs_here: bool,
+}
+
+/// Feature matrix of the plan against every known prebuilt kernel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildOrDownload {
+    pub planned: KernelFeatures,
+    pub comparisons: Vec<PrebuiltComparison>,
+    pub advisories: Vec<Advisory>,
+}
+
+/// Compares what the plan would build for `profile` with the prebuilt kernels that run on
+/// `cpu`, so the user can decide whether building is worth it
+pub fn compare_prebuilt(
+    config: &KernelConfig,
+    profile: KernelProfile,
+    cpu: &CpuInfo,
+) -> BuildOrDownload {
+    let planned = KernelFeatures::of(config);
+    let level = cpu.x86_64_level();
+    let comparisons: Vec<PrebuiltComparison> = PREBUILT
+        .iter()
+        .map(|kernel| PrebuiltComparison {
+            kernel: *kernel,
+            differences: planned.differences(&kernel.features),
+            runs_here: level >= kernel.features.x86_64_level,
+        })
+        .collect();
+
+    let mut advisories = Vec::new();
+    let candidates = comparisons
+        .iter()
+        .filter(|comparison| comparison.runs_here && comparison.kernel.profile == profile);
+    for comparison in candidates {
+        let name = comparison.kernel.name.to_string();
+        if comparison.differences.is_empty() {
+            advisories.push(Advisory::new(
+                Severity::Info,
+                "prebuilt-equivalent",
+                &[("kernel", name)],
+            ));
+        } else if comparison.differences.len() <= 2 {
+            let features = comparison
+                .differences
+                .iter()
+                .map(|difference| difference.feature.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            advisories.push(Advisory::new(
+                Severity::Info,
+                "prebuilt-close",
+                &[("kernel", name), ("features", features)],
+            ));
+        }
+    }
+    if level > 0 && planned.x86_64_level > level {
+        advisories.push(Advisory::new(
+            Severity::Blocker,
+            "prebuilt-level-unsupported",
+            &[
+                ("planned", planned.x86_64_level.to_string()),
+                ("supported", level.to_string()),
+            ],
+        ));
+    }
+
+    BuildOrDownload {
+        planned,
+        comparisons,
+        advisories,
+    }
+}
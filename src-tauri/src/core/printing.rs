@@ -0,0 +1,148 @@
+// src-tauri/src/core/printing.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue};
+use super::hardware::Device;
+use super::requirements::MachineRequirements;
+use super::system::SystemFacade;
+
+/// USB interface class of printers, `ic07` in an interface modalias
+const PRINTER_CLASS: &str = "ic07";
+
+/// USB interface class of still image devices, the class scanners that are not vendor
+/// specific use
+const STILL_IMAGE_CLASS: &str = "ic06";
+
+/// Saved CUPS queues, read when `lpstat` cannot run; only root can read them
+const CUPS_PRINTERS: &str = "/etc/cups/printers.conf";
+
+/// Printers, scanners and the print and scan services of the machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrintingSetup {
+    /// USB printer interfaces by sysfs name, e.g. `1-2:1.0`
+    pub printers: Vec<String>,
+    pub scanners: Vec<String>,
+    /// Device URIs of CUPS queues on USB printers, e.g. `usb://Brother/HL-L2350DW`;
+    /// they print again once the printer is plugged back in
+    #[serde(default)]
+    pub usb_queues: Vec<String>,
+    /// SANE is installed
+    pub sane: bool,
+}
+
+impl PrintingSetup {
+    /// Detects USB printers and scanners among `devices` along with CUPS queues on USB
+    /// printers and SANE, None when the machine neither prints nor scans
+    /// CUPS alone does not count: it comes with most desktops whether or not they print
+    pub fn detect(system: &dyn SystemFacade, devices: &[Device]) -> Option<Self> {
+        let interfaces = |class: &str| -> Vec<String> {
+            devices
+                .iter()
+                .filter(|device| device.bus == "usb")
+                .filter(|device| {
+                    device
+                        .modalias
+                        .as_deref()
+                        .is_some_and(|modalias| modalias.contains(class))
+                })
+                .map(|device| device.name.clone())
+                .collect()
+        };
+        let setup = PrintingSetup {
+            printers: interfaces(PRINTER_CLASS),
+            scanners: interfaces(STILL_IMAGE_CLASS),
+            usb_queues: usb_queues(system),
+            sane: system.has_program("scanimage") || system.exists(Path::new("/etc/sane.d")),
+        };
+        if setup.printers.is_empty()
+            && setup.scanners.is_empty()
+            && setup.usb_queues.is_empty()
+            && !setup.sane
+        {
+            return None;
+        }
+        Some(setup)
+    }
+
+    fn prints(&self) -> bool {
+        !self.usb_queues.is_empty() || !self.printers.is_empty()
+    }
+
+    fn scans(&self) -> bool {
+        self.sane || !self.scanners.is_empty()
+    }
+}
+
+impl MachineRequirements for PrintingSetup {
+    fn name(&self) -> &'static str {
+        "printing"
+    }
+
+    /// Options printing and scanning rely on, as modules loaded when a printer or scanner
+    /// is plugged in
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let mut required = Vec::new();
+        if self.prints() {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_USB_PRINTER",
+                ConfigValue::module(),
+                "usblp, the /dev/usb/lp* devices CUPS prints to USB printers through",
+            ));
+            required.push(ConfigSuggestion::new(
+                "CONFIG_USB_STORAGE",
+                ConfigValue::module(),
+                "multifunction printers show their card reader and driver disk as USB storage",
+            ));
+        }
+        if self.scans() {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_CHR_DEV_SG",
+                ConfigValue::module(),
+                "SANE reaches SCSI and some USB scanners through the generic SCSI driver",
+            ));
+        }
+        required
+    }
+
+    /// Warns when the plan leaves out what the machine prints or scans with
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        let mut advisory = Advisory::new(
+            Severity::Warning,
+            "printing-missing-config",
+            &[("symbols", symbols.join(", "))],
+        );
+        for requirement in missing {
+            advisory = advisory.suggest(requirement);
+        }
+        vec![advisory]
+    }
+}
+
+/// Device URIs of the CUPS queues on USB printers, from `lpstat -v` or the saved queues
+fn usb_queues(system: &dyn SystemFacade) -> Vec<String> {
+    let text = system
+        .run("lpstat", &["-v"])
+        .ok()
+        .filter(|output| output.success())
+        .map(|output| output.stdout)
+        .or_else(|| system.read_to_string(Path::new(CUPS_PRINTERS)).ok())
+        .unwrap_or_default();
+    let mut queues: Vec<String> = text
+        .lines()
+        .filter_map(|line| {
+            let start = line.find("usb://")?;
+            line[start..].split_whitespace().next().map(str::to_string)
+        })
+        .collect();
+    queues.sort();
+    queues.dedup();
+    queues
+}
@@ -0,0 +1,145 @@
+// src-tauri/src/core/privilege.rs
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+use super::system::SystemFacade;
+
+/// An operation that needs root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivilegedAction {
+    WriteFile { path: PathBuf, contents: String, mode: u32 },
+    RemoveFile { path: PathBuf },
+    Run { program: String, args: Vec<String> },
+}
+
+/// Runs as root to place a staged file: the payload is first copied somewhere only root can
+/// write, its sha256 checked against what the user side staged, and only then installed
+/// Arguments: staged path, expected sha256, octal mode, destination
+const INSTALL_STAGED: &str = r#"set -eu
+copy=$(mktemp)
+trap 'rm -f "$copy"' EXIT
+cat -- "$1" > "$copy"
+printf '%s  %s\n' "$2" "$copy" | sha256sum -c --status -
+install -D -m "$3" -- "$copy" "$4"
+"#;
+
+/// How the broker gains root for each action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Elevation {
+    AlreadyRoot,
+    Pkexec,
+    Sudo,
+}
+
+/// Funnels every privileged operation through one audited place
+/// The GUI never runs as root; each action is elevated individually
+pub struct PrivilegeBroker {
+    system: Arc<dyn SystemFacade>,
+    elevation: Elevation,
+}
+
+impl PrivilegeBroker {
+    /// Picks root, pkexec or sudo depending on what the session offers
+    pub fn detect(system: Arc<dyn SystemFacade>) -> Self {
+        let is_root = system
+            .run("id", &["-u"])
+            .map(|output| output.stdout.trim() == "0")
+            .unwrap_or(false);
+        let elevation = if is_root {
+            Elevation::AlreadyRoot
+        } else if system.has_program("pkexec") {
+            Elevation::Pkexec
+        } else {
+            Elevation::Sudo
+        };
+        PrivilegeBroker { system, elevation }
+    }
+
+    /// Creates a broker with an explicit elevation method
+    pub fn with_elevation(system: Arc<dyn SystemFacade>, elevation: Elevation) -> Self {
+        PrivilegeBroker { system, elevation }
+    }
+
+    /// Elevation method in use
+    pub fn elevation(&self) -> Elevation {
+        self.elevation
+    }
+
+    /// Performs one action as root
+    pub fn execute(&self, action: &PrivilegedAction) -> Result<()> {
+        match action {
+            PrivilegedAction::WriteFile { path, contents, mode } => {
+                let (_dir, staged) = stage(&self.system.staging_dir(), contents).with_context(|| format!("staging {}", path.display()))?;
+                let digest = hex::encode(Sha256::digest(contents.as_bytes()));
+                self.run_elevated(
+                    "sh",
+                    &[
+                        "-c",
+                        INSTALL_STAGED,
+                        "kernelforge-install",
+                        &staged.to_string_lossy(),
+                        &digest,
+                        &format!("{:o}", mode),
+                        &path.to_string_lossy(),
+                    ],
+                )
+            }
+            PrivilegedAction::RemoveFile { path } => self.run_elevated("rm", &["-f", &path.to_string_lossy()]),
+            PrivilegedAction::Run { program, args } => {
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                self.run_elevated(program, &args)
+            }
+        }
+    }
+
+    /// Performs actions in order, stopping at the first failure
+    pub fn execute_all(&self, actions: &[PrivilegedAction]) -> Result<()> {
+        for action in actions {
+            self.execute(action)?;
+        }
+        Ok(())
+    }
+
+    fn run_elevated(&self, program: &str, args: &[&str]) -> Result<()> {
+        let output = match self.elevation {
+            Elevation::AlreadyRoot => self.system.run(program, args),
+            Elevation::Pkexec | Elevation::Sudo => {
+                let wrapper = if self.elevation == Elevation::Pkexec { "pkexec" } else { "sudo" };
+                let mut full = vec![program];
+                full.extend_from_slice(args);
+                self.system.run(wrapper, &full)
+            }
+        }
+        .with_context(|| format!("running {}", program))?;
+        if !output.success() {
+            bail!("{} failed ({}): {}", program, output.status, output.stderr.trim());
+        }
+        Ok(())
+    }
+}
+
+/// Writes a payload into a fresh private directory for root to pick up
+/// The directory is 0700 and the file is created with O_EXCL, so no other user can plant a
+/// symlink or swap the payload; the directory is removed when the returned guard drops
+/// `parent` comes from the facade so root on the host can see the file from inside a sandbox
+fn stage(parent: &Path, contents: &str) -> Result<(TempDir, PathBuf)> {
+    let dir = tempfile::Builder::new().prefix("kernelforge-").tempdir_in(parent)?;
+    let staged = dir.path().join("payload");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&staged)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    Ok((dir, staged))
+}
@@ -0,0 +1,186 @@
+// src-tauri/src/core/procfs.rs
+
+use std::borrow::Cow;
+
+pub const PROC_MODULES: &str = "/proc/modules";
+pub const PROC_MOUNTS: &str = "/proc/mounts";
+pub const PROC_CPUINFO: &str = "/proc/cpuinfo";
+pub const PROC_MEMINFO: &str = "/proc/meminfo";
+
+/// One line of /proc/modules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleEntry<'a> {
+    pub name: &'a str,
+    pub size: u64,
+    pub refcount: u32,
+    pub used_by: Vec<&'a str>,
+    pub state: &'a str,
+}
+
+/// Parses /proc/modules, skipping malformed lines
+pub fn parse_modules(text: &str) -> Vec<ModuleEntry<'_>> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            let size = fields.next()?.parse().ok()?;
+            let refcount = fields.next()?.parse().ok()?;
+            let used_by = fields
+                .next()?
+                .split(',')
+                .filter(|user| !user.is_empty() && *user != "-")
+                .collect();
+            let state = fields.next().unwrap_or("Live");
+            Some(ModuleEntry {
+                name,
+                size,
+                refcount,
+                used_by,
+                state,
+            })
+        })
+        .collect()
+}
+
+/// One line of /proc/mounts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry<'a> {
+    pub source: Cow<'a, str>,
+    pub target: Cow<'a, str>,
+    pub fstype: &'a str,
+    pub options: &'a str,
+}
+
+impl MountEntry<'_> {
+    /// Whether a mount option such as `ro` is present
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.split(',').any(|candidate| candidate == option)
+    }
+}
+
+/// Parses /proc/mounts; only paths containing octal escapes are copied
+pub fn parse_mounts(text: &str) -> Vec<MountEntry<'_>> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(MountEntry {
+                source: unescape_octal(fields.next()?),
+                target: unescape_octal(fields.next()?),
+                fstype: fields.next()?,
+                options: fields.next()?,
+            })
+        })
+        .collect()
+}
+
+/// Decodes the `\040`-style escapes the kernel uses for whitespace in mount paths
+fn unescape_octal(field: &str) -> Cow<'_, str> {
+    if !field.contains('\\') {
+        return Cow::Borrowed(field);
+    }
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escape = bytes.get(index + 1..index + 4).filter(|digits| {
+            bytes[index] == b'\\' && digits.iter().all(|digit| (b'0'..=b'7').contains(digit))
+        });
+        match escape {
+            Some(digits) => {
+                out.push(digits.iter().fold(0u8, |acc, digit| acc.wrapping_mul(8) + (digit - b'0')));
+                index += 4;
+            }
+            None => {
+                out.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    Cow::Owned(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Key/value block describing one logical CPU in /proc/cpuinfo
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CpuInfoEntry<'a> {
+    pub fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> CpuInfoEntry<'a> {
+    /// Value of a field such as `vendor_id` or `model name`
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.fields
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, value)| *value)
+    }
+
+    /// CPU feature flags (`flags` on x86, `Features` on ARM)
+    pub fn flags(&self) -> impl Iterator<Item = &'a str> {
+        self.get("flags")
+            .or_else(|| self.get("Features"))
+            .unwrap_or("")
+            .split_whitespace()
+    }
+}
+
+/// Parses /proc/cpuinfo into one entry per processor block
+pub fn parse_cpuinfo(text: &str) -> Vec<CpuInfoEntry<'_>> {
+    let mut entries = Vec::new();
+    let mut current = CpuInfoEntry::default();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.fields.is_empty() {
+                entries.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current.fields.push((key.trim(), value.trim()));
+        }
+    }
+    if !current.fields.is_empty() {
+        entries.push(current);
+    }
+    entries
+}
+
+/// Parsed /proc/meminfo, values in kB as reported by the kernel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemInfo<'a> {
+    pub entries: Vec<(&'a str, u64)>,
+}
+
+impl MemInfo<'_> {
+    /// Value of a field such as `MemTotal`, in kB
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|(candidate, _)| *candidate == key)
+            .map(|(_, value)| *value)
+    }
+
+    /// Installed memory in kB
+    pub fn total_kb(&self) -> u64 {
+        self.get("MemTotal").unwrap_or(0)
+    }
+
+    /// Memory available for new work in kB, falling back to MemFree on old kernels
+    pub fn available_kb(&self) -> u64 {
+        self.get("MemAvailable")
+            .or_else(|| self.get("MemFree"))
+            .unwrap_or(0)
+    }
+}
+
+/// Parses /proc/meminfo
+pub fn parse_meminfo(text: &str) -> MemInfo<'_> {
+    let entries = text
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), value))
+        })
+        .collect();
+    MemInfo { entries }
+}
@@ -0,0 +1,169 @@
+// src-tauri/src/core/profiles.rs
+
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::ConfigValue;
+use super::generator::KernelProfile;
+use super::paths::Paths;
+use super::resolver::ConfigLayer;
+use super::state_file;
+
+/// A tuning profile the user defined, e.g. a laptop profile extending desktop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProfile {
+    pub name: String,
+    /// Built-in or custom profile this one starts from, None to build on the baseline
+    pub extends: Option<String>,
+    /// Applied after everything inherited, so they override it
+    pub assignments: Vec<(String, ConfigValue)>,
+}
+
+impl CustomProfile {
+    pub fn layer(&self) -> ConfigLayer {
+        ConfigLayer {
+            name: self.name.clone(),
+            assignments: self.assignments.clone(),
+        }
+    }
+}
+
+/// A profile as listed for picking, with what it extends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub name: String,
+    pub extends: Option<String>,
+    pub builtin: bool,
+}
+
+/// The user's profiles, kept with the settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileLibrary {
+    pub profiles: Vec<CustomProfile>,
+}
+
+impl ProfileLibrary {
+    pub fn load(paths: &Paths) -> Result<Self> {
+        let path = paths.profiles_path();
+        if !path.exists() {
+            return Ok(ProfileLibrary::default());
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.config_dir())?;
+        state_file::write(
+            &paths.profiles_path(),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CustomProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Built-in profiles followed by the user's
+    pub fn entries(&self) -> Vec<ProfileEntry> {
+        let builtin = KernelProfile::ALL.into_iter().map(|profile| ProfileEntry {
+            name: profile.name().to_string(),
+            extends: profile.parent().map(|parent| parent.name().to_string()),
+            builtin: true,
+        });
+        let custom = self.profiles.iter().map(|profile| ProfileEntry {
+            name: profile.name.clone(),
+            extends: profile.extends.clone(),
+            builtin: false,
+        });
+        builtin.chain(custom).collect()
+    }
+
+    /// `name` followed by every profile it extends, up to the one built on the baseline
+    /// A file edited by hand can still hold a cycle, which is reported rather than followed
+    pub fn lineage(&self, name: &str) -> Result<Vec<String>> {
+        let mut lineage: Vec<String> = Vec::new();
+        let mut next = Some(name.to_string());
+        while let Some(current) = next {
+            if lineage.contains(&current) {
+                lineage.push(current);
+                bail!("profile inheritance cycle: {}", lineage.join(" -> "));
+            }
+            next = match (self.get(&current), KernelProfile::from_name(&current)) {
+                (Some(custom), _) => custom.extends.clone(),
+                (None, Some(builtin)) => builtin.parent().map(|parent| parent.name().to_string()),
+                (None, None) => match lineage.last() {
+                    Some(child) => bail!("profile {} extends unknown profile {}", child, current),
+                    None => bail!("no profile named {}", current),
+                },
+            };
+            lineage.push(current);
+        }
+        Ok(lineage)
+    }
+
+    /// Layers of `name` and its ancestors, the furthest ancestor first so each profile
+    /// overrides what it inherits
+    pub fn layers(&self, name: &str) -> Result<Vec<ConfigLayer>> {
+        let lineage = self.lineage(name)?;
+        Ok(lineage
+            .iter()
+            .rev()
+            .map(|name| match self.get(name) {
+                Some(custom) => custom.layer(),
+                None => KernelProfile::from_name(name)
+                    .expect("lineage only holds known profiles")
+                    .layer(),
+            })
+            .collect())
+    }
+
+    /// Adds a profile or replaces the one with its name
+    /// Built-in names are reserved, and the parent must exist without leading back here
+    pub fn define(&mut self, profile: CustomProfile) -> Result<()> {
+        let name = profile.name.trim();
+        if name.is_empty() {
+            bail!("the profile needs a name");
+        }
+        if KernelProfile::from_name(name).is_some() {
+            bail!("{} is a built-in profile, pick another name", name);
+        }
+        let profile = CustomProfile {
+            name: name.to_string(),
+            ..profile
+        };
+        let mut updated = self.clone();
+        match updated
+            .profiles
+            .iter_mut()
+            .find(|existing| existing.name == name)
+        {
+            Some(existing) => *existing = profile,
+            None => updated.profiles.push(profile),
+        }
+        updated.lineage(name)?;
+        *self = updated;
+        Ok(())
+    }
+
+    /// Removes a profile no other profile extends
+    pub fn remove(&mut self, name: &str) -> Result<()> {
+        if self.get(name).is_none() {
+            bail!("no custom profile named {}", name);
+        }
+        let children: Vec<&str> = self
+            .profiles
+            .iter()
+            .filter(|profile| profile.extends.as_deref() == Some(name))
+            .map(|profile| profile.name.as_str())
+            .collect();
+        if !children.is_empty() {
+            bail!("{} is extended by {}", name, children.join(", "));
+        }
+        self.profiles.retain(|profile| profile.name != name);
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+// src-tauri/src/core/prompts.rs
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::build::BuildOptions;
+use super::config::{ConfigValue, KernelConfig};
+use super::kconfig::{KconfigTree, SymbolDef, SymbolType, Tristate};
+use super::symbols::SymbolId;
+
+/// Coarse grouping of symbols, from the Kconfig file that defines them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SymbolCategory {
+    Driver,
+    Filesystem,
+    Networking,
+    Security,
+    /// Debugging, tracing and self-tests
+    Debug,
+    Other,
+}
+
+impl SymbolCategory {
+    pub fn of(def: &SymbolDef, name: &str) -> Self {
+        let file = def.file.as_deref().unwrap_or("");
+        if file.contains("Kconfig.debug")
+            || file.starts_with("kernel/trace/")
+            || name.contains("DEBUG")
+            || name.ends_with("_TEST")
+            || name.contains("SELFTEST")
+        {
+            SymbolCategory::Debug
+        } else if file.starts_with("drivers/") || file.starts_with("sound/") {
+            SymbolCategory::Driver
+        } else if file.starts_with("fs/") {
+            SymbolCategory::Filesystem
+        } else if file.starts_with("net/") {
+            SymbolCategory::Networking
+        } else if file.starts_with("security/") || file.starts_with("crypto/") {
+            SymbolCategory::Security
+        } else {
+            SymbolCategory::Other
+        }
+    }
+}
+
+/// How a new symbol is answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptAnswer {
+    No,
+    /// Module where the symbol allows it, its default otherwise
+    Module,
+    /// Whatever Kconfig would pick on Enter
+    Default,
+}
+
+/// Answers for symbols the config does not mention yet, e.g. after moving to a newer kernel
+/// Seeding them before make means `olddefconfig` and `oldconfig` have nothing left to ask
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptPolicy {
+    pub answers: BTreeMap<SymbolCategory, PromptAnswer>,
+}
+
+impl Default for PromptPolicy {
+    fn default() -> Self {
+        PromptPolicy {
+            answers: BTreeMap::from([
+                (SymbolCategory::Driver, PromptAnswer::Module),
+                (SymbolCategory::Filesystem, PromptAnswer::Module),
+                (SymbolCategory::Networking, PromptAnswer::Module),
+                (SymbolCategory::Security, PromptAnswer::Default),
+                (SymbolCategory::Debug, PromptAnswer::No),
+                (SymbolCategory::Other, PromptAnswer::Default),
+            ]),
+        }
+    }
+}
+
+impl PromptPolicy {
+    pub fn answer(&self, category: SymbolCategory) -> PromptAnswer {
+        self.answers
+            .get(&category)
+            .copied()
+            .unwrap_or(PromptAnswer::Default)
+    }
+
+    /// Visible prompted symbols that `config` leaves unanswered
+    pub fn new_symbols<'t>(
+        &self,
+        tree: &'t KconfigTree,
+        config: &KernelConfig,
+    ) -> Vec<&'t SymbolDef> {
+        let symbols = tree.symbols();
+        let lookup = |id: SymbolId| config.get(symbols.name(id)).and_then(ConfigValue::as_str);
+        tree.defs()
+            .iter()
+            .filter(|def| def.prompt.is_some())
+            .filter(|def| config.get(symbols.name(def.id)).is_none())
+            .filter(|def| {
+                def.depends
+                    .as_ref()
+                    .map_or(Tristate::Yes, |expr| expr.eval(&lookup))
+                    != Tristate::No
+            })
+            .collect()
+    }
+
+    /// Writes an answer for every new symbol into `config` and returns what was written
+    /// Symbols answered with their default are left out, Kconfig fills those in itself
+    pub fn seed(
+        &self,
+        tree: &KconfigTree,
+        config: &mut KernelConfig,
+    ) -> Vec<(String, ConfigValue)> {
+        let modules = config.is_enabled("MODULES");
+        let mut seeded = Vec::new();
+        for def in self.new_symbols(tree, config) {
+            let name = tree.symbols().name(def.id);
+            let value = match (self.answer(SymbolCategory::of(def, name)), def.kind) {
+                (PromptAnswer::No, SymbolType::Bool | SymbolType::Tristate) => ConfigValue::NotSet,
+                (PromptAnswer::Module, SymbolType::Tristate) if modules => ConfigValue::module(),
+                _ => continue,
+            };
+            seeded.push((name.to_string(), value));
+        }
+        for (name, value) in &seeded {
+            config.set(name, value.clone());
+        }
+        seeded
+    }
+
+    /// Makes the build answer anything the seed missed with its default instead of waiting
+    /// KCONFIG_CONFIG points Kconfig at the seeded `config`, so make never falls back to
+    /// another `.config` and asks about symbols the seed already answered
+    pub fn apply(&self, options: &mut BuildOptions, config: &Path) {
+        options.answer_prompts = true;
+        options.env.retain(|(name, _)| name != "KCONFIG_CONFIG");
+        options.env.push((
+            String::from("KCONFIG_CONFIG"),
+            config.to_string_lossy().into_owned(),
+        ));
+    }
+}
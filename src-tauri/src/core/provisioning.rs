@@ -0,0 +1,216 @@
+// src-tauri/src/core/provisioning.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::attestation::Attestation;
+use super::cache::sha256_file;
+use super::fleet::ArtifactKind;
+use super::system::SystemFacade;
+
+/// Role directory name inside the export
+pub const ROLE_NAME: &str = "kernelforge_kernel";
+
+/// Where the package is placed on targets before installing
+const TARGET_STAGING: &str = "/var/tmp";
+
+/// Deployment snippet flavours
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProvisioningFormat {
+    AnsibleRole,
+    CloudInit,
+}
+
+/// The package to deploy, as named and hashed by the provenance statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisionedPackage {
+    pub version: String,
+    pub file_name: String,
+    pub sha256: String,
+    pub kind: ArtifactKind,
+}
+
+impl ProvisionedPackage {
+    /// Looks `package` up among the statement's subjects and checks it still matches
+    /// Snippets are only generated for exactly what the provenance describes
+    pub fn from_attestation(attestation: &Attestation, package: &Path) -> Result<Self> {
+        let file_name = package
+            .file_name()
+            .with_context(|| format!("{} has no file name", package.display()))?
+            .to_string_lossy()
+            .to_string();
+        let kind = ArtifactKind::of(package)?;
+        if kind == ArtifactKind::Uki {
+            bail!("unified kernel images are deployed with fleet mode, not a package manager");
+        }
+        let subject = attestation
+            .subject
+            .iter()
+            .find(|subject| subject.name == file_name)
+            .with_context(|| format!("{} is not a subject of the provenance", file_name))?;
+        let recorded = subject
+            .sha256()
+            .with_context(|| format!("the provenance has no sha256 for {}", file_name))?;
+        let actual = sha256_file(package)?;
+        if !actual.eq_ignore_ascii_case(recorded) {
+            bail!(
+                "{} changed since the provenance was written: expected {}, got {}",
+                file_name,
+                recorded,
+                actual
+            );
+        }
+        Ok(ProvisionedPackage {
+            version: attestation.predicate.version.clone(),
+            file_name,
+            sha256: actual,
+            kind,
+        })
+    }
+
+    fn target_path(&self) -> String {
+        format!("{}/{}", TARGET_STAGING, self.file_name)
+    }
+
+    /// Shell command installing the staged package
+    fn install_command(&self) -> String {
+        let path = self.target_path();
+        match self.kind {
+            ArtifactKind::Deb => format!("apt-get install -y {}", path),
+            ArtifactKind::Rpm => format!("dnf install -y {}", path),
+            ArtifactKind::Pacman => format!("pacman -U --noconfirm {}", path),
+            ArtifactKind::Uki => unreachable!("rejected in from_attestation"),
+        }
+    }
+}
+
+/// Writes an Ansible role under `output/kernelforge_kernel` carrying the package, returning
+/// the files written
+pub fn export_ansible_role(
+    package: &ProvisionedPackage,
+    source: &Path,
+    output: &Path,
+) -> Result<Vec<PathBuf>> {
+    let role = output.join(ROLE_NAME);
+    let mut written = Vec::new();
+    for dir in ["defaults", "tasks", "files"] {
+        fs::create_dir_all(role.join(dir))
+            .with_context(|| format!("creating {}", role.join(dir).display()))?;
+    }
+
+    let defaults = role.join("defaults/main.yml");
+    fs::write(
+        &defaults,
+        format!(
+            "---\n\
+             kernelforge_version: \"{version}\"\n\
+             kernelforge_package: \"{file}\"\n\
+             kernelforge_package_sha256: \"{sha256}\"\n\
+             kernelforge_staging: \"{staging}\"\n",
+            version = package.version,
+            file = package.file_name,
+            sha256 = package.sha256,
+            staging = TARGET_STAGING,
+        ),
+    )
+    .with_context(|| format!("writing {}", defaults.display()))?;
+    written.push(defaults);
+
+    let install = match package.kind {
+        ArtifactKind::Deb => {
+            "- name: Install the kernel package\n  \
+               ansible.builtin.apt:\n    \
+                 deb: \"{{ kernelforge_staging }}/{{ kernelforge_package }}\"\n"
+        }
+        ArtifactKind::Rpm => {
+            "- name: Install the kernel package\n  \
+               ansible.builtin.dnf:\n    \
+                 name: \"{{ kernelforge_staging }}/{{ kernelforge_package }}\"\n    \
+                 disable_gpg_check: true\n"
+        }
+        ArtifactKind::Pacman => {
+            "- name: Install the kernel package\n  \
+               community.general.pacman:\n    \
+                 name: \"{{ kernelforge_staging }}/{{ kernelforge_package }}\"\n    \
+                 state: present\n"
+        }
+        ArtifactKind::Uki => bail!("unified kernel images cannot be installed by the role"),
+    };
+    let tasks = role.join("tasks/main.yml");
+    fs::write(
+        &tasks,
+        format!(
+            "---\n\
+             - name: Copy the KernelForge {{{{ kernelforge_version }}}} package\n  \
+               ansible.builtin.copy:\n    \
+                 src: \"{{{{ kernelforge_package }}}}\"\n    \
+                 dest: \"{{{{ kernelforge_staging }}}}/{{{{ kernelforge_package }}}}\"\n    \
+                 mode: \"0644\"\n\
+             \n\
+             - name: Hash the copied package\n  \
+               ansible.builtin.stat:\n    \
+                 path: \"{{{{ kernelforge_staging }}}}/{{{{ kernelforge_package }}}}\"\n    \
+                 checksum_algorithm: sha256\n  \
+               register: kernelforge_copied\n\
+             \n\
+             - name: Check the package against the provenance digest\n  \
+               ansible.builtin.assert:\n    \
+                 that: kernelforge_copied.stat.checksum == kernelforge_package_sha256\n    \
+                 fail_msg: \"{{{{ kernelforge_package }}}} does not match its provenance\"\n\
+             \n\
+             {install}\
+             \n\
+             - name: Remove the staged package\n  \
+               ansible.builtin.file:\n    \
+                 path: \"{{{{ kernelforge_staging }}}}/{{{{ kernelforge_package }}}}\"\n    \
+                 state: absent\n",
+            install = install,
+        ),
+    )
+    .with_context(|| format!("writing {}", tasks.display()))?;
+    written.push(tasks);
+
+    let file = role.join("files").join(&package.file_name);
+    fs::copy(source, &file).with_context(|| format!("copying {}", source.display()))?;
+    written.push(file);
+    Ok(written)
+}
+
+/// Writes cloud-init user data embedding the package in `write_files` and installing it
+/// from `runcmd`; many clouds cap user data at a few MiB, so this suits local hypervisors
+pub fn export_cloud_init(
+    system: &dyn SystemFacade,
+    package: &ProvisionedPackage,
+    source: &Path,
+    output: &Path,
+) -> Result<PathBuf> {
+    let encoded = system
+        .run("base64", &["-w0", &source.to_string_lossy()])
+        .context("running base64")?;
+    if !encoded.success() {
+        bail!("base64 failed: {}", encoded.stderr.trim());
+    }
+    let target = package.target_path();
+    let text = format!(
+        "#cloud-config\n\
+         # KernelForge {version}, {file} sha256 {sha256}\n\
+         write_files:\n  \
+           - path: {target}\n    \
+             encoding: b64\n    \
+             permissions: \"0644\"\n    \
+             content: {content}\n\
+         runcmd:\n  \
+           - [sh, -c, \"echo '{sha256}  {target}' | sha256sum -c - && {install} && rm -f {target}\"]\n",
+        version = package.version,
+        file = package.file_name,
+        sha256 = package.sha256,
+        target = target,
+        content = encoded.stdout.trim(),
+        install = package.install_command(),
+    );
+    fs::write(output, text).with_context(|| format!("writing {}", output.display()))?;
+    Ok(output.to_path_buf())
+}
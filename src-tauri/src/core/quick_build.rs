@@ -0,0 +1,143 @@
+// src-tauri/src/core/quick_build.rs
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::build::BuildOptions;
+use super::config::{ConfigValue, KernelConfig};
+use super::pipeline::PipelineStage;
+use super::plan::{BuildPlan, BuildTarget};
+use super::resolver::ConfigLayer;
+use super::toolchain::CrossToolchain;
+use super::watchdog::WatchdogPolicy;
+
+/// Options that make a build slower without changing how the kernel behaves
+const FAST_OPTIONS: &[(&str, bool)] = &[
+    ("CONFIG_LTO_NONE", true),
+    ("CONFIG_LTO_CLANG_THIN", false),
+    ("CONFIG_LTO_CLANG_FULL", false),
+    // -O2, the kernel's default level
+    ("CONFIG_CC_OPTIMIZE_FOR_PERFORMANCE", true),
+    ("CONFIG_DEBUG_INFO_NONE", true),
+    ("CONFIG_DEBUG_INFO_DWARF_TOOLCHAIN_DEFAULT", false),
+    ("CONFIG_DEBUG_INFO_DWARF4", false),
+    ("CONFIG_DEBUG_INFO_DWARF5", false),
+    ("CONFIG_DEBUG_INFO_BTF", false),
+    ("CONFIG_MODULE_COMPRESS_NONE", true),
+    ("CONFIG_MODULE_COMPRESS_XZ", false),
+    ("CONFIG_MODULE_COMPRESS_ZSTD", false),
+];
+
+/// Fast-iteration settings for tuning options between boots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickBuild {
+    /// Wall-clock limit for the build stage; a quick build running past it has stopped
+    /// being quick and is flagged like a stall
+    pub time_box: Duration,
+}
+
+impl Default for QuickBuild {
+    fn default() -> Self {
+        QuickBuild {
+            time_box: Duration::from_secs(20 * 60),
+        }
+    }
+}
+
+/// A plan reduced for a quick build, with what changed since the previous one
+/// Kbuild records which options each object depends on, so in a tree that is not cleaned
+/// between builds only objects depending on the changed options are recompiled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickBuildPlan {
+    pub plan: BuildPlan,
+    /// Options whose value differs from the previous build, bare names
+    pub changed: Vec<String>,
+    pub time_box: Duration,
+}
+
+impl QuickBuild {
+    /// Layer turning off LTO, debug info and module compression, and keeping -O2
+    pub fn layer() -> ConfigLayer {
+        let mut layer = ConfigLayer::new("quick-build");
+        for (symbol, enabled) in FAST_OPTIONS {
+            let value = if *enabled {
+                ConfigValue::yes()
+            } else {
+                ConfigValue::NotSet
+            };
+            layer.set(symbol, value);
+        }
+        layer
+    }
+
+    /// Reduces `plan` for a quick build; `previous` is the config of the last build in the
+    /// same tree
+    pub fn prepare(&self, plan: &BuildPlan, previous: Option<&KernelConfig>) -> QuickBuildPlan {
+        let mut quick = plan.clone();
+        for (symbol, value) in Self::layer().assignments {
+            quick.config.set(&symbol, value);
+        }
+        // The optimisation level comes from the config; extra -O flags would override it
+        quick
+            .compiler_flags
+            .kcflags
+            .retain(|flag| !flag.starts_with("-O") && !flag.starts_with("-flto"));
+
+        let changed = previous
+            .map(|previous| changed_symbols(previous, &quick.config))
+            .unwrap_or_default();
+        // Only what a boot test needs: the image, modules and the selected blobs
+        quick.targets = if quick.arch.uses_device_trees() && !quick.selected_dtbs().is_empty() {
+            vec![
+                BuildTarget::KernelImage,
+                BuildTarget::Modules,
+                BuildTarget::Dtbs,
+            ]
+        } else {
+            vec![BuildTarget::KernelImage, BuildTarget::Modules]
+        };
+        if !quick.config.is_enabled("MODULES") {
+            quick
+                .targets
+                .retain(|target| *target != BuildTarget::Modules);
+        }
+
+        QuickBuildPlan {
+            plan: quick,
+            changed,
+            time_box: self.time_box,
+        }
+    }
+}
+
+impl QuickBuildPlan {
+    /// Points make at the reduced plan and arms the time box on the build stage
+    pub fn apply(&self, options: &mut BuildOptions, toolchain: &CrossToolchain) {
+        self.plan.apply(options, toolchain);
+        let mut watchdog = WatchdogPolicy::default().for_stage(PipelineStage::Build);
+        watchdog.limit = Some(self.time_box);
+        options.watchdog = Some(watchdog);
+    }
+}
+
+/// Options set differently in the two configs, absent counting as not set
+fn changed_symbols(previous: &KernelConfig, current: &KernelConfig) -> Vec<String> {
+    let symbols: BTreeSet<&str> = previous
+        .iter()
+        .chain(current.iter())
+        .map(|(symbol, _)| symbol)
+        .collect();
+    symbols
+        .into_iter()
+        .filter(|symbol| enabled_value(previous.get(symbol)) != enabled_value(current.get(symbol)))
+        .map(str::to_string)
+        .collect()
+}
+
+fn enabled_value(value: Option<&ConfigValue>) -> Option<&str> {
+    value
+        .and_then(ConfigValue::as_str)
+        .filter(|value| *value != "n")
+}
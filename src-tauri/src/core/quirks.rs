@@ -0,0 +1,234 @@
+// src-tauri/src/core/quirks.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::ConfigValue;
+use super::hardware::HardwareSnapshot;
+use super::keyring::Keyring;
+use super::paths::Paths;
+use super::remote_manifest::{self, RemoteManifest};
+use super::resolver::ConfigLayer;
+use super::system::SystemFacade;
+
+/// Quirks shipped with this release
+const BUNDLED_QUIRKS: &str = include_str!("../../data/quirks.json");
+
+/// Where newer quirk lists are published; the bundled file on the main branch
+/// A detached signature is published next to it with an `.asc` suffix
+pub const DEFAULT_QUIRKS_URL: &str =
+    "https://raw.githubusercontent.com/linuxiano85/KernelForge/main/src-tauri/data/quirks.json";
+
+/// How a quirk recognises a machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuirkMatch {
+    /// DMI system vendor, and a substring of the product name when given
+    Dmi {
+        vendor: String,
+        #[serde(default)]
+        product: Option<String>,
+    },
+    /// PCI vendor and device ID in lowercase hex, e.g. `10ec` and `c822`, optionally
+    /// narrowed to one board by the subsystem IDs
+    Pci {
+        vendor: String,
+        device: String,
+        #[serde(default)]
+        subvendor: Option<String>,
+        #[serde(default)]
+        subdevice: Option<String>,
+    },
+}
+
+impl QuirkMatch {
+    fn matches(&self, hardware: &HardwareSnapshot) -> bool {
+        match self {
+            QuirkMatch::Dmi { vendor, product } => {
+                hardware.vendor.as_deref().map(str::trim) == Some(vendor.as_str())
+                    && product.as_ref().is_none_or(|product| {
+                        hardware
+                            .product
+                            .as_deref()
+                            .is_some_and(|name| name.contains(product.as_str()))
+                    })
+            }
+            QuirkMatch::Pci {
+                vendor,
+                device,
+                subvendor,
+                subdevice,
+            } => hardware
+                .devices
+                .iter()
+                .filter(|entry| entry.bus == "pci")
+                .filter_map(|entry| entry.modalias.as_deref().and_then(pci_ids))
+                .any(|ids| {
+                    ids.vendor == *vendor
+                        && ids.device == *device
+                        && subvendor.as_ref().is_none_or(|id| ids.subvendor == *id)
+                        && subdevice.as_ref().is_none_or(|id| ids.subdevice == *id)
+                }),
+        }
+    }
+}
+
+/// A machine or device that needs config or command line changes to work properly
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quirk {
+    pub id: String,
+    /// The quirk applies when any of these match
+    pub matches: Vec<QuirkMatch>,
+    pub title: String,
+    pub explanation: String,
+    /// Assignments layered over the generated config
+    #[serde(default)]
+    pub config: BTreeMap<String, ConfigValue>,
+    /// Parameters for the kernel command line
+    #[serde(default)]
+    pub cmdline: Vec<String>,
+}
+
+impl Quirk {
+    pub fn applies(&self, hardware: &HardwareSnapshot) -> bool {
+        self.matches.iter().any(|matcher| matcher.matches(hardware))
+    }
+}
+
+/// Versioned quirk list; a higher revision replaces a lower one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuirkDatabase {
+    pub revision: u64,
+    pub quirks: Vec<Quirk>,
+}
+
+impl QuirkDatabase {
+    /// Quirks compiled into this release
+    pub fn bundled() -> Self {
+        QuirkDatabase::parse(BUNDLED_QUIRKS).expect("bundled quirks are valid")
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        serde_json::from_str(text).context("parsing hardware quirks")
+    }
+
+    /// The downloaded quirk list when it is newer than the bundled one
+    pub fn load(paths: &Paths) -> Self {
+        remote_manifest::load(QuirkDatabase::bundled(), &paths.quirks_path())
+    }
+
+    /// Fetches the published quirk list and keeps it if newer and signed by a pinned key,
+    /// returning whether it changed
+    pub async fn update(
+        &mut self,
+        client: &Client,
+        system: &dyn SystemFacade,
+        keyring: &Keyring,
+        url: &str,
+        paths: &Paths,
+    ) -> Result<bool> {
+        remote_manifest::update(
+            self,
+            client,
+            system,
+            keyring,
+            url,
+            &paths.quirks_path(),
+            paths,
+        )
+        .await
+    }
+
+    /// Quirks of the scanned machine
+    pub fn matching<'a>(&'a self, hardware: &HardwareSnapshot) -> Vec<&'a Quirk> {
+        self.quirks
+            .iter()
+            .filter(|quirk| quirk.applies(hardware))
+            .collect()
+    }
+
+    /// Config assignments of every matching quirk, to layer over a generated config
+    pub fn layer(&self, hardware: &HardwareSnapshot) -> ConfigLayer {
+        let mut layer = ConfigLayer::new("quirks");
+        for quirk in self.matching(hardware) {
+            for (symbol, value) in &quirk.config {
+                layer.set(symbol, value.clone());
+            }
+        }
+        layer
+    }
+
+    /// Command line parameters of every matching quirk
+    pub fn cmdline(&self, hardware: &HardwareSnapshot) -> Vec<String> {
+        let mut params: Vec<String> = Vec::new();
+        for quirk in self.matching(hardware) {
+            for param in &quirk.cmdline {
+                if !params.contains(param) {
+                    params.push(param.clone());
+                }
+            }
+        }
+        params
+    }
+
+    /// One advisory per matching quirk, so the user sees why the plan differs
+    pub fn advisories(&self, hardware: &HardwareSnapshot) -> Vec<Advisory> {
+        self.matching(hardware)
+            .into_iter()
+            .map(|quirk| {
+                let cmdline = if quirk.cmdline.is_empty() {
+                    String::from("-")
+                } else {
+                    quirk.cmdline.join(" ")
+                };
+                Advisory::new(
+                    Severity::Info,
+                    "hardware-quirk",
+                    &[
+                        ("title", quirk.title.clone()),
+                        ("explanation", quirk.explanation.clone()),
+                        ("cmdline", cmdline),
+                    ],
+                )
+            })
+            .collect()
+    }
+}
+
+impl RemoteManifest for QuirkDatabase {
+    fn parse(text: &str) -> Result<Self> {
+        QuirkDatabase::parse(text)
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+struct PciIds {
+    vendor: String,
+    device: String,
+    subvendor: String,
+    subdevice: String,
+}
+
+/// IDs from a modalias such as `pci:v000010ECd0000C822sv000010ECsd0000C822bc02sc80i00`
+fn pci_ids(modalias: &str) -> Option<PciIds> {
+    let rest = modalias.strip_prefix("pci:v")?;
+    let (vendor, rest) = rest.split_once('d')?;
+    let (device, rest) = rest.split_once("sv")?;
+    let (subvendor, rest) = rest.split_once("sd")?;
+    let (subdevice, _) = rest.split_once("bc")?;
+    // Each ID is zero-padded to eight digits
+    let id = |value: &str| value.get(4..).map(str::to_ascii_lowercase);
+    Some(PciIds {
+        vendor: id(vendor)?,
+        device: id(device)?,
+        subvendor: id(subvendor)?,
+        subdevice: id(subdevice)?,
+    })
+}
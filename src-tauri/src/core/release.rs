@@ -0,0 +1,107 @@
+// src-tauri/src/core/release.rs
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error};
+use serde::{Deserialize, Serialize};
+
+/// Numeric kernel release such as 6.9.3 or 6.10-rc2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReleaseNumber {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Release candidate number, None for final releases
+    pub rc: Option<u32>,
+}
+
+impl ReleaseNumber {
+    /// Creates a final release number
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        ReleaseNumber {
+            major,
+            minor,
+            patch,
+            rc: None,
+        }
+    }
+
+    /// Major/minor series, e.g. (6, 9) for 6.9.3
+    pub fn series(&self) -> (u32, u32) {
+        (self.major, self.minor)
+    }
+
+    /// Whether this is a release candidate
+    pub fn is_rc(&self) -> bool {
+        self.rc.is_some()
+    }
+}
+
+impl Ord for ReleaseNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // 6.10-rc2 sorts before 6.10
+            .then_with(|| match (self.rc, other.rc) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for ReleaseNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for ReleaseNumber {
+    type Err = Error;
+
+    /// Accepts `6.9`, `6.9.3`, `6.10-rc2` and uname strings like `6.9.3-arch1-1`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (numbers, suffix) = match text.find('-') {
+            Some(index) => (&text[..index], Some(&text[index + 1..])),
+            None => (text, None),
+        };
+        let mut parts = numbers.split('.');
+        let mut next = |name: &str| -> Result<Option<u32>, Error> {
+            parts
+                .next()
+                .map(|part| part.parse().with_context(|| format!("invalid {} in {:?}", name, text)))
+                .transpose()
+        };
+        let Some(major) = next("major")? else {
+            bail!("empty kernel version");
+        };
+        let minor = next("minor")?.with_context(|| format!("missing minor in {:?}", text))?;
+        let patch = next("patch")?.unwrap_or(0);
+        let rc = suffix
+            .and_then(|suffix| suffix.strip_prefix("rc"))
+            .and_then(|rc| rc.parse().ok());
+        Ok(ReleaseNumber {
+            major,
+            minor,
+            patch,
+            rc,
+        })
+    }
+}
+
+impl fmt::Display for ReleaseNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if self.patch > 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        if let Some(rc) = self.rc {
+            write!(f, "-rc{}", rc)?;
+        }
+        Ok(())
+    }
+}
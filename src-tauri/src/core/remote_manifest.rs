@@ -0,0 +1,82 @@
+// src-tauri/src/core/remote_manifest.rs
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+
+use super::cache::SignatureStatus;
+use super::keyring::Keyring;
+use super::paths::Paths;
+use super::state_file;
+use super::system::SystemFacade;
+
+/// A versioned JSON data file bundled with each release and published online between
+/// releases; a higher revision replaces a lower one
+pub trait RemoteManifest: Sized {
+    fn parse(text: &str) -> Result<Self>;
+
+    fn revision(&self) -> u64;
+}
+
+/// The downloaded copy at `path` when it is newer than `bundled`
+pub fn load<M: RemoteManifest>(bundled: M, path: &Path) -> M {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| M::parse(&text).ok())
+        .filter(|downloaded| downloaded.revision() > bundled.revision())
+        .unwrap_or(bundled)
+}
+
+/// Fetches the manifest published at `url` and keeps it at `path` if newer than `current`,
+/// returning whether it changed
+/// Manifests feed plans, so one is only used when a pinned key in the keyring signed it;
+/// the detached signature is published next to it with an `.asc` suffix
+pub async fn update<M: RemoteManifest>(
+    current: &mut M,
+    client: &Client,
+    system: &dyn SystemFacade,
+    keyring: &Keyring,
+    url: &str,
+    path: &Path,
+    paths: &Paths,
+) -> Result<bool> {
+    let text = download(client, url).await?;
+    let signature_url = format!("{}.asc", url);
+    let signature = download(client, &signature_url).await?;
+
+    let staging = tempfile::Builder::new()
+        .prefix("kernelforge-manifest-")
+        .tempdir_in(Paths::ensure(paths.cache_dir())?)?;
+    let file = staging.path().join("manifest.json");
+    let signature_file = staging.path().join("manifest.json.asc");
+    fs::write(&file, &text).with_context(|| format!("writing {}", file.display()))?;
+    fs::write(&signature_file, &signature)
+        .with_context(|| format!("writing {}", signature_file.display()))?;
+    let verification = keyring.verify(system, &file, &signature_file)?;
+    match (&verification.status, &verification.key) {
+        (SignatureStatus::Valid { .. }, Some(key)) if key.pinned => {}
+        _ => bail!("{} is not signed by a pinned key", url),
+    }
+
+    let published = M::parse(&text).with_context(|| format!("reading {}", url))?;
+    if published.revision() <= current.revision() {
+        return Ok(false);
+    }
+    state_file::write(path, text.as_bytes())?;
+    *current = published;
+    Ok(true)
+}
+
+async fn download(client: &Client, url: &str) -> Result<String> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("requesting {}", url))?
+        .text()
+        .await
+        .with_context(|| format!("downloading {}", url))
+}
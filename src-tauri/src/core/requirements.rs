@@ -0,0 +1,90 @@
+// src-tauri/src/core/requirements.rs
+
+use anyhow::{bail, Result};
+
+use super::advisory::Advisory;
+use super::config::{ConfigSuggestion, KernelConfig};
+use super::resolver::ConfigLayer;
+
+/// Something set up on the machine, such as its printers or its firewall, whose options
+/// a plan has to keep
+/// Implementors say what they need and how to report what is missing; keeping the
+/// options, finding what a config drops and refusing it follow from that
+pub trait MachineRequirements {
+    /// Names the setup where it keeps a removal group in use, and names its layer
+    fn name(&self) -> &'static str;
+
+    /// Options the setup relies on, each symbol once, with why
+    fn required(&self) -> Vec<ConfigSuggestion>;
+
+    /// Findings for a config leaving out `missing`, which is never empty
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory>;
+
+    /// Whether the setup needs `symbol`
+    fn needs(&self, symbol: &str) -> bool {
+        self.required()
+            .iter()
+            .any(|requirement| requirement.symbol == symbol)
+    }
+
+    /// What keeps `symbol` in use, empty when the setup does not need it
+    fn users_of(&self, symbol: &str) -> Vec<String> {
+        if self.needs(symbol) {
+            vec![self.name().to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn layer(&self) -> ConfigLayer {
+        ConfigLayer::from_suggestions(self.name(), &self.required())
+    }
+
+    /// Required options the config leaves out
+    fn missing(&self, config: &KernelConfig) -> Vec<ConfigSuggestion> {
+        self.required()
+            .into_iter()
+            .filter(|requirement| !config.is_enabled(&requirement.symbol))
+            .collect()
+    }
+
+    /// Findings for a config leaving out what the setup needs, none when it keeps all
+    fn check(&self, config: &KernelConfig) -> Vec<Advisory> {
+        let missing = self.missing(config);
+        if missing.is_empty() {
+            return Vec::new();
+        }
+        self.advisories(missing)
+    }
+
+    /// Refuses a config that would stop the setup from working
+    fn enforce(&self, config: &KernelConfig) -> Result<()> {
+        let symbols: Vec<String> = self
+            .missing(config)
+            .into_iter()
+            .map(|requirement| requirement.symbol)
+            .collect();
+        if symbols.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "{} on this machine needs {}",
+            self.name(),
+            symbols.join(", ")
+        );
+    }
+}
+
+/// `requirements` with each symbol once, keeping the first reason given for it
+pub fn unique(requirements: impl IntoIterator<Item = ConfigSuggestion>) -> Vec<ConfigSuggestion> {
+    let mut unique: Vec<ConfigSuggestion> = Vec::new();
+    for requirement in requirements {
+        if !unique
+            .iter()
+            .any(|existing| existing.symbol == requirement.symbol)
+        {
+            unique.push(requirement);
+        }
+    }
+    unique
+}
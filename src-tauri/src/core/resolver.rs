@@ -0,0 +1,438 @@
+// src-tauri/src/core/resolver.rs
+
+use std::collections::VecDeque;
+use std::thread;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::config::{validate, ConfigSuggestion, ConfigValue, KernelConfig};
+use super::kconfig::{ChoiceGroup, Expr, KconfigTree, SymbolDef, SymbolType, Tristate};
+use super::symbols::SymbolId;
+
+/// Upper bound on fixpoint passes, selects rarely chain deeper than a few levels
+const MAX_PASSES: usize = 16;
+
+/// A layer assignment: layer index, position across all layers, requested value
+type Request<'l> = (usize, usize, &'l ConfigValue);
+
+/// Named set of assignments applied on top of the layers before it
+/// Typical stack: baseline, profile, hardware, user overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigLayer {
+    pub name: String,
+    pub assignments: Vec<(String, ConfigValue)>,
+}
+
+impl ConfigLayer {
+    /// Creates an empty layer
+    pub fn new(name: &str) -> Self {
+        ConfigLayer {
+            name: name.to_string(),
+            assignments: Vec::new(),
+        }
+    }
+
+    /// Adds an assignment, later assignments win within the same layer
+    pub fn set(&mut self, symbol: &str, value: ConfigValue) {
+        self.assignments.push((symbol.to_string(), value));
+    }
+
+    /// Adds an assignment after checking the value against the symbol's Kconfig type
+    pub fn set_checked(&mut self, tree: &KconfigTree, symbol: &str, value: ConfigValue) -> Result<()> {
+        validate(tree, symbol, &value)?;
+        self.set(symbol, value);
+        Ok(())
+    }
+
+    /// Builder form of [`ConfigLayer::set`]
+    pub fn with(mut self, symbol: &str, value: ConfigValue) -> Self {
+        self.set(symbol, value);
+        self
+    }
+
+    /// Turns a complete configuration into a layer, e.g. one produced by `make defconfig`
+    pub fn from_config(name: &str, config: &KernelConfig) -> Self {
+        let mut layer = ConfigLayer::new(name);
+        for (symbol, value) in config.iter() {
+            layer.set(&format!("CONFIG_{}", symbol), value.clone());
+        }
+        layer
+    }
+
+    /// Turns analyzer suggestions into a layer
+    pub fn from_suggestions(name: &str, suggestions: &[ConfigSuggestion]) -> Self {
+        let mut layer = ConfigLayer::new(name);
+        for suggestion in suggestions {
+            layer.set(&suggestion.symbol, suggestion.value.clone());
+        }
+        layer
+    }
+}
+
+/// A layer request that the Kconfig rules did not allow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub symbol: String,
+    pub layer: String,
+    pub requested: ConfigValue,
+    pub resolved: ConfigValue,
+}
+
+/// A layer assignment whose value does not fit the symbol's type, e.g. HZ=y
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidAssignment {
+    pub symbol: String,
+    pub layer: String,
+    pub value: ConfigValue,
+    pub error: String,
+}
+
+/// Outcome of resolving a layer stack against a Kconfig tree
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    values: Vec<Option<String>>,
+    pub conflicts: Vec<Conflict>,
+    /// Symbols assigned by a layer but absent from the tree
+    pub unknown: Vec<String>,
+    /// Assignments ignored because their value does not match the symbol type
+    pub invalid: Vec<InvalidAssignment>,
+}
+
+impl Resolution {
+    /// Raw resolved value of a symbol, None when it is not visible
+    pub fn value(&self, id: SymbolId) -> Option<&str> {
+        self.values.get(id.index()).and_then(|value| value.as_deref())
+    }
+
+    /// Converts the resolution into an emit-ready configuration
+    pub fn to_config(&self, tree: &KconfigTree) -> KernelConfig {
+        let mut config = KernelConfig::new();
+        for (id, name) in tree.symbols().iter() {
+            match self.value(id) {
+                Some("n") => config.set(name, ConfigValue::NotSet),
+                Some(value) => config.set(name, ConfigValue::Set(value.to_string())),
+                None => {}
+            }
+        }
+        config
+    }
+}
+
+/// Precomputed view of a Kconfig tree that makes repeated resolution cheap
+/// Built once per source tree, then reused on every toggle from the UI
+pub struct ResolverIndex<'t> {
+    tree: &'t KconfigTree,
+    order: Vec<SymbolId>,
+    reverse_selects: Vec<Vec<(SymbolId, Option<Expr>)>>,
+}
+
+impl<'t> ResolverIndex<'t> {
+    /// Orders symbols dependencies-first and inverts `select` edges
+    pub fn build(tree: &'t KconfigTree) -> Self {
+        let count = tree.symbols().len();
+        let mut reverse_selects = vec![Vec::new(); count];
+        let mut dependents: Vec<Vec<SymbolId>> = vec![Vec::new(); count];
+        let mut pending = vec![0usize; count];
+
+        for def in tree.defs() {
+            for (target, condition) in &def.selects {
+                reverse_selects[target.index()].push((def.id, condition.clone()));
+            }
+            let mut inputs = Vec::new();
+            if let Some(depends) = &def.depends {
+                depends.for_each_symbol(&mut |id| inputs.push(id));
+            }
+            for (_, condition) in &def.defaults {
+                if let Some(condition) = condition {
+                    condition.for_each_symbol(&mut |id| inputs.push(id));
+                }
+            }
+            inputs.sort();
+            inputs.dedup();
+            for input in inputs {
+                if input != def.id && tree.def(input).is_some() {
+                    dependents[input.index()].push(def.id);
+                    pending[def.id.index()] += 1;
+                }
+            }
+        }
+
+        // Kahn's algorithm; symbols caught in dependency cycles are appended at the end
+        let mut order = Vec::with_capacity(tree.defs().len());
+        let mut queue: VecDeque<SymbolId> = tree
+            .defs()
+            .iter()
+            .map(|def| def.id)
+            .filter(|id| pending[id.index()] == 0)
+            .collect();
+        let mut placed = vec![false; count];
+        while let Some(id) = queue.pop_front() {
+            placed[id.index()] = true;
+            order.push(id);
+            for dependent in &dependents[id.index()] {
+                pending[dependent.index()] -= 1;
+                if pending[dependent.index()] == 0 {
+                    queue.push_back(*dependent);
+                }
+            }
+        }
+        order.extend(tree.defs().iter().map(|def| def.id).filter(|id| !placed[id.index()]));
+
+        ResolverIndex {
+            tree,
+            order,
+            reverse_selects,
+        }
+    }
+
+    /// Tree this index was built from
+    pub fn tree(&self) -> &'t KconfigTree {
+        self.tree
+    }
+
+    /// Resolves a layer stack, later layers overriding earlier ones
+    pub fn resolve(&self, layers: &[ConfigLayer]) -> Resolution {
+        let symbols = self.tree.symbols();
+        let mut requests: Vec<Option<Request>> = vec![None; symbols.len()];
+        let mut unknown = Vec::new();
+        let mut invalid = Vec::new();
+        let mut position = 0;
+        for (layer_index, layer) in layers.iter().enumerate() {
+            for (name, value) in &layer.assignments {
+                position += 1;
+                let Some(def) = symbols.lookup(name).and_then(|id| self.tree.def(id)) else {
+                    unknown.push(name.clone());
+                    continue;
+                };
+                if let Err(error) = value.typed(def.kind) {
+                    invalid.push(InvalidAssignment {
+                        symbol: name.clone(),
+                        layer: layer.name.clone(),
+                        value: value.clone(),
+                        error: error.to_string(),
+                    });
+                    continue;
+                }
+                requests[def.id.index()] = Some((layer_index, position, value));
+            }
+        }
+
+        let mut values: Vec<Option<String>> = vec![None; symbols.len()];
+        for _ in 0..MAX_PASSES {
+            let mut changed = false;
+            for id in &self.order {
+                let def = self.tree.def(*id).expect("order only holds defined symbols");
+                let next = match self.tree.choice_of(*id) {
+                    Some(choice) => self.choice_member(def, choice, &requests, &values),
+                    None => self.evaluate(def, requests[id.index()].map(|(_, _, value)| value), &values),
+                };
+                if values[id.index()] != next {
+                    values[id.index()] = next;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        for (id, name) in symbols.iter() {
+            let Some((layer_index, position, requested)) = requests[id.index()] else {
+                continue;
+            };
+            // Enabling a choice member in a later layer overrides earlier picks, that is no conflict
+            let superseded = self.tree.choice_of(id).is_some_and(|choice| {
+                choice.members.iter().any(|sibling| {
+                    values[sibling.index()].as_deref() == Some("y")
+                        && requests[sibling.index()].is_some_and(|(_, later, _)| later > position)
+                })
+            });
+            if superseded {
+                continue;
+            }
+            let resolved = normalize(values[id.index()].as_deref());
+            if normalize(requested.as_str()) != resolved {
+                conflicts.push(Conflict {
+                    symbol: name.to_string(),
+                    layer: layers[layer_index].name.clone(),
+                    requested: requested.clone(),
+                    resolved,
+                });
+            }
+        }
+
+        Resolution {
+            values,
+            conflicts,
+            unknown,
+            invalid,
+        }
+    }
+
+    /// Resolves several independent layer stacks in parallel, e.g. to compare profiles
+    pub fn resolve_many(&self, stacks: &[Vec<ConfigLayer>]) -> Vec<Resolution> {
+        thread::scope(|scope| {
+            let handles: Vec<_> = stacks
+                .iter()
+                .map(|layers| scope.spawn(move || self.resolve(layers)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("resolver thread panicked"))
+                .collect()
+        })
+    }
+
+    fn evaluate(
+        &self,
+        def: &SymbolDef,
+        request: Option<&ConfigValue>,
+        values: &[Option<String>],
+    ) -> Option<String> {
+        let lookup = |id: SymbolId| values.get(id.index()).and_then(|value| value.as_deref());
+        let visible = def.depends.as_ref().map_or(Tristate::Yes, |expr| expr.eval(&lookup));
+        // Symbols without a prompt cannot be changed by the user, only by defaults and selects
+        let request = request.filter(|_| def.prompt.is_some());
+
+        match def.kind {
+            SymbolType::Bool | SymbolType::Tristate => {
+                let wanted = match request {
+                    Some(value) => Tristate::from_value(value.as_str().unwrap_or("n")),
+                    None => self.default_tristate(def, &lookup),
+                };
+                let selected = self.reverse_selects[def.id.index()]
+                    .iter()
+                    .map(|(selector, condition)| {
+                        let by = lookup(*selector).map(Tristate::from_value).unwrap_or(Tristate::No);
+                        let when = condition.as_ref().map_or(Tristate::Yes, |expr| expr.eval(&lookup));
+                        by.min(when)
+                    })
+                    .max()
+                    .unwrap_or(Tristate::No);
+                let mut state = wanted.min(visible).max(selected);
+                if def.kind == SymbolType::Bool && state == Tristate::Module {
+                    state = Tristate::Yes;
+                }
+                if state == Tristate::No && visible == Tristate::No {
+                    None
+                } else {
+                    Some(state.as_str().to_string())
+                }
+            }
+            SymbolType::Int | SymbolType::Hex | SymbolType::String => {
+                if visible == Tristate::No {
+                    return None;
+                }
+                if let Some(value) = request.and_then(ConfigValue::as_str) {
+                    return Some(value.to_string());
+                }
+                let raw = self.first_default(def, &lookup)?;
+                let value = match self.tree.symbols().lookup(&raw) {
+                    Some(id) if self.tree.def(id).is_some() => lookup(id)?.to_string(),
+                    _ => raw,
+                };
+                if def.kind == SymbolType::String && !value.starts_with('"') {
+                    Some(format!("\"{}\"", value))
+                } else {
+                    Some(value)
+                }
+            }
+            SymbolType::Unknown => None,
+        }
+    }
+
+    /// Value of a choice member: `y` for the selected member and `n` for its visible siblings
+    fn choice_member(
+        &self,
+        def: &SymbolDef,
+        choice: &ChoiceGroup,
+        requests: &[Option<Request>],
+        values: &[Option<String>],
+    ) -> Option<String> {
+        let lookup = |id: SymbolId| values.get(id.index()).and_then(|value| value.as_deref());
+        let visible = |id: SymbolId| {
+            self.tree
+                .def(id)
+                .and_then(|member| member.depends.as_ref())
+                .map_or(Tristate::Yes, |expr| expr.eval(&lookup))
+                != Tristate::No
+        };
+        if !visible(def.id) {
+            return None;
+        }
+        // The most recent layer enabling a member wins, then the choice default, then the first member
+        let requested = choice
+            .members
+            .iter()
+            .filter(|id| visible(**id))
+            .filter_map(|id| match requests[id.index()] {
+                Some((_, position, value)) if value.is_enabled() => Some((position, *id)),
+                _ => None,
+            })
+            .max()
+            .map(|(_, id)| id);
+        let selected = requested.or_else(|| {
+            if choice.optional {
+                return None;
+            }
+            choice
+                .defaults
+                .iter()
+                .find(|(id, condition)| {
+                    visible(*id)
+                        && condition.as_ref().map_or(Tristate::Yes, |expr| expr.eval(&lookup)) != Tristate::No
+                })
+                .map(|(id, _)| *id)
+                .or_else(|| choice.members.iter().copied().find(|id| visible(*id)))
+        });
+        Some(if selected == Some(def.id) { "y" } else { "n" }.to_string())
+    }
+
+    fn first_default<'v>(
+        &self,
+        def: &SymbolDef,
+        lookup: &dyn Fn(SymbolId) -> Option<&'v str>,
+    ) -> Option<String> {
+        def.defaults
+            .iter()
+            .find(|(_, condition)| {
+                condition.as_ref().map_or(Tristate::Yes, |expr| expr.eval(lookup)) != Tristate::No
+            })
+            .map(|(value, _)| value.clone())
+    }
+
+    fn default_tristate<'v>(
+        &self,
+        def: &SymbolDef,
+        lookup: &dyn Fn(SymbolId) -> Option<&'v str>,
+    ) -> Tristate {
+        for (value, condition) in &def.defaults {
+            let when = condition.as_ref().map_or(Tristate::Yes, |expr| expr.eval(lookup));
+            if when == Tristate::No {
+                continue;
+            }
+            let state = match value.as_str() {
+                "y" | "m" | "n" => Tristate::from_value(value),
+                name => self
+                    .tree
+                    .symbols()
+                    .lookup(name)
+                    .and_then(lookup)
+                    .map(Tristate::from_value)
+                    .unwrap_or(Tristate::No),
+            };
+            return state.min(when);
+        }
+        Tristate::No
+    }
+}
+
+/// Treats an explicit `n` and an absent value the same when comparing outcomes
+fn normalize(value: Option<&str>) -> ConfigValue {
+    match value {
+        None | Some("n") => ConfigValue::NotSet,
+        Some(value) => ConfigValue::Set(value.to_string()),
+    }
+}
@@ -0,0 +1,181 @@
+// src-tauri/src/core/results.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::benchmark::BenchmarkResult;
+use super::boottime::BootTime;
+use super::gaming::GameResult;
+use super::generator::KernelProfile;
+use super::latency::{LatencyLoad, LatencyResult};
+use super::paths::Paths;
+use super::state_file;
+use super::suspend::SuspendTestResult;
+use super::system::SystemFacade;
+
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// Kernel a measurement was taken on
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct KernelTag {
+    /// `uname -r` of the booted kernel
+    pub version: String,
+    /// Profile the kernel was built with, None for kernels KernelForge did not build
+    pub profile: Option<KernelProfile>,
+}
+
+impl KernelTag {
+    /// Tags the running kernel
+    pub fn running(system: &dyn SystemFacade, profile: Option<KernelProfile>) -> Self {
+        KernelTag {
+            version: system
+                .read_trimmed(Path::new(OSRELEASE))
+                .unwrap_or_default(),
+            profile,
+        }
+    }
+}
+
+/// Measurements from every kernel booted, kept so kernels can be compared after the fact
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultsStore {
+    #[serde(default)]
+    benchmarks: Vec<BenchmarkResult>,
+    #[serde(default)]
+    latency: Vec<LatencyResult>,
+    #[serde(default)]
+    games: Vec<GameResult>,
+    #[serde(default)]
+    boots: Vec<BootTime>,
+    #[serde(default)]
+    suspends: Vec<SuspendTestResult>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl ResultsStore {
+    /// Loads the results, starting empty when none were saved yet
+    pub fn open(paths: &Paths) -> Result<Self> {
+        ResultsStore::load(&paths.results_path())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut store = if path.exists() {
+            let text =
+                fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            ResultsStore::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    /// Benchmark runs in the order they were recorded
+    pub fn benchmarks(&self) -> &[BenchmarkResult] {
+        &self.benchmarks
+    }
+
+    /// Runs of one benchmark on one kernel version, oldest first
+    pub fn benchmarks_for<'a>(
+        &'a self,
+        name: &'a str,
+        version: &'a str,
+    ) -> impl Iterator<Item = &'a BenchmarkResult> + 'a {
+        self.benchmarks
+            .iter()
+            .filter(move |result| result.name == name && result.kernel.version == version)
+    }
+
+    /// Appends a benchmark run and saves
+    pub fn add_benchmark(&mut self, result: BenchmarkResult) -> Result<()> {
+        self.append(|store| store.benchmarks.push(result))
+    }
+
+    /// cyclictest runs in the order they were recorded
+    pub fn latency(&self) -> &[LatencyResult] {
+        &self.latency
+    }
+
+    /// Most recent latency measurement of a kernel version under `load`
+    pub fn latest_latency(&self, version: &str, load: LatencyLoad) -> Option<&LatencyResult> {
+        self.latency
+            .iter()
+            .rev()
+            .find(|result| result.kernel.version == version && result.load == load)
+    }
+
+    /// Appends a latency measurement and saves
+    pub fn add_latency(&mut self, result: LatencyResult) -> Result<()> {
+        self.append(|store| store.latency.push(result))
+    }
+
+    /// Game captures in the order they were recorded
+    pub fn games(&self) -> &[GameResult] {
+        &self.games
+    }
+
+    /// Appends a game capture and saves
+    pub fn add_game(&mut self, result: GameResult) -> Result<()> {
+        self.append(|store| store.games.push(result))
+    }
+
+    /// Boot timings in the order they were recorded
+    pub fn boots(&self) -> &[BootTime] {
+        &self.boots
+    }
+
+    /// Most recent boot timing of a kernel version
+    pub fn latest_boot(&self, version: &str) -> Option<&BootTime> {
+        self.boots
+            .iter()
+            .rev()
+            .find(|boot| boot.kernel.version == version)
+    }
+
+    /// Appends a boot timing and saves
+    pub fn add_boot(&mut self, boot: BootTime) -> Result<()> {
+        self.append(|store| store.boots.push(boot))
+    }
+
+    /// Suspend cycles in the order they were run
+    pub fn suspends(&self) -> &[SuspendTestResult] {
+        &self.suspends
+    }
+
+    /// Failed suspend cycles of a kernel version
+    pub fn suspend_failures<'a>(
+        &'a self,
+        version: &'a str,
+    ) -> impl Iterator<Item = &'a SuspendTestResult> + 'a {
+        self.suspends
+            .iter()
+            .filter(move |result| !result.passed && result.kernel.version == version)
+    }
+
+    /// Appends a suspend cycle and saves
+    pub fn add_suspend(&mut self, result: SuspendTestResult) -> Result<()> {
+        self.append(|store| store.suspends.push(result))
+    }
+
+    /// Reloads under the writer lock, applies `push` and saves, so measurements recorded by
+    /// another process in the meantime are kept
+    fn append(&mut self, push: impl FnOnce(&mut Self)) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            Paths::ensure(parent)?;
+        }
+        let path = self.path.clone();
+        state_file::update(&path, |current| {
+            if let Some(text) = current {
+                *self = serde_json::from_str(&text)
+                    .with_context(|| format!("parsing {}", path.display()))?;
+                self.path = path.clone();
+            }
+            push(self);
+            Ok(serde_json::to_string_pretty(self)?)
+        })
+    }
+}
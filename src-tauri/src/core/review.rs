@@ -0,0 +1,319 @@
+// src-tauri/src/core/review.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::config::{ConfigValue, KernelConfig};
+use super::kconfig::{KconfigTree, SymbolDef};
+use super::popularity::{OptionPopularity, PopularityDataset};
+use super::resolver::ConfigLayer;
+use super::symbols::bare_name;
+use super::system::SystemFacade;
+
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// Area of the kernel a config option belongs to, in the order the report lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Subsystem {
+    Processor,
+    Scheduler,
+    Memory,
+    Block,
+    Filesystems,
+    Networking,
+    Drivers,
+    Virtualization,
+    Security,
+    Crypto,
+    Debugging,
+    General,
+}
+
+impl Subsystem {
+    /// Section heading in the report
+    pub fn title(self) -> &'static str {
+        match self {
+            Subsystem::Processor => "Processor and architecture",
+            Subsystem::Scheduler => "Scheduler and timers",
+            Subsystem::Memory => "Memory management",
+            Subsystem::Block => "Block layer",
+            Subsystem::Filesystems => "Filesystems",
+            Subsystem::Networking => "Networking",
+            Subsystem::Drivers => "Device drivers",
+            Subsystem::Virtualization => "Virtualization",
+            Subsystem::Security => "Security",
+            Subsystem::Crypto => "Cryptography",
+            Subsystem::Debugging => "Debugging and instrumentation",
+            Subsystem::General => "General setup",
+        }
+    }
+
+    /// Classifies by the Kconfig file declaring the option, falling back to its name
+    pub fn classify(name: &str, def: Option<&SymbolDef>) -> Self {
+        match def.and_then(|def| def.file.as_deref()) {
+            Some(file) => Subsystem::from_file(file),
+            None => Subsystem::from_name(bare_name(name)),
+        }
+    }
+
+    fn from_file(file: &str) -> Self {
+        let top = file.split('/').next().unwrap_or_default();
+        if file.contains("Kconfig.debug")
+            || file.contains("Kconfig.kasan")
+            || file.contains("Kconfig.ubsan")
+            || file.contains("Kconfig.kcsan")
+        {
+            Subsystem::Debugging
+        } else if file.starts_with("kernel/sched")
+            || file.starts_with("kernel/time")
+            || file == "kernel/Kconfig.hz"
+            || file == "kernel/Kconfig.preempt"
+        {
+            Subsystem::Scheduler
+        } else if file.starts_with("drivers/net") {
+            Subsystem::Networking
+        } else if file.starts_with("drivers/virtio")
+            || file.starts_with("drivers/hv")
+            || file.starts_with("arch/x86/kvm")
+            || top == "virt"
+        {
+            Subsystem::Virtualization
+        } else {
+            match top {
+                "arch" => Subsystem::Processor,
+                "mm" => Subsystem::Memory,
+                "block" => Subsystem::Block,
+                "fs" => Subsystem::Filesystems,
+                "net" => Subsystem::Networking,
+                "drivers" | "sound" => Subsystem::Drivers,
+                "security" => Subsystem::Security,
+                "crypto" => Subsystem::Crypto,
+                _ => Subsystem::General,
+            }
+        }
+    }
+
+    fn from_name(name: &str) -> Self {
+        let starts = |prefixes: &[&str]| prefixes.iter().any(|prefix| name.starts_with(prefix));
+        if starts(&[
+            "DEBUG_",
+            "KASAN",
+            "UBSAN",
+            "KCOV",
+            "KCSAN",
+            "PROVE_",
+            "LOCKDEP",
+            "FAULT_INJECTION",
+        ]) {
+            Subsystem::Debugging
+        } else if starts(&["HZ", "NO_HZ", "PREEMPT", "SCHED"]) {
+            Subsystem::Scheduler
+        } else if starts(&[
+            "NET",
+            "INET",
+            "IPV6",
+            "NF_",
+            "NETFILTER",
+            "TCP_",
+            "BRIDGE",
+            "WLAN",
+            "WIRELESS",
+        ]) {
+            Subsystem::Networking
+        } else if name.ends_with("_FS") || starts(&["EXT4", "BTRFS", "XFS", "NFS", "OVERLAY"]) {
+            Subsystem::Filesystems
+        } else if starts(&[
+            "TRANSPARENT_HUGEPAGE",
+            "ZSWAP",
+            "ZSMALLOC",
+            "SLUB",
+            "NUMA",
+            "MEMCG",
+            "HUGETLB",
+            "LRU_GEN",
+        ]) {
+            Subsystem::Memory
+        } else if starts(&["KVM", "VIRTIO", "HYPERV", "XEN", "VHOST"]) {
+            Subsystem::Virtualization
+        } else if starts(&[
+            "SECURITY",
+            "LSM",
+            "MODULE_SIG",
+            "LOCK_DOWN",
+            "HARDENED",
+            "STACKPROTECTOR",
+        ]) {
+            Subsystem::Security
+        } else if starts(&["CRYPTO"]) {
+            Subsystem::Crypto
+        } else if starts(&["BLK_", "BLOCK", "IOSCHED", "MQ_IOSCHED"]) {
+            Subsystem::Block
+        } else if starts(&[
+            "X86",
+            "CPU_",
+            "MCORE",
+            "MNATIVE",
+            "64BIT",
+            "SMP",
+            "NR_CPUS",
+            "EFI",
+            "MICROCODE",
+        ]) {
+            Subsystem::Processor
+        } else if starts(&[
+            "DRM", "USB", "SND", "SCSI", "ATA", "SATA", "NVME", "HID", "INPUT", "PCI", "I2C",
+            "SERIAL",
+        ]) {
+            Subsystem::Drivers
+        } else {
+            Subsystem::General
+        }
+    }
+}
+
+/// One option whose planned value differs from the distro configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deviation {
+    /// Bare symbol name
+    pub symbol: String,
+    /// Distro value, None when unset or absent
+    pub distro: Option<String>,
+    pub planned: Option<String>,
+    pub prompt: Option<String>,
+    /// First paragraph of the option's help text
+    pub rationale: Option<String>,
+    /// Last layer assigning the option, None when it changed through dependencies
+    pub layer: Option<String>,
+    /// How many major distros enable the option, None when the dataset lacks it
+    #[serde(default)]
+    pub popularity: Option<OptionPopularity>,
+}
+
+/// Deviations of a plan from the distro configuration, grouped by subsystem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewReport {
+    pub title: String,
+    pub sections: BTreeMap<Subsystem, Vec<Deviation>>,
+}
+
+impl ReviewReport {
+    /// Compares the planned config to the distro one, using the tree for metadata when available
+    pub fn build(
+        title: &str,
+        distro: &KernelConfig,
+        planned: &KernelConfig,
+        tree: Option<&KconfigTree>,
+        layers: &[ConfigLayer],
+    ) -> Self {
+        let mut origin = BTreeMap::new();
+        for layer in layers {
+            for (symbol, _) in &layer.assignments {
+                origin.insert(bare_name(symbol).to_string(), layer.name.clone());
+            }
+        }
+
+        let names: BTreeSet<&str> = distro
+            .iter()
+            .chain(planned.iter())
+            .map(|(name, _)| name)
+            .collect();
+        let popularity = PopularityDataset::bundled();
+        let mut sections: BTreeMap<Subsystem, Vec<Deviation>> = BTreeMap::new();
+        for name in names {
+            let before = distro.get(name).and_then(ConfigValue::as_str);
+            let after = planned.get(name).and_then(ConfigValue::as_str);
+            if before == after {
+                continue;
+            }
+            let def = tree.and_then(|tree| tree.def_by_name(name));
+            sections
+                .entry(Subsystem::classify(name, def))
+                .or_default()
+                .push(Deviation {
+                    symbol: name.to_string(),
+                    distro: before.map(str::to_string),
+                    planned: after.map(str::to_string),
+                    prompt: def.and_then(|def| def.prompt.clone()),
+                    rationale: def.and_then(|def| first_paragraph(&def.help)),
+                    layer: origin.get(name).cloned(),
+                    popularity: popularity.get(name),
+                });
+        }
+        ReviewReport {
+            title: title.to_string(),
+            sections,
+        }
+    }
+
+    /// Number of options that differ
+    pub fn len(&self) -> usize {
+        self.sections.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Renders the report as Markdown for pasting into forums and bug reports
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Config review: {}\n", self.title);
+        let _ = writeln!(
+            out,
+            "{} options differ from the distribution configuration.",
+            self.len()
+        );
+        for (subsystem, deviations) in &self.sections {
+            let _ = writeln!(out, "\n## {} ({})\n", subsystem.title(), deviations.len());
+            for deviation in deviations {
+                let _ = write!(
+                    out,
+                    "- `CONFIG_{}`: {} → {}",
+                    deviation.symbol,
+                    deviation.distro.as_deref().unwrap_or("not set"),
+                    deviation.planned.as_deref().unwrap_or("not set"),
+                );
+                match &deviation.layer {
+                    Some(layer) => {
+                        let _ = writeln!(out, " (set by {})", layer);
+                    }
+                    None => {
+                        let _ = writeln!(out, " (follows from dependencies)");
+                    }
+                }
+                if let Some(prompt) = &deviation.prompt {
+                    let _ = writeln!(out, "  *{}*", prompt);
+                }
+                if let Some(rationale) = &deviation.rationale {
+                    let _ = writeln!(out, "  {}", rationale);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Config of the running distro kernel from /boot/config-<release>
+pub fn distro_config(system: &dyn SystemFacade) -> Result<KernelConfig> {
+    let release = system
+        .read_trimmed(Path::new(OSRELEASE))
+        .context("reading kernel release")?;
+    let path = Path::new("/boot").join(format!("config-{}", release));
+    let text = system
+        .read_to_string(&path)
+        .with_context(|| format!("reading {}", path.display()))?;
+    KernelConfig::parse(&text)
+}
+
+/// First help paragraph joined onto one line
+fn first_paragraph(help: &str) -> Option<String> {
+    let paragraph = help
+        .split("\n\n")
+        .map(str::trim)
+        .find(|paragraph| !paragraph.is_empty())?;
+    Some(paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+}
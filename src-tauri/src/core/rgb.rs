@@ -0,0 +1,226 @@
+// src-tauri/src/core/rgb.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::explain::Explanation;
+use super::requirements::{self, MachineRequirements};
+use super::system::SystemFacade;
+
+const SYS_I2C: &str = "/sys/bus/i2c/devices";
+
+/// A program that drives lighting, fans or pumps from userspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RgbTool {
+    /// OpenRGB, which reaches RAM and motherboard lighting over SMBus and the rest over USB
+    OpenRgb,
+    /// liquidctl, for AIO coolers, fan hubs and PSUs over USB HID
+    Liquidctl,
+    /// ckb-next, the driver for Corsair keyboards and mice
+    CkbNext,
+}
+
+impl RgbTool {
+    pub const ALL: [RgbTool; 3] = [RgbTool::OpenRgb, RgbTool::Liquidctl, RgbTool::CkbNext];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RgbTool::OpenRgb => "OpenRGB",
+            RgbTool::Liquidctl => "liquidctl",
+            RgbTool::CkbNext => "ckb-next",
+        }
+    }
+
+    fn programs(self) -> &'static [&'static str] {
+        match self {
+            RgbTool::OpenRgb => &["openrgb"],
+            RgbTool::Liquidctl => &["liquidctl"],
+            RgbTool::CkbNext => &["ckb-next", "ckb-next-daemon"],
+        }
+    }
+
+    /// Options the tool talks to its devices through, whatever adapters the machine has
+    fn requirements(self) -> Vec<ConfigSuggestion> {
+        let hidraw = ConfigSuggestion::new(
+            "CONFIG_HIDRAW",
+            ConfigValue::yes(),
+            "USB lighting and cooling devices are driven through /dev/hidraw*",
+        );
+        let usb_hid = ConfigSuggestion::new(
+            "CONFIG_USB_HID",
+            ConfigValue::module(),
+            "binds the HID interfaces of USB lighting and cooling devices",
+        );
+        match self {
+            RgbTool::OpenRgb => vec![
+                ConfigSuggestion::new(
+                    "CONFIG_I2C",
+                    ConfigValue::yes(),
+                    "RAM and motherboard lighting controllers sit on the SMBus",
+                ),
+                ConfigSuggestion::new(
+                    "CONFIG_I2C_CHARDEV",
+                    ConfigValue::module(),
+                    "i2c-dev, the /dev/i2c-* devices OpenRGB reaches SMBus lighting through",
+                ),
+                hidraw,
+                usb_hid,
+            ],
+            RgbTool::Liquidctl => vec![hidraw, usb_hid],
+            RgbTool::CkbNext => vec![ConfigSuggestion::new(
+                "CONFIG_INPUT_UINPUT",
+                ConfigValue::module(),
+                "ckb-next replays key presses through a virtual uinput device",
+            )],
+        }
+    }
+}
+
+/// RGB and cooling tools installed on the machine and the SMBus adapters OpenRGB can use
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RgbSetup {
+    pub tools: Vec<RgbTool>,
+    /// Names of the SMBus adapters, e.g. `SMBus PIIX4 adapter port 0 at 0b00`
+    pub smbus_adapters: Vec<String>,
+}
+
+impl RgbSetup {
+    /// Detects installed RGB tools, None when there are none
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let tools: Vec<RgbTool> = RgbTool::ALL
+            .into_iter()
+            .filter(|tool| {
+                tool.programs()
+                    .iter()
+                    .any(|program| system.has_program(program))
+            })
+            .collect();
+        if tools.is_empty() {
+            return None;
+        }
+        let mut smbus_adapters: Vec<String> = system
+            .read_dir(Path::new(SYS_I2C))
+            .unwrap_or_default()
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("i2c-"))
+            })
+            .filter_map(|path| system.read_trimmed(&path.join("name")))
+            .filter(|name| name.starts_with("SMBus"))
+            .collect();
+        smbus_adapters.sort();
+        smbus_adapters.dedup();
+        Some(RgbSetup {
+            tools,
+            smbus_adapters,
+        })
+    }
+
+    fn smbus_requirements(&self) -> Vec<ConfigSuggestion> {
+        let mut required = Vec::new();
+        if self
+            .smbus_adapters
+            .iter()
+            .any(|adapter| adapter.contains("I801"))
+        {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_I2C_I801",
+                ConfigValue::module(),
+                "the Intel SMBus controller RGB memory is reached through",
+            ));
+        }
+        if self
+            .smbus_adapters
+            .iter()
+            .any(|adapter| adapter.contains("PIIX4"))
+        {
+            required.push(ConfigSuggestion::new(
+                "CONFIG_I2C_PIIX4",
+                ConfigValue::module(),
+                "the AMD SMBus controller RGB memory is reached through",
+            ));
+        }
+        required
+    }
+
+    fn names(&self) -> String {
+        let names: Vec<&str> = self.tools.iter().map(|tool| tool.name()).collect();
+        names.join(", ")
+    }
+
+    /// Entry for the plan report saying which options `config` keeps for the tools and why,
+    /// with those it leaves out as risks
+    pub fn explanation(&self, config: &KernelConfig) -> Explanation {
+        let (kept, missing): (Vec<ConfigSuggestion>, Vec<ConfigSuggestion>) = self
+            .required()
+            .into_iter()
+            .partition(|requirement| config.is_enabled(&requirement.symbol));
+        let short = |requirements: &[ConfigSuggestion]| -> Vec<String> {
+            requirements
+                .iter()
+                .map(|requirement| requirement.symbol.trim_start_matches("CONFIG_").to_string())
+                .collect()
+        };
+        let mut risks = Vec::new();
+        if !missing.is_empty() {
+            risks.push(format!(
+                "The plan leaves out {}, so {} cannot reach the devices that need them",
+                short(&missing).join(", "),
+                self.names()
+            ));
+        }
+        Explanation {
+            headline: format!("Kept for {}", self.names()),
+            why: String::from(
+                "Lighting and cooling tools drive their devices from userspace through raw HID, \
+                 the SMBus and uinput, which pruning would otherwise drop as unused.",
+            ),
+            benefits: vec![String::from(
+                "Lighting profiles, fan curves and pump speeds keep working",
+            )],
+            risks,
+            symbols: short(&kept),
+            layers: vec![String::from("rgb")],
+        }
+    }
+}
+
+impl MachineRequirements for RgbSetup {
+    fn name(&self) -> &'static str {
+        "rgb"
+    }
+
+    /// Options the tools need, each symbol once, with the SMBus controller drivers
+    /// OpenRGB needs on this machine
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let tools = self.tools.iter().flat_map(|tool| tool.requirements());
+        let smbus = if self.tools.contains(&RgbTool::OpenRgb) {
+            self.smbus_requirements()
+        } else {
+            Vec::new()
+        };
+        requirements::unique(tools.chain(smbus))
+    }
+
+    /// Warns when the plan leaves out what the tools talk to their devices through
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        let mut advisory = Advisory::new(
+            Severity::Warning,
+            "rgb-missing-config",
+            &[("tools", self.names()), ("symbols", symbols.join(", "))],
+        );
+        for requirement in missing {
+            advisory = advisory.suggest(requirement);
+        }
+        vec![advisory]
+    }
+}
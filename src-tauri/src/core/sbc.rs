@@ -0,0 +1,270 @@
+// src-tauri/src/core/sbc.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use super::config::ConfigValue;
+use super::dtb::stage_dtbs;
+use super::paths::Paths;
+use super::plan::{BuildPlan, TargetArch};
+use super::resolver::ConfigLayer;
+
+/// SoC family, which decides the defconfig, console and how the board boots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SbcFamily {
+    /// Broadcom SoCs, booted by the Pi firmware or U-Boot on top of it
+    RaspberryPi,
+    /// Rockchip SoCs, booted by U-Boot from SPI flash or eMMC
+    Rockchip,
+    /// StarFive RISC-V SoCs, booted by OpenSBI and U-Boot from SPI flash
+    StarFive,
+}
+
+/// A single-board computer KernelForge can build for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SbcBoard {
+    /// Identifier stored in plans, e.g. `rpi4`
+    pub id: &'static str,
+    pub name: &'static str,
+    pub family: SbcFamily,
+    pub arch: TargetArch,
+    /// Device tree blob relative to arch/<arch>/boot/dts
+    pub dtb: &'static str,
+    /// Config target producing the vendor's base configuration
+    pub defconfig: &'static str,
+    /// Source provider with the best support for the board
+    pub source: &'static str,
+    /// Serial console for the kernel command line
+    pub console: &'static str,
+    /// Platform and driver options the board cannot boot without
+    pub symbols: &'static [&'static str],
+}
+
+/// Boards homelab users run most, maintained by hand
+pub const BOARDS: &[SbcBoard] = &[
+    SbcBoard {
+        id: "rpi3",
+        name: "Raspberry Pi 3 Model B",
+        family: SbcFamily::RaspberryPi,
+        arch: TargetArch::Arm64,
+        dtb: "broadcom/bcm2837-rpi-3-b.dtb",
+        defconfig: "bcmrpi3_defconfig",
+        source: "raspberrypi",
+        console: "ttyS0,115200",
+        symbols: &[
+            "ARCH_BCM2835",
+            "MMC_BCM2835",
+            "SERIAL_8250_BCM2835AUX",
+            "USB_DWC2",
+        ],
+    },
+    SbcBoard {
+        id: "rpi4",
+        name: "Raspberry Pi 4 Model B",
+        family: SbcFamily::RaspberryPi,
+        arch: TargetArch::Arm64,
+        dtb: "broadcom/bcm2711-rpi-4-b.dtb",
+        defconfig: "bcm2711_defconfig",
+        source: "raspberrypi",
+        console: "ttyS0,115200",
+        symbols: &[
+            "ARCH_BCM2835",
+            "MMC_SDHCI_IPROC",
+            "BCMGENET",
+            "PCIE_BRCMSTB",
+        ],
+    },
+    SbcBoard {
+        id: "rpi5",
+        name: "Raspberry Pi 5",
+        family: SbcFamily::RaspberryPi,
+        arch: TargetArch::Arm64,
+        dtb: "broadcom/bcm2712-rpi-5-b.dtb",
+        defconfig: "bcm2712_defconfig",
+        source: "raspberrypi",
+        console: "ttyAMA10,115200",
+        symbols: &[
+            "ARCH_BCM2835",
+            "MMC_SDHCI_BRCMSTB",
+            "PCIE_BRCMSTB",
+            "MFD_RP1",
+        ],
+    },
+    SbcBoard {
+        id: "rockpro64",
+        name: "Pine64 RockPro64 (RK3399)",
+        family: SbcFamily::Rockchip,
+        arch: TargetArch::Arm64,
+        dtb: "rockchip/rk3399-rockpro64.dtb",
+        defconfig: "defconfig",
+        source: "kernel.org",
+        console: "ttyS2,1500000",
+        symbols: &[
+            "ARCH_ROCKCHIP",
+            "MMC_DW_ROCKCHIP",
+            "DWMAC_ROCKCHIP",
+            "SERIAL_8250_DW",
+        ],
+    },
+    SbcBoard {
+        id: "rock5b",
+        name: "Radxa ROCK 5B (RK3588)",
+        family: SbcFamily::Rockchip,
+        arch: TargetArch::Arm64,
+        dtb: "rockchip/rk3588-rock-5b.dtb",
+        defconfig: "defconfig",
+        source: "kernel.org",
+        console: "ttyS2,1500000",
+        symbols: &[
+            "ARCH_ROCKCHIP",
+            "MMC_SDHCI_OF_DWCMSHC",
+            "STMMAC_ETH",
+            "SERIAL_8250_DW",
+        ],
+    },
+    SbcBoard {
+        id: "orangepi5",
+        name: "Orange Pi 5 (RK3588S)",
+        family: SbcFamily::Rockchip,
+        arch: TargetArch::Arm64,
+        dtb: "rockchip/rk3588s-orangepi-5.dtb",
+        defconfig: "defconfig",
+        source: "kernel.org",
+        console: "ttyS2,1500000",
+        symbols: &[
+            "ARCH_ROCKCHIP",
+            "MMC_SDHCI_OF_DWCMSHC",
+            "STMMAC_ETH",
+            "SERIAL_8250_DW",
+        ],
+    },
+    SbcBoard {
+        id: "visionfive2",
+        name: "StarFive VisionFive 2 (JH7110)",
+        family: SbcFamily::StarFive,
+        arch: TargetArch::Riscv64,
+        dtb: "starfive/jh7110-starfive-visionfive-2-v1.3b.dtb",
+        defconfig: "defconfig",
+        source: "kernel.org",
+        console: "ttyS0,115200",
+        symbols: &[
+            "ARCH_STARFIVE",
+            "MMC_DW_STARFIVE",
+            "DWMAC_STARFIVE",
+            "SERIAL_8250_DW",
+        ],
+    },
+];
+
+impl SbcBoard {
+    pub fn get(id: &str) -> Option<&'static SbcBoard> {
+        BOARDS.iter().find(|board| board.id == id)
+    }
+
+    /// Boards this build can target; experimental architectures are left out unless enabled
+    pub fn available() -> impl Iterator<Item = &'static SbcBoard> {
+        BOARDS.iter().filter(|board| board.arch.is_available())
+    }
+
+    /// Options layered over the defconfig so a trimmed config keeps the board bootable
+    pub fn layer(&self) -> ConfigLayer {
+        let mut layer = ConfigLayer::new(self.id);
+        for symbol in self.symbols {
+            layer.set(&format!("CONFIG_{}", symbol), ConfigValue::yes());
+        }
+        // Firmware hands over a device tree and the root is on SD or eMMC
+        for symbol in [
+            "CONFIG_OF",
+            "CONFIG_MMC",
+            "CONFIG_MMC_BLOCK",
+            "CONFIG_EXT4_FS",
+        ] {
+            layer.set(symbol, ConfigValue::yes());
+        }
+        layer
+    }
+
+    /// Built blob for the board in a source tree
+    pub fn dtb_path(&self, source_dir: &Path) -> PathBuf {
+        source_dir
+            .join(format!("arch/{}/boot/dts", self.arch.make_arch()))
+            .join(self.dtb)
+    }
+
+    /// U-Boot extlinux.conf booting the kernel and the board's blob from the boot partition
+    pub fn extlinux_conf(&self, label: &str, root: &str) -> String {
+        format!(
+            "default {label}\n\
+             timeout 3\n\n\
+             label {label}\n\
+             \x20   kernel /{image}\n\
+             \x20   fdt /dtbs/{dtb}\n\
+             \x20   append root={root} rootwait console={console}\n",
+            label = label,
+            image = self.arch.image_target(),
+            dtb = self.dtb,
+            root = root,
+            console = self.console,
+        )
+    }
+
+    /// Lines for the Pi firmware's config.txt when booting without U-Boot, None for
+    /// boards the firmware cannot boot directly
+    pub fn firmware_config(&self) -> Option<String> {
+        if self.family != SbcFamily::RaspberryPi {
+            return None;
+        }
+        // The blob is staged under dtbs/ with its vendor directory, as extlinux.conf expects
+        Some(format!(
+            "arm_64bit=1\nkernel={}\ndevice_tree=dtbs/{}\n",
+            self.arch.image_target(),
+            self.dtb
+        ))
+    }
+
+    /// Lays out what a board's boot partition needs below `destination`: the kernel image,
+    /// the selected blobs under dtbs/, extlinux/extlinux.conf and, for Pis, a config.txt
+    /// fragment
+    pub fn stage_boot_files(
+        &self,
+        plan: &BuildPlan,
+        source_dir: &Path,
+        destination: &Path,
+        root: &str,
+    ) -> Result<Vec<PathBuf>> {
+        if plan.board.as_deref() != Some(self.id) {
+            bail!("the plan is not for {}", self.name);
+        }
+        let image = source_dir.join(plan.arch.image_path());
+        if !image.exists() {
+            bail!("{} has not been built", image.display());
+        }
+
+        let mut staged = stage_dtbs(plan, source_dir, destination)?;
+        Paths::ensure(destination)?;
+        let target = destination.join(plan.arch.image_target());
+        fs::copy(&image, &target).with_context(|| format!("copying {}", image.display()))?;
+        staged.push(target);
+
+        let mut write = |to: PathBuf, text: String| -> Result<()> {
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("creating {}", parent.display()))?;
+            }
+            fs::write(&to, text).with_context(|| format!("writing {}", to.display()))?;
+            staged.push(to);
+            Ok(())
+        };
+        write(
+            destination.join("extlinux/extlinux.conf"),
+            self.extlinux_conf(&plan.name, root),
+        )?;
+        if let Some(config) = self.firmware_config() {
+            write(destination.join("config-kernelforge.txt"), config)?;
+        }
+        Ok(staged)
+    }
+}
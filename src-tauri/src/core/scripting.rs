@@ -0,0 +1,447 @@
+// src-tauri/src/core/scripting.rs
+
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+use serde::{Deserialize, Serialize};
+
+use super::bootloader::BootEntryRequest;
+use super::cmdline::{is_protected, CmdlinePlan};
+use super::config::{ConfigValue, KernelConfig};
+use super::hibernate::HibernateSetup;
+use super::paths::Paths;
+use super::plan::BuildPlan;
+use super::resolver::ConfigLayer;
+use super::state_file;
+use super::system::{run_spec, CommandSpec, SystemFacade};
+
+/// Extension of hook scripts; anything else in a hook directory is left alone
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Operations a script may run before it is stopped, far beyond what a tweak needs
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Where in a build user scripts run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HookPoint {
+    /// Before the config is written to the source tree
+    PreConfigure,
+    /// After the config is settled, last chance to change it before the build
+    PostConfigure,
+    /// Before the built kernel is installed; only the command line can still change
+    PreInstall,
+}
+
+impl HookPoint {
+    pub const ALL: [HookPoint; 3] = [
+        HookPoint::PreConfigure,
+        HookPoint::PostConfigure,
+        HookPoint::PreInstall,
+    ];
+
+    /// Directory name under the hooks directory, also the `hook` constant scripts see
+    pub fn name(self) -> &'static str {
+        match self {
+            HookPoint::PreConfigure => "pre-configure",
+            HookPoint::PostConfigure => "post-configure",
+            HookPoint::PreInstall => "pre-install",
+        }
+    }
+
+    /// Whether scripts may still change the config
+    pub fn can_configure(self) -> bool {
+        self != HookPoint::PreInstall
+    }
+}
+
+/// What one script changed and reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookReport {
+    /// File name of the script
+    pub script: String,
+    /// Config assignments the script made, named after it
+    pub config: ConfigLayer,
+    /// Command line parameters the script added
+    pub cmdline: Vec<String>,
+    /// Lines the script printed
+    pub messages: Vec<String>,
+}
+
+/// Scripts installed for `point`, in file name order like run-parts
+pub fn hook_scripts(paths: &Paths, point: HookPoint) -> Result<Vec<PathBuf>> {
+    let dir = paths.hooks_dir().join(point.name());
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut scripts = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == SCRIPT_EXTENSION)
+        {
+            scripts.push(path);
+        }
+    }
+    scripts.sort();
+    Ok(scripts)
+}
+
+/// Plan and command line after a hook point's scripts ran, with what each did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    pub plan: BuildPlan,
+    pub cmdline: Option<CmdlinePlan>,
+    pub reports: Vec<HookReport>,
+}
+
+/// Runs every script installed for `point` against the plan, each seeing what the ones
+/// before it changed; a failing script fails the run, so nothing is half applied
+pub fn run_hooks(
+    paths: &Paths,
+    point: HookPoint,
+    plan: BuildPlan,
+    cmdline: Option<CmdlinePlan>,
+) -> Result<HookRun> {
+    let mut run = HookRun {
+        plan,
+        cmdline,
+        reports: Vec::new(),
+    };
+    for path in hook_scripts(paths, point)? {
+        let source =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let report = run_script(point, &path, &source, &run.plan, run.cmdline.as_ref())
+            .with_context(|| format!("{} hook {} failed", point.name(), path.display()))?;
+        for (symbol, value) in &report.config.assignments {
+            run.plan.config.set(symbol, value.clone());
+        }
+        if let Some(cmdline) = run.cmdline.as_mut() {
+            for param in &report.cmdline {
+                cmdline.add(param);
+            }
+        }
+        run.reports.push(report);
+    }
+    Ok(run)
+}
+
+/// Configure stage of a build: runs the pre-configure hooks, writes the config into
+/// `source_dir` and settles it with `make olddefconfig`, then runs the post-configure hooks
+/// and settles their edits again, so the build never starts from a config Kconfig rejects
+/// `env` is the build's, so Kconfig probes the compiler that will build
+pub fn configure_tree(
+    paths: &Paths,
+    system: &dyn SystemFacade,
+    plan: BuildPlan,
+    source_dir: &Path,
+    env: &[(String, String)],
+) -> Result<HookRun> {
+    let mut run = run_hooks(paths, HookPoint::PreConfigure, plan, None)?;
+    run.plan.config = olddefconfig(system, &run.plan.config, source_dir, env)?;
+    let post = run_hooks(paths, HookPoint::PostConfigure, run.plan, None)?;
+    run.plan = post.plan;
+    if post
+        .reports
+        .iter()
+        .any(|report| !report.config.assignments.is_empty())
+    {
+        run.plan.config = olddefconfig(system, &run.plan.config, source_dir, env)?;
+    }
+    run.reports.extend(post.reports);
+    Ok(run)
+}
+
+/// Command line of the new kernel's entry before the pre-install hooks: `cmdline` with the
+/// plan's parameters and, on a hibernating machine, the running kernel's resume parameters
+pub fn entry_cmdline(
+    system: &dyn SystemFacade,
+    plan: &BuildPlan,
+    mut cmdline: CmdlinePlan,
+) -> CmdlinePlan {
+    plan.carry_cmdline(&mut cmdline);
+    if let Some(setup) = HibernateSetup::detect(system) {
+        setup.carry_resume(&mut cmdline);
+    }
+    cmdline
+}
+
+/// Install stage: the boot entry for the kernel built from `plan`, whose command line is
+/// the entry's from `entry_cmdline` and then goes through the pre-install hooks
+pub fn boot_entry(
+    paths: &Paths,
+    system: &dyn SystemFacade,
+    plan: &BuildPlan,
+    version: &str,
+    kernel_image: &Path,
+    initramfs: Option<&Path>,
+    cmdline: CmdlinePlan,
+) -> Result<(BootEntryRequest, Vec<HookReport>)> {
+    let cmdline = entry_cmdline(system, plan, cmdline);
+    let run = run_hooks(paths, HookPoint::PreInstall, plan.clone(), Some(cmdline))?;
+    let cmdline = run
+        .cmdline
+        .as_ref()
+        .map(CmdlinePlan::render)
+        .unwrap_or_default();
+    Ok((
+        BootEntryRequest {
+            version: version.to_string(),
+            kernel_image: kernel_image.to_path_buf(),
+            initramfs: initramfs.map(Path::to_path_buf),
+            cmdline,
+        },
+        run.reports,
+    ))
+}
+
+/// Writes `config` as the tree's .config and returns it as `make olddefconfig` left it
+fn olddefconfig(
+    system: &dyn SystemFacade,
+    config: &KernelConfig,
+    source_dir: &Path,
+    env: &[(String, String)],
+) -> Result<KernelConfig> {
+    let dot_config = source_dir.join(".config");
+    fs::write(&dot_config, config.emit())
+        .with_context(|| format!("writing {}", dot_config.display()))?;
+    let mut spec = CommandSpec::new("make", &["olddefconfig"]);
+    spec.env = env.to_vec();
+    spec.cwd = Some(source_dir.to_path_buf());
+    let output = run_spec(&system.prepare_command(spec)).context("running make olddefconfig")?;
+    if !output.success() {
+        bail!("make olddefconfig failed: {}", output.stderr.trim());
+    }
+    let text = fs::read_to_string(&dot_config)
+        .with_context(|| format!("reading {}", dot_config.display()))?;
+    KernelConfig::parse(&text).context("parsing the olddefconfig output")
+}
+
+/// State a running script reads and changes through the registered functions
+struct ScriptState {
+    config: KernelConfig,
+    layer: ConfigLayer,
+    cmdline: Option<CmdlinePlan>,
+    added: Vec<String>,
+    messages: Vec<String>,
+}
+
+/// Runs one script in a fresh engine
+/// Scripts get the plan's identity as constants and a handful of functions:
+/// `get`, `enabled`, `set`, `enable`, `enable_module`, `disable`, `cmdline` and `cmdline_add`
+pub fn run_script(
+    point: HookPoint,
+    path: &Path,
+    source: &str,
+    plan: &BuildPlan,
+    cmdline: Option<&CmdlinePlan>,
+) -> Result<HookReport> {
+    let script = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let state = Rc::new(RefCell::new(ScriptState {
+        config: plan.config.clone(),
+        layer: ConfigLayer::new(&format!("hook:{}", script)),
+        cmdline: cmdline.cloned(),
+        added: Vec::new(),
+        messages: Vec::new(),
+    }));
+
+    let mut engine = sandboxed_engine();
+    let printed = state.clone();
+    engine.on_print(move |text| printed.borrow_mut().messages.push(text.to_string()));
+    let printed = state.clone();
+    engine.on_debug(move |text, _, _| printed.borrow_mut().messages.push(text.to_string()));
+
+    let reader = state.clone();
+    engine.register_fn("get", move |symbol: &str| -> Dynamic {
+        match reader
+            .borrow()
+            .config
+            .get(symbol)
+            .and_then(|value| value.as_str())
+        {
+            Some(value) => value.to_string().into(),
+            None => Dynamic::UNIT,
+        }
+    });
+    let reader = state.clone();
+    engine.register_fn("enabled", move |symbol: &str| {
+        reader.borrow().config.is_enabled(symbol)
+    });
+
+    let setter = Setter {
+        state: state.clone(),
+        point,
+    };
+    let set = setter.clone();
+    engine.register_fn("set", move |symbol: &str, value: &str| {
+        set.apply(symbol, ConfigValue::Set(value.to_string()))
+    });
+    let set = setter.clone();
+    engine.register_fn("set", move |symbol: &str, value: i64| {
+        set.apply(symbol, ConfigValue::int(value))
+    });
+    let set = setter.clone();
+    engine.register_fn("set", move |symbol: &str, value: bool| {
+        let value = if value {
+            ConfigValue::yes()
+        } else {
+            ConfigValue::NotSet
+        };
+        set.apply(symbol, value)
+    });
+    let set = setter.clone();
+    engine.register_fn("enable", move |symbol: &str| {
+        set.apply(symbol, ConfigValue::yes())
+    });
+    let set = setter.clone();
+    engine.register_fn("enable_module", move |symbol: &str| {
+        set.apply(symbol, ConfigValue::module())
+    });
+    let set = setter;
+    engine.register_fn("disable", move |symbol: &str| {
+        set.apply(symbol, ConfigValue::NotSet)
+    });
+
+    let reader = state.clone();
+    engine.register_fn("cmdline", move || -> Dynamic {
+        match &reader.borrow().cmdline {
+            Some(cmdline) => cmdline.render().into(),
+            None => Dynamic::UNIT,
+        }
+    });
+    let writer = state.clone();
+    engine.register_fn(
+        "cmdline_add",
+        move |param: &str| -> Result<(), Box<EvalAltResult>> {
+            let mut state = writer.borrow_mut();
+            let Some(cmdline) = state.cmdline.as_mut() else {
+                return Err(format!(
+                    "the command line cannot be changed at {}, use a pre-install hook",
+                    point.name()
+                )
+                .into());
+            };
+            let param = param.trim();
+            // Whitespace is only part of a parameter inside a quoted value
+            if param.is_empty() || (param.contains(char::is_whitespace) && !param.contains('"')) {
+                return Err(format!("{:?} is not a single kernel parameter", param).into());
+            }
+            if is_protected(param) {
+                return Err(format!(
+                    "{} finds or unlocks the root filesystem and is left as configured",
+                    param
+                )
+                .into());
+            }
+            cmdline.add(param);
+            state.added.push(param.to_string());
+            Ok(())
+        },
+    );
+
+    let mut scope = Scope::new();
+    scope.push_constant("hook", point.name().to_string());
+    scope.push_constant("plan_name", plan.name.clone());
+    scope.push_constant("version", plan.version.clone());
+    scope.push_constant("arch", plan.arch.make_arch().to_string());
+    engine
+        .run_with_scope(&mut scope, source)
+        .map_err(|error| anyhow!("{}", error))?;
+    drop(engine);
+
+    let state = Rc::try_unwrap(state)
+        .map_err(|_| anyhow!("script state is still shared after the run"))?
+        .into_inner();
+    Ok(HookReport {
+        script,
+        config: state.layer,
+        cmdline: state.added,
+        messages: state.messages,
+    })
+}
+
+/// Registered config setter, refusing changes once the kernel is built
+#[derive(Clone)]
+struct Setter {
+    state: Rc<RefCell<ScriptState>>,
+    point: HookPoint,
+}
+
+impl Setter {
+    fn apply(&self, symbol: &str, value: ConfigValue) -> Result<(), Box<EvalAltResult>> {
+        if !self.point.can_configure() {
+            return Err(format!(
+                "the kernel is already built, {} cannot change the config",
+                self.point.name()
+            )
+            .into());
+        }
+        let name = symbol.strip_prefix("CONFIG_").unwrap_or(symbol);
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!("{} is not a Kconfig symbol", symbol).into());
+        }
+        let symbol = format!("CONFIG_{}", name);
+        let mut state = self.state.borrow_mut();
+        state.config.set(&symbol, value.clone());
+        state.layer.set(&symbol, value);
+        Ok(())
+    }
+}
+
+/// Engine without file access and with bounded work and memory, so a broken script
+/// fails instead of hanging or exhausting the application
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(64 * 1024)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000);
+    // eval would run code the operation limit was not set up for at compile time
+    engine.disable_symbol("eval");
+    engine
+}
+
+/// Checks a script parses, for the editor to report mistakes before a build runs it
+pub fn check_script(source: &str) -> Result<()> {
+    sandboxed_engine()
+        .compile(source)
+        .map(|_| ())
+        .map_err(|error| anyhow!("{}", error))
+}
+
+/// Writes a script for `point`, replacing one with the same name
+pub fn save_script(paths: &Paths, point: HookPoint, name: &str, source: &str) -> Result<PathBuf> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        bail!("{} is not a valid script name", name);
+    }
+    check_script(source)?;
+    let dir = Paths::ensure(paths.hooks_dir().join(point.name()))?;
+    let file = if name.ends_with(&format!(".{}", SCRIPT_EXTENSION)) {
+        name.to_string()
+    } else {
+        format!("{}.{}", name, SCRIPT_EXTENSION)
+    };
+    let path = dir.join(file);
+    state_file::write(&path, source.as_bytes())?;
+    Ok(path)
+}
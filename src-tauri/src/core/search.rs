@@ -0,0 +1,144 @@
+// src-tauri/src/core/search.rs
+
+use serde::{Deserialize, Serialize};
+
+use super::config::KernelConfig;
+use super::kconfig::KconfigTree;
+use super::popularity::{OptionPopularity, PopularityDataset};
+
+/// Results returned when the caller does not ask for a limit
+pub const DEFAULT_LIMIT: usize = 50;
+
+/// A symbol and its prompt, lowercased once so queries only compare bytes
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    name: String,
+    prompt: Option<String>,
+    name_lower: String,
+    prompt_lower: String,
+}
+
+/// One search result for the config browser
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub symbol: String,
+    pub prompt: Option<String>,
+    /// Current value in the loaded config, None when unset
+    pub value: Option<String>,
+    pub score: u32,
+    /// How many major distros enable the option, None when the dataset lacks it
+    #[serde(default)]
+    pub popularity: Option<OptionPopularity>,
+}
+
+/// Prebuilt index over every symbol name and prompt of a Kconfig tree
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    entries: Vec<IndexEntry>,
+    popularity: PopularityDataset,
+}
+
+impl SearchIndex {
+    /// Indexes every defined symbol; built once per source tree
+    pub fn build(tree: &KconfigTree) -> Self {
+        let entries = tree
+            .defs()
+            .iter()
+            .map(|def| {
+                let name = tree.symbols().name(def.id).to_string();
+                let prompt = def.prompt.clone();
+                IndexEntry {
+                    name_lower: name.to_ascii_lowercase(),
+                    prompt_lower: prompt.as_deref().unwrap_or_default().to_lowercase(),
+                    name,
+                    prompt,
+                }
+            })
+            .collect();
+        SearchIndex {
+            entries,
+            popularity: PopularityDataset::bundled(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Best matches for `query`, ranked by match quality with enabled options first on ties
+    /// A `CONFIG_` prefix on the query is ignored
+    pub fn search(
+        &self,
+        query: &str,
+        config: Option<&KernelConfig>,
+        limit: usize,
+    ) -> Vec<SearchHit> {
+        let query = query.trim().to_lowercase();
+        let query = query.strip_prefix("config_").unwrap_or(&query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut hits: Vec<(u32, bool, &IndexEntry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let name = match_score(&entry.name_lower, query).map(|score| score * 2);
+                let prompt = match_score(&entry.prompt_lower, query);
+                let score = name.max(prompt)?;
+                let enabled = config.is_some_and(|config| config.is_enabled(&entry.name));
+                Some((score, enabled, entry))
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| b.1.cmp(&a.1))
+                .then_with(|| a.2.name.cmp(&b.2.name))
+        });
+        hits.truncate(limit);
+        hits.into_iter()
+            .map(|(score, _, entry)| SearchHit {
+                symbol: entry.name.clone(),
+                prompt: entry.prompt.clone(),
+                value: config
+                    .and_then(|config| config.get(&entry.name))
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                score,
+                popularity: self.popularity.get(&entry.name),
+            })
+            .collect()
+    }
+}
+
+/// Scores how well `query` matches `text`: exact, prefix, word start, substring, then subsequence
+fn match_score(text: &str, query: &str) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+    if text == query {
+        return Some(100);
+    }
+    if text.starts_with(query) {
+        return Some(80);
+    }
+    if let Some(position) = text.find(query) {
+        let word_start = text[..position].ends_with(['_', ' ', '-', '(']);
+        return Some(if word_start { 60 } else { 40 });
+    }
+    // Fuzzy: every query character appears in order; tighter spans score higher
+    let mut chars = text.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.chars() {
+        let (index, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(index);
+        last = index;
+    }
+    let span = (last - first.unwrap_or(0) + 1) as u32;
+    let spread = span.saturating_sub(query.len() as u32);
+    Some(20u32.saturating_sub(spread).max(1))
+}
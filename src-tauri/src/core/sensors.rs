@@ -0,0 +1,290 @@
+// src-tauri/src/core/sensors.rs
+
+use std::iter;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue};
+use super::requirements::{self, MachineRequirements};
+use super::system::SystemFacade;
+
+const SYS_HWMON: &str = "/sys/class/hwmon";
+
+/// Where sensors-detect records the modules it found: the lm_sensors service settings on
+/// Arch-like and Fedora-like systems, /etc/modules on Debian-like ones and modules-load.d
+const LM_SENSORS_MODULES: &[&str] = &[
+    "/etc/conf.d/lm_sensors",
+    "/etc/sysconfig/lm_sensors",
+    "/etc/modules",
+    "/etc/modules-load.d",
+];
+
+/// What the embedded systems removal drops wholesale: the buses sensor chips sit on and
+/// the hwmon core even Super-I/O chips register with
+const BLANKET_REMOVALS: &[&str] = &["CONFIG_I2C", "CONFIG_SPI", "CONFIG_HWMON"];
+
+const SENSORS_CONFIGS: &[&str] = &["/etc/sensors3.conf", "/etc/sensors.d"];
+const FANCONTROL_CONFIG: &str = "/etc/fancontrol";
+
+/// hwmon chip names, or the module names lm-sensors lists, and the option of their driver;
+/// the first matching prefix wins
+const CHIP_DRIVERS: &[(&str, &str)] = &[
+    ("nct668", "CONFIG_SENSORS_NCT6683"),
+    ("nct67", "CONFIG_SENSORS_NCT6775"),
+    ("it8", "CONFIG_SENSORS_IT87"),
+    ("k10temp", "CONFIG_SENSORS_K10TEMP"),
+    ("coretemp", "CONFIG_SENSORS_CORETEMP"),
+    ("asusec", "CONFIG_SENSORS_ASUS_EC"),
+    ("asus_ec_sensors", "CONFIG_SENSORS_ASUS_EC"),
+    ("w83627", "CONFIG_SENSORS_W83627EHF"),
+    ("f71", "CONFIG_SENSORS_F71882FG"),
+    ("jc42", "CONFIG_SENSORS_JC42"),
+    ("spd5118", "CONFIG_SENSORS_SPD5118"),
+    ("drivetemp", "CONFIG_SENSORS_DRIVETEMP"),
+    ("dell_smm", "CONFIG_SENSORS_DELL_SMM"),
+    ("corsaircpro", "CONFIG_SENSORS_CORSAIR_CPRO"),
+    ("corsairpsu", "CONFIG_SENSORS_CORSAIR_PSU"),
+    ("thinkpad", "CONFIG_THINKPAD_ACPI"),
+    ("acpitz", "CONFIG_ACPI_THERMAL"),
+    ("nvme", "CONFIG_NVME_HWMON"),
+];
+
+/// Driver options that are plain switches rather than modules
+const BUILT_IN: &[&str] = &["CONFIG_NVME_HWMON"];
+
+/// Option of the driver behind a chip or module name, None for chips another
+/// subsystem's driver provides, such as GPU sensors
+pub fn chip_driver(name: &str) -> Option<&'static str> {
+    let name = name.replace('-', "_");
+    CHIP_DRIVERS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, symbol)| *symbol)
+}
+
+/// A sensor chip registered with hwmon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensorChip {
+    /// hwmon name such as `nct6798` or `k10temp`
+    pub name: String,
+    /// `i2c` or `spi` for chips on those buses, whose controller has to stay too
+    pub bus: Option<String>,
+    /// Name of the I2C adapter the chip hangs off, e.g. `SMBus I801 adapter at efa0`
+    pub adapter: Option<String>,
+}
+
+impl SensorChip {
+    /// Options the chip needs, the hwmon core, its bus and controller first
+    fn requirements(&self) -> Vec<ConfigSuggestion> {
+        let mut required = vec![hwmon()];
+        match self.bus.as_deref() {
+            Some("i2c") => {
+                required.push(ConfigSuggestion::new(
+                    "CONFIG_I2C",
+                    ConfigValue::yes(),
+                    "memory and board sensors are read over I2C and SMBus",
+                ));
+                let adapter = self.adapter.as_deref().unwrap_or_default();
+                if adapter.contains("I801") {
+                    required.push(ConfigSuggestion::new(
+                        "CONFIG_I2C_I801",
+                        ConfigValue::module(),
+                        "the Intel SMBus controller the sensors sit on",
+                    ));
+                } else if adapter.contains("PIIX4") {
+                    required.push(ConfigSuggestion::new(
+                        "CONFIG_I2C_PIIX4",
+                        ConfigValue::module(),
+                        "the AMD SMBus controller the sensors sit on",
+                    ));
+                }
+            }
+            Some("spi") => required.push(ConfigSuggestion::new(
+                "CONFIG_SPI",
+                ConfigValue::yes(),
+                "the sensor is read over SPI",
+            )),
+            _ => {}
+        }
+        if let Some(symbol) = chip_driver(&self.name) {
+            let value = if BUILT_IN.contains(&symbol) {
+                ConfigValue::yes()
+            } else {
+                ConfigValue::module()
+            };
+            required.push(ConfigSuggestion::new(
+                symbol,
+                value,
+                &format!("the driver of the {} sensors", self.name),
+            ));
+        }
+        required
+    }
+}
+
+/// Sensor chips of the machine and how lm-sensors and fancontrol are set up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SensorSetup {
+    pub chips: Vec<SensorChip>,
+    /// Modules sensors-detect set up for loading at boot
+    pub configured_modules: Vec<String>,
+    /// lm-sensors has a configuration
+    pub lm_sensors: bool,
+    /// fancontrol drives the fans from these sensors, so losing them stops fan control
+    pub fancontrol: bool,
+}
+
+impl SensorSetup {
+    /// Reads the registered hwmon chips and the lm-sensors and fancontrol configuration,
+    /// None when there is no chip a config choice could remove
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let mut chips = Vec::new();
+        for dir in system.read_dir(Path::new(SYS_HWMON)).unwrap_or_default() {
+            let Some(name) = system.read_trimmed(&dir.join("name")) else {
+                continue;
+            };
+            let bus = system
+                .read_trimmed(&dir.join("device/modalias"))
+                .and_then(|modalias| {
+                    let (bus, _) = modalias.split_once(':')?;
+                    matches!(bus, "i2c" | "spi").then(|| bus.to_string())
+                });
+            // The device link points into the adapter's directory, which holds its name
+            let adapter = if bus.as_deref() == Some("i2c") {
+                system.read_trimmed(&dir.join("device/../name"))
+            } else {
+                None
+            };
+            chips.push(SensorChip { name, bus, adapter });
+        }
+        chips.sort_by(|a, b| a.name.cmp(&b.name));
+        chips.dedup();
+
+        let mut configured_modules = Vec::new();
+        for path in LM_SENSORS_MODULES {
+            let path = Path::new(path);
+            let files = if path.ends_with("modules-load.d") {
+                system.read_dir(path).unwrap_or_default()
+            } else {
+                vec![path.to_path_buf()]
+            };
+            for file in files {
+                let text = system.read_to_string(&file).unwrap_or_default();
+                configured_modules.extend(configured_sensor_modules(&text));
+            }
+        }
+        configured_modules.sort();
+        configured_modules.dedup();
+
+        let setup = SensorSetup {
+            chips,
+            configured_modules,
+            lm_sensors: SENSORS_CONFIGS
+                .iter()
+                .any(|path| system.exists(Path::new(path))),
+            fancontrol: system.exists(Path::new(FANCONTROL_CONFIG)),
+        };
+        if setup.required().is_empty() {
+            return None;
+        }
+        Some(setup)
+    }
+}
+
+impl MachineRequirements for SensorSetup {
+    fn name(&self) -> &'static str {
+        "sensors"
+    }
+
+    /// Options the chips and the configured modules need, each symbol once
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let chips = self.chips.iter().flat_map(SensorChip::requirements);
+        let modules = self.configured_modules.iter().filter_map(|module| {
+            let symbol = chip_driver(module)?;
+            Some(ConfigSuggestion::new(
+                symbol,
+                ConfigValue::module(),
+                &format!("sensors-detect set up {} to load at boot", module),
+            ))
+        });
+        let required = requirements::unique(iter::once(hwmon()).chain(chips).chain(modules));
+        // The core alone keeps nothing a config could drop
+        if required.len() == 1 {
+            return Vec::new();
+        }
+        required
+    }
+
+    /// Warns when the plan drops sensor drivers, and separately when it drops the I2C or
+    /// SPI bus or the hwmon core wholesale, which takes the board's sensor chips with it
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+
+        for &bus in BLANKET_REMOVALS {
+            if !missing.iter().any(|requirement| requirement.symbol == bus) {
+                continue;
+            }
+            let chips: Vec<&str> = self
+                .chips
+                .iter()
+                .filter(|chip| {
+                    chip.requirements()
+                        .iter()
+                        .any(|requirement| requirement.symbol == bus)
+                })
+                .map(|chip| chip.name.as_str())
+                .collect();
+            advisories.push(Advisory::new(
+                Severity::Warning,
+                "sensors-bus-removed",
+                &[("bus", bus.to_string()), ("chips", chips.join(", "))],
+            ));
+        }
+
+        let symbols: Vec<&str> = missing
+            .iter()
+            .map(|requirement| requirement.symbol.as_str())
+            .collect();
+        let key = if self.fancontrol {
+            "fancontrol-missing-config"
+        } else {
+            "sensors-missing-config"
+        };
+        let mut advisory =
+            Advisory::new(Severity::Warning, key, &[("symbols", symbols.join(", "))]);
+        for requirement in missing {
+            advisory = advisory.suggest(requirement);
+        }
+        advisories.push(advisory);
+        advisories
+    }
+}
+
+/// The hwmon core every sensor driver registers with
+fn hwmon() -> ConfigSuggestion {
+    ConfigSuggestion::new(
+        "CONFIG_HWMON",
+        ConfigValue::yes(),
+        "the hardware monitoring core every sensor driver registers with",
+    )
+}
+
+/// Sensor modules in /etc/modules, a modules-load.d file or the `HWMON_MODULES=` line of
+/// the lm_sensors service settings
+fn configured_sensor_modules(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .flat_map(|line| {
+            let modules = match line.split_once('=') {
+                Some((key, value)) if key.trim() == "HWMON_MODULES" => value.trim_matches('"'),
+                Some(_) => "",
+                None => line,
+            };
+            modules.split_whitespace().map(str::to_string)
+        })
+        .filter(|module| chip_driver(module).is_some())
+        .collect()
+}
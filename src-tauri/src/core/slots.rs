@@ -0,0 +1,220 @@
+// src-tauri/src/core/slots.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::bootloader::{BootEntryRequest, Bootloader};
+use super::clock::unix_now;
+use super::paths::Paths;
+use super::privilege::PrivilegedAction;
+use super::smoke::SmokeReport;
+use super::state_file;
+use super::system::SystemFacade;
+
+/// One of the two managed boot entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Slot {
+    A,
+    B,
+}
+
+impl Slot {
+    pub fn other(self) -> Slot {
+        match self {
+            Slot::A => Slot::B,
+            Slot::B => Slot::A,
+        }
+    }
+
+    /// Name the slot's boot entry is registered under, so there are only ever two entries
+    pub fn entry_name(self) -> &'static str {
+        match self {
+            Slot::A => "slot-a",
+            Slot::B => "slot-b",
+        }
+    }
+}
+
+/// Kernel installed in a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotContents {
+    /// Kernel release, as `uname -r` reports it once booted
+    pub release: String,
+    pub installed_at: u64,
+    /// Set when the kernel booted but its smoke tests failed
+    pub failed: bool,
+}
+
+/// What a trial of the standby slot ended in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrialOutcome {
+    /// The standby kernel passed and is now the default
+    Promoted { slot: Slot },
+    /// The standby kernel failed; the active slot stays the default
+    Rejected { slot: Slot, failures: Vec<String> },
+}
+
+/// Two boot entries, active and standby; new kernels only ever replace the standby one and
+/// become the default after a one-shot boot passes the smoke tests
+/// A kernel that hangs or panics is never promoted: the next boot falls back to the active slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotTable {
+    pub active: Slot,
+    pub a: Option<SlotContents>,
+    pub b: Option<SlotContents>,
+    /// Standby slot booted once and waiting for its smoke tests
+    pub on_trial: Option<Slot>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl SlotTable {
+    /// Loads the table, starting with slot A active and both slots empty
+    pub fn open(paths: &Paths) -> Result<Self> {
+        let path = paths.slots_path();
+        let mut table = if path.exists() {
+            let text =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            SlotTable {
+                active: Slot::A,
+                a: None,
+                b: None,
+                on_trial: None,
+                path: PathBuf::new(),
+            }
+        };
+        table.path = path;
+        Ok(table)
+    }
+
+    pub fn standby(&self) -> Slot {
+        self.active.other()
+    }
+
+    pub fn contents(&self, slot: Slot) -> Option<&SlotContents> {
+        match slot {
+            Slot::A => self.a.as_ref(),
+            Slot::B => self.b.as_ref(),
+        }
+    }
+
+    fn contents_mut(&mut self, slot: Slot) -> &mut Option<SlotContents> {
+        match slot {
+            Slot::A => &mut self.a,
+            Slot::B => &mut self.b,
+        }
+    }
+
+    /// Replaces the standby slot with `kernel` and arms a one-shot boot into it
+    /// Returns the privileged steps; the table is saved once they are planned
+    pub fn install_to_standby(
+        &mut self,
+        system: &dyn SystemFacade,
+        bootloader: &Bootloader,
+        release: &str,
+        kernel_image: &Path,
+        initramfs: Option<&Path>,
+        cmdline: &str,
+    ) -> Result<Vec<PrivilegedAction>> {
+        if !bootloader.supports_slots() {
+            bail!(
+                "A/B slots need systemd-boot or direct UEFI entries, not {}",
+                bootloader.name()
+            );
+        }
+        if self.on_trial.is_some() {
+            bail!("the standby slot is still on trial; finish it before installing another kernel");
+        }
+        let slot = self.standby();
+        let mut actions = bootloader.remove_actions(slot.entry_name(), system);
+        actions.extend(bootloader.install_actions(&BootEntryRequest {
+            version: slot.entry_name().to_string(),
+            kernel_image: kernel_image.to_path_buf(),
+            initramfs: initramfs.map(Path::to_path_buf),
+            cmdline: cmdline.to_string(),
+        }));
+        *self.contents_mut(slot) = Some(SlotContents {
+            release: release.to_string(),
+            installed_at: unix_now(),
+            failed: false,
+        });
+        self.save()?;
+        Ok(actions)
+    }
+
+    /// Privileged steps booting the standby slot once; run after `install_to_standby` completed
+    /// An entry that does not exist yet cannot be targeted, hence the separate step
+    pub fn arm_trial(
+        &mut self,
+        system: &dyn SystemFacade,
+        bootloader: &Bootloader,
+    ) -> Result<Vec<PrivilegedAction>> {
+        let slot = self.standby();
+        if self.contents(slot).is_none() {
+            bail!("the standby slot is empty");
+        }
+        let actions = bootloader.boot_once_actions(slot.entry_name(), system);
+        if actions.is_empty() {
+            bail!("the standby boot entry was not found");
+        }
+        self.on_trial = Some(slot);
+        self.save()?;
+        Ok(actions)
+    }
+
+    /// Settles the trial from the smoke tests of the running kernel
+    /// Promotion only happens when the running kernel is the one in the trial slot
+    pub fn finish_trial(
+        &mut self,
+        system: &dyn SystemFacade,
+        bootloader: &Bootloader,
+        report: &SmokeReport,
+    ) -> Result<(TrialOutcome, Vec<PrivilegedAction>)> {
+        let Some(slot) = self.on_trial else {
+            bail!("no slot is on trial");
+        };
+        let release = self
+            .contents(slot)
+            .map(|contents| contents.release.clone())
+            .unwrap_or_default();
+        let booted = report.version == release;
+        let (outcome, actions) = if booted && report.passed() {
+            self.active = slot;
+            (
+                TrialOutcome::Promoted { slot },
+                bootloader.set_default_actions(slot.entry_name(), system),
+            )
+        } else {
+            let failures = if booted {
+                report
+                    .failures()
+                    .map(|check| format!("{:?}: {}", check.kind, check.detail))
+                    .collect()
+            } else {
+                vec![format!(
+                    "booted {} instead of {}, the trial kernel did not come up",
+                    report.version, release
+                )]
+            };
+            if let Some(contents) = self.contents_mut(slot) {
+                contents.failed = true;
+            }
+            (TrialOutcome::Rejected { slot, failures }, Vec::new())
+        };
+        self.on_trial = None;
+        self.save()?;
+        Ok((outcome, actions))
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            Paths::ensure(parent)?;
+        }
+        state_file::write(&self.path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
@@ -0,0 +1,246 @@
+// src-tauri/src/core/smoke.rs
+
+use std::path::Path;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::hardware::HardwareSnapshot;
+use super::modules::{modules_in_tier, ModuleIndex, ModuleTier};
+use super::net::{self_test, Endpoint};
+use super::system::SystemFacade;
+
+const PROC_ROUTE: &str = "/proc/net/route";
+const PROC_ASOUND_CARDS: &str = "/proc/asound/cards";
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// Checks run after rebooting into a new kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmokeCheckKind {
+    Network,
+    GpuDriver,
+    Audio,
+    CriticalModules,
+}
+
+/// Outcome of one check; Skipped when the machine lacked the feature before the update too
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmokeStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// One check and what it found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmokeCheck {
+    pub kind: SmokeCheckKind,
+    pub status: SmokeStatus,
+    pub detail: String,
+}
+
+impl SmokeCheck {
+    fn new(kind: SmokeCheckKind, status: SmokeStatus, detail: impl Into<String>) -> Self {
+        SmokeCheck {
+            kind,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Pass/fail report for the kernel that is currently running
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmokeReport {
+    pub version: String,
+    pub ran_at: u64,
+    pub checks: Vec<SmokeCheck>,
+}
+
+impl SmokeReport {
+    /// Whether no check failed; a canary boot is only promoted to default when this holds
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != SmokeStatus::Failed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &SmokeCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == SmokeStatus::Failed)
+    }
+}
+
+/// Compares the running system against the snapshot taken before the update
+/// With a client, network connectivity is checked against `endpoints`; otherwise a default
+/// route is enough, and without one the network check is skipped
+pub async fn run_smoke_tests(
+    system: &dyn SystemFacade,
+    before: &HardwareSnapshot,
+    now: &HardwareSnapshot,
+    client: Option<&Client>,
+    endpoints: &[Endpoint],
+) -> SmokeReport {
+    let version = system
+        .read_trimmed(Path::new(OSRELEASE))
+        .unwrap_or_default();
+    let mut checks = vec![check_network(system, client, endpoints).await];
+    checks.push(check_gpu(before, now));
+    checks.push(check_audio(system, before));
+    checks.push(check_critical_modules(system, &version, before, now));
+    SmokeReport {
+        version,
+        ran_at: unix_now(),
+        checks,
+    }
+}
+
+async fn check_network(
+    system: &dyn SystemFacade,
+    client: Option<&Client>,
+    endpoints: &[Endpoint],
+) -> SmokeCheck {
+    let kind = SmokeCheckKind::Network;
+    let routes = system
+        .read_to_string(Path::new(PROC_ROUTE))
+        .unwrap_or_default();
+    // Destination 00000000 is the default route
+    let default_route = routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let interface = fields.next()?;
+        (fields.next()? == "00000000").then(|| interface.to_string())
+    });
+    // Offline and isolated machines have no default route with any kernel
+    let Some(interface) = default_route else {
+        return SmokeCheck::new(kind, SmokeStatus::Skipped, "no default route");
+    };
+    let Some(client) = client.filter(|_| !endpoints.is_empty()) else {
+        return SmokeCheck::new(
+            kind,
+            SmokeStatus::Passed,
+            format!("default route via {}", interface),
+        );
+    };
+    let results = self_test(client, endpoints).await;
+    match results.iter().find(|check| check.ok()) {
+        Some(check) => SmokeCheck::new(
+            kind,
+            SmokeStatus::Passed,
+            format!("reached {} via {}", check.endpoint.name, interface),
+        ),
+        None => SmokeCheck::new(
+            kind,
+            SmokeStatus::Failed,
+            results
+                .iter()
+                .filter_map(|check| check.error.clone())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+    }
+}
+
+fn check_gpu(before: &HardwareSnapshot, now: &HardwareSnapshot) -> SmokeCheck {
+    let kind = SmokeCheckKind::GpuDriver;
+    let drivers = |snapshot: &HardwareSnapshot| -> Vec<String> {
+        snapshot
+            .display_devices()
+            .filter_map(|device| device.driver.clone())
+            .collect()
+    };
+    let expected = drivers(before);
+    if expected.is_empty() {
+        return SmokeCheck::new(
+            kind,
+            SmokeStatus::Skipped,
+            "no GPU driver was bound before the update",
+        );
+    }
+    let bound = drivers(now);
+    let missing: Vec<&String> = expected
+        .iter()
+        .filter(|driver| !bound.contains(driver))
+        .collect();
+    if missing.is_empty() {
+        SmokeCheck::new(kind, SmokeStatus::Passed, bound.join(", "))
+    } else {
+        SmokeCheck::new(
+            kind,
+            SmokeStatus::Failed,
+            format!(
+                "not bound: {}",
+                missing
+                    .iter()
+                    .map(|driver| driver.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        )
+    }
+}
+
+fn check_audio(system: &dyn SystemFacade, before: &HardwareSnapshot) -> SmokeCheck {
+    let kind = SmokeCheckKind::Audio;
+    let had_audio = before
+        .loaded_modules
+        .iter()
+        .any(|module| module.starts_with("snd_"));
+    let cards = system
+        .read_to_string(Path::new(PROC_ASOUND_CARDS))
+        .unwrap_or_default();
+    // Card lines start with the index, e.g. " 0 [PCH            ]: HDA-Intel - HDA Intel PCH"
+    let count = cards
+        .lines()
+        .filter(|line| line.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+        .count();
+    match (count, had_audio) {
+        (0, false) => SmokeCheck::new(
+            kind,
+            SmokeStatus::Skipped,
+            "no sound card before the update",
+        ),
+        (0, true) => SmokeCheck::new(kind, SmokeStatus::Failed, "no sound card found"),
+        (count, _) => SmokeCheck::new(
+            kind,
+            SmokeStatus::Passed,
+            format!("{} sound card(s)", count),
+        ),
+    }
+}
+
+fn check_critical_modules(
+    system: &dyn SystemFacade,
+    version: &str,
+    before: &HardwareSnapshot,
+    now: &HardwareSnapshot,
+) -> SmokeCheck {
+    let kind = SmokeCheckKind::CriticalModules;
+    let critical = modules_in_tier(&before.loaded_modules, ModuleTier::Critical);
+    if critical.is_empty() {
+        return SmokeCheck::new(
+            kind,
+            SmokeStatus::Skipped,
+            "no critical modules were loaded before",
+        );
+    }
+    let index = ModuleIndex::load(system, version).unwrap_or_default();
+    let missing: Vec<String> = critical
+        .into_iter()
+        .filter(|module| !now.loaded_modules.contains(module) && !index.provides(module))
+        .collect();
+    if missing.is_empty() {
+        SmokeCheck::new(
+            kind,
+            SmokeStatus::Passed,
+            "all previously loaded critical modules available",
+        )
+    } else {
+        SmokeCheck::new(
+            kind,
+            SmokeStatus::Failed,
+            format!("unavailable: {}", missing.join(", ")),
+        )
+    }
+}
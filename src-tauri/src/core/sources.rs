@@ -0,0 +1,360 @@
+// src-tauri/src/core/sources.rs
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::paths::Paths;
+use super::release::ReleaseNumber;
+use super::state_file;
+use super::system::{run_spec, CommandSpec, SystemFacade};
+use super::version_catalog::VersionCatalog;
+
+/// Where a provider's kernel trees come from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SourceOrigin {
+    /// Release tarballs listed in kernel.org's releases.json
+    KernelOrg,
+    /// A git repository, versioned by tags starting with `tag_prefix`
+    /// With a `branch`, the branch head is offered as the only version instead
+    Git {
+        url: String,
+        #[serde(default)]
+        tag_prefix: String,
+        #[serde(default)]
+        branch: Option<String>,
+    },
+}
+
+/// A named source of kernel trees: kernel.org, a distribution or a fork
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceProvider {
+    /// Short identifier stored in plans, e.g. `kernel.org` or `xanmod`
+    pub id: String,
+    pub name: String,
+    pub origin: SourceOrigin,
+    /// Shipped with KernelForge rather than added by the user
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl SourceProvider {
+    fn builtin(id: &str, name: &str, origin: SourceOrigin) -> Self {
+        SourceProvider {
+            id: id.to_string(),
+            name: name.to_string(),
+            origin,
+            builtin: true,
+        }
+    }
+
+    fn git(id: &str, name: &str, url: &str, tag_prefix: &str) -> Self {
+        SourceProvider::builtin(
+            id,
+            name,
+            SourceOrigin::Git {
+                url: url.to_string(),
+                tag_prefix: tag_prefix.to_string(),
+                branch: None,
+            },
+        )
+    }
+
+    /// Versions the provider currently offers; kernel.org ones come from `catalog`
+    pub fn list(
+        &self,
+        system: &dyn SystemFacade,
+        catalog: &VersionCatalog,
+    ) -> Result<Vec<ProviderVersion>> {
+        match &self.origin {
+            SourceOrigin::KernelOrg => Ok(catalog
+                .versions
+                .iter()
+                .map(|version| ProviderVersion {
+                    provider: self.id.clone(),
+                    version: version.version.clone(),
+                    release: version.release,
+                    git_ref: None,
+                    source_url: version.source_url.clone(),
+                })
+                .collect()),
+            SourceOrigin::Git {
+                url,
+                tag_prefix,
+                branch: Some(branch),
+            } => {
+                let heads = ls_remote(system, "--heads", url, branch)?;
+                if heads.is_empty() {
+                    bail!("{} has no branch {}", url, branch);
+                }
+                Ok(vec![ProviderVersion {
+                    provider: self.id.clone(),
+                    version: branch.clone(),
+                    release: branch
+                        .strip_prefix(tag_prefix.as_str())
+                        .and_then(|rest| rest.parse().ok()),
+                    git_ref: Some(branch.clone()),
+                    source_url: Some(url.clone()),
+                }])
+            }
+            SourceOrigin::Git {
+                url,
+                tag_prefix,
+                branch: None,
+            } => {
+                let pattern = format!("refs/tags/{}*", tag_prefix);
+                let mut versions: Vec<ProviderVersion> =
+                    ls_remote(system, "--tags", url, &pattern)?
+                        .into_iter()
+                        .filter_map(|tag| {
+                            let release = tag.strip_prefix(tag_prefix.as_str())?.parse().ok()?;
+                            Some(ProviderVersion {
+                                provider: self.id.clone(),
+                                version: tag.clone(),
+                                release: Some(release),
+                                git_ref: Some(tag),
+                                source_url: Some(url.clone()),
+                            })
+                        })
+                        .collect();
+                // Newest first, like releases.json
+                versions.sort_by_key(|version| Reverse(version.release));
+                Ok(versions)
+            }
+        }
+    }
+}
+
+/// One version a provider offers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderVersion {
+    pub provider: String,
+    /// Tag, branch or kernel.org version as the provider names it
+    pub version: String,
+    /// Upstream release it is based on, when the name reveals it
+    pub release: Option<ReleaseNumber>,
+    /// Tag or branch to check out, None for tarballs
+    pub git_ref: Option<String>,
+    /// Tarball or repository URL
+    pub source_url: Option<String>,
+}
+
+impl ProviderVersion {
+    /// Shallow clone of a git version into `destination`
+    pub fn checkout(&self, system: &dyn SystemFacade, destination: &Path) -> Result<()> {
+        let (Some(url), Some(git_ref)) = (&self.source_url, &self.git_ref) else {
+            bail!(
+                "{} {} is a tarball, fetch it through the mirrors",
+                self.provider,
+                self.version
+            );
+        };
+        let destination = destination.to_string_lossy();
+        let spec = CommandSpec::new(
+            "git",
+            &[
+                "clone",
+                "--depth",
+                "1",
+                "--branch",
+                git_ref,
+                url,
+                &destination,
+            ],
+        );
+        let output = run_spec(&system.prepare_command(spec)).context("running git clone")?;
+        if !output.success() {
+            bail!("git clone of {} failed: {}", url, output.stderr.trim());
+        }
+        Ok(())
+    }
+}
+
+/// Names of the refs under `pattern` in a remote repository
+fn ls_remote(
+    system: &dyn SystemFacade,
+    kind: &str,
+    url: &str,
+    pattern: &str,
+) -> Result<Vec<String>> {
+    let output = system
+        .run("git", &["ls-remote", kind, "--refs", url, pattern])
+        .context("running git ls-remote")?;
+    if !output.success() {
+        bail!("listing {} failed: {}", url, output.stderr.trim());
+    }
+    Ok(output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter_map(|name| {
+            name.strip_prefix("refs/tags/")
+                .or_else(|| name.strip_prefix("refs/heads/"))
+        })
+        .map(str::to_string)
+        .collect())
+}
+
+/// Built-in providers followed by the ones the user added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceProviders {
+    pub providers: Vec<SourceProvider>,
+}
+
+impl Default for SourceProviders {
+    fn default() -> Self {
+        SourceProviders {
+            providers: vec![
+                SourceProvider::builtin("kernel.org", "kernel.org", SourceOrigin::KernelOrg),
+                SourceProvider::git(
+                    "ubuntu-mainline",
+                    "Ubuntu mainline",
+                    "https://git.launchpad.net/~ubuntu-kernel-test/ubuntu/+source/linux/+git/mainline-crack",
+                    "cod/mainline/v",
+                ),
+                // kernel-ark is the tree Fedora's kernel is built from; dist-git only holds
+                // the spec file
+                SourceProvider::builtin(
+                    "fedora",
+                    "Fedora kernel-ark",
+                    SourceOrigin::Git {
+                        url: String::from("https://gitlab.com/cki-project/kernel-ark.git"),
+                        tag_prefix: String::from("kernel-"),
+                        branch: Some(String::from("os-build")),
+                    },
+                ),
+                SourceProvider::builtin(
+                    "raspberrypi",
+                    "Raspberry Pi",
+                    SourceOrigin::Git {
+                        url: String::from("https://github.com/raspberrypi/linux.git"),
+                        tag_prefix: String::from("rpi-"),
+                        branch: Some(String::from("rpi-6.6.y")),
+                    },
+                ),
+                SourceProvider::git(
+                    "linux-zen",
+                    "linux-zen",
+                    "https://github.com/zen-kernel/zen-kernel.git",
+                    "v",
+                ),
+                SourceProvider::git(
+                    "xanmod",
+                    "XanMod",
+                    "https://gitlab.com/xanmod/linux.git",
+                    "",
+                ),
+            ],
+        }
+    }
+}
+
+impl SourceProviders {
+    /// Built-in providers plus the user's, from the config directory
+    pub fn load(paths: &Paths) -> Result<Self> {
+        let mut providers = SourceProviders::default();
+        let path = paths.sources_path();
+        if path.exists() {
+            let text =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let added: Vec<SourceProvider> = serde_json::from_str(&text)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            providers.providers.extend(added);
+        }
+        Ok(providers)
+    }
+
+    /// Saves the user-added providers; built-ins are never written
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.config_dir())?;
+        let added: Vec<&SourceProvider> = self
+            .providers
+            .iter()
+            .filter(|provider| !provider.builtin)
+            .collect();
+        state_file::write(
+            &paths.sources_path(),
+            serde_json::to_string_pretty(&added)?.as_bytes(),
+        )
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SourceProvider> {
+        self.providers.iter().find(|provider| provider.id == id)
+    }
+
+    /// Adds a git fork or distro tree by URL, tracking either its tags or one branch
+    pub fn add_git(
+        &mut self,
+        id: &str,
+        name: &str,
+        url: &str,
+        tag_prefix: &str,
+        branch: Option<&str>,
+    ) -> Result<()> {
+        if self.get(id).is_some() {
+            bail!("a source named {} already exists", id);
+        }
+        if !url.starts_with("https://") && !url.starts_with("git://") {
+            bail!("{} is not an https:// or git:// repository URL", url);
+        }
+        self.providers.push(SourceProvider {
+            id: id.to_string(),
+            name: name.to_string(),
+            origin: SourceOrigin::Git {
+                url: url.to_string(),
+                tag_prefix: tag_prefix.to_string(),
+                branch: branch.map(str::to_string),
+            },
+            builtin: false,
+        });
+        Ok(())
+    }
+
+    /// Removes a user-added provider
+    pub fn remove(&mut self, id: &str) -> Result<()> {
+        match self.get(id) {
+            None => bail!("no source named {}", id),
+            Some(provider) if provider.builtin => bail!("{} is built in", id),
+            Some(_) => {
+                self.providers.retain(|provider| provider.id != id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Versions of every provider; a provider that cannot be listed reports its error
+    /// instead of hiding the others
+    pub fn list(
+        &self,
+        system: &dyn SystemFacade,
+        catalog: &VersionCatalog,
+    ) -> Vec<ProviderListing> {
+        self.providers
+            .iter()
+            .map(|provider| match provider.list(system, catalog) {
+                Ok(versions) => ProviderListing {
+                    provider: provider.clone(),
+                    versions,
+                    error: None,
+                },
+                Err(error) => ProviderListing {
+                    provider: provider.clone(),
+                    versions: Vec::new(),
+                    error: Some(format!("{:#}", error)),
+                },
+            })
+            .collect()
+    }
+}
+
+/// What one provider offers, for the catalog view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderListing {
+    pub provider: SourceProvider,
+    pub versions: Vec<ProviderVersion>,
+    pub error: Option<String>,
+}
@@ -0,0 +1,140 @@
+// src-tauri/src/core/state_file.rs
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+
+use super::paths::Paths;
+
+/// State may hold host names, so only the owner reads it in per-user mode
+const FILE_MODE: u32 = 0o600;
+
+/// In the shared layout every member of the group reads and replaces it
+const SHARED_FILE_MODE: u32 = 0o660;
+
+/// Tells apart temporary files of writers in the same process
+static WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// `name` with `suffix` appended, next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_else(|| OsString::from("state"));
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Mode of state files and locks next to `path`: group-writable in a shared (setgid)
+/// directory, owner-only elsewhere
+fn file_mode(path: &Path) -> u32 {
+    match path.parent() {
+        Some(dir) if Paths::is_shared(dir) => SHARED_FILE_MODE,
+        _ => FILE_MODE,
+    }
+}
+
+/// Gives a file this writer created `mode`, which the umask may have narrowed
+fn set_mode(file: &File, mode: u32) -> std::io::Result<()> {
+    if file.metadata()?.permissions().mode() & 0o777 == mode {
+        return Ok(());
+    }
+    file.set_permissions(fs::Permissions::from_mode(mode))
+}
+
+/// Advisory lock serialising writers of one state file, e.g. the GUI and the CLI
+/// Taken on a `.lock` sidecar: writes replace the file itself, so a lock on it would stay
+/// behind on the old inode
+#[derive(Debug)]
+pub struct StateLock {
+    _file: File,
+}
+
+impl StateLock {
+    /// Blocks until no other writer holds the lock
+    pub fn acquire(path: &Path) -> Result<Self> {
+        let lock_path = sibling(path, ".lock");
+        let mode = file_mode(path);
+        let file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode)
+            .open(&lock_path)
+        {
+            Ok(file) => {
+                set_mode(&file, mode)
+                    .with_context(|| format!("sharing {}", lock_path.display()))?;
+                file
+            }
+            // Another writer created it, with the mode above
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => OpenOptions::new()
+                .write(true)
+                .open(&lock_path)
+                .with_context(|| format!("opening {}", lock_path.display()))?,
+            Err(error) => {
+                return Err(error).with_context(|| format!("opening {}", lock_path.display()))
+            }
+        };
+        file.lock()
+            .with_context(|| format!("locking {}", lock_path.display()))?;
+        Ok(StateLock { _file: file })
+    }
+}
+
+/// Writes through a temporary file of this writer only and renames it over `path`, so
+/// readers see the old or the new contents in full and never need the lock
+/// The caller holds the lock
+fn replace(path: &Path, contents: &[u8]) -> Result<()> {
+    let temporary = sibling(
+        path,
+        &format!(
+            ".{}.{}.tmp",
+            process::id(),
+            WRITE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ),
+    );
+    let mode = file_mode(path);
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(&temporary)
+        .and_then(|mut file| {
+            set_mode(&file, mode)?;
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .with_context(|| format!("writing {}", temporary.display()));
+    if let Err(error) = written.and_then(|_| {
+        fs::rename(&temporary, path).with_context(|| format!("replacing {}", path.display()))
+    }) {
+        let _ = fs::remove_file(&temporary);
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Replaces `path` atomically while holding its writer lock
+pub fn write(path: &Path, contents: &[u8]) -> Result<()> {
+    let _lock = StateLock::acquire(path)?;
+    replace(path, contents)
+}
+
+/// Read-modify-write under one lock, so concurrent updates are applied in turn instead of
+/// the last writer discarding the others
+/// `change` gets the current contents, None when the file does not exist yet
+pub fn update(path: &Path, change: impl FnOnce(Option<String>) -> Result<String>) -> Result<()> {
+    let _lock = StateLock::acquire(path)?;
+    let current = if path.exists() {
+        Some(fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?)
+    } else {
+        None
+    };
+    replace(path, change(current)?.as_bytes())
+}
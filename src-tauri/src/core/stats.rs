@@ -0,0 +1,197 @@
+// src-tauri/src/core/stats.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::paths::Paths;
+use super::state_file;
+
+/// Warnings seen in one build, by category such as `unused-variable`, `objtool` or `modpost`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarningSummary {
+    pub total: usize,
+    pub categories: BTreeMap<String, usize>,
+}
+
+impl WarningSummary {
+    /// Counts the warning on a line of build output, if any
+    pub fn observe(&mut self, line: &str) {
+        if let Some(category) = warning_category(line) {
+            self.total += 1;
+            *self.categories.entry(category).or_default() += 1;
+        }
+    }
+
+    /// Summarizes a complete build log
+    pub fn from_log<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut summary = WarningSummary::default();
+        for line in lines {
+            summary.observe(line);
+        }
+        summary
+    }
+
+    /// Differences against an earlier build of the same plan
+    pub fn compare(&self, previous: &WarningSummary) -> WarningRegression {
+        let mut new_categories = Vec::new();
+        let mut increased = Vec::new();
+        for (category, &count) in &self.categories {
+            match previous.categories.get(category) {
+                None => new_categories.push(category.clone()),
+                Some(&before) if count > before => increased.push(CategoryIncrease {
+                    category: category.clone(),
+                    before,
+                    after: count,
+                }),
+                Some(_) => {}
+            }
+        }
+        WarningRegression {
+            new_categories,
+            increased,
+            total_before: previous.total,
+            total_after: self.total,
+        }
+    }
+}
+
+/// A category that produced more warnings than in the previous build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryIncrease {
+    pub category: String,
+    pub before: usize,
+    pub after: usize,
+}
+
+/// How a build's warnings changed compared to the previous build of the plan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarningRegression {
+    /// Warning types that did not occur before
+    pub new_categories: Vec<String>,
+    pub increased: Vec<CategoryIncrease>,
+    pub total_before: usize,
+    pub total_after: usize,
+}
+
+impl WarningRegression {
+    /// New warning types are what counts as a regression; more of a known type is only reported
+    pub fn is_regression(&self) -> bool {
+        !self.new_categories.is_empty()
+    }
+}
+
+/// One finished build in the statistics history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildRecord {
+    pub plan: String,
+    pub version: String,
+    pub started_at: u64,
+    pub duration_secs: u64,
+    pub success: bool,
+    pub warnings: WarningSummary,
+    /// Per-plan warning allowance, None when unbudgeted
+    #[serde(default)]
+    pub warning_budget: Option<usize>,
+    /// Size of the kernel image in bytes, None for failed builds
+    #[serde(default)]
+    pub image_size: Option<u64>,
+}
+
+impl BuildRecord {
+    /// Whether the build produced more warnings than its budget allows
+    pub fn over_budget(&self) -> bool {
+        self.warning_budget
+            .is_some_and(|budget| self.warnings.total > budget)
+    }
+}
+
+/// Build history kept in the data directory, one JSON file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsStore {
+    builds: Vec<BuildRecord>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl StatsStore {
+    /// Loads the history, starting empty when none was saved yet
+    pub fn open(paths: &Paths) -> Result<Self> {
+        StatsStore::load(&paths.stats_path())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut store = if path.exists() {
+            let text =
+                fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            StatsStore::default()
+        };
+        store.path = path.to_path_buf();
+        Ok(store)
+    }
+
+    /// Builds in the order they were recorded
+    pub fn builds(&self) -> &[BuildRecord] {
+        &self.builds
+    }
+
+    /// Builds of one plan, oldest first
+    pub fn builds_for<'a>(&'a self, plan: &'a str) -> impl Iterator<Item = &'a BuildRecord> + 'a {
+        self.builds.iter().filter(move |build| build.plan == plan)
+    }
+
+    /// Most recent build of a plan
+    pub fn latest(&self, plan: &str) -> Option<&BuildRecord> {
+        self.builds.iter().rev().find(|build| build.plan == plan)
+    }
+
+    /// Appends a build and saves, returning its warning changes against the plan's previous build
+    /// Builds recorded by other processes since loading are kept and compared against too
+    pub fn record(&mut self, record: BuildRecord) -> Result<Option<WarningRegression>> {
+        if let Some(parent) = self.path.parent() {
+            Paths::ensure(parent)?;
+        }
+        let path = self.path.clone();
+        let mut regression = None;
+        state_file::update(&path, |current| {
+            if let Some(text) = current {
+                self.builds = serde_json::from_str::<StatsStore>(&text)
+                    .with_context(|| format!("parsing {}", path.display()))?
+                    .builds;
+            }
+            regression = self
+                .latest(&record.plan)
+                .map(|previous| record.warnings.compare(&previous.warnings));
+            self.builds.push(record);
+            Ok(serde_json::to_string_pretty(self)?)
+        })?;
+        Ok(regression)
+    }
+}
+
+/// Category of a compiler, objtool or modpost warning line
+/// GCC and Clang name the flag in brackets, e.g. `warning: unused variable 'x' [-Wunused-variable]`
+fn warning_category(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.starts_with("WARNING: modpost:") {
+        return Some("modpost".to_string());
+    }
+    let (_, message) = line.split_once(": warning: ")?;
+    if message.starts_with("objtool:") {
+        return Some("objtool".to_string());
+    }
+    let category = message
+        .rfind("[-W")
+        .and_then(|start| {
+            let flag = &message[start + 3..];
+            let end = flag.find([']', '=', ','])?;
+            Some(flag[..end].to_string())
+        })
+        .unwrap_or_else(|| "other".to_string());
+    Some(category)
+}
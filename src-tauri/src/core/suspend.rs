@@ -0,0 +1,228 @@
+// src-tauri/src/core/suspend.rs
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::clock::unix_now;
+use super::hardware::HardwareSnapshot;
+use super::privilege::{PrivilegeBroker, PrivilegedAction};
+use super::results::KernelTag;
+use super::review::distro_config;
+use super::system::SystemFacade;
+
+const MEM_SLEEP: &str = "/sys/power/mem_sleep";
+const SUSPEND_STATS: &str = "/sys/power/suspend_stats";
+
+/// Symbol prefixes recorded with a failure, as they decide how suspend behaves
+const POWER_SYMBOL_PREFIXES: &[&str] = &[
+    "PM",
+    "SUSPEND",
+    "HIBERNATE",
+    "HIBERNATION",
+    "ACPI",
+    "CPU_IDLE",
+    "RTC_DRV_CMOS",
+    "RTC_HCTOSYS",
+    "X86_INTEL_LPSS",
+    "PINCTRL_AMD",
+    "AMD_PMC",
+    "INTEL_PMC_CORE",
+];
+
+/// Sleep state entered through /sys/power/mem_sleep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SuspendMode {
+    /// Suspend-to-idle, the only mode on many modern laptops
+    S2Idle,
+    /// S3 suspend-to-RAM
+    Deep,
+}
+
+impl SuspendMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SuspendMode::S2Idle => "s2idle",
+            SuspendMode::Deep => "deep",
+        }
+    }
+
+    /// Modes the running kernel offers, from e.g. `s2idle [deep]`
+    pub fn supported(system: &dyn SystemFacade) -> Vec<SuspendMode> {
+        let modes = system
+            .read_trimmed(Path::new(MEM_SLEEP))
+            .unwrap_or_default();
+        modes
+            .split_whitespace()
+            .filter_map(|mode| match mode.trim_matches(|c| c == '[' || c == ']') {
+                "s2idle" => Some(SuspendMode::S2Idle),
+                "deep" => Some(SuspendMode::Deep),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Selected entry of /sys/power/mem_sleep, e.g. `deep` from `s2idle [deep]`
+/// Kept as text so modes KernelForge does not test, such as `shallow`, can be restored too
+fn selected_mem_sleep(system: &dyn SystemFacade) -> Option<String> {
+    system
+        .read_trimmed(Path::new(MEM_SLEEP))?
+        .split_whitespace()
+        .find_map(|mode| mode.strip_prefix('[')?.strip_suffix(']').map(str::to_string))
+}
+
+/// Counters from /sys/power/suspend_stats
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspendStats {
+    pub success: u64,
+    pub fail: u64,
+    pub last_failed_dev: Option<String>,
+    pub last_failed_step: Option<String>,
+}
+
+impl SuspendStats {
+    pub fn read(system: &dyn SystemFacade) -> Self {
+        let dir = Path::new(SUSPEND_STATS);
+        let count = |name: &str| {
+            system
+                .read_trimmed(&dir.join(name))
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0)
+        };
+        let text = |name: &str| {
+            system
+                .read_trimmed(&dir.join(name))
+                .filter(|value| !value.is_empty())
+        };
+        SuspendStats {
+            success: count("success"),
+            fail: count("fail"),
+            last_failed_dev: text("last_failed_dev"),
+            last_failed_step: text("last_failed_step"),
+        }
+    }
+}
+
+/// Outcome of one suspend cycle, with the power management config of the kernel that ran it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspendTestResult {
+    pub kernel: KernelTag,
+    pub mode: SuspendMode,
+    pub recorded_at: u64,
+    pub passed: bool,
+    pub elapsed_secs: u64,
+    pub failures: Vec<String>,
+    /// Device and step the kernel blamed, when it recorded a failure
+    pub failed_device: Option<String>,
+    pub failed_step: Option<String>,
+    /// Power-related symbols of the running kernel's config, for diagnosis
+    pub config: BTreeMap<String, String>,
+}
+
+/// Suspends for `seconds` with an RTC wake alarm, then checks the machine came back whole
+/// Devices and drivers are compared against the snapshot taken before suspending
+pub fn run_suspend_test(
+    broker: &PrivilegeBroker,
+    system: &dyn SystemFacade,
+    mode: SuspendMode,
+    seconds: u32,
+    kernel: KernelTag,
+) -> Result<SuspendTestResult> {
+    if !SuspendMode::supported(system).contains(&mode) {
+        bail!("the running kernel does not offer {} suspend", mode.name());
+    }
+    if !system.has_program("rtcwake") {
+        bail!("rtcwake is not installed");
+    }
+    let before = HardwareSnapshot::capture(system)?;
+    let stats_before = SuspendStats::read(system);
+
+    let started = Instant::now();
+    // mem_sleep picks what `rtcwake -m mem` enters, so both happen in one elevated shell,
+    // whose exit trap puts the previous mode back whether or not the cycle succeeded
+    let restore = selected_mem_sleep(system)
+        .map(|previous| format!("trap 'echo {} > {}' EXIT; ", previous, MEM_SLEEP))
+        .unwrap_or_default();
+    let script = format!(
+        "{}echo {} > {} && rtcwake -m mem -s {}",
+        restore,
+        mode.name(),
+        MEM_SLEEP,
+        seconds
+    );
+    let cycle = broker.execute(&PrivilegedAction::Run {
+        program: "sh".to_string(),
+        args: vec!["-c".to_string(), script],
+    });
+    let elapsed_secs = started.elapsed().as_secs();
+
+    let after = HardwareSnapshot::capture(system)?;
+    let stats_after = SuspendStats::read(system);
+    let mut failures = Vec::new();
+    if let Err(error) = cycle {
+        failures.push(format!("{:#}", error));
+    }
+    if stats_after.fail > stats_before.fail {
+        failures.push(format!(
+            "kernel recorded {} failed suspend attempt(s)",
+            stats_after.fail - stats_before.fail
+        ));
+    } else if stats_after.success == stats_before.success {
+        failures.push("no completed suspend was recorded".to_string());
+    }
+    let drivers_after = after.drivers();
+    let lost: Vec<String> = before
+        .drivers()
+        .into_iter()
+        .filter(|driver| !drivers_after.contains(driver))
+        .collect();
+    if !lost.is_empty() {
+        failures.push(format!("drivers unbound after resume: {}", lost.join(", ")));
+    }
+    let missing = before.devices.len().saturating_sub(after.devices.len());
+    if missing > 0 {
+        failures.push(format!("{} device(s) missing after resume", missing));
+    }
+
+    let passed = failures.is_empty();
+    let (failed_device, failed_step, config) = if passed {
+        (None, None, BTreeMap::new())
+    } else {
+        (
+            stats_after.last_failed_dev,
+            stats_after.last_failed_step,
+            power_config(system),
+        )
+    };
+    Ok(SuspendTestResult {
+        kernel,
+        mode,
+        recorded_at: unix_now(),
+        passed,
+        elapsed_secs,
+        failures,
+        failed_device,
+        failed_step,
+        config,
+    })
+}
+
+/// Power management symbols of the running kernel, empty when its config is not in /boot
+fn power_config(system: &dyn SystemFacade) -> BTreeMap<String, String> {
+    let Ok(config) = distro_config(system) else {
+        return BTreeMap::new();
+    };
+    config
+        .iter()
+        .filter(|(name, _)| {
+            POWER_SYMBOL_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .map(|(name, value)| (name.to_string(), value.as_str().unwrap_or("n").to_string()))
+        .collect()
+}
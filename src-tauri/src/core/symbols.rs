@@ -0,0 +1,77 @@
+// src-tauri/src/core/symbols.rs
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Compact handle for an interned Kconfig symbol
+/// Ids are dense so per-symbol state can live in plain vectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SymbolId(u32);
+
+impl SymbolId {
+    /// Position of the symbol in dense per-symbol tables
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Strips the `CONFIG_` prefix used by .config files
+/// Kconfig sources and .config files then agree on one spelling per symbol
+pub fn bare_name(name: &str) -> &str {
+    name.strip_prefix("CONFIG_").unwrap_or(name)
+}
+
+/// Interner mapping symbol names to dense ids
+#[derive(Debug, Default, Clone)]
+pub struct SymbolTable {
+    names: Vec<String>,
+    ids: HashMap<String, SymbolId>,
+}
+
+impl SymbolTable {
+    /// Creates an empty symbol table
+    pub fn new() -> Self {
+        SymbolTable::default()
+    }
+
+    /// Returns the id for `name`, allocating one on first use
+    pub fn intern(&mut self, name: &str) -> SymbolId {
+        let name = bare_name(name);
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = SymbolId(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Looks up an already interned symbol
+    pub fn lookup(&self, name: &str) -> Option<SymbolId> {
+        self.ids.get(bare_name(name)).copied()
+    }
+
+    /// Bare name of an interned symbol
+    pub fn name(&self, id: SymbolId) -> &str {
+        &self.names[id.index()]
+    }
+
+    /// Number of interned symbols
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether no symbol has been interned yet
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates over all symbols in id order
+    pub fn iter(&self) -> impl Iterator<Item = (SymbolId, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (SymbolId(index as u32), name.as_str()))
+    }
+}
@@ -0,0 +1,153 @@
+// src-tauri/src/core/sysctl.rs
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::generator::KernelProfile;
+use super::privilege::{PrivilegeBroker, PrivilegedAction};
+use super::system::SystemFacade;
+
+/// Drop-in file managed by KernelForge
+pub const SYSCTL_FILE: &str = "/etc/sysctl.d/90-kernelforge.conf";
+
+/// One runtime tunable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SysctlSetting {
+    pub key: String,
+    pub value: String,
+    pub reason: String,
+}
+
+/// Values that were live before a profile was applied, needed to revert it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SysctlBackup {
+    pub previous: Vec<(String, String)>,
+}
+
+/// Runtime tuning that accompanies a kernel profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SysctlProfile {
+    pub name: String,
+    pub settings: Vec<SysctlSetting>,
+}
+
+impl SysctlProfile {
+    /// Tunables matched to a kernel profile
+    pub fn for_profile(profile: KernelProfile) -> Self {
+        let settings: &[(&str, &str, &str)] = match profile {
+            KernelProfile::Desktop => &[
+                ("vm.swappiness", "30", "prefer dropping cache over swapping interactive apps"),
+                ("vm.vfs_cache_pressure", "50", "keep directory and inode caches warm"),
+                ("kernel.sched_autogroup_enabled", "1", "group tasks per session for responsiveness"),
+            ],
+            KernelProfile::Gaming => &[
+                ("vm.swappiness", "10", "avoid swapping game assets out under memory pressure"),
+                ("vm.vfs_cache_pressure", "50", "keep directory and inode caches warm"),
+                ("vm.max_map_count", "2147483642", "some Proton titles exhaust the default map count"),
+                ("kernel.split_lock_mitigate", "0", "split lock mitigation causes stutter in older games"),
+                ("kernel.sched_autogroup_enabled", "1", "keep background builds from starving the game"),
+                ("net.core.netdev_max_backlog", "16384", "absorb packet bursts in online games"),
+            ],
+            KernelProfile::Server => &[
+                ("vm.swappiness", "10", "keep service working sets resident"),
+                ("kernel.sched_autogroup_enabled", "0", "autogroup only helps interactive sessions"),
+                ("net.core.somaxconn", "4096", "larger accept queue for busy listeners"),
+                ("net.core.rmem_max", "16777216", "allow large receive windows"),
+                ("net.core.wmem_max", "16777216", "allow large send windows"),
+                ("net.core.default_qdisc", "fq", "fair queueing pairs with BBR"),
+                ("net.ipv4.tcp_congestion_control", "bbr", "better throughput on lossy links"),
+            ],
+            KernelProfile::Containers => &[
+                ("vm.swappiness", "10", "keep service working sets resident"),
+                ("kernel.sched_autogroup_enabled", "0", "autogroup only helps interactive sessions"),
+                ("net.core.somaxconn", "4096", "larger accept queue for busy listeners"),
+                ("net.ipv4.ip_forward", "1", "route traffic between container networks and the host"),
+                ("net.bridge.bridge-nf-call-iptables", "1", "apply the firewall to bridged container traffic"),
+                ("fs.inotify.max_user_instances", "8192", "every container runs its own file watchers"),
+                ("kernel.keys.maxkeys", "20000", "each container takes a session keyring"),
+            ],
+            KernelProfile::Rt => &[
+                ("kernel.sched_rt_runtime_us", "-1", "let real-time tasks use the whole period"),
+                ("kernel.timer_migration", "0", "keep timers on the CPU that armed them"),
+                ("vm.stat_interval", "10", "fewer vmstat wakeups on isolated cores"),
+                ("vm.swappiness", "10", "avoid page-in latency spikes"),
+            ],
+            KernelProfile::Debug => &[
+                ("kernel.panic_on_warn", "0", "keep running after sanitizer reports so they can be collected"),
+                ("kernel.kptr_restrict", "0", "show kernel addresses in reports"),
+                ("kernel.dmesg_restrict", "0", "let the fuzzer read sanitizer output"),
+                ("kernel.softlockup_panic", "0", "instrumented code trips the watchdog without a real hang"),
+            ],
+        };
+        SysctlProfile {
+            name: profile.name().to_string(),
+            settings: settings
+                .iter()
+                .map(|(key, value, reason)| SysctlSetting {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    reason: reason.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Drops settings the running kernel does not expose under /proc/sys
+    pub fn supported_only(mut self, system: &dyn SystemFacade) -> Self {
+        self.settings.retain(|setting| system.exists(&proc_path(&setting.key)));
+        self
+    }
+
+    /// Renders the sysctl.d drop-in
+    pub fn render(&self) -> String {
+        let mut out = format!("# Generated by KernelForge for the {} profile\n", self.name);
+        for setting in &self.settings {
+            out.push_str(&format!("\n# {}\n{} = {}\n", setting.reason, setting.key, setting.value));
+        }
+        out
+    }
+
+    /// Installs the drop-in and loads it, returning the previous values for [`SysctlProfile::revert`]
+    pub fn apply(&self, broker: &PrivilegeBroker, system: &dyn SystemFacade) -> Result<SysctlBackup> {
+        let previous = self
+            .settings
+            .iter()
+            .filter_map(|setting| {
+                let current = system.read_trimmed(&proc_path(&setting.key))?;
+                // Multi-value keys are reported tab separated but written space separated
+                Some((setting.key.clone(), current.split_whitespace().collect::<Vec<_>>().join(" ")))
+            })
+            .collect();
+        broker.execute_all(&[
+            PrivilegedAction::WriteFile {
+                path: PathBuf::from(SYSCTL_FILE),
+                contents: self.render(),
+                mode: 0o644,
+            },
+            PrivilegedAction::Run {
+                program: String::from("sysctl"),
+                args: vec![String::from("-p"), String::from(SYSCTL_FILE)],
+            },
+        ])?;
+        Ok(SysctlBackup { previous })
+    }
+
+    /// Removes the drop-in and restores the values recorded by [`SysctlProfile::apply`]
+    pub fn revert(broker: &PrivilegeBroker, backup: &SysctlBackup) -> Result<()> {
+        let mut actions = vec![PrivilegedAction::RemoveFile {
+            path: PathBuf::from(SYSCTL_FILE),
+        }];
+        actions.extend(backup.previous.iter().map(|(key, value)| PrivilegedAction::Run {
+            program: String::from("sysctl"),
+            args: vec![String::from("-w"), format!("{}={}", key, value)],
+        }));
+        broker.execute_all(&actions)
+    }
+}
+
+/// Location of a sysctl key under /proc/sys
+fn proc_path(key: &str) -> PathBuf {
+    Path::new("/proc/sys").join(key.replace('.', "/"))
+}
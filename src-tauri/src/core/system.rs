@@ -0,0 +1,138 @@
+// src-tauri/src/core/system.rs
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Captured result of a finished command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub status: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    /// Whether the command exited with status 0
+    pub fn success(&self) -> bool {
+        self.status == 0
+    }
+}
+
+/// A process to launch, before any sandbox-specific wrapping
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// Variables to drop from the inherited environment
+    pub env_remove: Vec<String>,
+    pub cwd: Option<PathBuf>,
+}
+
+impl CommandSpec {
+    /// Creates a spec for `program` with `args`
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        CommandSpec {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..CommandSpec::default()
+        }
+    }
+}
+
+/// Single entry point for touching the host system
+/// Scanners and builders go through this so they can run against fixtures or a sandboxed host
+pub trait SystemFacade: Send + Sync {
+    /// Reads a whole file as UTF-8
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Lists the entries of a directory
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Whether a path exists
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Runs a program to completion and captures its output
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+
+    /// Rewrites a command so it runs on the host, e.g. through flatpak-spawn
+    /// Long-running processes such as builds are spawned from the returned spec
+    fn prepare_command(&self, spec: CommandSpec) -> CommandSpec {
+        spec
+    }
+
+    /// Rewrites a long-running command whose process group gets signalled, e.g. to pause a build
+    /// Returns the file the group's id is written to when the group lives outside this sandbox;
+    /// None means the spawned child leads the group itself
+    fn prepare_group_command(&self, spec: CommandSpec) -> (CommandSpec, Option<PathBuf>) {
+        (self.prepare_command(spec), None)
+    }
+
+    /// Directory for files handed to processes on the host, such as payloads for root
+    fn staging_dir(&self) -> PathBuf {
+        env::temp_dir()
+    }
+
+    /// Reads a sysfs-style single value file, trimmed, None when missing
+    fn read_trimmed(&self, path: &Path) -> Option<String> {
+        self.read_to_string(path)
+            .ok()
+            .map(|value| value.trim().to_string())
+    }
+
+    /// Whether a program can be found on PATH
+    /// The name is passed as a single argument so it is never interpreted by a shell
+    fn has_program(&self, program: &str) -> bool {
+        self.run("which", &[program])
+            .map(|output| output.success())
+            .unwrap_or(false)
+    }
+}
+
+/// Facade backed directly by the local filesystem and process table
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalSystem;
+
+impl SystemFacade for LocalSystem {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        run_spec(&CommandSpec::new(program, args))
+    }
+}
+
+/// Runs a prepared spec to completion on this side of any sandbox
+pub fn run_spec(spec: &CommandSpec) -> io::Result<CommandOutput> {
+    let mut command = Command::new(&spec.program);
+    command.args(&spec.args);
+    for name in &spec.env_remove {
+        command.env_remove(name);
+    }
+    command.envs(spec.env.iter().map(|(key, value)| (key, value)));
+    if let Some(cwd) = &spec.cwd {
+        command.current_dir(cwd);
+    }
+    let output = command.output()?;
+    Ok(CommandOutput {
+        status: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
@@ -0,0 +1,211 @@
+// src-tauri/src/core/telemetry.rs
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::cpu::{CpuVendor, Microarch};
+use super::generator::KernelProfile;
+use super::hardware::{Firmware, HardwareSnapshot};
+use super::results::{KernelTag, ResultsStore};
+
+/// Opt-in reporting; nothing is sent unless the user enabled it and chose an endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+}
+
+impl TelemetrySettings {
+    /// Endpoint to talk to, None while reporting is off
+    fn active_endpoint(&self) -> Option<&str> {
+        self.endpoint
+            .as_deref()
+            .map(|endpoint| endpoint.trim_end_matches('/'))
+            .filter(|endpoint| self.enabled && !endpoint.is_empty())
+    }
+}
+
+/// Coarse GPU family, from the bound display driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GpuVendor {
+    Intel,
+    Amd,
+    Nvidia,
+    Other,
+    None,
+}
+
+/// Hardware described coarsely enough that it cannot identify a machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardwareClass {
+    pub cpu_vendor: CpuVendor,
+    pub microarch: Microarch,
+    pub gpu: GpuVendor,
+    pub firmware: Firmware,
+    pub virtual_machine: bool,
+    /// RAM rounded up to a power of two, capped at 128
+    pub memory_gib: u32,
+}
+
+impl HardwareClass {
+    /// Drops names, serials and device lists, keeping only what groups similar machines
+    pub fn of(snapshot: &HardwareSnapshot) -> Self {
+        let gpu = snapshot
+            .display_devices()
+            .filter_map(|device| device.driver.as_deref())
+            .map(|driver| match driver {
+                "i915" | "xe" => GpuVendor::Intel,
+                "amdgpu" | "radeon" => GpuVendor::Amd,
+                "nouveau" | "nvidia" => GpuVendor::Nvidia,
+                _ => GpuVendor::Other,
+            })
+            .next()
+            .unwrap_or(GpuVendor::None);
+        let gib = snapshot.memory.total_kb.div_ceil(1 << 20).max(1) as u32;
+        HardwareClass {
+            cpu_vendor: snapshot.cpu.vendor,
+            microarch: snapshot.cpu.microarch,
+            gpu,
+            firmware: snapshot.firmware,
+            virtual_machine: snapshot.is_virtual(),
+            memory_gib: gib.next_power_of_two().min(128),
+        }
+    }
+}
+
+/// What one machine reports about one kernel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub hardware: HardwareClass,
+    pub kernel_version: String,
+    pub profile: Option<KernelProfile>,
+    pub boot_success: bool,
+    /// Percent change of each benchmark's mean time against the baseline kernel
+    pub benchmark_deltas: BTreeMap<String, f64>,
+}
+
+impl Report {
+    pub fn new(
+        snapshot: &HardwareSnapshot,
+        kernel: &KernelTag,
+        boot_success: bool,
+        benchmark_deltas: BTreeMap<String, f64>,
+    ) -> Self {
+        Report {
+            hardware: HardwareClass::of(snapshot),
+            kernel_version: kernel.version.clone(),
+            profile: kernel.profile,
+            boot_success,
+            benchmark_deltas,
+        }
+    }
+}
+
+/// Change of every benchmark run on both kernels, from their latest comparable results
+pub fn benchmark_deltas(
+    results: &ResultsStore,
+    version: &str,
+    baseline: &str,
+) -> BTreeMap<String, f64> {
+    let latest_mean = |name: &str, kernel: &str| {
+        results
+            .benchmarks()
+            .iter()
+            .rev()
+            .filter(|result| result.comparable())
+            .find(|result| result.name == name && result.kernel.version == kernel)
+            .and_then(|result| result.mean_ms())
+    };
+    let mut deltas = BTreeMap::new();
+    for result in results.benchmarks() {
+        if deltas.contains_key(&result.name) {
+            continue;
+        }
+        if let (Some(now), Some(before)) = (
+            latest_mean(&result.name, version),
+            latest_mean(&result.name, baseline),
+        ) {
+            if before > 0.0 {
+                deltas.insert(result.name.clone(), (now - before) / before * 100.0);
+            }
+        }
+    }
+    deltas
+}
+
+/// How kernels fared on machines like this one, as aggregated by the endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AggregateStats {
+    pub reports: u64,
+    pub boot_success_rate: f64,
+    pub median_deltas: BTreeMap<String, f64>,
+}
+
+/// Sends a report; fails without contacting anyone when reporting is off
+pub async fn submit_report(
+    client: &Client,
+    settings: &TelemetrySettings,
+    report: &Report,
+) -> Result<()> {
+    let Some(endpoint) = settings.active_endpoint() else {
+        bail!("telemetry is disabled");
+    };
+    let url = format!("{}/v1/reports", endpoint);
+    client
+        .post(&url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(report)?)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("submitting report to {}", url))?;
+    Ok(())
+}
+
+/// Success rate of a profile on similar hardware; None when reporting is off
+pub async fn aggregate_for(
+    client: &Client,
+    settings: &TelemetrySettings,
+    hardware: &HardwareClass,
+    profile: Option<KernelProfile>,
+) -> Result<Option<AggregateStats>> {
+    let Some(endpoint) = settings.active_endpoint() else {
+        return Ok(None);
+    };
+    let url = format!("{}/v1/aggregate", endpoint);
+    let mut query = serde_json::to_value(hardware)?
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(key, value)| {
+                    let value = value
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| value.to_string());
+                    (key.clone(), value)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if let Some(profile) = profile {
+        query.push(("profile".to_string(), profile.name().to_string()));
+    }
+    let text = client
+        .get(&url)
+        .query(&query)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("requesting {}", url))?
+        .text()
+        .await
+        .with_context(|| format!("downloading {}", url))?;
+    let stats =
+        serde_json::from_str(&text).with_context(|| format!("reading aggregate from {}", url))?;
+    Ok(Some(stats))
+}
@@ -0,0 +1,272 @@
+// src-tauri/src/core/thunderbolt.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue};
+use super::requirements::MachineRequirements;
+use super::system::SystemFacade;
+
+const SYS_THUNDERBOLT: &str = "/sys/bus/thunderbolt/devices";
+const SYS_DRM: &str = "/sys/class/drm";
+
+/// A Thunderbolt or USB4 controller, `domainN` in sysfs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThunderboltDomain {
+    pub name: String,
+    /// Security level devices are authorized under: `none`, `user`, `secure`, `dponly`
+    /// or `usbonly`
+    pub security: Option<String>,
+}
+
+/// A router attached to a controller: a dock, display or storage enclosure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThunderboltDevice {
+    /// Route such as `0-1`, or `0-301` further down a daisy chain
+    pub route: String,
+    pub vendor: Option<String>,
+    pub name: Option<String>,
+    /// Whether its tunnels are up; boltd authorizes devices the user approved
+    pub authorized: bool,
+}
+
+impl ThunderboltDevice {
+    pub fn label(&self) -> String {
+        match (&self.vendor, &self.name) {
+            (Some(vendor), Some(name)) => format!("{} {}", vendor, name),
+            (None, Some(name)) => name.clone(),
+            _ => self.route.clone(),
+        }
+    }
+}
+
+/// Thunderbolt controllers, what is plugged into them and the external DisplayPort
+/// outputs that may run through a dock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThunderboltSetup {
+    pub domains: Vec<ThunderboltDomain>,
+    pub devices: Vec<ThunderboltDevice>,
+    /// Connected DisplayPort connectors, e.g. `card1-DP-3`; with a dock attached these
+    /// are usually tunneled through it
+    pub dock_displays: Vec<String>,
+}
+
+impl ThunderboltSetup {
+    /// Reads the Thunderbolt bus, None when the machine has no controller
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let root = Path::new(SYS_THUNDERBOLT);
+        let mut entries: Vec<String> = system
+            .read_dir(root)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+            .collect();
+        entries.sort();
+
+        let domains: Vec<ThunderboltDomain> = entries
+            .iter()
+            .filter(|name| name.starts_with("domain"))
+            .map(|name| ThunderboltDomain {
+                name: name.clone(),
+                security: system.read_trimmed(&root.join(name).join("security")),
+            })
+            .collect();
+        if domains.is_empty() {
+            return None;
+        }
+
+        // Routers are `<domain>-<route>`; route 0 is the host's own
+        let devices: Vec<ThunderboltDevice> = entries
+            .iter()
+            .filter(|name| {
+                name.split_once('-').is_some_and(|(domain, route)| {
+                    domain.chars().all(|c| c.is_ascii_digit())
+                        && route != "0"
+                        && !route.is_empty()
+                        && route.chars().all(|c| c.is_ascii_hexdigit())
+                })
+            })
+            .map(|route| {
+                let dir = root.join(route);
+                ThunderboltDevice {
+                    route: route.clone(),
+                    vendor: system.read_trimmed(&dir.join("vendor_name")),
+                    name: system.read_trimmed(&dir.join("device_name")),
+                    authorized: system
+                        .read_trimmed(&dir.join("authorized"))
+                        .is_some_and(|value| value != "0"),
+                }
+            })
+            .collect();
+
+        let dock_displays = if devices.is_empty() {
+            Vec::new()
+        } else {
+            let mut connectors: Vec<String> = system
+                .read_dir(Path::new(SYS_DRM))
+                .unwrap_or_default()
+                .iter()
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.contains("-DP-"))
+                        && system.read_trimmed(&path.join("status")).as_deref() == Some("connected")
+                })
+                .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+                .collect();
+            connectors.sort();
+            connectors
+        };
+
+        Some(ThunderboltSetup {
+            domains,
+            devices,
+            dock_displays,
+        })
+    }
+
+    /// Options displays on a dock need, empty when no dock drives a display
+    fn display_requirements(&self) -> Vec<ConfigSuggestion> {
+        if self.dock_displays.is_empty() {
+            return Vec::new();
+        }
+        vec![
+            ConfigSuggestion::new(
+                "CONFIG_DRM_DISPLAY_DP_TUNNEL",
+                ConfigValue::yes(),
+                "manages the bandwidth of DisplayPort tunnels through the dock",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_TYPEC",
+                ConfigValue::module(),
+                "USB-C port management, for docks driving displays in alternate mode",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_TYPEC_UCSI",
+                ConfigValue::module(),
+                "reports USB-C partners through the firmware",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_UCSI_ACPI",
+                ConfigValue::module(),
+                "the ACPI interface laptops expose UCSI through",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_TYPEC_DP_ALTMODE",
+                ConfigValue::module(),
+                "DisplayPort alternate mode, when the dock is not tunneling",
+            ),
+        ]
+    }
+}
+
+impl MachineRequirements for ThunderboltSetup {
+    fn name(&self) -> &'static str {
+        "thunderbolt"
+    }
+
+    /// Options the controllers, their tunnels and dock peripherals need
+    fn required(&self) -> Vec<ConfigSuggestion> {
+        let mut required = vec![
+            ConfigSuggestion::new(
+                "CONFIG_USB4",
+                ConfigValue::module(),
+                "the thunderbolt driver, which authorizes devices and sets up their tunnels",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_HOTPLUG_PCI",
+                ConfigValue::yes(),
+                "devices behind a PCIe tunnel come and go at runtime",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_HOTPLUG_PCI_PCIE",
+                ConfigValue::yes(),
+                "hot plugs the PCIe tunnel of a dock or enclosure",
+            ),
+            ConfigSuggestion::new(
+                "CONFIG_USB_XHCI_PCI",
+                ConfigValue::module(),
+                "the USB controller of a dock sits on its PCIe tunnel",
+            ),
+        ];
+        if !self.devices.is_empty() {
+            required.extend([
+                ConfigSuggestion::new(
+                    "CONFIG_USB_RTL8152",
+                    ConfigValue::module(),
+                    "the Realtek Ethernet most docks carry",
+                ),
+                ConfigSuggestion::new(
+                    "CONFIG_USB_NET_CDC_NCM",
+                    ConfigValue::module(),
+                    "the standard USB Ethernet class other docks use",
+                ),
+                ConfigSuggestion::new(
+                    "CONFIG_SND_USB_AUDIO",
+                    ConfigValue::module(),
+                    "the headset jack and speakers of a dock",
+                ),
+            ]);
+        }
+        required.extend(self.display_requirements());
+        required
+    }
+
+    /// Warns when the plan leaves out what the controllers, docks or their displays need
+    fn advisories(&self, missing: Vec<ConfigSuggestion>) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        let display_symbols: Vec<String> = self
+            .display_requirements()
+            .into_iter()
+            .map(|requirement| requirement.symbol)
+            .collect();
+        let (display, other): (Vec<ConfigSuggestion>, Vec<ConfigSuggestion>) = missing
+            .into_iter()
+            .partition(|requirement| display_symbols.contains(&requirement.symbol));
+
+        if !other.is_empty() {
+            let symbols: Vec<&str> = other
+                .iter()
+                .map(|requirement| requirement.symbol.as_str())
+                .collect();
+            let mut devices: Vec<String> =
+                self.devices.iter().map(ThunderboltDevice::label).collect();
+            if devices.is_empty() {
+                devices.push(String::from("none"));
+            }
+            let mut advisory = Advisory::new(
+                Severity::Warning,
+                "thunderbolt-missing-config",
+                &[
+                    ("symbols", symbols.join(", ")),
+                    ("devices", devices.join(", ")),
+                ],
+            );
+            for requirement in other {
+                advisory = advisory.suggest(requirement);
+            }
+            advisories.push(advisory);
+        }
+        if !display.is_empty() {
+            let symbols: Vec<&str> = display
+                .iter()
+                .map(|requirement| requirement.symbol.as_str())
+                .collect();
+            let mut advisory = Advisory::new(
+                Severity::Warning,
+                "dock-display-missing-config",
+                &[
+                    ("displays", self.dock_displays.join(", ")),
+                    ("symbols", symbols.join(", ")),
+                ],
+            );
+            for requirement in display {
+                advisory = advisory.suggest(requirement);
+            }
+            advisories.push(advisory);
+        }
+        advisories
+    }
+}
@@ -0,0 +1,118 @@
+// src-tauri/src/core/toolchain.rs
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::build::BuildOptions;
+use super::plan::TargetArch;
+use super::system::SystemFacade;
+
+/// Compiler used to build for an architecture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CrossCompiler {
+    /// The host's own compiler, target and host being the same
+    Native,
+    /// A GNU cross toolchain, e.g. `riscv64-linux-gnu-gcc`
+    Gcc { prefix: String },
+    /// Clang and LLVM binutils, which target every architecture they were built with
+    Clang,
+}
+
+/// How to compile for a target architecture on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossToolchain {
+    pub arch: TargetArch,
+    pub compiler: CrossCompiler,
+}
+
+impl CrossToolchain {
+    /// Finds a compiler for `arch`: the native one, a GNU cross toolchain, then Clang
+    pub fn detect(system: &dyn SystemFacade, arch: TargetArch) -> Result<Self> {
+        if TargetArch::host() == Some(arch) {
+            return Ok(CrossToolchain {
+                arch,
+                compiler: CrossCompiler::Native,
+            });
+        }
+        if let Some(prefix) = gcc_prefixes(arch)
+            .iter()
+            .find(|prefix| system.has_program(&format!("{}gcc", prefix)))
+        {
+            return Ok(CrossToolchain {
+                arch,
+                compiler: CrossCompiler::Gcc {
+                    prefix: prefix.to_string(),
+                },
+            });
+        }
+        if system.has_program("clang") && system.has_program("ld.lld") {
+            let output = system
+                .run("clang", &["--print-targets"])
+                .context("running clang --print-targets")?;
+            let supported = output
+                .stdout
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .any(|target| target == llvm_target(arch));
+            if supported {
+                return Ok(CrossToolchain {
+                    arch,
+                    compiler: CrossCompiler::Clang,
+                });
+            }
+        }
+        bail!(
+            "no compiler for {} found, install {}gcc or a clang built with the {} target",
+            arch.make_arch(),
+            gcc_prefixes(arch)[0],
+            llvm_target(arch)
+        );
+    }
+
+    /// Make variables selecting the compiler
+    pub fn env(&self) -> Vec<(String, String)> {
+        match &self.compiler {
+            CrossCompiler::Native => Vec::new(),
+            CrossCompiler::Gcc { prefix } => vec![(String::from("CROSS_COMPILE"), prefix.clone())],
+            CrossCompiler::Clang => vec![(String::from("LLVM"), String::from("1"))],
+        }
+    }
+
+    /// C compiler make should call, e.g. to put ccache in front of it
+    pub fn cc(&self) -> String {
+        match &self.compiler {
+            CrossCompiler::Native => String::from("gcc"),
+            CrossCompiler::Gcc { prefix } => format!("{}gcc", prefix),
+            CrossCompiler::Clang => String::from("clang"),
+        }
+    }
+
+    /// Points make at the toolchain, replacing any earlier choice
+    pub fn apply(&self, options: &mut BuildOptions) {
+        options
+            .env
+            .retain(|(key, _)| !matches!(key.as_str(), "CROSS_COMPILE" | "LLVM"));
+        options.env.extend(self.env());
+    }
+}
+
+/// GNU triplet prefixes distributions package cross compilers under, most common first
+fn gcc_prefixes(arch: TargetArch) -> &'static [&'static str] {
+    match arch {
+        TargetArch::X86_64 => &["x86_64-linux-gnu-"],
+        TargetArch::Arm64 => &["aarch64-linux-gnu-"],
+        TargetArch::Arm => &["arm-linux-gnueabihf-", "arm-linux-gnueabi-"],
+        TargetArch::Riscv64 => &["riscv64-linux-gnu-", "riscv64-unknown-linux-gnu-"],
+    }
+}
+
+/// Name `clang --print-targets` lists the architecture under
+fn llvm_target(arch: TargetArch) -> &'static str {
+    match arch {
+        TargetArch::X86_64 => "x86-64",
+        TargetArch::Arm64 => "aarch64",
+        TargetArch::Arm => "arm",
+        TargetArch::Riscv64 => "riscv64",
+    }
+}
@@ -0,0 +1,407 @@
+// src-tauri/src/core/version_catalog.rs
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use super::clock::unix_now;
+use super::fetch::FetchRequest;
+use super::install::{InstallTransaction, TransactionState};
+use super::paths::Paths;
+use super::release::ReleaseNumber;
+use super::state_file;
+use super::stats::StatsStore;
+use super::system::SystemFacade;
+
+/// Where kernel.org publishes its release list
+pub const RELEASES_URL: &str = "https://www.kernel.org/releases.json";
+
+/// Base of every tarball URL that can be served by a mirror instead
+const CDN_BASE: &str = "https://cdn.kernel.org/pub/linux/kernel/";
+
+const MODULES_ROOT: &str = "/lib/modules";
+
+const OSRELEASE: &str = "/proc/sys/kernel/osrelease";
+
+/// How long a downloaded catalog is used before it is checked again, unless configured
+pub const DEFAULT_CATALOG_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often kernel.org is asked about the catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogSettings {
+    pub ttl_secs: u64,
+}
+
+impl Default for CatalogSettings {
+    fn default() -> Self {
+        CatalogSettings {
+            ttl_secs: DEFAULT_CATALOG_TTL.as_secs(),
+        }
+    }
+}
+
+impl CatalogSettings {
+    /// Configured TTL, never below a minute so a typo cannot hammer kernel.org
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_secs.max(60))
+    }
+}
+
+/// Release track a version belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Moniker {
+    Mainline,
+    Stable,
+    Longterm,
+    LinuxNext,
+}
+
+/// One entry of the catalog, with everything needed to fetch and review it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelVersion {
+    /// As published, e.g. `6.9.3`, `6.10-rc2` or `next-20240604`
+    pub version: String,
+    /// None for linux-next snapshots
+    pub release: Option<ReleaseNumber>,
+    pub moniker: Moniker,
+    pub eol: bool,
+    /// Release date as YYYY-MM-DD
+    pub released: String,
+    /// Seconds since the epoch
+    pub released_at: u64,
+    /// Source tarball, None for linux-next
+    pub source_url: Option<String>,
+    /// Detached PGP signature of the uncompressed tarball; release candidates have none
+    pub signature_url: Option<String>,
+    /// Full commit log since the previous release, stable and longterm only
+    pub changelog_url: Option<String>,
+    /// Diff against the previous release on git.kernel.org
+    pub diffview_url: Option<String>,
+}
+
+impl KernelVersion {
+    /// Tarball path relative to a mirror root, None when only git.kernel.org serves it
+    pub fn mirror_path(&self) -> Option<&str> {
+        self.source_url.as_deref()?.strip_prefix(CDN_BASE)
+    }
+
+    /// Signature path relative to a mirror root
+    pub fn signature_path(&self) -> Option<&str> {
+        self.signature_url.as_deref()?.strip_prefix(CDN_BASE)
+    }
+
+    /// Download of the tarball through the mirror list
+    pub fn fetch_request(&self) -> Option<FetchRequest> {
+        self.mirror_path().map(FetchRequest::new)
+    }
+}
+
+/// Most recent build of a version, successful or not
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastBuild {
+    pub plan: String,
+    pub started_at: u64,
+    pub success: bool,
+}
+
+/// What this machine has done with a version, for the badges in the version picker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionStatus {
+    /// At least one build succeeded
+    pub built: bool,
+    /// KernelForge installed it and its modules are still under /lib/modules; a distro
+    /// kernel of the same version does not count
+    pub installed: bool,
+    /// The booted kernel is this version
+    pub running: bool,
+    pub last_build: Option<LastBuild>,
+}
+
+/// A catalog version joined with its local status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    #[serde(flatten)]
+    pub version: KernelVersion,
+    pub status: VersionStatus,
+}
+
+/// The catalog as the version picker shows it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnotatedCatalog {
+    pub fetched_at: u64,
+    pub age_secs: u64,
+    pub latest_stable: String,
+    pub versions: Vec<CatalogEntry>,
+}
+
+/// Layout of releases.json, only the fields the catalog keeps
+#[derive(Deserialize)]
+struct ReleasesFile {
+    latest_stable: LatestStable,
+    releases: Vec<ReleaseEntry>,
+}
+
+#[derive(Deserialize)]
+struct LatestStable {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct ReleaseEntry {
+    version: String,
+    moniker: Moniker,
+    #[serde(default)]
+    iseol: bool,
+    released: ReleaseDate,
+    source: Option<String>,
+    pgp: Option<String>,
+    changelog: Option<String>,
+    diffview: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ReleaseDate {
+    timestamp: u64,
+    isodate: String,
+}
+
+impl ReleaseEntry {
+    fn into_version(self) -> KernelVersion {
+        // Older lists leave pgp empty although kernel.org signs every CDN tarball
+        let signature_url = self.pgp.or_else(|| {
+            self.source
+                .as_deref()
+                .filter(|source| source.starts_with(CDN_BASE))
+                .and_then(|source| source.strip_suffix(".tar.xz"))
+                .map(|stem| format!("{}.tar.sign", stem))
+        });
+        KernelVersion {
+            release: self.version.parse().ok(),
+            version: self.version,
+            moniker: self.moniker,
+            eol: self.iseol,
+            released: self.released.isodate,
+            released_at: self.released.timestamp,
+            source_url: self.source,
+            signature_url,
+            changelog_url: self.changelog,
+            diffview_url: self.diffview,
+        }
+    }
+}
+
+/// Versions currently offered by kernel.org, cached between runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionCatalog {
+    /// Seconds since the epoch when kernel.org last confirmed the list
+    pub fetched_at: u64,
+    /// Validators of the last download, sent back so an unchanged list costs a 304
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Seconds since `fetched_at` when the catalog was handed out
+    #[serde(default)]
+    pub age_secs: u64,
+    pub latest_stable: String,
+    pub versions: Vec<KernelVersion>,
+}
+
+impl VersionCatalog {
+    /// Reads releases.json as published by kernel.org
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: ReleasesFile = serde_json::from_str(text).context("parsing releases.json")?;
+        Ok(VersionCatalog {
+            fetched_at: unix_now(),
+            etag: None,
+            last_modified: None,
+            age_secs: 0,
+            latest_stable: file.latest_stable.version,
+            versions: file
+                .releases
+                .into_iter()
+                .map(ReleaseEntry::into_version)
+                .collect(),
+        })
+    }
+
+    /// The catalog saved by the last refresh, if any
+    pub fn load_cached(paths: &Paths) -> Result<Option<Self>> {
+        let path = paths.catalog_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let catalog =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(Some(catalog))
+    }
+
+    fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.cache_dir())?;
+        state_file::write(
+            &paths.catalog_path(),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Time since kernel.org last confirmed the list
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched_at))
+    }
+
+    /// Whether the catalog is older than the configured TTL
+    pub fn is_stale(&self, settings: &CatalogSettings) -> bool {
+        self.age() >= settings.ttl()
+    }
+
+    /// The cached catalog while fresh, otherwise a refreshed one, with `age_secs` filled in
+    /// A stale cache is still returned when kernel.org cannot be reached
+    pub async fn load(client: &Client, paths: &Paths, settings: &CatalogSettings) -> Result<Self> {
+        let cached = VersionCatalog::load_cached(paths)?;
+        let mut catalog = match cached {
+            Some(catalog) if !catalog.is_stale(settings) => catalog,
+            cached => match VersionCatalog::refresh(client, paths, cached.as_ref()).await {
+                Ok(catalog) => catalog,
+                Err(error) => cached.ok_or(error)?,
+            },
+        };
+        catalog.age_secs = catalog.age().as_secs();
+        Ok(catalog)
+    }
+
+    /// Asks kernel.org for releases.json and replaces the cache
+    /// With a `cached` catalog the request is conditional, and a 304 only renews its timestamp
+    pub async fn refresh(
+        client: &Client,
+        paths: &Paths,
+        cached: Option<&VersionCatalog>,
+    ) -> Result<Self> {
+        let mut request = client.get(RELEASES_URL);
+        if let Some(etag) = cached.and_then(|catalog| catalog.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = cached.and_then(|catalog| catalog.last_modified.as_deref()) {
+            request = request.header(IF_MODIFIED_SINCE, modified);
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("requesting {}", RELEASES_URL))?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            let mut catalog = cached.clone();
+            catalog.fetched_at = unix_now();
+            catalog.save(paths)?;
+            return Ok(catalog);
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let text = response
+            .text()
+            .await
+            .with_context(|| format!("downloading {}", RELEASES_URL))?;
+        let mut catalog = VersionCatalog::parse(&text)?;
+        catalog.etag = etag;
+        catalog.last_modified = last_modified;
+        catalog.save(paths)?;
+        Ok(catalog)
+    }
+
+    /// Keeps the cache warm for a long-running process: checks once per TTL, so the catalog
+    /// is rarely stale when the UI asks for it
+    /// Failures are left for the next tick; the stale cache stays in place meanwhile
+    pub fn spawn_refresh(
+        client: Client,
+        paths: Paths,
+        settings: CatalogSettings,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(settings.ttl());
+            loop {
+                ticker.tick().await;
+                let _ = VersionCatalog::load(&client, &paths, &settings).await;
+            }
+        })
+    }
+
+    pub fn find(&self, version: &str) -> Option<&KernelVersion> {
+        self.versions.iter().find(|entry| entry.version == version)
+    }
+
+    /// Every version with its build, install and boot status on this machine
+    /// Local releases match on the version number, so `6.9.3-kernelforge` counts as 6.9.3
+    /// `installs` are KernelForge's own install records; only committed ones count
+    pub fn annotate(
+        &self,
+        system: &dyn SystemFacade,
+        stats: &StatsStore,
+        installs: &[InstallTransaction],
+    ) -> AnnotatedCatalog {
+        let installed: Vec<ReleaseNumber> = installs
+            .iter()
+            .filter(|install| install.state == TransactionState::Committed)
+            .filter(|install| system.exists(&Path::new(MODULES_ROOT).join(&install.version)))
+            .filter_map(|install| install.version.parse().ok())
+            .collect();
+        let running: Option<ReleaseNumber> = system
+            .read_trimmed(Path::new(OSRELEASE))
+            .and_then(|release| release.parse().ok());
+
+        let versions = self
+            .versions
+            .iter()
+            .map(|version| {
+                let matches = |other: &str| match version.release {
+                    Some(release) => other.parse::<ReleaseNumber>().ok() == Some(release),
+                    None => other == version.version,
+                };
+                let builds: Vec<_> = stats
+                    .builds()
+                    .iter()
+                    .filter(|record| matches(&record.version))
+                    .collect();
+                let status = VersionStatus {
+                    built: builds.iter().any(|record| record.success),
+                    installed: version
+                        .release
+                        .is_some_and(|release| installed.contains(&release)),
+                    running: version.release.is_some() && running == version.release,
+                    last_build: builds.iter().max_by_key(|record| record.started_at).map(
+                        |record| LastBuild {
+                            plan: record.plan.clone(),
+                            started_at: record.started_at,
+                            success: record.success,
+                        },
+                    ),
+                };
+                CatalogEntry {
+                    version: version.clone(),
+                    status,
+                }
+            })
+            .collect();
+        AnnotatedCatalog {
+            fetched_at: self.fetched_at,
+            age_secs: self.age_secs,
+            latest_stable: self.latest_stable.clone(),
+            versions,
+        }
+    }
+}
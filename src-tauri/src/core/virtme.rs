@@ -0,0 +1,112 @@
+// src-tauri/src/core/virtme.rs
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::system::{run_spec, CommandSpec, SystemFacade};
+
+/// Exit status of coreutils `timeout` when the limit was hit
+const TIMEOUT_STATUS: i32 = 124;
+/// Run inside the guest when the caller gives nothing else, proving the new kernel is up
+const DEFAULT_EXEC: &str = "uname -r";
+
+/// Boots the kernel just built in a source tree with virtme-ng, against the host's root
+/// filesystem exported read-only, so a config change can be tried in seconds
+/// Writes inside the guest land in a throwaway overlay and never reach the host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtmeRun {
+    /// Tree `make` ran in; vng picks up its kernel image and modules
+    pub source_dir: PathBuf,
+    /// Command run in the guest, after which the VM shuts down
+    pub exec: String,
+    pub memory_mb: u32,
+    pub cpus: u32,
+    pub timeout_secs: u64,
+}
+
+impl VirtmeRun {
+    pub fn new(source_dir: &Path) -> Self {
+        VirtmeRun {
+            source_dir: source_dir.to_path_buf(),
+            exec: DEFAULT_EXEC.to_string(),
+            memory_mb: 2048,
+            cpus: 2,
+            timeout_secs: 120,
+        }
+    }
+
+    /// Arguments for vng; without `--rw` the root filesystem is read-only to the guest
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            String::from("--memory"),
+            format!("{}M", self.memory_mb),
+            String::from("--cpus"),
+            self.cpus.to_string(),
+            String::from("--exec"),
+            self.exec.clone(),
+        ]
+    }
+
+    pub fn run(&self, system: &dyn SystemFacade) -> Result<VirtmeReport> {
+        if !system.has_program("vng") {
+            bail!("virtme-ng is not installed");
+        }
+        if !self.source_dir.join("arch").exists() || !self.source_dir.join(".config").exists() {
+            bail!(
+                "{} is not a configured kernel tree",
+                self.source_dir.display()
+            );
+        }
+        let limit = self.timeout_secs.to_string();
+        let mut args = vec![String::from("--kill-after=10"), limit, String::from("vng")];
+        args.extend(self.args());
+        let spec = system.prepare_command(CommandSpec {
+            program: String::from("timeout"),
+            args,
+            env: Vec::new(),
+            env_remove: Vec::new(),
+            cwd: Some(self.source_dir.clone()),
+        });
+        let started = Instant::now();
+        let output = run_spec(&spec).context("running vng")?;
+        let timed_out = output.status == TIMEOUT_STATUS;
+        Ok(VirtmeReport {
+            exec: self.exec.clone(),
+            status: output.status,
+            passed: output.success(),
+            timed_out,
+            duration_secs: started.elapsed().as_secs(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+}
+
+/// What the guest command printed and how it exited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtmeReport {
+    pub exec: String,
+    /// Exit status of the guest command, which vng passes through
+    pub status: i32,
+    pub passed: bool,
+    pub timed_out: bool,
+    pub duration_secs: u64,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Boots the kernel built in `source_dir` and runs `exec` in it, or `uname -r` when None
+pub fn boot_in_virtme(
+    system: &dyn SystemFacade,
+    source_dir: &Path,
+    exec: Option<&str>,
+) -> Result<VirtmeReport> {
+    let mut run = VirtmeRun::new(source_dir);
+    if let Some(exec) = exec {
+        run.exec = exec.to_string();
+    }
+    run.run(system)
+}
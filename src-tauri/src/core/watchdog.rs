@@ -0,0 +1,186 @@
+// src-tauri/src/core/watchdog.rs
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{Advisory, Severity};
+use super::pipeline::PipelineStage;
+
+/// Wall-clock limits per stage and how long a stage may go without printing anything
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchdogPolicy {
+    /// Stages without an entry may run as long as they keep printing
+    pub stage_limits: BTreeMap<PipelineStage, Duration>,
+    /// Silence after which a stage counts as hung, None to never flag silence
+    pub inactivity: Option<Duration>,
+    /// Output lines kept for the stall report
+    pub tail_lines: usize,
+}
+
+impl Default for WatchdogPolicy {
+    fn default() -> Self {
+        let minutes = |count: u64| Duration::from_secs(count * 60);
+        WatchdogPolicy {
+            stage_limits: BTreeMap::from([
+                (PipelineStage::Fetch, minutes(60)),
+                (PipelineStage::Verify, minutes(10)),
+                (PipelineStage::Extract, minutes(20)),
+                (PipelineStage::Patch, minutes(10)),
+                (PipelineStage::Configure, minutes(15)),
+                (PipelineStage::Build, minutes(8 * 60)),
+                (PipelineStage::Package, minutes(60)),
+                (PipelineStage::BootTest, minutes(10)),
+                (PipelineStage::Install, minutes(30)),
+            ]),
+            // A link of vmlinux with LTO can be silent for a long time
+            inactivity: Some(minutes(15)),
+            tail_lines: 40,
+        }
+    }
+}
+
+impl WatchdogPolicy {
+    /// Limits applying to one stage
+    pub fn for_stage(&self, stage: PipelineStage) -> StageWatchdog {
+        StageWatchdog {
+            stage,
+            limit: self.stage_limits.get(&stage).copied(),
+            inactivity: self.inactivity,
+            tail_lines: self.tail_lines,
+        }
+    }
+}
+
+/// Limits for the stage currently running
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageWatchdog {
+    pub stage: PipelineStage,
+    pub limit: Option<Duration>,
+    pub inactivity: Option<Duration>,
+    pub tail_lines: usize,
+}
+
+impl StageWatchdog {
+    /// Why the stage looks stuck, if it does
+    /// `deadline` is where the limit currently ends; waiting on a stall pushes it out
+    pub fn check(&self, deadline: Option<Instant>, tail: &OutputTail) -> Option<StallKind> {
+        if let (Some(limit), Some(deadline)) = (self.limit, deadline) {
+            if Instant::now() >= deadline {
+                return Some(StallKind::Timeout {
+                    limit_secs: limit.as_secs(),
+                });
+            }
+        }
+        let idle = tail.idle();
+        self.inactivity
+            .filter(|inactivity| idle >= *inactivity)
+            .map(|_| StallKind::Inactive {
+                idle_secs: idle.as_secs(),
+            })
+    }
+
+    /// When the limit runs out for a stage started now
+    pub fn deadline(&self) -> Option<Instant> {
+        self.limit.map(|limit| Instant::now() + limit)
+    }
+}
+
+/// Last lines of output and when the most recent one arrived
+#[derive(Debug, Clone)]
+pub struct OutputTail {
+    lines: VecDeque<String>,
+    capacity: usize,
+    last_output: Instant,
+}
+
+impl OutputTail {
+    pub fn new(capacity: usize) -> Self {
+        OutputTail {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            last_output: Instant::now(),
+        }
+    }
+
+    pub fn observe(&mut self, line: &str) {
+        if self.capacity > 0 {
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.to_string());
+        }
+        self.last_output = Instant::now();
+    }
+
+    /// Restarts the silence clock without recording a line
+    pub fn touch(&mut self) {
+        self.last_output = Instant::now();
+    }
+
+    /// Time since the last line
+    pub fn idle(&self) -> Duration {
+        self.last_output.elapsed()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+/// Why a stage was flagged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StallKind {
+    /// Ran past the stage's wall-clock limit
+    Timeout { limit_secs: u64 },
+    /// Printed nothing for too long, typically blocked on a prompt
+    Inactive { idle_secs: u64 },
+}
+
+/// A stuck stage, reported to the user who then picks a `StallAction`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stall {
+    pub stage: PipelineStage,
+    pub kind: StallKind,
+    pub elapsed_secs: u64,
+    /// Output just before the stall, oldest first
+    pub last_output: Vec<String>,
+}
+
+impl Stall {
+    pub fn advisory(&self) -> Advisory {
+        let last_line = self.last_output.last().cloned().unwrap_or_default();
+        match self.kind {
+            StallKind::Timeout { limit_secs } => Advisory::new(
+                Severity::Warning,
+                "stage-timed-out",
+                &[
+                    ("stage", self.stage.name().to_string()),
+                    ("minutes", (limit_secs / 60).to_string()),
+                    ("line", last_line),
+                ],
+            ),
+            StallKind::Inactive { idle_secs } => Advisory::new(
+                Severity::Warning,
+                "stage-inactive",
+                &[
+                    ("stage", self.stage.name().to_string()),
+                    ("minutes", (idle_secs / 60).to_string()),
+                    ("line", last_line),
+                ],
+            ),
+        }
+    }
+}
+
+/// What to do about a stall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StallAction {
+    /// Stop the stage and fail it
+    Kill,
+    /// Stop the stage and start it again
+    Retry,
+    /// Leave it running; the limit and silence clock start over
+    KeepWaiting,
+}
@@ -0,0 +1,557 @@
+// src-tauri/src/core/wizard.rs
+
+use std::fs;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::Advisory;
+use super::config::ConfigValue;
+use super::containers;
+use super::generator::{ConfigGenerator, KernelProfile};
+use super::hardware::HardwareSnapshot;
+use super::lsm::{SecurityModule, SYS_LSM};
+use super::paths::Paths;
+use super::plan::{BuildPlan, TargetArch};
+use super::policy::Policy;
+use super::quirks::QuirkDatabase;
+use super::requirements::MachineRequirements;
+use super::resolver::ConfigLayer;
+use super::state_file;
+use super::system::SystemFacade;
+use super::version_catalog::VersionCatalog;
+
+/// Pages of the first-run wizard, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WizardStep {
+    ScanHardware,
+    ChooseGoals,
+    PickVersion,
+    ReviewRemovals,
+    Plan,
+    Done,
+}
+
+impl WizardStep {
+    fn previous(self) -> Option<Self> {
+        match self {
+            WizardStep::ScanHardware => None,
+            WizardStep::ChooseGoals => Some(WizardStep::ScanHardware),
+            WizardStep::PickVersion => Some(WizardStep::ChooseGoals),
+            WizardStep::ReviewRemovals => Some(WizardStep::PickVersion),
+            WizardStep::Plan => Some(WizardStep::ReviewRemovals),
+            WizardStep::Done => Some(WizardStep::Plan),
+        }
+    }
+}
+
+/// Kconfig symbols the wizard offers to drop together, and the modules that mean the
+/// hardware or feature is in use on this machine
+struct RemovalGroup {
+    id: &'static str,
+    symbols: &'static [&'static str],
+    modules: &'static [&'static str],
+    /// The security module the group removes; it is kept while the running kernel has
+    /// it active, since the distribution's boot policy depends on it
+    lsm: Option<SecurityModule>,
+}
+
+/// Removal group dropping V4L2 and every camera driver
+/// A group named after one of the machine's setups is the explicit choice to build
+/// without that setup, so the setup neither keeps it in use nor adds its layer
+const NO_CAMERA: &str = "camera";
+
+const REMOVAL_GROUPS: &[RemovalGroup] = &[
+    RemovalGroup {
+        id: "legacy-ports",
+        symbols: &[
+            "CONFIG_PARPORT",
+            "CONFIG_BLK_DEV_FD",
+            "CONFIG_ISA",
+            "CONFIG_EISA",
+        ],
+        modules: &["parport", "parport_pc", "lp", "floppy"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "infiniband",
+        symbols: &["CONFIG_INFINIBAND"],
+        modules: &["ib_core", "mlx4_ib", "mlx5_ib", "rdma_cm"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "fibre-channel",
+        symbols: &[
+            "CONFIG_SCSI_FC_ATTRS",
+            "CONFIG_SCSI_LPFC",
+            "CONFIG_SCSI_QLA_FC",
+        ],
+        modules: &["scsi_transport_fc", "lpfc", "qla2xxx"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "tape-drives",
+        symbols: &["CONFIG_CHR_DEV_ST", "CONFIG_CHR_DEV_OSST"],
+        modules: &["st", "osst"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "amateur-radio",
+        symbols: &["CONFIG_HAMRADIO", "CONFIG_AX25"],
+        modules: &["ax25", "netrom", "rose"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "legacy-protocols",
+        symbols: &["CONFIG_ATALK", "CONFIG_X25", "CONFIG_LAPB"],
+        modules: &["appletalk", "x25", "lapb"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "exotic-protocols",
+        symbols: &[
+            "CONFIG_IP_SCTP",
+            "CONFIG_IP_DCCP",
+            "CONFIG_TIPC",
+            "CONFIG_RDS",
+            "CONFIG_ATM",
+            "CONFIG_PHONET",
+            "CONFIG_IEEE802154",
+        ],
+        modules: &["sctp", "dccp", "tipc", "rds", "atm", "phonet", "ieee802154"],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "obscure-filesystems",
+        symbols: &[
+            "CONFIG_REISERFS_FS",
+            "CONFIG_JFS_FS",
+            "CONFIG_HFS_FS",
+            "CONFIG_HFSPLUS_FS",
+        ],
+        modules: &["reiserfs", "jfs", "hfs", "hfsplus"],
+        lsm: None,
+    },
+    // The embedded systems removal of the bloat categories: the I2C and SPI buses and the
+    // sensor drivers on them, which desktops read their board and memory sensors through
+    RemovalGroup {
+        id: "embedded-buses",
+        symbols: &["CONFIG_I2C", "CONFIG_SPI", "CONFIG_HWMON"],
+        modules: &["i2c_hid_acpi", "i2c_hid_of", "spidev"],
+        lsm: None,
+    },
+    // The explicit "no camera" choice: cameras are otherwise kept whenever one is found
+    RemovalGroup {
+        id: NO_CAMERA,
+        symbols: &["CONFIG_MEDIA_SUPPORT"],
+        modules: &[],
+        lsm: None,
+    },
+    RemovalGroup {
+        id: "selinux",
+        symbols: &["CONFIG_SECURITY_SELINUX"],
+        modules: &[],
+        lsm: Some(SecurityModule::SeLinux),
+    },
+    RemovalGroup {
+        id: "apparmor",
+        symbols: &["CONFIG_SECURITY_APPARMOR"],
+        modules: &[],
+        lsm: Some(SecurityModule::AppArmor),
+    },
+    RemovalGroup {
+        id: "smack",
+        symbols: &["CONFIG_SECURITY_SMACK"],
+        modules: &[],
+        lsm: Some(SecurityModule::Smack),
+    },
+    RemovalGroup {
+        id: "tomoyo",
+        symbols: &["CONFIG_SECURITY_TOMOYO"],
+        modules: &[],
+        lsm: Some(SecurityModule::Tomoyo),
+    },
+];
+
+/// A group the user may drop, with the reasons it should stay when there are any
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovalCandidate {
+    pub id: String,
+    pub symbols: Vec<String>,
+    /// Loaded modules, mounted filesystems and active security modules that need the
+    /// group; removal is refused while this is not empty
+    pub in_use: Vec<String>,
+    /// Removing the group would break boot, e.g. the security module enforcing the
+    /// distribution's policy
+    pub critical: bool,
+}
+
+/// Everything the user can do on a wizard page, so the GUI and the CLI send the same input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WizardAction {
+    ScanHardware,
+    ChooseGoal { profile: KernelProfile },
+    PickVersion { version: String },
+    ReviewRemovals { groups: Vec<String> },
+    BuildPlan { name: String },
+    Accept,
+    Back,
+}
+
+/// The wizard as the GUI and CLI render it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WizardView {
+    pub wizard: Wizard,
+    /// Filled on the removal review page, empty elsewhere
+    pub removal_candidates: Vec<RemovalCandidate>,
+    /// Cautions raised by the last action
+    pub advisories: Vec<Advisory>,
+}
+
+impl WizardView {
+    pub fn new(wizard: Wizard, advisories: Vec<Advisory>) -> Self {
+        let removal_candidates = if wizard.step == WizardStep::ReviewRemovals {
+            wizard.removal_candidates()
+        } else {
+            Vec::new()
+        };
+        WizardView {
+            wizard,
+            removal_candidates,
+            advisories,
+        }
+    }
+}
+
+/// Guided first-run flow: scan hardware → choose goals → pick version → review removals
+/// → plan. Every answer is checked before the wizard moves on, and the state survives
+/// restarts so the user can leave and come back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wizard {
+    pub step: WizardStep,
+    pub hardware: Option<HardwareSnapshot>,
+    pub profile: Option<KernelProfile>,
+    pub version: Option<String>,
+    /// Removal groups the user accepted
+    #[serde(default)]
+    pub removals: Vec<String>,
+    pub plan: Option<BuildPlan>,
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        Wizard {
+            step: WizardStep::ScanHardware,
+            hardware: None,
+            profile: None,
+            version: None,
+            removals: Vec::new(),
+            plan: None,
+        }
+    }
+}
+
+impl Wizard {
+    /// The saved wizard, or a fresh one on first run
+    pub fn load(paths: &Paths) -> Result<Self> {
+        let path = paths.wizard_path();
+        if !path.exists() {
+            return Ok(Wizard::default());
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.state_dir())?;
+        state_file::write(
+            &paths.wizard_path(),
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+    }
+
+    /// Whether the user already finished the wizard
+    pub fn is_done(&self) -> bool {
+        self.step == WizardStep::Done
+    }
+
+    /// Applies one action; `catalog` is only consulted when picking a version and
+    /// `quirks` when building the plan
+    /// Returns the advisories the new page should show
+    pub fn apply(
+        &mut self,
+        action: WizardAction,
+        system: &dyn SystemFacade,
+        catalog: Option<&VersionCatalog>,
+        quirks: &QuirkDatabase,
+    ) -> Result<Vec<Advisory>> {
+        match action {
+            WizardAction::ScanHardware => {
+                self.scan_hardware(HardwareSnapshot::capture(system)?)?;
+                Ok(Vec::new())
+            }
+            WizardAction::ChooseGoal { profile } => self.choose_goal(profile),
+            WizardAction::PickVersion { version } => {
+                let catalog = catalog.context("picking a version needs the version catalog")?;
+                self.pick_version(catalog, &version)?;
+                Ok(Vec::new())
+            }
+            WizardAction::ReviewRemovals { groups } => {
+                self.review_removals(&groups)?;
+                Ok(Vec::new())
+            }
+            WizardAction::BuildPlan { name } => {
+                let policy = Policy::installed(system)?;
+                self.build_plan(&name, quirks, policy.as_ref())?;
+                let hardware = self.hardware.as_ref().context("scan the hardware first")?;
+                Ok(quirks.advisories(hardware))
+            }
+            WizardAction::Accept => {
+                self.accept()?;
+                Ok(Vec::new())
+            }
+            WizardAction::Back => {
+                self.back()?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn expect(&self, step: WizardStep) -> Result<()> {
+        if self.step != step {
+            bail!("the wizard is at {:?}, not {:?}", self.step, step);
+        }
+        Ok(())
+    }
+
+    pub fn scan_hardware(&mut self, hardware: HardwareSnapshot) -> Result<()> {
+        self.expect(WizardStep::ScanHardware)?;
+        self.hardware = Some(hardware);
+        self.step = WizardStep::ChooseGoals;
+        Ok(())
+    }
+
+    /// Picks the tuning profile; the debug profile is refused here since it is never
+    /// meant as a first kernel
+    pub fn choose_goal(&mut self, profile: KernelProfile) -> Result<Vec<Advisory>> {
+        self.expect(WizardStep::ChooseGoals)?;
+        if profile == KernelProfile::Debug {
+            bail!("the debug profile is for kernel development, set it up from the plan editor");
+        }
+        self.profile = Some(profile);
+        self.step = WizardStep::PickVersion;
+        Ok(profile.advisories())
+    }
+
+    /// Picks a catalog version that is still maintained and has a tarball to fetch
+    pub fn pick_version(&mut self, catalog: &VersionCatalog, version: &str) -> Result<()> {
+        self.expect(WizardStep::PickVersion)?;
+        let entry = catalog
+            .find(version)
+            .with_context(|| format!("{} is not in the version catalog", version))?;
+        if entry.eol {
+            bail!("{} has reached end of life", version);
+        }
+        if entry.source_url.is_none() {
+            bail!("{} has no release tarball", version);
+        }
+        self.version = Some(version.to_string());
+        self.step = WizardStep::ReviewRemovals;
+        Ok(())
+    }
+
+    /// Groups the user may remove, with what keeps each one in use on this machine
+    pub fn removal_candidates(&self) -> Vec<RemovalCandidate> {
+        REMOVAL_GROUPS
+            .iter()
+            .map(|group| RemovalCandidate {
+                id: group.id.to_string(),
+                symbols: group
+                    .symbols
+                    .iter()
+                    .map(|symbol| symbol.to_string())
+                    .collect(),
+                in_use: self.in_use(group),
+                critical: self.lsm_in_use(group).is_some(),
+            })
+            .collect()
+    }
+
+    fn in_use(&self, group: &RemovalGroup) -> Vec<String> {
+        let Some(hardware) = &self.hardware else {
+            return Vec::new();
+        };
+        if let Some(reason) = self.lsm_in_use(group) {
+            return vec![reason];
+        }
+        let modules = hardware.loaded_modules.iter().filter(|module| {
+            let module = module.replace('-', "_");
+            group.modules.contains(&module.as_str())
+        });
+        let filesystems = hardware
+            .filesystems
+            .iter()
+            .filter(|fstype| group.modules.contains(&fstype.as_str()));
+        let mut in_use: Vec<String> = modules.chain(filesystems).cloned().collect();
+        // Removing what containers need would stop them from starting
+        if group
+            .symbols
+            .iter()
+            .any(|symbol| containers::required_by(symbol).is_some())
+        {
+            in_use.extend(
+                hardware
+                    .container_runtimes
+                    .iter()
+                    .map(|runtime| runtime.name().to_string()),
+            );
+        }
+        for setup in hardware.setups() {
+            if setup.name() == group.id {
+                continue;
+            }
+            for symbol in group.symbols {
+                in_use.extend(setup.users_of(symbol));
+            }
+        }
+        in_use.sort();
+        in_use.dedup();
+        in_use
+    }
+
+    /// Why the group's security module has to stay, None when it is not an LSM group or
+    /// the running kernel does not use it
+    fn lsm_in_use(&self, group: &RemovalGroup) -> Option<String> {
+        let lsm = group.lsm?;
+        let hardware = self.hardware.as_ref()?;
+        // A snapshot without the list proves nothing unused, so the module stays
+        if hardware.lsms.is_empty() {
+            return Some(format!("{} (could not read {})", lsm.name(), SYS_LSM));
+        }
+        hardware
+            .lsms
+            .contains(lsm.name())
+            .then(|| format!("{} (active security module)", lsm.name()))
+    }
+
+    /// Accepts the groups to remove; an empty list keeps everything
+    pub fn review_removals(&mut self, groups: &[String]) -> Result<()> {
+        self.expect(WizardStep::ReviewRemovals)?;
+        let candidates = self.removal_candidates();
+        for id in groups {
+            let candidate = candidates
+                .iter()
+                .find(|candidate| candidate.id == *id)
+                .with_context(|| format!("unknown removal group {}", id))?;
+            if candidate.critical {
+                bail!(
+                    "{} is critical on this machine and cannot be removed: {}",
+                    id,
+                    candidate.in_use.join(", ")
+                );
+            }
+            if !candidate.in_use.is_empty() {
+                bail!(
+                    "{} is in use on this machine: {}",
+                    id,
+                    candidate.in_use.join(", ")
+                );
+            }
+        }
+        self.removals = groups.to_vec();
+        self.removals.sort();
+        self.removals.dedup();
+        self.step = WizardStep::Plan;
+        Ok(())
+    }
+
+    /// Generates the plan from the answers and the machine's quirks, meeting the
+    /// organization's policy when one is installed; it is shown for review before `accept`
+    pub fn build_plan(
+        &mut self,
+        name: &str,
+        quirks: &QuirkDatabase,
+        policy: Option<&Policy>,
+    ) -> Result<&BuildPlan> {
+        self.expect(WizardStep::Plan)?;
+        if name.trim().is_empty() {
+            bail!("the plan needs a name");
+        }
+        let (Some(hardware), Some(profile), Some(version)) =
+            (&self.hardware, self.profile, &self.version)
+        else {
+            bail!("the wizard state is incomplete, go back and answer every step");
+        };
+        let arch = TargetArch::host().context("this architecture is not supported")?;
+        if arch != TargetArch::X86_64 {
+            bail!("the wizard only generates x86_64 plans, use the plan editor for ARM");
+        }
+        let mut removals = ConfigLayer::new("wizard-removals");
+        for group in REMOVAL_GROUPS
+            .iter()
+            .filter(|group| self.removals.iter().any(|id| id == group.id))
+        {
+            for symbol in group.symbols {
+                removals.set(symbol, ConfigValue::NotSet);
+            }
+        }
+        let mut generator = ConfigGenerator::x86_64_baseline().with_profile(profile);
+        if !hardware.container_runtimes.is_empty() {
+            generator = generator.with_layer(containers::layer());
+        }
+        let setups = self.kept_setups(hardware);
+        for setup in &setups {
+            generator = generator.with_layer(setup.layer());
+        }
+        generator = generator
+            .with_layer(removals)
+            .with_layer(quirks.layer(hardware));
+        if let Some(policy) = policy {
+            generator = generator.with_layer(policy.layer());
+        }
+        let mut plan = BuildPlan::new(name.trim(), version, arch, generator.generate());
+        plan.cmdline = quirks.cmdline(hardware);
+        plan.validate_with_policy(policy)?;
+        containers::enforce(&hardware.container_runtimes, &plan.config)?;
+        for setup in &setups {
+            setup.enforce(&plan.config)?;
+        }
+        Ok(self.plan.insert(plan))
+    }
+
+    /// The machine's setups, without those the user chose to build without
+    fn kept_setups<'a>(&self, hardware: &'a HardwareSnapshot) -> Vec<&'a dyn MachineRequirements> {
+        hardware
+            .setups()
+            .into_iter()
+            .filter(|setup| !self.removals.iter().any(|id| id == setup.name()))
+            .collect()
+    }
+
+    /// Confirms the reviewed plan and finishes the wizard
+    pub fn accept(&mut self) -> Result<()> {
+        self.expect(WizardStep::Plan)?;
+        if self.plan.is_none() {
+            bail!("generate the plan before accepting it");
+        }
+        self.step = WizardStep::Done;
+        Ok(())
+    }
+
+    /// Returns to the previous page; answers are kept so they can be changed, but the
+    /// plan is dropped since it no longer matches them
+    pub fn back(&mut self) -> Result<()> {
+        let previous = self
+            .step
+            .previous()
+            .context("the wizard is already at its first step")?;
+        if previous <= WizardStep::Plan {
+            self.plan = None;
+        }
+        self.step = previous;
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+// src-tauri/src/core/workspace.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::cache::sha256_file;
+use super::clock::unix_now;
+use super::config::KernelConfig;
+use super::paths::Paths;
+use super::state_file;
+
+/// Extensions of files a kernel is built from; build products are not watched
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "c", "h", "S", "rs", "s", "lds", "dts", "dtsi", "sh", "pl", "py", "awk", "tbl",
+];
+
+/// Generated sources that look like hand-written ones
+const GENERATED_SUFFIXES: &[&str] = &[".mod.c", ".lex.c", ".tab.c", ".tab.h", ".asn1.c", ".asn1.h"];
+
+/// Directories holding headers kbuild generates from the config
+const GENERATED_DIRS: &[&str] = &["include/generated", "include/config"];
+
+/// Size and modification time of a watched file, with its digest when the snapshot was taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    /// Nanoseconds since the epoch, as whole seconds miss quick successive edits
+    pub modified_ns: u64,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// State of a source tree right after KernelForge last changed it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub root: PathBuf,
+    pub taken_at: u64,
+    /// Paths relative to `root`
+    pub files: BTreeMap<String, FileStamp>,
+    /// The .config as KernelForge wrote it, to tell which options were edited
+    pub config: Option<String>,
+    /// Modification time of every scanned directory, "" being `root`; one whose time did
+    /// not move gained and lost no entries and is not listed again
+    #[serde(default)]
+    pub dirs: BTreeMap<String, u64>,
+}
+
+/// An option whose value was changed in .config by hand; None is absent or not set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigEdit {
+    pub symbol: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Edits made to a source tree outside KernelForge since its snapshot
+/// A tree with any is dirty: what gets built is no longer what the plan describes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutsideChanges {
+    /// When the snapshot compared against was taken
+    pub since: u64,
+    pub modified: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Option-level view of a modified .config
+    pub config: Vec<ConfigEdit>,
+}
+
+impl OutsideChanges {
+    pub fn is_dirty(&self) -> bool {
+        !self.modified.is_empty() || !self.added.is_empty() || !self.removed.is_empty()
+    }
+}
+
+impl WorkspaceSnapshot {
+    /// Hashes every source file under `root`; take it after each step that changes the
+    /// tree on purpose, such as patching, configuring and building
+    pub fn take(root: &Path) -> Result<Self> {
+        let scan = scan(root, None)?;
+        let mut files = BTreeMap::new();
+        for (relative, (modified, size)) in scan.files {
+            let sha256 = sha256_file(&root.join(&relative))?;
+            files.insert(
+                relative,
+                FileStamp {
+                    modified_ns: modified,
+                    size,
+                    sha256,
+                },
+            );
+        }
+        Ok(WorkspaceSnapshot {
+            root: root.to_path_buf(),
+            taken_at: unix_now(),
+            files,
+            config: fs::read_to_string(root.join(".config")).ok(),
+            dirs: scan.dirs,
+        })
+    }
+
+    /// Snapshot of `root` saved earlier, None when KernelForge never took one
+    pub fn load(paths: &Paths, root: &Path) -> Result<Option<Self>> {
+        let path = snapshot_path(paths, root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let snapshot =
+            serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(Some(snapshot))
+    }
+
+    pub fn save(&self, paths: &Paths) -> Result<()> {
+        Paths::ensure(paths.workspaces_dir())?;
+        let text = serde_json::to_string(self)?;
+        state_file::write(&snapshot_path(paths, &self.root), text.as_bytes())
+    }
+
+    /// What changed since the snapshot; only files whose size or time moved are hashed,
+    /// and a file touched without changing its content does not count
+    /// Directories whose time did not move are not listed again, their known files are only
+    /// looked at, so watching a tree costs a stat per source file
+    pub fn changes(&self) -> Result<OutsideChanges> {
+        let current = scan(&self.root, Some(&self.listings()))?.files;
+        let mut changes = OutsideChanges {
+            since: self.taken_at,
+            ..OutsideChanges::default()
+        };
+        for (relative, (modified, size)) in &current {
+            match self.files.get(relative) {
+                None => changes.added.push(relative.clone()),
+                Some(stamp) if stamp.modified_ns == *modified && stamp.size == *size => {}
+                Some(stamp) => {
+                    if sha256_file(&self.root.join(relative))? != stamp.sha256 {
+                        changes.modified.push(relative.clone());
+                    }
+                }
+            }
+        }
+        changes.removed = self
+            .files
+            .keys()
+            .filter(|relative| !current.contains_key(*relative))
+            .cloned()
+            .collect();
+        if changes
+            .modified
+            .iter()
+            .any(|relative| relative == ".config")
+            || changes.added.iter().any(|relative| relative == ".config")
+        {
+            changes.config = self.config_edits()?;
+        }
+        Ok(changes)
+    }
+
+    /// Files and directories the snapshot found directly inside each directory
+    fn listings(&self) -> Listings<'_> {
+        let mut listings = Listings {
+            modified: &self.dirs,
+            entries: BTreeMap::new(),
+        };
+        for relative in self.files.keys() {
+            listings
+                .entries
+                .entry(parent(relative))
+                .or_default()
+                .0
+                .push(relative);
+        }
+        for relative in self.dirs.keys().filter(|relative| !relative.is_empty()) {
+            listings
+                .entries
+                .entry(parent(relative))
+                .or_default()
+                .1
+                .push(relative);
+        }
+        listings
+    }
+
+    fn config_edits(&self) -> Result<Vec<ConfigEdit>> {
+        let before = KernelConfig::parse(self.config.as_deref().unwrap_or_default())?;
+        let path = self.root.join(".config");
+        let after = KernelConfig::parse(
+            &fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?,
+        )
+        .with_context(|| format!("parsing {}", path.display()))?;
+        let symbols: BTreeSet<&str> = before
+            .iter()
+            .chain(after.iter())
+            .map(|(symbol, _)| symbol)
+            .collect();
+        Ok(symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let old = before.get(symbol).and_then(|value| value.as_str());
+                let new = after.get(symbol).and_then(|value| value.as_str());
+                (old != new).then(|| ConfigEdit {
+                    symbol: symbol.to_string(),
+                    before: old.map(str::to_string),
+                    after: new.map(str::to_string),
+                })
+            })
+            .collect())
+    }
+}
+
+/// Edits made to `root` since its saved snapshot, None when it has none
+pub fn outside_changes(paths: &Paths, root: &Path) -> Result<Option<OutsideChanges>> {
+    WorkspaceSnapshot::load(paths, root)?
+        .map(|snapshot| snapshot.changes())
+        .transpose()
+}
+
+/// Saved snapshots are keyed by a digest of the tree's path
+fn snapshot_path(paths: &Paths, root: &Path) -> PathBuf {
+    let digest = hex::encode(Sha256::digest(root.to_string_lossy().as_bytes()));
+    paths
+        .workspaces_dir()
+        .join(format!("{}.json", &digest[..16]))
+}
+
+/// Watched files and scanned directories, by path relative to the tree's root
+#[derive(Default)]
+struct Scan {
+    /// Modification time and size
+    files: BTreeMap<String, (u64, u64)>,
+    /// Modification time
+    dirs: BTreeMap<String, u64>,
+}
+
+/// What an earlier snapshot found in each directory: its files, then its subdirectories
+struct Listings<'a> {
+    modified: &'a BTreeMap<String, u64>,
+    entries: BTreeMap<&'a str, (Vec<&'a str>, Vec<&'a str>)>,
+}
+
+/// Scans the tree, listing only directories that changed since `previous` when given
+fn scan(root: &Path, previous: Option<&Listings>) -> Result<Scan> {
+    let mut scan = Scan::default();
+    scan_dir(root, root, previous, &mut scan)?;
+    if let Ok(metadata) = fs::metadata(root.join(".config")) {
+        scan.files.insert(String::from(".config"), stamp(&metadata));
+    }
+    Ok(scan)
+}
+
+fn scan_dir(root: &Path, dir: &Path, previous: Option<&Listings>, scan: &mut Scan) -> Result<()> {
+    let relative = dir
+        .strip_prefix(root)
+        .unwrap_or(dir)
+        .to_string_lossy()
+        .to_string();
+    let metadata = fs::metadata(dir).with_context(|| format!("reading {}", dir.display()))?;
+    let (modified, _) = stamp(&metadata);
+    scan.dirs.insert(relative.clone(), modified);
+    if let Some(listings) =
+        previous.filter(|listings| listings.modified.get(&relative) == Some(&modified))
+    {
+        let (files, dirs) = listings
+            .entries
+            .get(relative.as_str())
+            .cloned()
+            .unwrap_or_default();
+        // A file replaced by a rename would have moved the directory's time
+        for file in files {
+            if let Ok(metadata) = fs::symlink_metadata(root.join(file)) {
+                if metadata.is_file() {
+                    scan.files.insert(file.to_string(), stamp(&metadata));
+                }
+            }
+        }
+        for subdir in dirs {
+            scan_dir(root, &root.join(subdir), previous, scan)?;
+        }
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // Dot directories are version control and kbuild's scratch space
+        if name.starts_with('.') {
+            continue;
+        }
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        // Symlinks are skipped: the DT include prefixes would otherwise loop
+        let kind = entry.file_type()?;
+        if kind.is_dir() {
+            if !GENERATED_DIRS
+                .iter()
+                .any(|generated| relative.ends_with(generated))
+            {
+                scan_dir(root, &path, previous, scan)?;
+            }
+        } else if kind.is_file() && is_source(&name) {
+            scan.files.insert(relative, stamp(&entry.metadata()?));
+        }
+    }
+    Ok(())
+}
+
+/// Directory holding `relative`, "" for the root
+fn parent(relative: &str) -> &str {
+    relative.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+fn is_source(name: &str) -> bool {
+    if name.starts_with("Kconfig") || name.starts_with("Makefile") || name.starts_with("Kbuild") {
+        return true;
+    }
+    let known = name
+        .rsplit_once('.')
+        .is_some_and(|(_, extension)| SOURCE_EXTENSIONS.contains(&extension));
+    known
+        && !GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+fn stamp(metadata: &fs::Metadata) -> (u64, u64) {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    (modified, metadata.len())
+}
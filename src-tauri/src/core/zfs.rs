@@ -0,0 +1,212 @@
+// src-tauri/src/core/zfs.rs
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::advisory::{has_blocker, Advisory, Severity};
+use super::config::{ConfigSuggestion, ConfigValue, KernelConfig};
+use super::i18n::Message;
+use super::procfs::{parse_mounts, PROC_MOUNTS};
+use super::release::ReleaseNumber;
+use super::system::SystemFacade;
+
+/// OpenZFS release as (major, minor, patch)
+type ZfsRelease = (u32, u32, u32);
+
+/// First OpenZFS release supporting each kernel series, from the OpenZFS release notes
+const SUPPORT_TABLE: &[(ZfsRelease, (u32, u32))] = &[
+    ((2, 1, 15), (6, 7)),
+    ((2, 2, 0), (6, 5)),
+    ((2, 2, 2), (6, 6)),
+    ((2, 2, 3), (6, 7)),
+    ((2, 2, 4), (6, 8)),
+    ((2, 2, 5), (6, 9)),
+    ((2, 2, 6), (6, 10)),
+    ((2, 2, 7), (6, 12)),
+    ((2, 3, 0), (6, 12)),
+    ((2, 3, 1), (6, 13)),
+    ((2, 3, 2), (6, 14)),
+    ((2, 3, 3), (6, 15)),
+    ((2, 3, 4), (6, 16)),
+];
+
+/// An imported pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZfsPool {
+    pub name: String,
+    pub health: String,
+    /// Whether / lives on this pool
+    pub holds_root: bool,
+}
+
+/// ZFS state of the machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZfsSetup {
+    pub version: Option<ReleaseNumber>,
+    pub pools: Vec<ZfsPool>,
+    /// Pool of the / dataset per /proc/mounts, known even when `zpool` cannot be run
+    #[serde(default)]
+    pub root_pool: Option<String>,
+}
+
+impl ZfsSetup {
+    /// Detects imported pools and the OpenZFS module version, None when ZFS is not in use
+    pub fn detect(system: &dyn SystemFacade) -> Option<Self> {
+        let root_dataset = system
+            .read_to_string(Path::new(PROC_MOUNTS))
+            .ok()
+            .and_then(|text| {
+                parse_mounts(&text)
+                    .into_iter()
+                    .find(|mount| mount.target == "/" && mount.fstype == "zfs")
+                    .map(|mount| mount.source.into_owned())
+            });
+        let root_pool = root_dataset.as_deref().and_then(|dataset| dataset.split('/').next());
+
+        let pools: Vec<ZfsPool> = system
+            .run("zpool", &["list", "-H", "-o", "name,health"])
+            .ok()
+            .filter(|output| output.success())
+            .map(|output| {
+                output
+                    .stdout
+                    .lines()
+                    .filter_map(|line| {
+                        let (name, health) = line.split_once('\t')?;
+                        Some(ZfsPool {
+                            name: name.to_string(),
+                            health: health.trim().to_string(),
+                            holds_root: Some(name) == root_pool,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if pools.is_empty() && root_pool.is_none() {
+            return None;
+        }
+
+        let version = system
+            .read_trimmed(Path::new("/sys/module/zfs/version"))
+            .or_else(|| {
+                let output = system.run("zfs", &["version"]).ok()?;
+                // Prints "zfs-2.2.4-1" followed by "zfs-kmod-2.2.4-1"
+                output
+                    .stdout
+                    .lines()
+                    .find_map(|line| line.strip_prefix("zfs-kmod-"))
+                    .map(str::to_string)
+            })
+            .and_then(|version| version.parse().ok());
+
+        let root_pool = root_pool.map(str::to_string);
+        Some(ZfsSetup { version, pools, root_pool })
+    }
+
+    /// Whether the root filesystem is on ZFS
+    pub fn root_on_zfs(&self) -> bool {
+        self.root_pool.is_some() || self.pools.iter().any(|pool| pool.holds_root)
+    }
+
+    /// Newest kernel series the installed OpenZFS supports
+    pub fn newest_supported_series(&self) -> Option<(u32, u32)> {
+        let version = self.version?;
+        let installed = (version.major, version.minor, version.patch);
+        SUPPORT_TABLE
+            .iter()
+            .filter(|(release, _)| {
+                // Releases of an older branch do not cover kernels a newer branch added later
+                *release <= installed && (release.0, release.1) == (installed.0, installed.1)
+            })
+            .map(|(_, series)| *series)
+            .max()
+    }
+
+    /// Guardrail findings; blockers when the root pool would become unmountable
+    pub fn check(&self, target: &ReleaseNumber, config: &KernelConfig) -> Vec<Advisory> {
+        let mut advisories = Vec::new();
+        let severity = if self.root_on_zfs() { Severity::Blocker } else { Severity::Warning };
+        let pools: Vec<&str> = self.pools.iter().map(|pool| pool.name.as_str()).collect();
+
+        match (self.version, self.newest_supported_series()) {
+            (Some(version), Some(newest)) if target.series() > newest => advisories.push(Advisory::new(
+                severity,
+                "zfs-unsupported",
+                &[
+                    ("version", version.to_string()),
+                    ("newest", format!("{}.{}", newest.0, newest.1)),
+                    ("pools", pools.join(", ")),
+                    ("target", target.to_string()),
+                ],
+            )),
+            (Some(_), Some(_)) => {}
+            (version, _) => advisories.push(Advisory::new(
+                severity,
+                "zfs-unknown",
+                &[
+                    ("version", version.map(|version| version.to_string()).unwrap_or_else(|| String::from("(unknown)"))),
+                    ("target", target.to_string()),
+                ],
+            )),
+        }
+
+        let mut missing = Vec::new();
+        for symbol in ["CONFIG_MODULES", "CONFIG_ZLIB_INFLATE", "CONFIG_ZLIB_DEFLATE"] {
+            if !config.is_enabled(symbol) {
+                missing.push(ConfigSuggestion::new(symbol, ConfigValue::yes(), "required to build and load the zfs module"));
+            }
+        }
+        if config.is_enabled("CONFIG_TRIM_UNUSED_KSYMS") {
+            missing.push(ConfigSuggestion::new(
+                "CONFIG_TRIM_UNUSED_KSYMS",
+                ConfigValue::NotSet,
+                "trimming exports removes symbols the out-of-tree zfs module links against",
+            ));
+        }
+        if !missing.is_empty() {
+            let symbols: Vec<&str> = missing.iter().map(|suggestion| suggestion.symbol.as_str()).collect();
+            let mut advisory = Advisory::new(
+                severity,
+                "zfs-missing-config",
+                &[("symbols", symbols.join(", "))],
+            );
+            for suggestion in missing {
+                advisory = advisory.suggest(suggestion);
+            }
+            advisories.push(advisory);
+        }
+
+        if config.is_enabled("CONFIG_PREEMPT_RT") {
+            advisories.push(
+                Advisory::new(
+                    severity,
+                    "zfs-preempt-rt",
+                    &[],
+                )
+                .suggest(ConfigSuggestion::new(
+                    "CONFIG_PREEMPT_RT",
+                    ConfigValue::NotSet,
+                    "OpenZFS does not support PREEMPT_RT",
+                )),
+            );
+        }
+
+        advisories
+    }
+
+    /// Refuses an install that would leave the root pool unmountable
+    pub fn guard_install(&self, target: &ReleaseNumber, config: &KernelConfig) -> Result<()> {
+        let advisories = self.check(target, config);
+        if has_blocker(&advisories) {
+            let reasons: Vec<&str> = advisories
+                .iter()
+                .filter(|advisory| advisory.severity == Severity::Blocker)
+                .map(|advisory| advisory.title.text.as_str())
+                .collect();
+            bail!(Message::new("zfs-install-blocked", &[("reasons", reasons.join("; "))]));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+// src-tauri/src/lib.rs
+
+pub mod commands;
+pub mod core;
+
+use std::env;
+
+use anyhow::{Context, Result};
+
+use crate::core::host::host_system;
+use crate::core::paths::{InstallMode, Paths, SETUP_ARG};
+use crate::core::privilege::PrivilegeBroker;
+
+/// The application with its state and commands registered, for the binary to run with its
+/// generated context
+/// The install mode is picked here, once at startup, from `--mode` or KERNELFORGE_MODE;
+/// `--setup-system` first creates the shared layout as root
+pub fn app() -> Result<tauri::Builder<tauri::Wry>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mode = InstallMode::at_startup(args.iter().cloned())?;
+    let paths = Paths::for_mode(mode)?;
+    if mode == InstallMode::SystemWide {
+        if args.iter().any(|arg| arg == SETUP_ARG) {
+            PrivilegeBroker::detect(host_system())
+                .execute_all(&paths.system_setup_actions())
+                .context("setting up the shared layout")?;
+        }
+        paths.check_system_layout()?;
+    }
+    Ok(commands::register(tauri::Builder::default(), paths))
+}
@@ -0,0 +1,161 @@
+// src-tauri/tests/emit.rs
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use kernelforge::core::config::{ConfigValue, KernelConfig};
+use kernelforge::core::generator::{ConfigGenerator, KernelProfile};
+use kernelforge::core::kconfig::KconfigTree;
+use kernelforge::core::resolver::{ConfigLayer, ResolverIndex};
+use proptest::prelude::*;
+
+/// Kernel versions with a fixture tree under tests/fixtures/kconfig
+const VERSIONS: &[&str] = &["6.6", "6.12"];
+
+const PROFILES: &[KernelProfile] = &[
+    KernelProfile::Desktop,
+    KernelProfile::Gaming,
+    KernelProfile::Server,
+    KernelProfile::Containers,
+    KernelProfile::Rt,
+    KernelProfile::Debug,
+];
+
+/// Set to rewrite the golden files from the current output after an intended change
+const BLESS: &str = "KERNELFORGE_BLESS";
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+fn fixture_tree(version: &str) -> KconfigTree {
+    let dir = fixtures().join("fixtures/kconfig");
+    let mut tree = KconfigTree::new();
+    for file in ["common.Kconfig".to_string(), format!("{}.Kconfig", version)] {
+        let text = fs::read_to_string(dir.join(&file)).expect("fixture tree is readable");
+        tree.parse_str(&text);
+    }
+    tree
+}
+
+/// Compares `actual` with tests/golden/<name>, or rewrites it when blessing
+fn check_golden(name: &str, actual: &str) {
+    let path = fixtures().join("golden").join(name);
+    if env::var_os(BLESS).is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("{} is missing; run with {}=1 to create it", name, BLESS));
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{} changed at line {}:\n  expected: {:?}\n  actual:   {:?}\nrun with {}=1 if the change is intended",
+            name,
+            line + 1,
+            expected.lines().nth(line),
+            actual.lines().nth(line),
+            BLESS
+        );
+    }
+}
+
+#[test]
+fn flattened_profiles_match_golden() {
+    for profile in PROFILES {
+        let config = ConfigGenerator::x86_64_baseline()
+            .with_profile(*profile)
+            .generate();
+        check_golden(&format!("flat/{}.config", profile.name()), &config.emit());
+    }
+}
+
+#[test]
+fn resolved_profiles_match_golden() {
+    for version in VERSIONS {
+        let tree = fixture_tree(version);
+        let index = ResolverIndex::build(&tree);
+        for profile in PROFILES {
+            let resolution = ConfigGenerator::x86_64_baseline()
+                .with_profile(*profile)
+                .resolve(&index);
+            check_golden(
+                &format!("{}/{}.config", version, profile.name()),
+                &resolution.to_config(&tree).emit(),
+            );
+        }
+    }
+}
+
+fn symbol_name() -> impl Strategy<Value = String> {
+    "(CONFIG_)?[A-Z][A-Z0-9_]{0,24}"
+}
+
+fn config_value() -> impl Strategy<Value = ConfigValue> {
+    prop_oneof![
+        Just(ConfigValue::yes()),
+        Just(ConfigValue::module()),
+        Just(ConfigValue::NotSet),
+        any::<i32>().prop_map(|value| ConfigValue::int(value.into())),
+        any::<u32>().prop_map(|value| ConfigValue::hex(value.into())),
+        "[a-zA-Z0-9 ./_-]{0,32}".prop_map(|value| ConfigValue::string(&value)),
+    ]
+}
+
+fn kernel_config() -> impl Strategy<Value = KernelConfig> {
+    prop::collection::vec((symbol_name(), config_value()), 0..64).prop_map(|entries| {
+        let mut config = KernelConfig::new();
+        for (name, value) in entries {
+            config.set(&name, value);
+        }
+        config
+    })
+}
+
+proptest! {
+    #[test]
+    fn parse_inverts_emit(config in kernel_config()) {
+        let parsed = KernelConfig::parse(&config.emit()).unwrap();
+        prop_assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn emit_is_idempotent(config in kernel_config()) {
+        let once = config.emit();
+        let twice = KernelConfig::parse(&once).unwrap().emit();
+        prop_assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn emit_is_sorted_without_duplicates(config in kernel_config()) {
+        let emitted = config.emit();
+        let names: Vec<&str> = emitted
+            .lines()
+            .map(|line| {
+                let line = line.trim_start_matches("# ");
+                line.split(['=', ' ']).next().unwrap()
+            })
+            .collect();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        prop_assert_eq!(&names, &sorted);
+        prop_assert_eq!(names.iter().collect::<BTreeSet<_>>().len(), names.len());
+        prop_assert_eq!(names.len(), config.len());
+    }
+
+    #[test]
+    fn layer_order_decides_the_value(value in config_value(), other in config_value()) {
+        let config = ConfigGenerator::default()
+            .with_layer(ConfigLayer::new("first").with("CONFIG_FOO", other))
+            .with_layer(ConfigLayer::new("second").with("CONFIG_FOO", value.clone()))
+            .generate();
+        prop_assert_eq!(config.get("FOO"), Some(&value));
+    }
+}
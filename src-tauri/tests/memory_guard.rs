@@ -0,0 +1,38 @@
+// src-tauri/tests/memory_guard.rs
+
+use kernelforge::core::memory_guard::MemoryGuard;
+
+fn goals(targets: &[&str]) -> Vec<String> {
+    targets.iter().map(|target| target.to_string()).collect()
+}
+
+fn lto_guard() -> MemoryGuard {
+    MemoryGuard {
+        serialize_link: true,
+        ..MemoryGuard::default()
+    }
+}
+
+#[test]
+fn full_and_image_builds_link_vmlinux_first() {
+    let guard = lto_guard();
+    assert_eq!(guard.phases(&[]), [goals(&["vmlinux"]), goals(&[])]);
+    assert_eq!(
+        guard.phases(&goals(&["bzImage", "modules"])),
+        [goals(&["vmlinux"]), goals(&["bzImage", "modules"])]
+    );
+}
+
+#[test]
+fn headers_only_build_skips_the_link() {
+    let guard = lto_guard();
+    assert_eq!(
+        guard.phases(&goals(&["headers_install"])),
+        [goals(&["headers_install"])]
+    );
+    assert_eq!(guard.phases(&goals(&["modules"])), [goals(&["modules"])]);
+    assert_eq!(
+        guard.phases(&goals(&["rockchip/rk3588-rock-5b.dtb"])),
+        [goals(&["rockchip/rk3588-rock-5b.dtb"])]
+    );
+}
@@ -0,0 +1,117 @@
+// src-tauri/tests/procfs.rs
+
+use std::fs;
+use std::path::PathBuf;
+
+use kernelforge::core::procfs::{parse_cpuinfo, parse_meminfo, parse_modules, parse_mounts};
+
+/// Reads a captured /proc file under tests/fixtures/proc
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/proc")
+        .join(name);
+    fs::read_to_string(&path).expect("fixture is readable")
+}
+
+#[test]
+fn modules_fixture_parses() {
+    let text = fixture("modules");
+    let modules = parse_modules(&text);
+
+    // The truncated last line is skipped rather than failing the whole parse
+    assert_eq!(modules.len(), 8);
+
+    let nvidia = &modules[1];
+    assert_eq!(nvidia.name, "nvidia_modeset");
+    assert_eq!(nvidia.size, 1_556_480);
+    assert_eq!(nvidia.refcount, 8);
+    assert_eq!(nvidia.used_by, ["nvidia_drm"]);
+    assert_eq!(nvidia.state, "Live");
+
+    let snd = modules.iter().find(|module| module.name == "snd").unwrap();
+    assert_eq!(snd.used_by, ["snd_hda_intel", "snd_hda_codec", "snd_pcm"]);
+
+    let btusb = modules.iter().find(|module| module.name == "btusb").unwrap();
+    assert!(btusb.used_by.is_empty());
+    assert_eq!(btusb.state, "Loading");
+}
+
+#[test]
+fn mounts_fixture_parses() {
+    let text = fixture("mounts");
+    let mounts = parse_mounts(&text);
+
+    // The line without fstype and options is skipped
+    assert_eq!(mounts.len(), 6);
+
+    let root = &mounts[0];
+    assert_eq!(root.source, "rpool/ROOT/ubuntu");
+    assert_eq!(root.target, "/");
+    assert_eq!(root.fstype, "zfs");
+    assert!(root.has_option("relatime"));
+    assert!(!root.has_option("rel"));
+
+    let esp = mounts.iter().find(|mount| mount.target == "/boot/efi").unwrap();
+    assert_eq!(esp.fstype, "vfat");
+    assert!(esp.has_option("fmask=0077"));
+
+    let backup = mounts.iter().find(|mount| mount.fstype == "ext4").unwrap();
+    assert_eq!(backup.target, "/media/user/My Backup Drive");
+    assert!(backup.has_option("ro"));
+
+    let share = mounts.iter().find(|mount| mount.fstype == "cifs").unwrap();
+    assert_eq!(share.target, "/mnt/tab\tdir");
+}
+
+#[test]
+fn cpuinfo_fixture_parses() {
+    let text = fixture("cpuinfo");
+    let cpus = parse_cpuinfo(&text);
+
+    assert_eq!(cpus.len(), 2);
+    assert_eq!(cpus[1].get("processor"), Some("1"));
+    assert_eq!(cpus[0].get("vendor_id"), Some("GenuineIntel"));
+    assert_eq!(
+        cpus[0].get("model name"),
+        Some("Intel(R) Xeon(R) Processor")
+    );
+    assert_eq!(cpus[0].get("cpu cores"), Some("2"));
+    // A key with an empty value is kept, one missing from the block is not
+    assert_eq!(cpus[0].get("power management"), Some(""));
+    assert_eq!(cpus[0].get("Features"), None);
+    assert!(cpus[0].flags().any(|flag| flag == "avx2"));
+    // Flags are whole words, avx512 alone is only a prefix of the listed ones
+    assert!(!cpus[0].flags().any(|flag| flag == "avx512"));
+}
+
+#[test]
+fn arm64_cpuinfo_reads_features_as_flags() {
+    let text = fixture("cpuinfo-arm64");
+    let cpus = parse_cpuinfo(&text);
+
+    // The last block has no trailing blank line
+    assert_eq!(cpus.len(), 2);
+    assert_eq!(cpus[1].get("CPU implementer"), Some("0x61"));
+    assert_eq!(cpus[0].get("CPU architecture"), Some("8"));
+    assert!(cpus[1].flags().any(|flag| flag == "asimd"));
+}
+
+#[test]
+fn meminfo_fixture_parses() {
+    let text = fixture("meminfo");
+    let meminfo = parse_meminfo(&text);
+
+    assert_eq!(meminfo.total_kb(), 6_147_400);
+    assert_eq!(meminfo.available_kb(), 5_502_972);
+    assert_eq!(meminfo.get("Active(anon)"), Some(12));
+    // Counts without a unit parse as well
+    assert_eq!(meminfo.get("HugePages_Total"), Some(0));
+    assert_eq!(meminfo.get("VmallocTotal"), Some(34_359_738_367));
+    assert_eq!(meminfo.get("MemUsed"), None);
+}
+
+#[test]
+fn meminfo_without_mem_available_falls_back_to_mem_free() {
+    let meminfo = parse_meminfo("MemTotal:        2048000 kB\nMemFree:          512000 kB\n");
+    assert_eq!(meminfo.available_kb(), 512_000);
+}
@@ -0,0 +1,143 @@
+// src-tauri/tests/state_file.rs
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process;
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use kernelforge::core::state_file;
+use kernelforge::core::stats::{BuildRecord, StatsStore, WarningSummary};
+
+const WRITERS: usize = 8;
+const ROUNDS: usize = 25;
+
+/// Fresh directory per test, so tests running in parallel do not share files
+fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("kernelforge-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs `work(writer)` on every writer thread at once
+fn race(work: impl Fn(usize) + Send + Sync + 'static) {
+    let work = Arc::new(work);
+    let start = Arc::new(Barrier::new(WRITERS));
+    let handles: Vec<_> = (0..WRITERS)
+        .map(|writer| {
+            let (work, start) = (Arc::clone(&work), Arc::clone(&start));
+            thread::spawn(move || {
+                start.wait();
+                work(writer);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("writer panicked");
+    }
+}
+
+#[test]
+fn readers_never_see_a_torn_file() {
+    let path = scratch("torn").join("state.json");
+    state_file::write(&path, b"[]").unwrap();
+    let writer_path = path.clone();
+    race(move |writer| {
+        for round in 0..ROUNDS {
+            // Large enough that a torn write would be caught mid-file
+            let payload = vec![writer * ROUNDS + round; 4096];
+            state_file::write(&writer_path, &serde_json::to_vec(&payload).unwrap()).unwrap();
+            let text = fs::read_to_string(&writer_path).unwrap();
+            let read: Vec<usize> = serde_json::from_str(&text).expect("state file is whole");
+            assert!(read.is_empty() || read.iter().all(|value| *value == read[0]));
+        }
+    });
+}
+
+#[test]
+fn concurrent_updates_are_not_lost() {
+    let path = scratch("update").join("counter");
+    let writer_path = path.clone();
+    race(move |_| {
+        for _ in 0..ROUNDS {
+            state_file::update(&writer_path, |current| {
+                let count: usize = current.map_or(0, |text| text.parse().unwrap());
+                Ok((count + 1).to_string())
+            })
+            .unwrap();
+        }
+    });
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        (WRITERS * ROUNDS).to_string()
+    );
+}
+
+#[test]
+fn stats_recorded_by_concurrent_processes_are_all_kept() {
+    let path = scratch("stats").join("stats.json");
+    let writer_path = path.clone();
+    race(move |writer| {
+        // Each writer loads once up front, like a GUI and a CLI opened side by side
+        let mut store = StatsStore::load(&writer_path).unwrap();
+        for round in 0..ROUNDS {
+            store
+                .record(BuildRecord {
+                    plan: format!("writer-{}", writer),
+                    version: String::from("6.9.3"),
+                    started_at: round as u64,
+                    duration_secs: 1,
+                    success: true,
+                    warnings: WarningSummary::default(),
+                    warning_budget: None,
+                    image_size: None,
+                })
+                .unwrap();
+        }
+    });
+    let store = StatsStore::load(&path).unwrap();
+    assert_eq!(store.builds().len(), WRITERS * ROUNDS);
+    for writer in 0..WRITERS {
+        let started: Vec<u64> = store
+            .builds_for(&format!("writer-{}", writer))
+            .map(|build| build.started_at)
+            .collect();
+        assert_eq!(started, (0..ROUNDS as u64).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn no_temporary_files_are_left_behind() {
+    let dir = scratch("leftovers");
+    let path = dir.join("state.json");
+    let writer_path = path.clone();
+    race(move |writer| {
+        for _ in 0..ROUNDS {
+            state_file::write(&writer_path, writer.to_string().as_bytes()).unwrap();
+        }
+    });
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["state.json", "state.json.lock"]);
+}
+
+#[test]
+fn state_in_a_shared_directory_stays_group_writable() {
+    let private = scratch("private");
+    state_file::write(&private.join("state.json"), b"[]").unwrap();
+    let mode = |path: PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(private.join("state.json")), 0o600);
+    assert_eq!(mode(private.join("state.json.lock")), 0o600);
+
+    let shared = scratch("shared");
+    fs::set_permissions(&shared, fs::Permissions::from_mode(0o2775)).unwrap();
+    state_file::write(&shared.join("state.json"), b"[]").unwrap();
+    assert_eq!(mode(shared.join("state.json")), 0o660);
+    assert_eq!(mode(shared.join("state.json.lock")), 0o660);
+}